@@ -3,13 +3,21 @@
 /// This module implements the observe-retry-fix workflow for agentic code generation.
 /// When tests fail or compilation errors occur, the executor analyzes the errors
 /// and generates refinements to fix them.
-use bodhya_core::{EngagementMode, ModelRequest, ModelRole, Result};
+use bodhya_core::{EngagementMode, ModelRequest, ModelResponse, ModelRole, Result};
 use bodhya_model_registry::ModelRegistry;
+use bodhya_storage::SqliteStorage;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tracing::Instrument;
 
+use crate::checkpoint::{CheckpointStore, RunCheckpoint};
+use crate::code_blocks::extract_rust_code;
 use crate::impl_gen::ImplCode;
 use crate::planner::CodePlan;
+use crate::prompt_budget::{PromptBudget, PromptSection};
+use crate::prompts::PromptLoader;
 use crate::tdd::TestCode;
+use crate::test_runner::{self, TestSummary};
 use crate::tools::{CodeAgentTools, CommandOutput};
 
 /// Error analysis result
@@ -26,7 +34,7 @@ pub struct ErrorAnalysis {
 }
 
 /// Categories of errors
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ErrorCategory {
     /// Compilation errors (syntax, type errors, etc.)
     Compilation,
@@ -41,18 +49,26 @@ pub enum ErrorCategory {
 /// Analyzes error output and extracts structured information
 pub struct ErrorAnalyzer {
     registry: Arc<ModelRegistry>,
+    prompts: PromptLoader,
 }
 
 impl ErrorAnalyzer {
     /// Create a new error analyzer
     pub fn new(registry: Arc<ModelRegistry>) -> Result<Self> {
-        Ok(Self { registry })
+        Self::with_prompt_loader(registry, &PromptLoader::new())
+    }
+
+    /// Create a new error analyzer, resolving its prompt through `loader`
+    pub fn with_prompt_loader(registry: Arc<ModelRegistry>, loader: &PromptLoader) -> Result<Self> {
+        Ok(Self {
+            registry,
+            prompts: loader.clone(),
+        })
     }
 
     /// Load the error analyzer prompt
     fn load_prompt(&self) -> Result<String> {
-        // Use embedded prompt from file
-        Ok(include_str!("../../../prompts/code/error_analyzer.txt").to_string())
+        self.prompts.load("error_analyzer.txt")
     }
 
     /// Analyze command output and extract error information using LLM
@@ -118,7 +134,8 @@ impl ErrorAnalyzer {
                 .get_model(&ModelRole::Planner, "code", &EngagementMode::Minimum)?;
 
         // Create model request
-        let request = ModelRequest::new(ModelRole::Planner, "code", prompt);
+        let request =
+            ModelRequest::new(ModelRole::Planner, "code", prompt).with_role_as_system_prompt();
 
         // Call the model backend
         let backend = self.registry.get_backend(&model_info.id).ok_or_else(|| {
@@ -333,21 +350,47 @@ impl ErrorAnalyzer {
     }
 }
 
+/// Code extracted from a model response
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedCode {
+    /// The extracted source code
+    pub code: String,
+    /// The model hit its token limit, so the code may be incomplete
+    pub truncated: bool,
+}
+
 /// Code refiner - generates fixed code based on error analysis
 pub struct CodeRefiner {
     registry: Arc<ModelRegistry>,
+    /// Prompt size limit; code and tests are trimmed to fit
+    budget: PromptBudget,
+    prompts: PromptLoader,
 }
 
 impl CodeRefiner {
     /// Create a new code refiner
     pub fn new(registry: Arc<ModelRegistry>) -> Result<Self> {
-        Ok(Self { registry })
+        Self::with_prompt_loader(registry, &PromptLoader::new())
+    }
+
+    /// Create a new code refiner, resolving its prompt through `loader`
+    pub fn with_prompt_loader(registry: Arc<ModelRegistry>, loader: &PromptLoader) -> Result<Self> {
+        Ok(Self {
+            registry,
+            budget: PromptBudget::default(),
+            prompts: loader.clone(),
+        })
+    }
+
+    /// Limit refinement prompts to `max_tokens` estimated tokens
+    pub fn with_context_tokens(mut self, max_tokens: usize) -> Self {
+        self.budget = PromptBudget::new(max_tokens);
+        self
     }
 
     /// Load the code refiner prompt
     fn load_prompt(&self) -> Result<String> {
-        // Use embedded prompt from file
-        Ok(include_str!("../../../prompts/code/code_refiner.txt").to_string())
+        self.prompts.load("code_refiner.txt")
     }
 
     /// Generate refined implementation based on error analysis using LLM
@@ -415,17 +458,21 @@ impl CodeRefiner {
             error_analysis.suggestions.join("\n")
         );
 
-        // Fill in the template
-        let prompt = prompt_template
-            .replace("{plan_context}", &plan_context)
-            .replace("{current_code}", &original_impl.code)
-            .replace("{test_code}", &test_code.code)
-            .replace("{error_analysis}", &error_analysis_text)
-            .replace("{iteration}", &iteration.to_string())
-            .replace(
-                "{previous_error_category}",
-                &format!("{:?}", error_analysis.category),
-            );
+        // Fill in the template, trimming tests and then code if it is too long
+        let prompt = self.budget.render(
+            &prompt_template,
+            vec![
+                PromptSection::required("plan_context", plan_context),
+                PromptSection::trimmable("current_code", &original_impl.code, 1),
+                PromptSection::trimmable("test_code", &test_code.code, 0),
+                PromptSection::required("error_analysis", error_analysis_text),
+                PromptSection::required("iteration", iteration.to_string()),
+                PromptSection::required(
+                    "previous_error_category",
+                    format!("{:?}", error_analysis.category),
+                ),
+            ],
+        );
 
         // Get coder model from registry
         let model_info =
@@ -433,7 +480,8 @@ impl CodeRefiner {
                 .get_model(&ModelRole::Coder, "code", &EngagementMode::Minimum)?;
 
         // Create model request
-        let request = ModelRequest::new(ModelRole::Coder, "code", prompt);
+        let request =
+            ModelRequest::new(ModelRole::Coder, "code", prompt).with_role_as_system_prompt();
 
         // Call the model backend
         let backend = self.registry.get_backend(&model_info.id).ok_or_else(|| {
@@ -446,7 +494,7 @@ impl CodeRefiner {
         let response = backend.generate(request).await?;
 
         // Extract code from response (look for ```rust code blocks)
-        let refined_code = self.extract_code_from_response(&response.text)?;
+        let refined_code = self.extract_code_from_response(&response)?.code;
 
         // Count lines of code
         let loc = refined_code
@@ -457,35 +505,22 @@ impl CodeRefiner {
         Ok(ImplCode {
             code: refined_code,
             loc,
+            language: original_impl.language,
+            dependencies: original_impl.dependencies.clone(),
         })
     }
 
-    /// Extract Rust code from LLM response
-    fn extract_code_from_response(&self, response: &str) -> Result<String> {
-        // Look for ```rust code blocks
-        if let Some(start) = response.find("```rust") {
-            let code_start = start + 7; // Skip "```rust"
-            if let Some(end) = response[code_start..].find("```") {
-                let code = response[code_start..code_start + end].trim();
-                return Ok(code.to_string());
-            }
+    /// Extract Rust code from LLM response, flagging output cut off by the token limit
+    fn extract_code_from_response(&self, response: &ModelResponse) -> Result<ExtractedCode> {
+        let truncated = response.is_truncated();
+        if truncated {
+            tracing::warn!("Model output hit the token limit; extracted code may be incomplete");
         }
 
-        // Try generic code blocks
-        if let Some(start) = response.find("```") {
-            let code_start = start + 3;
-            if let Some(end) = response[code_start..].find("```") {
-                let code = response[code_start..code_start + end].trim();
-                // Skip language identifier if present
-                if let Some(newline) = code.find('\n') {
-                    return Ok(code[newline..].trim().to_string());
-                }
-                return Ok(code.to_string());
-            }
-        }
-
-        // If no code blocks found, return the whole response (might be plain code)
-        Ok(response.trim().to_string())
+        Ok(ExtractedCode {
+            code: extract_rust_code(&response.text),
+            truncated,
+        })
     }
 
     /// Heuristic-based code refinement (fallback)
@@ -498,17 +533,15 @@ impl CodeRefiner {
 
         // Apply simple heuristic fixes based on error category
         match error_analysis.category {
-            ErrorCategory::Compilation => {
-                // Add common imports if missing
+            // Add common imports if missing
+            ErrorCategory::Compilation
                 if error_analysis
                     .messages
                     .iter()
                     .any(|m| m.contains("cannot find"))
-                    && !refined_code.contains("use std::")
-                {
-                    refined_code =
-                        format!("use std::fmt;\nuse std::error::Error;\n\n{}", refined_code);
-                }
+                    && !refined_code.contains("use std::") =>
+            {
+                refined_code = format!("use std::fmt;\nuse std::error::Error;\n\n{}", refined_code);
             }
             ErrorCategory::TestFailure => {
                 // Heuristics can't fix logic errors
@@ -520,6 +553,8 @@ impl CodeRefiner {
         Ok(ImplCode {
             code: refined_code,
             loc: original_impl.loc,
+            language: original_impl.language,
+            dependencies: original_impl.dependencies.clone(),
         })
     }
 }
@@ -529,128 +564,288 @@ pub struct AgenticExecutor {
     analyzer: ErrorAnalyzer,
     refiner: CodeRefiner,
     max_iterations: usize,
+    checkpoints: Option<Arc<dyn CheckpointStore>>,
+    metrics: Option<Arc<SqliteStorage>>,
 }
 
 impl AgenticExecutor {
     /// Create a new agentic executor
     pub fn new(registry: Arc<ModelRegistry>, max_iterations: usize) -> Result<Self> {
+        Self::with_prompt_loader(registry, max_iterations, &PromptLoader::new())
+    }
+
+    /// Create a new agentic executor, resolving its prompts through `loader`
+    pub fn with_prompt_loader(
+        registry: Arc<ModelRegistry>,
+        max_iterations: usize,
+        loader: &PromptLoader,
+    ) -> Result<Self> {
         Ok(Self {
-            analyzer: ErrorAnalyzer::new(Arc::clone(&registry))?,
-            refiner: CodeRefiner::new(registry)?,
+            analyzer: ErrorAnalyzer::with_prompt_loader(Arc::clone(&registry), loader)?,
+            refiner: CodeRefiner::with_prompt_loader(registry, loader)?,
             max_iterations,
+            checkpoints: None,
+            metrics: None,
         })
     }
 
+    /// Limit refinement prompts to `max_tokens` estimated tokens
+    pub fn with_context_tokens(mut self, max_tokens: usize) -> Self {
+        self.refiner = self.refiner.with_context_tokens(max_tokens);
+        self
+    }
+
+    /// Save a checkpoint into `store` after each iteration, so an
+    /// interrupted run can be picked up with [`AgenticExecutor::resume`]
+    pub fn with_checkpoints(mut self, store: Arc<dyn CheckpointStore>) -> Self {
+        self.checkpoints = Some(store);
+        self
+    }
+
+    /// Record the test counts of each finished run as quality metrics in `storage`
+    pub fn with_metrics(mut self, storage: Arc<SqliteStorage>) -> Self {
+        self.metrics = Some(storage);
+        self
+    }
+
     /// Execute the observe-retry-fix loop
     ///
-    /// Returns the final implementation and a summary of the execution
+    /// If the checkpoint store holds an interrupted run of `task_id`, that
+    /// run is continued (see [`AgenticExecutor::resume`]) and `initial_impl`
+    /// is ignored. Returns the final implementation and a summary of the
+    /// execution.
     pub async fn execute_with_retry(
         &self,
+        task_id: &str,
         initial_impl: ImplCode,
         test_code: &TestCode,
         plan: &CodePlan,
         tools: &CodeAgentTools,
-        _test_path: &str,
         impl_path: &str,
     ) -> Result<(ImplCode, ExecutionSummary)> {
-        let mut current_impl = initial_impl;
-        let mut iteration = 0;
-        let mut attempts = Vec::new();
+        if self.has_checkpoint(task_id)? {
+            return self
+                .resume(task_id, test_code, plan, tools, impl_path)
+                .await;
+        }
+        let state = RunCheckpoint::new(task_id, initial_impl);
+        self.run_from(state, test_code, plan, tools, impl_path)
+            .await
+    }
+
+    /// Whether an interrupted run of `task_id` can be resumed
+    pub fn has_checkpoint(&self, task_id: &str) -> Result<bool> {
+        match &self.checkpoints {
+            Some(store) => Ok(store.load(task_id)?.is_some()),
+            None => Ok(false),
+        }
+    }
+
+    /// Continue the interrupted run of `task_id` from its last checkpoint
+    ///
+    /// Iterations already completed are not repeated; their attempts are
+    /// included in the returned summary.
+    pub async fn resume(
+        &self,
+        task_id: &str,
+        test_code: &TestCode,
+        plan: &CodePlan,
+        tools: &CodeAgentTools,
+        impl_path: &str,
+    ) -> Result<(ImplCode, ExecutionSummary)> {
+        let store = self.checkpoints.as_ref().ok_or_else(|| {
+            bodhya_core::Error::Config("No checkpoint store configured".to_string())
+        })?;
+        let state = store.load(task_id)?.ok_or_else(|| {
+            bodhya_core::Error::InvalidInput(format!("No checkpoint for task '{}'", task_id))
+        })?;
 
-        while iteration < self.max_iterations {
-            iteration += 1;
+        tracing::info!(
+            task_id,
+            iteration = state.iteration,
+            "Resuming agentic run from checkpoint"
+        );
+        self.run_from(state, test_code, plan, tools, impl_path)
+            .await
+    }
 
-            // Write current implementation
-            tools.write_file(impl_path, &current_impl.code).await?;
+    /// Run iterations after the last one recorded in `state`
+    async fn run_from(
+        &self,
+        mut state: RunCheckpoint,
+        test_code: &TestCode,
+        plan: &CodePlan,
+        tools: &CodeAgentTools,
+        impl_path: &str,
+    ) -> Result<(ImplCode, ExecutionSummary)> {
+        let mut successful = false;
+        let started = std::time::Instant::now();
 
-            // Run tests
-            let test_result = tools.run_cargo("test", &[]).await?;
+        while state.iteration < self.max_iterations {
+            state.iteration += 1;
+            let iteration = state.iteration;
 
-            let attempt = AttemptSummary {
+            let span = tracing::info_span!(
+                "agentic_iteration",
                 iteration,
-                success: test_result.success,
-                error_category: if test_result.success {
-                    None
-                } else {
-                    Some(self.analyzer.categorize_error(&test_result.stderr))
-                },
-                error_count: if test_result.success {
-                    0
-                } else {
-                    self.analyzer
-                        .extract_error_messages(&test_result.stderr)
-                        .len()
-                },
-            };
-
-            attempts.push(attempt);
-
-            if test_result.success {
-                // Success! Return the working implementation
-                return Ok((
-                    current_impl,
-                    ExecutionSummary {
-                        total_iterations: iteration,
-                        successful: true,
-                        attempts,
-                    },
-                ));
-            }
-
-            // Analyze errors with full context for LLM
-            let plan_context = format!(
-                "Purpose: {}\nRequirements: {}",
-                plan.purpose,
-                plan.requirements.join(", ")
+                duration_ms = tracing::field::Empty
             );
-            let error_analysis = self
-                .analyzer
-                .analyze_with_context(
-                    &test_result,
-                    &plan_context,
-                    &current_impl.code,
-                    &test_code.code,
+            let start = std::time::Instant::now();
+            let finished = self
+                .run_iteration(
+                    iteration,
+                    &mut state.current_impl,
+                    &mut state.attempts,
+                    test_code,
+                    plan,
+                    tools,
+                    impl_path,
                 )
-                .await?;
-
-            // Check if we've reached max iterations
-            if iteration >= self.max_iterations {
-                return Ok((
-                    current_impl,
-                    ExecutionSummary {
-                        total_iterations: iteration,
-                        successful: false,
-                        attempts,
-                    },
-                ));
+                .instrument(span.clone())
+                .await;
+            span.record("duration_ms", start.elapsed().as_millis() as u64);
+
+            if let Some(success) = finished? {
+                successful = success;
+                break;
             }
+            self.save_checkpoint(&state);
+        }
 
-            // Refine the implementation with iteration context
-            current_impl = self
-                .refiner
-                .refine_with_iteration(&current_impl, test_code, &error_analysis, plan, iteration)
-                .await?;
+        // The run is over; there is nothing left to resume
+        if let Some(store) = &self.checkpoints {
+            if let Err(e) = store.remove(&state.task_id) {
+                tracing::warn!(task_id = %state.task_id, "Failed to remove checkpoint: {}", e);
+            }
         }
+        self.save_metrics(&state, started.elapsed());
 
-        // Shouldn't reach here, but just in case
         Ok((
-            current_impl,
+            state.current_impl,
             ExecutionSummary {
-                total_iterations: iteration,
-                successful: false,
-                attempts,
+                total_iterations: state.iteration,
+                successful,
+                attempts: state.attempts,
             },
         ))
     }
+
+    /// Save `state` if checkpointing is enabled
+    ///
+    /// A failed save only costs the ability to resume, so the run continues.
+    fn save_checkpoint(&self, state: &RunCheckpoint) {
+        if let Some(store) = &self.checkpoints {
+            if let Err(e) = store.save(state) {
+                tracing::warn!(task_id = %state.task_id, "Failed to save checkpoint: {}", e);
+            }
+        }
+    }
+
+    /// Record the last attempt's test counts if metrics are enabled
+    ///
+    /// Like checkpoints, metrics are a side record; failing to save them
+    /// does not fail the run.
+    fn save_metrics(&self, state: &RunCheckpoint, elapsed: std::time::Duration) {
+        let (Some(storage), Some(last)) = (&self.metrics, state.attempts.last()) else {
+            return;
+        };
+        let metrics = last
+            .tests
+            .to_quality_metrics(&state.task_id)
+            .with_iterations(state.iteration as i32)
+            .with_execution_time(elapsed.as_millis() as i64);
+        if let Err(e) = storage.save_metrics(&metrics) {
+            tracing::warn!(task_id = %state.task_id, "Failed to save quality metrics: {}", e);
+        }
+    }
+
+    /// Test the current implementation once and refine it if the tests fail
+    ///
+    /// Returns `Some(success)` when the loop should stop: the tests passed or
+    /// this was the last iteration.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_iteration(
+        &self,
+        iteration: usize,
+        current_impl: &mut ImplCode,
+        attempts: &mut Vec<AttemptSummary>,
+        test_code: &TestCode,
+        plan: &CodePlan,
+        tools: &CodeAgentTools,
+        impl_path: &str,
+    ) -> Result<Option<bool>> {
+        // Write current implementation
+        tools.write_file(impl_path, &current_impl.code).await?;
+
+        // Run tests with the project's own runner
+        let runner = test_runner::detect_runner(tools, current_impl.language).await;
+        let test_result = runner.run(tools).await?;
+
+        let attempt = AttemptSummary {
+            iteration,
+            success: test_result.success,
+            tests: runner.summarize(&test_result),
+            error_category: if test_result.success {
+                None
+            } else {
+                Some(self.analyzer.categorize_error(&test_result.stderr))
+            },
+            error_count: if test_result.success {
+                0
+            } else {
+                self.analyzer
+                    .extract_error_messages(&test_result.stderr)
+                    .len()
+            },
+        };
+
+        attempts.push(attempt);
+
+        if test_result.success {
+            // Success! Keep the working implementation
+            return Ok(Some(true));
+        }
+
+        // Analyze errors with full context for LLM
+        let plan_context = format!(
+            "Purpose: {}\nRequirements: {}",
+            plan.purpose,
+            plan.requirements.join(", ")
+        );
+        let error_analysis = self
+            .analyzer
+            .analyze_with_context(
+                &test_result,
+                &plan_context,
+                &current_impl.code,
+                &test_code.code,
+            )
+            .await?;
+
+        // Check if we've reached max iterations
+        if iteration >= self.max_iterations {
+            return Ok(Some(false));
+        }
+
+        // Refine the implementation with iteration context
+        *current_impl = self
+            .refiner
+            .refine_with_iteration(current_impl, test_code, &error_analysis, plan, iteration)
+            .await?;
+        Ok(None)
+    }
 }
 
 /// Summary of a single attempt in the retry loop
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AttemptSummary {
     /// Iteration number
     pub iteration: usize,
     /// Whether this attempt succeeded
     pub success: bool,
+    /// Test counts of this attempt
+    #[serde(default)]
+    pub tests: TestSummary,
     /// Error category (if failed)
     pub error_category: Option<ErrorCategory>,
     /// Number of errors encountered
@@ -671,6 +866,68 @@ pub struct ExecutionSummary {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bodhya_core::FinishReason;
+
+    fn empty_registry() -> Arc<ModelRegistry> {
+        let manifest =
+            serde_json::from_value(serde_json::json!({ "models": {}, "backends": {} })).unwrap();
+        Arc::new(ModelRegistry::from_manifest(manifest, "/nonexistent"))
+    }
+
+    #[test]
+    fn test_extract_code_flags_truncated_response() {
+        let refiner = CodeRefiner::new(empty_registry()).unwrap();
+        let text = "```rust\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n```";
+
+        let complete = refiner
+            .extract_code_from_response(&ModelResponse::new(text))
+            .unwrap();
+        assert!(!complete.truncated);
+        assert!(complete.code.starts_with("fn add"));
+
+        let truncated = refiner
+            .extract_code_from_response(
+                &ModelResponse::new(text).with_finish_reason(FinishReason::Length),
+            )
+            .unwrap();
+        assert!(truncated.truncated);
+        assert_eq!(truncated.code, complete.code);
+    }
+
+    #[tokio::test]
+    async fn test_refine_prompt_fits_context_budget() {
+        use crate::prompt_budget::estimate_tokens;
+        use bodhya_model_registry::MockBackend;
+
+        let backend = Arc::new(MockBackend::new("mock").with_response("```rust\nfn add() {}\n```"));
+        let refiner = CodeRefiner::new(Arc::new(Arc::clone(&backend).registry("code")))
+            .unwrap()
+            .with_context_tokens(2000);
+
+        let analysis = ErrorAnalysis {
+            category: ErrorCategory::Compilation,
+            messages: vec!["error[E0425]: cannot find value `total`".to_string()],
+            suggestions: vec![],
+            root_cause: None,
+        };
+        let huge_impl = ImplCode::new("let value = compute();\n".repeat(2000));
+
+        let refined = refiner
+            .refine(
+                &huge_impl,
+                &TestCode::new("#[test]\nfn t() {}"),
+                &analysis,
+                &CodePlan::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(refined.code, "fn add() {}");
+
+        let prompt = &backend.requests()[0].prompt;
+        assert!(estimate_tokens(prompt) <= 2000);
+        assert!(prompt.contains("cannot find value `total`"));
+        assert!(prompt.contains("[truncated to fit context]"));
+    }
 
     #[test]
     fn test_error_category_equality() {
@@ -701,6 +958,7 @@ mod tests {
         let attempt = AttemptSummary {
             iteration: 1,
             success: false,
+            tests: TestSummary::default(),
             error_category: Some(ErrorCategory::Compilation),
             error_count: 2,
         };
@@ -720,18 +978,21 @@ mod tests {
                 AttemptSummary {
                     iteration: 1,
                     success: false,
+                    tests: TestSummary::default(),
                     error_category: Some(ErrorCategory::Compilation),
                     error_count: 2,
                 },
                 AttemptSummary {
                     iteration: 2,
                     success: false,
+                    tests: TestSummary::default(),
                     error_category: Some(ErrorCategory::TestFailure),
                     error_count: 1,
                 },
                 AttemptSummary {
                     iteration: 3,
                     success: true,
+                    tests: TestSummary::default(),
                     error_category: None,
                     error_count: 0,
                 },
@@ -743,4 +1004,160 @@ mod tests {
         assert_eq!(summary.attempts.len(), 3);
         assert!(summary.attempts[2].success);
     }
+
+    #[tokio::test]
+    async fn test_resume_continues_from_checkpointed_iteration() {
+        use crate::checkpoint::InMemoryCheckpointStore;
+        use bodhya_core::{ApprovalHook, ApprovalRequest};
+        use bodhya_model_registry::MockBackend;
+        use bodhya_tools_mcp::ToolRegistry;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Allows the first file write, then fails every later one
+        struct CrashOnSecondWrite(AtomicUsize);
+
+        #[async_trait::async_trait]
+        impl ApprovalHook for CrashOnSecondWrite {
+            async fn approve(&self, request: &ApprovalRequest) -> bool {
+                !matches!(request, ApprovalRequest::WriteFile { .. })
+                    || self.0.fetch_add(1, Ordering::SeqCst) == 0
+            }
+        }
+
+        // No Cargo.toml, so every test run fails quickly
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = Arc::new(InMemoryCheckpointStore::new());
+        let test_code = TestCode::new("#[test]\nfn adds() { assert_eq!(add(2, 3), 5); }");
+        let plan = CodePlan::with_purpose("Add two numbers");
+        let refined = "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}";
+
+        let backend = MockBackend::new("mock")
+            .with_role_response(ModelRole::Planner, "## Error Category\nCOMPILATION\n")
+            .with_role_response(ModelRole::Coder, format!("```rust\n{}\n```", refined));
+        let executor = AgenticExecutor::new(Arc::new(backend.into_registry("code")), 3)
+            .unwrap()
+            .with_checkpoints(store.clone());
+        let crashing_tools =
+            CodeAgentTools::new(Arc::new(ToolRegistry::with_defaults()), dir.path())
+                .with_approval(Arc::new(CrashOnSecondWrite(AtomicUsize::new(0))));
+
+        // Iteration 1 completes; iteration 2 dies writing the refined code
+        let result = executor
+            .execute_with_retry(
+                "task-1",
+                ImplCode::new("pub fn add(a: i32, b: i32) -> i32 {\n    a - b\n}"),
+                &test_code,
+                &plan,
+                &crashing_tools,
+                "src/lib.rs",
+            )
+            .await;
+        assert!(result.is_err());
+
+        let checkpoint = store.load("task-1").unwrap().unwrap();
+        assert_eq!(checkpoint.iteration, 1);
+        assert_eq!(checkpoint.current_impl.code, refined);
+        assert_eq!(checkpoint.attempts.len(), 1);
+
+        let backend = Arc::new(
+            MockBackend::new("mock")
+                .with_role_response(ModelRole::Planner, "## Error Category\nCOMPILATION\n"),
+        );
+        let executor = AgenticExecutor::new(Arc::new(Arc::clone(&backend).registry("code")), 2)
+            .unwrap()
+            .with_checkpoints(store.clone());
+        let tools = CodeAgentTools::new(Arc::new(ToolRegistry::with_defaults()), dir.path());
+
+        // Starting the task again continues the interrupted run
+        assert!(executor.has_checkpoint("task-1").unwrap());
+        let (final_impl, summary) = executor
+            .execute_with_retry(
+                "task-1",
+                ImplCode::new("unused"),
+                &test_code,
+                &plan,
+                &tools,
+                "src/lib.rs",
+            )
+            .await
+            .unwrap();
+
+        // Iteration 2 tested the checkpointed code without regenerating it
+        assert_eq!(summary.total_iterations, 2);
+        let iterations: Vec<usize> = summary.attempts.iter().map(|a| a.iteration).collect();
+        assert_eq!(iterations, vec![1, 2]);
+        assert_eq!(final_impl.code, refined);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/lib.rs")).unwrap(),
+            refined
+        );
+        assert!(backend
+            .requests()
+            .iter()
+            .all(|request| request.role != ModelRole::Coder));
+        assert!(store.load("task-1").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resume_without_checkpoint_is_rejected() {
+        use crate::checkpoint::InMemoryCheckpointStore;
+        use bodhya_tools_mcp::ToolRegistry;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let executor = AgenticExecutor::new(empty_registry(), 2)
+            .unwrap()
+            .with_checkpoints(Arc::new(InMemoryCheckpointStore::new()));
+        let tools = CodeAgentTools::new(Arc::new(ToolRegistry::with_defaults()), dir.path());
+
+        let err = executor
+            .resume(
+                "missing",
+                &TestCode::new(""),
+                &CodePlan::with_purpose("x"),
+                &tools,
+                "src/x.rs",
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, bodhya_core::Error::InvalidInput(_)));
+    }
+
+    #[tokio::test]
+    async fn test_finished_run_records_quality_metrics() {
+        use bodhya_model_registry::MockBackend;
+        use bodhya_tools_mcp::ToolRegistry;
+
+        // No Cargo.toml, so the single iteration fails
+        let dir = tempfile::TempDir::new().unwrap();
+        let storage = Arc::new(SqliteStorage::in_memory().unwrap());
+        let session = bodhya_storage::Session::new();
+        storage.save_session(&session).unwrap();
+        let mut record = bodhya_storage::TaskRecord::new(&session.id, "code", "Add", "code");
+        record.id = "task-1".to_string();
+        storage.save_task(&record).unwrap();
+        let backend = MockBackend::new("mock")
+            .with_role_response(ModelRole::Planner, "## Error Category\nCOMPILATION\n");
+        let executor = AgenticExecutor::new(Arc::new(backend.into_registry("code")), 1)
+            .unwrap()
+            .with_metrics(Arc::clone(&storage));
+        let tools = CodeAgentTools::new(Arc::new(ToolRegistry::with_defaults()), dir.path());
+
+        let (_, summary) = executor
+            .execute_with_retry(
+                "task-1",
+                ImplCode::new("pub fn add() {}"),
+                &TestCode::new("#[test]\nfn adds() {}"),
+                &CodePlan::with_purpose("Add two numbers"),
+                &tools,
+                "src/lib.rs",
+            )
+            .await
+            .unwrap();
+
+        assert!(!summary.successful);
+        let metrics = storage.get_metrics("task-1").unwrap().unwrap();
+        assert_eq!(metrics.iterations, 1);
+        assert!(metrics.custom_metrics.unwrap().contains("\"tests\""));
+    }
 }
@@ -1,7 +1,9 @@
 /// BDD and Gherkin feature generation
 ///
 /// This module handles generating Gherkin feature files from task descriptions and plans.
+use crate::model_call::generate_nonempty;
 use crate::planner::CodePlan;
+use crate::prompts::PromptLoader;
 use bodhya_core::{EngagementMode, ModelRequest, ModelRole, Result};
 use bodhya_model_registry::ModelRegistry;
 use std::sync::Arc;
@@ -13,6 +15,10 @@ pub struct GherkinFeature {
     pub name: String,
     /// Feature description
     pub description: String,
+    /// Tags applied to the feature (without the leading `@`)
+    pub tags: Vec<String>,
+    /// Background steps run before every scenario
+    pub background: Vec<GherkinStep>,
     /// Scenarios in the feature
     pub scenarios: Vec<GherkinScenario>,
 }
@@ -23,6 +29,8 @@ impl GherkinFeature {
         Self {
             name: name.into(),
             description: description.into(),
+            tags: Vec::new(),
+            background: Vec::new(),
             scenarios: Vec::new(),
         }
     }
@@ -32,15 +40,55 @@ impl GherkinFeature {
         self.scenarios.push(scenario);
     }
 
+    /// Add a tag to the feature
+    pub fn add_tag(&mut self, tag: impl Into<String>) {
+        self.tags.push(tag.into());
+    }
+
+    /// Add a Background step
+    pub fn add_background_step(&mut self, step: GherkinStep) {
+        self.background.push(step);
+    }
+
+    /// Scenarios with the Background steps prepended to each
+    pub fn scenarios_with_background(&self) -> Vec<GherkinScenario> {
+        self.scenarios
+            .iter()
+            .map(|scenario| {
+                let mut expanded = scenario.clone();
+                expanded.steps = self
+                    .background
+                    .iter()
+                    .chain(scenario.steps.iter())
+                    .cloned()
+                    .collect();
+                expanded
+            })
+            .collect()
+    }
+
     /// Format as Gherkin text
     pub fn to_gherkin(&self) -> String {
         let mut output = String::new();
 
+        if !self.tags.is_empty() {
+            output.push_str(&format!("{}\n", format_tags(&self.tags)));
+        }
         output.push_str(&format!("Feature: {}\n", self.name));
         output.push_str(&format!("  {}\n", self.description));
 
+        if !self.background.is_empty() {
+            output.push_str("\n  Background:\n");
+            for step in &self.background {
+                output.push_str(&format!("    {} {}\n", step.keyword, step.text));
+            }
+        }
+
         for scenario in &self.scenarios {
             output.push('\n');
+            if !scenario.tags.is_empty() {
+                output.push_str(&format!("  {}\n", format_tags(&scenario.tags)));
+            }
             output.push_str(&format!("  Scenario: {}\n", scenario.name));
 
             for step in &scenario.steps {
@@ -52,11 +100,21 @@ impl GherkinFeature {
     }
 }
 
+/// Format tags as a Gherkin tag line (e.g., "@smoke @slow")
+fn format_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| format!("@{}", tag))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// A Gherkin scenario with Given/When/Then steps
 #[derive(Clone, Debug, PartialEq)]
 pub struct GherkinScenario {
     /// Scenario name
     pub name: String,
+    /// Tags applied to the scenario (without the leading `@`)
+    pub tags: Vec<String>,
     /// Steps in the scenario
     pub steps: Vec<GherkinStep>,
 }
@@ -66,6 +124,7 @@ impl GherkinScenario {
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
+            tags: Vec::new(),
             steps: Vec::new(),
         }
     }
@@ -75,6 +134,11 @@ impl GherkinScenario {
         self.steps.push(step);
     }
 
+    /// Add a tag to the scenario
+    pub fn add_tag(&mut self, tag: impl Into<String>) {
+        self.tags.push(tag.into());
+    }
+
     /// Add a Given step
     pub fn given(&mut self, text: impl Into<String>) {
         self.add_step(GherkinStep::given(text));
@@ -147,18 +211,19 @@ impl BddGenerator {
         })
     }
 
+    /// Create a new BDD generator, resolving its prompt through `loader`
+    pub fn with_prompt_loader(registry: Arc<ModelRegistry>, loader: &PromptLoader) -> Result<Self> {
+        let prompt_template = loader.load("bdd.txt")?;
+
+        Ok(Self {
+            registry,
+            prompt_template,
+        })
+    }
+
     /// Load the BDD prompt template
     fn load_prompt_template() -> Result<String> {
-        let prompt_path = std::path::Path::new("prompts/code/bdd.txt");
-
-        if prompt_path.exists() {
-            std::fs::read_to_string(prompt_path).map_err(|e| {
-                bodhya_core::Error::Config(format!("Failed to load BDD prompt: {}", e))
-            })
-        } else {
-            // Embedded default prompt
-            Ok(include_str!("../../../prompts/code/bdd.txt").to_string())
-        }
+        crate::prompts::load_prompt("bdd.txt")
     }
 
     /// Generate Gherkin features from task description and plan
@@ -180,7 +245,8 @@ impl BddGenerator {
                 .get_model(&ModelRole::Planner, "code", &EngagementMode::Minimum)?;
 
         // Create model request
-        let request = ModelRequest::new(ModelRole::Planner, "code", prompt);
+        let request =
+            ModelRequest::new(ModelRole::Planner, "code", prompt).with_role_as_system_prompt();
 
         // Call the model backend
         let backend = self.registry.get_backend(&model_info.id).ok_or_else(|| {
@@ -190,7 +256,7 @@ impl BddGenerator {
             ))
         })?;
 
-        let response = backend.generate(request).await?;
+        let response = generate_nonempty(backend.as_ref(), request, "BDD features").await?;
 
         // Parse Gherkin from response
         let feature = Self::parse_gherkin_from_response(&response.text, task_description);
@@ -235,17 +301,45 @@ impl BddGenerator {
         output
     }
 
+    /// Build a stub feature without calling a model
+    ///
+    /// Used when no model registry is available (e.g., plan-only previews).
+    pub fn stub_feature(task_description: &str) -> GherkinFeature {
+        Self::parse_gherkin_from_response("", task_description)
+    }
+
     /// Parse Gherkin from model response
     ///
-    /// Simple parser for Phase 6. Looks for Feature/Scenario/Given/When/Then keywords.
-    fn parse_gherkin_from_response(response: &str, task_description: &str) -> GherkinFeature {
-        let lines: Vec<&str> = response.lines().collect();
+    /// Looks for Feature/Background/Scenario/Scenario Outline keywords, steps,
+    /// and `@tags`. If the response contains code fences only fenced content
+    /// is parsed; otherwise parsing starts at the first tag or `Feature:` line,
+    /// so `GherkinFeature::to_gherkin` output parses back to an equal feature.
+    /// Scenario Outlines are expanded into one concrete scenario per `Examples` row.
+    pub fn parse_gherkin_from_response(response: &str, task_description: &str) -> GherkinFeature {
+        let mut lines: Vec<&str> = response.lines().collect();
+
+        // Un-fenced Gherkin: skip any preamble and treat the rest as one block
+        let fenced = lines.iter().any(|line| line.trim().starts_with("```"));
+        if !fenced {
+            let start = lines.iter().position(|line| {
+                let trimmed = line.trim();
+                trimmed.starts_with("Feature:") || trimmed.starts_with('@')
+            });
+            lines = match start {
+                Some(start) => lines.split_off(start),
+                None => Vec::new(),
+            };
+        }
 
         let mut feature_name = String::new();
         let mut feature_description = String::new();
+        let mut feature_tags: Vec<String> = Vec::new();
+        let mut background: Vec<GherkinStep> = Vec::new();
+        let mut in_background = false;
         let mut scenarios: Vec<GherkinScenario> = Vec::new();
-        let mut current_scenario: Option<GherkinScenario> = None;
-        let mut in_code_block = false;
+        let mut current_scenario: Option<ScenarioBuilder> = None;
+        let mut pending_tags: Vec<String> = Vec::new();
+        let mut in_code_block = !fenced;
 
         for line in lines {
             let trimmed = line.trim();
@@ -256,56 +350,93 @@ impl BddGenerator {
                 continue;
             }
 
-            if !in_code_block {
+            if !in_code_block || trimmed.starts_with('#') {
+                continue;
+            }
+
+            // Tags apply to the next Feature or Scenario
+            if trimmed.starts_with('@') {
+                pending_tags.extend(
+                    trimmed
+                        .split_whitespace()
+                        .filter_map(|tag| tag.strip_prefix('@'))
+                        .filter(|tag| !tag.is_empty())
+                        .map(str::to_string),
+                );
                 continue;
             }
 
             // Parse Feature
-            if trimmed.starts_with("Feature:") {
-                feature_name = trimmed
-                    .strip_prefix("Feature:")
-                    .unwrap_or("")
-                    .trim()
-                    .to_string();
+            if let Some(name) = trimmed.strip_prefix("Feature:") {
+                feature_name = name.trim().to_string();
+                feature_tags.append(&mut pending_tags);
                 continue;
             }
 
-            // Parse Scenario
-            if trimmed.starts_with("Scenario:") {
+            // Parse Background
+            if trimmed.starts_with("Background:") {
+                if let Some(builder) = current_scenario.take() {
+                    scenarios.extend(builder.finish());
+                }
+                in_background = true;
+                continue;
+            }
+
+            // Parse Scenario / Scenario Outline
+            let scenario_header = trimmed
+                .strip_prefix("Scenario Outline:")
+                .or_else(|| trimmed.strip_prefix("Scenario Template:"))
+                .map(|name| (name, true))
+                .or_else(|| trimmed.strip_prefix("Scenario:").map(|name| (name, false)));
+            if let Some((name, is_outline)) = scenario_header {
                 // Save previous scenario if any
-                if let Some(scenario) = current_scenario.take() {
-                    scenarios.push(scenario);
+                if let Some(builder) = current_scenario.take() {
+                    scenarios.extend(builder.finish());
                 }
+                in_background = false;
 
-                let scenario_name = trimmed.strip_prefix("Scenario:").unwrap_or("").trim();
-                current_scenario = Some(GherkinScenario::new(scenario_name));
+                let mut scenario = GherkinScenario::new(name.trim());
+                scenario.tags.append(&mut pending_tags);
+                current_scenario = Some(ScenarioBuilder::new(scenario, is_outline));
+                continue;
+            }
+
+            // Parse Examples tables for Scenario Outlines
+            if trimmed.starts_with("Examples:") || trimmed.starts_with("Scenarios:") {
+                pending_tags.clear();
+                if let Some(ref mut builder) = current_scenario {
+                    builder.start_examples();
+                }
+                continue;
+            }
+
+            if trimmed.starts_with('|') {
+                if let Some(ref mut builder) = current_scenario {
+                    builder.add_table_row(parse_table_row(trimmed));
+                }
                 continue;
             }
 
             // Parse steps
-            if let Some(ref mut scenario) = current_scenario {
-                if trimmed.starts_with("Given ") {
-                    let text = trimmed.strip_prefix("Given ").unwrap_or("");
-                    scenario.given(text);
-                } else if trimmed.starts_with("When ") {
-                    let text = trimmed.strip_prefix("When ").unwrap_or("");
-                    scenario.when(text);
-                } else if trimmed.starts_with("Then ") {
-                    let text = trimmed.strip_prefix("Then ").unwrap_or("");
-                    scenario.then(text);
-                } else if trimmed.starts_with("And ") {
-                    let text = trimmed.strip_prefix("And ").unwrap_or("");
-                    scenario.add_step(GherkinStep::and(text));
+            if let Some(step) = parse_step(trimmed) {
+                if in_background {
+                    background.push(step);
+                } else if let Some(ref mut builder) = current_scenario {
+                    builder.scenario.add_step(step);
                 }
-            } else if !trimmed.is_empty() && feature_description.is_empty() {
+            } else if current_scenario.is_none()
+                && !in_background
+                && !trimmed.is_empty()
+                && feature_description.is_empty()
+            {
                 // Feature description (before first scenario)
                 feature_description = trimmed.to_string();
             }
         }
 
         // Save last scenario
-        if let Some(scenario) = current_scenario {
-            scenarios.push(scenario);
+        if let Some(builder) = current_scenario {
+            scenarios.extend(builder.finish());
         }
 
         // Fallback if parsing failed
@@ -318,6 +449,8 @@ impl BddGenerator {
         }
 
         let mut feature = GherkinFeature::new(feature_name, feature_description);
+        feature.tags = feature_tags;
+        feature.background = background;
         for scenario in scenarios {
             feature.add_scenario(scenario);
         }
@@ -335,6 +468,95 @@ impl BddGenerator {
     }
 }
 
+/// A scenario being parsed, with any Examples rows for outlines
+struct ScenarioBuilder {
+    scenario: GherkinScenario,
+    is_outline: bool,
+    header: Option<Vec<String>>,
+    examples: Vec<Vec<(String, String)>>,
+}
+
+impl ScenarioBuilder {
+    fn new(scenario: GherkinScenario, is_outline: bool) -> Self {
+        Self {
+            scenario,
+            is_outline,
+            header: None,
+            examples: Vec::new(),
+        }
+    }
+
+    /// Begin a new Examples table (the next row is its header)
+    fn start_examples(&mut self) {
+        self.header = None;
+    }
+
+    fn add_table_row(&mut self, cells: Vec<String>) {
+        if !self.is_outline {
+            // Data tables on plain scenarios aren't modelled
+            return;
+        }
+
+        match self.header {
+            None => self.header = Some(cells),
+            Some(ref header) => self
+                .examples
+                .push(header.iter().cloned().zip(cells).collect()),
+        }
+    }
+
+    /// Produce the concrete scenarios (one per Examples row for outlines)
+    fn finish(self) -> Vec<GherkinScenario> {
+        if !self.is_outline || self.examples.is_empty() {
+            return vec![self.scenario];
+        }
+
+        self.examples
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                let substitute = |text: &str| {
+                    row.iter().fold(text.to_string(), |acc, (key, value)| {
+                        acc.replace(&format!("<{}>", key), value)
+                    })
+                };
+
+                let mut scenario = GherkinScenario::new(format!(
+                    "{} (example {})",
+                    substitute(&self.scenario.name),
+                    index + 1
+                ));
+                scenario.tags = self.scenario.tags.clone();
+                for step in &self.scenario.steps {
+                    scenario.add_step(GherkinStep::new(&step.keyword, substitute(&step.text)));
+                }
+                scenario
+            })
+            .collect()
+    }
+}
+
+/// Parse a step line into a `GherkinStep`, if it starts with a step keyword
+fn parse_step(line: &str) -> Option<GherkinStep> {
+    ["Given", "When", "Then", "And", "But"]
+        .iter()
+        .find_map(|keyword| {
+            line.strip_prefix(keyword)
+                .and_then(|rest| rest.strip_prefix(' '))
+                .map(|text| GherkinStep::new(*keyword, text.trim()))
+        })
+}
+
+/// Split a `| a | b |` table row into trimmed cells
+fn parse_table_row(line: &str) -> Vec<String> {
+    line.trim()
+        .trim_start_matches('|')
+        .trim_end_matches('|')
+        .split('|')
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -453,6 +675,13 @@ Feature: Config loader
         assert_eq!(feature.scenarios[0].name, "Basic behavior");
     }
 
+    #[test]
+    fn test_stub_feature() {
+        let feature = BddGenerator::stub_feature("test task");
+        assert!(feature.name.contains("test task"));
+        assert_eq!(feature.scenarios.len(), 1);
+    }
+
     #[test]
     fn test_format_plan() {
         let mut plan = CodePlan::with_purpose("Build a config loader");
@@ -493,4 +722,187 @@ Feature: Config loader
         assert!(template.contains("{plan}"));
         assert!(template.contains("Gherkin"));
     }
+
+    #[test]
+    fn test_repeated_construction_reads_prompt_once() {
+        use crate::prompts::PromptCache;
+        use bodhya_model_registry::ModelManifest;
+        use std::collections::HashMap;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("bdd.txt"),
+            "custom {task_description} {plan}",
+        )
+        .unwrap();
+
+        let cache = Arc::new(PromptCache::new(8));
+        let loader = PromptLoader::new()
+            .with_dir(dir.path())
+            .with_cache(cache.clone());
+        let manifest = ModelManifest {
+            models: HashMap::new(),
+            backends: HashMap::new(),
+        };
+        let registry = Arc::new(ModelRegistry::from_manifest(manifest, "/tmp/models"));
+
+        for _ in 0..5 {
+            let generator = BddGenerator::with_prompt_loader(registry.clone(), &loader).unwrap();
+            assert!(generator.prompt_template.starts_with("custom"));
+        }
+        assert_eq!(cache.disk_reads(), 1);
+    }
+
+    #[test]
+    fn test_parse_gherkin_background_outline_and_tags() {
+        let response = r#"
+```gherkin
+@config
+Feature: Config loader
+  Load and validate configuration files
+
+  Background:
+    Given a temporary config directory
+    And the loader is initialized
+
+  @smoke
+  Scenario: Load valid config
+    Given a valid config.yaml file
+    When load_config is called
+    Then the Config struct is populated
+
+  @parsing @slow
+  Scenario Outline: Parse <format> files
+    Given a <format> file containing "<value>"
+    When load_config is called
+    Then the port is <port>
+
+    Examples:
+      | format | value     | port |
+      | yaml   | port: 80  | 80   |
+      | json   | {"p": 81} | 81   |
+```
+"#;
+
+        let feature = BddGenerator::parse_gherkin_from_response(response, "config loader");
+
+        assert_eq!(feature.name, "Config loader");
+        assert_eq!(feature.description, "Load and validate configuration files");
+        assert_eq!(feature.tags, vec!["config"]);
+
+        assert_eq!(feature.background.len(), 2);
+        assert_eq!(feature.background[0].keyword, "Given");
+        assert_eq!(feature.background[1].text, "the loader is initialized");
+
+        assert_eq!(feature.scenarios.len(), 3);
+        assert_eq!(feature.scenarios[0].name, "Load valid config");
+        assert_eq!(feature.scenarios[0].tags, vec!["smoke"]);
+        assert_eq!(feature.scenarios[0].steps.len(), 3);
+
+        let yaml = &feature.scenarios[1];
+        assert_eq!(yaml.name, "Parse yaml files (example 1)");
+        assert_eq!(yaml.tags, vec!["parsing", "slow"]);
+        assert_eq!(yaml.steps[0].text, "a yaml file containing \"port: 80\"");
+        assert_eq!(yaml.steps[2].text, "the port is 80");
+
+        let json = &feature.scenarios[2];
+        assert_eq!(json.name, "Parse json files (example 2)");
+        assert_eq!(json.steps[0].text, "a json file containing \"{\"p\": 81}\"");
+        assert_eq!(json.steps[2].text, "the port is 81");
+
+        // Background applies to every scenario
+        let with_background = feature.scenarios_with_background();
+        assert_eq!(with_background.len(), 3);
+        for scenario in &with_background {
+            assert_eq!(scenario.steps[0].text, "a temporary config directory");
+            assert_eq!(scenario.steps[1].keyword, "And");
+        }
+        assert_eq!(with_background[1].steps.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_scenario_outline_without_examples() {
+        let response = r#"
+```gherkin
+Feature: Outline
+  Scenario Outline: Uses <value>
+    Given <value>
+```
+"#;
+
+        let feature = BddGenerator::parse_gherkin_from_response(response, "outline");
+        assert_eq!(feature.scenarios.len(), 1);
+        assert_eq!(feature.scenarios[0].name, "Uses <value>");
+    }
+
+    #[test]
+    fn test_to_gherkin_with_background_and_tags() {
+        let mut feature = GherkinFeature::new("Tagged", "A tagged feature");
+        feature.add_tag("wip");
+        feature.add_background_step(GherkinStep::given("a clean workspace"));
+
+        let mut scenario = GherkinScenario::new("Run");
+        scenario.add_tag("smoke");
+        scenario.when("it runs");
+        feature.add_scenario(scenario);
+
+        let gherkin = feature.to_gherkin();
+        assert!(gherkin.starts_with("@wip\nFeature: Tagged"));
+        assert!(gherkin.contains("  Background:\n    Given a clean workspace"));
+        assert!(gherkin.contains("  @smoke\n  Scenario: Run"));
+    }
+
+    fn representative_feature() -> GherkinFeature {
+        let mut feature = GherkinFeature::new("Config loader", "Load and validate configuration");
+        feature.add_tag("config");
+        feature.add_background_step(GherkinStep::given("a temporary config directory"));
+
+        let mut valid = GherkinScenario::new("Load valid config");
+        valid.add_tag("smoke");
+        valid.given("a valid config.yaml file");
+        valid.when("load_config is called");
+        valid.then("the Config struct is populated");
+        valid.add_step(GherkinStep::and("no errors are returned"));
+        feature.add_scenario(valid);
+
+        let mut missing = GherkinScenario::new("Handle missing file");
+        missing.given("the config file does not exist");
+        missing.when("load_config is called");
+        missing.then("an error is returned");
+        missing.add_step(GherkinStep::new("But", "the process does not panic"));
+        feature.add_scenario(missing);
+
+        feature
+    }
+
+    #[test]
+    fn test_gherkin_round_trip() {
+        let feature = representative_feature();
+
+        let parsed = BddGenerator::parse_gherkin_from_response(&feature.to_gherkin(), "other");
+        assert_eq!(parsed, feature);
+    }
+
+    #[test]
+    fn test_gherkin_round_trip_fenced() {
+        let feature = representative_feature();
+        let fenced = format!(
+            "Here is the feature:\n\n```gherkin\n{}```\n",
+            feature.to_gherkin()
+        );
+
+        let parsed = BddGenerator::parse_gherkin_from_response(&fenced, "other");
+        assert_eq!(parsed, feature);
+    }
+
+    #[test]
+    fn test_parse_unfenced_gherkin_skips_preamble() {
+        let response = "Sure! Here it is.\n\nFeature: Greeting\n  Say hello\n\n  Scenario: Hello\n    When greet is called\n    Then it returns hello\n";
+
+        let feature = BddGenerator::parse_gherkin_from_response(response, "greeting");
+        assert_eq!(feature.name, "Greeting");
+        assert_eq!(feature.description, "Say hello");
+        assert_eq!(feature.scenarios.len(), 1);
+        assert_eq!(feature.scenarios[0].steps.len(), 2);
+    }
 }
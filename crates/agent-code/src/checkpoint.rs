@@ -0,0 +1,133 @@
+/// Checkpoints of agentic retry runs
+///
+/// The executor saves its state after each iteration, so a run that is
+/// interrupted can resume from the last completed iteration instead of
+/// repeating the model calls that got it there.
+use crate::agentic_executor::AttemptSummary;
+use crate::impl_gen::ImplCode;
+use bodhya_core::{Error, Result};
+use bodhya_storage::SqliteStorage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// State of an agentic run after its last completed iteration
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunCheckpoint {
+    /// Task the run belongs to
+    pub task_id: String,
+    /// Iterations completed so far
+    pub iteration: usize,
+    /// Implementation to test in the next iteration
+    pub current_impl: ImplCode,
+    /// Attempts made so far
+    pub attempts: Vec<AttemptSummary>,
+}
+
+impl RunCheckpoint {
+    /// State of a run that has not started yet
+    pub fn new(task_id: impl Into<String>, initial_impl: ImplCode) -> Self {
+        Self {
+            task_id: task_id.into(),
+            iteration: 0,
+            current_impl: initial_impl,
+            attempts: Vec::new(),
+        }
+    }
+}
+
+/// Storage for run checkpoints, one per task
+pub trait CheckpointStore: Send + Sync {
+    /// Store `checkpoint`, replacing the task's previous one
+    fn save(&self, checkpoint: &RunCheckpoint) -> Result<()>;
+
+    /// Look up the checkpoint of task `task_id`
+    fn load(&self, task_id: &str) -> Result<Option<RunCheckpoint>>;
+
+    /// Forget the checkpoint of task `task_id`, once its run has finished
+    fn remove(&self, task_id: &str) -> Result<()>;
+}
+
+/// In-process checkpoint store
+#[derive(Debug, Default)]
+pub struct InMemoryCheckpointStore {
+    checkpoints: Mutex<HashMap<String, RunCheckpoint>>,
+}
+
+impl InMemoryCheckpointStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, RunCheckpoint>> {
+        self.checkpoints.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CheckpointStore for InMemoryCheckpointStore {
+    fn save(&self, checkpoint: &RunCheckpoint) -> Result<()> {
+        self.lock()
+            .insert(checkpoint.task_id.clone(), checkpoint.clone());
+        Ok(())
+    }
+
+    fn load(&self, task_id: &str) -> Result<Option<RunCheckpoint>> {
+        Ok(self.lock().get(task_id).cloned())
+    }
+
+    fn remove(&self, task_id: &str) -> Result<()> {
+        self.lock().remove(task_id);
+        Ok(())
+    }
+}
+
+/// Persistent checkpoint store backed by the history database
+impl CheckpointStore for SqliteStorage {
+    fn save(&self, checkpoint: &RunCheckpoint) -> Result<()> {
+        let json = serde_json::to_string(checkpoint)?;
+        self.put_checkpoint(&checkpoint.task_id, &json)
+    }
+
+    fn load(&self, task_id: &str) -> Result<Option<RunCheckpoint>> {
+        match self.get_checkpoint(task_id)? {
+            Some(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|e| Error::Serialization(format!("Invalid checkpoint: {}", e))),
+            None => Ok(None),
+        }
+    }
+
+    fn remove(&self, task_id: &str) -> Result<()> {
+        self.delete_checkpoint(task_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agentic_executor::ErrorCategory;
+
+    #[test]
+    fn test_sqlite_store_round_trip() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let mut checkpoint = RunCheckpoint::new("task-1", ImplCode::new("fn add() {}"));
+        checkpoint.iteration = 1;
+        checkpoint.attempts.push(AttemptSummary {
+            iteration: 1,
+            success: false,
+            tests: Default::default(),
+            error_category: Some(ErrorCategory::Compilation),
+            error_count: 2,
+        });
+
+        CheckpointStore::save(&storage, &checkpoint).unwrap();
+        assert_eq!(
+            CheckpointStore::load(&storage, "task-1").unwrap(),
+            Some(checkpoint)
+        );
+
+        CheckpointStore::remove(&storage, "task-1").unwrap();
+        assert!(CheckpointStore::load(&storage, "task-1").unwrap().is_none());
+    }
+}
@@ -0,0 +1,210 @@
+//! Fenced code block extraction from model output
+//!
+//! Models often surround the real implementation with explanation snippets,
+//! split one file across several blocks, or nest fences, so extraction looks
+//! at every block rather than the first one.
+
+/// A fenced code block found in model output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CodeBlock {
+    /// Language tag after the opening fence (lowercased, may be empty)
+    pub language: String,
+    /// Block contents without the fences
+    pub code: String,
+}
+
+impl CodeBlock {
+    fn is_rust(&self) -> bool {
+        matches!(self.language.as_str(), "rust" | "rs")
+    }
+
+    fn is_bare(&self) -> bool {
+        self.language.is_empty()
+    }
+}
+
+/// Collect every fenced code block in `text`
+///
+/// A block closes on a fence at least as long as the one that opened it, so
+/// ```` fences can contain ``` lines. An unclosed block runs to the end.
+pub(crate) fn code_blocks(text: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(usize, CodeBlock)> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let fence_len = trimmed.chars().take_while(|&c| c == '`').count();
+
+        match current.as_mut() {
+            Some((open_len, _)) if fence_len >= *open_len && trimmed.len() == fence_len => {
+                if let Some((_, block)) = current.take() {
+                    blocks.push(block);
+                }
+            }
+            Some((_, block)) => {
+                block.code.push_str(line);
+                block.code.push('\n');
+            }
+            None if fence_len >= 3 => {
+                let language = trimmed[fence_len..]
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or("")
+                    .to_lowercase();
+                current = Some((
+                    fence_len,
+                    CodeBlock {
+                        language,
+                        code: String::new(),
+                    },
+                ));
+            }
+            None => {}
+        }
+    }
+
+    if let Some((_, block)) = current {
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Extract the Rust source from a model response
+///
+/// Prefers Rust-tagged blocks, then untagged ones, then any block. Several
+/// candidate blocks that declare distinct items are treated as one file split
+/// across explanations and concatenated; otherwise the largest block wins.
+/// Text without fences is returned as-is.
+pub(crate) fn extract_rust_code(text: &str) -> String {
+    let blocks = code_blocks(text);
+
+    let rust: Vec<&CodeBlock> = blocks.iter().filter(|b| b.is_rust()).collect();
+    let bare: Vec<&CodeBlock> = blocks.iter().filter(|b| b.is_bare()).collect();
+    let candidates = if !rust.is_empty() {
+        rust
+    } else if !bare.is_empty() {
+        bare
+    } else {
+        blocks.iter().collect()
+    };
+
+    let candidates: Vec<&str> = candidates
+        .iter()
+        .map(|b| b.code.trim())
+        .filter(|code| !code.is_empty())
+        .collect();
+
+    match candidates.as_slice() {
+        [] => text.trim().to_string(),
+        [only] => only.to_string(),
+        many if is_split_file(many) => many.join("\n\n"),
+        many => many
+            .iter()
+            .max_by_key(|code| code.len())
+            .map(|code| code.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// Whether every block declares items and no item is declared twice
+fn is_split_file(blocks: &[&str]) -> bool {
+    let mut seen = std::collections::HashSet::new();
+    blocks.iter().all(|block| {
+        let items = top_level_items(block);
+        !items.is_empty() && items.into_iter().all(|item| seen.insert(item))
+    })
+}
+
+/// Declarations at the start of unindented lines (`fn name`, `impl Foo`, ...)
+fn top_level_items(code: &str) -> Vec<String> {
+    const KEYWORDS: [&str; 10] = [
+        "fn", "struct", "enum", "trait", "mod", "type", "const", "static", "impl", "use",
+    ];
+
+    code.lines()
+        .filter(|line| !line.starts_with(char::is_whitespace))
+        .filter_map(|line| {
+            let mut words = line.split_whitespace().peekable();
+            while let Some(word) = words.peek() {
+                if word.starts_with("pub") || *word == "async" || *word == "unsafe" {
+                    words.next();
+                } else {
+                    break;
+                }
+            }
+
+            let keyword = words.next()?;
+            if !KEYWORDS.contains(&keyword) {
+                return None;
+            }
+            let rest = line.split_once(keyword)?.1;
+            let signature = rest.split(['{', '(', ';', '<', ':']).next()?.trim();
+            Some(format!("{} {}", keyword, signature))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_blocks_with_nested_fence() {
+        let text = "````markdown\n```rust\nfn inner() {}\n```\n````\n";
+        let blocks = code_blocks(text);
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].language, "markdown");
+        assert!(blocks[0].code.contains("```rust"));
+    }
+
+    #[test]
+    fn test_prefers_largest_rust_block() {
+        let text = "Call it like this:\n```rust\nlet x = add(1, 2);\n```\n\
+                    Here is the implementation:\n```rust\n\
+                    pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n```\n";
+
+        assert_eq!(
+            extract_rust_code(text),
+            "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}"
+        );
+    }
+
+    #[test]
+    fn test_overlapping_blocks_are_not_concatenated() {
+        let text = "Before:\n```rust\nfn add(a: i32) -> i32 { a }\n```\n\
+                    After:\n```rust\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n```\n";
+
+        assert_eq!(
+            extract_rust_code(text),
+            "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}"
+        );
+    }
+
+    #[test]
+    fn test_concatenates_file_split_across_blocks() {
+        let text = "First the struct:\n```rust\npub struct Point {\n    x: i32,\n}\n```\n\
+                    Then its methods:\n```rust\nimpl Point {\n    fn x(&self) -> i32 { self.x }\n}\n```\n";
+
+        let code = extract_rust_code(text);
+        assert!(code.starts_with("pub struct Point"));
+        assert!(code.contains("impl Point {"));
+    }
+
+    #[test]
+    fn test_rs_fence() {
+        let text = "```rs\nfn main() {}\n```";
+        assert_eq!(extract_rust_code(text), "fn main() {}");
+    }
+
+    #[test]
+    fn test_bare_fence_preferred_over_other_languages() {
+        let text = "```toml\n[dependencies]\n```\n```\nfn main() {}\n```";
+        assert_eq!(extract_rust_code(text), "fn main() {}");
+    }
+
+    #[test]
+    fn test_no_fence_returns_text() {
+        assert_eq!(extract_rust_code("  fn main() {}\n"), "fn main() {}");
+    }
+}
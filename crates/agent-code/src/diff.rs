@@ -0,0 +1,116 @@
+//! Unified diffs for previewing file writes
+//!
+//! Renders the line-level edit script shared with the edit tool (see
+//! [`line_edits`]) in the `diff -u` format, with three lines of context.
+
+use bodhya_tools_mcp::{line_edits, LineEdit, LineOp};
+
+/// Lines of unchanged context shown around each change
+const CONTEXT_LINES: usize = 3;
+
+/// Compute a unified diff from `old` to `new`
+///
+/// Returns an empty string when the contents are identical.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let edits = line_edits(&old_lines, &new_lines);
+
+    let changed: Vec<usize> = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| edit.op != LineOp::Equal)
+        .map(|(i, _)| i)
+        .collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    let mut output = format!("--- {}\n+++ {}\n", old_label, new_label);
+    for (start, end) in hunk_ranges(&changed, edits.len()) {
+        write_hunk(&mut output, &edits[start..end]);
+    }
+    output
+}
+
+/// Group changed edit indices into hunks, merging ones whose context overlaps
+fn hunk_ranges(changed: &[usize], len: usize) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &index in changed {
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let end = (index + CONTEXT_LINES + 1).min(len);
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+    ranges
+}
+
+fn write_hunk(output: &mut String, edits: &[LineEdit<'_>]) {
+    let old_count = edits.iter().filter(|e| e.op != LineOp::Insert).count();
+    let new_count = edits.iter().filter(|e| e.op != LineOp::Delete).count();
+    // Empty ranges point at the line before the hunk, as `diff -u` does
+    let old_start = edits[0].old_pos + usize::from(old_count > 0);
+    let new_start = edits[0].new_pos + usize::from(new_count > 0);
+
+    output.push_str(&format!(
+        "@@ -{},{} +{},{} @@\n",
+        old_start, old_count, new_start, new_count
+    ));
+    for edit in edits {
+        let prefix = match edit.op {
+            LineOp::Equal => ' ',
+            LineOp::Delete => '-',
+            LineOp::Insert => '+',
+        };
+        output.push(prefix);
+        output.push_str(edit.line);
+        output.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_content_has_empty_diff() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "a/f", "b/f"), "");
+    }
+
+    #[test]
+    fn test_modified_line() {
+        let diff = unified_diff("one\ntwo\nthree\n", "one\n2\nthree\n", "a/f", "b/f");
+        assert_eq!(
+            diff,
+            "--- a/f\n+++ b/f\n@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n"
+        );
+    }
+
+    #[test]
+    fn test_new_file_is_full_addition() {
+        let diff = unified_diff("", "fn main() {}\n", "/dev/null", "b/main.rs");
+        assert_eq!(
+            diff,
+            "--- /dev/null\n+++ b/main.rs\n@@ -0,0 +1,1 @@\n+fn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_distant_changes_use_separate_hunks() {
+        let old: String = (1..=20).map(|i| format!("{}\n", i)).collect();
+        let new: String = (1..=20)
+            .map(|i| match i {
+                2 => "two\n".to_string(),
+                19 => "nineteen\n".to_string(),
+                _ => format!("{}\n", i),
+            })
+            .collect();
+        let diff = unified_diff(&old, &new, "a/f", "b/f");
+
+        assert_eq!(diff.matches("@@ -").count(), 2);
+        assert!(diff.contains("@@ -1,5 +1,5 @@"));
+        assert!(diff.contains("@@ -16,5 +16,5 @@"));
+    }
+}
@@ -2,27 +2,257 @@
 ///
 /// This module handles generating Rust code to make failing tests pass.
 use crate::bdd::GherkinFeature;
+use crate::language::Language;
+use crate::model_call::generate_complete;
 use crate::planner::CodePlan;
+use crate::prompts::PromptLoader;
 use crate::tdd::TestCode;
 use bodhya_core::{EngagementMode, ModelRequest, ModelRole, Result};
 use bodhya_model_registry::ModelRegistry;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// An external crate the generated code needs
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dependency {
+    /// Crate name
+    pub name: String,
+    /// Version requirement (latest if `None`)
+    pub version: Option<String>,
+    /// Cargo features to enable
+    pub features: Vec<String>,
+}
+
+impl Dependency {
+    /// Depend on the latest version of `name` with default features
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: None,
+            features: Vec::new(),
+        }
+    }
+
+    /// Parse one entry of a "Dependencies:" list
+    ///
+    /// Accepts `serde`, `serde 1.0`, `serde@1.0`, `serde = "1.0"`, and
+    /// Cargo.toml-style tables, each optionally with `features = [...]` or
+    /// `(features: a, b)`.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let entry = entry.trim().trim_start_matches(['-', '*']).trim();
+        let entry = entry.trim_matches('`');
+        let name_end = entry
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(entry.len());
+        let name = &entry[..name_end];
+        if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let rest = &entry[name_end..];
+
+        let (rest, features) = match rest.find("features") {
+            Some(start) => {
+                let list = rest[start + "features".len()..]
+                    .trim_start_matches(|c: char| c.is_whitespace() || c == '=' || c == ':')
+                    .trim_start_matches('[');
+                let end = list.find([']', ')', '}']).unwrap_or(list.len());
+                let features = list[..end]
+                    .split(',')
+                    .map(|feature| feature.trim().trim_matches(['"', '\'', '`']).to_string())
+                    .filter(|feature| !feature.is_empty())
+                    .collect();
+                (&rest[..start], features)
+            }
+            None => (rest, Vec::new()),
+        };
+
+        let version = rest.find(|c: char| c.is_ascii_digit()).map(|start| {
+            let version = &rest[start..];
+            let end = version
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(version.len());
+            version[..end].trim_end_matches('.').to_string()
+        });
+
+        Some(Self {
+            name: name.to_string(),
+            version,
+            features,
+        })
+    }
+}
+
+/// Crates that ship with Rust (or refer to the current crate) and need no install
+const BUILTIN_CRATES: &[&str] = &[
+    "std",
+    "core",
+    "alloc",
+    "crate",
+    "self",
+    "super",
+    "proc_macro",
+];
+
+/// Parse the "Dependencies:" section of a model response
+///
+/// The section is a heading line followed by a bulleted list, or a single
+/// line with comma-separated names (`Dependencies: serde, regex`).
+pub fn parse_dependency_section(response: &str) -> Vec<Dependency> {
+    let mut lines = response.lines();
+    while let Some(line) = lines.next() {
+        let heading = line.trim().trim_matches(['#', '*', ' ']);
+        let Some(rest) = heading
+            .strip_prefix("Dependencies")
+            .or_else(|| heading.strip_prefix("dependencies"))
+        else {
+            continue;
+        };
+        // Only a heading ("Dependencies:"), not prose that starts with the word
+        let rest = rest.trim_start_matches('*');
+        let Some(rest) = rest.strip_prefix(':').or(rest.is_empty().then_some("")) else {
+            continue;
+        };
+        let rest = rest.trim_start_matches('*').trim();
+
+        if !rest.is_empty() {
+            return split_entries(rest)
+                .into_iter()
+                .filter(|entry| !entry.trim().eq_ignore_ascii_case("none"))
+                .filter_map(Dependency::parse)
+                .collect();
+        }
+
+        return lines
+            .map(str::trim)
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| line.starts_with('-') || line.starts_with('*'))
+            .filter(|line| {
+                !line
+                    .trim_start_matches(['-', '*'])
+                    .trim()
+                    .eq_ignore_ascii_case("none")
+            })
+            .filter_map(Dependency::parse)
+            .collect();
+    }
+    Vec::new()
+}
+
+/// Split `text` at commas outside brackets and parentheses
+fn split_entries(text: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let (mut depth, mut start) = (0i32, 0);
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' | '(' | '{' => depth += 1,
+            ']' | ')' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                entries.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    entries.push(&text[start..]);
+    entries
+}
+
+/// External crates named by `use` and `extern crate` items in Rust `code`
+///
+/// Standard library crates and modules declared in `code` are skipped.
+pub fn infer_dependencies(code: &str) -> Vec<Dependency> {
+    let item = |prefix: &str, line: &str| -> Option<String> {
+        let rest = line.strip_prefix(prefix)?.trim_start_matches("::");
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        Some(rest[..end].to_string()).filter(|name| !name.is_empty())
+    };
+
+    let lines: Vec<&str> = code.lines().map(|line| line.trim_start()).collect();
+    let strip_pub = |line: &str| line.strip_prefix("pub ").unwrap_or(line).to_string();
+    let local_modules: Vec<String> = lines
+        .iter()
+        .filter_map(|line| item("mod ", &strip_pub(line)))
+        .collect();
+
+    let mut names: Vec<String> = Vec::new();
+    for line in &lines {
+        let line = strip_pub(line);
+        let Some(name) = item("use ", &line).or_else(|| item("extern crate ", &line)) else {
+            continue;
+        };
+        if !BUILTIN_CRATES.contains(&name.as_str())
+            && !local_modules.contains(&name)
+            && !names.contains(&name)
+        {
+            names.push(name);
+        }
+    }
+    names.into_iter().map(Dependency::new).collect()
+}
+
 /// Generated implementation code
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ImplCode {
     /// The generated Rust implementation code
     pub code: String,
     /// Estimated lines of code
     pub loc: usize,
+    /// Language the code is written in
+    pub language: Language,
+    /// External crates the model declared, installed before tests run
+    pub dependencies: Vec<Dependency>,
 }
 
 impl ImplCode {
-    /// Create a new ImplCode instance
+    /// Create a new ImplCode instance without dependencies
+    ///
+    /// Crates named in `use` items are not installed: a `use` of a sibling
+    /// module would otherwise pull a same-named crate from crates.io. See
+    /// [`infer_dependencies`] for reporting them.
     pub fn new(code: impl Into<String>) -> Self {
         let code = code.into();
         let loc = Self::count_loc(&code);
-        Self { code, loc }
+        Self {
+            code,
+            loc,
+            language: Language::default(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// Crates the code's `use` items reference that were not declared
+    pub fn undeclared_dependencies(&self) -> Vec<Dependency> {
+        if self.language != Language::Rust {
+            return Vec::new();
+        }
+        infer_dependencies(&self.code)
+            .into_iter()
+            .filter(|inferred| {
+                !self
+                    .dependencies
+                    .iter()
+                    .any(|dep| dep.name == inferred.name)
+            })
+            .collect()
+    }
+
+    /// Set the language the code is written in
+    ///
+    /// Declared dependencies are dropped for languages other than Rust.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        if language != Language::Rust {
+            self.dependencies.clear();
+        }
+        self
+    }
+
+    /// Set the dependencies the model explicitly declared
+    pub fn with_dependencies(mut self, declared: Vec<Dependency>) -> Self {
+        self.dependencies = declared;
+        self
     }
 
     /// Count non-empty, non-comment lines of code
@@ -36,6 +266,20 @@ impl ImplCode {
     }
 }
 
+/// Line the coder prompt asks the model to end its answer with
+///
+/// Sent as a stop sequence, so the model halts there instead of carrying on
+/// with further code blocks.
+pub const END_MARKER: &str = "<<END>>";
+
+/// The part of a response before [`END_MARKER`]
+///
+/// Backends that ignore stop sequences return the marker and whatever the
+/// model wrote after it; that tail is not part of the answer.
+pub fn before_end_marker(text: &str) -> &str {
+    text.find(END_MARKER).map_or(text, |end| &text[..end])
+}
+
 /// Implementation code generator
 pub struct ImplGenerator {
     registry: Arc<ModelRegistry>,
@@ -53,18 +297,19 @@ impl ImplGenerator {
         })
     }
 
+    /// Create a new implementation generator, resolving its prompt through `loader`
+    pub fn with_prompt_loader(registry: Arc<ModelRegistry>, loader: &PromptLoader) -> Result<Self> {
+        let prompt_template = loader.load("coder.txt")?;
+
+        Ok(Self {
+            registry,
+            prompt_template,
+        })
+    }
+
     /// Load the coder prompt template
     fn load_prompt_template() -> Result<String> {
-        let prompt_path = std::path::Path::new("prompts/code/coder.txt");
-
-        if prompt_path.exists() {
-            std::fs::read_to_string(prompt_path).map_err(|e| {
-                bodhya_core::Error::Config(format!("Failed to load coder prompt: {}", e))
-            })
-        } else {
-            // Embedded default prompt
-            Ok(include_str!("../../../prompts/code/coder.txt").to_string())
-        }
+        crate::prompts::load_prompt("coder.txt")
     }
 
     /// Generate implementation code from tests, feature, and plan
@@ -90,7 +335,9 @@ impl ImplGenerator {
                 .get_model(&ModelRole::Coder, "code", &EngagementMode::Minimum)?;
 
         // Create model request
-        let request = ModelRequest::new(ModelRole::Coder, "code", prompt);
+        let request = ModelRequest::new(ModelRole::Coder, "code", prompt)
+            .with_role_as_system_prompt()
+            .with_stop(vec![END_MARKER.to_string()]);
 
         // Call the model backend
         let backend = self.registry.get_backend(&model_info.id).ok_or_else(|| {
@@ -100,12 +347,15 @@ impl ImplGenerator {
             ))
         })?;
 
-        let response = backend.generate(request).await?;
+        let response = generate_complete(backend.as_ref(), request, "implementation").await?;
 
-        // Extract Rust code from response
-        let impl_code = Self::extract_rust_code(&response.text);
+        // Extract Rust code from the answer proper
+        let answer = before_end_marker(&response.text);
+        let impl_code = Self::extract_rust_code(answer);
 
-        Ok(ImplCode::new(impl_code))
+        Ok(ImplCode::new(impl_code)
+            .with_language(plan.language)
+            .with_dependencies(parse_dependency_section(answer)))
     }
 
     /// Format a plan for inclusion in the prompt
@@ -295,4 +545,141 @@ pub fn example() {
         // Note: the /* line might still be counted by our simple filter
         assert!(loc >= 3);
     }
+
+    #[test]
+    fn test_parse_dependency_section() {
+        let response = r#"
+```rust
+use serde::Serialize;
+```
+
+Dependencies:
+- serde = { version = "1.0", features = ["derive"] }
+- regex 1.11
+- `chrono`
+
+This code derives Serialize.
+"#;
+        assert_eq!(
+            parse_dependency_section(response),
+            vec![
+                Dependency {
+                    name: "serde".to_string(),
+                    version: Some("1.0".to_string()),
+                    features: vec!["derive".to_string()],
+                },
+                Dependency {
+                    name: "regex".to_string(),
+                    version: Some("1.11".to_string()),
+                    features: vec![],
+                },
+                Dependency::new("chrono"),
+            ]
+        );
+
+        let inline =
+            parse_dependency_section("**Dependencies:** tokio@1 (features: full, macros), anyhow");
+        assert_eq!(inline[0].version.as_deref(), Some("1"));
+        assert_eq!(inline[0].features, vec!["full", "macros"]);
+        assert_eq!(inline[1], Dependency::new("anyhow"));
+
+        assert!(parse_dependency_section("Dependencies: none").is_empty());
+        assert!(parse_dependency_section("No extra crates needed").is_empty());
+        assert!(parse_dependency_section("Dependencies are not needed here").is_empty());
+    }
+
+    #[test]
+    fn test_infer_dependencies_from_use() {
+        let code = r#"
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+pub use regex::Regex;
+use crate::parser::Parser;
+use self::helpers::trim;
+use serde_json::Value;
+extern crate rand;
+
+mod helpers;
+use helpers::other;
+"#;
+        let names: Vec<String> = infer_dependencies(code)
+            .into_iter()
+            .map(|dep| dep.name)
+            .collect();
+        assert_eq!(names, vec!["serde", "regex", "serde_json", "rand"]);
+    }
+
+    #[test]
+    fn test_only_declared_dependencies_are_installed() {
+        let code = "use serde::Serialize;\nuse regex::Regex;\nuse utils::helper;\n";
+        assert!(ImplCode::new(code).dependencies.is_empty());
+
+        let impl_code = ImplCode::new(code).with_dependencies(vec![Dependency::parse(
+            "serde = \"1\" features = [\"derive\"]",
+        )
+        .unwrap()]);
+
+        assert_eq!(impl_code.dependencies.len(), 1);
+        assert_eq!(impl_code.dependencies[0].features, vec!["derive"]);
+        let undeclared: Vec<String> = impl_code
+            .undeclared_dependencies()
+            .into_iter()
+            .map(|dep| dep.name)
+            .collect();
+        assert_eq!(undeclared, vec!["regex", "utils"]);
+
+        let python = ImplCode::new("import os\nuse serde;").with_language(Language::Python);
+        assert!(python.dependencies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generate_sends_end_marker_as_stop_sequence() {
+        let backend = Arc::new(
+            bodhya_model_registry::MockBackend::new("mock")
+                .with_response("```rust\npub fn add(a: i32, b: i32) -> i32 { a + b }\n```"),
+        );
+        let generator =
+            ImplGenerator::new(Arc::new(Arc::clone(&backend).registry("code"))).unwrap();
+
+        let impl_code = generator
+            .generate(
+                &TestCode::new("#[test]\nfn test_add() {}"),
+                &GherkinFeature::new("Add", "Adds numbers"),
+                &CodePlan::new(),
+            )
+            .await
+            .unwrap();
+
+        assert!(impl_code.code.contains("pub fn add"));
+        let request = backend.requests().pop().unwrap();
+        assert_eq!(request.stop, vec![END_MARKER.to_string()]);
+        assert!(request.prompt.contains(END_MARKER));
+    }
+
+    #[tokio::test]
+    async fn test_generate_ignores_text_after_end_marker() {
+        // A backend that does not honour stop sequences keeps going
+        let backend = Arc::new(
+            bodhya_model_registry::MockBackend::new("mock").with_response(
+                "```rust\npub fn add(a: i32, b: i32) -> i32 { a + b }\n```\n\
+             Dependencies: none\n<<END>>\n\
+             ```rust\npub fn extra() {}\n```\nDependencies: regex\n",
+            ),
+        );
+        let generator =
+            ImplGenerator::new(Arc::new(Arc::clone(&backend).registry("code"))).unwrap();
+
+        let impl_code = generator
+            .generate(
+                &TestCode::new("#[test]\nfn test_add() {}"),
+                &GherkinFeature::new("Add", "Adds numbers"),
+                &CodePlan::new(),
+            )
+            .await
+            .unwrap();
+
+        assert!(impl_code.code.contains("pub fn add"));
+        assert!(!impl_code.code.contains("extra"));
+        assert!(impl_code.dependencies.is_empty());
+    }
 }
@@ -0,0 +1,102 @@
+/// Target language for generated code
+///
+/// The plan decides the language; generated tests and implementations carry
+/// it so files are written and tests run with the right toolchain.
+use serde::{Deserialize, Serialize};
+
+/// Programming language of generated code
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    /// Rust (tested with `cargo test`)
+    #[default]
+    Rust,
+    /// Python (tested with `pytest`)
+    Python,
+    /// JavaScript or TypeScript (tested with `npm test`)
+    JavaScript,
+}
+
+impl Language {
+    /// Get the language name as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Python => "python",
+            Language::JavaScript => "javascript",
+        }
+    }
+
+    /// Guess the language a task asks for, defaulting to Rust
+    pub fn detect(text: &str) -> Self {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .map(str::to_lowercase)
+            .collect();
+        let mentions = |names: &[&str]| words.iter().any(|w| names.contains(&w.as_str()));
+
+        if mentions(&["python", "py", "pytest", "django", "flask"]) {
+            Language::Python
+        } else if mentions(&["javascript", "js", "typescript", "ts", "nodejs", "npm"]) {
+            Language::JavaScript
+        } else {
+            Language::Rust
+        }
+    }
+
+    /// Command and arguments that run this language's test suite
+    pub fn test_command(&self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            Language::Rust => ("cargo", &["test"]),
+            Language::Python => ("pytest", &[]),
+            Language::JavaScript => ("npm", &["test"]),
+        }
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            Language::detect("Write a Python CSV parser"),
+            Language::Python
+        );
+        assert_eq!(
+            Language::detect("Build a Node.js REST client"),
+            Language::JavaScript
+        );
+        assert_eq!(
+            Language::detect("Implement a tree of nodes"),
+            Language::Rust
+        );
+        assert_eq!(
+            Language::detect("Create a fibonacci function"),
+            Language::Rust
+        );
+    }
+
+    #[test]
+    fn test_test_command() {
+        assert_eq!(Language::Rust.test_command(), ("cargo", &["test"][..]));
+        assert_eq!(Language::Python.test_command().0, "pytest");
+        assert_eq!(Language::JavaScript.test_command(), ("npm", &["test"][..]));
+    }
+
+    #[test]
+    fn test_language_serialization() {
+        assert_eq!(
+            serde_json::to_string(&Language::JavaScript).unwrap(),
+            "\"javascript\""
+        );
+        assert_eq!(Language::default(), Language::Rust);
+    }
+}
@@ -5,35 +5,148 @@
 /// Phase 7: TDD, implementation generation, and review (current)
 /// Phase 8: Tool integration with CodeAgentTools (v1.1)
 use async_trait::async_trait;
-use bodhya_core::{Agent, AgentCapability, AgentContext, AgentResult, Result, Task};
-use bodhya_model_registry::ModelRegistry;
+use bodhya_core::{
+    Agent, AgentCapability, AgentContext, AgentResult, PreviousResult, Result, RunEvent, Task,
+};
+use bodhya_model_registry::{ModelRegistry, SharedRegistry};
+use bodhya_storage::SqliteStorage;
+use std::future::Future;
 use std::sync::Arc;
+use tracing::Instrument;
 
 pub mod agentic_executor;
 mod bdd;
+mod checkpoint;
+mod code_blocks;
+mod diff;
 mod impl_gen;
+mod language;
+mod metadata;
+mod model_call;
+mod plan_cache;
 mod planner;
+mod prompt_budget;
+pub mod prompts;
+mod retry;
 mod review;
 mod tdd;
+pub mod test_runner;
 pub mod tools; // NEW: Tool wrapper module
 pub mod validate;
 
 // Re-export public types
 pub use agentic_executor::{
     AgenticExecutor, AttemptSummary, ErrorAnalysis, ErrorAnalyzer, ErrorCategory, ExecutionSummary,
+    ExtractedCode,
 };
 pub use bdd::{BddGenerator, GherkinFeature, GherkinScenario, GherkinStep};
-pub use impl_gen::{ImplCode, ImplGenerator};
-pub use planner::{CodePlan, Planner};
+pub use checkpoint::{CheckpointStore, InMemoryCheckpointStore, RunCheckpoint};
+pub use diff::unified_diff;
+pub use impl_gen::{
+    infer_dependencies, parse_dependency_section, Dependency, ImplCode, ImplGenerator,
+};
+pub use language::Language;
+pub use metadata::CodeResultMetadata;
+pub use plan_cache::{plan_cache_key, InMemoryPlanCache, PlanCache};
+pub use planner::{with_clarifications, Clarification, CodePlan, PlanOutcome, Planner};
+pub use prompt_budget::{estimate_tokens, PromptBudget, PromptSection, DEFAULT_CONTEXT_TOKENS};
+pub use prompts::{PromptCache, PromptLoader};
+pub use retry::{RetryBudget, RetryPolicy};
 pub use review::{CodeReview, CodeReviewer, ReviewStatus, ReviewSuggestion, SuggestionPriority};
 pub use tdd::{TddGenerator, TestCode};
-pub use tools::{CodeAgentTools, CommandOutput, ExecutionStats}; // NEW
+pub use test_runner::{CargoRunner, NpmRunner, PytestRunner, TestRunner, TestSummary};
+pub use tools::{CodeAgentTools, CommandOutput, CrateKind, ExecutionStats}; // NEW
 pub use validate::{CodeValidator, ValidationResult, ValidationSummary};
 
+/// Run one pipeline stage inside a `stage` span, recording how long it took
+///
+/// Stage transitions are also written to the context's event log, if any.
+async fn stage<T>(
+    ctx: &AgentContext,
+    task: &Task,
+    name: &'static str,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let span = tracing::info_span!(
+        "stage",
+        task_id = %task.id,
+        stage = name,
+        duration_ms = tracing::field::Empty
+    );
+    ctx.record_event(RunEvent::StageStarted {
+        stage: name.to_string(),
+    });
+    let start = std::time::Instant::now();
+    let result = future.instrument(span.clone()).await;
+    let duration_ms = start.elapsed().as_millis() as u64;
+    span.record("duration_ms", duration_ms);
+    ctx.record_event(RunEvent::StageFinished {
+        stage: name.to_string(),
+        duration_ms,
+        success: result.is_ok(),
+    });
+
+    if let Err(e) = &result {
+        tracing::warn!(parent: &span, error = %e, "Pipeline stage failed");
+    }
+    result
+}
+
+/// Run a pipeline stage, retrying transient failures while `budget` allows
+async fn stage_with_retry<T, F, Fut>(
+    ctx: &AgentContext,
+    task: &Task,
+    name: &'static str,
+    budget: &RetryBudget,
+    mut run: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut retries = 0;
+    loop {
+        let error = match stage(ctx, task, name, run()).await {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        let Some(delay) = budget.take(retries, &error) else {
+            return Err(error);
+        };
+        retries += 1;
+        tracing::warn!(
+            task_id = %task.id,
+            stage = name,
+            retry = retries,
+            retries_left = budget.remaining(),
+            error = %error,
+            "Retrying pipeline stage"
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// What the clarification check decided before the pipeline runs
+enum Clarified {
+    /// Go ahead, reusing the plan the check already made, if any
+    Proceed(Option<CodePlan>),
+    /// Stop with the clarifying questions
+    Ask(AgentResult),
+}
+
 /// Code generation agent
 pub struct CodeAgent {
     enabled: bool,
-    registry: Option<Arc<ModelRegistry>>,
+    registry: Option<SharedRegistry>,
+    retry: RetryPolicy,
+    /// Prompt templates, cached across tasks
+    prompts: PromptLoader,
+    /// Where retry runs checkpoint their progress, so they can be resumed
+    checkpoints: Option<Arc<dyn CheckpointStore>>,
+    /// Where retry runs record their test counts as quality metrics
+    metrics: Option<Arc<SqliteStorage>>,
+    /// Plans reused for previously seen task descriptions
+    plan_cache: Option<Arc<dyn PlanCache>>,
 }
 
 impl CodeAgent {
@@ -42,25 +155,96 @@ impl CodeAgent {
         Self {
             enabled: true,
             registry: None,
+            retry: RetryPolicy::default(),
+            prompts: PromptLoader::new(),
+            checkpoints: None,
+            metrics: None,
+            plan_cache: None,
         }
     }
 
     /// Create a new CodeAgent with model registry (Phase 6+)
     pub fn with_registry(registry: Arc<ModelRegistry>) -> Self {
+        Self::with_shared_registry(SharedRegistry::new(registry))
+    }
+
+    /// Create a new CodeAgent using whichever registry `registry` holds when
+    /// a task starts
+    pub fn with_shared_registry(registry: SharedRegistry) -> Self {
         Self {
             enabled: true,
             registry: Some(registry),
+            retry: RetryPolicy::default(),
+            prompts: PromptLoader::new(),
+            checkpoints: None,
+            metrics: None,
+            plan_cache: None,
         }
     }
 
+    /// Retry failed stages of the TDD pipeline according to `policy`
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Resolve prompt templates through `loader` (and its cache)
+    pub fn with_prompt_loader(mut self, loader: PromptLoader) -> Self {
+        self.prompts = loader;
+        self
+    }
+
+    /// Checkpoint retry runs in `store`; a task whose id has a checkpoint
+    /// continues from it instead of starting over
+    pub fn with_checkpoints(mut self, store: Arc<dyn CheckpointStore>) -> Self {
+        self.checkpoints = Some(store);
+        self
+    }
+
+    /// Record the test counts of retry runs as quality metrics in `storage`
+    pub fn with_metrics(mut self, storage: Arc<SqliteStorage>) -> Self {
+        self.metrics = Some(storage);
+        self
+    }
+
+    /// Reuse plans from `cache` when a task description was planned before
+    /// with the same planner model and prompt
+    pub fn with_plan_cache(mut self, cache: Arc<dyn PlanCache>) -> Self {
+        self.plan_cache = Some(cache);
+        self
+    }
+
+    /// Planner using the agent's prompt loader and plan cache
+    fn planner(&self, registry: Arc<ModelRegistry>) -> Result<Planner> {
+        let planner = Planner::with_prompt_loader(registry, &self.prompts)?;
+        Ok(match &self.plan_cache {
+            Some(cache) => planner.with_cache(Arc::clone(cache)),
+            None => planner,
+        })
+    }
+
     /// Create a new CodeAgent with specific enabled state
     pub fn with_enabled(enabled: bool) -> Self {
         Self {
             enabled,
             registry: None,
+            retry: RetryPolicy::default(),
+            prompts: PromptLoader::new(),
+            checkpoints: None,
+            metrics: None,
+            plan_cache: None,
         }
     }
 
+    /// The model registry, recording model calls if the context has an event recorder
+    fn registry_for(&self, ctx: &AgentContext) -> Option<Arc<ModelRegistry>> {
+        let registry = self.registry.as_ref()?.current();
+        Some(match &ctx.events {
+            Some(events) => Arc::new(registry.with_events(events)),
+            None => registry,
+        })
+    }
+
     /// Generate static hello world code (Phase 5 fallback)
     fn generate_hello_world(&self) -> String {
         r#"fn main() {
@@ -71,20 +255,93 @@ impl CodeAgent {
     }
 
     /// Generate code using planner and BDD (Phase 6)
-    async fn generate_with_bdd(&self, task: &Task) -> Result<String> {
-        let registry = self.registry.as_ref().ok_or_else(|| {
+    async fn generate_with_bdd(
+        &self,
+        task: &Task,
+        ctx: &AgentContext,
+        checked_plan: Option<&CodePlan>,
+    ) -> Result<String> {
+        let registry = &self.registry_for(ctx).ok_or_else(|| {
             bodhya_core::Error::Config("Model registry not configured for CodeAgent".to_string())
         })?;
 
-        // Step 1: Create a plan
-        let planner = Planner::new(Arc::clone(registry))?;
-        let plan = planner.plan(&task.description).await?;
-
-        // Step 2: Generate Gherkin features from plan
-        let bdd_generator = BddGenerator::new(Arc::clone(registry))?;
-        let feature = bdd_generator.generate(&task.description, &plan).await?;
+        // Step 1-2: Create a plan and generate Gherkin features from it
+        let (plan, feature) = self
+            .plan_and_features(registry, task, ctx, checked_plan)
+            .await?;
 
         // Step 3: Format the output (Phase 6: just return the Gherkin)
+        Ok(Self::format_plan_and_features(&plan, &feature))
+    }
+
+    /// Produce only the plan and Gherkin features (plan-only mode)
+    ///
+    /// Runs just the Planner and BddGenerator stages and never touches disk.
+    /// Without a model registry (or if the model calls fail) a stub plan is used.
+    async fn plan_only(
+        &self,
+        task: &Task,
+        ctx: &AgentContext,
+        checked_plan: Option<&CodePlan>,
+    ) -> String {
+        let (plan, feature) = match self.registry_for(ctx) {
+            Some(registry) => match self
+                .plan_and_features(&registry, task, ctx, checked_plan)
+                .await
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::warn!(task_id = %task.id, error = %e, "Planning failed, falling back to stub plan");
+                    Self::stub_plan_and_features(task)
+                }
+            },
+            None => Self::stub_plan_and_features(task),
+        };
+
+        let mut output = String::new();
+        output.push_str(&format!("# Plan Preview: {}\n\n", task.description));
+        output.push_str(&Self::format_plan_and_features(&plan, &feature));
+        output
+    }
+
+    /// Run the Planner (unless `checked_plan` is given) and BddGenerator stages
+    async fn plan_and_features(
+        &self,
+        registry: &Arc<ModelRegistry>,
+        task: &Task,
+        ctx: &AgentContext,
+        checked_plan: Option<&CodePlan>,
+    ) -> Result<(CodePlan, GherkinFeature)> {
+        let plan = match checked_plan {
+            Some(plan) => plan.clone(),
+            None => {
+                let planner = self.planner(Arc::clone(registry))?;
+                stage(ctx, task, "plan", planner.plan(&task.description)).await?
+            }
+        };
+
+        let bdd_generator = BddGenerator::with_prompt_loader(Arc::clone(registry), &self.prompts)?;
+        let feature = stage(
+            ctx,
+            task,
+            "bdd",
+            bdd_generator.generate(&task.description, &plan),
+        )
+        .await?;
+
+        Ok((plan, feature))
+    }
+
+    /// Build a stub plan and feature without calling any model
+    fn stub_plan_and_features(task: &Task) -> (CodePlan, GherkinFeature) {
+        (
+            Planner::stub_plan(&task.description),
+            BddGenerator::stub_feature(&task.description),
+        )
+    }
+
+    /// Format a plan and its Gherkin features as markdown
+    fn format_plan_and_features(plan: &CodePlan, feature: &GherkinFeature) -> String {
         let mut output = String::new();
         output.push_str("## Plan\n\n");
         output.push_str(&format!("**Purpose**: {}\n\n", plan.purpose));
@@ -100,7 +357,57 @@ impl CodeAgent {
         output.push_str("## BDD Features\n\n");
         output.push_str(&feature.to_gherkin());
 
-        Ok(output)
+        output
+    }
+
+    /// Fold answered clarifications into the description, or ask for them
+    ///
+    /// Answers in `payload.clarifications` are appended to the description
+    /// for re-planning. Otherwise, with `payload.clarify` set, an ambiguous
+    /// plan fails the task with its questions in `clarification_questions`
+    /// metadata, and a clear plan is handed on so the pipeline doesn't plan
+    /// again.
+    async fn clarify(&self, task: &mut Task, ctx: &AgentContext) -> Clarified {
+        let clarifications: Vec<Clarification> = task
+            .payload
+            .get("clarifications")
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default();
+        if !clarifications.is_empty() {
+            task.description = with_clarifications(&task.description, &clarifications);
+            return Clarified::Proceed(None);
+        }
+
+        if task.payload.get("clarify").and_then(|v| v.as_bool()) != Some(true) {
+            return Clarified::Proceed(None);
+        }
+        let Some(planner) = self
+            .registry_for(ctx)
+            .and_then(|registry| self.planner(registry).ok())
+        else {
+            return Clarified::Proceed(None);
+        };
+        match stage(
+            ctx,
+            task,
+            "plan",
+            planner.plan_or_clarify(&task.description),
+        )
+        .await
+        {
+            Ok(PlanOutcome::NeedsClarification(questions)) => Clarified::Ask(
+                AgentResult::failure(
+                    task.id.clone(),
+                    "Task is ambiguous; answer the clarifying questions and resubmit",
+                )
+                .with_metadata(serde_json::json!({ "clarification_questions": questions })),
+            ),
+            Ok(PlanOutcome::Plan(plan)) => Clarified::Proceed(Some(plan)),
+            Err(e) => {
+                tracing::warn!(task_id = %task.id, error = %e, "Clarification check failed");
+                Clarified::Proceed(None)
+            }
+        }
     }
 
     /// Extract CodeAgentTools from AgentContext
@@ -113,7 +420,17 @@ impl CodeAgent {
         let registry_arc = tools_arc.downcast::<ToolRegistry>().ok()?;
 
         let working_dir = ctx.get_working_dir().ok()?;
-        Some(CodeAgentTools::new(registry_arc, working_dir))
+        let mut tools = CodeAgentTools::new(registry_arc, working_dir);
+        if let Some(output_dir) = &ctx.output_dir {
+            tools = tools.with_output_dir(output_dir.clone());
+        }
+        if let Some(approval) = &ctx.approval {
+            tools = tools.with_approval(Arc::clone(approval));
+        }
+        if let Some(events) = &ctx.events {
+            tools = tools.with_events(events.clone());
+        }
+        Some(tools)
     }
 
     /// Execute task with tools (Phase 8/9)
@@ -124,20 +441,32 @@ impl CodeAgent {
         task: &Task,
         tools: &CodeAgentTools,
         ctx: &AgentContext,
-    ) -> Result<String> {
+        checked_plan: Option<&CodePlan>,
+    ) -> Result<(String, CodeResultMetadata)> {
         let mut output = String::new();
         output.push_str(&format!("# Executing: {}\n\n", task.description));
 
         // Require model registry for code generation
-        let registry = self.registry.as_ref().ok_or_else(|| {
+        let registry = &self.registry_for(ctx).ok_or_else(|| {
             bodhya_core::Error::Config(
                 "Model registry required for tool-based execution".to_string(),
             )
         })?;
 
+        // Confirm the files to be written before spending any model calls
+        let planned = self.plan_file_operations(&task.description);
+        let planned: Vec<&str> = planned.iter().map(String::as_str).collect();
+        tools.confirm_planned_writes(&planned).await?;
+
+        ctx.report_phase("planning");
         output.push_str("## Step 1: Planning\n\n");
-        let planner = Planner::new(Arc::clone(registry))?;
-        let plan = planner.plan(&task.description).await?;
+        let plan = match checked_plan {
+            Some(plan) => plan.clone(),
+            None => {
+                let planner = self.planner(Arc::clone(registry))?;
+                stage(ctx, task, "plan", planner.plan(&task.description)).await?
+            }
+        };
         output.push_str(&format!("**Purpose**: {}\n", plan.purpose));
         if !plan.components.is_empty() {
             output.push_str("**Components**: ");
@@ -146,25 +475,43 @@ impl CodeAgent {
         }
         output.push('\n');
 
+        ctx.report_phase("bdd");
         output.push_str("## Step 2: Generating BDD Features\n\n");
-        let bdd_generator = BddGenerator::new(Arc::clone(registry))?;
-        let feature = bdd_generator.generate(&task.description, &plan).await?;
+        let bdd_generator = BddGenerator::with_prompt_loader(Arc::clone(registry), &self.prompts)?;
+        let feature = stage(
+            ctx,
+            task,
+            "bdd",
+            bdd_generator.generate(&task.description, &plan),
+        )
+        .await?;
         output.push_str(&format!(
             "Feature: {} ({} scenarios)\n\n",
             feature.name,
             feature.scenarios.len()
         ));
 
+        ctx.report_phase("tdd");
         output.push_str("## Step 3: Generating Tests (RED Phase)\n\n");
-        let tdd_generator = TddGenerator::new(Arc::clone(registry))?;
-        let test_code = tdd_generator.generate(&feature, &plan).await?;
+        let tdd_generator = TddGenerator::with_prompt_loader(Arc::clone(registry), &self.prompts)?;
+        let test_code = stage(ctx, task, "tdd", tdd_generator.generate(&feature, &plan)).await?;
         output.push_str(&format!("Generated {} test(s)\n\n", test_code.test_count));
 
+        ctx.report_phase("implementation");
         output.push_str("## Step 4: Generating Implementation (GREEN Phase)\n\n");
-        let impl_generator = ImplGenerator::new(Arc::clone(registry))?;
-        let impl_code = impl_generator.generate(&test_code, &feature, &plan).await?;
+        let impl_generator =
+            ImplGenerator::with_prompt_loader(Arc::clone(registry), &self.prompts)?;
+        let impl_code = stage(
+            ctx,
+            task,
+            "impl",
+            impl_generator.generate(&test_code, &feature, &plan),
+        )
+        .await?;
         output.push_str(&format!("Generated {} lines of code\n\n", impl_code.loc));
+        let mut metadata = CodeResultMetadata::new(&plan, &feature, &test_code, &impl_code);
 
+        ctx.report_phase("writing_files");
         output.push_str("## Step 5: Writing Files to Disk\n\n");
 
         // Determine file paths based on task description
@@ -188,6 +535,42 @@ impl CodeAgent {
             }
         }
 
+        if impl_code.language == Language::Rust && !impl_code.dependencies.is_empty() {
+            ctx.report_phase("dependencies");
+            output.push_str("### Installing Dependencies\n\n");
+            for dep in &impl_code.dependencies {
+                let features: Vec<&str> = dep.features.iter().map(String::as_str).collect();
+                match tools
+                    .add_dependency(&dep.name, dep.version.as_deref(), &features)
+                    .await
+                {
+                    Ok(result) if result.success => {
+                        output.push_str(&format!("✓ Added {}\n", dep.name))
+                    }
+                    // Tests will show whether the crate was really needed
+                    Ok(result) => output.push_str(&format!(
+                        "✗ Failed to add {}: {}\n",
+                        dep.name,
+                        result.stderr.trim()
+                    )),
+                    Err(e) => {
+                        output.push_str(&format!("✗ Cannot add dependencies: {}\n", e));
+                        break;
+                    }
+                }
+            }
+            output.push('\n');
+        }
+        let undeclared = impl_code.undeclared_dependencies();
+        if !undeclared.is_empty() {
+            let names: Vec<&str> = undeclared.iter().map(|dep| dep.name.as_str()).collect();
+            output.push_str(&format!(
+                "Not installing crates the code uses without declaring: {}\n\n",
+                names.join(", ")
+            ));
+        }
+
+        ctx.report_phase("testing");
         output.push_str("## Step 6: Running Tests\n\n");
 
         // Check if retry mode is enabled (Phase 3)
@@ -197,23 +580,42 @@ impl CodeAgent {
         let final_impl = if use_retry {
             output.push_str("*Using agentic retry loop (observe-retry-fix)*\n\n");
             let max_iterations = ctx.execution_limits.max_iterations;
-            let executor = AgenticExecutor::new(Arc::clone(registry), max_iterations)?;
+            let mut executor = AgenticExecutor::with_prompt_loader(
+                Arc::clone(registry),
+                max_iterations,
+                &self.prompts,
+            )?
+            .with_context_tokens(ctx.config.models.defaults.context_tokens);
+            if let Some(store) = &self.checkpoints {
+                executor = executor.with_checkpoints(Arc::clone(store));
+                if executor.has_checkpoint(&task.id)? {
+                    output.push_str("*Resuming from checkpoint*\n\n");
+                }
+            }
+            if let Some(storage) = &self.metrics {
+                executor = executor.with_metrics(Arc::clone(storage));
+            }
 
-            let (final_impl, summary) = executor
-                .execute_with_retry(
+            let (final_impl, summary) = stage(
+                ctx,
+                task,
+                "agentic",
+                executor.execute_with_retry(
+                    &task.id,
                     impl_code.clone(),
                     &test_code,
                     &plan,
                     tools,
-                    &test_path,
                     &impl_path,
-                )
-                .await?;
+                ),
+            )
+            .await?;
 
             output.push_str(&format!(
                 "Completed after {} iteration(s)\n",
                 summary.total_iterations
             ));
+            metadata.iterations = summary.total_iterations;
             if summary.successful {
                 output.push_str("✓ Tests PASSED\n\n");
             } else {
@@ -234,32 +636,52 @@ impl CodeAgent {
                 output.push('\n');
             }
             output.push('\n');
+            metadata.loc = final_impl.loc;
+            metadata.impl_code = final_impl.code.clone();
             final_impl
         } else {
             // Single execution without retry
-            let test_result = tools.run_cargo("test", &[]).await?;
-            if test_result.success {
-                output.push_str("✓ Tests PASSED\n\n");
-                output.push_str("```\n");
-                let stdout_lines: Vec<&str> = test_result.stdout.lines().collect();
-                let start = stdout_lines.len().saturating_sub(10);
-                output.push_str(&stdout_lines[start..].join("\n"));
-                output.push_str("\n```\n\n");
+            let runner = test_runner::detect_runner(tools, test_code.language).await;
+            let result = runner.run(tools).await?;
+            if result.success {
+                output.push_str(&format!(
+                    "✓ Tests PASSED ({} via {})\n\n",
+                    runner.summarize(&result),
+                    runner.name()
+                ));
             } else {
-                output.push_str("✗ Tests FAILED\n\n");
-                output.push_str("```\n");
-                output.push_str(&test_result.stderr);
-                output.push_str("\n```\n\n");
+                output.push_str(&format!(
+                    "✗ Tests FAILED ({} via {})\n\n",
+                    runner.summarize(&result),
+                    runner.name()
+                ));
             }
+            output.push_str("```\n");
+            let combined = format!("{}\n{}", result.stdout, result.stderr);
+            let output_lines: Vec<&str> = combined.trim().lines().collect();
+            let start = output_lines.len().saturating_sub(10);
+            output.push_str(&output_lines[start..].join("\n"));
+            output.push_str("\n```\n\n");
             impl_code
         };
 
         // Step 7: Review the code (if tests passed)
-        let test_result = tools.run_cargo("test", &[]).await?;
-        if test_result.success {
+        let runner = test_runner::detect_runner(tools, test_code.language).await;
+        let final_result = runner.run(tools).await?;
+        let test_summary = runner.summarize(&final_result);
+        metadata.tests = Some(test_summary);
+        if final_result.success {
+            ctx.report_phase("review");
             output.push_str("## Step 7: Code Review\n\n");
-            let reviewer = CodeReviewer::new(Arc::clone(registry))?;
-            let review = reviewer.review(&final_impl, &plan, "Tests passed").await?;
+            let reviewer = CodeReviewer::with_prompt_loader(Arc::clone(registry), &self.prompts)?;
+            let review = stage(
+                ctx,
+                task,
+                "review",
+                reviewer.review(&final_impl, &plan, "Tests passed"),
+            )
+            .await?;
+            metadata.review_status = Some(review.status.clone());
 
             match review.status {
                 ReviewStatus::Approved => output.push_str("✓ Code review: APPROVED\n"),
@@ -290,8 +712,22 @@ impl CodeAgent {
             stats.commands_executed
         ));
         output.push_str(&format!("- Bytes written: {} bytes\n", stats.bytes_written));
+        output.push_str(&format!("- Tests: {}\n", test_summary));
+
+        Ok((output, metadata))
+    }
 
-        Ok(output)
+    /// Files `execute_with_tools` will create or modify for a task, tests first
+    ///
+    /// Paths are relative to the output (or working) directory.
+    pub fn plan_file_operations(&self, description: &str) -> Vec<String> {
+        let (test_path, impl_path) = self.determine_file_paths(description);
+        let mut planned = vec![test_path, impl_path];
+        // Declared dependencies are added to the manifest
+        if Language::detect(description) == Language::Rust {
+            planned.push("Cargo.toml".to_string());
+        }
+        planned
     }
 
     /// Determine file paths for test and implementation based on task description
@@ -318,30 +754,58 @@ impl CodeAgent {
 
     /// Generate code using full TDD pipeline (Phase 7)
     /// Planner → BDD → TDD → Implementation → Review
-    async fn generate_with_tdd(&self, task: &Task) -> Result<String> {
-        let registry = self.registry.as_ref().ok_or_else(|| {
+    async fn generate_with_tdd(
+        &self,
+        task: &Task,
+        ctx: &AgentContext,
+        checked_plan: Option<&CodePlan>,
+    ) -> Result<(String, CodeResultMetadata)> {
+        let registry = &self.registry_for(ctx).ok_or_else(|| {
             bodhya_core::Error::Config("Model registry not configured for CodeAgent".to_string())
         })?;
-
-        // Step 1: Create a plan
-        let planner = Planner::new(Arc::clone(registry))?;
-        let plan = planner.plan(&task.description).await?;
+        // Retries are shared by every stage of this run
+        let budget = self.retry.budget();
+
+        // Step 1: Create a plan, unless the clarification check already did
+        let plan = match checked_plan {
+            Some(plan) => plan.clone(),
+            None => {
+                let planner = self.planner(Arc::clone(registry))?;
+                stage_with_retry(ctx, task, "plan", &budget, || {
+                    planner.plan(&task.description)
+                })
+                .await?
+            }
+        };
 
         // Step 2: Generate Gherkin features from plan
-        let bdd_generator = BddGenerator::new(Arc::clone(registry))?;
-        let feature = bdd_generator.generate(&task.description, &plan).await?;
+        let bdd_generator = BddGenerator::with_prompt_loader(Arc::clone(registry), &self.prompts)?;
+        let feature = stage_with_retry(ctx, task, "bdd", &budget, || {
+            bdd_generator.generate(&task.description, &plan)
+        })
+        .await?;
 
         // Step 3: Generate failing tests (RED phase)
-        let tdd_generator = TddGenerator::new(Arc::clone(registry))?;
-        let test_code = tdd_generator.generate(&feature, &plan).await?;
+        let tdd_generator = TddGenerator::with_prompt_loader(Arc::clone(registry), &self.prompts)?;
+        let test_code = stage_with_retry(ctx, task, "tdd", &budget, || {
+            tdd_generator.generate(&feature, &plan)
+        })
+        .await?;
 
         // Step 4: Generate implementation to make tests pass (GREEN phase)
-        let impl_generator = ImplGenerator::new(Arc::clone(registry))?;
-        let impl_code = impl_generator.generate(&test_code, &feature, &plan).await?;
+        let impl_generator =
+            ImplGenerator::with_prompt_loader(Arc::clone(registry), &self.prompts)?;
+        let impl_code = stage_with_retry(ctx, task, "impl", &budget, || {
+            impl_generator.generate(&test_code, &feature, &plan)
+        })
+        .await?;
 
         // Step 5: Review the code (REFACTOR phase)
-        let reviewer = CodeReviewer::new(Arc::clone(registry))?;
-        let review = reviewer.review(&impl_code, &plan, "Tests passed").await?;
+        let reviewer = CodeReviewer::with_prompt_loader(Arc::clone(registry), &self.prompts)?;
+        let review = stage_with_retry(ctx, task, "review", &budget, || {
+            reviewer.review(&impl_code, &plan, "Tests passed")
+        })
+        .await?;
 
         // Step 6: Format the complete output
         let mut output = String::new();
@@ -394,10 +858,113 @@ impl CodeAgent {
             output.push('\n');
         }
 
-        Ok(output)
+        let mut metadata = CodeResultMetadata::new(&plan, &feature, &test_code, &impl_code);
+        metadata.review_status = Some(review.status);
+        Ok((output, metadata))
+    }
+
+    /// Rework an earlier result's implementation using the task description as feedback
+    ///
+    /// The last fenced block of the earlier result is taken as the implementation
+    /// and the one before it, if any, as its tests.
+    async fn refine_previous(
+        &self,
+        task: &Task,
+        previous: &PreviousResult,
+        ctx: &AgentContext,
+    ) -> Result<(String, CodeResultMetadata)> {
+        let registry = self.registry_for(ctx).ok_or_else(|| {
+            bodhya_core::Error::Config("Model registry not configured for CodeAgent".to_string())
+        })?;
+
+        let earlier = serde_json::from_value::<CodeResultMetadata>(previous.metadata.clone()).ok();
+        let (impl_source, test_source) = match &earlier {
+            Some(metadata) if !metadata.impl_code.is_empty() => {
+                (metadata.impl_code.clone(), metadata.test_code.clone())
+            }
+            _ => code_from_content(&previous.content).ok_or_else(|| {
+                bodhya_core::Error::InvalidInput(format!(
+                    "Result of task '{}' contains no code to refine",
+                    previous.task_id
+                ))
+            })?,
+        };
+
+        let language = earlier
+            .as_ref()
+            .map(|metadata| metadata.language)
+            .unwrap_or_default();
+        let mut plan = CodePlan::new();
+        plan.purpose = earlier
+            .as_ref()
+            .map(|metadata| metadata.purpose.clone())
+            .unwrap_or_else(|| previous.description.clone());
+        plan.language = language;
+
+        let original = ImplCode::new(impl_source).with_language(language);
+        let test_code = TestCode::new(test_source).with_language(language);
+        let feedback = ErrorAnalysis {
+            category: ErrorCategory::Unknown,
+            messages: vec![task.description.clone()],
+            suggestions: Vec::new(),
+            root_cause: Some("Requested changes to the previous implementation".to_string()),
+        };
+
+        let refiner = agentic_executor::CodeRefiner::with_prompt_loader(registry, &self.prompts)?
+            .with_context_tokens(ctx.config.models.defaults.context_tokens);
+        let refined = stage(
+            ctx,
+            task,
+            "refine",
+            refiner.refine(&original, &test_code, &feedback, &plan),
+        )
+        .await?;
+
+        let mut output = String::new();
+        output.push_str("# Code Refinement Complete\n\n");
+        output.push_str(&format!("**Feedback**: {}\n\n", task.description));
+        output.push_str("## Tests\n\n");
+        output.push_str("```rust\n");
+        output.push_str(&test_code.code);
+        output.push_str("\n```\n\n");
+        output.push_str("## Implementation\n\n");
+        output.push_str(&format!("{} lines of code\n\n", refined.loc));
+        output.push_str("```rust\n");
+        output.push_str(&refined.code);
+        output.push_str("\n```\n");
+
+        let diff = unified_diff(&original.code, &refined.code, "previous", "refined");
+        if !diff.is_empty() {
+            output.push_str("\n## Changes\n\n```diff\n");
+            output.push_str(&diff);
+            output.push_str("```\n");
+        }
+
+        let feature = GherkinFeature::new(&plan.purpose, "");
+        let mut metadata = CodeResultMetadata::new(&plan, &feature, &test_code, &refined);
+        if let Some(earlier) = earlier {
+            metadata.scenario_count = earlier.scenario_count;
+            metadata.iterations = earlier.iterations + 1;
+        }
+        Ok((output, metadata))
     }
 }
 
+/// Implementation and tests in the report of a result without stored code
+///
+/// Only blocks tagged with a language hold code: bare blocks hold test
+/// output and `diff` blocks hold changes. The implementation is the last
+/// code block and the tests the one before it.
+fn code_from_content(content: &str) -> Option<(String, String)> {
+    let mut blocks: Vec<String> = code_blocks::code_blocks(content)
+        .into_iter()
+        .filter(|block| !block.language.is_empty() && block.language != "diff")
+        .map(|block| block.code)
+        .collect();
+    let impl_code = blocks.pop()?;
+    Some((impl_code, blocks.pop().unwrap_or_default()))
+}
+
 impl Default for CodeAgent {
     fn default() -> Self {
         Self::new()
@@ -435,15 +1002,56 @@ impl Agent for CodeAgent {
         }
     }
 
-    async fn handle(&self, task: Task, ctx: AgentContext) -> Result<AgentResult> {
+    fn required_tools(&self) -> Vec<String> {
+        ["filesystem", "shell", "edit", "search", "mcp"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect()
+    }
+
+    async fn handle(&self, mut task: Task, ctx: AgentContext) -> Result<AgentResult> {
+        // Vague tasks may ask the caller to clarify instead of guessing a plan
+        let checked_plan = match self.clarify(&mut task, &ctx).await {
+            Clarified::Ask(result) => return Ok(result),
+            Clarified::Proceed(plan) => plan,
+        };
+        let checked_plan = checked_plan.as_ref();
+
+        // Plan-only mode: stop after planning and BDD, never touch disk
+        if ctx.execution_mode == bodhya_core::ExecutionMode::PlanOnly {
+            let content = self.plan_only(&task, &ctx, checked_plan).await;
+            return Ok(AgentResult::success(task.id, content));
+        }
+
+        // Feedback on an earlier result goes straight to the refiner
+        if let Some(previous) = &ctx.previous_result {
+            return match self.refine_previous(&task, previous, &ctx).await {
+                Ok((output, metadata)) => {
+                    Ok(AgentResult::success(task.id, output).with_metadata(metadata.to_value()))
+                }
+                Err(e) => Ok(AgentResult::failure(
+                    task.id,
+                    format!("Code refinement failed: {}", e),
+                )),
+            };
+        }
+
         // Phase 8/9: Try tool-based execution first if tools are available
         if let Some(tools) = Self::get_tools_from_context(&ctx) {
-            match self.execute_with_tools(&task, &tools, &ctx).await {
-                Ok(output) => return Ok(AgentResult::success(task.id, output)),
+            match self
+                .execute_with_tools(&task, &tools, &ctx, checked_plan)
+                .await
+            {
+                Ok((output, metadata)) => {
+                    return Ok(
+                        AgentResult::success(task.id, output).with_metadata(metadata.to_value())
+                    );
+                }
                 Err(e) => {
-                    eprintln!(
-                        "Tool-based execution failed: {}, falling back to model-based",
-                        e
+                    tracing::warn!(
+                        task_id = %task.id,
+                        error = %e,
+                        "Tool-based execution failed, falling back to model-based"
                     );
                 }
             }
@@ -453,17 +1061,22 @@ impl Agent for CodeAgent {
         let content = if self.registry.is_some() {
             // Phase 7: Use full TDD pipeline (Planner → BDD → TDD → Implementation → Review)
             // Falls back to Phase 6 BDD-only if TDD pipeline fails
-            match self.generate_with_tdd(&task).await {
-                Ok(output) => output,
+            match self.generate_with_tdd(&task, &ctx, checked_plan).await {
+                Ok((output, metadata)) => {
+                    return Ok(
+                        AgentResult::success(task.id, output).with_metadata(metadata.to_value())
+                    );
+                }
                 Err(e) => {
-                    eprintln!("TDD pipeline failed: {}, trying BDD-only", e);
-                    match self.generate_with_bdd(&task).await {
+                    tracing::warn!(task_id = %task.id, error = %e, "TDD pipeline failed, trying BDD-only");
+                    match self.generate_with_bdd(&task, &ctx, checked_plan).await {
                         Ok(output) => output,
                         Err(e2) => {
                             // Fall back to static response on all errors
-                            eprintln!(
-                                "BDD generation also failed: {}, falling back to static response",
-                                e2
+                            tracing::warn!(
+                                task_id = %task.id,
+                                error = %e2,
+                                "BDD generation also failed, falling back to static response"
                             );
                             let code = self.generate_hello_world();
                             format!(
@@ -617,6 +1230,659 @@ mod tests {
         assert!(agent_result.content.contains("Hello, World!"));
     }
 
+    /// Mock backend scripted for one run of the TDD pipeline
+    fn scripted_tdd_backend() -> Arc<bodhya_model_registry::MockBackend> {
+        Arc::new(script_tdd_pipeline(
+            bodhya_model_registry::MockBackend::new("mock"),
+        ))
+    }
+
+    /// Queue the replies for one run of the TDD pipeline on `backend`
+    fn script_tdd_pipeline(
+        backend: bodhya_model_registry::MockBackend,
+    ) -> bodhya_model_registry::MockBackend {
+        use bodhya_core::ModelRole;
+
+        backend
+            .with_role_response(
+                ModelRole::Planner,
+                "## Purpose: Add two numbers\n## Components\n- add function\n",
+            )
+            .with_role_response(
+                ModelRole::Planner,
+                "Feature: Addition\n  Scenario: Add positives\n    \
+                     Given two numbers 2 and 3\n    When I add them\n    Then I get 5\n",
+            )
+            .with_role_response(
+                ModelRole::Coder,
+                "```rust\n#[test]\nfn test_add() {\n    assert_eq!(add(2, 3), 5);\n}\n```",
+            )
+            .with_role_response(
+                ModelRole::Coder,
+                "```rust\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n```",
+            )
+            .with_role_response(
+                ModelRole::Reviewer,
+                "APPROVED\n## Strengths\n- Simple and correct\n",
+            )
+    }
+
+    #[tokio::test]
+    async fn test_clarify_returns_questions_then_folds_answers() {
+        use bodhya_core::ModelRole;
+
+        let backend = Arc::new(
+            bodhya_model_registry::MockBackend::new("mock")
+                .with_role_response(ModelRole::Planner, "Not sure what to build."),
+        );
+        let agent = CodeAgent::with_registry(Arc::new(Arc::clone(&backend).registry("code")));
+        let ctx = || {
+            AgentContext::new(Default::default())
+                .with_execution_mode(bodhya_core::ExecutionMode::PlanOnly)
+        };
+
+        let vague =
+            Task::new("Make it better").with_payload(serde_json::json!({ "clarify": true }));
+        let result = agent.handle(vague, ctx()).await.unwrap();
+        assert!(!result.success);
+        let questions = result.metadata["clarification_questions"]
+            .as_array()
+            .unwrap();
+        assert_eq!(questions.len(), 3);
+
+        let answered = Task::new("Make it better").with_payload(serde_json::json!({
+            "clarify": true,
+            "clarifications": [
+                { "question": questions[0], "answer": "Add two numbers" }
+            ]
+        }));
+        let result = agent.handle(answered, ctx()).await.unwrap();
+        assert!(result.success);
+        assert!(result.content.contains("Clarifications:"));
+        assert!(result.content.contains("Add two numbers"));
+    }
+
+    #[tokio::test]
+    async fn test_clear_plan_from_clarify_check_is_reused() {
+        use bodhya_core::ModelRole;
+
+        let backend = scripted_tdd_backend();
+        let agent = CodeAgent::with_registry(Arc::new(Arc::clone(&backend).registry("code")));
+        let ctx = AgentContext::new(Default::default())
+            .with_execution_mode(bodhya_core::ExecutionMode::PlanOnly);
+
+        let task = Task::new("Write a function that adds two numbers")
+            .with_payload(serde_json::json!({ "clarify": true }));
+        let result = agent.handle(task, ctx).await.unwrap();
+
+        assert!(result.success);
+        assert!(result.content.contains("**Purpose**: Add two numbers"));
+        assert!(result.content.contains("Feature: Addition"));
+        // One planner call for the plan, one for the BDD features
+        let planner_calls = backend
+            .requests()
+            .iter()
+            .filter(|r| r.role == ModelRole::Planner)
+            .count();
+        assert_eq!(planner_calls, 2);
+    }
+
+    #[tokio::test]
+    async fn test_tdd_pipeline_with_mock_backend() {
+        use bodhya_core::ModelRole;
+
+        let backend = scripted_tdd_backend();
+        let agent = CodeAgent::with_registry(Arc::new(Arc::clone(&backend).registry("code")));
+
+        let (output, _) = agent
+            .generate_with_tdd(
+                &Task::new("Write a function that adds two numbers"),
+                &AgentContext::new(Default::default()),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(output.contains("**Purpose**: Add two numbers"));
+        assert!(output.contains("Feature: Addition"));
+        assert!(output.contains("fn test_add()"));
+        assert!(output.contains("pub fn add(a: i32, b: i32) -> i32"));
+        assert!(output.contains("Simple and correct"));
+
+        let roles: Vec<_> = backend.requests().into_iter().map(|r| r.role).collect();
+        assert_eq!(
+            roles,
+            vec![
+                ModelRole::Planner,
+                ModelRole::Planner,
+                ModelRole::Coder,
+                ModelRole::Coder,
+                ModelRole::Reviewer
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generators_reject_empty_model_responses() {
+        use bodhya_core::Error;
+        use bodhya_model_registry::MockBackend;
+
+        /// A registry whose models all answer with whitespace
+        fn empty_registry() -> Arc<ModelRegistry> {
+            let backend = MockBackend::new("mock");
+            for _ in 0..2 {
+                backend.push_response(" \n\t");
+            }
+            Arc::new(backend.into_registry("code"))
+        }
+
+        fn assert_empty_error<T: std::fmt::Debug>(result: Result<T>, output: &str) {
+            let err = result.unwrap_err();
+            assert!(matches!(err, Error::Model(_)), "{:?}", err);
+            let message = err.to_string();
+            assert!(message.contains("empty response"), "{}", message);
+            assert!(
+                message.ends_with(&format!("while generating the {}", output)),
+                "{}",
+                message
+            );
+        }
+
+        let plan = CodePlan::new();
+        let feature = GherkinFeature::new("Addition", "");
+        let test_code = TestCode::new("#[test]\nfn test_add() {}");
+        let impl_code = ImplCode::new("pub fn add() {}");
+
+        let planner = Planner::new(empty_registry()).unwrap();
+        assert_empty_error(planner.plan("Add two numbers").await, "plan");
+
+        let bdd = BddGenerator::new(empty_registry()).unwrap();
+        assert_empty_error(bdd.generate("Add two numbers", &plan).await, "BDD features");
+
+        let tdd = TddGenerator::new(empty_registry()).unwrap();
+        assert_empty_error(tdd.generate(&feature, &plan).await, "tests");
+
+        let implementer = ImplGenerator::new(empty_registry()).unwrap();
+        assert_empty_error(
+            implementer.generate(&test_code, &feature, &plan).await,
+            "implementation",
+        );
+
+        let reviewer = CodeReviewer::new(empty_registry()).unwrap();
+        assert_empty_error(
+            reviewer.review(&impl_code, &plan, "Tests passed").await,
+            "review",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tdd_result_carries_structured_metadata() {
+        let backend = scripted_tdd_backend();
+        let agent = CodeAgent::with_registry(Arc::new(backend.registry("code")));
+
+        let result = agent
+            .handle(
+                Task::new("Write a function that adds two numbers"),
+                AgentContext::new(Default::default()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.metadata["test_count"], 1);
+        assert_eq!(result.metadata["loc"], 3);
+        assert_eq!(result.metadata["review_status"], "approved");
+        assert_eq!(result.metadata["scenario_count"], 1);
+        assert_eq!(result.metadata["purpose"], "Add two numbers");
+
+        let metadata: CodeResultMetadata = serde_json::from_value(result.metadata).unwrap();
+        assert_eq!(metadata.review_status, Some(ReviewStatus::Approved));
+        assert_eq!(metadata.iterations, 1);
+        assert_eq!(metadata.tests, None);
+    }
+
+    #[tokio::test]
+    async fn test_refine_previous_result_sends_prior_code_to_refiner() {
+        use bodhya_core::ModelRole;
+
+        let backend = scripted_tdd_backend();
+        let agent = CodeAgent::with_registry(Arc::new(Arc::clone(&backend).registry("code")));
+        let first = agent
+            .handle(
+                Task::new("Write a function that adds two numbers"),
+                AgentContext::new(Default::default()),
+            )
+            .await
+            .unwrap();
+
+        backend.push_role_response(
+            ModelRole::Coder,
+            "```rust\npub fn add(a: i64, b: i64) -> i64 {\n    a + b\n}\n```",
+        );
+        let previous = PreviousResult {
+            task_id: first.task_id.clone(),
+            description: "Write a function that adds two numbers".to_string(),
+            domain: "code".to_string(),
+            content: first.content,
+            metadata: first.metadata,
+        };
+        let result = agent
+            .handle(
+                Task::new("Use i64 instead of i32"),
+                AgentContext::new(Default::default()).with_previous_result(previous),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        let prompt = backend.requests().pop().unwrap().prompt;
+        assert!(prompt.contains("pub fn add(a: i32, b: i32) -> i32"));
+        assert!(prompt.contains("fn test_add()"));
+        assert!(prompt.contains("Use i64 instead of i32"));
+        assert!(result.content.contains("pub fn add(a: i64, b: i64) -> i64"));
+        assert_eq!(result.metadata["iterations"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_plan_cache_is_filled_by_pipeline() {
+        let backend = scripted_tdd_backend();
+        let cache = Arc::new(InMemoryPlanCache::new());
+        let agent = CodeAgent::with_registry(Arc::new(backend.registry("code")))
+            .with_plan_cache(cache.clone());
+
+        let result = agent
+            .handle(
+                Task::new("Write a function that adds two numbers"),
+                AgentContext::new(Default::default()),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_refine_tool_run_result_uses_stored_code() {
+        use bodhya_core::ModelRole;
+
+        let backend = scripted_tdd_backend();
+        let agent = CodeAgent::with_registry(Arc::new(Arc::clone(&backend).registry("code")));
+        backend.push_role_response(
+            ModelRole::Coder,
+            "```rust\npub fn add(a: i64, b: i64) -> i64 {\n    a + b\n}\n```",
+        );
+
+        // Tool runs report test output in a bare block, not the code
+        let mut plan = CodePlan::new();
+        plan.purpose = "Add two numbers".to_string();
+        let metadata = CodeResultMetadata::new(
+            &plan,
+            &GherkinFeature::new("Add", ""),
+            &TestCode::new("#[test]\nfn test_add() {\n    assert_eq!(add(2, 3), 5);\n}"),
+            &ImplCode::new("pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}"),
+        );
+        let previous = PreviousResult {
+            task_id: "tool-run".to_string(),
+            description: "Write a function that adds two numbers".to_string(),
+            domain: "code".to_string(),
+            content: "## Step 6: Running Tests\n\n✓ Tests PASSED\n\n```\ntest result: ok. 1 passed\n```\n"
+                .to_string(),
+            metadata: metadata.to_value(),
+        };
+        let result = agent
+            .handle(
+                Task::new("Use i64 instead of i32"),
+                AgentContext::new(Default::default()).with_previous_result(previous),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        let prompt = backend.requests().pop().unwrap().prompt;
+        assert!(prompt.contains("pub fn add(a: i32, b: i32) -> i32"));
+        assert!(prompt.contains("fn test_add()"));
+        assert!(!prompt.contains("test result: ok"));
+    }
+
+    #[tokio::test]
+    async fn test_refine_refined_result_skips_changes_diff() {
+        use bodhya_core::ModelRole;
+
+        let backend = scripted_tdd_backend();
+        let agent = CodeAgent::with_registry(Arc::new(Arc::clone(&backend).registry("code")));
+        let mut previous_result = agent
+            .handle(
+                Task::new("Write a function that adds two numbers"),
+                AgentContext::new(Default::default()),
+            )
+            .await
+            .unwrap();
+
+        for (feedback, code) in [
+            ("Use i64 instead of i32", "i64"),
+            ("Use u64 instead of i64", "u64"),
+        ] {
+            backend.push_role_response(
+                ModelRole::Coder,
+                format!(
+                    "```rust\npub fn add(a: {0}, b: {0}) -> {0} {{\n    a + b\n}}\n```",
+                    code
+                ),
+            );
+            let previous = PreviousResult {
+                task_id: previous_result.task_id.clone(),
+                description: "Write a function that adds two numbers".to_string(),
+                domain: "code".to_string(),
+                content: previous_result.content,
+                metadata: previous_result.metadata,
+            };
+            previous_result = agent
+                .handle(
+                    Task::new(feedback),
+                    AgentContext::new(Default::default()).with_previous_result(previous),
+                )
+                .await
+                .unwrap();
+            assert!(previous_result.success, "{:?}", previous_result.error);
+        }
+
+        let prompt = backend.requests().pop().unwrap().prompt;
+        assert!(prompt.contains("pub fn add(a: i64, b: i64) -> i64"));
+        assert!(prompt.contains("fn test_add()"));
+        assert!(!prompt.contains("+pub fn add"));
+        assert!(previous_result
+            .content
+            .contains("pub fn add(a: u64, b: u64) -> u64"));
+        assert_eq!(previous_result.metadata["iterations"], 3);
+    }
+
+    #[test]
+    fn test_code_from_content_skips_output_and_diff_blocks() {
+        let content = "## Tests\n\n```rust\nfn test_add() {}\n```\n\n## Implementation\n\n\
+                       ```rust\nfn add() {}\n```\n\n## Changes\n\n```diff\n-old\n+new\n```\n\n\
+                       ```\ntest result: ok\n```\n";
+
+        assert_eq!(
+            code_from_content(content),
+            Some((
+                "fn add() {}\n".to_string(),
+                "fn test_add() {}\n".to_string()
+            ))
+        );
+        assert_eq!(code_from_content("```\ntest result: ok\n```\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_refine_previous_uses_configured_context_tokens() {
+        use bodhya_core::ModelRole;
+
+        let backend = scripted_tdd_backend();
+        let agent = CodeAgent::with_registry(Arc::new(Arc::clone(&backend).registry("code")));
+        backend.push_role_response(
+            ModelRole::Coder,
+            "```rust\npub fn add(a: i64, b: i64) -> i64 {\n    a + b\n}\n```",
+        );
+
+        // Code far beyond the budget; only it and the tests can be trimmed
+        let long_impl = format!(
+            "pub fn add(a: i32, b: i32) -> i32 {{\n    a + b\n}}\n{}",
+            "// keep it fast\n".repeat(2000)
+        );
+        let metadata = CodeResultMetadata::new(
+            &CodePlan::with_purpose("Add two numbers"),
+            &GherkinFeature::new("Add", ""),
+            &TestCode::new("#[test]\nfn test_add() {\n    assert_eq!(add(2, 3), 5);\n}"),
+            &ImplCode::new(long_impl),
+        );
+        let previous = PreviousResult {
+            task_id: "previous".to_string(),
+            description: "Write a function that adds two numbers".to_string(),
+            domain: "code".to_string(),
+            content: String::new(),
+            metadata: metadata.to_value(),
+        };
+        let context_tokens = 1000;
+        let mut config = bodhya_core::AppConfig::default();
+        config.models.defaults.context_tokens = context_tokens;
+        agent
+            .handle(
+                Task::new("Use i64 instead of i32"),
+                AgentContext::new(config).with_previous_result(previous),
+            )
+            .await
+            .unwrap();
+
+        let prompt = backend.requests().pop().unwrap().prompt;
+        assert!(prompt.contains("truncated to fit context"));
+        assert!(estimate_tokens(&prompt) <= context_tokens);
+    }
+
+    /// Records the `stage` field and duration of every closed `stage` span
+    #[derive(Clone, Default)]
+    struct StageRecorder {
+        stages: Arc<std::sync::Mutex<Vec<(String, bool)>>>,
+    }
+
+    #[derive(Default)]
+    struct StageFields {
+        task_id: Option<String>,
+        stage: Option<String>,
+        duration_ms: Option<u64>,
+    }
+
+    impl tracing::field::Visit for StageFields {
+        fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+            if field.name() == "duration_ms" {
+                self.duration_ms = Some(value);
+            }
+        }
+
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            if field.name() == "stage" {
+                self.stage = Some(value.to_string());
+            }
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "task_id" {
+                self.task_id = Some(format!("{:?}", value));
+            }
+        }
+    }
+
+    impl<S> tracing_subscriber::Layer<S> for StageRecorder
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = StageFields::default();
+            attrs.record(&mut fields);
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(fields);
+            }
+        }
+
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if let Some(span) = ctx.span(id) {
+                if let Some(fields) = span.extensions_mut().get_mut::<StageFields>() {
+                    values.record(fields);
+                }
+            }
+        }
+
+        fn on_close(&self, id: tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+            let Some(span) = ctx.span(&id) else { return };
+            if span.name() != "stage" {
+                return;
+            }
+            let extensions = span.extensions();
+            if let Some(fields) = extensions.get::<StageFields>() {
+                let complete =
+                    fields.task_id.as_deref() == Some("task-1") && fields.duration_ms.is_some();
+                self.stages
+                    .lock()
+                    .unwrap()
+                    .push((fields.stage.clone().unwrap_or_default(), complete));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tdd_pipeline_emits_stage_spans() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let recorder = StageRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let agent = CodeAgent::with_registry(Arc::new(scripted_tdd_backend().registry("code")));
+        let task = Task {
+            id: "task-1".to_string(),
+            ..Task::new("Write a function that adds two numbers")
+        };
+        let ctx = AgentContext::new(Default::default());
+        agent.generate_with_tdd(&task, &ctx, None).await.unwrap();
+
+        let stages = recorder.stages.lock().unwrap().clone();
+        let names: Vec<&str> = stages.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["plan", "bdd", "tdd", "impl", "review"]);
+        assert!(stages.iter().all(|(_, complete)| *complete));
+    }
+
+    #[tokio::test]
+    async fn test_tdd_pipeline_retries_transient_failure() {
+        use bodhya_core::ModelRole;
+        use bodhya_model_registry::MockBackend;
+
+        let backend = Arc::new(script_tdd_pipeline(
+            MockBackend::new("mock").with_role_unavailable(ModelRole::Planner, "connection reset"),
+        ));
+        let agent = CodeAgent::with_registry(Arc::new(Arc::clone(&backend).registry("code")))
+            .with_retry_policy(RetryPolicy::new(1, 2).with_backoff(std::time::Duration::ZERO));
+
+        let (output, _) = agent
+            .generate_with_tdd(
+                &Task::new("Write a function that adds two numbers"),
+                &AgentContext::new(Default::default()),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(output.contains("**Purpose**: Add two numbers"));
+        assert!(output.contains("pub fn add(a: i32, b: i32) -> i32"));
+        // The failed planner call was retried once
+        let planner_calls = backend
+            .requests()
+            .iter()
+            .filter(|r| r.role == ModelRole::Planner)
+            .count();
+        assert_eq!(planner_calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_tdd_pipeline_does_not_retry_permanent_model_error() {
+        use bodhya_core::ModelRole;
+        use bodhya_model_registry::MockBackend;
+
+        let backend = Arc::new(script_tdd_pipeline(
+            MockBackend::new("mock").with_role_failure(ModelRole::Planner, "model file corrupt"),
+        ));
+        let agent = CodeAgent::with_registry(Arc::new(Arc::clone(&backend).registry("code")))
+            .with_retry_policy(RetryPolicy::new(1, 2).with_backoff(std::time::Duration::ZERO));
+
+        let err = agent
+            .generate_with_tdd(
+                &Task::new("Write a function that adds two numbers"),
+                &AgentContext::new(Default::default()),
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("model file corrupt"));
+        assert_eq!(backend.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tdd_pipeline_writes_event_log() {
+        use bodhya_core::{events::read_events, EventRecorder, JsonlEventSink, ModelRole};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("events.jsonl");
+        let sink = Arc::new(JsonlEventSink::new(&path).unwrap());
+        let ctx =
+            AgentContext::new(Default::default()).with_events(EventRecorder::new("task-1", sink));
+
+        let agent = CodeAgent::with_registry(Arc::new(scripted_tdd_backend().registry("code")));
+        let task = Task {
+            id: "task-1".to_string(),
+            ..Task::new("Write a function that adds two numbers")
+        };
+        agent.generate_with_tdd(&task, &ctx, None).await.unwrap();
+
+        let records = read_events(&path).unwrap();
+        assert!(records.iter().all(|r| r.task_id == "task-1"));
+
+        // Each stage starts, makes its model call(s), and finishes in order
+        let sequence: Vec<String> = records
+            .iter()
+            .map(|r| match &r.event {
+                RunEvent::StageStarted { stage } => format!("start:{}", stage),
+                RunEvent::StageFinished { stage, success, .. } => {
+                    assert!(success);
+                    format!("end:{}", stage)
+                }
+                RunEvent::ModelCall {
+                    role,
+                    completion_tokens,
+                    ..
+                } => {
+                    assert!(*completion_tokens > 0);
+                    format!("model:{}", role)
+                }
+                other => panic!("unexpected event: {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            sequence,
+            vec![
+                "start:plan",
+                "model:planner",
+                "end:plan",
+                "start:bdd",
+                "model:planner",
+                "end:bdd",
+                "start:tdd",
+                "model:coder",
+                "end:tdd",
+                "start:impl",
+                "model:coder",
+                "end:impl",
+                "start:review",
+                "model:reviewer",
+                "end:review",
+            ]
+        );
+        assert!(matches!(
+            records[1].event,
+            RunEvent::ModelCall {
+                role: ModelRole::Planner,
+                ..
+            }
+        ));
+    }
+
     #[tokio::test]
     async fn test_code_agent_without_tools_falls_back() {
         // Create agent without tools in context
@@ -635,6 +1901,83 @@ mod tests {
         assert!(!agent_result.content.contains("Tool-Based Execution"));
     }
 
+    #[tokio::test]
+    async fn test_code_agent_plan_only_writes_no_files() {
+        use bodhya_core::ExecutionMode;
+        use bodhya_tools_mcp::ToolRegistry;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let tools = Arc::new(ToolRegistry::with_defaults());
+        let ctx = AgentContext::new(Default::default())
+            .with_working_dir(temp_dir.path().to_path_buf())
+            .with_execution_mode(ExecutionMode::PlanOnly)
+            .with_tools(tools as Arc<dyn std::any::Any + Send + Sync>);
+
+        let agent = CodeAgent::new();
+        let task = Task::new("Generate a fibonacci function");
+        let result = agent.handle(task, ctx).await.unwrap();
+
+        assert!(result.success);
+        assert!(result.content.contains("## Plan"));
+        assert!(result.content.contains("## BDD Features"));
+        assert!(result.content.contains("Feature:"));
+        assert!(!result.content.contains("fn main()"));
+
+        // Nothing should have been written to the working directory
+        let entries: Vec<_> = std::fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn test_plan_file_operations_lists_test_and_impl_paths() {
+        let agent = CodeAgent::new();
+
+        let planned = agent.plan_file_operations("Generate fibonacci function");
+
+        assert_eq!(
+            planned,
+            vec!["tests/fibonacci_test.rs", "src/fibonacci.rs", "Cargo.toml"]
+        );
+        let python = agent.plan_file_operations("Generate fibonacci function in Python");
+        assert!(!python.contains(&"Cargo.toml".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_declined_file_plan_stops_before_model_calls() {
+        use bodhya_core::{ApprovalHook, ApprovalRequest};
+        use bodhya_tools_mcp::ToolRegistry;
+
+        struct DeclinePlan;
+
+        #[async_trait::async_trait]
+        impl ApprovalHook for DeclinePlan {
+            async fn approve(&self, request: &ApprovalRequest) -> bool {
+                !matches!(request, ApprovalRequest::PlannedWrites { .. })
+            }
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let tools = CodeAgentTools::new(Arc::new(ToolRegistry::with_defaults()), temp_dir.path())
+            .with_approval(Arc::new(DeclinePlan));
+        let backend = scripted_tdd_backend();
+        let agent = CodeAgent::with_registry(Arc::new(Arc::clone(&backend).registry("code")));
+
+        let err = agent
+            .execute_with_tools(
+                &Task::new("Generate fibonacci function"),
+                &tools,
+                &AgentContext::new(Default::default()),
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("tests/fibonacci_test.rs"));
+        assert!(backend.requests().is_empty());
+        assert!(std::fs::read_dir(temp_dir.path()).unwrap().next().is_none());
+    }
+
     #[tokio::test]
     async fn test_determine_file_paths() {
         let agent = CodeAgent::new();
@@ -0,0 +1,68 @@
+/// Structured results of a code generation run
+///
+/// Stored in `AgentResult.metadata` so API clients can read the outcome of
+/// each pipeline stage without parsing the markdown report.
+use crate::bdd::GherkinFeature;
+use crate::impl_gen::ImplCode;
+use crate::language::Language;
+use crate::planner::CodePlan;
+use crate::review::ReviewStatus;
+use crate::tdd::TestCode;
+use crate::test_runner::TestSummary;
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable summary of a code generation run
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CodeResultMetadata {
+    /// Purpose from the plan
+    pub purpose: String,
+    /// Number of Gherkin scenarios generated
+    pub scenario_count: usize,
+    /// Number of tests generated
+    pub test_count: usize,
+    /// Lines of implementation code
+    pub loc: usize,
+    /// Language of the generated code
+    pub language: Language,
+    /// Review verdict, if the code was reviewed
+    pub review_status: Option<ReviewStatus>,
+    /// Implementation attempts, including retries
+    pub iterations: usize,
+    /// Results of running the tests, if they were run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tests: Option<TestSummary>,
+    /// Generated tests, read back when a later task refines this result
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub test_code: String,
+    /// Final implementation, read back when a later task refines this result
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub impl_code: String,
+}
+
+impl CodeResultMetadata {
+    /// Summarize one pass through the pipeline
+    pub fn new(
+        plan: &CodePlan,
+        feature: &GherkinFeature,
+        test_code: &TestCode,
+        impl_code: &ImplCode,
+    ) -> Self {
+        Self {
+            purpose: plan.purpose.clone(),
+            scenario_count: feature.scenarios.len(),
+            test_count: test_code.test_count,
+            loc: impl_code.loc,
+            language: impl_code.language,
+            review_status: None,
+            iterations: 1,
+            tests: None,
+            test_code: test_code.code.clone(),
+            impl_code: impl_code.code.clone(),
+        }
+    }
+
+    /// Value for `AgentResult.metadata`
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
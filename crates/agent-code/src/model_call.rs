@@ -0,0 +1,159 @@
+//! Model calls shared by the pipeline stages
+//!
+//! Some local models occasionally answer with nothing at all. Parsed as a
+//! plan or as code, an empty answer becomes an empty result that flows
+//! silently through the rest of the pipeline, so stages call the model
+//! through [`generate_nonempty`] instead. Stages that extract code call
+//! [`generate_complete`], which also refuses output cut off by the token
+//! limit, since half a function never compiles.
+
+use bodhya_core::config::ModelDefaults;
+use bodhya_core::{Error, ModelBackend, ModelRequest, ModelResponse, Result};
+
+/// Call `backend`, asking once more if the response is empty or whitespace
+///
+/// `output` names what the stage expected (e.g. "plan") for the error
+/// returned when the second response is empty too.
+pub(crate) async fn generate_nonempty(
+    backend: &dyn ModelBackend,
+    request: ModelRequest,
+    output: &str,
+) -> Result<ModelResponse> {
+    let role = request.role.clone();
+    let response = backend.generate(request.clone()).await?;
+    if !response.text.trim().is_empty() {
+        return Ok(response);
+    }
+
+    tracing::warn!(%role, "Model returned an empty response; retrying once");
+    let response = backend.generate(request).await?;
+    if response.text.trim().is_empty() {
+        return Err(Error::Model(format!(
+            "{} model '{}' returned an empty response twice while generating the {}",
+            role,
+            backend.id(),
+            output
+        )));
+    }
+    Ok(response)
+}
+
+/// Like [`generate_nonempty`], but also asks once more with twice the token
+/// limit if the response is cut off by it
+///
+/// A request without a limit is retried with twice the configured default.
+/// Output that is still cut off is an error rather than truncated code.
+pub(crate) async fn generate_complete(
+    backend: &dyn ModelBackend,
+    request: ModelRequest,
+    output: &str,
+) -> Result<ModelResponse> {
+    let role = request.role.clone();
+    let limit = request
+        .max_tokens
+        .unwrap_or_else(|| ModelDefaults::default().max_tokens);
+    let response = generate_nonempty(backend, request.clone(), output).await?;
+    if !response.is_truncated() {
+        return Ok(response);
+    }
+
+    let raised = limit.saturating_mul(2);
+    tracing::warn!(%role, limit, raised, "Model output hit the token limit; retrying once");
+    let response = generate_nonempty(backend, request.with_max_tokens(raised), output).await?;
+    if response.is_truncated() {
+        return Err(Error::Model(format!(
+            "{} model '{}' hit the {}-token limit while generating the {}",
+            role,
+            backend.id(),
+            raised,
+            output
+        )));
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bodhya_core::ModelRole;
+    use bodhya_model_registry::MockBackend;
+
+    #[tokio::test]
+    async fn test_retries_empty_response_once() {
+        let backend = MockBackend::new("mock")
+            .with_response("  \n")
+            .with_response("plan");
+
+        let response = generate_nonempty(
+            &backend,
+            ModelRequest::new(ModelRole::Planner, "code", "p"),
+            "plan",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.text, "plan");
+        assert_eq!(backend.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_two_empty_responses_are_an_error() {
+        let backend = MockBackend::new("mock")
+            .with_response("")
+            .with_response(" ")
+            .with_response("never reached");
+
+        let err = generate_nonempty(
+            &backend,
+            ModelRequest::new(ModelRole::Coder, "code", "p"),
+            "tests",
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, Error::Model(_)));
+        assert!(err.to_string().contains(
+            "coder model 'mock' returned an empty response twice while generating the tests"
+        ));
+        assert_eq!(backend.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_response_is_retried_with_higher_limit() {
+        let backend = MockBackend::new("mock")
+            .with_truncated_response("```rust\nfn add(")
+            .with_response("```rust\nfn add() {}\n```");
+
+        let response = generate_complete(
+            &backend,
+            ModelRequest::new(ModelRole::Coder, "code", "p").with_max_tokens(1000),
+            "implementation",
+        )
+        .await
+        .unwrap();
+
+        assert!(!response.is_truncated());
+        let limits: Vec<_> = backend.requests().iter().map(|r| r.max_tokens).collect();
+        assert_eq!(limits, vec![Some(1000), Some(2000)]);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_twice_is_an_error() {
+        let backend = MockBackend::new("mock")
+            .with_truncated_response("fn add(")
+            .with_truncated_response("fn add(a: i32");
+
+        let err = generate_complete(
+            &backend,
+            ModelRequest::new(ModelRole::Coder, "code", "p"),
+            "tests",
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, Error::Model(_)));
+        assert!(err
+            .to_string()
+            .contains("hit the 4096-token limit while generating the tests"));
+    }
+}
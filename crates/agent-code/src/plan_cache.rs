@@ -0,0 +1,147 @@
+/// Planner result caching
+///
+/// Plans are keyed by a hash of the normalized task description, the planner
+/// model and its prompt template, so re-running the same task skips the
+/// planner model call entirely while a new model or prompt plans afresh.
+use crate::planner::CodePlan;
+use bodhya_core::{Error, Result};
+use bodhya_storage::SqliteStorage;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Storage for previously generated plans
+pub trait PlanCache: Send + Sync {
+    /// Look up a cached plan by key
+    fn get(&self, key: &str) -> Result<Option<CodePlan>>;
+
+    /// Store a plan under `key`
+    fn put(&self, key: &str, plan: &CodePlan) -> Result<()>;
+}
+
+/// Compute the cache key for a task planned by `model_id` with `prompt_template`
+///
+/// Descriptions are lowercased and whitespace is collapsed, so trivial
+/// formatting differences share a cache entry.
+pub fn plan_cache_key(task_description: &str, model_id: &str, prompt_template: &str) -> String {
+    let normalized = task_description
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+
+    let mut hasher = Sha256::new();
+    hasher.update(model_id.as_bytes());
+    hasher.update([0]);
+    hasher.update(Sha256::digest(prompt_template.as_bytes()));
+    hasher.update(normalized.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// In-process plan cache
+#[derive(Debug, Default)]
+pub struct InMemoryPlanCache {
+    plans: Mutex<HashMap<String, CodePlan>>,
+}
+
+impl InMemoryPlanCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of cached plans
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, CodePlan>> {
+        self.plans.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl PlanCache for InMemoryPlanCache {
+    fn get(&self, key: &str) -> Result<Option<CodePlan>> {
+        Ok(self.lock().get(key).cloned())
+    }
+
+    fn put(&self, key: &str, plan: &CodePlan) -> Result<()> {
+        self.lock().insert(key.to_string(), plan.clone());
+        Ok(())
+    }
+}
+
+/// Persistent plan cache backed by the history database
+impl PlanCache for SqliteStorage {
+    fn get(&self, key: &str) -> Result<Option<CodePlan>> {
+        match self.get_cached_plan(key)? {
+            Some(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|e| Error::Serialization(format!("Invalid cached plan: {}", e))),
+            None => Ok(None),
+        }
+    }
+
+    fn put(&self, key: &str, plan: &CodePlan) -> Result<()> {
+        let json = serde_json::to_string(plan)?;
+        self.put_cached_plan(key, &json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_cache_key_normalizes_description() {
+        let key = |description| plan_cache_key(description, "planner", "Plan: {task_description}");
+        assert_eq!(key("Build a  parser\n"), key("build a parser"));
+        assert_ne!(key("build a parser"), key("build a lexer"));
+        assert_eq!(key("x").len(), 64);
+    }
+
+    #[test]
+    fn test_plan_cache_key_covers_model_and_prompt() {
+        let key = plan_cache_key("build a parser", "planner", "Plan: {task_description}");
+        assert_ne!(
+            key,
+            plan_cache_key(
+                "build a parser",
+                "other-planner",
+                "Plan: {task_description}"
+            )
+        );
+        assert_ne!(
+            key,
+            plan_cache_key("build a parser", "planner", "Outline: {task_description}")
+        );
+    }
+
+    #[test]
+    fn test_in_memory_cache() {
+        let cache = InMemoryPlanCache::new();
+        assert!(cache.get("k").unwrap().is_none());
+
+        cache.put("k", &CodePlan::with_purpose("cached")).unwrap();
+        assert_eq!(cache.get("k").unwrap().unwrap().purpose, "cached");
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_sqlite_cache_round_trip() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let mut plan = CodePlan::with_purpose("Load config");
+        plan.add_component("Config struct");
+        plan.add_edge_case("Empty file");
+        plan.set_approach("serde_yaml");
+
+        let key = plan_cache_key("load config", "planner", "Plan: {task_description}");
+        PlanCache::put(&storage, &key, &plan).unwrap();
+        assert_eq!(PlanCache::get(&storage, &key).unwrap(), Some(plan));
+    }
+}
@@ -2,12 +2,17 @@
 ///
 /// This module handles the first step of the CodeAgent pipeline:
 /// analyzing a task description and creating a structured plan.
+use crate::language::Language;
+use crate::model_call::generate_nonempty;
+use crate::plan_cache::{plan_cache_key, PlanCache};
+use crate::prompts::PromptLoader;
 use bodhya_core::{EngagementMode, ModelRequest, ModelRole, Result};
 use bodhya_model_registry::ModelRegistry;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// A structured plan for code generation
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CodePlan {
     /// What the code is meant to do
     pub purpose: String,
@@ -19,6 +24,9 @@ pub struct CodePlan {
     pub edge_cases: Vec<String>,
     /// High-level implementation approach
     pub approach: String,
+    /// Language to generate code in
+    #[serde(default)]
+    pub language: Language,
 }
 
 impl CodePlan {
@@ -30,6 +38,7 @@ impl CodePlan {
             requirements: Vec::new(),
             edge_cases: Vec::new(),
             approach: String::new(),
+            language: Language::default(),
         }
     }
 
@@ -41,6 +50,7 @@ impl CodePlan {
             requirements: Vec::new(),
             edge_cases: Vec::new(),
             approach: String::new(),
+            language: Language::default(),
         }
     }
 
@@ -63,6 +73,68 @@ impl CodePlan {
     pub fn set_approach(&mut self, approach: impl Into<String>) {
         self.approach = approach.into();
     }
+
+    /// Questions that would fill this plan's gaps, or none if it is specific enough
+    ///
+    /// A plan is ambiguous when it names no components or its purpose is
+    /// generic (the parser's fallback, or only a word or two).
+    pub fn clarifying_questions(&self) -> Vec<String> {
+        let generic_purpose = self.purpose.starts_with(FALLBACK_PURPOSE_PREFIX)
+            || self.purpose.split_whitespace().count() < 3;
+        if !generic_purpose && !self.components.is_empty() {
+            return Vec::new();
+        }
+
+        let mut questions = Vec::new();
+        if generic_purpose {
+            questions.push("What should the code do? Describe the expected behavior.".to_string());
+        }
+        if self.components.is_empty() {
+            questions.push("Which functions, types, or modules should it provide?".to_string());
+        }
+        if self.requirements.is_empty() {
+            questions.push(
+                "What inputs should it accept, and what should it return or produce?".to_string(),
+            );
+        }
+        questions
+    }
+}
+
+/// Purpose given to plans the model response said nothing about
+const FALLBACK_PURPOSE_PREFIX: &str = "Implement: ";
+
+/// Result of planning a task that may be too vague to plan
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlanOutcome {
+    /// The task was specific enough to plan
+    Plan(CodePlan),
+    /// Questions to answer before planning again
+    NeedsClarification(Vec<String>),
+}
+
+/// A clarifying question and the user's answer to it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Clarification {
+    pub question: String,
+    pub answer: String,
+}
+
+/// `task_description` with the answers to clarifying questions appended, for re-planning
+pub fn with_clarifications(task_description: &str, clarifications: &[Clarification]) -> String {
+    let mut description = task_description.trim_end().to_string();
+    if clarifications.is_empty() {
+        return description;
+    }
+    description.push_str("\n\nClarifications:");
+    for clarification in clarifications {
+        description.push_str(&format!(
+            "\n- {} {}",
+            clarification.question.trim(),
+            clarification.answer.trim()
+        ));
+    }
+    description
 }
 
 impl Default for CodePlan {
@@ -75,6 +147,7 @@ impl Default for CodePlan {
 pub struct Planner {
     registry: Arc<ModelRegistry>,
     prompt_template: String,
+    cache: Option<Arc<dyn PlanCache>>,
 }
 
 impl Planner {
@@ -86,26 +159,75 @@ impl Planner {
         Ok(Self {
             registry,
             prompt_template,
+            cache: None,
         })
     }
 
+    /// Create a new planner, resolving its prompt through `loader`
+    pub fn with_prompt_loader(registry: Arc<ModelRegistry>, loader: &PromptLoader) -> Result<Self> {
+        let prompt_template = loader.load("planner.txt")?;
+
+        Ok(Self {
+            registry,
+            prompt_template,
+            cache: None,
+        })
+    }
+
+    /// Reuse plans from `cache` for previously seen descriptions
+    pub fn with_cache(mut self, cache: Arc<dyn PlanCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
     /// Load the planner prompt template
     fn load_prompt_template() -> Result<String> {
-        // Try to load from file first, fall back to embedded default
-        let prompt_path = std::path::Path::new("prompts/code/planner.txt");
+        crate::prompts::load_prompt("planner.txt")
+    }
+
+    /// Generate a plan from a task description
+    ///
+    /// With a cache configured, a hit returns the stored plan without calling
+    /// the model. Cache errors are logged and otherwise ignored.
+    pub async fn plan(&self, task_description: &str) -> Result<CodePlan> {
+        let Some(cache) = &self.cache else {
+            return self.generate_plan(task_description).await;
+        };
 
-        if prompt_path.exists() {
-            std::fs::read_to_string(prompt_path).map_err(|e| {
-                bodhya_core::Error::Config(format!("Failed to load planner prompt: {}", e))
-            })
+        let model_info =
+            self.registry
+                .get_model(&ModelRole::Planner, "code", &EngagementMode::Minimum)?;
+        let key = plan_cache_key(task_description, &model_info.id, &self.prompt_template);
+        match cache.get(&key) {
+            Ok(Some(plan)) => {
+                tracing::debug!("Plan cache hit for {}", key);
+                return Ok(plan);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Plan cache lookup failed: {}", e),
+        }
+
+        let plan = self.generate_plan(task_description).await?;
+        if let Err(e) = cache.put(&key, &plan) {
+            tracing::warn!("Failed to cache plan: {}", e);
+        }
+
+        Ok(plan)
+    }
+
+    /// Generate a plan, or clarifying questions if the plan comes out ambiguous
+    pub async fn plan_or_clarify(&self, task_description: &str) -> Result<PlanOutcome> {
+        let plan = self.plan(task_description).await?;
+        let questions = plan.clarifying_questions();
+        if questions.is_empty() {
+            Ok(PlanOutcome::Plan(plan))
         } else {
-            // Embedded default prompt
-            Ok(include_str!("../../../prompts/code/planner.txt").to_string())
+            Ok(PlanOutcome::NeedsClarification(questions))
         }
     }
 
-    /// Generate a plan from a task description
-    pub async fn plan(&self, task_description: &str) -> Result<CodePlan> {
+    /// Call the planner model and parse its response
+    async fn generate_plan(&self, task_description: &str) -> Result<CodePlan> {
         // For Phase 6, we'll use the model registry to call the planner model
         // Build the prompt from template
         let prompt = self
@@ -118,7 +240,8 @@ impl Planner {
                 .get_model(&ModelRole::Planner, "code", &EngagementMode::Minimum)?;
 
         // Create model request
-        let request = ModelRequest::new(ModelRole::Planner, "code", prompt);
+        let request =
+            ModelRequest::new(ModelRole::Planner, "code", prompt).with_role_as_system_prompt();
 
         // Call the model backend
         let backend = self.registry.get_backend(&model_info.id).ok_or_else(|| {
@@ -128,7 +251,7 @@ impl Planner {
             ))
         })?;
 
-        let response = backend.generate(request).await?;
+        let response = generate_nonempty(backend.as_ref(), request, "plan").await?;
 
         // Parse the model response into a structured plan
         // For Phase 6, we'll do simple parsing
@@ -137,6 +260,13 @@ impl Planner {
         Ok(plan)
     }
 
+    /// Build a stub plan without calling a model
+    ///
+    /// Used when no model registry is available (e.g., plan-only previews).
+    pub fn stub_plan(task_description: &str) -> CodePlan {
+        Self::parse_plan_from_response("", task_description)
+    }
+
     /// Parse plan from model response
     ///
     /// This is a simple parser for Phase 6. Future phases may use more sophisticated parsing.
@@ -243,9 +373,11 @@ impl Planner {
 
         // Fallback: if we couldn't parse anything, create a basic plan
         if plan.purpose.is_empty() {
-            plan.purpose = format!("Implement: {}", task_description);
+            plan.purpose = format!("{}{}", FALLBACK_PURPOSE_PREFIX, task_description);
         }
 
+        plan.language = Language::detect(task_description);
+
         plan
     }
 }
@@ -361,6 +493,24 @@ mod tests {
         assert!(plan.purpose.contains("build a function"));
     }
 
+    #[test]
+    fn test_stub_plan() {
+        let plan = Planner::stub_plan("build a parser");
+        assert_eq!(plan.purpose, "Implement: build a parser");
+        assert!(plan.components.is_empty());
+        assert_eq!(plan.language, Language::Rust);
+    }
+
+    #[test]
+    fn test_python_plan_selects_pytest() {
+        let plan = Planner::stub_plan("Write a Python function that parses CSV rows");
+        assert_eq!(plan.language, Language::Python);
+        assert_eq!(plan.language.test_command().0, "pytest");
+
+        let test_code = crate::TestCode::new("def test_parse(): pass").with_language(plan.language);
+        assert_eq!(test_code.language, Language::Python);
+    }
+
     #[test]
     fn test_load_prompt_template() {
         let template = Planner::load_prompt_template();
@@ -369,4 +519,176 @@ mod tests {
         assert!(template.contains("{task_description}"));
         assert!(template.contains("planning"));
     }
+
+    use crate::plan_cache::InMemoryPlanCache;
+    use async_trait::async_trait;
+    use bodhya_core::{BackendType, ModelBackend, ModelResponse};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Backend that counts how often it is called
+    struct CountingBackend {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ModelBackend for CountingBackend {
+        fn id(&self) -> &str {
+            "counting"
+        }
+
+        fn backend_type(&self) -> BackendType {
+            BackendType::Local
+        }
+
+        async fn generate(&self, _request: ModelRequest) -> Result<ModelResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ModelResponse::new("**Purpose**: Parse CSV files"))
+        }
+    }
+
+    fn counting_registry(calls: Arc<AtomicUsize>) -> Arc<ModelRegistry> {
+        let manifest = serde_json::from_value(serde_json::json!({
+            "models": {
+                "test_planner": {
+                    "role": "planner",
+                    "domain": "code",
+                    "display_name": "Test Planner",
+                    "source_url": "https://example.com/planner.gguf",
+                    "size_gb": 1.0,
+                    "checksum": "sha256:abc",
+                    "backend": "local"
+                }
+            },
+            "backends": {}
+        }))
+        .unwrap();
+
+        let mut registry = ModelRegistry::from_manifest(manifest, "/nonexistent");
+        registry.register_backend(
+            "test_planner".to_string(),
+            Arc::new(CountingBackend { calls }),
+        );
+        Arc::new(registry)
+    }
+
+    #[tokio::test]
+    async fn test_cached_plan_skips_backend() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache = Arc::new(InMemoryPlanCache::new());
+        let planner = Planner::new(counting_registry(calls.clone()))
+            .unwrap()
+            .with_cache(cache.clone());
+
+        let first = planner.plan("Parse CSV files").await.unwrap();
+        let second = planner.plan("  parse csv   files ").await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first, second);
+        assert_eq!(first.purpose, "Parse CSV files");
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_changed_prompt_misses_plan_cache() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache = Arc::new(InMemoryPlanCache::new());
+        let registry = counting_registry(calls.clone());
+        let prompt_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            prompt_dir.path().join("planner.txt"),
+            "Outline the work for: {task_description}",
+        )
+        .unwrap();
+
+        let default_planner = Planner::with_prompt_loader(registry.clone(), &PromptLoader::new())
+            .unwrap()
+            .with_cache(cache.clone());
+        let custom_planner =
+            Planner::with_prompt_loader(registry, &PromptLoader::new().with_dir(prompt_dir.path()))
+                .unwrap()
+                .with_cache(cache.clone());
+
+        default_planner.plan("Parse CSV files").await.unwrap();
+        default_planner.plan("Parse CSV files").await.unwrap();
+        custom_planner.plan("Parse CSV files").await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_planner_without_cache_always_calls_backend() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let planner = Planner::new(counting_registry(calls.clone())).unwrap();
+
+        planner.plan("Parse CSV files").await.unwrap();
+        planner.plan("Parse CSV files").await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_planner_sends_role_as_system_prompt() {
+        let backend = Arc::new(
+            bodhya_model_registry::MockBackend::new("mock")
+                .with_response("**Purpose**: Parse CSV files"),
+        );
+        let planner = Planner::new(Arc::new(Arc::clone(&backend).registry("code"))).unwrap();
+
+        planner.plan("Parse CSV files").await.unwrap();
+
+        let request = backend.requests().pop().unwrap();
+        let system = request.system_prompt.unwrap();
+        assert!(system.starts_with("You are a software planning assistant"));
+        assert!(!request.prompt.contains("You are"));
+        assert!(request.prompt.contains("Parse CSV files"));
+    }
+
+    fn mock_planner(response: &str) -> Planner {
+        let backend = bodhya_model_registry::MockBackend::new("mock").with_response(response);
+        Planner::new(Arc::new(backend.into_registry("code"))).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_specific_plan_needs_no_clarification() {
+        let planner = mock_planner(
+            "**Purpose**: Parse CSV files into records\n\
+             **Components**:\n- parse_csv function\n- Record struct",
+        );
+
+        match planner.plan_or_clarify("Parse CSV files").await.unwrap() {
+            PlanOutcome::Plan(plan) => assert_eq!(plan.components.len(), 2),
+            other => panic!("expected a plan, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vague_plan_asks_clarifying_questions() {
+        let planner = mock_planner("I'm not sure what you want.");
+
+        match planner.plan_or_clarify("Make it better").await.unwrap() {
+            PlanOutcome::NeedsClarification(questions) => {
+                assert_eq!(questions.len(), 3);
+                assert!(questions[0].contains("What should the code do"));
+                assert!(questions[1].contains("functions, types, or modules"));
+            }
+            other => panic!("expected questions, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_with_clarifications_appends_answers() {
+        let description = with_clarifications(
+            "Make a parser",
+            &[Clarification {
+                question: "Which format?".to_string(),
+                answer: "CSV with a header row".to_string(),
+            }],
+        );
+        assert_eq!(
+            description,
+            "Make a parser\n\nClarifications:\n- Which format? CSV with a header row"
+        );
+        assert_eq!(with_clarifications("Make a parser", &[]), "Make a parser");
+    }
 }
@@ -0,0 +1,182 @@
+//! Prompt size budgeting
+//!
+//! Prompts that concatenate plans, code, tests, and errors can outgrow a
+//! model's context window and fail in opaque ways inside the backend. The
+//! budget estimates tokens (about four characters each) and trims the least
+//! important sections first so the prompt fits.
+
+pub use bodhya_core::config::DEFAULT_CONTEXT_TOKENS;
+
+/// Appended to sections that were shortened
+const TRUNCATION_NOTE: &str = "\n... [truncated to fit context]";
+
+/// Rough token count of `text` (one token per four characters)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// A named piece of a prompt
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PromptSection {
+    /// Placeholder name (e.g., "current_code")
+    pub name: String,
+    /// Section text
+    pub content: String,
+    /// Trim order: sections with lower priority are trimmed first;
+    /// `None` is never trimmed
+    pub priority: Option<u32>,
+}
+
+impl PromptSection {
+    /// A section that is never trimmed (e.g., the error being fixed)
+    pub fn required(name: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            content: content.into(),
+            priority: None,
+        }
+    }
+
+    /// A section that may be trimmed, lower priorities first
+    pub fn trimmable(name: impl Into<String>, content: impl Into<String>, priority: u32) -> Self {
+        Self {
+            name: name.into(),
+            content: content.into(),
+            priority: Some(priority),
+        }
+    }
+}
+
+/// Maximum prompt size, in estimated tokens
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PromptBudget {
+    max_tokens: usize,
+}
+
+impl PromptBudget {
+    /// Create a budget of `max_tokens` estimated tokens
+    pub fn new(max_tokens: usize) -> Self {
+        Self { max_tokens }
+    }
+
+    /// Maximum prompt size, in estimated tokens
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Fill `{name}` placeholders in `template` with `sections`, trimming to fit
+    ///
+    /// Trimmable sections keep their beginning and lose their end, lowest
+    /// priority first. Required sections and the template itself are never
+    /// trimmed, so the result can still exceed the budget if they alone do.
+    pub fn render(&self, template: &str, mut sections: Vec<PromptSection>) -> String {
+        let render = |sections: &[PromptSection]| {
+            sections
+                .iter()
+                .fold(template.to_string(), |prompt, section| {
+                    prompt.replace(&format!("{{{}}}", section.name), &section.content)
+                })
+        };
+
+        let mut excess = estimate_tokens(&render(&sections)).saturating_sub(self.max_tokens);
+        if excess == 0 {
+            return render(&sections);
+        }
+
+        let mut order: Vec<usize> = (0..sections.len())
+            .filter(|&i| sections[i].priority.is_some())
+            .collect();
+        order.sort_by_key(|&i| sections[i].priority);
+
+        for index in order {
+            let section = &mut sections[index];
+            let before = estimate_tokens(&section.content);
+            if before <= estimate_tokens(TRUNCATION_NOTE) {
+                // Too short for truncation to save anything
+                continue;
+            }
+            let keep_tokens = before.saturating_sub(excess + estimate_tokens(TRUNCATION_NOTE));
+            let keep_chars = keep_tokens * 4;
+
+            tracing::warn!(
+                section = %section.name,
+                tokens = before,
+                kept_tokens = keep_tokens,
+                budget = self.max_tokens,
+                "Prompt over budget; truncating section"
+            );
+            section.content = section.content.chars().take(keep_chars).collect();
+            section.content.push_str(TRUNCATION_NOTE);
+
+            excess =
+                excess.saturating_sub(before.saturating_sub(estimate_tokens(&section.content)));
+            if excess == 0 {
+                break;
+            }
+        }
+
+        let prompt = render(&sections);
+        if estimate_tokens(&prompt) > self.max_tokens {
+            tracing::warn!(
+                tokens = estimate_tokens(&prompt),
+                budget = self.max_tokens,
+                "Prompt still over budget after truncation"
+            );
+        }
+        prompt
+    }
+}
+
+impl Default for PromptBudget {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONTEXT_TOKENS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn test_prompt_within_budget_is_unchanged() {
+        let prompt = PromptBudget::new(100).render(
+            "Code:\n{code}\nError:\n{error}",
+            vec![
+                PromptSection::trimmable("code", "fn main() {}", 0),
+                PromptSection::required("error", "E0425"),
+            ],
+        );
+        assert_eq!(prompt, "Code:\nfn main() {}\nError:\nE0425");
+    }
+
+    #[test]
+    fn test_oversized_prompt_trims_code_and_keeps_error() {
+        let template = "Plan:\n{plan}\nCode:\n{code}\nTests:\n{tests}\nError:\n{error}";
+        let error = "error[E0425]: cannot find value `x` in this scope";
+        let budget = PromptBudget::new(500);
+
+        let prompt = budget.render(
+            template,
+            vec![
+                PromptSection::required("plan", "Purpose: add numbers"),
+                PromptSection::trimmable("code", "let a = 1;\n".repeat(1000), 0),
+                PromptSection::trimmable("tests", "#[test]\nfn t() {}\n".repeat(20), 1),
+                PromptSection::required("error", error),
+            ],
+        );
+
+        assert!(estimate_tokens(&prompt) <= 500);
+        assert!(prompt.contains("Purpose: add numbers"));
+        assert!(prompt.contains(error));
+        assert!(prompt.contains("[truncated to fit context]"));
+        // The higher-priority tests section fits once the code is trimmed
+        assert_eq!(prompt.matches("#[test]").count(), 20);
+    }
+}
@@ -0,0 +1,384 @@
+/// Prompt template loading
+///
+/// All CodeAgent prompt templates are resolved here: an external prompt
+/// directory is checked first (so prompts can be customized without a
+/// rebuild), falling back to the defaults embedded in the binary.
+/// External prompts are kept in an in-memory cache shared by clones of a
+/// loader, so repeated generator construction doesn't go back to disk; a
+/// cached prompt is re-read when its file's modification time changes.
+///
+/// `CodeAgent` owns one loader and hands it to every generator it builds.
+use bodhya_core::{Error, Result};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Default external prompt directory, relative to the working directory
+pub const DEFAULT_PROMPT_DIR: &str = "prompts/code";
+
+/// Embedded default prompts, keyed by file name
+const EMBEDDED_PROMPTS: &[(&str, &str)] = &[
+    ("bdd.txt", include_str!("../../../prompts/code/bdd.txt")),
+    (
+        "code_refiner.txt",
+        include_str!("../../../prompts/code/code_refiner.txt"),
+    ),
+    ("coder.txt", include_str!("../../../prompts/code/coder.txt")),
+    (
+        "error_analyzer.txt",
+        include_str!("../../../prompts/code/error_analyzer.txt"),
+    ),
+    (
+        "planner.txt",
+        include_str!("../../../prompts/code/planner.txt"),
+    ),
+    (
+        "reviewer.txt",
+        include_str!("../../../prompts/code/reviewer.txt"),
+    ),
+    ("tdd.txt", include_str!("../../../prompts/code/tdd.txt")),
+];
+
+/// Number of external prompt files kept by the shared cache
+pub const DEFAULT_CACHE_CAPACITY: usize = 32;
+
+/// Thread-safe LRU cache of external prompt files, keyed by path
+///
+/// Each lookup compares the file's modification time against the cached
+/// copy and re-reads it if the file has changed.
+#[derive(Debug)]
+pub struct PromptCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+    disk_reads: AtomicUsize,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<PathBuf, CachedPrompt>,
+    /// Least recently used path at the front
+    order: VecDeque<PathBuf>,
+}
+
+#[derive(Debug)]
+struct CachedPrompt {
+    content: String,
+    modified: Option<SystemTime>,
+}
+
+impl PromptCache {
+    /// Create an empty cache holding at most `capacity` prompts
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(CacheState::default()),
+            disk_reads: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of times a prompt file has been read from disk
+    pub fn disk_reads(&self) -> usize {
+        self.disk_reads.load(Ordering::Relaxed)
+    }
+
+    /// Number of prompts currently cached
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop all cached prompts
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        state.order.clear();
+    }
+
+    /// Return the cached contents of `path`, reading it from disk on a miss
+    pub fn get_or_read(&self, path: &Path) -> std::io::Result<String> {
+        let mut state = self.lock();
+
+        let modified = std::fs::metadata(path)?.modified().ok();
+
+        if let Some(cached) = state.entries.get(path) {
+            if cached.modified == modified {
+                let content = cached.content.clone();
+                state.touch(path);
+                return Ok(content);
+            }
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        self.disk_reads.fetch_add(1, Ordering::Relaxed);
+
+        state.entries.insert(
+            path.to_path_buf(),
+            CachedPrompt {
+                content: content.clone(),
+                modified,
+            },
+        );
+        state.touch(path);
+
+        while state.entries.len() > self.capacity {
+            match state.order.pop_front() {
+                Some(evicted) => {
+                    state.entries.remove(&evicted);
+                }
+                None => break,
+            }
+        }
+
+        Ok(content)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CacheState {
+    /// Mark `path` as most recently used
+    fn touch(&mut self, path: &Path) {
+        if let Some(pos) = self.order.iter().position(|p| p == path) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(path.to_path_buf());
+    }
+}
+
+/// Look up an embedded default prompt by file name
+pub fn embedded_prompt(name: &str) -> Option<&'static str> {
+    EMBEDDED_PROMPTS
+        .iter()
+        .find(|(prompt_name, _)| *prompt_name == name)
+        .map(|(_, content)| *content)
+}
+
+/// Resolves prompt templates from an external directory with embedded fallback
+///
+/// Clones share one cache.
+#[derive(Debug, Clone)]
+pub struct PromptLoader {
+    prompt_dir: PathBuf,
+    cache: Arc<PromptCache>,
+}
+
+impl PromptLoader {
+    /// Create a loader with its own cache, using the default prompt directory
+    pub fn new() -> Self {
+        Self {
+            prompt_dir: PathBuf::from(DEFAULT_PROMPT_DIR),
+            cache: Arc::new(PromptCache::new(DEFAULT_CACHE_CAPACITY)),
+        }
+    }
+
+    /// Use `cache` instead of a cache of its own
+    pub fn with_cache(mut self, cache: Arc<PromptCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Use a custom external prompt directory
+    pub fn with_dir(mut self, prompt_dir: impl Into<PathBuf>) -> Self {
+        self.prompt_dir = prompt_dir.into();
+        self
+    }
+
+    /// The external directory searched before the embedded defaults
+    pub fn prompt_dir(&self) -> &Path {
+        &self.prompt_dir
+    }
+
+    /// Load a prompt by file name (e.g., "bdd.txt")
+    pub fn load(&self, name: &str) -> Result<String> {
+        let external_path = self.prompt_dir.join(name);
+
+        if external_path.exists() {
+            return self.cache.get_or_read(&external_path).map_err(|e| {
+                Error::Config(format!(
+                    "Failed to load prompt '{}' from {}: {}",
+                    name,
+                    external_path.display(),
+                    e
+                ))
+            });
+        }
+
+        embedded_prompt(name).map(str::to_string).ok_or_else(|| {
+            Error::Config(format!(
+                "Prompt '{}' not found: no file at {} and no embedded default",
+                name,
+                external_path.display()
+            ))
+        })
+    }
+}
+
+impl Default for PromptLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Load a prompt using the default prompt directory, without caching
+pub fn load_prompt(name: &str) -> Result<String> {
+    PromptLoader::new().load(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_prompt_takes_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("bdd.txt"), "custom {task_description}").unwrap();
+
+        let loader = PromptLoader::new().with_dir(dir.path());
+        let prompt = loader.load("bdd.txt").unwrap();
+        assert_eq!(prompt, "custom {task_description}");
+    }
+
+    #[test]
+    fn test_embedded_fallback_when_external_missing() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let loader = PromptLoader::new().with_dir(dir.path().join("typo"));
+        let prompt = loader.load("planner.txt").unwrap();
+        assert_eq!(prompt, embedded_prompt("planner.txt").unwrap());
+    }
+
+    #[test]
+    fn test_missing_prompt_names_the_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let loader = PromptLoader::new().with_dir(dir.path());
+        let err = loader.load("does_not_exist.txt").unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("does_not_exist.txt"));
+        assert!(message.contains("no embedded default"));
+    }
+
+    #[test]
+    fn test_all_embedded_prompts_present() {
+        for name in [
+            "bdd.txt",
+            "code_refiner.txt",
+            "coder.txt",
+            "error_analyzer.txt",
+            "planner.txt",
+            "reviewer.txt",
+            "tdd.txt",
+        ] {
+            assert!(
+                embedded_prompt(name).is_some_and(|p| !p.is_empty()),
+                "missing embedded prompt {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_prompt_dir() {
+        assert_eq!(
+            PromptLoader::default().prompt_dir(),
+            Path::new(DEFAULT_PROMPT_DIR)
+        );
+    }
+
+    #[test]
+    fn test_cache_reads_file_once() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("bdd.txt"), "cached").unwrap();
+
+        let cache = Arc::new(PromptCache::new(4));
+        let loader = PromptLoader::new()
+            .with_dir(dir.path())
+            .with_cache(cache.clone());
+
+        for _ in 0..3 {
+            assert_eq!(loader.load("bdd.txt").unwrap(), "cached");
+        }
+        assert_eq!(cache.disk_reads(), 1);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            std::fs::write(dir.path().join(name), name).unwrap();
+        }
+
+        let cache = PromptCache::new(2);
+        cache.get_or_read(&dir.path().join("a.txt")).unwrap();
+        cache.get_or_read(&dir.path().join("b.txt")).unwrap();
+        // Touch a.txt so b.txt becomes least recently used
+        cache.get_or_read(&dir.path().join("a.txt")).unwrap();
+        cache.get_or_read(&dir.path().join("c.txt")).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.disk_reads(), 3);
+
+        cache.get_or_read(&dir.path().join("a.txt")).unwrap();
+        assert_eq!(cache.disk_reads(), 3);
+        cache.get_or_read(&dir.path().join("b.txt")).unwrap();
+        assert_eq!(cache.disk_reads(), 4);
+    }
+
+    #[test]
+    fn test_cache_rereads_on_mtime_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tdd.txt");
+        std::fs::write(&path, "v1").unwrap();
+
+        let cache = PromptCache::new(4);
+        assert_eq!(cache.get_or_read(&path).unwrap(), "v1");
+        assert_eq!(cache.get_or_read(&path).unwrap(), "v1");
+        assert_eq!(cache.disk_reads(), 1);
+
+        std::fs::write(&path, "v2").unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+
+        assert_eq!(cache.get_or_read(&path).unwrap(), "v2");
+        assert_eq!(cache.disk_reads(), 2);
+    }
+
+    #[test]
+    fn test_clear_forces_reread() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tdd.txt");
+        std::fs::write(&path, "v1").unwrap();
+
+        let cache = PromptCache::new(4);
+        cache.get_or_read(&path).unwrap();
+        cache.clear();
+        assert!(cache.is_empty());
+        cache.get_or_read(&path).unwrap();
+        assert_eq!(cache.disk_reads(), 2);
+    }
+
+    #[test]
+    fn test_loader_clones_share_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("bdd.txt"), "shared").unwrap();
+
+        let loader = PromptLoader::new().with_dir(dir.path());
+        let clone = loader.clone();
+        loader.load("bdd.txt").unwrap();
+        clone.load("bdd.txt").unwrap();
+
+        let other = PromptLoader::new().with_dir(dir.path());
+        other.load("bdd.txt").unwrap();
+        assert_eq!(loader.cache.disk_reads(), 1);
+        assert_eq!(other.cache.disk_reads(), 1);
+    }
+}
@@ -0,0 +1,139 @@
+//! Pipeline-wide retry budget
+//!
+//! Model calls fail transiently (a dropped connection, an overloaded
+//! server). Each pipeline stage may be retried a few times, drawing on a
+//! budget shared by the whole run, so a flaky pipeline cannot retry forever
+//! and no single stage can use up the retries meant for the others.
+
+use bodhya_core::Error;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// How often failed pipeline stages are retried
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries allowed for any one stage
+    pub max_retries_per_stage: u32,
+    /// Retries allowed across all stages of one run
+    pub max_total_retries: u32,
+    /// Delay before the first retry of a stage; doubles on each further retry
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Allow `per_stage` retries of any one stage and `total` retries overall
+    pub fn new(per_stage: u32, total: u32) -> Self {
+        Self {
+            max_retries_per_stage: per_stage,
+            max_total_retries: total,
+            ..Self::default()
+        }
+    }
+
+    /// Never retry
+    pub fn none() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Set the delay before the first retry of a stage
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Start a fresh budget for one pipeline run
+    pub fn budget(&self) -> RetryBudget {
+        RetryBudget {
+            policy: *self,
+            remaining: AtomicU32::new(self.max_total_retries),
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries_per_stage: 2,
+            max_total_retries: 3,
+            backoff: Duration::from_millis(250),
+        }
+    }
+}
+
+/// Retries left for one pipeline run
+#[derive(Debug)]
+pub struct RetryBudget {
+    policy: RetryPolicy,
+    remaining: AtomicU32,
+}
+
+impl RetryBudget {
+    /// Retries left across all stages
+    pub fn remaining(&self) -> u32 {
+        self.remaining.load(Ordering::SeqCst)
+    }
+
+    /// Take one retry for a stage that has already been retried `stage_retries`
+    /// times, returning how long to wait first, or `None` if it may not retry
+    pub fn take(&self, stage_retries: u32, error: &Error) -> Option<Duration> {
+        if !is_transient(error) || stage_retries >= self.policy.max_retries_per_stage {
+            return None;
+        }
+        self.remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .ok()?;
+        Some(self.policy.backoff * 2u32.saturating_pow(stage_retries))
+    }
+}
+
+/// Whether `error` may go away if the stage is simply run again
+///
+/// Other model errors (an empty or truncated answer, a missing model) would
+/// only fail the same way again.
+fn is_transient(error: &Error) -> bool {
+    matches!(error, Error::ModelUnavailable(_) | Error::Network(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model_error() -> Error {
+        Error::ModelUnavailable("connection reset".to_string())
+    }
+
+    #[test]
+    fn test_budget_shared_across_stages() {
+        let budget = RetryPolicy::new(2, 3)
+            .with_backoff(Duration::from_millis(10))
+            .budget();
+
+        // One stage may take at most its own share
+        assert_eq!(
+            budget.take(0, &model_error()),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(
+            budget.take(1, &model_error()),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(budget.take(2, &model_error()), None);
+
+        // The next stage gets what is left of the shared budget
+        assert!(budget.take(0, &model_error()).is_some());
+        assert_eq!(budget.remaining(), 0);
+        assert_eq!(budget.take(0, &model_error()), None);
+    }
+
+    #[test]
+    fn test_permanent_errors_not_retried() {
+        let budget = RetryPolicy::default().budget();
+        let error = Error::Config("no registry".to_string());
+
+        assert_eq!(budget.take(0, &error), None);
+        let error = Error::Model("returned an empty response twice".to_string());
+        assert_eq!(budget.take(0, &error), None);
+        assert_eq!(budget.remaining(), 3);
+        assert_eq!(RetryPolicy::none().budget().take(0, &model_error()), None);
+    }
+}
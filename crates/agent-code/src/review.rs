@@ -2,13 +2,17 @@
 ///
 /// This module handles reviewing generated code and suggesting improvements.
 use crate::impl_gen::ImplCode;
+use crate::model_call::generate_nonempty;
 use crate::planner::CodePlan;
+use crate::prompts::PromptLoader;
 use bodhya_core::{EngagementMode, ModelRequest, ModelRole, Result};
 use bodhya_model_registry::ModelRegistry;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// Code review result
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ReviewStatus {
     /// Code is approved without changes
     Approved,
@@ -218,18 +222,19 @@ impl CodeReviewer {
         })
     }
 
+    /// Create a new code reviewer, resolving its prompt through `loader`
+    pub fn with_prompt_loader(registry: Arc<ModelRegistry>, loader: &PromptLoader) -> Result<Self> {
+        let prompt_template = loader.load("reviewer.txt")?;
+
+        Ok(Self {
+            registry,
+            prompt_template,
+        })
+    }
+
     /// Load the reviewer prompt template
     fn load_prompt_template() -> Result<String> {
-        let prompt_path = std::path::Path::new("prompts/code/reviewer.txt");
-
-        if prompt_path.exists() {
-            std::fs::read_to_string(prompt_path).map_err(|e| {
-                bodhya_core::Error::Config(format!("Failed to load reviewer prompt: {}", e))
-            })
-        } else {
-            // Embedded default prompt
-            Ok(include_str!("../../../prompts/code/reviewer.txt").to_string())
-        }
+        crate::prompts::load_prompt("reviewer.txt")
     }
 
     /// Review generated code
@@ -254,7 +259,8 @@ impl CodeReviewer {
                 .get_model(&ModelRole::Reviewer, "code", &EngagementMode::Minimum)?;
 
         // Create model request
-        let request = ModelRequest::new(ModelRole::Reviewer, "code", prompt);
+        let request =
+            ModelRequest::new(ModelRole::Reviewer, "code", prompt).with_role_as_system_prompt();
 
         // Call the model backend
         let backend = self.registry.get_backend(&model_info.id).ok_or_else(|| {
@@ -264,7 +270,7 @@ impl CodeReviewer {
             ))
         })?;
 
-        let response = backend.generate(request).await?;
+        let response = generate_nonempty(backend.as_ref(), request, "review").await?;
 
         // Parse review from response
         Ok(CodeReview::new(response.text))
@@ -432,4 +438,30 @@ None
         assert!(formatted.contains("Requirements:"));
         assert!(formatted.contains("- Must handle concurrent requests"));
     }
+
+    #[test]
+    fn test_repeated_construction_reads_prompt_once() {
+        use crate::prompts::PromptCache;
+        use bodhya_model_registry::ModelManifest;
+        use std::collections::HashMap;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("reviewer.txt"), "custom reviewer").unwrap();
+
+        let cache = Arc::new(PromptCache::new(8));
+        let loader = PromptLoader::new()
+            .with_dir(dir.path())
+            .with_cache(cache.clone());
+        let manifest = ModelManifest {
+            models: HashMap::new(),
+            backends: HashMap::new(),
+        };
+        let registry = Arc::new(ModelRegistry::from_manifest(manifest, "/tmp/models"));
+
+        for _ in 0..5 {
+            let reviewer = CodeReviewer::with_prompt_loader(registry.clone(), &loader).unwrap();
+            assert_eq!(reviewer.prompt_template, "custom reviewer");
+        }
+        assert_eq!(cache.disk_reads(), 1);
+    }
 }
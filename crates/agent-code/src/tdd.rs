@@ -2,7 +2,10 @@
 ///
 /// This module handles generating failing tests from Gherkin scenarios.
 use crate::bdd::GherkinFeature;
+use crate::language::Language;
+use crate::model_call::generate_complete;
 use crate::planner::CodePlan;
+use crate::prompts::PromptLoader;
 use bodhya_core::{EngagementMode, ModelRequest, ModelRole, Result};
 use bodhya_model_registry::ModelRegistry;
 use std::sync::Arc;
@@ -14,6 +17,8 @@ pub struct TestCode {
     pub code: String,
     /// Number of test cases generated
     pub test_count: usize,
+    /// Language the tests are written in
+    pub language: Language,
 }
 
 impl TestCode {
@@ -21,7 +26,17 @@ impl TestCode {
     pub fn new(code: impl Into<String>) -> Self {
         let code = code.into();
         let test_count = Self::count_tests(&code);
-        Self { code, test_count }
+        Self {
+            code,
+            test_count,
+            language: Language::default(),
+        }
+    }
+
+    /// Set the language the tests are written in
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
     }
 
     /// Count the number of #[test] and #[tokio::test] attributes in the code
@@ -52,18 +67,19 @@ impl TddGenerator {
         })
     }
 
+    /// Create a new TDD generator, resolving its prompt through `loader`
+    pub fn with_prompt_loader(registry: Arc<ModelRegistry>, loader: &PromptLoader) -> Result<Self> {
+        let prompt_template = loader.load("tdd.txt")?;
+
+        Ok(Self {
+            registry,
+            prompt_template,
+        })
+    }
+
     /// Load the TDD prompt template
     fn load_prompt_template() -> Result<String> {
-        let prompt_path = std::path::Path::new("prompts/code/tdd.txt");
-
-        if prompt_path.exists() {
-            std::fs::read_to_string(prompt_path).map_err(|e| {
-                bodhya_core::Error::Config(format!("Failed to load TDD prompt: {}", e))
-            })
-        } else {
-            // Embedded default prompt
-            Ok(include_str!("../../../prompts/code/tdd.txt").to_string())
-        }
+        crate::prompts::load_prompt("tdd.txt")
     }
 
     /// Generate test code from Gherkin feature and plan
@@ -83,7 +99,8 @@ impl TddGenerator {
                 .get_model(&ModelRole::Coder, "code", &EngagementMode::Minimum)?;
 
         // Create model request
-        let request = ModelRequest::new(ModelRole::Coder, "code", prompt);
+        let request =
+            ModelRequest::new(ModelRole::Coder, "code", prompt).with_role_as_system_prompt();
 
         // Call the model backend
         let backend = self.registry.get_backend(&model_info.id).ok_or_else(|| {
@@ -93,12 +110,12 @@ impl TddGenerator {
             ))
         })?;
 
-        let response = backend.generate(request).await?;
+        let response = generate_complete(backend.as_ref(), request, "tests").await?;
 
         // Extract Rust code from response
         let test_code = Self::extract_rust_code(&response.text);
 
-        Ok(TestCode::new(test_code))
+        Ok(TestCode::new(test_code).with_language(plan.language))
     }
 
     /// Format a plan for inclusion in the prompt
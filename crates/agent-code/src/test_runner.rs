@@ -0,0 +1,309 @@
+/// Test runners for generated projects
+///
+/// Each runner knows how to invoke one test toolchain and how to read
+/// pass/fail counts from its output, so the agent reports results the same
+/// way for Cargo, pytest, and npm projects.
+use crate::language::Language;
+use crate::tools::{CodeAgentTools, CommandOutput};
+use async_trait::async_trait;
+use bodhya_core::Result;
+use bodhya_storage::QualityMetrics;
+use serde::{Deserialize, Serialize};
+
+/// Pass/fail/ignored counts parsed from test output
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestSummary {
+    /// Number of tests that passed
+    pub passed: usize,
+    /// Number of tests that failed
+    pub failed: usize,
+    /// Number of tests that were ignored or skipped
+    pub ignored: usize,
+}
+
+impl TestSummary {
+    /// Parse `cargo test` output, summing the `test result:` line of each test binary
+    ///
+    /// Returns `None` if no tests ran (e.g., the build failed).
+    pub fn from_cargo_output(output: &str) -> Option<Self> {
+        output
+            .lines()
+            .filter(|line| line.trim_start().starts_with("test result:"))
+            .map(Self::from_line)
+            .reduce(|total, binary| TestSummary {
+                passed: total.passed + binary.passed,
+                failed: total.failed + binary.failed,
+                ignored: total.ignored + binary.ignored,
+            })
+    }
+
+    /// Read "N passed", "N failed", and "N ignored"/"N skipped" counts from one line
+    fn from_line(line: &str) -> Self {
+        Self {
+            passed: count_of(line, "passed"),
+            failed: count_of(line, "failed") + count_of(line, "error") + count_of(line, "errors"),
+            ignored: count_of(line, "ignored") + count_of(line, "skipped"),
+        }
+    }
+
+    /// Number of tests that ran (passed or failed)
+    pub fn total(&self) -> usize {
+        self.passed + self.failed
+    }
+
+    /// Record these counts as quality metrics for `task_id`
+    ///
+    /// The quality score is the pass rate (0-100).
+    pub fn to_quality_metrics(&self, task_id: impl Into<String>) -> QualityMetrics {
+        let mut metrics = QualityMetrics::new(task_id)
+            .with_custom_metrics(serde_json::json!({ "tests": self }).to_string());
+        if self.total() > 0 {
+            metrics = metrics.with_quality_score(self.passed as f64 * 100.0 / self.total() as f64);
+        }
+        metrics
+    }
+}
+
+impl std::fmt::Display for TestSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} passed", self.passed, self.total())?;
+        if self.ignored > 0 {
+            write!(f, ", {} ignored", self.ignored)?;
+        }
+        Ok(())
+    }
+}
+
+/// A test toolchain the agent can run
+#[async_trait]
+pub trait TestRunner: Send + Sync {
+    /// Runner name (e.g., "cargo")
+    fn name(&self) -> &'static str;
+
+    /// Language whose test command this runner invokes
+    fn language(&self) -> Language;
+
+    /// Read pass/fail/ignored counts from the runner's output
+    fn parse_summary(&self, output: &str) -> TestSummary;
+
+    /// Run the test suite in the tools' working directory
+    async fn run(&self, tools: &CodeAgentTools) -> Result<CommandOutput> {
+        tools.run_tests(self.language()).await
+    }
+
+    /// Read the counts of a run from its stdout and stderr
+    fn summarize(&self, result: &CommandOutput) -> TestSummary {
+        self.parse_summary(&format!("{}\n{}", result.stdout, result.stderr))
+    }
+}
+
+/// `cargo test`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CargoRunner;
+
+impl TestRunner for CargoRunner {
+    fn name(&self) -> &'static str {
+        "cargo"
+    }
+
+    fn language(&self) -> Language {
+        Language::Rust
+    }
+
+    fn parse_summary(&self, output: &str) -> TestSummary {
+        TestSummary::from_cargo_output(output).unwrap_or_default()
+    }
+}
+
+/// `pytest`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PytestRunner;
+
+impl TestRunner for PytestRunner {
+    fn name(&self) -> &'static str {
+        "pytest"
+    }
+
+    fn language(&self) -> Language {
+        Language::Python
+    }
+
+    /// Reads the final `== N passed, M failed in 0.1s ==` summary line
+    fn parse_summary(&self, output: &str) -> TestSummary {
+        output
+            .lines()
+            .rev()
+            .find(|line| line.contains(" passed") || line.contains(" failed"))
+            .map(TestSummary::from_line)
+            .unwrap_or_default()
+    }
+}
+
+/// `npm test` (Jest-style summaries)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NpmRunner;
+
+impl TestRunner for NpmRunner {
+    fn name(&self) -> &'static str {
+        "npm"
+    }
+
+    fn language(&self) -> Language {
+        Language::JavaScript
+    }
+
+    /// Reads the `Tests: M failed, N passed, T total` summary line
+    fn parse_summary(&self, output: &str) -> TestSummary {
+        output
+            .lines()
+            .find(|line| line.trim_start().starts_with("Tests:"))
+            .map(TestSummary::from_line)
+            .unwrap_or_default()
+    }
+}
+
+/// Runner for a language
+pub fn runner_for(language: Language) -> Box<dyn TestRunner> {
+    match language {
+        Language::Rust => Box::new(CargoRunner),
+        Language::Python => Box::new(PytestRunner),
+        Language::JavaScript => Box::new(NpmRunner),
+    }
+}
+
+/// Pick a runner from the project's manifest, falling back to `language`
+pub async fn detect_runner(tools: &CodeAgentTools, language: Language) -> Box<dyn TestRunner> {
+    const MANIFESTS: [(&str, Language); 5] = [
+        ("Cargo.toml", Language::Rust),
+        ("package.json", Language::JavaScript),
+        ("pyproject.toml", Language::Python),
+        ("setup.py", Language::Python),
+        ("pytest.ini", Language::Python),
+    ];
+
+    for (manifest, manifest_language) in MANIFESTS {
+        if tools.file_exists(manifest).await.unwrap_or(false) {
+            return runner_for(manifest_language);
+        }
+    }
+    runner_for(language)
+}
+
+/// The number immediately before `label` in `line` (e.g., "3 passed"), or 0
+fn count_of(line: &str, label: &str) -> usize {
+    let words: Vec<&str> = line
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';' || c == '=')
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    words
+        .windows(2)
+        .filter(|pair| pair[1] == label)
+        .filter_map(|pair| pair[0].parse::<usize>().ok())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bodhya_tools_mcp::ToolRegistry;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    const CARGO_OUTPUT: &str = "\
+running 3 tests
+test tests::test_add ... ok
+test tests::test_sub ... ok
+test tests::test_div ... FAILED
+
+test result: FAILED. 2 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s
+
+running 1 test
+test it_works ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+
+    const CARGO_ALL_PASSED: &str = "\
+running 2 tests
+test tests::test_fib_zero ... ok
+test tests::test_fib_ten ... ok
+
+test result: ok. 2 passed; 0 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.00s
+
+   Doc-tests fibonacci
+
+running 0 tests
+
+test result: ok. 0 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+
+    fn summary(passed: usize, failed: usize, ignored: usize) -> TestSummary {
+        TestSummary {
+            passed,
+            failed,
+            ignored,
+        }
+    }
+
+    #[test]
+    fn test_cargo_summary_all_passed() {
+        let parsed = TestSummary::from_cargo_output(CARGO_ALL_PASSED).unwrap();
+        assert_eq!(parsed, summary(2, 0, 1));
+        assert_eq!(parsed.to_string(), "2/2 passed, 1 ignored");
+    }
+
+    #[test]
+    fn test_cargo_summary_partial_failure() {
+        let parsed = TestSummary::from_cargo_output(CARGO_OUTPUT).unwrap();
+        assert_eq!(parsed, summary(3, 1, 0));
+        assert_eq!(parsed.to_string(), "3/4 passed");
+    }
+
+    #[test]
+    fn test_cargo_summary_build_failure() {
+        let output = "error[E0425]: cannot find value `x` in this scope\n\
+                      error: could not compile `fibonacci`";
+        assert_eq!(TestSummary::from_cargo_output(output), None);
+        assert_eq!(CargoRunner.parse_summary(output), TestSummary::default());
+    }
+
+    #[test]
+    fn test_summary_to_quality_metrics() {
+        let metrics = summary(3, 1, 0).to_quality_metrics("task-1");
+        assert_eq!(metrics.quality_score, Some(75.0));
+
+        let custom: serde_json::Value =
+            serde_json::from_str(&metrics.custom_metrics.unwrap()).unwrap();
+        assert_eq!(custom["tests"]["failed"], 1);
+    }
+
+    #[test]
+    fn test_pytest_runner_parses_counts() {
+        let output = "tests/test_csv.py ..F.s\n\
+                      ====== 3 passed, 1 failed, 1 skipped in 0.12s ======";
+        assert_eq!(PytestRunner.parse_summary(output), summary(3, 1, 1));
+        assert_eq!(
+            PytestRunner.parse_summary("==== 2 passed, 1 error in 0.05s ===="),
+            summary(2, 1, 0)
+        );
+    }
+
+    #[test]
+    fn test_npm_runner_parses_counts() {
+        let output = "Test Suites: 1 failed, 1 total\n\
+                      Tests:       1 failed, 4 passed, 5 total";
+        assert_eq!(NpmRunner.parse_summary(output), summary(4, 1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_detect_runner_from_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let tools = CodeAgentTools::new(Arc::new(ToolRegistry::with_defaults()), temp_dir.path());
+
+        assert_eq!(detect_runner(&tools, Language::Rust).await.name(), "cargo");
+
+        std::fs::write(temp_dir.path().join("pyproject.toml"), "[project]").unwrap();
+        assert_eq!(detect_runner(&tools, Language::Rust).await.name(), "pytest");
+    }
+}
@@ -2,9 +2,14 @@
 ///
 /// This module provides a high-level, agent-friendly interface to the tool system,
 /// wrapping the low-level ToolRegistry with methods tailored for code generation tasks.
-use bodhya_core::{Result, ToolRequest};
+use crate::diff::unified_diff;
+use crate::language::Language;
+use bodhya_core::{
+    ApprovalHook, ApprovalRequest, EventRecorder, Result, RunEvent, ToolRequest, ToolResponse,
+};
 use bodhya_tools_mcp::ToolRegistry;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -44,6 +49,16 @@ impl CommandOutput {
     }
 }
 
+/// What a scaffolded crate builds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrateKind {
+    /// A library (`src/lib.rs`)
+    Lib,
+    /// A binary (`src/main.rs`)
+    Bin,
+}
+
 /// Execution statistics for tracking tool usage
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ExecutionStats {
@@ -71,6 +86,16 @@ impl ExecutionStats {
     pub fn reset(&mut self) {
         *self = Self::default();
     }
+
+    /// Add the counts from `other` to these statistics
+    pub fn merge(&mut self, other: &ExecutionStats) {
+        self.files_read += other.files_read;
+        self.files_written += other.files_written;
+        self.files_listed += other.files_listed;
+        self.commands_executed += other.commands_executed;
+        self.bytes_read += other.bytes_read;
+        self.bytes_written += other.bytes_written;
+    }
 }
 
 /// High-level tool wrapper for CodeAgent
@@ -80,19 +105,110 @@ impl ExecutionStats {
 pub struct CodeAgentTools {
     /// Tool registry for low-level operations
     registry: Arc<ToolRegistry>,
-    /// Working directory for file operations
+    /// Working directory for commands, and for file operations unless
+    /// `output_dir` is set
     working_dir: PathBuf,
+    /// Root for file operations when generated files go elsewhere
+    output_dir: Option<PathBuf>,
     /// Execution statistics (thread-safe)
     stats: Arc<Mutex<ExecutionStats>>,
+    /// Approval hook consulted before writes and commands
+    approval: Option<Arc<dyn ApprovalHook>>,
+    /// Paths approved up front by [`Self::confirm_planned_writes`]; writes to
+    /// them are not asked about again
+    approved_writes: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Event recorder notified of each tool call
+    events: Option<EventRecorder>,
 }
 
+/// Fail with `InvalidInput` unless `name` is a valid crate name
+fn check_crate_name(name: &str) -> Result<()> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(bodhya_core::Error::InvalidInput(format!(
+            "Invalid crate name '{}'",
+            name
+        )))
+    }
+}
+
+/// String parameters longer than this are logged as their size only
+const MAX_LOGGED_PARAM_LEN: usize = 200;
+
 impl CodeAgentTools {
     /// Create a new CodeAgentTools instance
     pub fn new(registry: Arc<ToolRegistry>, working_dir: impl Into<PathBuf>) -> Self {
         Self {
             registry,
             working_dir: working_dir.into(),
+            output_dir: None,
             stats: Arc::new(Mutex::new(ExecutionStats::new())),
+            approval: None,
+            approved_writes: Arc::new(Mutex::new(HashSet::new())),
+            events: None,
+        }
+    }
+
+    /// Read and write files under `output_dir`; commands still run in the
+    /// working directory
+    pub fn with_output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(output_dir.into());
+        self
+    }
+
+    /// Accumulate statistics into `stats`, which other tool wrappers may share
+    pub fn with_shared_stats(mut self, stats: Arc<Mutex<ExecutionStats>>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Require approval from `approval` before each file write and command
+    pub fn with_approval(mut self, approval: Arc<dyn ApprovalHook>) -> Self {
+        self.approval = Some(approval);
+        self
+    }
+
+    /// Record each tool call (with a summary of its parameters) into `events`
+    pub fn with_events(mut self, events: EventRecorder) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Run `request` through the registry, recording the call if events are enabled
+    async fn execute(&self, request: ToolRequest) -> Result<ToolResponse> {
+        let Some(events) = &self.events else {
+            return self.registry.execute(request).await;
+        };
+
+        let tool = request.tool.clone();
+        let operation = request.operation.clone();
+        let params = summarize_params(&request.params);
+        let result = self.registry.execute(request).await;
+        events.record(RunEvent::ToolCall {
+            tool,
+            operation,
+            params,
+            success: matches!(&result, Ok(response) if response.success),
+        });
+        result
+    }
+
+    /// Ask the approval hook (if any) whether `request` may proceed
+    async fn check_approval(&self, request: ApprovalRequest) -> Result<()> {
+        match &self.approval {
+            Some(hook) if !hook.approve(&request).await => Err(bodhya_core::Error::Tool(format!(
+                "Operation not approved: {}",
+                request
+            ))),
+            _ => Ok(()),
         }
     }
 
@@ -106,33 +222,95 @@ impl CodeAgentTools {
         self.stats.lock().await.reset();
     }
 
-    /// Resolve a path relative to the working directory
+    /// Resolve a path relative to the output directory, or the working
+    /// directory if none is set
     fn resolve_path(&self, path: impl AsRef<Path>) -> PathBuf {
         let path = path.as_ref();
         if path.is_absolute() {
             path.to_path_buf()
         } else {
-            self.working_dir.join(path)
+            self.output_dir
+                .as_ref()
+                .unwrap_or(&self.working_dir)
+                .join(path)
         }
     }
 
     /// Read a file and return its contents
     pub async fn read_file(&self, path: impl AsRef<Path>) -> Result<String> {
+        let path = path.as_ref();
+        let request = self.read_request(path)?;
+        let response = self.execute(request).await?;
+        self.read_content(path, response).await
+    }
+
+    /// Read several files concurrently, returning their contents in the order given
+    ///
+    /// Fails with the first error (in path order) if any file cannot be read.
+    pub async fn read_files<P: AsRef<Path>>(&self, paths: &[P]) -> Result<Vec<String>> {
+        let requests = paths
+            .iter()
+            .map(|path| self.read_request(path))
+            .collect::<Result<Vec<_>>>()?;
+
+        let calls: Vec<_> = requests
+            .iter()
+            .map(|request| {
+                (
+                    request.tool.clone(),
+                    request.operation.clone(),
+                    summarize_params(&request.params),
+                )
+            })
+            .collect();
+        let results = self.registry.execute_batch(requests).await;
+
+        if let Some(events) = &self.events {
+            for ((tool, operation, params), result) in calls.into_iter().zip(&results) {
+                events.record(RunEvent::ToolCall {
+                    tool,
+                    operation,
+                    params,
+                    success: matches!(result, Ok(response) if response.success),
+                });
+            }
+        }
+
+        let mut contents = Vec::with_capacity(results.len());
+        for (path, result) in paths.iter().zip(results) {
+            contents.push(self.read_content(path.as_ref(), result?).await?);
+        }
+        Ok(contents)
+    }
+
+    /// Filesystem read request for `path`
+    fn read_request(&self, path: impl AsRef<Path>) -> Result<ToolRequest> {
         let resolved = self.resolve_path(path);
         let path_str = resolved
             .to_str()
             .ok_or_else(|| bodhya_core::Error::Tool("Invalid path encoding".to_string()))?;
 
-        let request = ToolRequest::new(
+        Ok(ToolRequest::new(
             "filesystem",
             "read",
             serde_json::json!({
                 "path": path_str
             }),
-        );
-
-        let response = self.registry.execute(request).await?;
+        ))
+    }
 
+    /// Contents from a read response, counted in the stats
+    ///
+    /// A response cut down to the registry's size cap is an error: code built
+    /// from a partial file would be silently wrong.
+    async fn read_content(&self, path: &Path, response: ToolResponse) -> Result<String> {
+        if response.success && response.truncated {
+            return Err(bodhya_core::Error::Tool(format!(
+                "{} exceeds the tool response limit of {} bytes and was truncated",
+                path.display(),
+                self.registry.max_response_bytes()
+            )));
+        }
         if response.success {
             let content = response.data["content"].as_str().unwrap_or("").to_string();
 
@@ -154,10 +332,58 @@ impl CodeAgentTools {
     /// Write content to a file
     pub async fn write_file(&self, path: impl AsRef<Path>, content: &str) -> Result<()> {
         let resolved = self.resolve_path(path);
+        self.write_resolved(resolved, content, None).await
+    }
+
+    /// Write content to a file, returning a unified diff of the change
+    ///
+    /// The diff is computed against the existing content (a full addition when
+    /// the file is new) and is also shown to the approval hook, if any.
+    pub async fn write_file_with_diff(
+        &self,
+        path: impl AsRef<Path>,
+        content: &str,
+    ) -> Result<String> {
+        let path = path.as_ref();
+        let resolved = self.resolve_path(path);
+        let new_label = format!("b/{}", path.display());
+
+        let diff = if self.file_exists(&resolved).await? {
+            let existing = self.read_file(&resolved).await?;
+            unified_diff(
+                &existing,
+                content,
+                &format!("a/{}", path.display()),
+                &new_label,
+            )
+        } else {
+            unified_diff("", content, "/dev/null", &new_label)
+        };
+
+        self.write_resolved(resolved, content, Some(diff.clone()))
+            .await?;
+        Ok(diff)
+    }
+
+    async fn write_resolved(
+        &self,
+        resolved: PathBuf,
+        content: &str,
+        diff: Option<String>,
+    ) -> Result<()> {
         let path_str = resolved
             .to_str()
             .ok_or_else(|| bodhya_core::Error::Tool("Invalid path encoding".to_string()))?;
 
+        if !self.approved_writes.lock().await.contains(&resolved) {
+            self.check_approval(ApprovalRequest::WriteFile {
+                path: resolved.clone(),
+                bytes: content.len(),
+                diff,
+            })
+            .await?;
+        }
+
         let request = ToolRequest::new(
             "filesystem",
             "write",
@@ -167,7 +393,7 @@ impl CodeAgentTools {
             }),
         );
 
-        let response = self.registry.execute(request).await?;
+        let response = self.execute(request).await?;
 
         if response.success {
             // Update stats
@@ -185,6 +411,48 @@ impl CodeAgentTools {
         }
     }
 
+    /// Ask the approval hook (if any) once for all the files a run will write
+    ///
+    /// Lets the user see every path up front instead of one write at a time;
+    /// once approved, writes to these paths are not asked about again.
+    pub async fn confirm_planned_writes(&self, paths: &[&str]) -> Result<()> {
+        let paths: Vec<PathBuf> = paths.iter().map(|path| self.resolve_path(path)).collect();
+        self.check_approval(ApprovalRequest::PlannedWrites {
+            paths: paths.clone(),
+        })
+        .await?;
+        self.approved_writes.lock().await.extend(paths);
+        Ok(())
+    }
+
+    /// Roll a file back to the `.bak` backup taken by a previous write or edit
+    pub async fn restore_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let resolved = self.resolve_path(path);
+        let path_str = resolved
+            .to_str()
+            .ok_or_else(|| bodhya_core::Error::Tool("Invalid path encoding".to_string()))?;
+
+        let request = ToolRequest::new(
+            "filesystem",
+            "restore",
+            serde_json::json!({
+                "path": path_str
+            }),
+        );
+
+        let response = self.execute(request).await?;
+
+        if response.success {
+            Ok(())
+        } else {
+            Err(bodhya_core::Error::Tool(
+                response
+                    .error
+                    .unwrap_or_else(|| "Failed to restore file".to_string()),
+            ))
+        }
+    }
+
     /// List files in a directory
     pub async fn list_files(&self, path: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
         let resolved = self.resolve_path(path);
@@ -200,7 +468,7 @@ impl CodeAgentTools {
             }),
         );
 
-        let response = self.registry.execute(request).await?;
+        let response = self.execute(request).await?;
 
         if response.success {
             let mut results = Vec::new();
@@ -252,7 +520,7 @@ impl CodeAgentTools {
             }),
         );
 
-        let response = self.registry.execute(request).await?;
+        let response = self.execute(request).await?;
 
         if response.success {
             Ok(response.data["exists"].as_bool().unwrap_or(false))
@@ -265,18 +533,97 @@ impl CodeAgentTools {
         }
     }
 
-    /// Execute a shell command
+    /// Create a minimal crate named `name` in the output directory
+    ///
+    /// Writes `<name>/Cargo.toml` and a stub `src/lib.rs` or `src/main.rs`,
+    /// returning the created paths. Fails if `<name>/Cargo.toml` already exists.
+    pub async fn scaffold_crate(&self, name: &str, kind: CrateKind) -> Result<Vec<PathBuf>> {
+        check_crate_name(name)?;
+
+        let manifest = Path::new(name).join("Cargo.toml");
+        if self.file_exists(&manifest).await? {
+            return Err(bodhya_core::Error::Tool(format!(
+                "Crate '{}' already exists",
+                name
+            )));
+        }
+
+        let (source, stub) = match kind {
+            CrateKind::Lib => ("lib.rs", format!("//! {}\n", name)),
+            CrateKind::Bin => (
+                "main.rs",
+                "fn main() {\n    println!(\"Hello, world!\");\n}\n".to_string(),
+            ),
+        };
+        let source = Path::new(name).join("src").join(source);
+        let cargo_toml = format!(
+            "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n",
+            name
+        );
+
+        self.write_file(&manifest, &cargo_toml).await?;
+        self.write_file(&source, &stub).await?;
+
+        Ok(vec![self.resolve_path(manifest), self.resolve_path(source)])
+    }
+
+    /// Add a dependency to the crate in the output directory with `cargo add`
+    ///
+    /// Runs offline, so only crates already in the local registry cache can be
+    /// added. `version` is a requirement such as `"1.0"` (latest cached if `None`). A failed
+    /// `cargo add` is reported in the returned output; a cargo without the
+    /// `add` subcommand (older than 1.62) is an error.
+    pub async fn add_dependency(
+        &self,
+        name: &str,
+        version: Option<&str>,
+        features: &[&str],
+    ) -> Result<CommandOutput> {
+        check_crate_name(name)?;
+
+        let spec = match version {
+            Some(version) => format!("{}@{}", name, version),
+            None => name.to_string(),
+        };
+        let manifest = self.resolve_path("Cargo.toml");
+        let manifest = manifest
+            .to_str()
+            .ok_or_else(|| bodhya_core::Error::Tool("Invalid path encoding".to_string()))?;
+        let features = features.join(",");
+
+        let mut args = vec![spec.as_str(), "--offline", "--manifest-path", manifest];
+        if !features.is_empty() {
+            args.extend(["--features", features.as_str()]);
+        }
+
+        let output = self.run_cargo("add", &args).await?;
+        if !output.success && output.stderr.contains("no such command") {
+            return Err(bodhya_core::Error::Tool(
+                "`cargo add` is not available; it requires cargo 1.62 or later".to_string(),
+            ));
+        }
+        Ok(output)
+    }
+
+    /// Execute a shell command in the working directory
     pub async fn run_command(&self, command: &str, args: &[&str]) -> Result<CommandOutput> {
+        self.check_approval(ApprovalRequest::RunCommand {
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        })
+        .await?;
+
         let request = ToolRequest::new(
             "shell",
             "exec",
             serde_json::json!({
                 "command": command,
-                "args": args
+                "args": args,
+                "cwd": self.working_dir
             }),
         );
 
-        let response = self.registry.execute(request).await?;
+        let response = self.execute(request).await?;
 
         // Update stats
         let mut stats = self.stats.lock().await;
@@ -309,6 +656,12 @@ impl CodeAgentTools {
         self.run_command("cargo", &cargo_args).await
     }
 
+    /// Run the test suite with the runner for `language`
+    pub async fn run_tests(&self, language: Language) -> Result<CommandOutput> {
+        let (command, args) = language.test_command();
+        self.run_command(command, args).await
+    }
+
     /// Edit a file with the specified operation
     ///
     /// # Arguments
@@ -339,7 +692,7 @@ impl CodeAgentTools {
             }),
         );
 
-        let response = self.registry.execute(request).await?;
+        let response = self.execute(request).await?;
 
         if response.success {
             let modified_content = response.data["modified_content"]
@@ -397,7 +750,7 @@ impl CodeAgentTools {
             }),
         );
 
-        let response = self.registry.execute(request).await?;
+        let response = self.execute(request).await?;
 
         if response.success {
             let matches = response.data["matches"]
@@ -418,6 +771,21 @@ impl CodeAgentTools {
     }
 }
 
+/// Copy of `params` with long strings (e.g., file contents) replaced by their size
+fn summarize_params(params: &serde_json::Value) -> serde_json::Value {
+    match params {
+        serde_json::Value::String(text) if text.len() > MAX_LOGGED_PARAM_LEN => {
+            serde_json::Value::String(format!("<{} bytes>", text.len()))
+        }
+        serde_json::Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| (key.clone(), summarize_params(value)))
+            .collect(),
+        serde_json::Value::Array(items) => items.iter().map(summarize_params).collect(),
+        other => other.clone(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -491,6 +859,46 @@ mod tests {
         assert_eq!(stats.bytes_read, test_content.len());
     }
 
+    #[tokio::test]
+    async fn test_read_file_over_response_limit_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = Arc::new(ToolRegistry::with_defaults().with_max_response_bytes(64));
+        let tools = CodeAgentTools::new(registry, temp_dir.path());
+
+        std::fs::write(temp_dir.path().join("big.rs"), "x".repeat(1024)).unwrap();
+
+        let err = tools.read_file("big.rs").await.unwrap_err();
+        assert!(err.to_string().contains("truncated"));
+        assert_eq!(tools.get_stats().await.files_read, 0);
+    }
+
+    #[tokio::test]
+    async fn test_read_files_preserves_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let tools = create_test_tools(&temp_dir);
+
+        let paths: Vec<String> = (0..12).map(|i| format!("file{}.txt", i)).collect();
+        for (i, path) in paths.iter().enumerate() {
+            // Larger files first, so later reads tend to finish earlier
+            let content = format!("{}:{}", i, "x".repeat((12 - i) * 10_000));
+            tools.write_file(path, &content).await.unwrap();
+        }
+
+        let contents = tools.read_files(&paths).await.unwrap();
+        assert_eq!(contents.len(), paths.len());
+        for (i, content) in contents.iter().enumerate() {
+            assert!(content.starts_with(&format!("{}:", i)));
+            assert_eq!(content.len(), format!("{}:", i).len() + (12 - i) * 10_000);
+        }
+        assert_eq!(tools.get_stats().await.files_read, paths.len());
+
+        let err = tools
+            .read_files(&["file0.txt", "missing.txt"])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, bodhya_core::Error::Tool(_)));
+    }
+
     #[tokio::test]
     async fn test_file_exists() {
         let temp_dir = TempDir::new().unwrap();
@@ -560,6 +968,28 @@ mod tests {
         assert_eq!(absolute, PathBuf::from("/tmp/test.txt"));
     }
 
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn test_output_dir_receives_files_while_commands_run_in_working_dir() {
+        let working_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let tools = create_test_tools(&working_dir).with_output_dir(output_dir.path());
+
+        tools
+            .write_file("src/lib.rs", "// generated")
+            .await
+            .unwrap();
+        assert!(output_dir.path().join("src/lib.rs").exists());
+        assert!(!working_dir.path().join("src/lib.rs").exists());
+        assert!(tools.file_exists("src/lib.rs").await.unwrap());
+
+        let output = tools.run_command("pwd", &[]).await.unwrap();
+        assert_eq!(
+            PathBuf::from(output.stdout.trim()).canonicalize().unwrap(),
+            working_dir.path().canonicalize().unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_stats_reset() {
         let temp_dir = TempDir::new().unwrap();
@@ -574,6 +1004,45 @@ mod tests {
         assert_eq!(stats.files_written, 0);
     }
 
+    #[test]
+    fn test_stats_merge() {
+        let mut total = ExecutionStats {
+            files_written: 1,
+            bytes_written: 10,
+            ..Default::default()
+        };
+        total.merge(&ExecutionStats {
+            files_read: 2,
+            files_written: 3,
+            bytes_written: 5,
+            ..Default::default()
+        });
+
+        assert_eq!(total.files_read, 2);
+        assert_eq!(total.files_written, 4);
+        assert_eq!(total.bytes_written, 15);
+    }
+
+    #[tokio::test]
+    async fn test_shared_stats_sum_across_tools() {
+        let temp_dir = TempDir::new().unwrap();
+        let stats = Arc::new(Mutex::new(ExecutionStats::new()));
+        let first = create_test_tools(&temp_dir).with_shared_stats(Arc::clone(&stats));
+        let second = create_test_tools(&temp_dir).with_shared_stats(Arc::clone(&stats));
+
+        let (a, b) = tokio::join!(
+            first.write_file("a.txt", "hello"),
+            second.write_file("b.txt", "world!")
+        );
+        a.unwrap();
+        b.unwrap();
+
+        let total = stats.lock().await.clone();
+        assert_eq!(total.files_written, 2);
+        assert_eq!(total.bytes_written, 11);
+        assert_eq!(first.get_stats().await.files_written, 2);
+    }
+
     #[tokio::test]
     async fn test_nested_directory_operations() {
         let temp_dir = TempDir::new().unwrap();
@@ -593,4 +1062,199 @@ mod tests {
         let exists = tools.file_exists("nested/dir/test.txt").await.unwrap();
         assert!(exists);
     }
+
+    struct DenyWrites;
+
+    #[async_trait::async_trait]
+    impl ApprovalHook for DenyWrites {
+        async fn approve(&self, request: &ApprovalRequest) -> bool {
+            !matches!(request, ApprovalRequest::WriteFile { .. })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_denied_write_creates_no_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let tools = create_test_tools(&temp_dir).with_approval(Arc::new(DenyWrites));
+
+        let result = tools.write_file("src/main.rs", "fn main() {}").await;
+        assert!(result.is_err());
+        assert!(!temp_dir.path().join("src/main.rs").exists());
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+        assert_eq!(tools.get_stats().await.files_written, 0);
+
+        // Commands are still allowed by this hook
+        let output = tools.run_command("echo", &["ok"]).await.unwrap();
+        assert!(output.success);
+    }
+
+    /// Approves the plan, counts the per-file prompts, and denies them
+    #[derive(Default)]
+    struct ApprovePlanOnly {
+        write_prompts: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl ApprovalHook for ApprovePlanOnly {
+        async fn approve(&self, request: &ApprovalRequest) -> bool {
+            if matches!(request, ApprovalRequest::WriteFile { .. }) {
+                self.write_prompts
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                return false;
+            }
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_planned_writes_are_not_asked_again() {
+        let temp_dir = TempDir::new().unwrap();
+        let hook = Arc::new(ApprovePlanOnly::default());
+        let tools = create_test_tools(&temp_dir).with_approval(hook.clone());
+
+        tools.confirm_planned_writes(&["src/lib.rs"]).await.unwrap();
+        tools
+            .write_file("src/lib.rs", "pub fn a() {}\n")
+            .await
+            .unwrap();
+        tools
+            .write_file("src/lib.rs", "pub fn b() {}\n")
+            .await
+            .unwrap();
+        assert!(tools.write_file("src/other.rs", "").await.is_err());
+
+        assert_eq!(
+            hook.write_prompts.load(std::sync::atomic::Ordering::SeqCst),
+            1
+        );
+        assert!(!temp_dir.path().join("src/other.rs").exists());
+    }
+
+    #[tokio::test]
+    async fn test_write_file_with_diff() {
+        let temp_dir = TempDir::new().unwrap();
+        let tools = create_test_tools(&temp_dir);
+
+        let diff = tools
+            .write_file_with_diff("lib.rs", "fn a() {}\nfn b() {}\n")
+            .await
+            .unwrap();
+        assert!(diff.starts_with("--- /dev/null\n+++ b/lib.rs\n"));
+        assert!(diff.contains("+fn a() {}\n+fn b() {}\n"));
+
+        let diff = tools
+            .write_file_with_diff("lib.rs", "fn a() {}\nfn b() -> u8 { 1 }\n")
+            .await
+            .unwrap();
+        assert!(diff.contains("-fn b() {}\n+fn b() -> u8 { 1 }\n"));
+        assert!(diff.contains(" fn a() {}\n"));
+        assert_eq!(
+            tools.read_file("lib.rs").await.unwrap(),
+            "fn a() {}\nfn b() -> u8 { 1 }\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_file_after_backed_up_edit() {
+        let temp_dir = TempDir::new().unwrap();
+        let tools = create_test_tools(&temp_dir);
+        tools.write_file("lib.rs", "fn good() {}\n").await.unwrap();
+
+        let operation = serde_json::json!({
+            "operation": "replace",
+            "old": "good",
+            "new": "bad"
+        });
+        let path = temp_dir.path().join("lib.rs");
+        let request = ToolRequest::new(
+            "edit",
+            "edit",
+            serde_json::json!({
+                "path": path.to_str().unwrap(),
+                "operation": operation,
+                "backup": true
+            }),
+        );
+        assert!(tools.registry.execute(request).await.unwrap().success);
+        assert_eq!(tools.read_file("lib.rs").await.unwrap(), "fn bad() {}\n");
+
+        tools.restore_file("lib.rs").await.unwrap();
+        assert_eq!(tools.read_file("lib.rs").await.unwrap(), "fn good() {}\n");
+        assert!(tools.restore_file("lib.rs").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scaffold_crate_compiles() {
+        let temp_dir = TempDir::new().unwrap();
+        let tools = create_test_tools(&temp_dir);
+
+        let created = tools
+            .scaffold_crate("scaffolded", CrateKind::Lib)
+            .await
+            .unwrap();
+        assert_eq!(
+            created,
+            vec![
+                temp_dir.path().join("scaffolded/Cargo.toml"),
+                temp_dir.path().join("scaffolded/src/lib.rs"),
+            ]
+        );
+
+        let manifest = created[0].to_str().unwrap();
+        let output = tools
+            .run_cargo(
+                "check",
+                &["--offline", "--quiet", "--manifest-path", manifest],
+            )
+            .await
+            .unwrap();
+        assert!(output.success, "cargo check failed: {}", output.stderr);
+
+        let err = tools
+            .scaffold_crate("scaffolded", CrateKind::Bin)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        assert!(!temp_dir.path().join("scaffolded/src/main.rs").exists());
+    }
+
+    #[tokio::test]
+    async fn test_scaffold_crate_rejects_invalid_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let tools = create_test_tools(&temp_dir);
+
+        for name in ["", "1st", "../escape", "my crate"] {
+            let result = tools.scaffold_crate(name, CrateKind::Bin).await;
+            assert!(matches!(result, Err(bodhya_core::Error::InvalidInput(_))));
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs `cargo add` and serde in the local registry cache (add_dependency runs offline)"]
+    async fn test_add_dependency_updates_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let tools = create_test_tools(&temp_dir);
+
+        tools.scaffold_crate("app", CrateKind::Lib).await.unwrap();
+        let registry = Arc::new(ToolRegistry::with_defaults());
+        let tools = CodeAgentTools::new(registry, temp_dir.path().join("app"));
+
+        let output = tools
+            .add_dependency("serde", Some("1"), &["derive"])
+            .await
+            .unwrap();
+        assert!(output.success, "{}", output.stderr);
+
+        let manifest = std::fs::read_to_string(temp_dir.path().join("app/Cargo.toml")).unwrap();
+        assert!(manifest.contains("serde = { version = \"1\", features = [\"derive\"] }"));
+    }
+
+    #[tokio::test]
+    async fn test_add_dependency_rejects_invalid_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let tools = create_test_tools(&temp_dir);
+
+        let result = tools.add_dependency("--locked", None, &[]).await;
+        assert!(matches!(result, Err(bodhya_core::Error::InvalidInput(_))));
+    }
 }
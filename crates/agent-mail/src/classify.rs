@@ -7,9 +7,11 @@
 /// - Sensitivity detection
 /// - Content filtering
 use crate::draft::EmailDraft;
+use serde::{Deserialize, Serialize};
 
 /// Email category
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EmailCategory {
     /// Formal business email
     FormalBusiness,
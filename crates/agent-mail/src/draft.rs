@@ -124,7 +124,8 @@ impl DraftGenerator {
                 .get_model(&ModelRole::Writer, "mail", &EngagementMode::Minimum)?;
 
         // Create model request
-        let request = ModelRequest::new(ModelRole::Writer, "mail", prompt);
+        let request =
+            ModelRequest::new(ModelRole::Writer, "mail", prompt).with_role_as_system_prompt();
 
         // Call the model backend
         let backend = self.registry.get_backend(&model_info.id).ok_or_else(|| {
@@ -2,23 +2,28 @@
 ///
 /// Phase 8: Email drafting and refinement
 use async_trait::async_trait;
-use bodhya_core::{Agent, AgentCapability, AgentContext, AgentResult, Result, Task};
-use bodhya_model_registry::ModelRegistry;
+use bodhya_core::{
+    Agent, AgentCapability, AgentContext, AgentResult, ExecutionLimits, PreviousResult, Result,
+    Task,
+};
+use bodhya_model_registry::{ModelRegistry, SharedRegistry};
 use std::sync::Arc;
 
 mod classify;
 mod draft;
+mod metadata;
 mod refine;
 
 // Re-export public types
 pub use classify::{EmailCategory, EmailClassification, EmailClassifier};
 pub use draft::{DraftGenerator, EmailDraft};
+pub use metadata::MailResultMetadata;
 pub use refine::{EmailRefiner, RefinedEmail, RefinementGoal};
 
 /// Mail generation agent
 pub struct MailAgent {
     enabled: bool,
-    registry: Option<Arc<ModelRegistry>>,
+    registry: Option<SharedRegistry>,
 }
 
 impl MailAgent {
@@ -32,6 +37,12 @@ impl MailAgent {
 
     /// Create a new MailAgent with model registry
     pub fn with_registry(registry: Arc<ModelRegistry>) -> Self {
+        Self::with_shared_registry(SharedRegistry::new(registry))
+    }
+
+    /// Create a new MailAgent using whichever registry `registry` holds when
+    /// a task starts
+    pub fn with_shared_registry(registry: SharedRegistry) -> Self {
         Self {
             enabled: true,
             registry: Some(registry),
@@ -56,10 +67,16 @@ impl MailAgent {
     }
 
     /// Generate email with drafting and optional refinement
-    async fn generate_email(&self, task: &Task) -> Result<String> {
-        let registry = self.registry.as_ref().ok_or_else(|| {
-            bodhya_core::Error::Config("Model registry not configured for MailAgent".to_string())
-        })?;
+    async fn generate_email(&self, task: &Task) -> Result<(String, MailResultMetadata)> {
+        let registry = &self
+            .registry
+            .as_ref()
+            .map(SharedRegistry::current)
+            .ok_or_else(|| {
+                bodhya_core::Error::Config(
+                    "Model registry not configured for MailAgent".to_string(),
+                )
+            })?;
 
         // Extract context and purpose from task description
         // Simple heuristic: first 100 chars as context, rest as purpose
@@ -78,9 +95,45 @@ impl MailAgent {
         let refined = refiner.refine(&draft, RefinementGoal::All).await?;
 
         // Step 3: Format the output
+        Ok(Self::format_email("Email Generation Complete", &refined))
+    }
+
+    /// Rework an earlier email using the task description as feedback
+    async fn refine_previous(
+        &self,
+        task: &Task,
+        previous: &PreviousResult,
+    ) -> Result<(String, MailResultMetadata)> {
+        let registry = &self
+            .registry
+            .as_ref()
+            .map(SharedRegistry::current)
+            .ok_or_else(|| {
+                bodhya_core::Error::Config(
+                    "Model registry not configured for MailAgent".to_string(),
+                )
+            })?;
+
+        // Prefer the structured email over re-parsing the markdown report
+        let draft = match serde_json::from_value::<MailResultMetadata>(previous.metadata.clone()) {
+            Ok(metadata) => EmailDraft::new(metadata.subject, metadata.body),
+            Err(_) => EmailDraft::from_text(&previous.content),
+        };
+
+        let refiner = EmailRefiner::new(Arc::clone(registry))?;
+        let refined = refiner
+            .refine(&draft, RefinementGoal::Custom(task.description.clone()))
+            .await?;
+
+        Ok(Self::format_email("Email Refinement Complete", &refined))
+    }
+
+    /// Render a refined email as a markdown report with its metadata
+    fn format_email(title: &str, refined: &RefinedEmail) -> (String, MailResultMetadata) {
+        let category = EmailClassifier::new().classify(&refined.draft).category;
         let mut output = String::new();
 
-        output.push_str("# Email Generation Complete\n\n");
+        output.push_str(&format!("# {}\n\n", title));
 
         output.push_str("## Final Email\n\n");
         output.push_str(&refined.draft.full_email);
@@ -93,7 +146,7 @@ impl MailAgent {
             }
         }
 
-        Ok(output)
+        (output, MailResultMetadata::new(refined, category))
     }
 }
 
@@ -132,11 +185,37 @@ impl Agent for MailAgent {
         }
     }
 
-    async fn handle(&self, task: Task, _ctx: AgentContext) -> Result<AgentResult> {
+    /// Mail drafting never writes files or runs commands
+    fn default_execution_limits(&self) -> ExecutionLimits {
+        ExecutionLimits {
+            max_file_writes: 0,
+            max_command_executions: 0,
+            ..ExecutionLimits::default()
+        }
+    }
+
+    async fn handle(&self, task: Task, ctx: AgentContext) -> Result<AgentResult> {
+        // Feedback on an earlier email goes straight to the refiner
+        if let Some(previous) = &ctx.previous_result {
+            return match self.refine_previous(&task, previous).await {
+                Ok((output, metadata)) => {
+                    Ok(AgentResult::success(task.id, output).with_metadata(metadata.to_value()))
+                }
+                Err(e) => Ok(AgentResult::failure(
+                    task.id,
+                    format!("Email refinement failed: {}", e),
+                )),
+            };
+        }
+
         let content = if self.registry.is_some() {
             // Use draft and refine pipeline
             match self.generate_email(&task).await {
-                Ok(output) => output,
+                Ok((output, metadata)) => {
+                    return Ok(
+                        AgentResult::success(task.id, output).with_metadata(metadata.to_value())
+                    );
+                }
                 Err(e) => {
                     // Fall back to static email on error
                     eprintln!("Email generation failed: {}, falling back to static", e);
@@ -195,6 +274,18 @@ mod tests {
         assert!(!cap.description.is_empty());
     }
 
+    #[test]
+    fn test_mail_agent_default_limits_forbid_side_effects() {
+        let limits = MailAgent::new().default_execution_limits();
+
+        assert_eq!(limits.max_file_writes, 0);
+        assert_eq!(limits.max_command_executions, 0);
+        assert_eq!(
+            limits.max_iterations,
+            ExecutionLimits::default().max_iterations
+        );
+    }
+
     #[tokio::test]
     async fn test_mail_agent_handle_returns_success() {
         let agent = MailAgent::new();
@@ -209,6 +300,84 @@ mod tests {
         assert!(agent_result.content.contains("Subject"));
     }
 
+    #[tokio::test]
+    async fn test_mail_agent_result_carries_structured_metadata() {
+        use bodhya_core::ModelRole;
+        use bodhya_model_registry::MockBackend;
+
+        let backend = MockBackend::new("mock")
+            .with_role_response(
+                ModelRole::Writer,
+                "Subject: Team offsite\n\nHi all, the offsite is on Friday.",
+            )
+            .with_role_response(
+                ModelRole::Writer,
+                "**Refined Email**:\nSubject: Team offsite on Friday\n\n\
+                 Hi everyone, our offsite is this Friday.\n\n\
+                 **Changes Made**:\n- Put the date in the subject\n- Warmer greeting\n",
+            );
+        let agent = MailAgent::with_registry(Arc::new(backend.into_registry("mail")));
+
+        let result = agent
+            .handle(
+                Task::new("Tell the team about the offsite"),
+                AgentContext::new(Default::default()),
+            )
+            .await
+            .unwrap();
+
+        let metadata: MailResultMetadata = serde_json::from_value(result.metadata).unwrap();
+        assert_eq!(metadata.subject, "Team offsite on Friday");
+        assert!(metadata.body.contains("our offsite is this Friday"));
+        assert_eq!(metadata.category, EmailCategory::FormalBusiness);
+        assert_eq!(
+            metadata.refinements,
+            vec!["Put the date in the subject", "Warmer greeting"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mail_agent_refines_previous_result() {
+        use bodhya_core::ModelRole;
+        use bodhya_model_registry::MockBackend;
+
+        let backend = Arc::new(MockBackend::new("mock").with_role_response(
+            ModelRole::Writer,
+            "**Refined Email**:\nSubject: Offsite moved\n\nHi all, the offsite is now Monday.\n\n\
+             **Changes Made**:\n- Moved the date to Monday\n",
+        ));
+        let agent = MailAgent::with_registry(Arc::new(Arc::clone(&backend).registry("mail")));
+        let previous = PreviousResult {
+            task_id: "t1".to_string(),
+            description: "Tell the team about the offsite".to_string(),
+            domain: "mail".to_string(),
+            content: "# Email Generation Complete".to_string(),
+            metadata: serde_json::json!({
+                "subject": "Team offsite",
+                "body": "Hi all, the offsite is on Friday.",
+                "category": "formal_business",
+                "refinements": []
+            }),
+        };
+
+        let result = agent
+            .handle(
+                Task::new("Move the offsite to Monday"),
+                AgentContext::new(Default::default()).with_previous_result(previous),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        let requests = backend.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].prompt.contains("the offsite is on Friday"));
+        assert!(requests[0].prompt.contains("Move the offsite to Monday"));
+
+        let metadata: MailResultMetadata = serde_json::from_value(result.metadata).unwrap();
+        assert_eq!(metadata.subject, "Offsite moved");
+    }
+
     #[tokio::test]
     async fn test_mail_agent_handle_includes_task_description() {
         let agent = MailAgent::new();
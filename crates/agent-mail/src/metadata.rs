@@ -0,0 +1,37 @@
+/// Structured results of an email generation run
+///
+/// Stored in `AgentResult.metadata` so API clients can read the final email
+/// as fields instead of parsing the markdown report.
+use crate::classify::EmailCategory;
+use crate::refine::RefinedEmail;
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable summary of a generated email
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MailResultMetadata {
+    /// Subject line of the final email
+    pub subject: String,
+    /// Body of the final email
+    pub body: String,
+    /// Category assigned by the classifier
+    pub category: EmailCategory,
+    /// Changes made while refining the draft
+    pub refinements: Vec<String>,
+}
+
+impl MailResultMetadata {
+    /// Summarize a refined email
+    pub fn new(refined: &RefinedEmail, category: EmailCategory) -> Self {
+        Self {
+            subject: refined.draft.subject.clone(),
+            body: refined.draft.body.clone(),
+            category,
+            refinements: refined.changes.clone(),
+        }
+    }
+
+    /// Value for `AgentResult.metadata`
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
@@ -17,6 +17,8 @@ pub enum RefinementGoal {
     Conciseness,
     /// All of the above
     All,
+    /// Apply free-form feedback, such as a user's review comments
+    Custom(String),
 }
 
 impl RefinementGoal {
@@ -27,6 +29,7 @@ impl RefinementGoal {
             RefinementGoal::Tone => "make tone more polite and professional",
             RefinementGoal::Conciseness => "reduce verbosity while maintaining completeness",
             RefinementGoal::All => "improve clarity, tone, and conciseness",
+            RefinementGoal::Custom(feedback) => feedback,
         }
     }
 }
@@ -138,7 +141,8 @@ impl EmailRefiner {
                 .get_model(&ModelRole::Writer, "mail", &EngagementMode::Minimum)?;
 
         // Create model request
-        let request = ModelRequest::new(ModelRole::Writer, "mail", prompt);
+        let request =
+            ModelRequest::new(ModelRole::Writer, "mail", prompt).with_role_as_system_prompt();
 
         // Call the model backend
         let backend = self.registry.get_backend(&model_info.id).ok_or_else(|| {
@@ -0,0 +1,403 @@
+//! OpenAI-compatible chat completions
+//!
+//! `POST /v1/chat/completions` turns a conversation into a task, routes it
+//! like `POST /tasks`, and waits for it in the task queue, so chat shares the
+//! worker limit with every other task. The agent is picked from the last user
+//! message; system messages and earlier turns are given to it as context.
+//!
+//! With `stream: true` the reply is sent as server-sent
+//! `chat.completion.chunk` events ending in `data: [DONE]`, so
+//! OpenAI-compatible clients can talk to Bodhya directly. Agents produce
+//! their reply in one piece, so streaming is post-hoc: the chunks are the
+//! finished reply split by line, sent once the task completes.
+
+use crate::models::{
+    ChatChoice, ChatChunkChoice, ChatCompletion, ChatCompletionChunk, ChatCompletionRequest,
+    ChatDelta, ChatMessage,
+};
+use crate::routes::ApiError;
+use crate::state::AppState;
+use axum::{
+    extract::State,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use bodhya_core::Task;
+use chrono::Utc;
+use futures::Stream;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// POST /v1/chat/completions - Answer the conversation's last user message
+///
+/// The task is routed before anything is sent, so a conversation no agent
+/// can take is rejected with 400 in both modes.
+pub async fn chat_completions(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Result<Response, ApiError> {
+    let mut task = build_chat_task(&state, &request).map_err(ApiError::BadRequest)?;
+    let agent_id = state
+        .controller
+        .route_only(&task)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    // Keep the agent chosen from the last message, then hand it the rest of
+    // the conversation (which would otherwise sway routing)
+    if task.domain_hint.is_none() {
+        task.domain_hint = state
+            .list_agents()
+            .iter()
+            .find(|agent| agent.id() == agent_id)
+            .map(|agent| agent.capability().domain);
+    }
+    task.description = with_conversation(&request.messages, &task.description);
+    let header = CompletionHeader {
+        id: format!("chatcmpl-{}", task.id),
+        created: Utc::now().timestamp(),
+        model: agent_id,
+    };
+
+    if request.stream {
+        return Ok(stream_completion(state, task, header).into_response());
+    }
+
+    let content = run_task(&state, task)
+        .await
+        .map_err(ApiError::InternalError)?;
+    Ok(Json(ChatCompletion {
+        id: header.id,
+        object: "chat.completion".to_string(),
+        created: header.created,
+        model: header.model,
+        choices: vec![ChatChoice {
+            index: 0,
+            message: ChatMessage::assistant(content),
+            finish_reason: "stop".to_string(),
+        }],
+    })
+    .into_response())
+}
+
+/// Task for the last user message, carrying the whole conversation as payload
+///
+/// `model` becomes the domain hint when an agent serves that domain.
+fn build_chat_task(state: &AppState, request: &ChatCompletionRequest) -> Result<Task, String> {
+    let prompt = request
+        .messages
+        .iter()
+        .rev()
+        .find(|message| message.role == "user")
+        .map(|message| message.content.trim())
+        .filter(|content| !content.is_empty())
+        .ok_or_else(|| "Conversation must contain a non-empty user message".to_string())?;
+
+    let mut task = Task::new(prompt).with_payload(serde_json::json!({
+        "messages": request.messages,
+    }));
+
+    if let Some(model) = &request.model {
+        let serves_domain = state
+            .list_agents()
+            .iter()
+            .any(|agent| agent.capability().domain == *model);
+        if serves_domain {
+            task = task.with_domain(model.clone());
+        }
+    }
+
+    Ok(task)
+}
+
+/// `prompt` preceded by the conversation's system messages and earlier turns
+fn with_conversation(messages: &[ChatMessage], prompt: &str) -> String {
+    let last_user = messages
+        .iter()
+        .rposition(|message| message.role == "user")
+        .unwrap_or(messages.len());
+    let instructions: Vec<&str> = messages
+        .iter()
+        .filter(|message| message.role == "system")
+        .map(|message| message.content.trim())
+        .filter(|content| !content.is_empty())
+        .collect();
+    let earlier: Vec<String> = messages[..last_user]
+        .iter()
+        .filter(|message| message.role != "system")
+        .map(|message| format!("{}: {}", message.role, message.content.trim()))
+        .collect();
+
+    if instructions.is_empty() && earlier.is_empty() {
+        return prompt.to_string();
+    }
+    let mut description = String::new();
+    if !instructions.is_empty() {
+        description.push_str(&format!("Instructions:\n{}\n\n", instructions.join("\n")));
+    }
+    if !earlier.is_empty() {
+        description.push_str(&format!("Conversation so far:\n{}\n\n", earlier.join("\n")));
+    }
+    description.push_str(&format!("Request:\n{}", prompt));
+    description
+}
+
+/// Queue `task` and wait for it, returning its content or why it failed
+async fn run_task(state: &Arc<AppState>, task: Task) -> Result<String, String> {
+    let info = state.submit_task(task).await;
+    state.enqueue(&info.task_id, info.priority);
+    match state.wait_for_result(&info.task_id).await {
+        Some(result) if result.success => Ok(result.content),
+        Some(result) => Err(result.error.unwrap_or_else(|| "Task failed".to_string())),
+        None => Err("Task result is no longer available".to_string()),
+    }
+}
+
+/// Fields shared by every chunk of one completion
+struct CompletionHeader {
+    id: String,
+    created: i64,
+    model: String,
+}
+
+impl CompletionHeader {
+    fn event(&self, delta: ChatDelta, finish_reason: Option<&str>) -> Event {
+        let chunk = ChatCompletionChunk {
+            id: self.id.clone(),
+            object: "chat.completion.chunk".to_string(),
+            created: self.created,
+            model: self.model.clone(),
+            choices: vec![ChatChunkChoice {
+                index: 0,
+                delta,
+                finish_reason: finish_reason.map(str::to_string),
+            }],
+        };
+        Event::default().data(serde_json::to_string(&chunk).unwrap_or_default())
+    }
+}
+
+/// Stream the role, then the reply line by line, then the stop chunk
+///
+/// The reply chunks follow once the task has finished. A failed task sends
+/// an `error` event instead of the reply.
+fn stream_completion(
+    state: Arc<AppState>,
+    task: Task,
+    header: CompletionHeader,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let _ = tx.send(header.event(
+        ChatDelta {
+            role: Some("assistant".to_string()),
+            content: None,
+        },
+        None,
+    ));
+
+    tokio::spawn(async move {
+        match run_task(&state, task).await {
+            Ok(content) => {
+                for line in content.split_inclusive('\n') {
+                    let delta = ChatDelta {
+                        role: None,
+                        content: Some(line.to_string()),
+                    };
+                    let _ = tx.send(header.event(delta, None));
+                }
+                let _ = tx.send(header.event(ChatDelta::default(), Some("stop")));
+            }
+            Err(error) => {
+                let body = serde_json::json!({ "error": { "message": error } });
+                let _ = tx.send(Event::default().data(body.to_string()));
+            }
+        }
+        let _ = tx.send(Event::default().data("[DONE]"));
+    });
+
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok(event), rx))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::router;
+    use bodhya_controller::Controller;
+    use bodhya_core::{Agent, AgentCapability, AgentContext, AgentResult};
+
+    /// Agent that answers every task with a fixed two-line reply
+    struct EchoAgent;
+
+    #[async_trait::async_trait]
+    impl Agent for EchoAgent {
+        fn id(&self) -> &'static str {
+            "echo"
+        }
+
+        fn capability(&self) -> AgentCapability {
+            AgentCapability::new("echo", vec![], "Echoes the task")
+                .with_keywords(vec!["hello".to_string()])
+        }
+
+        async fn handle(&self, task: Task, _ctx: AgentContext) -> bodhya_core::Result<AgentResult> {
+            let content = format!("You said:\n{}", task.description);
+            Ok(AgentResult::success(task.id, content))
+        }
+    }
+
+    async fn spawn_server() -> String {
+        let controller = Controller::new(vec![Arc::new(EchoAgent) as Arc<dyn Agent>]);
+        spawn_server_with(Arc::new(AppState::new(controller))).await
+    }
+
+    async fn spawn_server_with(state: Arc<AppState>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router(state)).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    async fn post_chat(base_url: &str, body: serde_json::Value) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(format!("{}/v1/chat/completions", base_url))
+            .header("content-type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_non_streaming_completion() {
+        let base_url = spawn_server().await;
+
+        let response = post_chat(
+            &base_url,
+            serde_json::json!({
+                "model": "echo",
+                "messages": [
+                    { "role": "system", "content": "Be brief" },
+                    { "role": "user", "content": "hello" }
+                ]
+            }),
+        )
+        .await;
+        assert_eq!(response.status().as_u16(), 200);
+
+        let completion: ChatCompletion =
+            serde_json::from_str(&response.text().await.unwrap()).unwrap();
+        assert!(completion.id.starts_with("chatcmpl-"));
+        assert_eq!(completion.object, "chat.completion");
+        assert_eq!(completion.model, "echo");
+        assert_eq!(completion.choices.len(), 1);
+        assert_eq!(
+            completion.choices[0].message,
+            ChatMessage::assistant("You said:\nInstructions:\nBe brief\n\nRequest:\nhello")
+        );
+        assert_eq!(completion.choices[0].finish_reason, "stop");
+    }
+
+    #[tokio::test]
+    async fn test_earlier_turns_reach_the_agent_through_the_queue() {
+        let controller = Controller::new(vec![Arc::new(EchoAgent) as Arc<dyn Agent>]);
+        let state = Arc::new(AppState::new(controller).with_workers(1));
+        let base_url = spawn_server_with(Arc::clone(&state)).await;
+
+        let response = post_chat(
+            &base_url,
+            serde_json::json!({
+                "messages": [
+                    { "role": "user", "content": "hello there" },
+                    { "role": "assistant", "content": "Hi! What can I do?" },
+                    { "role": "user", "content": "say it again" }
+                ]
+            }),
+        )
+        .await;
+        assert_eq!(response.status().as_u16(), 200);
+
+        let completion: ChatCompletion =
+            serde_json::from_str(&response.text().await.unwrap()).unwrap();
+        assert_eq!(
+            completion.choices[0].message.content,
+            "You said:\nConversation so far:\nuser: hello there\nassistant: Hi! What can I do?\n\n\
+             Request:\nsay it again"
+        );
+        // Routed on the last message alone, then run as a queued task
+        assert_eq!(completion.model, "echo");
+        let task_id = completion.id.strip_prefix("chatcmpl-").unwrap();
+        let info = state.get_task_info(task_id).await.unwrap();
+        assert_eq!(info.status, crate::models::TaskStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_completion() {
+        let base_url = spawn_server().await;
+
+        let response = post_chat(
+            &base_url,
+            serde_json::json!({
+                "messages": [{ "role": "user", "content": "hello" }],
+                "stream": true
+            }),
+        )
+        .await;
+        assert_eq!(response.status().as_u16(), 200);
+        assert!(response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/event-stream"));
+
+        let body = response.text().await.unwrap();
+        let data: Vec<&str> = body
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .collect();
+        assert_eq!(data.last(), Some(&"[DONE]"));
+
+        let chunks: Vec<ChatCompletionChunk> = data[..data.len() - 1]
+            .iter()
+            .map(|d| serde_json::from_str(d).unwrap())
+            .collect();
+        assert!(chunks.iter().all(|c| c.object == "chat.completion.chunk"));
+        assert!(chunks
+            .iter()
+            .all(|c| c.id == chunks[0].id && c.model == "echo"));
+
+        let first = &chunks[0].choices[0];
+        assert_eq!(first.delta.role.as_deref(), Some("assistant"));
+        let last = &chunks[chunks.len() - 1].choices[0];
+        assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+
+        let content: String = chunks
+            .iter()
+            .filter_map(|c| c.choices[0].delta.content.as_deref())
+            .collect();
+        assert_eq!(content, "You said:\nhello");
+        // One chunk per line of the reply, plus the role and stop chunks
+        assert_eq!(chunks.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_conversation_without_user_message_is_rejected() {
+        let base_url = spawn_server().await;
+
+        let response = post_chat(
+            &base_url,
+            serde_json::json!({
+                "messages": [{ "role": "system", "content": "Be brief" }],
+                "stream": true
+            }),
+        )
+        .await;
+        assert_eq!(response.status().as_u16(), 400);
+    }
+}
@@ -0,0 +1,225 @@
+//! Per-task progress events for WebSocket clients
+//!
+//! Every update to a task is numbered and kept in a bounded buffer, so a
+//! client whose connection drops can reconnect with `?since=<seq>` and receive
+//! the events it missed before live events resume. A task's buffer is
+//! dropped once it finishes; later clients get the stored result instead.
+
+use crate::models::{SequencedMessage, WsMessage};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Default number of recent events kept per task
+pub const DEFAULT_EVENT_BUFFER: usize = 256;
+
+/// Recent and live events of one task
+struct TaskEvents {
+    recent: VecDeque<SequencedMessage>,
+    last_seq: u64,
+    live: broadcast::Sender<SequencedMessage>,
+}
+
+/// Events to replay to a client, then a receiver for the ones that follow
+pub struct Subscription {
+    /// Buffered events after the requested sequence number, oldest first
+    pub replay: Vec<SequencedMessage>,
+    /// Whether events after the requested sequence number were already
+    /// dropped from the buffer (the replay starts later than asked)
+    pub missed: bool,
+    /// Sequence number of the task's latest event when subscribing
+    pub last_seq: u64,
+    /// Events published after subscribing
+    pub live: broadcast::Receiver<SequencedMessage>,
+}
+
+impl Subscription {
+    /// Subscription to a finished task whose final event has `complete_seq`
+    ///
+    /// Nothing is replayed and the live feed is closed. Only a client that
+    /// saw every event before the final one has missed nothing: the caller
+    /// sends the stored result itself.
+    pub fn finished(since: u64, complete_seq: u64) -> Self {
+        Self {
+            replay: Vec::new(),
+            missed: since.saturating_add(1) < complete_seq,
+            last_seq: complete_seq,
+            live: broadcast::channel(1).1,
+        }
+    }
+}
+
+/// Sequenced event buffers for all tasks
+pub struct EventHub {
+    tasks: Mutex<HashMap<String, TaskEvents>>,
+    capacity: usize,
+}
+
+impl EventHub {
+    /// Keep up to `capacity` recent events per task
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            tasks: Mutex::new(HashMap::new()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Record `message` for `task_id`, returning its sequence number
+    ///
+    /// Sequence numbers start at 1 and increase by one per task.
+    pub fn publish(&self, task_id: &str, message: WsMessage) -> u64 {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        let events = Self::entry(&mut tasks, task_id, self.capacity);
+        Self::push(events, message, self.capacity)
+    }
+
+    /// Record the final `message` of `task_id` and drop the task's buffer
+    ///
+    /// Current subscribers still receive `message`. Returns its sequence number.
+    pub fn finish(&self, task_id: &str, message: WsMessage) -> u64 {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        let events = Self::entry(&mut tasks, task_id, self.capacity);
+        let seq = Self::push(events, message, self.capacity);
+        tasks.remove(task_id);
+        seq
+    }
+
+    /// Number of tasks with buffered events
+    pub fn task_count(&self) -> usize {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    fn push(events: &mut TaskEvents, message: WsMessage, capacity: usize) -> u64 {
+        events.last_seq += 1;
+        let event = SequencedMessage {
+            seq: events.last_seq,
+            message,
+        };
+        if events.recent.len() == capacity {
+            events.recent.pop_front();
+        }
+        events.recent.push_back(event.clone());
+        // No receivers is fine: nobody is watching this task right now
+        let _ = events.live.send(event);
+
+        events.last_seq
+    }
+
+    /// Subscribe to `task_id`, replaying buffered events with `seq > since`
+    ///
+    /// Replay and subscription happen under one lock, so every event is in
+    /// exactly one of `replay` and `live`.
+    pub fn subscribe(&self, task_id: &str, since: u64) -> Subscription {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        let events = Self::entry(&mut tasks, task_id, self.capacity);
+
+        let replay: Vec<SequencedMessage> = events
+            .recent
+            .iter()
+            .filter(|event| event.seq > since)
+            .cloned()
+            .collect();
+        let first_available = events
+            .recent
+            .front()
+            .map_or(events.last_seq + 1, |event| event.seq);
+
+        Subscription {
+            replay,
+            missed: since.saturating_add(1) < first_available && since < events.last_seq,
+            last_seq: events.last_seq,
+            live: events.live.subscribe(),
+        }
+    }
+
+    fn entry<'a>(
+        tasks: &'a mut HashMap<String, TaskEvents>,
+        task_id: &str,
+        capacity: usize,
+    ) -> &'a mut TaskEvents {
+        tasks
+            .entry(task_id.to_string())
+            .or_insert_with(|| TaskEvents {
+                recent: VecDeque::with_capacity(capacity),
+                last_seq: 0,
+                live: broadcast::channel(capacity).0,
+            })
+    }
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_BUFFER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(content: &str) -> WsMessage {
+        WsMessage::TaskOutput {
+            task_id: "t".to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_then_live_without_gaps() {
+        let hub = EventHub::new(8);
+        assert_eq!(hub.publish("t", output("a")), 1);
+        assert_eq!(hub.publish("t", output("b")), 2);
+        assert_eq!(hub.publish("other", output("x")), 1);
+
+        let mut sub = hub.subscribe("t", 1);
+        assert_eq!(
+            sub.replay.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert!(!sub.missed);
+
+        hub.publish("t", output("c"));
+        assert_eq!(sub.live.recv().await.unwrap().seq, 3);
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let hub = EventHub::new(2);
+        for content in ["a", "b", "c"] {
+            hub.publish("t", output(content));
+        }
+
+        let sub = hub.subscribe("t", 0);
+        assert_eq!(
+            sub.replay.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert!(sub.missed);
+        assert!(!hub.subscribe("t", 1).missed);
+        assert!(!hub.subscribe("t", 3).missed);
+    }
+
+    #[tokio::test]
+    async fn test_finish_delivers_final_event_and_drops_buffer() {
+        let hub = EventHub::new(8);
+        hub.publish("t", output("a"));
+        let mut sub = hub.subscribe("t", 0);
+
+        assert_eq!(hub.finish("t", output("done")), 2);
+        assert_eq!(hub.task_count(), 0);
+
+        assert_eq!(sub.live.recv().await.unwrap().seq, 2);
+        assert!(matches!(
+            sub.live.recv().await,
+            Err(broadcast::error::RecvError::Closed)
+        ));
+    }
+
+    #[test]
+    fn test_finished_subscription_reports_missed_events() {
+        assert!(!Subscription::finished(4, 5).missed);
+        assert!(!Subscription::finished(5, 5).missed);
+        assert!(Subscription::finished(2, 5).missed);
+        assert!(!Subscription::finished(u64::MAX, 5).missed);
+    }
+}
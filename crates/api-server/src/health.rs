@@ -0,0 +1,268 @@
+//! Readiness checks behind `GET /health`
+//!
+//! Each model in the registry is checked for readiness: a loaded backend must
+//! pass its health check, and a local model without one must have its file
+//! on disk. A deep check also asks each loaded backend for a one-token
+//! completion. A model is critical when an enabled agent serves its domain.
+//!
+//! The registry checked is the one the agents run on (see
+//! [`AppState::with_models`]). Deep checks cost a model call per backend, so
+//! their result is reused for a while and concurrent ones share one probe.
+
+use crate::models::{AgentHealth, BackendHealth, HealthResponse};
+use crate::state::AppState;
+use axum::http::StatusCode;
+use bodhya_core::{ModelBackend, ModelRequest, ModelRole};
+use bodhya_model_registry::ModelRegistry;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// How long a deep check waits for a backend to answer
+pub const DEFAULT_DEEP_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long the result of a deep check is reused
+pub const DEFAULT_DEEP_CHECK_TTL: Duration = Duration::from_secs(60);
+
+/// Result of the last deep check
+#[derive(Default)]
+pub struct DeepCheckCache {
+    last: Mutex<Option<DeepCheck>>,
+}
+
+/// When a registry was probed and what was found
+struct DeepCheck {
+    checked_at: Instant,
+    registry: Arc<ModelRegistry>,
+    backends: Vec<BackendHealth>,
+}
+
+impl DeepCheckCache {
+    /// The cached result for `registry` if younger than `ttl`, else a fresh probe
+    ///
+    /// The lock is held while probing, so concurrent callers wait for the
+    /// probe in flight and reuse its result.
+    async fn get_or_probe(
+        &self,
+        registry: &Arc<ModelRegistry>,
+        ttl: Duration,
+        probe: impl std::future::Future<Output = Vec<BackendHealth>>,
+    ) -> Vec<BackendHealth> {
+        let mut last = self.last.lock().await;
+        if let Some(check) = last.as_ref() {
+            if Arc::ptr_eq(&check.registry, registry) && check.checked_at.elapsed() < ttl {
+                return check.backends.clone();
+            }
+        }
+        let backends = probe.await;
+        *last = Some(DeepCheck {
+            checked_at: Instant::now(),
+            registry: Arc::clone(registry),
+            backends: backends.clone(),
+        });
+        backends
+    }
+}
+
+/// Build the health report, returning 503 when a critical backend is not ready
+pub async fn check(state: &AppState, deep: bool) -> (StatusCode, HealthResponse) {
+    let agents: Vec<AgentHealth> = state
+        .list_agents()
+        .iter()
+        .map(|agent| AgentHealth {
+            id: agent.id().to_string(),
+            domain: agent.capability().domain,
+            enabled: agent.is_enabled(),
+        })
+        .collect();
+
+    let timeout = state.deep_check_timeout;
+    let backends = match state.models() {
+        Some(registry) if deep => {
+            state
+                .deep_checks
+                .get_or_probe(
+                    &registry,
+                    state.deep_check_ttl,
+                    check_backends(&registry, &agents, true, timeout),
+                )
+                .await
+        }
+        Some(registry) => check_backends(&registry, &agents, false, timeout).await,
+        None => Vec::new(),
+    };
+
+    let (status_code, status) = if backends.iter().any(|b| b.critical && !b.ready) {
+        (StatusCode::SERVICE_UNAVAILABLE, "unavailable")
+    } else if backends.iter().any(|b| !b.ready) {
+        (StatusCode::OK, "degraded")
+    } else {
+        (StatusCode::OK, "ok")
+    };
+
+    let response = HealthResponse {
+        status: status.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        uptime_seconds: state.uptime_seconds(),
+        backends,
+        agents,
+    };
+    (status_code, response)
+}
+
+/// Readiness of every model in `registry`
+async fn check_backends(
+    registry: &ModelRegistry,
+    agents: &[AgentHealth],
+    deep: bool,
+    timeout: Duration,
+) -> Vec<BackendHealth> {
+    let mut backends = Vec::new();
+    for model in registry.list_models() {
+        let backend_kind = registry
+            .manifest()
+            .get_model(&model.id)
+            .map(|definition| definition.backend.clone())
+            .unwrap_or_default();
+
+        let (ready, detail) = match registry.get_backend(&model.id) {
+            Some(backend) if deep => probe(backend, &model.role, &model.domain, timeout).await,
+            Some(backend) => match backend.health_check().await {
+                Ok(true) => (true, None),
+                Ok(false) => (false, Some("backend reports unhealthy".to_string())),
+                Err(e) => (false, Some(e.to_string())),
+            },
+            None if backend_kind == "local" && model.installed => (true, None),
+            None if backend_kind == "local" => (
+                false,
+                Some(format!(
+                    "model file not found: {}",
+                    registry.get_model_path(&model.id).display()
+                )),
+            ),
+            None => (false, Some("no backend registered".to_string())),
+        };
+
+        let critical = agents
+            .iter()
+            .any(|agent| agent.enabled && agent.domain == model.domain);
+
+        backends.push(BackendHealth {
+            model_id: model.id,
+            backend: backend_kind,
+            domain: model.domain,
+            critical,
+            ready,
+            detail,
+        });
+    }
+    backends.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+    backends
+}
+
+/// Ask `backend` for a one-token completion
+async fn probe(
+    backend: Arc<dyn ModelBackend>,
+    role: &ModelRole,
+    domain: &str,
+    timeout: Duration,
+) -> (bool, Option<String>) {
+    let request = ModelRequest::new(role.clone(), domain, "ping").with_max_tokens(1);
+    match tokio::time::timeout(timeout, backend.generate(request)).await {
+        Ok(Ok(_)) => (true, None),
+        Ok(Err(e)) => (false, Some(e.to_string())),
+        Err(_) => (
+            false,
+            Some(format!("no response within {}s", timeout.as_secs())),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bodhya_agent_code::CodeAgent;
+    use bodhya_controller::Controller;
+    use bodhya_core::Agent;
+    use bodhya_model_registry::MockBackend;
+
+    fn state_with(backend: MockBackend) -> AppState {
+        let agent = Arc::new(CodeAgent::new()) as Arc<dyn Agent>;
+        AppState::new(Controller::new(vec![agent]))
+            .with_models(Arc::new(backend.into_registry("code")))
+    }
+
+    #[tokio::test]
+    async fn test_shallow_health_reports_backends_and_agents() {
+        let state = state_with(MockBackend::new("mock"));
+
+        let (status, health) = check(&state, false).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(health.status, "ok");
+        assert_eq!(health.backends.len(), ModelRole::ALL.len());
+        assert!(health.backends.iter().all(|b| b.ready && b.critical));
+        assert_eq!(health.agents.len(), 1);
+        assert_eq!(health.agents[0].domain, "code");
+        assert!(health.agents[0].enabled);
+    }
+
+    #[tokio::test]
+    async fn test_deep_health_calls_backend() {
+        let mut backend = MockBackend::new("mock");
+        for _ in ModelRole::ALL {
+            backend = backend.with_response("pong");
+        }
+        let (status, health) = check(&state_with(backend), true).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(health.backends.iter().all(|b| b.ready));
+
+        // Shallow checks pass, but the backend cannot actually answer
+        let state = state_with(MockBackend::new("mock"));
+        assert_eq!(check(&state, false).await.0, StatusCode::OK);
+        let (status, health) = check(&state, true).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(health.status, "unavailable");
+        assert!(health
+            .backends
+            .iter()
+            .all(|b| !b.ready && b.detail.as_deref().unwrap().contains("no response queued")));
+    }
+
+    #[tokio::test]
+    async fn test_deep_health_reuses_recent_probe() {
+        let backend = Arc::new(MockBackend::new("mock"));
+        for _ in ModelRole::ALL {
+            backend.push_response("pong");
+        }
+        let agent = Arc::new(CodeAgent::new()) as Arc<dyn Agent>;
+        let state = AppState::new(Controller::new(vec![agent]))
+            .with_models(Arc::new(Arc::clone(&backend).registry("code")));
+
+        let (first, second) = tokio::join!(check(&state, true), check(&state, true));
+        assert_eq!(first.0, StatusCode::OK);
+        assert_eq!(second.0, StatusCode::OK);
+        assert_eq!(backend.requests().len(), ModelRole::ALL.len());
+
+        // Once the result expires the backends are probed again
+        let state = state.with_deep_check_ttl(Duration::ZERO);
+        assert_eq!(check(&state, true).await.0, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(backend.requests().len(), 2 * ModelRole::ALL.len());
+    }
+
+    #[tokio::test]
+    async fn test_missing_backend_outside_agent_domains_is_degraded() {
+        let agent = Arc::new(CodeAgent::new()) as Arc<dyn Agent>;
+        let registry = MockBackend::new("mock").into_registry("mail");
+        let manifest = registry.manifest().clone();
+        let state = AppState::new(Controller::new(vec![agent])).with_models(Arc::new(
+            ModelRegistry::from_manifest(manifest, "/nonexistent"),
+        ));
+
+        let (status, health) = check(&state, false).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(health.status, "degraded");
+        assert!(health.backends.iter().all(|b| !b.critical && !b.ready));
+    }
+}
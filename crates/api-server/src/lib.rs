@@ -1,10 +1,16 @@
 /// Bodhya API Server
 ///
 /// Provides REST and WebSocket APIs for task submission and monitoring
+pub mod chat;
+pub mod events;
+pub mod health;
 pub mod middleware;
 pub mod models;
+pub mod openapi;
+pub mod queue;
 pub mod routes;
 pub mod state;
+pub mod webhook;
 pub mod websocket;
 
 pub use models::*;
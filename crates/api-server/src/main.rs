@@ -1,60 +1,115 @@
 /// Bodhya API Server - Main Entry Point
-use axum::{
-    routing::{get, post},
-    Router,
-};
 use bodhya_agent_code::CodeAgent;
 use bodhya_agent_mail::MailAgent;
-use bodhya_api_server::{middleware, routes, state::AppState, websocket};
+use bodhya_api_server::{
+    middleware::CorsConfig, routes, state::AppState, websocket::BackpressureConfig,
+};
 use bodhya_controller::Controller;
-use bodhya_core::Agent;
+use bodhya_core::{Agent, AppConfig, LogFormat, Logging};
+use bodhya_model_registry::{ModelRegistry, SharedRegistry};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tower::ServiceBuilder;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "bodhya_api_server=info,tower_http=info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    Logging::new(
+        LogFormat::resolve(log_format_arg()),
+        "bodhya_api_server=info,tower_http=info",
+    )
+    .init();
 
     tracing::info!("Starting Bodhya API Server");
 
+    // One model registry serves the agents, the preload, `/health` and
+    // `/admin/reload`
+    let models = load_models().await;
+
     // Create agents
-    let code_agent = Arc::new(CodeAgent::new()) as Arc<dyn Agent>;
-    let mail_agent = Arc::new(MailAgent::new()) as Arc<dyn Agent>;
+    let (code_agent, mail_agent) = match &models {
+        Some(registry) => (
+            Arc::new(CodeAgent::with_shared_registry(registry.clone())) as Arc<dyn Agent>,
+            Arc::new(MailAgent::with_shared_registry(registry.clone())) as Arc<dyn Agent>,
+        ),
+        None => (
+            Arc::new(CodeAgent::new()) as Arc<dyn Agent>,
+            Arc::new(MailAgent::new()) as Arc<dyn Agent>,
+        ),
+    };
 
     tracing::info!("Initialized agents: code, mail");
 
-    // Create controller
-    let controller = Controller::new(vec![code_agent, mail_agent]);
+    // Create controller, starting the MCP servers the config lists
+    let config = load_config()?;
+    let controller = Controller::with_config_and_mcp(config, vec![code_agent, mail_agent]).await?;
 
     // Create application state
-    let state = Arc::new(AppState::new(controller));
+    let mut state = AppState::new(controller);
+    if let Some(max_batch_size) = std::env::var("BODHYA_MAX_BATCH_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+    {
+        state = state.with_max_batch_size(max_batch_size);
+    }
+    if let Some(workers) = std::env::var("BODHYA_WORKERS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+    {
+        state = state.with_workers(workers);
+    }
+    let task_timeout = std::env::var("BODHYA_TASK_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map_or(state.task_timeout, std::time::Duration::from_secs);
+    let metadata_timeout = std::env::var("BODHYA_METADATA_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map_or(state.metadata_timeout, std::time::Duration::from_secs);
+    state = state.with_route_timeouts(task_timeout, metadata_timeout);
+    if let Some(ttl) = std::env::var("BODHYA_DEEP_CHECK_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+    {
+        state = state.with_deep_check_ttl(std::time::Duration::from_secs(ttl));
+    }
+    if let Ok(origins) = std::env::var("BODHYA_CORS_ORIGINS") {
+        let origins: Vec<_> = origins
+            .split(',')
+            .map(str::trim)
+            .filter(|o| !o.is_empty())
+            .collect();
+        state = state.with_cors(CorsConfig::default().with_origins(origins));
+    }
+    let mut backpressure = BackpressureConfig::default();
+    if let Some(buffer) = std::env::var("BODHYA_WS_BUFFER")
+        .ok()
+        .and_then(|v| v.parse().ok())
+    {
+        backpressure.buffer = buffer;
+    }
+    if let Ok(policy) = std::env::var("BODHYA_WS_ON_LAG") {
+        match policy.parse() {
+            Ok(on_lag) => backpressure.on_lag = on_lag,
+            Err(e) => tracing::warn!("Ignoring BODHYA_WS_ON_LAG: {}", e),
+        }
+    }
+    state = state.with_ws_backpressure(backpressure);
+    if let Some(registry) = models {
+        state = state.with_models(registry);
+    }
+    if let Some(allow) = std::env::var("BODHYA_ALLOW_PRIVATE_CALLBACKS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+    {
+        state = state.with_private_callbacks(allow);
+    }
+    if let Ok(token) = std::env::var("BODHYA_ADMIN_TOKEN") {
+        state = state.with_admin_token(token);
+    }
+    let state = Arc::new(state);
 
     // Build router
-    let app = Router::new()
-        // REST API routes
-        .route("/health", get(routes::health_check))
-        .route("/agents", get(routes::list_agents))
-        .route("/tasks", post(routes::submit_task))
-        .route("/tasks/:id", get(routes::get_task_status))
-        .route("/tasks/:id/result", get(routes::get_task_result))
-        // WebSocket route
-        .route("/ws/tasks/:id", get(websocket::ws_handler))
-        // Add state and middleware
-        .with_state(state)
-        .layer(
-            ServiceBuilder::new()
-                .layer(middleware::trace_layer())
-                .layer(middleware::cors_layer()),
-        );
+    let app = routes::router(state);
 
     // Bind address
     let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
@@ -66,3 +121,64 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Model registry from `BODHYA_MODELS_MANIFEST`, with a local backend for
+/// each local model and the installed ones loaded
+///
+/// Without a manifest (or if it can't be read) the agents run without models.
+async fn load_models() -> Option<SharedRegistry> {
+    let manifest = std::path::PathBuf::from(std::env::var("BODHYA_MODELS_MANIFEST").ok()?);
+    let models_dir = std::env::var("BODHYA_MODELS_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| manifest.with_file_name("models"));
+    let mut registry = match ModelRegistry::from_manifest_file(&manifest, models_dir) {
+        Ok(registry) => registry,
+        Err(e) => {
+            tracing::warn!("Agents will run without models: {}", e);
+            return None;
+        }
+    };
+    registry.register_local_backends();
+
+    // Load models now so the first task does not pay for it
+    for (model_id, outcome) in registry.preload_all_installed().await {
+        match outcome {
+            Ok(()) => tracing::info!("Preloaded model {}", model_id),
+            Err(e) => tracing::warn!("Failed to preload model {}: {}", model_id, e),
+        }
+    }
+    Some(SharedRegistry::new(Arc::new(registry)))
+}
+
+/// Config from `BODHYA_CONFIG`, else `~/.bodhya/config/default.yaml` if it
+/// exists (as written by `bodhya init`), else the defaults
+fn load_config() -> anyhow::Result<AppConfig> {
+    if let Ok(path) = std::env::var("BODHYA_CONFIG") {
+        return Ok(AppConfig::from_file(path)?);
+    }
+    match home::home_dir().map(|home| home.join(".bodhya/config/default.yaml")) {
+        Some(path) if path.exists() => {
+            tracing::info!("Using config {}", path.display());
+            Ok(AppConfig::from_file(path)?)
+        }
+        _ => Ok(AppConfig::default()),
+    }
+}
+
+/// Value of `--log-format <FORMAT>` (or `--log-format=<FORMAT>`), if given
+fn log_format_arg() -> Option<LogFormat> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--log-format") {
+            Some("") => args.next(),
+            Some(rest) => rest.strip_prefix('=').map(str::to_string),
+            None => continue,
+        };
+        match value.as_deref().map(str::parse) {
+            Some(Ok(format)) => return Some(format),
+            Some(Err(e)) => eprintln!("Ignoring --log-format: {}", e),
+            None => eprintln!("Ignoring --log-format: missing value"),
+        }
+    }
+    None
+}
@@ -1,14 +1,245 @@
-/// Middleware for CORS, logging, and error handling
-use tower_http::cors::{Any, CorsLayer};
+/// Middleware for CORS, logging, timeouts, admin auth, and error handling
+use crate::models::ErrorResponse;
+use crate::state::AppState;
+use axum::error_handling::HandleErrorLayer;
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderName, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::{BoxError, Json, Router};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceBuilder;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, Any, CorsLayer};
 use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
 use tracing::Level;
 
-/// Create CORS layer with permissive defaults
-pub fn cors_layer() -> CorsLayer {
+/// Allowed CORS origins, methods, and headers
+///
+/// Origins are exact (`https://ui.example.com`), `*` for any origin, or end
+/// in `:*` to allow any port (`http://localhost:*`). Methods and headers
+/// accept `*` for any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    /// Send `Access-Control-Allow-Credentials: true`
+    pub allow_credentials: bool,
+}
+
+impl Default for CorsConfig {
+    /// Any method and header from localhost on any port
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec![
+                "http://localhost:*".to_string(),
+                "http://127.0.0.1:*".to_string(),
+            ],
+            allowed_methods: vec!["*".to_string()],
+            allowed_headers: vec!["*".to_string()],
+            allow_credentials: false,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Restrict to the given origins
+    pub fn with_origins<I, S>(mut self, origins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_origins = origins.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Restrict to the given methods
+    pub fn with_methods<I, S>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_methods = methods.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Restrict to the given request headers
+    pub fn with_headers<I, S>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_headers = headers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Allow credentialed requests
+    pub fn with_credentials(mut self, allow_credentials: bool) -> Self {
+        self.allow_credentials = allow_credentials;
+        self
+    }
+
+    /// Whether `origin` is allowed by this configuration
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|pattern| origin_matches(pattern, origin))
+    }
+}
+
+/// Match an origin against an exact, `*`, or `scheme://host:*` pattern
+fn origin_matches(pattern: &str, origin: &str) -> bool {
+    if pattern == "*" || pattern == origin {
+        return true;
+    }
+
+    match pattern.strip_suffix(":*") {
+        Some(prefix) => match origin.strip_prefix(prefix) {
+            Some("") => true,
+            Some(rest) => rest
+                .strip_prefix(':')
+                .is_some_and(|port| !port.is_empty() && port.chars().all(|c| c.is_ascii_digit())),
+            None => false,
+        },
+        None => false,
+    }
+}
+
+fn is_wildcard(values: &[String]) -> bool {
+    values.iter().any(|v| v == "*")
+}
+
+/// Create the CORS layer from configuration
+///
+/// Browsers reject `Access-Control-Allow-Origin: *` on credentialed requests,
+/// so with credentials enabled wildcards are answered by echoing the
+/// request's origin, method, and headers instead.
+pub fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let credentials = config.allow_credentials;
+
+    let origin = if is_wildcard(&config.allowed_origins) && !credentials {
+        AllowOrigin::from(Any)
+    } else {
+        let config = config.clone();
+        AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+            origin
+                .to_str()
+                .map(|origin| config.allows_origin(origin))
+                .unwrap_or(false)
+        })
+    };
+
+    let methods = if is_wildcard(&config.allowed_methods) {
+        if credentials {
+            AllowMethods::mirror_request()
+        } else {
+            AllowMethods::from(Any)
+        }
+    } else {
+        AllowMethods::list(
+            config
+                .allowed_methods
+                .iter()
+                .filter_map(|m| Method::from_bytes(m.to_uppercase().as_bytes()).ok()),
+        )
+    };
+
+    let headers = if is_wildcard(&config.allowed_headers) {
+        if credentials {
+            AllowHeaders::mirror_request()
+        } else {
+            AllowHeaders::from(Any)
+        }
+    } else {
+        AllowHeaders::list(
+            config
+                .allowed_headers
+                .iter()
+                .filter_map(|h| HeaderName::from_bytes(h.to_lowercase().as_bytes()).ok()),
+        )
+    };
+
     CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any)
+        .allow_origin(origin)
+        .allow_methods(methods)
+        .allow_headers(headers)
+        .allow_credentials(credentials)
+}
+
+/// Fail requests to `router` that take longer than `timeout` with 504
+///
+/// Only the time until the response starts counts, so a streamed body can
+/// outlast the timeout.
+pub fn with_timeout<S>(router: Router<S>, timeout: Duration) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router.layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(move |error: BoxError| async move {
+                timeout_error(error, timeout)
+            }))
+            .timeout(timeout),
+    )
+}
+
+fn timeout_error(error: BoxError, timeout: Duration) -> Response {
+    if error.is::<tower::timeout::error::Elapsed>() {
+        let message = format!("Request timed out after {:?}", timeout);
+        (
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(ErrorResponse::new(message)),
+        )
+            .into_response()
+    } else {
+        let message = format!("Unhandled internal error: {}", error);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(message)),
+        )
+            .into_response()
+    }
+}
+
+/// Refuse requests without the admin bearer token
+///
+/// Admin routes are disabled (403) unless the state has an admin token, and
+/// requests whose `Authorization` header does not carry it get 401.
+pub async fn require_admin(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(expected) = state.admin_token.as_deref() else {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::new(
+                "Admin routes are disabled: no admin token is configured",
+            )),
+        )
+            .into_response();
+    };
+    let given = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match given {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse::new("Missing or invalid admin token")),
+        )
+            .into_response(),
+    }
+}
+
+/// Compare without returning early, so timing doesn't reveal the token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
 }
 
 /// Create tracing/logging layer
@@ -26,10 +257,127 @@ mod tests {
 
     #[test]
     fn test_cors_layer_creation() {
-        let _layer = cors_layer();
+        let _layer = cors_layer(&CorsConfig::default());
         // Just ensure it can be created without panic
     }
 
+    #[test]
+    fn test_cors_layer_wildcard_with_credentials() {
+        // Wildcards with credentials must not panic (tower-http rejects `Any` there)
+        let config = CorsConfig::default()
+            .with_origins(["*"])
+            .with_credentials(true);
+        let _layer = cors_layer(&config);
+    }
+
+    #[test]
+    fn test_origin_matching() {
+        let config = CorsConfig::default();
+        assert!(config.allows_origin("http://localhost:5173"));
+        assert!(config.allows_origin("http://127.0.0.1:8080"));
+        assert!(config.allows_origin("http://localhost"));
+        assert!(!config.allows_origin("http://localhost.evil.com"));
+        assert!(!config.allows_origin("https://example.com"));
+
+        let config = CorsConfig::default().with_origins(["https://ui.example.com"]);
+        assert!(config.allows_origin("https://ui.example.com"));
+        assert!(!config.allows_origin("http://localhost:3000"));
+
+        assert!(CorsConfig::default()
+            .with_origins(["*"])
+            .allows_origin("https://anything.test"));
+    }
+
+    /// Serve a trivial route behind the CORS layer and return its URL
+    async fn spawn_cors_server(config: CorsConfig) -> String {
+        let app = axum::Router::new()
+            .route("/ping", axum::routing::get(|| async { "pong" }))
+            .layer(cors_layer(&config));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{}/ping", addr)
+    }
+
+    #[tokio::test]
+    async fn test_configured_origin_is_reflected() {
+        let config = CorsConfig::default()
+            .with_origins(["https://ui.example.com"])
+            .with_credentials(true);
+        let url = spawn_cors_server(config).await;
+        let client = reqwest::Client::new();
+
+        let allowed = client
+            .get(&url)
+            .header("Origin", "https://ui.example.com")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            allowed.headers()["access-control-allow-origin"],
+            "https://ui.example.com"
+        );
+        assert_eq!(
+            allowed.headers()["access-control-allow-credentials"],
+            "true"
+        );
+
+        let rejected = client
+            .get(&url)
+            .header("Origin", "https://evil.example.com")
+            .send()
+            .await
+            .unwrap();
+        assert!(rejected
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_origin_without_credentials() {
+        let url = spawn_cors_server(CorsConfig::default().with_origins(["*"])).await;
+
+        let response = reqwest::Client::new()
+            .get(&url)
+            .header("Origin", "https://anywhere.test")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["access-control-allow-origin"], "*");
+    }
+
+    #[tokio::test]
+    async fn test_slow_handler_times_out_with_504() {
+        let app = with_timeout(
+            axum::Router::new()
+                .route("/fast", axum::routing::get(|| async { "done" }))
+                .route(
+                    "/slow",
+                    axum::routing::get(|| async {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        "done"
+                    }),
+                ),
+            Duration::from_millis(100),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let fast = reqwest::get(format!("http://{}/fast", addr)).await.unwrap();
+        assert_eq!(fast.status().as_u16(), 200);
+
+        let slow = reqwest::get(format!("http://{}/slow", addr)).await.unwrap();
+        assert_eq!(slow.status().as_u16(), 504);
+        let body: ErrorResponse = serde_json::from_str(&slow.text().await.unwrap()).unwrap();
+        assert!(body.error.contains("timed out"));
+    }
+
     #[test]
     fn test_trace_layer_creation() {
         let _layer = trace_layer();
@@ -1,5 +1,5 @@
 /// API request and response models
-use bodhya_core::AgentCapability;
+use bodhya_core::{AgentCapability, EngagementMode, ModelRole, TaskPriority};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +16,14 @@ pub struct SubmitTaskRequest {
     /// Optional structured payload
     #[serde(default)]
     pub payload: serde_json::Value,
+
+    /// Optional http(s) URL that receives the result when the task finishes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+
+    /// Scheduling priority (default: normal)
+    #[serde(default)]
+    pub priority: TaskPriority,
 }
 
 /// Response when task is submitted
@@ -31,6 +39,30 @@ pub struct SubmitTaskResponse {
     pub created_at: DateTime<Utc>,
 }
 
+/// Response when a batch of tasks is submitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchSubmitResponse {
+    /// Assigned task IDs, in the same order as the submitted tasks
+    pub task_ids: Vec<String>,
+}
+
+/// Validation error for one element of a batch submission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchItemError {
+    /// Index of the invalid task in the submitted array
+    pub index: usize,
+
+    /// Why the task was rejected
+    pub error: String,
+}
+
+/// Error response for a rejected batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchErrorResponse {
+    pub error: String,
+    pub items: Vec<BatchItemError>,
+}
+
 /// Task status enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -70,6 +102,10 @@ pub struct TaskInfo {
     /// Task description
     pub description: String,
 
+    /// Scheduling priority
+    #[serde(default)]
+    pub priority: TaskPriority,
+
     /// When task was created
     pub created_at: DateTime<Utc>,
 
@@ -84,6 +120,14 @@ pub struct TaskInfo {
     /// Progress percentage (0-100)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub progress: Option<u8>,
+
+    /// Pipeline phase the task is currently in (e.g., "planning")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+
+    /// When the task last reported activity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_activity: Option<DateTime<Utc>>,
 }
 
 /// Task result
@@ -151,9 +195,79 @@ pub struct AgentList {
 /// Health check response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthResponse {
+    /// `ok`, `degraded` (a non-critical backend is down), or `unavailable`
     pub status: String,
     pub version: String,
     pub uptime_seconds: u64,
+
+    /// Readiness of each model backend
+    #[serde(default)]
+    pub backends: Vec<BackendHealth>,
+
+    /// Whether each agent is enabled
+    #[serde(default)]
+    pub agents: Vec<AgentHealth>,
+}
+
+/// Readiness of one model backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendHealth {
+    pub model_id: String,
+
+    /// Backend kind from the manifest (e.g., "local", "remote")
+    pub backend: String,
+
+    pub domain: String,
+
+    /// Whether an enabled agent depends on this model
+    pub critical: bool,
+
+    pub ready: bool,
+
+    /// Why the backend is not ready
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Whether an agent is enabled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentHealth {
+    pub id: String,
+    pub domain: String,
+    pub enabled: bool,
+}
+
+/// Response from `POST /admin/reload`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadResponse {
+    /// Models listed in the reloaded manifest
+    pub models: usize,
+    /// How many of them are installed
+    pub installed: usize,
+}
+
+/// Model the registry would pick, from `GET /models/select`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSelection {
+    pub model_id: String,
+    pub display_name: String,
+    pub role: ModelRole,
+    pub domain: String,
+    /// Backend type (local, remote)
+    pub backend: String,
+    /// Whether the model file is present
+    pub installed: bool,
+    pub model_path: String,
+}
+
+/// Error response when the engagement mode rules out model selection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngagementErrorResponse {
+    pub error: String,
+    /// Mode the selection was asked for
+    pub requested_mode: EngagementMode,
+    /// Mode models can be selected in
+    pub required_mode: EngagementMode,
 }
 
 /// Error response
@@ -180,6 +294,92 @@ impl ErrorResponse {
     }
 }
 
+/// A message in a chat conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// "system", "user", or "assistant"
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+/// Request to `POST /v1/chat/completions`, in the OpenAI chat completion shape
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionRequest {
+    /// Domain of the agent to use (e.g., "code"); other values route by the
+    /// conversation like a task without a domain hint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Conversation so far; the last user message is the task
+    pub messages: Vec<ChatMessage>,
+
+    /// Stream the reply as server-sent `chat.completion.chunk` events
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// Non-streaming response from `POST /v1/chat/completions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletion {
+    /// "chatcmpl-" followed by the task ID
+    pub id: String,
+    /// Always "chat.completion"
+    pub object: String,
+    /// Unix timestamp (seconds) of the request
+    pub created: i64,
+    /// ID of the agent that handled the task
+    pub model: String,
+    pub choices: Vec<ChatChoice>,
+}
+
+/// The reply of a non-streaming chat completion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatChoice {
+    pub index: u32,
+    pub message: ChatMessage,
+    pub finish_reason: String,
+}
+
+/// One server-sent event of a streaming chat completion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChunk {
+    /// Same for every chunk of one completion
+    pub id: String,
+    /// Always "chat.completion.chunk"
+    pub object: String,
+    pub created: i64,
+    /// ID of the agent that handled the task
+    pub model: String,
+    pub choices: Vec<ChatChunkChoice>,
+}
+
+/// The part of the reply carried by one chunk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatChunkChoice {
+    pub index: u32,
+    pub delta: ChatDelta,
+    /// "stop" on the last chunk, null before it
+    pub finish_reason: Option<String>,
+}
+
+/// Reply text added by a chunk; the first chunk carries only the role
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChatDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
 /// WebSocket message types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -192,6 +392,12 @@ pub enum WsMessage {
         progress: Option<u8>,
     },
 
+    /// Task entered a new pipeline phase (e.g., "planning")
+    TaskPhase {
+        task_id: String,
+        phase: String,
+    },
+
     /// Task output chunk (streaming)
     TaskOutput {
         task_id: String,
@@ -208,6 +414,12 @@ pub enum WsMessage {
         error: Option<String>,
     },
 
+    /// Events after `since` are no longer buffered and were skipped
+    EventsMissed {
+        task_id: String,
+        since: u64,
+    },
+
     /// Error occurred
     Error {
         message: String,
@@ -218,6 +430,24 @@ pub enum WsMessage {
     Pong,
 }
 
+/// A task event with its sequence number, as sent to WebSocket clients
+///
+/// Sequence numbers increase by one per event of a task; reconnect with
+/// `?since=<seq>` to receive the events after `seq`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedMessage {
+    pub seq: u64,
+    #[serde(flatten)]
+    pub message: WsMessage,
+}
+
+impl SequencedMessage {
+    /// Whether this is the task's final `task_complete` event
+    pub fn is_complete(&self) -> bool {
+        matches!(self.message, WsMessage::TaskComplete { .. })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
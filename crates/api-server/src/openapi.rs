@@ -0,0 +1,842 @@
+/// OpenAPI document generation
+///
+/// The spec served at `GET /openapi.json` is assembled from the `ApiSchema`
+/// implementations below, one per model in `models.rs`. Tests check that
+/// every serialized field of each model is declared in its schema, so the
+/// spec fails loudly when a model changes without its schema.
+use crate::models::{
+    AgentHealth, AgentInfo, AgentList, BackendHealth, BatchErrorResponse, BatchItemError,
+    BatchSubmitResponse, ChatChoice, ChatCompletion, ChatCompletionRequest, ChatMessage,
+    EngagementErrorResponse, ErrorResponse, HealthResponse, ModelSelection, ReloadResponse,
+    SubmitTaskRequest, SubmitTaskResponse, TaskInfo, TaskResult, TaskStatus,
+};
+use serde_json::{json, Value};
+
+/// A model with an OpenAPI component schema
+pub trait ApiSchema {
+    /// Component name under `#/components/schemas`
+    const NAME: &'static str;
+
+    /// OpenAPI schema object for this model
+    fn schema() -> Value;
+
+    /// `$ref` pointing at this model's component schema
+    fn reference() -> Value {
+        json!({ "$ref": format!("#/components/schemas/{}", Self::NAME) })
+    }
+}
+
+impl ApiSchema for SubmitTaskRequest {
+    const NAME: &'static str = "SubmitTaskRequest";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["description"],
+            "properties": {
+                "domain": {
+                    "type": "string",
+                    "nullable": true,
+                    "description": "Optional domain hint for routing"
+                },
+                "description": { "type": "string", "description": "Task description" },
+                "payload": {
+                    "type": "object",
+                    "nullable": true,
+                    "description": "Optional structured payload"
+                },
+                "priority": {
+                    "type": "string",
+                    "enum": ["low", "normal", "high"],
+                    "default": "normal",
+                    "description": "Scheduling priority; higher-priority tasks run first"
+                },
+                "callback_url": {
+                    "type": "string",
+                    "format": "uri",
+                    "nullable": true,
+                    "description": "http(s) URL that receives the AgentResult JSON when the task finishes; private network hosts are refused unless the server allows them"
+                }
+            }
+        })
+    }
+}
+
+impl ApiSchema for SubmitTaskResponse {
+    const NAME: &'static str = "SubmitTaskResponse";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["task_id", "status", "created_at"],
+            "properties": {
+                "task_id": { "type": "string" },
+                "status": TaskStatus::reference(),
+                "created_at": { "type": "string", "format": "date-time" }
+            }
+        })
+    }
+}
+
+impl ApiSchema for BatchSubmitResponse {
+    const NAME: &'static str = "BatchSubmitResponse";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["task_ids"],
+            "properties": {
+                "task_ids": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Task IDs in submission order"
+                }
+            }
+        })
+    }
+}
+
+impl ApiSchema for BatchItemError {
+    const NAME: &'static str = "BatchItemError";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["index", "error"],
+            "properties": {
+                "index": { "type": "integer", "minimum": 0 },
+                "error": { "type": "string" }
+            }
+        })
+    }
+}
+
+impl ApiSchema for BatchErrorResponse {
+    const NAME: &'static str = "BatchErrorResponse";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["error", "items"],
+            "properties": {
+                "error": { "type": "string" },
+                "items": { "type": "array", "items": BatchItemError::reference() }
+            }
+        })
+    }
+}
+
+impl ApiSchema for TaskStatus {
+    const NAME: &'static str = "TaskStatus";
+
+    fn schema() -> Value {
+        json!({
+            "type": "string",
+            "enum": ["pending", "in_progress", "completed", "failed"]
+        })
+    }
+}
+
+impl ApiSchema for TaskInfo {
+    const NAME: &'static str = "TaskInfo";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["task_id", "status", "description", "created_at"],
+            "properties": {
+                "task_id": { "type": "string" },
+                "status": TaskStatus::reference(),
+                "domain": { "type": "string", "nullable": true },
+                "description": { "type": "string" },
+                "priority": { "type": "string", "enum": ["low", "normal", "high"] },
+                "created_at": { "type": "string", "format": "date-time" },
+                "started_at": { "type": "string", "format": "date-time", "nullable": true },
+                "completed_at": { "type": "string", "format": "date-time", "nullable": true },
+                "progress": { "type": "integer", "minimum": 0, "maximum": 100, "nullable": true },
+                "phase": { "type": "string", "nullable": true },
+                "last_activity": { "type": "string", "format": "date-time", "nullable": true }
+            }
+        })
+    }
+}
+
+impl ApiSchema for TaskResult {
+    const NAME: &'static str = "TaskResult";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["task_id", "success", "completed_at"],
+            "properties": {
+                "task_id": { "type": "string" },
+                "success": { "type": "boolean" },
+                "content": {
+                    "type": "string",
+                    "nullable": true,
+                    "description": "Result content if successful"
+                },
+                "error": {
+                    "type": "string",
+                    "nullable": true,
+                    "description": "Error message if failed"
+                },
+                "metadata": { "type": "object", "nullable": true },
+                "completed_at": { "type": "string", "format": "date-time" }
+            }
+        })
+    }
+}
+
+impl ApiSchema for AgentInfo {
+    const NAME: &'static str = "AgentInfo";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "domain", "intents", "description", "enabled"],
+            "properties": {
+                "id": { "type": "string" },
+                "domain": { "type": "string" },
+                "intents": { "type": "array", "items": { "type": "string" } },
+                "description": { "type": "string" },
+                "enabled": { "type": "boolean" }
+            }
+        })
+    }
+}
+
+impl ApiSchema for AgentList {
+    const NAME: &'static str = "AgentList";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["agents"],
+            "properties": {
+                "agents": { "type": "array", "items": AgentInfo::reference() }
+            }
+        })
+    }
+}
+
+impl ApiSchema for HealthResponse {
+    const NAME: &'static str = "HealthResponse";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["status", "version", "uptime_seconds"],
+            "properties": {
+                "status": { "type": "string", "enum": ["ok", "degraded", "unavailable"] },
+                "version": { "type": "string" },
+                "uptime_seconds": { "type": "integer", "format": "int64" },
+                "backends": { "type": "array", "items": BackendHealth::reference() },
+                "agents": { "type": "array", "items": AgentHealth::reference() }
+            }
+        })
+    }
+}
+
+impl ApiSchema for BackendHealth {
+    const NAME: &'static str = "BackendHealth";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["model_id", "backend", "domain", "critical", "ready"],
+            "properties": {
+                "model_id": { "type": "string" },
+                "backend": { "type": "string", "description": "Backend kind from the model manifest" },
+                "domain": { "type": "string" },
+                "critical": {
+                    "type": "boolean",
+                    "description": "Whether an enabled agent depends on this model"
+                },
+                "ready": { "type": "boolean" },
+                "detail": { "type": "string", "description": "Why the backend is not ready" }
+            }
+        })
+    }
+}
+
+impl ApiSchema for AgentHealth {
+    const NAME: &'static str = "AgentHealth";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "domain", "enabled"],
+            "properties": {
+                "id": { "type": "string" },
+                "domain": { "type": "string" },
+                "enabled": { "type": "boolean" }
+            }
+        })
+    }
+}
+
+impl ApiSchema for ReloadResponse {
+    const NAME: &'static str = "ReloadResponse";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["models", "installed"],
+            "properties": {
+                "models": { "type": "integer", "description": "Models listed in the manifest" },
+                "installed": { "type": "integer", "description": "Models installed on disk" }
+            }
+        })
+    }
+}
+
+impl ApiSchema for ModelSelection {
+    const NAME: &'static str = "ModelSelection";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["model_id", "display_name", "role", "domain", "backend", "installed", "model_path"],
+            "properties": {
+                "model_id": { "type": "string" },
+                "display_name": { "type": "string" },
+                "role": { "type": "string", "example": "coder" },
+                "domain": { "type": "string" },
+                "backend": { "type": "string", "description": "Backend type (local, remote)" },
+                "installed": { "type": "boolean", "description": "Whether the model file is present" },
+                "model_path": { "type": "string" }
+            }
+        })
+    }
+}
+
+impl ApiSchema for EngagementErrorResponse {
+    const NAME: &'static str = "EngagementErrorResponse";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["error", "requested_mode", "required_mode"],
+            "properties": {
+                "error": { "type": "string" },
+                "requested_mode": { "type": "string", "enum": ["minimum", "medium", "maximum"] },
+                "required_mode": { "type": "string", "enum": ["minimum", "medium", "maximum"] }
+            }
+        })
+    }
+}
+
+impl ApiSchema for ChatMessage {
+    const NAME: &'static str = "ChatMessage";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["role", "content"],
+            "properties": {
+                "role": { "type": "string", "enum": ["system", "user", "assistant"] },
+                "content": { "type": "string" }
+            }
+        })
+    }
+}
+
+impl ApiSchema for ChatCompletionRequest {
+    const NAME: &'static str = "ChatCompletionRequest";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["messages"],
+            "properties": {
+                "model": {
+                    "type": "string",
+                    "nullable": true,
+                    "description": "Agent domain to route to; other values route by the conversation"
+                },
+                "messages": {
+                    "type": "array",
+                    "items": ChatMessage::reference(),
+                    "description": "Conversation so far; the last user message is the task"
+                },
+                "stream": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Stream the reply as server-sent chat.completion.chunk events"
+                }
+            }
+        })
+    }
+}
+
+impl ApiSchema for ChatChoice {
+    const NAME: &'static str = "ChatChoice";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["index", "message", "finish_reason"],
+            "properties": {
+                "index": { "type": "integer" },
+                "message": ChatMessage::reference(),
+                "finish_reason": { "type": "string" }
+            }
+        })
+    }
+}
+
+impl ApiSchema for ChatCompletion {
+    const NAME: &'static str = "ChatCompletion";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "object", "created", "model", "choices"],
+            "properties": {
+                "id": { "type": "string" },
+                "object": { "type": "string", "enum": ["chat.completion"] },
+                "created": { "type": "integer", "description": "Unix timestamp in seconds" },
+                "model": { "type": "string", "description": "ID of the agent that handled the task" },
+                "choices": { "type": "array", "items": ChatChoice::reference() }
+            }
+        })
+    }
+}
+
+impl ApiSchema for ErrorResponse {
+    const NAME: &'static str = "ErrorResponse";
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["error"],
+            "properties": {
+                "error": { "type": "string" },
+                "details": { "type": "string", "nullable": true }
+            }
+        })
+    }
+}
+
+/// Insert a model's schema into the components map
+fn register<T: ApiSchema>(schemas: &mut serde_json::Map<String, Value>) {
+    schemas.insert(T::NAME.to_string(), T::schema());
+}
+
+/// JSON response body referencing a component schema
+fn json_content(schema: Value) -> Value {
+    json!({ "application/json": { "schema": schema } })
+}
+
+/// Response object with a description and JSON body
+fn response(description: &str, schema: Value) -> Value {
+    json!({ "description": description, "content": json_content(schema) })
+}
+
+/// The `task_id` path parameter
+fn task_id_parameter() -> Value {
+    json!({
+        "name": "id",
+        "in": "path",
+        "required": true,
+        "description": "Unique task identifier",
+        "schema": { "type": "string" }
+    })
+}
+
+/// Build the complete OpenAPI 3 document for the REST API
+pub fn openapi_document() -> Value {
+    let mut schemas = serde_json::Map::new();
+    register::<SubmitTaskRequest>(&mut schemas);
+    register::<SubmitTaskResponse>(&mut schemas);
+    register::<BatchSubmitResponse>(&mut schemas);
+    register::<BatchItemError>(&mut schemas);
+    register::<BatchErrorResponse>(&mut schemas);
+    register::<TaskStatus>(&mut schemas);
+    register::<TaskInfo>(&mut schemas);
+    register::<TaskResult>(&mut schemas);
+    register::<AgentInfo>(&mut schemas);
+    register::<AgentList>(&mut schemas);
+    register::<HealthResponse>(&mut schemas);
+    register::<BackendHealth>(&mut schemas);
+    register::<AgentHealth>(&mut schemas);
+    register::<ReloadResponse>(&mut schemas);
+    register::<ModelSelection>(&mut schemas);
+    register::<EngagementErrorResponse>(&mut schemas);
+    register::<ChatMessage>(&mut schemas);
+    register::<ChatCompletionRequest>(&mut schemas);
+    register::<ChatChoice>(&mut schemas);
+    register::<ChatCompletion>(&mut schemas);
+    register::<ErrorResponse>(&mut schemas);
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Bodhya API",
+            "description": "REST API for Bodhya - Local-first Multi-Agent AI Platform",
+            "version": env!("CARGO_PKG_VERSION")
+        },
+        "paths": {
+            "/health": {
+                "get": {
+                    "summary": "Health check",
+                    "tags": ["Health"],
+                    "parameters": [{
+                        "name": "deep",
+                        "in": "query",
+                        "required": false,
+                        "description": "Ask each model backend for a tiny completion",
+                        "schema": { "type": "boolean", "default": false }
+                    }],
+                    "responses": {
+                        "200": response("Server is healthy", HealthResponse::reference()),
+                        "503": response(
+                            "A backend an enabled agent depends on is not ready",
+                            HealthResponse::reference()
+                        )
+                    }
+                }
+            },
+            "/admin/reload": {
+                "post": {
+                    "summary": "Reload the model manifest",
+                    "description": "Re-read the manifest and installed models; the agents use them from their next task, running tasks keep the previous registry. Requires the admin bearer token.",
+                    "tags": ["Admin"],
+                    "security": [{ "adminToken": [] }],
+                    "responses": {
+                        "200": response("Models reloaded", ReloadResponse::reference()),
+                        "401": response("Missing or invalid admin token", ErrorResponse::reference()),
+                        "403": response("Admin routes are disabled", ErrorResponse::reference()),
+                        "404": response("No model registry is configured", ErrorResponse::reference()),
+                        "500": response("The manifest could not be loaded", ErrorResponse::reference())
+                    }
+                }
+            },
+            "/agents": {
+                "get": {
+                    "summary": "List available agents",
+                    "tags": ["Agents"],
+                    "responses": {
+                        "200": response("List of agents", AgentList::reference())
+                    }
+                }
+            },
+            "/models/select": {
+                "get": {
+                    "summary": "Show which model would be selected",
+                    "description": "Resolves role, domain, and engagement mode through the model registry without running anything",
+                    "tags": ["Models"],
+                    "parameters": [
+                        {
+                            "name": "role",
+                            "in": "query",
+                            "required": true,
+                            "schema": { "type": "string", "example": "coder" }
+                        },
+                        {
+                            "name": "domain",
+                            "in": "query",
+                            "required": true,
+                            "schema": { "type": "string", "example": "code" }
+                        },
+                        {
+                            "name": "mode",
+                            "in": "query",
+                            "required": false,
+                            "schema": { "type": "string", "enum": ["minimum", "medium", "maximum"], "default": "minimum" }
+                        }
+                    ],
+                    "responses": {
+                        "200": response("Selected model", ModelSelection::reference()),
+                        "400": response("Invalid role or mode", ErrorResponse::reference()),
+                        "403": response(
+                            "The engagement mode rules out model selection",
+                            EngagementErrorResponse::reference()
+                        ),
+                        "404": response(
+                            "No matching model, or no model registry is configured",
+                            ErrorResponse::reference()
+                        )
+                    }
+                }
+            },
+            "/tasks": {
+                "post": {
+                    "summary": "Submit a new task",
+                    "tags": ["Tasks"],
+                    "parameters": [{
+                        "name": "Idempotency-Key",
+                        "in": "header",
+                        "required": false,
+                        "description": "Repeat submissions with the same key return the original task",
+                        "schema": { "type": "string" }
+                    }],
+                    "requestBody": {
+                        "required": true,
+                        "content": json_content(SubmitTaskRequest::reference())
+                    },
+                    "responses": {
+                        "200": response(
+                            "Task already created with this Idempotency-Key",
+                            SubmitTaskResponse::reference()
+                        ),
+                        "201": response("Task created", SubmitTaskResponse::reference()),
+                        "400": response("Invalid request", ErrorResponse::reference())
+                    }
+                }
+            },
+            "/tasks/batch": {
+                "post": {
+                    "summary": "Submit a batch of tasks",
+                    "tags": ["Tasks"],
+                    "requestBody": {
+                        "required": true,
+                        "content": json_content(json!({
+                            "type": "array",
+                            "items": SubmitTaskRequest::reference()
+                        }))
+                    },
+                    "responses": {
+                        "201": response("Tasks created", BatchSubmitResponse::reference()),
+                        "400": response(
+                            "Invalid batch (per-item errors when elements fail validation)",
+                            BatchErrorResponse::reference()
+                        )
+                    }
+                }
+            },
+            "/tasks/{id}": {
+                "get": {
+                    "summary": "Get task status",
+                    "tags": ["Tasks"],
+                    "parameters": [task_id_parameter()],
+                    "responses": {
+                        "200": response("Task information", TaskInfo::reference()),
+                        "404": response("Task not found", ErrorResponse::reference())
+                    }
+                }
+            },
+            "/v1/chat/completions": {
+                "post": {
+                    "summary": "OpenAI-compatible chat completion",
+                    "description": "Runs the last user message as a task; with stream=true the reply is sent as text/event-stream chat.completion.chunk events ending in [DONE]",
+                    "tags": ["Chat"],
+                    "requestBody": {
+                        "required": true,
+                        "content": json_content(ChatCompletionRequest::reference())
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Completion, or an event stream when stream=true",
+                            "content": {
+                                "application/json": { "schema": ChatCompletion::reference() },
+                                "text/event-stream": { "schema": { "type": "string" } }
+                            }
+                        },
+                        "400": response("No user message, or no agent can take the task", ErrorResponse::reference()),
+                        "500": response("The task failed", ErrorResponse::reference())
+                    }
+                }
+            },
+            "/tasks/{id}/result": {
+                "get": {
+                    "summary": "Get task result",
+                    "tags": ["Tasks"],
+                    "parameters": [task_id_parameter()],
+                    "responses": {
+                        "200": response("Task result", TaskResult::reference()),
+                        "400": response("Task not yet complete", ErrorResponse::reference()),
+                        "404": response("Task not found", ErrorResponse::reference())
+                    }
+                }
+            }
+        },
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": {
+                "adminToken": { "type": "http", "scheme": "bearer" }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bodhya_core::TaskPriority;
+    use chrono::Utc;
+    use serde::Serialize;
+
+    /// Every field `value` serializes must be declared in `T`'s schema
+    fn assert_fields_documented<T: ApiSchema + Serialize>(value: &T) {
+        let serialized = serde_json::to_value(value).unwrap();
+        let schema = T::schema();
+        let properties = schema["properties"].as_object().unwrap();
+
+        for key in serialized.as_object().unwrap().keys() {
+            assert!(
+                properties.contains_key(key),
+                "{} field '{}' missing from schema",
+                T::NAME,
+                key
+            );
+        }
+        for required in schema["required"].as_array().unwrap() {
+            assert!(
+                properties.contains_key(required.as_str().unwrap()),
+                "{} requires undeclared field {}",
+                T::NAME,
+                required
+            );
+        }
+    }
+
+    #[test]
+    fn test_schemas_cover_model_fields() {
+        let now = Utc::now();
+        assert_fields_documented(&SubmitTaskRequest {
+            domain: Some("code".to_string()),
+            description: "task".to_string(),
+            payload: json!({}),
+            callback_url: Some("http://localhost/hook".to_string()),
+            priority: TaskPriority::High,
+        });
+        assert_fields_documented(&SubmitTaskResponse {
+            task_id: "1".to_string(),
+            status: TaskStatus::Pending,
+            created_at: now,
+        });
+        assert_fields_documented(&TaskInfo {
+            task_id: "1".to_string(),
+            status: TaskStatus::Completed,
+            domain: Some("code".to_string()),
+            description: "task".to_string(),
+            priority: TaskPriority::Normal,
+            created_at: now,
+            started_at: Some(now),
+            completed_at: Some(now),
+            progress: Some(100),
+            phase: Some("review".to_string()),
+            last_activity: Some(now),
+        });
+        assert_fields_documented(&TaskResult {
+            task_id: "1".to_string(),
+            success: false,
+            content: Some("out".to_string()),
+            error: Some("err".to_string()),
+            metadata: json!({}),
+            completed_at: now,
+        });
+        assert_fields_documented(&BatchSubmitResponse {
+            task_ids: vec!["1".to_string()],
+        });
+        assert_fields_documented(&BatchItemError {
+            index: 0,
+            error: "invalid".to_string(),
+        });
+        assert_fields_documented(&BatchErrorResponse {
+            error: "invalid".to_string(),
+            items: vec![],
+        });
+        assert_fields_documented(&AgentList { agents: vec![] });
+        assert_fields_documented(&HealthResponse {
+            status: "ok".to_string(),
+            version: "0.1.0".to_string(),
+            uptime_seconds: 1,
+            backends: vec![],
+            agents: vec![],
+        });
+        assert_fields_documented(&BackendHealth {
+            model_id: "coder".to_string(),
+            backend: "local".to_string(),
+            domain: "code".to_string(),
+            critical: true,
+            ready: false,
+            detail: Some("model file not found".to_string()),
+        });
+        assert_fields_documented(&AgentHealth {
+            id: "code".to_string(),
+            domain: "code".to_string(),
+            enabled: true,
+        });
+        assert_fields_documented(&ReloadResponse {
+            models: 3,
+            installed: 1,
+        });
+        assert_fields_documented(&ModelSelection {
+            model_id: "code_coder".to_string(),
+            display_name: "Coder".to_string(),
+            role: bodhya_core::ModelRole::Coder,
+            domain: "code".to_string(),
+            backend: "local".to_string(),
+            installed: true,
+            model_path: "/models/code_coder.gguf".to_string(),
+        });
+        assert_fields_documented(&EngagementErrorResponse {
+            error: "gated".to_string(),
+            requested_mode: bodhya_core::EngagementMode::Medium,
+            required_mode: bodhya_core::EngagementMode::Minimum,
+        });
+        assert_fields_documented(&ChatCompletionRequest {
+            model: Some("code".to_string()),
+            messages: vec![ChatMessage::assistant("hi")],
+            stream: true,
+        });
+        assert_fields_documented(&ChatMessage::assistant("hi"));
+        assert_fields_documented(&ChatCompletion {
+            id: "chatcmpl-1".to_string(),
+            object: "chat.completion".to_string(),
+            created: 0,
+            model: "code".to_string(),
+            choices: vec![],
+        });
+        assert_fields_documented(&ChatChoice {
+            index: 0,
+            message: ChatMessage::assistant("hi"),
+            finish_reason: "stop".to_string(),
+        });
+        assert_fields_documented(&ErrorResponse::with_details("error", "details"));
+    }
+
+    #[test]
+    fn test_task_status_schema_matches_serde() {
+        let schema = TaskStatus::schema();
+        let variants: Vec<_> = schema["enum"].as_array().unwrap().iter().collect();
+        for status in [
+            TaskStatus::Pending,
+            TaskStatus::InProgress,
+            TaskStatus::Completed,
+            TaskStatus::Failed,
+        ] {
+            assert!(variants.contains(&&serde_json::to_value(status).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_all_references_resolve() {
+        let document = openapi_document();
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+
+        fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+            match value {
+                Value::Object(map) => {
+                    if let Some(Value::String(r)) = map.get("$ref") {
+                        refs.push(r.clone());
+                    }
+                    map.values().for_each(|v| collect_refs(v, refs));
+                }
+                Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+                _ => {}
+            }
+        }
+
+        let mut refs = Vec::new();
+        collect_refs(&document, &mut refs);
+        assert!(!refs.is_empty());
+        for r in refs {
+            let name = r.strip_prefix("#/components/schemas/").unwrap();
+            assert!(schemas.contains_key(name), "unresolved $ref {}", r);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Priority queue of tasks waiting for a worker
+//!
+//! Workers take the highest-priority task first; tasks of equal priority run
+//! in submission order.
+
+use bodhya_core::TaskPriority;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+/// Default number of tasks executed at once
+pub const DEFAULT_WORKERS: usize = 4;
+
+/// A task waiting in the queue
+#[derive(Debug, PartialEq, Eq)]
+struct Queued {
+    priority: TaskPriority,
+    /// Submission order, used to keep FIFO within a priority
+    seq: u64,
+    task_id: String,
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap pops the greatest: highest priority, then earliest
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Tasks waiting to run, highest priority first
+#[derive(Debug, Default)]
+pub struct TaskQueue {
+    pending: Mutex<BinaryHeap<Queued>>,
+    next_seq: AtomicU64,
+    available: Notify,
+}
+
+impl TaskQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `task_id` and wake a waiting worker
+    pub fn push(&self, task_id: impl Into<String>, priority: TaskPriority) {
+        let queued = Queued {
+            priority,
+            seq: self.next_seq.fetch_add(1, AtomicOrdering::Relaxed),
+            task_id: task_id.into(),
+        };
+        self.lock().push(queued);
+        self.available.notify_one();
+    }
+
+    /// Wait for the next task to run
+    pub async fn pop(&self) -> String {
+        loop {
+            if let Some(queued) = self.try_pop() {
+                return queued;
+            }
+            self.available.notified().await;
+        }
+    }
+
+    /// Take the next task if one is waiting
+    pub fn try_pop(&self) -> Option<String> {
+        self.lock().pop().map(|queued| queued.task_id)
+    }
+
+    /// Number of tasks waiting
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BinaryHeap<Queued>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_then_fifo() {
+        let queue = TaskQueue::new();
+        queue.push("low-1", TaskPriority::Low);
+        queue.push("normal-1", TaskPriority::Normal);
+        queue.push("high-1", TaskPriority::High);
+        queue.push("normal-2", TaskPriority::Normal);
+        queue.push("high-2", TaskPriority::High);
+
+        let order: Vec<String> = std::iter::from_fn(|| queue.try_pop()).collect();
+        assert_eq!(
+            order,
+            vec!["high-1", "high-2", "normal-1", "normal-2", "low-1"]
+        );
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pop_waits_for_push() {
+        let queue = std::sync::Arc::new(TaskQueue::new());
+        let waiter = {
+            let queue = std::sync::Arc::clone(&queue);
+            tokio::spawn(async move { queue.pop().await })
+        };
+
+        tokio::task::yield_now().await;
+        queue.push("later", TaskPriority::Normal);
+
+        assert_eq!(waiter.await.unwrap(), "later");
+    }
+}
@@ -1,23 +1,77 @@
 /// REST API route handlers
 use crate::models::{
-    AgentInfo, AgentList, ErrorResponse, HealthResponse, SubmitTaskRequest, SubmitTaskResponse,
-    TaskInfo, TaskResult,
+    AgentInfo, AgentList, BatchErrorResponse, BatchItemError, BatchSubmitResponse,
+    EngagementErrorResponse, ErrorResponse, HealthResponse, ModelSelection, ReloadResponse,
+    SubmitTaskRequest, SubmitTaskResponse, TaskInfo, TaskResult,
 };
 use crate::state::AppState;
+use crate::{chat, health, middleware, openapi, webhook, websocket};
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
-    Json,
+    routing::{get, post},
+    Json, Router,
 };
-use bodhya_core::Task;
+use bodhya_core::{EngagementMode, ModelRole, Task};
+use bodhya_model_registry::SUPPORTED_ENGAGEMENT;
+use serde::Deserialize;
 use std::sync::Arc;
+use tower::ServiceBuilder;
+
+/// Build the application router with all REST and WebSocket routes
+pub fn router(state: Arc<AppState>) -> Router {
+    let cors = middleware::cors_layer(&state.cors);
+
+    // Routes that run tasks or call models get the long timeout
+    let task_routes = Router::new()
+        .route("/health", get(health_check))
+        .route("/tasks", post(submit_task))
+        .route("/tasks/batch", post(submit_task_batch))
+        .route(
+            "/admin/reload",
+            post(reload_models).route_layer(axum::middleware::from_fn_with_state(
+                Arc::clone(&state),
+                middleware::require_admin,
+            )),
+        )
+        // OpenAI-compatible routes
+        .route("/v1/chat/completions", post(chat::chat_completions));
+    let metadata_routes = Router::new()
+        .route("/openapi.json", get(openapi_spec))
+        .route("/agents", get(list_agents))
+        .route("/models/select", get(select_model))
+        .route("/tasks/:id", get(get_task_status))
+        .route("/tasks/:id/result", get(get_task_result));
+
+    Router::new()
+        // REST API routes
+        .merge(middleware::with_timeout(task_routes, state.task_timeout))
+        .merge(middleware::with_timeout(
+            metadata_routes,
+            state.metadata_timeout,
+        ))
+        // WebSocket route
+        .route("/ws/tasks/:id", get(websocket::ws_handler))
+        // Add state and middleware
+        .with_state(state)
+        .layer(
+            ServiceBuilder::new()
+                .layer(middleware::trace_layer())
+                .layer(cors),
+        )
+}
 
 /// Custom error type for API handlers
 #[derive(Debug)]
 pub enum ApiError {
     NotFound(String),
     BadRequest(String),
+    InvalidBatch(Vec<BatchItemError>),
+    EngagementViolation {
+        message: String,
+        requested_mode: EngagementMode,
+    },
     InternalError(String),
 }
 
@@ -26,6 +80,24 @@ impl IntoResponse for ApiError {
         let (status, error_response) = match self {
             ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, ErrorResponse::new(msg)),
             ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, ErrorResponse::new(msg)),
+            ApiError::InvalidBatch(items) => {
+                let body = BatchErrorResponse {
+                    error: format!("{} task(s) in the batch are invalid", items.len()),
+                    items,
+                };
+                return (StatusCode::BAD_REQUEST, Json(body)).into_response();
+            }
+            ApiError::EngagementViolation {
+                message,
+                requested_mode,
+            } => {
+                let body = EngagementErrorResponse {
+                    error: message,
+                    requested_mode,
+                    required_mode: SUPPORTED_ENGAGEMENT,
+                };
+                return (StatusCode::FORBIDDEN, Json(body)).into_response();
+            }
             ApiError::InternalError(msg) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, ErrorResponse::new(msg))
             }
@@ -35,20 +107,129 @@ impl IntoResponse for ApiError {
     }
 }
 
+/// Request header used to deduplicate retried submissions
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
 /// POST /tasks - Submit a new task
+///
+/// If the request carries an `Idempotency-Key` header that was already used,
+/// the existing task is returned with `200 OK` instead of creating a new one.
 pub async fn submit_task(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(request): Json<SubmitTaskRequest>,
 ) -> Result<(StatusCode, Json<SubmitTaskResponse>), ApiError> {
     // Validate request
-    if request.description.trim().is_empty() {
+    validate_submit_request(&request, state.allow_private_callbacks)
+        .map_err(ApiError::BadRequest)?;
+
+    let idempotency_key = match headers.get(IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => match value.to_str().map(str::trim) {
+            Ok(key) if !key.is_empty() => Some(key.to_string()),
+            _ => {
+                return Err(ApiError::BadRequest(
+                    "Idempotency-Key header must be a non-empty string".to_string(),
+                ))
+            }
+        },
+        None => None,
+    };
+
+    let callback_url = request.callback_url.clone();
+    let task = build_task(request);
+
+    let (task_info, created) = match idempotency_key {
+        Some(key) => {
+            let (task_info, created) = state.submit_task_idempotent(&key, task, callback_url).await;
+            if created {
+                spawn_execution(&state, &task_info);
+            }
+            (task_info, created)
+        }
+        None => (enqueue_task(&state, task, callback_url).await, true),
+    };
+
+    // Return response
+    let response = SubmitTaskResponse {
+        task_id: task_info.task_id,
+        status: task_info.status,
+        created_at: task_info.created_at,
+    };
+    let status = if created {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+
+    Ok((status, Json(response)))
+}
+
+/// POST /tasks/batch - Submit several tasks at once
+///
+/// Every element is validated before anything is enqueued; if any element is
+/// invalid the whole batch is rejected with per-item errors.
+pub async fn submit_task_batch(
+    State(state): State<Arc<AppState>>,
+    Json(requests): Json<Vec<SubmitTaskRequest>>,
+) -> Result<(StatusCode, Json<BatchSubmitResponse>), ApiError> {
+    if requests.is_empty() {
         return Err(ApiError::BadRequest(
-            "Task description cannot be empty".to_string(),
+            "Batch must contain at least one task".to_string(),
         ));
     }
 
-    // Create core task
-    let mut task = Task::new(request.description.clone());
+    if requests.len() > state.max_batch_size {
+        return Err(ApiError::BadRequest(format!(
+            "Batch of {} tasks exceeds the maximum of {}",
+            requests.len(),
+            state.max_batch_size
+        )));
+    }
+
+    let errors: Vec<BatchItemError> = requests
+        .iter()
+        .enumerate()
+        .filter_map(|(index, request)| {
+            validate_submit_request(request, state.allow_private_callbacks)
+                .err()
+                .map(|error| BatchItemError { index, error })
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(ApiError::InvalidBatch(errors));
+    }
+
+    let mut task_ids = Vec::with_capacity(requests.len());
+    for request in requests {
+        let callback_url = request.callback_url.clone();
+        let task_info = enqueue_task(&state, build_task(request), callback_url).await;
+        task_ids.push(task_info.task_id);
+    }
+
+    Ok((StatusCode::CREATED, Json(BatchSubmitResponse { task_ids })))
+}
+
+/// Check a task submission, returning a description of the problem if invalid
+///
+/// `allow_private_callbacks` permits callback URLs on private network addresses.
+fn validate_submit_request(
+    request: &SubmitTaskRequest,
+    allow_private_callbacks: bool,
+) -> Result<(), String> {
+    if request.description.trim().is_empty() {
+        return Err("Task description cannot be empty".to_string());
+    }
+
+    if let Some(ref callback_url) = request.callback_url {
+        webhook::validate_callback_url(callback_url, allow_private_callbacks)?;
+    }
+
+    Ok(())
+}
+
+/// Build a core task from a submission
+fn build_task(request: SubmitTaskRequest) -> Task {
+    let mut task = Task::new(request.description);
 
     if let Some(domain) = request.domain {
         task = task.with_domain(domain);
@@ -58,26 +239,19 @@ pub async fn submit_task(
         task = task.with_payload(request.payload);
     }
 
-    // Submit task
-    let task_info = state.submit_task(task.clone()).await;
-
-    // Spawn background execution
-    let state_clone = Arc::clone(&state);
-    let task_id = task_info.task_id.clone();
-    tokio::spawn(async move {
-        if let Err(e) = state_clone.execute_task(&task_id).await {
-            tracing::error!("Task execution failed: {}", e);
-        }
-    });
+    task.with_priority(request.priority)
+}
 
-    // Return response
-    let response = SubmitTaskResponse {
-        task_id: task_info.task_id,
-        status: task_info.status,
-        created_at: task_info.created_at,
-    };
+/// Store a task and start executing it in the background
+async fn enqueue_task(state: &Arc<AppState>, task: Task, callback_url: Option<String>) -> TaskInfo {
+    let task_info = state.submit_task_with_callback(task, callback_url).await;
+    spawn_execution(state, &task_info);
+    task_info
+}
 
-    Ok((StatusCode::CREATED, Json(response)))
+/// Queue a stored task for the worker pool
+fn spawn_execution(state: &Arc<AppState>, task_info: &TaskInfo) {
+    state.enqueue(&task_info.task_id, task_info.priority);
 }
 
 /// GET /tasks/:id - Get task status
@@ -144,13 +318,96 @@ pub async fn list_agents(State(state): State<Arc<AppState>>) -> Json<AgentList>
     })
 }
 
+/// Query parameters for `GET /models/select`
+#[derive(Debug, Deserialize)]
+pub struct ModelSelectQuery {
+    pub role: String,
+    pub domain: String,
+    /// Engagement mode (default: minimum)
+    pub mode: Option<String>,
+}
+
+/// GET /models/select - Model the registry would pick, without running anything
+///
+/// Returns 403 with the required mode when the engagement mode rules the
+/// selection out, and 404 when no model matches or no registry is configured.
+pub async fn select_model(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ModelSelectQuery>,
+) -> Result<Json<ModelSelection>, ApiError> {
+    let role = ModelRole::parse(&query.role).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let mode: EngagementMode = match &query.mode {
+        Some(mode) => mode
+            .parse()
+            .map_err(|e: bodhya_core::Error| ApiError::BadRequest(e.to_string()))?,
+        None => EngagementMode::default(),
+    };
+    let registry = state
+        .models()
+        .ok_or_else(|| ApiError::NotFound("No model registry is configured".to_string()))?;
+
+    let info = registry
+        .get_model(&role, &query.domain, &mode)
+        .map_err(|e| match e {
+            bodhya_core::Error::EngagementViolation(_) => ApiError::EngagementViolation {
+                message: e.to_string(),
+                requested_mode: mode,
+            },
+            bodhya_core::Error::ModelNotFound(_) => ApiError::NotFound(e.to_string()),
+            other => ApiError::InternalError(other.to_string()),
+        })?;
+
+    Ok(Json(ModelSelection {
+        model_id: info.id,
+        display_name: info.definition.display_name,
+        role: info.definition.role,
+        domain: info.definition.domain,
+        backend: info.definition.backend,
+        installed: info.installed,
+        model_path: info.model_path.display().to_string(),
+    }))
+}
+
+/// GET /openapi.json - OpenAPI 3 document for the REST API
+pub async fn openapi_spec() -> Json<serde_json::Value> {
+    Json(openapi::openapi_document())
+}
+
+/// Query parameters for `GET /health`
+#[derive(Debug, Default, Deserialize)]
+pub struct HealthQuery {
+    /// Ask each backend for a tiny completion instead of its health check
+    #[serde(default)]
+    pub deep: bool,
+}
+
 /// GET /health - Health check
-pub async fn health_check(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
-    Json(HealthResponse {
-        status: "ok".to_string(),
-        version: env!("CARGO_PKG_VERSION").to_string(),
-        uptime_seconds: state.uptime_seconds(),
-    })
+///
+/// Returns 503 when a backend an enabled agent depends on is not ready.
+pub async fn health_check(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HealthQuery>,
+) -> (StatusCode, Json<HealthResponse>) {
+    let (status, response) = health::check(&state, query.deep).await;
+    (status, Json(response))
+}
+
+/// POST /admin/reload - Re-read the model manifest and installed models
+///
+/// Tasks already running keep the registry they started with.
+pub async fn reload_models(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ReloadResponse>, ApiError> {
+    let registry = state
+        .reload_models()
+        .map_err(|e| ApiError::InternalError(format!("Failed to reload models: {}", e)))?
+        .ok_or_else(|| ApiError::NotFound("No model registry is configured".to_string()))?;
+
+    let models = registry.list_models();
+    Ok(Json(ReloadResponse {
+        models: models.len(),
+        installed: models.iter().filter(|model| model.installed).count(),
+    }))
 }
 
 #[cfg(test)]
@@ -179,9 +436,11 @@ mod tests {
             domain: Some("code".to_string()),
             description: "test task".to_string(),
             payload: serde_json::json!({}),
+            callback_url: None,
+            priority: Default::default(),
         };
 
-        let result = submit_task(State(state), Json(request)).await;
+        let result = submit_task(State(state), HeaderMap::new(), Json(request)).await;
         assert!(result.is_ok());
 
         let (status, response) = result.unwrap();
@@ -197,9 +456,11 @@ mod tests {
             domain: None,
             description: "   ".to_string(),
             payload: serde_json::Value::Null,
+            callback_url: None,
+            priority: Default::default(),
         };
 
-        let result = submit_task(State(state), Json(request)).await;
+        let result = submit_task(State(state), HeaderMap::new(), Json(request)).await;
         assert!(result.is_err());
     }
 
@@ -227,8 +488,520 @@ mod tests {
     async fn test_health_check() {
         let state = create_test_state();
 
-        let result = health_check(State(state)).await;
-        assert_eq!(result.0.status, "ok");
-        assert!(!result.0.version.is_empty());
+        let (status, Json(health)) =
+            health_check(State(state), Query(HealthQuery::default())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(health.status, "ok");
+        assert!(!health.version.is_empty());
+        assert_eq!(health.agents.len(), 2);
+        assert!(health.agents.iter().all(|agent| agent.enabled));
+    }
+
+    #[tokio::test]
+    async fn test_reload_models_endpoint() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manifest_path = dir.path().join("models.yaml");
+        std::fs::write(
+            &manifest_path,
+            r#"
+models:
+  code_coder:
+    role: coder
+    domain: code
+    display_name: "Coder"
+    source_url: "https://example.com/coder.gguf"
+    size_gb: 3.5
+    checksum: "sha256:def456"
+    backend: local
+"#,
+        )
+        .unwrap();
+        let registry =
+            bodhya_model_registry::ModelRegistry::from_manifest_file(&manifest_path, dir.path())
+                .unwrap();
+        let models = bodhya_model_registry::SharedRegistry::new(Arc::new(registry));
+        let agents_registry = models.clone();
+        let controller =
+            Controller::new(vec![
+                Arc::new(CodeAgent::with_shared_registry(models.clone()))
+                    as Arc<dyn bodhya_core::Agent>,
+            ]);
+        let state = Arc::new(
+            AppState::new(controller)
+                .with_models(models)
+                .with_admin_token("s3cret"),
+        );
+        let before = agents_registry.current();
+        let base_url = spawn_server(Arc::clone(&state)).await;
+
+        // The manifest gains a planner
+        let mut manifest = std::fs::read_to_string(&manifest_path).unwrap();
+        manifest.push_str(
+            r#"
+  code_planner:
+    role: planner
+    domain: code
+    display_name: "Planner"
+    source_url: "https://example.com/planner.gguf"
+    size_gb: 3.5
+    checksum: "sha256:abc123"
+    backend: local
+"#,
+        );
+        std::fs::write(&manifest_path, manifest).unwrap();
+        std::fs::write(dir.path().join("code_coder.gguf"), b"fake model").unwrap();
+        let reload = |token: Option<&'static str>| {
+            let mut request = reqwest::Client::new().post(format!("{}/admin/reload", base_url));
+            if let Some(token) = token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+            request.send()
+        };
+
+        assert_eq!(reload(None).await.unwrap().status().as_u16(), 401);
+        assert_eq!(reload(Some("wrong")).await.unwrap().status().as_u16(), 401);
+        assert!(agents_registry
+            .current()
+            .get_model_by_id("code_planner")
+            .is_err());
+
+        let response = reload(Some("s3cret")).await.unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        let body: serde_json::Value =
+            serde_json::from_str(&response.text().await.unwrap()).unwrap();
+        assert_eq!(body, serde_json::json!({ "models": 2, "installed": 1 }));
+
+        // The agents see the reloaded manifest
+        let after = agents_registry.current();
+        assert!(!Arc::ptr_eq(&before, &after));
+        assert!(after.get_model_by_id("code_planner").is_ok());
+        assert!(Arc::ptr_eq(&after, &state.models().unwrap()));
+
+        // Without a registry there is nothing to reload
+        let result = reload_models(State(create_test_state())).await;
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_disabled_without_token() {
+        let base_url = spawn_server(create_test_state()).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/admin/reload", base_url))
+            .header("Authorization", "Bearer anything")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 403);
+    }
+
+    #[tokio::test]
+    async fn test_select_model_endpoint() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let manifest_path = dir.path().join("models.yaml");
+        std::fs::write(
+            &manifest_path,
+            r#"
+models:
+  code_coder:
+    role: coder
+    domain: code
+    display_name: "Coder"
+    source_url: "https://example.com/coder.gguf"
+    size_gb: 3.5
+    checksum: "sha256:def456"
+    backend: local
+"#,
+        )
+        .unwrap();
+        let registry =
+            bodhya_model_registry::ModelRegistry::from_manifest_file(&manifest_path, dir.path())
+                .unwrap();
+        let controller = Controller::new(vec![
+            Arc::new(CodeAgent::new()) as Arc<dyn bodhya_core::Agent>
+        ]);
+        let state = Arc::new(AppState::new(controller).with_models(Arc::new(registry)));
+        let base_url = spawn_server(state).await;
+        let select = |query: &'static str| {
+            let url = format!("{}/models/select?{}", base_url, query);
+            async move {
+                let response = reqwest::get(url).await.unwrap();
+                let status = response.status().as_u16();
+                let body: serde_json::Value =
+                    serde_json::from_str(&response.text().await.unwrap()).unwrap();
+                (status, body)
+            }
+        };
+
+        let (status, body) = select("role=coder&domain=code&mode=minimum").await;
+        assert_eq!(status, 200);
+        assert_eq!(body["model_id"], "code_coder");
+        assert_eq!(body["role"], "coder");
+        assert_eq!(body["installed"], false);
+
+        let (status, body) = select("role=coder&domain=code&mode=medium").await;
+        assert_eq!(status, 403);
+        assert_eq!(body["requested_mode"], "medium");
+        assert_eq!(body["required_mode"], "minimum");
+        assert!(body["error"].as_str().unwrap().contains("Minimum"));
+
+        let (status, _) = select("role=writer&domain=mail").await;
+        assert_eq!(status, 404);
+
+        let (status, _) = select("role=poet&domain=code").await;
+        assert_eq!(status, 400);
+    }
+
+    /// Serve the full router on an ephemeral port and return its base URL
+    async fn spawn_server(state: Arc<AppState>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router(state)).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_openapi_spec_endpoint() {
+        let base_url = spawn_server(create_test_state()).await;
+
+        let response = reqwest::get(format!("{}/openapi.json", base_url))
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+        let spec: serde_json::Value =
+            serde_json::from_str(&response.text().await.unwrap()).unwrap();
+
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+
+        let submit = &spec["paths"]["/tasks"]["post"];
+        assert_eq!(
+            submit["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/SubmitTaskRequest"
+        );
+        assert!(submit["responses"]["201"].is_object());
+
+        let request_schema = &spec["components"]["schemas"]["SubmitTaskRequest"];
+        assert_eq!(
+            request_schema["required"],
+            serde_json::json!(["description"])
+        );
+        assert_eq!(
+            request_schema["properties"]["description"]["type"],
+            "string"
+        );
+
+        assert!(spec["paths"]["/tasks/{id}"]["get"].is_object());
+        assert!(spec["paths"]["/tasks/{id}/result"]["get"].is_object());
+        assert!(spec["paths"]["/agents"]["get"].is_object());
+    }
+
+    fn task_request(description: &str) -> SubmitTaskRequest {
+        SubmitTaskRequest {
+            domain: Some("code".to_string()),
+            description: description.to_string(),
+            payload: serde_json::Value::Null,
+            callback_url: None,
+            priority: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_rejects_invalid_items() {
+        let state = create_test_state();
+
+        let requests = vec![task_request("ok"), task_request(" "), task_request("")];
+        let result = submit_task_batch(State(state.clone()), Json(requests)).await;
+
+        match result {
+            Err(ApiError::InvalidBatch(items)) => {
+                let indexes: Vec<_> = items.iter().map(|item| item.index).collect();
+                assert_eq!(indexes, vec![1, 2]);
+            }
+            other => panic!("Expected InvalidBatch, got {:?}", other),
+        }
+
+        // Nothing from a rejected batch is enqueued
+        assert!(state.tasks.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_enforces_cap() {
+        let controller = Controller::new(vec![
+            Arc::new(CodeAgent::new()) as Arc<dyn bodhya_core::Agent>
+        ]);
+        let state = Arc::new(AppState::new(controller).with_max_batch_size(2));
+
+        let requests = vec![task_request("a"), task_request("b"), task_request("c")];
+        let result = submit_task_batch(State(state.clone()), Json(requests)).await;
+        assert!(matches!(result, Err(ApiError::BadRequest(_))));
+
+        let result = submit_task_batch(State(state), Json(vec![])).await;
+        assert!(matches!(result, Err(ApiError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_and_poll_to_completion() {
+        let base_url = spawn_server(create_test_state()).await;
+        let client = reqwest::Client::new();
+
+        let body = serde_json::json!([
+            { "domain": "code", "description": "first task" },
+            { "domain": "code", "description": "second task" },
+            { "domain": "mail", "description": "third task" }
+        ]);
+        let response = client
+            .post(format!("{}/tasks/batch", base_url))
+            .header("content-type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 201);
+
+        let batch: BatchSubmitResponse =
+            serde_json::from_str(&response.text().await.unwrap()).unwrap();
+        assert_eq!(batch.task_ids.len(), 3);
+
+        for (task_id, description) in
+            batch
+                .task_ids
+                .iter()
+                .zip(["first task", "second task", "third task"])
+        {
+            let mut info: TaskInfo;
+            let mut attempts = 0;
+            loop {
+                let text = client
+                    .get(format!("{}/tasks/{}", base_url, task_id))
+                    .send()
+                    .await
+                    .unwrap()
+                    .text()
+                    .await
+                    .unwrap();
+                info = serde_json::from_str(&text).unwrap();
+                if info.status.is_terminal() || attempts >= 100 {
+                    break;
+                }
+                attempts += 1;
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+
+            assert_eq!(info.description, description);
+            assert_eq!(info.status, TaskStatus::Completed);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_http_error_body() {
+        let base_url = spawn_server(create_test_state()).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/tasks/batch", base_url))
+            .header("content-type", "application/json")
+            .body(r#"[{"description":"ok"},{"description":""}]"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 400);
+
+        let body: BatchErrorResponse =
+            serde_json::from_str(&response.text().await.unwrap()).unwrap();
+        assert_eq!(body.items.len(), 1);
+        assert_eq!(body.items[0].index, 1);
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejects_non_http_callback() {
+        let state = create_test_state();
+
+        let mut request = task_request("test task");
+        request.callback_url = Some("ftp://example.com/hook".to_string());
+
+        let result = submit_task(State(state), HeaderMap::new(), Json(request)).await;
+        assert!(matches!(result, Err(ApiError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejects_private_callback() {
+        let state = create_test_state();
+
+        let mut request = task_request("test task");
+        request.callback_url = Some("http://127.0.0.1:6379/".to_string());
+
+        let result = submit_task(State(state), HeaderMap::new(), Json(request)).await;
+        assert!(
+            matches!(result, Err(ApiError::BadRequest(msg)) if msg.contains("private network"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_callback_fires_with_result() {
+        use axum::extract::State as MockState;
+        use tokio::sync::mpsc;
+
+        // Mock receiver: fails the first delivery to exercise the retry
+        let (tx, mut rx) = mpsc::unbounded_channel::<bodhya_core::AgentResult>();
+        let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mock = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |MockState((tx, attempts)): MockState<(
+                        mpsc::UnboundedSender<bodhya_core::AgentResult>,
+                        Arc<std::sync::atomic::AtomicUsize>,
+                    )>,
+                     Json(result): Json<bodhya_core::AgentResult>| async move {
+                        if attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                            return StatusCode::SERVICE_UNAVAILABLE;
+                        }
+                        tx.send(result).unwrap();
+                        StatusCode::OK
+                    },
+                ),
+            )
+            .with_state((tx, attempts.clone()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let callback_url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, mock).await.unwrap();
+        });
+
+        let controller = Controller::new(vec![
+            Arc::new(CodeAgent::new()) as Arc<dyn bodhya_core::Agent>
+        ]);
+        let state = Arc::new(
+            AppState::new(controller)
+                .with_callback_retry(3, std::time::Duration::from_millis(10))
+                .with_private_callbacks(true),
+        );
+
+        let mut request = task_request("test task");
+        request.callback_url = Some(callback_url);
+        let (_, response) = submit_task(State(state), HeaderMap::new(), Json(request))
+            .await
+            .unwrap();
+
+        let delivered = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+            .await
+            .expect("callback was not delivered")
+            .unwrap();
+        assert_eq!(delivered.task_id, response.0.task_id);
+        assert!(delivered.success);
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_submit_with_idempotency_key_creates_single_task() {
+        let state = create_test_state();
+        let mut headers = HeaderMap::new();
+        headers.insert(IDEMPOTENCY_KEY_HEADER, "retry-123".parse().unwrap());
+
+        let (status, first) = submit_task(
+            State(state.clone()),
+            headers.clone(),
+            Json(task_request("test task")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, second) = submit_task(
+            State(state.clone()),
+            headers,
+            Json(task_request("test task")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(second.0.task_id, first.0.task_id);
+
+        assert_eq!(state.tasks.read().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_idempotent_submissions() {
+        let state = create_test_state();
+        let mut headers = HeaderMap::new();
+        headers.insert(IDEMPOTENCY_KEY_HEADER, "race".parse().unwrap());
+
+        let submissions = (0..8).map(|_| {
+            submit_task(
+                State(state.clone()),
+                headers.clone(),
+                Json(task_request("test task")),
+            )
+        });
+        let results = futures::future::join_all(submissions).await;
+
+        let ids: std::collections::HashSet<_> = results
+            .into_iter()
+            .map(|r| r.unwrap().1 .0.task_id)
+            .collect();
+        assert_eq!(ids.len(), 1);
+        assert_eq!(state.tasks.read().await.len(), 1);
+    }
+
+    /// Agent that takes far longer than any test timeout
+    struct SlowAgent;
+
+    #[async_trait::async_trait]
+    impl bodhya_core::Agent for SlowAgent {
+        fn id(&self) -> &'static str {
+            "slow"
+        }
+
+        fn capability(&self) -> bodhya_core::AgentCapability {
+            bodhya_core::AgentCapability::new("slow", vec![], "Slow test agent")
+        }
+
+        async fn handle(
+            &self,
+            task: Task,
+            _ctx: bodhya_core::AgentContext,
+        ) -> bodhya_core::Result<bodhya_core::AgentResult> {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            Ok(bodhya_core::AgentResult::success(task.id, "done"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routes_use_configured_timeouts() {
+        let controller = Controller::new(vec![Arc::new(SlowAgent) as Arc<dyn bodhya_core::Agent>]);
+        let state = Arc::new(AppState::new(controller).with_route_timeouts(
+            std::time::Duration::from_millis(200),
+            std::time::Duration::from_secs(10),
+        ));
+        let base_url = spawn_server(state).await;
+        let client = reqwest::Client::new();
+
+        let chat = client
+            .post(format!("{}/v1/chat/completions", base_url))
+            .header("content-type", "application/json")
+            .body(r#"{"model":"slow","messages":[{"role":"user","content":"wait"}]}"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(chat.status().as_u16(), 504);
+
+        let agents = client
+            .get(format!("{}/agents", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(agents.status().as_u16(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejects_empty_idempotency_key() {
+        let state = create_test_state();
+        let mut headers = HeaderMap::new();
+        headers.insert(IDEMPOTENCY_KEY_HEADER, " ".parse().unwrap());
+
+        let result = submit_task(State(state), headers, Json(task_request("test task"))).await;
+        assert!(matches!(result, Err(ApiError::BadRequest(_))));
     }
 }
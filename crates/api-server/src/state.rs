@@ -1,12 +1,19 @@
 /// Application state management
-use crate::models::{TaskInfo, TaskResult, TaskStatus};
+use crate::events::{EventHub, Subscription, DEFAULT_EVENT_BUFFER};
+use crate::health;
+use crate::middleware::CorsConfig;
+use crate::models::{TaskInfo, TaskResult, TaskStatus, WsMessage};
+use crate::queue::{TaskQueue, DEFAULT_WORKERS};
+use crate::webhook;
+use crate::websocket::BackpressureConfig;
 use bodhya_controller::Controller;
-use bodhya_core::{Agent, AgentResult, Task};
+use bodhya_core::{Agent, AgentResult, ProgressCallback, Task, TaskPriority};
+use bodhya_model_registry::{ModelRegistry, SharedRegistry};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Instant;
-use tokio::sync::RwLock;
+use std::sync::{Arc, Once};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex, RwLock};
 
 /// Stored task state
 #[derive(Debug, Clone)]
@@ -14,8 +21,31 @@ pub struct StoredTask {
     pub info: TaskInfo,
     pub result: Option<AgentResult>,
     pub core_task: Task,
+    /// URL to notify with the result when the task finishes
+    pub callback_url: Option<String>,
+    /// Sequence number of the `task_complete` event, once the result is stored
+    pub complete_seq: Option<u64>,
 }
 
+/// A task created under an `Idempotency-Key`
+#[derive(Debug, Clone)]
+pub struct IdempotencyEntry {
+    pub task_id: String,
+    pub created_at: Instant,
+}
+
+/// Default lifetime of idempotency keys
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Default timeout of routes that run tasks or call models
+pub const DEFAULT_TASK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Default timeout of routes that only read server state
+pub const DEFAULT_METADATA_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default maximum number of tasks accepted by `POST /tasks/batch`
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
@@ -27,20 +57,214 @@ pub struct AppState {
 
     /// Server start time
     pub start_time: Instant,
+
+    /// Maximum number of tasks accepted in one batch submission
+    pub max_batch_size: usize,
+
+    /// HTTP client used for completion callbacks
+    pub http_client: reqwest::Client,
+
+    /// Whether callback URLs may point at loopback, link-local or private addresses
+    pub allow_private_callbacks: bool,
+
+    /// Delivery attempts per completion callback
+    pub callback_attempts: u32,
+
+    /// Delay before the first callback retry (doubled after each failure)
+    pub callback_retry_delay: Duration,
+
+    /// Idempotency key -> task created with it
+    pub idempotency_keys: Arc<Mutex<HashMap<String, IdempotencyEntry>>>,
+
+    /// How long an idempotency key is remembered
+    pub idempotency_ttl: Duration,
+
+    /// Allowed CORS origins, methods, and headers
+    pub cors: CorsConfig,
+
+    /// Recent progress events per task, replayed to reconnecting WebSocket clients
+    pub events: Arc<EventHub>,
+
+    /// Outgoing queue size and lag handling for WebSocket clients
+    pub ws_backpressure: BackpressureConfig,
+
+    /// Model registry the agents run on, reported by `/health` and
+    /// replaced wholesale by `POST /admin/reload`
+    models: Option<SharedRegistry>,
+
+    /// Bearer token required by `/admin` routes; without one they are refused
+    pub admin_token: Option<String>,
+
+    /// How long `/health?deep=true` waits for each backend
+    pub deep_check_timeout: Duration,
+
+    /// How long the result of `/health?deep=true` is reused
+    pub deep_check_ttl: Duration,
+
+    /// Result of the last deep health check
+    pub(crate) deep_checks: Arc<health::DeepCheckCache>,
+
+    /// Tasks waiting for a worker, highest priority first
+    pub queue: Arc<TaskQueue>,
+
+    /// Number of tasks executed at once
+    pub workers: usize,
+
+    /// Timeout of `/tasks`, `/v1/chat/completions`, `/health`, and `/admin/reload`
+    pub task_timeout: Duration,
+
+    /// Timeout of `/agents`, `/openapi.json`, and task status and result lookups
+    pub metadata_timeout: Duration,
+
+    /// Starts the worker pool on the first queued task
+    workers_started: Arc<Once>,
 }
 
 impl AppState {
     /// Create new application state
     pub fn new(controller: Controller) -> Self {
         Self {
-            controller: Arc::new(controller),
+            controller: controller.into_shared(),
             tasks: Arc::new(RwLock::new(HashMap::new())),
             start_time: Instant::now(),
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            http_client: webhook::callback_client(false),
+            allow_private_callbacks: false,
+            callback_attempts: webhook::DEFAULT_CALLBACK_ATTEMPTS,
+            callback_retry_delay: webhook::DEFAULT_CALLBACK_RETRY_DELAY,
+            idempotency_keys: Arc::new(Mutex::new(HashMap::new())),
+            idempotency_ttl: DEFAULT_IDEMPOTENCY_TTL,
+            cors: CorsConfig::default(),
+            events: Arc::new(EventHub::new(DEFAULT_EVENT_BUFFER)),
+            ws_backpressure: BackpressureConfig::default(),
+            models: None,
+            admin_token: None,
+            deep_check_timeout: health::DEFAULT_DEEP_CHECK_TIMEOUT,
+            deep_check_ttl: health::DEFAULT_DEEP_CHECK_TTL,
+            deep_checks: Arc::new(health::DeepCheckCache::default()),
+            queue: Arc::new(TaskQueue::new()),
+            workers: DEFAULT_WORKERS,
+            task_timeout: DEFAULT_TASK_TIMEOUT,
+            metadata_timeout: DEFAULT_METADATA_TIMEOUT,
+            workers_started: Arc::new(Once::new()),
         }
     }
 
+    /// Keep up to `capacity` recent progress events per task for replay
+    pub fn with_event_buffer(mut self, capacity: usize) -> Self {
+        self.events = Arc::new(EventHub::new(capacity));
+        self
+    }
+
+    /// Set how many tasks are executed at once
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Set the timeouts of task routes and metadata routes
+    ///
+    /// Requests that run longer are answered with 504.
+    pub fn with_route_timeouts(
+        mut self,
+        task_timeout: Duration,
+        metadata_timeout: Duration,
+    ) -> Self {
+        self.task_timeout = task_timeout;
+        self.metadata_timeout = metadata_timeout;
+        self
+    }
+
+    /// Report readiness of the backends in `models` from `/health`
+    ///
+    /// Pass the handle the agents were built with, so the report covers the
+    /// models tasks actually run on and a reload reaches the agents.
+    pub fn with_models(mut self, models: impl Into<SharedRegistry>) -> Self {
+        self.models = Some(models.into());
+        self
+    }
+
+    /// Require `token` as a bearer token on `/admin` routes
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Self {
+        self.admin_token = Some(token.into());
+        self
+    }
+
+    /// The current model registry, if one is configured
+    pub fn models(&self) -> Option<Arc<ModelRegistry>> {
+        self.models.as_ref().map(SharedRegistry::current)
+    }
+
+    /// Re-read the model manifest and swap in the reloaded registry
+    ///
+    /// Agents sharing the registry use it from their next task; tasks
+    /// already running keep the previous one. Returns `None` when no
+    /// registry is configured.
+    pub fn reload_models(&self) -> bodhya_core::Result<Option<Arc<ModelRegistry>>> {
+        self.models.as_ref().map(SharedRegistry::reload).transpose()
+    }
+
+    /// Set how long a deep health check waits for each backend
+    pub fn with_deep_check_timeout(mut self, timeout: Duration) -> Self {
+        self.deep_check_timeout = timeout;
+        self
+    }
+
+    /// Set how long the result of a deep health check is reused
+    pub fn with_deep_check_ttl(mut self, ttl: Duration) -> Self {
+        self.deep_check_ttl = ttl;
+        self
+    }
+
+    /// Set how WebSocket clients that fall behind are handled
+    pub fn with_ws_backpressure(mut self, backpressure: BackpressureConfig) -> Self {
+        self.ws_backpressure = backpressure;
+        self
+    }
+
+    /// Set the CORS configuration
+    pub fn with_cors(mut self, cors: CorsConfig) -> Self {
+        self.cors = cors;
+        self
+    }
+
+    /// Set how long idempotency keys are remembered
+    pub fn with_idempotency_ttl(mut self, ttl: Duration) -> Self {
+        self.idempotency_ttl = ttl;
+        self
+    }
+
+    /// Set how many times a completion callback is attempted and the initial retry delay
+    pub fn with_callback_retry(mut self, attempts: u32, retry_delay: Duration) -> Self {
+        self.callback_attempts = attempts;
+        self.callback_retry_delay = retry_delay;
+        self
+    }
+
+    /// Allow (or refuse) callback URLs on loopback, link-local and private addresses
+    pub fn with_private_callbacks(mut self, allow: bool) -> Self {
+        self.allow_private_callbacks = allow;
+        self.http_client = webhook::callback_client(allow);
+        self
+    }
+
+    /// Set the maximum batch submission size
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
     /// Submit a new task
-    pub async fn submit_task(&self, mut task: Task) -> TaskInfo {
+    pub async fn submit_task(&self, task: Task) -> TaskInfo {
+        self.submit_task_with_callback(task, None).await
+    }
+
+    /// Submit a new task whose result is POSTed to `callback_url` on completion
+    pub async fn submit_task_with_callback(
+        &self,
+        mut task: Task,
+        callback_url: Option<String>,
+    ) -> TaskInfo {
         // Ensure task has timestamp
         if task.created_at == DateTime::<Utc>::MIN_UTC {
             task.created_at = Utc::now();
@@ -51,16 +275,21 @@ impl AppState {
             status: TaskStatus::Pending,
             domain: task.domain_hint.clone(),
             description: task.description.clone(),
+            priority: task.priority,
             created_at: task.created_at,
             started_at: None,
             completed_at: None,
             progress: None,
+            phase: None,
+            last_activity: None,
         };
 
         let stored = StoredTask {
             info: info.clone(),
             result: None,
             core_task: task,
+            callback_url,
+            complete_seq: None,
         };
 
         self.tasks
@@ -71,6 +300,40 @@ impl AppState {
         info
     }
 
+    /// Submit a task unless one was already created with `idempotency_key`
+    ///
+    /// Returns the task info and whether a new task was created. The key map
+    /// stays locked across the check and the insert, so concurrent requests
+    /// with the same key create exactly one task.
+    pub async fn submit_task_idempotent(
+        &self,
+        idempotency_key: &str,
+        task: Task,
+        callback_url: Option<String>,
+    ) -> (TaskInfo, bool) {
+        let mut keys = self.idempotency_keys.lock().await;
+
+        let ttl = self.idempotency_ttl;
+        keys.retain(|_, entry| entry.created_at.elapsed() < ttl);
+
+        if let Some(entry) = keys.get(idempotency_key) {
+            if let Some(info) = self.get_task_info(&entry.task_id).await {
+                return (info, false);
+            }
+        }
+
+        let info = self.submit_task_with_callback(task, callback_url).await;
+        keys.insert(
+            idempotency_key.to_string(),
+            IdempotencyEntry {
+                task_id: info.task_id.clone(),
+                created_at: Instant::now(),
+            },
+        );
+
+        (info, true)
+    }
+
     /// Get task info by ID
     pub async fn get_task_info(&self, task_id: &str) -> Option<TaskInfo> {
         self.tasks.read().await.get(task_id).map(|t| t.info.clone())
@@ -99,6 +362,45 @@ impl AppState {
         })
     }
 
+    /// Wait until `task_id` finishes, returning its result
+    ///
+    /// Returns `None` if the task is unknown.
+    pub async fn wait_for_result(&self, task_id: &str) -> Option<AgentResult> {
+        let (info, mut subscription) = self.watch_task(task_id, None).await?;
+        if !info.status.is_terminal() {
+            loop {
+                match subscription.live.recv().await {
+                    Ok(event) if matches!(event.message, WsMessage::TaskComplete { .. }) => break,
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+        let tasks = self.tasks.read().await;
+        tasks.get(task_id)?.result.clone()
+    }
+
+    /// Current task info plus its events after `since` and a live feed
+    ///
+    /// Events are published while the task map is write-locked, so holding
+    /// the read lock here makes the snapshot consistent with the subscription.
+    /// With no `since`, nothing is replayed. A finished task's events are no
+    /// longer buffered, so its subscription replays nothing either.
+    pub async fn watch_task(
+        &self,
+        task_id: &str,
+        since: Option<u64>,
+    ) -> Option<(TaskInfo, Subscription)> {
+        let tasks = self.tasks.read().await;
+        let stored = tasks.get(task_id)?;
+        let since = since.unwrap_or(u64::MAX);
+        let subscription = match stored.complete_seq {
+            Some(complete_seq) => Subscription::finished(since, complete_seq),
+            None => self.events.subscribe(task_id, since),
+        };
+        Some((stored.info.clone(), subscription))
+    }
+
     /// Update task status
     pub async fn update_task_status(
         &self,
@@ -110,6 +412,7 @@ impl AppState {
         if let Some(stored) = tasks.get_mut(task_id) {
             stored.info.status = status;
             stored.info.progress = progress;
+            stored.info.last_activity = Some(Utc::now());
 
             match status {
                 TaskStatus::InProgress if stored.info.started_at.is_none() => {
@@ -123,6 +426,33 @@ impl AppState {
                 _ => {}
             }
 
+            self.events.publish(
+                task_id,
+                WsMessage::TaskStatus {
+                    task_id: task_id.to_string(),
+                    status,
+                    progress,
+                },
+            );
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record that a task entered `phase`, refreshing its `last_activity`
+    pub async fn record_activity(&self, task_id: &str, phase: &str) -> bool {
+        let mut tasks = self.tasks.write().await;
+        if let Some(stored) = tasks.get_mut(task_id) {
+            stored.info.phase = Some(phase.to_string());
+            stored.info.last_activity = Some(Utc::now());
+            self.events.publish(
+                task_id,
+                WsMessage::TaskPhase {
+                    task_id: task_id.to_string(),
+                    phase: phase.to_string(),
+                },
+            );
             true
         } else {
             false
@@ -141,7 +471,21 @@ impl AppState {
 
             stored.info.status = status;
             stored.info.completed_at = Some(Utc::now());
+            stored.info.last_activity = stored.info.completed_at;
             stored.info.progress = Some(100);
+
+            self.events.publish(
+                task_id,
+                WsMessage::TaskStatus {
+                    task_id: task_id.to_string(),
+                    status,
+                    progress: Some(100),
+                },
+            );
+            stored.complete_seq = Some(
+                self.events
+                    .finish(task_id, complete_message(task_id, &result)),
+            );
             stored.result = Some(result);
 
             true
@@ -150,6 +494,24 @@ impl AppState {
         }
     }
 
+    /// Queue a stored task for execution, starting the worker pool if needed
+    pub fn enqueue(self: &Arc<Self>, task_id: &str, priority: TaskPriority) {
+        self.workers_started.call_once(|| {
+            for _ in 0..self.workers {
+                let state = Arc::clone(self);
+                tokio::spawn(async move {
+                    loop {
+                        let task_id = state.queue.pop().await;
+                        if let Err(e) = state.execute_task(&task_id).await {
+                            tracing::error!("Task execution failed: {}", e);
+                        }
+                    }
+                });
+            }
+        });
+        self.queue.push(task_id, priority);
+    }
+
     /// Execute a task (blocking operation - should run in background)
     pub async fn execute_task(&self, task_id: &str) -> anyhow::Result<()> {
         // Get the task
@@ -165,29 +527,77 @@ impl AppState {
         self.update_task_status(task_id, TaskStatus::InProgress, Some(0))
             .await;
 
-        // Execute via controller
-        let result = self.controller.execute(task).await;
+        // Execute via controller, recording each phase it reports as activity
+        let (phase_tx, mut phase_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let progress: ProgressCallback = Arc::new(move |phase| {
+            let _ = phase_tx.send(phase.to_string());
+        });
 
-        // Store result
-        match result {
-            Ok(agent_result) => {
-                self.store_result(task_id, agent_result).await;
-            }
-            Err(e) => {
-                let error_result = AgentResult {
-                    task_id: task_id.to_string(),
-                    content: String::new(),
-                    metadata: serde_json::Value::Null,
-                    success: false,
-                    error: Some(e.to_string()),
-                };
-                self.store_result(task_id, error_result).await;
+        let execution = self.controller.execute_with_progress(task, progress);
+        tokio::pin!(execution);
+
+        let result = loop {
+            tokio::select! {
+                result = &mut execution => break result,
+                Some(phase) = phase_rx.recv() => {
+                    self.record_activity(task_id, &phase).await;
+                }
             }
+        };
+
+        while let Ok(phase) = phase_rx.try_recv() {
+            self.record_activity(task_id, &phase).await;
         }
 
+        let agent_result = match result {
+            Ok(agent_result) => agent_result,
+            Err(e) => AgentResult {
+                task_id: task_id.to_string(),
+                content: String::new(),
+                metadata: serde_json::Value::Null,
+                success: false,
+                error: Some(e.to_string()),
+            },
+        };
+
+        // Store result
+        self.store_result(task_id, agent_result.clone()).await;
+
+        self.notify_callback(task_id, agent_result).await;
+
         Ok(())
     }
 
+    /// POST the result to the task's callback URL, if one was registered
+    ///
+    /// Delivery runs in the background, so a slow or unreachable callback
+    /// does not hold up the worker's next task.
+    async fn notify_callback(&self, task_id: &str, result: AgentResult) {
+        let callback_url = {
+            let tasks = self.tasks.read().await;
+            tasks.get(task_id).and_then(|t| t.callback_url.clone())
+        };
+
+        if let Some(callback_url) = callback_url {
+            let client = self.http_client.clone();
+            let attempts = self.callback_attempts;
+            let retry_delay = self.callback_retry_delay;
+            let task_id = task_id.to_string();
+            tokio::spawn(async move {
+                if let Err(e) =
+                    webhook::deliver(&client, &callback_url, &result, attempts, retry_delay).await
+                {
+                    tracing::error!(
+                        "Giving up on callback for task {} to {}: {}",
+                        task_id,
+                        callback_url,
+                        e
+                    );
+                }
+            });
+        }
+    }
+
     /// Get uptime in seconds
     pub fn uptime_seconds(&self) -> u64 {
         self.start_time.elapsed().as_secs()
@@ -199,6 +609,16 @@ impl AppState {
     }
 }
 
+/// The `task_complete` WebSocket message for `result`
+pub(crate) fn complete_message(task_id: &str, result: &AgentResult) -> WsMessage {
+    WsMessage::TaskComplete {
+        task_id: task_id.to_string(),
+        success: result.success,
+        result: result.success.then(|| result.content.clone()),
+        error: result.error.clone(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,4 +713,272 @@ mod tests {
         // After sleep, uptime should be >= initial uptime
         assert!(uptime2 >= uptime1);
     }
+
+    #[test]
+    fn test_max_batch_size() {
+        let state = AppState::new(create_test_controller());
+        assert_eq!(state.max_batch_size, DEFAULT_MAX_BATCH_SIZE);
+
+        let state = state.with_max_batch_size(5);
+        assert_eq!(state.max_batch_size, 5);
+    }
+
+    #[tokio::test]
+    async fn test_submit_task_idempotent() {
+        let state = AppState::new(create_test_controller());
+
+        let (first, created) = state
+            .submit_task_idempotent("key-1", Task::new("test task"), None)
+            .await;
+        assert!(created);
+
+        let (second, created) = state
+            .submit_task_idempotent("key-1", Task::new("test task"), None)
+            .await;
+        assert!(!created);
+        assert_eq!(second.task_id, first.task_id);
+
+        let (other, created) = state
+            .submit_task_idempotent("key-2", Task::new("test task"), None)
+            .await;
+        assert!(created);
+        assert_ne!(other.task_id, first.task_id);
+        assert_eq!(state.tasks.read().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_expires() {
+        let state = AppState::new(create_test_controller()).with_idempotency_ttl(Duration::ZERO);
+
+        let (first, _) = state
+            .submit_task_idempotent("key-1", Task::new("test task"), None)
+            .await;
+        let (second, created) = state
+            .submit_task_idempotent("key-1", Task::new("test task"), None)
+            .await;
+        assert!(created);
+        assert_ne!(second.task_id, first.task_id);
+    }
+
+    /// Agent that walks through several phases with a pause between each
+    struct PhasedAgent;
+
+    #[async_trait::async_trait]
+    impl Agent for PhasedAgent {
+        fn id(&self) -> &'static str {
+            "phased"
+        }
+
+        fn capability(&self) -> bodhya_core::AgentCapability {
+            bodhya_core::AgentCapability::new("phased", vec![], "Multi-phase test agent")
+        }
+
+        async fn handle(
+            &self,
+            task: Task,
+            ctx: bodhya_core::AgentContext,
+        ) -> bodhya_core::Result<AgentResult> {
+            for phase in ["planning", "coding", "review"] {
+                ctx.report_phase(phase);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            Ok(AgentResult::success(task.id, "done"))
+        }
+    }
+
+    /// Poll until the task reports `phase`, returning its activity timestamp
+    async fn wait_for_phase(state: &AppState, task_id: &str, phase: &str) -> DateTime<Utc> {
+        for _ in 0..200 {
+            let info = state.get_task_info(task_id).await.unwrap();
+            if info.phase.as_deref() == Some(phase) {
+                return info.last_activity.unwrap();
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("task never reached phase {}", phase);
+    }
+
+    #[tokio::test]
+    async fn test_last_activity_advances_across_phases() {
+        let state = Arc::new(AppState::new(Controller::new(vec![
+            Arc::new(PhasedAgent) as Arc<dyn Agent>
+        ])));
+        let info = state.submit_task(Task::new("multi-phase task")).await;
+        assert!(info.last_activity.is_none());
+
+        let runner = Arc::clone(&state);
+        let task_id = info.task_id.clone();
+        let handle = tokio::spawn(async move { runner.execute_task(&task_id).await });
+
+        let planning = wait_for_phase(&state, &info.task_id, "planning").await;
+        let coding = wait_for_phase(&state, &info.task_id, "coding").await;
+        let review = wait_for_phase(&state, &info.task_id, "review").await;
+        assert!(planning < coding);
+        assert!(coding < review);
+
+        handle.await.unwrap().unwrap();
+        let done = state.get_task_info(&info.task_id).await.unwrap();
+        assert_eq!(done.status, TaskStatus::Completed);
+        assert!(done.last_activity.unwrap() > review);
+    }
+
+    #[tokio::test]
+    async fn test_watch_task_replays_events_after_since() {
+        let state = AppState::new(create_test_controller());
+        let info = state.submit_task(Task::new("watched task")).await;
+        let task_id = info.task_id.as_str();
+
+        state
+            .update_task_status(task_id, TaskStatus::InProgress, Some(0))
+            .await;
+        state.record_activity(task_id, "planning").await;
+        state.record_activity(task_id, "coding").await;
+
+        let (_, first) = state.watch_task(task_id, None).await.unwrap();
+        assert!(first.replay.is_empty());
+        assert_eq!(first.last_seq, 3);
+
+        // The client saw seq 1 before disconnecting
+        let (_, mut resumed) = state.watch_task(task_id, Some(1)).await.unwrap();
+        assert_eq!(
+            resumed.replay.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert!(!resumed.missed);
+
+        state
+            .store_result(task_id, AgentResult::success(task_id, "done"))
+            .await;
+        let status = resumed.live.recv().await.unwrap();
+        assert_eq!(status.seq, 4);
+        let complete = resumed.live.recv().await.unwrap();
+        assert_eq!(complete.seq, 5);
+        assert!(matches!(
+            complete.message,
+            WsMessage::TaskComplete { success: true, .. }
+        ));
+
+        assert!(state.watch_task("missing", None).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_finished_task_events_are_evicted() {
+        let state = AppState::new(create_test_controller());
+        let info = state.submit_task(Task::new("finished task")).await;
+        let task_id = info.task_id.as_str();
+
+        state.record_activity(task_id, "planning").await;
+        state
+            .store_result(task_id, AgentResult::success(task_id, "done"))
+            .await;
+        assert_eq!(state.events.task_count(), 0);
+
+        let (info, finished) = state.watch_task(task_id, Some(1)).await.unwrap();
+        assert!(info.status.is_terminal());
+        assert!(finished.replay.is_empty());
+        assert!(finished.missed);
+        assert_eq!(finished.last_seq, 3);
+        assert_eq!(state.events.task_count(), 0);
+    }
+
+    /// Agent that records the order tasks start in; "blocker" waits for `gate`
+    struct OrderAgent {
+        started: Arc<std::sync::Mutex<Vec<String>>>,
+        gate: Arc<tokio::sync::Notify>,
+    }
+
+    #[async_trait::async_trait]
+    impl Agent for OrderAgent {
+        fn id(&self) -> &'static str {
+            "order"
+        }
+
+        fn capability(&self) -> bodhya_core::AgentCapability {
+            bodhya_core::AgentCapability::new("order", vec![], "Start-order test agent")
+        }
+
+        async fn handle(
+            &self,
+            task: Task,
+            _ctx: bodhya_core::AgentContext,
+        ) -> bodhya_core::Result<AgentResult> {
+            self.started.lock().unwrap().push(task.description.clone());
+            if task.description == "blocker" {
+                self.gate.notified().await;
+            }
+            Ok(AgentResult::success(task.id, "done"))
+        }
+    }
+
+    async fn wait_until_finished(state: &AppState, task_id: &str) {
+        for _ in 0..200 {
+            if state
+                .get_task_info(task_id)
+                .await
+                .unwrap()
+                .status
+                .is_terminal()
+            {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("task {} never finished", task_id);
+    }
+
+    #[tokio::test]
+    async fn test_high_priority_task_runs_before_low() {
+        let started = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let gate = Arc::new(tokio::sync::Notify::new());
+        let agent = OrderAgent {
+            started: Arc::clone(&started),
+            gate: Arc::clone(&gate),
+        };
+        let state = Arc::new(
+            AppState::new(Controller::new(vec![Arc::new(agent) as Arc<dyn Agent>])).with_workers(1),
+        );
+
+        // Keep the only worker busy while the other tasks are queued
+        let blocker = state.submit_task(Task::new("blocker")).await;
+        state.enqueue(&blocker.task_id, blocker.priority);
+        while started.lock().unwrap().is_empty() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let mut submitted = Vec::new();
+        for (description, priority) in [("low", TaskPriority::Low), ("high", TaskPriority::High)] {
+            let info = state
+                .submit_task(Task::new(description).with_priority(priority))
+                .await;
+            assert_eq!(info.priority, priority);
+            state.enqueue(&info.task_id, info.priority);
+            submitted.push(info.task_id);
+        }
+        assert_eq!(state.queue.len(), 2);
+
+        gate.notify_one();
+        for task_id in &submitted {
+            wait_until_finished(&state, task_id).await;
+        }
+        assert_eq!(*started.lock().unwrap(), vec!["blocker", "high", "low"]);
+    }
+
+    #[tokio::test]
+    async fn test_hanging_callback_does_not_block_queue() {
+        // Accepts connections into its backlog but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let callback_url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let state = Arc::new(AppState::new(create_test_controller()).with_workers(1));
+        let first = state
+            .submit_task_with_callback(Task::new("first task"), Some(callback_url))
+            .await;
+        state.enqueue(&first.task_id, first.priority);
+        wait_until_finished(&state, &first.task_id).await;
+
+        let second = state.submit_task(Task::new("second task")).await;
+        state.enqueue(&second.task_id, second.priority);
+        wait_until_finished(&state, &second.task_id).await;
+        drop(listener);
+    }
 }
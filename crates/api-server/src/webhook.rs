@@ -0,0 +1,212 @@
+/// Task completion webhooks
+///
+/// When a task is submitted with a `callback_url`, the final `AgentResult`
+/// is POSTed there as JSON once the task finishes, retrying on failure.
+/// Loopback, link-local and private addresses are refused unless enabled,
+/// so a client can't make the server POST to internal services: literal
+/// addresses when the task is submitted, resolved ones on delivery.
+use bodhya_core::AgentResult;
+use bodhya_tools_mcp::{is_public_address, PublicResolver};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default number of delivery attempts per callback
+pub const DEFAULT_CALLBACK_ATTEMPTS: u32 = 3;
+
+/// Default delay before the first retry (doubled after each failure)
+pub const DEFAULT_CALLBACK_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Timeout of one delivery attempt
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Check that a callback URL is an absolute http(s) URL
+///
+/// Unless `allow_private` is set, hosts that are loopback, link-local or
+/// private addresses (or `localhost`) are refused.
+pub fn validate_callback_url(callback_url: &str, allow_private: bool) -> Result<(), String> {
+    let url = reqwest::Url::parse(callback_url)
+        .map_err(|e| format!("Invalid callback_url '{}': {}", callback_url, e))?;
+
+    let host = match url.scheme() {
+        "http" | "https" => url
+            .host_str()
+            .ok_or_else(|| format!("callback_url '{}' has no host", callback_url))?,
+        scheme => {
+            return Err(format!(
+                "callback_url scheme '{}' is not supported (use http or https)",
+                scheme
+            ))
+        }
+    };
+
+    if !allow_private && is_private_host(host) {
+        return Err(format!(
+            "callback_url host '{}' is a private network address and is not allowed",
+            host
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `host` names this machine or a non-public address
+fn is_private_host(host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    if host == "localhost" || host.ends_with(".localhost") {
+        return true;
+    }
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .is_ok_and(|ip| !is_public_address(ip))
+}
+
+/// HTTP client for callback delivery
+///
+/// Unless `allow_private` is set, host names that resolve only to private
+/// addresses are refused, and redirects (which could lead anywhere) are not
+/// followed.
+pub fn callback_client(allow_private: bool) -> reqwest::Client {
+    let builder = reqwest::Client::builder().timeout(CALLBACK_TIMEOUT);
+    let builder = if allow_private {
+        builder
+    } else {
+        builder
+            .dns_resolver(Arc::new(PublicResolver))
+            .redirect(reqwest::redirect::Policy::none())
+    };
+    builder.build().unwrap_or_default()
+}
+
+/// POST `result` to `callback_url`, retrying with exponential backoff
+///
+/// Returns the number of attempts made on success, or the last error after
+/// `max_attempts` failures.
+pub async fn deliver(
+    client: &reqwest::Client,
+    callback_url: &str,
+    result: &AgentResult,
+    max_attempts: u32,
+    retry_delay: Duration,
+) -> Result<u32, String> {
+    let body =
+        serde_json::to_string(result).map_err(|e| format!("Failed to serialize result: {}", e))?;
+    let max_attempts = max_attempts.max(1);
+    let mut delay = retry_delay;
+    let mut last_error = String::new();
+
+    for attempt in 1..=max_attempts {
+        let response = client
+            .post(callback_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone())
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => return Ok(attempt),
+            Ok(response) => last_error = format!("callback returned {}", response.status()),
+            Err(e) => last_error = format!("callback request failed: {}", e),
+        }
+
+        tracing::warn!(
+            "Callback to {} failed (attempt {}/{}): {}",
+            callback_url,
+            attempt,
+            max_attempts,
+            last_error
+        );
+
+        if attempt < max_attempts {
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_callback_url() {
+        assert!(validate_callback_url("https://example.com/hook", false).is_ok());
+        assert!(validate_callback_url("http://93.184.216.34/hook", false).is_ok());
+
+        assert!(validate_callback_url("ftp://example.com/hook", false).is_err());
+        assert!(validate_callback_url("file:///etc/passwd", false).is_err());
+        assert!(validate_callback_url("not a url", false).is_err());
+    }
+
+    #[test]
+    fn test_private_callback_hosts_refused_unless_allowed() {
+        for url in [
+            "http://localhost:8080/hook",
+            "http://api.localhost/hook",
+            "http://127.0.0.1:8080/hook",
+            "http://10.0.0.5/hook",
+            "http://192.168.1.1/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]:8080/hook",
+        ] {
+            let err = validate_callback_url(url, false).unwrap_err();
+            assert!(err.contains("private network"), "{}: {}", url, err);
+            assert!(validate_callback_url(url, true).is_ok(), "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_client_refuses_names_resolving_to_private_addresses() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://localhost:{}/hook",
+            listener.local_addr().unwrap().port()
+        );
+        tokio::spawn(async move {
+            let app = axum::Router::new().route("/hook", axum::routing::post(|| async { "ok" }));
+            axum::serve(listener, app).await.unwrap();
+        });
+        let result = AgentResult::success("task-1", "done");
+
+        let refused = deliver(
+            &callback_client(false),
+            &url,
+            &result,
+            1,
+            Duration::from_millis(1),
+        )
+        .await;
+        assert!(refused.is_err());
+
+        let delivered = deliver(
+            &callback_client(true),
+            &url,
+            &result,
+            1,
+            Duration::from_millis(1),
+        )
+        .await;
+        assert_eq!(delivered, Ok(1));
+    }
+
+    #[tokio::test]
+    async fn test_deliver_gives_up_after_max_attempts() {
+        // Nothing listens on this port once the listener is dropped
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        drop(listener);
+
+        let result = AgentResult::success("task-1", "done");
+        let outcome = deliver(
+            &reqwest::Client::new(),
+            &url,
+            &result,
+            2,
+            Duration::from_millis(1),
+        )
+        .await;
+        assert!(outcome.is_err());
+    }
+}
@@ -1,81 +1,171 @@
 /// WebSocket handler for real-time task updates
-use crate::models::WsMessage;
-use crate::state::AppState;
+///
+/// Every task event is sent with a `seq` number. A client that reconnects
+/// with `?since=<seq>` first receives the buffered events it missed; if they
+/// are no longer buffered, it is told so with an `events_missed` message.
+///
+/// Outgoing messages go through a bounded queue drained by a writer task, so
+/// a slow client cannot make the server buffer without limit. When the queue
+/// is full, the [`LagPolicy`] decides between dropping progress events and
+/// disconnecting; the final `task_complete` message is never dropped.
+use crate::models::{SequencedMessage, WsMessage};
+use crate::state::{complete_message, AppState};
 use axum::{
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
-        Path, State,
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
     },
     response::Response,
 };
-use futures::{sink::SinkExt, stream::StreamExt};
+use futures::{
+    sink::{Sink, SinkExt},
+    stream::{SplitStream, StreamExt},
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use std::sync::Arc;
-use tokio::time::{interval, Duration};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+/// Default number of messages queued for a WebSocket client
+pub const DEFAULT_WS_BUFFER: usize = 64;
+
+/// What to do when a client reads slower than task events arrive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LagPolicy {
+    /// Skip progress events until the client catches up
+    #[default]
+    DropProgress,
+    /// Close the connection; the client can reconnect with `?since=<seq>`
+    Disconnect,
+}
+
+impl FromStr for LagPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop" | "drop_progress" => Ok(LagPolicy::DropProgress),
+            "close" | "disconnect" => Ok(LagPolicy::Disconnect),
+            other => Err(format!("Unknown lag policy: {}", other)),
+        }
+    }
+}
+
+/// Outgoing message queue limits for WebSocket clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackpressureConfig {
+    /// Messages queued per client before `on_lag` applies
+    pub buffer: usize,
+    pub on_lag: LagPolicy,
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            buffer: DEFAULT_WS_BUFFER,
+            on_lag: LagPolicy::default(),
+        }
+    }
+}
+
+/// WebSocket query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct WsQuery {
+    /// Sequence number of the last event the client received
+    pub since: Option<u64>,
+}
 
 /// WebSocket upgrade handler
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     Path(task_id): Path<String>,
+    Query(query): Query<WsQuery>,
     State(state): State<Arc<AppState>>,
 ) -> Response {
-    ws.on_upgrade(move |socket| handle_socket(socket, task_id, state))
+    ws.on_upgrade(move |socket| handle_socket(socket, task_id, query.since, state))
 }
 
 /// Handle WebSocket connection
-async fn handle_socket(socket: WebSocket, task_id: String, state: Arc<AppState>) {
-    let (mut sender, mut receiver) = socket.split();
+async fn handle_socket(
+    socket: WebSocket,
+    task_id: String,
+    since: Option<u64>,
+    state: Arc<AppState>,
+) {
+    let (sender, mut receiver) = socket.split();
+    let (mut outbound, writer) = Outbound::spawn(sender, state.ws_backpressure);
+
+    stream_events(&mut outbound, &mut receiver, &task_id, since, &state).await;
 
+    // Let the writer flush what is queued, then close
+    drop(outbound);
+    let _ = writer.await;
+}
+
+/// Queue task events for the client until the task completes or the client leaves
+async fn stream_events(
+    outbound: &mut Outbound,
+    receiver: &mut SplitStream<WebSocket>,
+    task_id: &str,
+    since: Option<u64>,
+    state: &AppState,
+) {
     // Check if task exists
-    let task_info = match state.get_task_info(&task_id).await {
-        Some(info) => info,
-        None => {
-            let error = WsMessage::Error {
-                message: format!("Task {} not found", task_id),
-            };
-            let _ = sender
-                .send(Message::Text(serde_json::to_string(&error).unwrap()))
-                .await;
-            return;
-        }
+    let Some((task_info, subscription)) = state.watch_task(task_id, since).await else {
+        let error = WsMessage::Error {
+            message: format!("Task {} not found", task_id),
+        };
+        outbound.send(&error).await;
+        return;
     };
+    let mut live = subscription.live;
 
-    // Send initial status
-    let status_msg = WsMessage::TaskStatus {
-        task_id: task_info.task_id.clone(),
-        status: task_info.status,
-        progress: task_info.progress,
+    let mut last_sent = match since {
+        // Resume where the client left off
+        Some(since) if !subscription.missed => {
+            for event in subscription.replay {
+                if !outbound.send(&event).await {
+                    return;
+                }
+                if event.is_complete() {
+                    return;
+                }
+            }
+            subscription.last_seq.max(since)
+        }
+        // New client, or the missed events are no longer buffered: start
+        // from the current status
+        _ => {
+            if let Some(since) = since {
+                if !send_missed(outbound, task_id, since).await {
+                    return;
+                }
+            }
+            let status_msg = SequencedMessage {
+                seq: subscription.last_seq,
+                message: WsMessage::TaskStatus {
+                    task_id: task_info.task_id.clone(),
+                    status: task_info.status,
+                    progress: task_info.progress,
+                },
+            };
+            if !outbound.send(&status_msg).await {
+                return;
+            }
+            subscription.last_seq
+        }
     };
 
-    if sender
-        .send(Message::Text(serde_json::to_string(&status_msg).unwrap()))
-        .await
-        .is_err()
-    {
-        return;
-    }
-
     // If task already complete, send result and close
     if task_info.status.is_terminal() {
-        if let Some(result) = state.get_task_result(&task_id).await {
-            let complete_msg = WsMessage::TaskComplete {
-                task_id: task_id.clone(),
-                success: result.success,
-                result: result.content,
-                error: result.error,
-            };
-
-            let _ = sender
-                .send(Message::Text(serde_json::to_string(&complete_msg).unwrap()))
-                .await;
-        }
+        send_result(outbound, state, task_id).await;
         return;
     }
 
-    // Poll for updates
-    let mut poll_interval = interval(Duration::from_millis(500));
-    let mut last_status = task_info.status;
-    let mut last_progress = task_info.progress;
-
     loop {
         tokio::select! {
             // Check for client messages
@@ -83,16 +173,9 @@ async fn handle_socket(socket: WebSocket, task_id: String, state: Arc<AppState>)
                 match msg {
                     Some(Ok(Message::Text(text))) => {
                         // Handle ping/pong
-                        if let Ok(ws_msg) = serde_json::from_str::<WsMessage>(&text) {
-                            if matches!(ws_msg, WsMessage::Ping) {
-                                let pong = WsMessage::Pong;
-                                if sender
-                                    .send(Message::Text(serde_json::to_string(&pong).unwrap()))
-                                    .await
-                                    .is_err()
-                                {
-                                    break;
-                                }
+                        if let Ok(WsMessage::Ping) = serde_json::from_str::<WsMessage>(&text) {
+                            if !outbound.send(&WsMessage::Pong).await {
+                                break;
                             }
                         }
                     }
@@ -106,60 +189,200 @@ async fn handle_socket(socket: WebSocket, task_id: String, state: Arc<AppState>)
                 }
             }
 
-            // Poll for task updates
-            _ = poll_interval.tick() => {
-                let current_info = match state.get_task_info(&task_id).await {
-                    Some(info) => info,
-                    None => break,
+            // Forward task events as they are published
+            event = live.recv() => {
+                let events = match event {
+                    Ok(event) => vec![event],
+                    // Fell behind the live feed: pick up the rest from the buffer
+                    Err(RecvError::Lagged(_)) => {
+                        let Some((info, resumed)) = state.watch_task(task_id, Some(last_sent)).await
+                        else {
+                            break;
+                        };
+                        if resumed.missed && !send_missed(outbound, task_id, last_sent).await {
+                            return;
+                        }
+                        if info.status.is_terminal() {
+                            send_result(outbound, state, task_id).await;
+                            return;
+                        }
+                        live = resumed.live;
+                        resumed.replay
+                    }
+                    Err(RecvError::Closed) => break,
                 };
 
-                // Send status update if changed
-                if current_info.status != last_status || current_info.progress != last_progress {
-                    last_status = current_info.status;
-                    last_progress = current_info.progress;
-
-                    let status_msg = WsMessage::TaskStatus {
-                        task_id: task_id.clone(),
-                        status: current_info.status,
-                        progress: current_info.progress,
-                    };
-
-                    if sender
-                        .send(Message::Text(serde_json::to_string(&status_msg).unwrap()))
-                        .await
-                        .is_err()
-                    {
-                        break;
+                for event in events {
+                    // Already sent while replaying
+                    if event.seq <= last_sent {
+                        continue;
+                    }
+                    last_sent = event.seq;
+                    // The final result waits for room instead of being dropped
+                    if event.is_complete() {
+                        outbound.send(&event).await;
+                        return;
+                    }
+                    if outbound.offer(&event) == Offer::Closed {
+                        return;
                     }
                 }
+            }
+        }
+    }
+}
 
-                // If task completed, send result and close
-                if current_info.status.is_terminal() {
-                    if let Some(result) = state.get_task_result(&task_id).await {
-                        let complete_msg = WsMessage::TaskComplete {
-                            task_id: task_id.clone(),
-                            success: result.success,
-                            result: result.content,
-                            error: result.error,
-                        };
+/// Tell the client that events after `since` were skipped
+async fn send_missed(outbound: &Outbound, task_id: &str, since: u64) -> bool {
+    let notice = WsMessage::EventsMissed {
+        task_id: task_id.to_string(),
+        since,
+    };
+    outbound.send(&notice).await
+}
 
-                        let _ = sender
-                            .send(Message::Text(
-                                serde_json::to_string(&complete_msg).unwrap(),
-                            ))
-                            .await;
-                    }
-                    break;
+/// Send the stored result of a finished task, numbered as when it was published
+async fn send_result(outbound: &Outbound, state: &AppState, task_id: &str) {
+    let tasks = state.tasks.read().await;
+    let Some((result, seq)) = tasks
+        .get(task_id)
+        .and_then(|stored| Some((stored.result.clone()?, stored.complete_seq?)))
+    else {
+        return;
+    };
+    drop(tasks);
+
+    let complete_msg = SequencedMessage {
+        seq,
+        message: complete_message(task_id, &result),
+    };
+    outbound.send(&complete_msg).await;
+}
+
+/// Outcome of offering a live event to a client's queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Offer {
+    Queued,
+    /// The queue was full and the event was skipped
+    Dropped,
+    /// The client is gone or was disconnected for lagging
+    Closed,
+}
+
+/// Bounded queue of messages for one client
+struct Outbound {
+    queue: mpsc::Sender<Message>,
+    on_lag: LagPolicy,
+    /// Signals the writer to close the connection immediately
+    close: Option<oneshot::Sender<CloseFrame<'static>>>,
+}
+
+impl Outbound {
+    /// Start a writer task that drains the queue into `sink`
+    fn spawn<S>(sink: S, config: BackpressureConfig) -> (Self, JoinHandle<()>)
+    where
+        S: Sink<Message> + Unpin + Send + 'static,
+    {
+        let (queue, mut pending) = mpsc::channel(config.buffer.max(1));
+        let (close, mut closed) = oneshot::channel::<CloseFrame<'static>>();
+
+        let writer = tokio::spawn(async move {
+            let mut sink = sink;
+            // The close signal is dropped along with the queue; keep draining then
+            let mut watch_close = true;
+            loop {
+                tokio::select! {
+                    biased;
+                    frame = &mut closed, if watch_close => match frame {
+                        Ok(frame) => {
+                            let _ = sink.send(Message::Close(Some(frame))).await;
+                            break;
+                        }
+                        Err(_) => watch_close = false,
+                    },
+                    message = pending.recv() => match message {
+                        Some(message) => {
+                            if sink.send(message).await.is_err() {
+                                break;
+                            }
+                        }
+                        None => break,
+                    },
                 }
             }
+            let _ = sink.close().await;
+        });
+
+        let outbound = Self {
+            queue,
+            on_lag: config.on_lag,
+            close: Some(close),
+        };
+        (outbound, writer)
+    }
+
+    /// Queue `message`, waiting for room; returns whether the client is still there
+    async fn send(&self, message: &impl Serialize) -> bool {
+        self.queue.send(to_text(message)).await.is_ok()
+    }
+
+    /// Queue a progress event without waiting, applying the lag policy when full
+    fn offer(&mut self, event: &SequencedMessage) -> Offer {
+        match self.queue.try_send(to_text(event)) {
+            Ok(()) => Offer::Queued,
+            Err(TrySendError::Full(_)) => match self.on_lag {
+                LagPolicy::DropProgress => {
+                    tracing::debug!(seq = event.seq, "WebSocket client lagging, dropped event");
+                    Offer::Dropped
+                }
+                LagPolicy::Disconnect => {
+                    if let Some(close) = self.close.take() {
+                        let _ = close.send(CloseFrame {
+                            code: close_code::AGAIN,
+                            reason: "client too slow; reconnect with ?since=<last seq>".into(),
+                        });
+                    }
+                    Offer::Closed
+                }
+            },
+            Err(TrySendError::Closed(_)) => Offer::Closed,
         }
     }
 }
 
+fn to_text(message: &impl Serialize) -> Message {
+    Message::Text(serde_json::to_string(message).unwrap())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::TaskStatus;
+    use std::time::Duration;
+
+    fn progress(seq: u64) -> SequencedMessage {
+        SequencedMessage {
+            seq,
+            message: WsMessage::TaskOutput {
+                task_id: "slow".to_string(),
+                content: format!("chunk {}", seq),
+            },
+        }
+    }
+
+    /// A client that reads one message per millisecond until the connection closes
+    fn slow_client(
+        mut client: futures::channel::mpsc::Receiver<Message>,
+    ) -> JoinHandle<Vec<Message>> {
+        tokio::spawn(async move {
+            let mut received = Vec::new();
+            while let Some(message) = client.next().await {
+                received.push(message);
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+            received
+        })
+    }
 
     #[test]
     fn test_ws_message_serialization() {
@@ -201,4 +424,96 @@ mod tests {
         assert!(json.contains("error"));
         assert!(json.contains("Something went wrong"));
     }
+
+    #[test]
+    fn test_sequenced_message_serialization() {
+        let msg = SequencedMessage {
+            seq: 7,
+            message: WsMessage::TaskPhase {
+                task_id: "task-789".to_string(),
+                phase: "planning".to_string(),
+            },
+        };
+
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["seq"], 7);
+        assert_eq!(json["type"], "task_phase");
+        assert_eq!(json["phase"], "planning");
+
+        let parsed: SequencedMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.seq, 7);
+    }
+
+    #[tokio::test]
+    async fn test_slow_client_drops_progress_but_gets_result() {
+        let (sink, client) = futures::channel::mpsc::channel::<Message>(0);
+        let client = slow_client(client);
+        let config = BackpressureConfig {
+            buffer: 2,
+            on_lag: LagPolicy::DropProgress,
+        };
+        let (mut outbound, writer) = Outbound::spawn(sink, config);
+
+        let offers: Vec<Offer> = (1..=50).map(|seq| outbound.offer(&progress(seq))).collect();
+        assert!(offers.contains(&Offer::Dropped));
+        assert!(!offers.contains(&Offer::Closed));
+
+        let complete = SequencedMessage {
+            seq: 51,
+            message: WsMessage::TaskComplete {
+                task_id: "slow".to_string(),
+                success: true,
+                result: Some("done".to_string()),
+                error: None,
+            },
+        };
+        assert!(outbound.send(&complete).await);
+        drop(outbound);
+        writer.await.unwrap();
+
+        let received: Vec<SequencedMessage> = client
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|message| match message {
+                Message::Text(text) => serde_json::from_str(&text).unwrap(),
+                other => panic!("unexpected message: {:?}", other),
+            })
+            .collect();
+        assert!(received.len() < 51);
+        assert!(received.windows(2).all(|pair| pair[0].seq < pair[1].seq));
+        let last = received.last().unwrap();
+        assert_eq!(last.seq, 51);
+        assert!(last.is_complete());
+    }
+
+    #[tokio::test]
+    async fn test_slow_client_disconnected_when_configured() {
+        let (sink, client) = futures::channel::mpsc::channel::<Message>(0);
+        let config = BackpressureConfig {
+            buffer: 1,
+            on_lag: LagPolicy::Disconnect,
+        };
+        let (mut outbound, writer) = Outbound::spawn(sink, config);
+
+        let offers: Vec<Offer> = (1..=10).map(|seq| outbound.offer(&progress(seq))).collect();
+        assert_eq!(offers.last(), Some(&Offer::Closed));
+
+        let received = slow_client(client).await.unwrap();
+        writer.await.unwrap();
+        match received.last() {
+            Some(Message::Close(Some(frame))) => {
+                assert_eq!(frame.code, close_code::AGAIN);
+                assert!(frame.reason.contains("too slow"));
+            }
+            other => panic!("expected close frame, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_lag_policy_from_str() {
+        assert_eq!("drop".parse(), Ok(LagPolicy::DropProgress));
+        assert_eq!("close".parse(), Ok(LagPolicy::Disconnect));
+        assert!("ignore".parse::<LagPolicy>().is_err());
+    }
 }
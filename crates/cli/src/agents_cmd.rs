@@ -1,18 +1,51 @@
 /// Agent information and listing command
 use bodhya_agent_code::CodeAgent;
 use bodhya_agent_mail::MailAgent;
-use bodhya_core::{Agent, Result};
+use bodhya_core::{Agent, AgentCapability, Result};
 
-/// List all available agents and their capabilities
-pub fn list_agents() -> Result<()> {
+/// All built-in agents
+fn builtin_agents() -> Vec<Box<dyn Agent>> {
+    vec![Box::new(CodeAgent::new()), Box::new(MailAgent::new())]
+}
+
+/// Whether a capability matches the optional intent and domain filters
+///
+/// The intent filter matches any declared intent or keyword; both filters
+/// are case-insensitive.
+fn matches_filter(cap: &AgentCapability, intent: Option<&str>, domain: Option<&str>) -> bool {
+    let domain_ok = domain.map_or(true, |domain| cap.domain.eq_ignore_ascii_case(domain));
+    let intent_ok = intent.map_or(true, |intent| {
+        cap.intents
+            .iter()
+            .chain(cap.keywords.iter())
+            .any(|candidate| candidate.eq_ignore_ascii_case(intent))
+    });
+
+    domain_ok && intent_ok
+}
+
+/// Agents whose capability matches the given filters
+fn filter_agents(
+    agents: Vec<Box<dyn Agent>>,
+    intent: Option<&str>,
+    domain: Option<&str>,
+) -> Vec<Box<dyn Agent>> {
+    agents
+        .into_iter()
+        .filter(|agent| matches_filter(&agent.capability(), intent, domain))
+        .collect()
+}
+
+/// List available agents and their capabilities, optionally filtered by intent or domain
+pub fn list_agents(intent: Option<&str>, domain: Option<&str>) -> Result<()> {
     println!("\nAvailable Agents:");
     println!("{}", "=".repeat(80));
 
-    // Create agent instances
-    let code_agent = CodeAgent::new();
-    let mail_agent = MailAgent::new();
+    let agents = filter_agents(builtin_agents(), intent, domain);
 
-    let agents: Vec<Box<dyn Agent>> = vec![Box::new(code_agent), Box::new(mail_agent)];
+    if agents.is_empty() {
+        println!("\nNo agents match the given filters.");
+    }
 
     for agent in agents {
         let cap = agent.capability();
@@ -84,10 +117,50 @@ mod tests {
 
     #[test]
     fn test_list_agents() {
-        let result = list_agents();
+        let result = list_agents(None, None);
         assert!(result.is_ok());
     }
 
+    fn filtered_ids(intent: Option<&str>, domain: Option<&str>) -> Vec<String> {
+        filter_agents(builtin_agents(), intent, domain)
+            .iter()
+            .map(|agent| agent.id().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_agents_by_domain() {
+        let code_id = CodeAgent::new().id().to_string();
+        let mail_id = MailAgent::new().id().to_string();
+
+        let ids = filtered_ids(None, Some("code"));
+        assert!(ids.contains(&code_id));
+        assert!(!ids.contains(&mail_id));
+
+        assert_eq!(filtered_ids(None, Some("CODE")), ids);
+        assert!(filtered_ids(None, Some("nope")).is_empty());
+    }
+
+    #[test]
+    fn test_filter_agents_by_intent() {
+        let mail_cap = MailAgent::new().capability();
+        let intent = mail_cap.intents[0].to_uppercase();
+
+        let ids = filtered_ids(Some(&intent), None);
+        assert!(ids.contains(&MailAgent::new().id().to_string()));
+        assert!(filtered_ids(Some("no-such-intent"), None).is_empty());
+    }
+
+    #[test]
+    fn test_matches_filter_uses_keywords() {
+        let cap = AgentCapability::new("code", vec!["generate".to_string()], "Code")
+            .with_keywords(vec!["rust".to_string()]);
+        assert!(matches_filter(&cap, Some("Generate"), None));
+        assert!(matches_filter(&cap, Some("RUST"), Some("Code")));
+        assert!(!matches_filter(&cap, Some("rust"), Some("mail")));
+        assert!(matches_filter(&cap, None, None));
+    }
+
     #[test]
     fn test_show_agent_code() {
         let result = show_agent("code");
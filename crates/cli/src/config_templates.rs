@@ -102,6 +102,7 @@ impl ConfigTemplate {
             tools: Default::default(),
             paths: PathsConfig::default(),
             logging: LoggingConfig::default(),
+            routing: Default::default(),
         }
     }
 
@@ -0,0 +1,106 @@
+//! Free disk space checks before model installs
+//!
+//! Model files run to several gigabytes, so installs check the models
+//! directory's filesystem has room before downloading anything.
+
+use bodhya_core::{Error, Result};
+use std::io;
+use std::path::Path;
+
+use crate::utils;
+
+/// Free space kept in reserve beyond the model's own size
+pub const INSTALL_MARGIN_BYTES: u64 = 1_000_000_000;
+
+/// Reports the space available to the current user on a filesystem
+pub trait FreeSpace {
+    /// Bytes available on the filesystem holding `path`
+    fn available_bytes(&self, path: &Path) -> io::Result<u64>;
+}
+
+/// Queries the operating system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemFreeSpace;
+
+impl FreeSpace for SystemFreeSpace {
+    fn available_bytes(&self, path: &Path) -> io::Result<u64> {
+        fs2::available_space(path)
+    }
+}
+
+/// Fail unless the filesystem holding `dir` has `needed` bytes free plus
+/// [`INSTALL_MARGIN_BYTES`]
+///
+/// `dir` need not exist yet; its nearest existing ancestor is checked. When
+/// free space cannot be determined the check is skipped with a warning.
+pub fn ensure_free_space(
+    probe: &dyn FreeSpace,
+    model_id: &str,
+    dir: &Path,
+    needed: u64,
+) -> Result<()> {
+    let Some(existing) = dir.ancestors().find(|path| path.exists()) else {
+        return Ok(());
+    };
+    let available = match probe.available_bytes(existing) {
+        Ok(available) => available,
+        Err(e) => {
+            tracing::warn!(
+                "Could not determine free space on {}: {}",
+                existing.display(),
+                e
+            );
+            return Ok(());
+        }
+    };
+
+    let required = needed.saturating_add(INSTALL_MARGIN_BYTES);
+    if available < required {
+        return Err(Error::Io(format!(
+            "Not enough disk space to install '{}': it needs {} (plus {} headroom) \
+             but only {} is free on {}. Free up space and try again.",
+            model_id,
+            utils::format_bytes(needed),
+            utils::format_bytes(INSTALL_MARGIN_BYTES),
+            utils::format_bytes(available),
+            existing.display()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    struct Fixed(u64);
+
+    impl FreeSpace for Fixed {
+        fn available_bytes(&self, _path: &Path) -> io::Result<u64> {
+            Ok(self.0)
+        }
+    }
+
+    #[test]
+    fn test_requires_margin_beyond_model_size() {
+        let dir = TempDir::new().unwrap();
+        let needed = 4_000_000_000;
+
+        assert!(ensure_free_space(&Fixed(needed), "m", dir.path(), needed).is_err());
+        assert!(ensure_free_space(
+            &Fixed(needed + INSTALL_MARGIN_BYTES),
+            "m",
+            &dir.path().join("not/yet/created"),
+            needed
+        )
+        .is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_system_free_space() {
+        let dir = TempDir::new().unwrap();
+        assert!(SystemFreeSpace.available_bytes(dir.path()).unwrap() > 0);
+    }
+}
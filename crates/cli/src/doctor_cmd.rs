@@ -0,0 +1,400 @@
+/// Environment diagnostics
+///
+/// This module implements the `bodhya doctor` command, which runs a list of
+/// named checks (config, models, toolchain, MCP servers) and prints a
+/// pass/warn/fail report with a hint for fixing each problem. The command
+/// fails if any critical check fails.
+use bodhya_core::{AppConfig, Error, McpServerConfig, Result};
+use bodhya_model_registry::{ModelDownloader, ModelRegistry};
+use bodhya_tools_mcp::{McpClient, StdioMcpClient};
+use colored::Colorize;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::utils;
+
+/// How long to wait for an MCP server to connect and list its tools
+pub const MCP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of a single diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticStatus {
+    /// Everything is in order
+    Pass,
+    /// Something may not work, but Bodhya can still run
+    Warn,
+    /// Bodhya cannot work until this is fixed
+    Fail,
+}
+
+/// Result of one named check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Short name of what was checked (e.g., "config")
+    pub name: String,
+    /// Outcome
+    pub status: DiagnosticStatus,
+    /// What was found
+    pub message: String,
+    /// How to fix it, for warnings and failures
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    /// A passing check
+    pub fn pass(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: DiagnosticStatus::Pass,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    /// A non-critical problem
+    pub fn warn(
+        name: impl Into<String>,
+        message: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            status: DiagnosticStatus::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    /// A critical problem
+    pub fn fail(
+        name: impl Into<String>,
+        message: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            status: DiagnosticStatus::Fail,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    /// Render as a report line, with the hint indented below
+    pub fn render(&self, color: bool) -> String {
+        let (glyph, label) = match self.status {
+            DiagnosticStatus::Pass => ("✓", "PASS"),
+            DiagnosticStatus::Warn => ("⚠", "WARN"),
+            DiagnosticStatus::Fail => ("✗", "FAIL"),
+        };
+        let line = format!("{} [{}] {}: {}", glyph, label, self.name, self.message);
+        let mut output = match (color, self.status) {
+            (false, _) => line,
+            (true, DiagnosticStatus::Pass) => line.green().to_string(),
+            (true, DiagnosticStatus::Warn) => line.yellow().to_string(),
+            (true, DiagnosticStatus::Fail) => line.red().bold().to_string(),
+        };
+        if let Some(hint) = &self.hint {
+            output.push_str(&format!("\n    → {}", hint));
+        }
+        output
+    }
+}
+
+/// Run every diagnostic and print the report
+///
+/// Returns an error (and so a non-zero exit code) if any check failed.
+pub async fn run_doctor() -> Result<()> {
+    let config_path = utils::default_config_path()?;
+    let manifest_path = utils::models_manifest_path()?;
+    let models_dir = utils::models_dir()?;
+
+    let (config_check, config) = check_config(&config_path);
+    let mut diagnostics = vec![config_check];
+    diagnostics.push(check_models(&manifest_path, &models_dir).await);
+    diagnostics.push(check_program(
+        "cargo",
+        "Install Rust from https://rustup.rs to build and test generated code",
+    ));
+    diagnostics.push(check_program(
+        "rustfmt",
+        "Run 'rustup component add rustfmt' to format generated code",
+    ));
+    if let Some(config) = &config {
+        for server in config.tools.enabled_mcp_servers() {
+            diagnostics.push(check_mcp_server(server).await);
+        }
+    }
+
+    let color = utils::color_enabled();
+    println!("Bodhya doctor\n");
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic.render(color));
+    }
+
+    let count = |status| diagnostics.iter().filter(|d| d.status == status).count();
+    let failed = count(DiagnosticStatus::Fail);
+    println!(
+        "\n{} passed, {} warnings, {} failed",
+        count(DiagnosticStatus::Pass),
+        count(DiagnosticStatus::Warn),
+        failed
+    );
+
+    if failed > 0 {
+        return Err(Error::Config(format!(
+            "{} critical check(s) failed",
+            failed
+        )));
+    }
+    Ok(())
+}
+
+/// Check the config file exists and parses, returning it if it does
+pub fn check_config(path: &Path) -> (Diagnostic, Option<AppConfig>) {
+    const NAME: &str = "config";
+
+    if !path.exists() {
+        let diagnostic = Diagnostic::fail(
+            NAME,
+            format!("{} not found", path.display()),
+            "Run 'bodhya init' to create a configuration",
+        );
+        return (diagnostic, None);
+    }
+
+    match AppConfig::from_file(path) {
+        Ok(config) => {
+            let diagnostic = Diagnostic::pass(
+                NAME,
+                format!("{} is valid (profile '{}')", path.display(), config.profile),
+            );
+            (diagnostic, Some(config))
+        }
+        Err(e) => {
+            let diagnostic = Diagnostic::fail(
+                NAME,
+                format!("{} is invalid: {}", path.display(), e),
+                "Fix the file, or run 'bodhya init --force' to regenerate it",
+            );
+            (diagnostic, None)
+        }
+    }
+}
+
+/// Check the models directory exists and holds at least one installed model
+/// whose files match the manifest checksums
+pub async fn check_models(manifest_path: &Path, models_dir: &Path) -> Diagnostic {
+    const NAME: &str = "models";
+
+    if !models_dir.is_dir() {
+        return Diagnostic::fail(
+            NAME,
+            format!("models directory {} not found", models_dir.display()),
+            "Run 'bodhya init' to create it",
+        );
+    }
+    if !manifest_path.exists() {
+        return Diagnostic::fail(
+            NAME,
+            format!("models manifest {} not found", manifest_path.display()),
+            "Run 'bodhya init' to create it",
+        );
+    }
+    let registry = match ModelRegistry::from_manifest_file(manifest_path, models_dir) {
+        Ok(registry) => registry,
+        Err(e) => {
+            return Diagnostic::fail(NAME, e.to_string(), "Fix the models manifest");
+        }
+    };
+
+    let mut ids = registry.manifest().model_ids();
+    ids.sort();
+    let installed: Vec<&String> = ids
+        .iter()
+        .filter(|id| registry.is_model_installed(id))
+        .collect();
+    if installed.is_empty() {
+        return Diagnostic::fail(
+            NAME,
+            "no models installed",
+            format!(
+                "Install one with 'bodhya models install <id>' (available: {})",
+                ids.join(", ")
+            ),
+        );
+    }
+
+    let downloader = ModelDownloader::new();
+    let mut verified = Vec::new();
+    let mut corrupt = Vec::new();
+    for id in installed {
+        let Some(definition) = registry.manifest().get_model(id) else {
+            continue;
+        };
+        let mut ok = true;
+        for (path, (_, checksum)) in registry.model_files(id).iter().zip(definition.sources()) {
+            if downloader.verify_checksum(path, checksum).await.is_err() {
+                ok = false;
+                break;
+            }
+        }
+        if ok {
+            verified.push(id.as_str());
+        } else {
+            corrupt.push(id.as_str());
+        }
+    }
+
+    match (verified.is_empty(), corrupt.is_empty()) {
+        (false, true) => Diagnostic::pass(
+            NAME,
+            format!("{} installed and verified", verified.join(", ")),
+        ),
+        (false, false) => Diagnostic::warn(
+            NAME,
+            format!(
+                "{} verified, but {} failed checksum verification",
+                verified.join(", "),
+                corrupt.join(", ")
+            ),
+            "Remove and reinstall the failing models with 'bodhya models remove/install <id>'",
+        ),
+        (true, _) => Diagnostic::fail(
+            NAME,
+            format!(
+                "no installed model passed checksum verification ({})",
+                corrupt.join(", ")
+            ),
+            "Remove and reinstall with 'bodhya models remove <id>' and 'bodhya models install <id>'",
+        ),
+    }
+}
+
+/// Check `program --version` runs, failing with `hint` if it does not
+pub fn check_program(program: &str, hint: &str) -> Diagnostic {
+    match Command::new(program).arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let version = stdout.lines().next().unwrap_or("").trim();
+            Diagnostic::pass(program, format!("found ({})", version))
+        }
+        Ok(output) => Diagnostic::fail(
+            program,
+            format!("'{} --version' exited with {}", program, output.status),
+            hint,
+        ),
+        Err(e) => Diagnostic::fail(program, format!("not found on PATH ({})", e), hint),
+    }
+}
+
+/// Check an MCP server connects and lists its tools within [`MCP_TIMEOUT`]
+///
+/// MCP servers are optional, so an unreachable one is only a warning.
+pub async fn check_mcp_server(server: &McpServerConfig) -> Diagnostic {
+    let name = format!("mcp:{}", server.name);
+    let hint = format!(
+        "Check the server with 'bodhya tools test-mcp {}', or disable it with 'bodhya tools toggle-mcp {}'",
+        server.name, server.name
+    );
+
+    let mut client = StdioMcpClient::new();
+    let probe = async {
+        client.connect(server).await?;
+        let tools = client.list_tools().await;
+        let _ = client.disconnect().await;
+        tools
+    };
+    match tokio::time::timeout(MCP_TIMEOUT, probe).await {
+        Ok(Ok(tools)) => Diagnostic::pass(name, format!("reachable, {} tools", tools.len())),
+        Ok(Err(e)) => Diagnostic::warn(name, format!("unreachable: {}", e), hint),
+        Err(_) => Diagnostic::warn(
+            name,
+            format!("no response within {}s", MCP_TIMEOUT.as_secs()),
+            hint,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const MANIFEST: &str = r#"
+models:
+  tiny:
+    role: coder
+    domain: code
+    display_name: "Tiny"
+    source_url: "https://example.com/tiny.gguf"
+    size_gb: 0.1
+    checksum: "sha256:9a129038d9a00aed0cf6a7ea059ca50a813449061ab87848cf1a13eafdf33b2c"
+    backend: local
+"#;
+
+    #[test]
+    fn test_missing_config_fails() {
+        let dir = TempDir::new().unwrap();
+        let (diagnostic, config) = check_config(&dir.path().join("default.yaml"));
+
+        assert_eq!(diagnostic.status, DiagnosticStatus::Fail);
+        assert!(diagnostic.hint.unwrap().contains("bodhya init"));
+        assert!(config.is_none());
+    }
+
+    #[test]
+    fn test_malformed_config_fails() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("default.yaml");
+        std::fs::write(&path, "profile: [unclosed").unwrap();
+
+        let (diagnostic, config) = check_config(&path);
+
+        assert_eq!(diagnostic.status, DiagnosticStatus::Fail);
+        assert!(diagnostic.message.contains("invalid"));
+        assert!(config.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_models_need_an_installed_verified_model() {
+        let dir = TempDir::new().unwrap();
+        let manifest = dir.path().join("models.yaml");
+        let models_dir = dir.path().join("models");
+
+        let missing_dir = check_models(&manifest, &models_dir).await;
+        assert_eq!(missing_dir.status, DiagnosticStatus::Fail);
+
+        std::fs::create_dir(&models_dir).unwrap();
+        std::fs::write(&manifest, MANIFEST).unwrap();
+        let none_installed = check_models(&manifest, &models_dir).await;
+        assert_eq!(none_installed.status, DiagnosticStatus::Fail);
+        assert!(none_installed.hint.unwrap().contains("tiny"));
+
+        std::fs::write(models_dir.join("tiny.gguf"), "corrupt").unwrap();
+        let corrupt = check_models(&manifest, &models_dir).await;
+        assert_eq!(corrupt.status, DiagnosticStatus::Fail);
+
+        std::fs::write(models_dir.join("tiny.gguf"), "weights").unwrap();
+        let verified = check_models(&manifest, &models_dir).await;
+        assert_eq!(verified.status, DiagnosticStatus::Pass, "{:?}", verified);
+    }
+
+    #[test]
+    fn test_missing_program_fails() {
+        let diagnostic = check_program("bodhya-no-such-program", "install it");
+
+        assert_eq!(diagnostic.status, DiagnosticStatus::Fail);
+        assert_eq!(diagnostic.hint.as_deref(), Some("install it"));
+    }
+
+    #[test]
+    fn test_render_includes_hint() {
+        let diagnostic = Diagnostic::warn("mcp:fs", "unreachable", "check it");
+
+        assert_eq!(
+            diagnostic.render(false),
+            "⚠ [WARN] mcp:fs: unreachable\n    → check it"
+        );
+    }
+}
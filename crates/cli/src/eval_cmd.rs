@@ -0,0 +1,198 @@
+/// Evaluation commands
+///
+/// This module implements model evaluation commands:
+/// - `bodhya eval compare --model-a <id> --model-b <id> [--case <id>]`
+use bodhya_core::{Error, ModelBackend, Result};
+use bodhya_eval_common::{BackendComparison, ScoredResult};
+use bodhya_model_registry::{LocalMistralBackend, ModelInfo, ModelRegistry};
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::utils;
+
+/// Run the standard evaluation cases of the models' domain through both
+/// models and report which one scored higher
+pub async fn compare_models(model_a: &str, model_b: &str, case: Option<&str>) -> Result<()> {
+    if model_a == model_b {
+        return Err(Error::InvalidInput(
+            "Pick two different models to compare".to_string(),
+        ));
+    }
+
+    let manifest_path = utils::models_manifest_path()?;
+    let models_dir = utils::models_dir()?;
+
+    if !manifest_path.exists() {
+        return Err(Error::Config(
+            "Models manifest not found. Run 'bodhya init' first.".to_string(),
+        ));
+    }
+
+    let registry = ModelRegistry::from_manifest_file(&manifest_path, &models_dir)?;
+    let info_a = installed_model(&registry, model_a)?;
+    let info_b = installed_model(&registry, model_b)?;
+
+    let domain = info_a.definition.domain.clone();
+    if info_b.definition.domain != domain {
+        return Err(Error::InvalidInput(format!(
+            "Models serve different domains: '{}' is {}, '{}' is {}",
+            model_a, domain, model_b, info_b.definition.domain
+        )));
+    }
+
+    let backend_a = local_backend(&info_a);
+    let backend_b = local_backend(&info_b);
+
+    println!(
+        "Comparing {} (A) and {} (B) on {} evaluation cases",
+        model_a, model_b, domain
+    );
+
+    let winners = match domain.as_str() {
+        "code" => {
+            use bodhya_eval_code_agent::{compare_backends, get_standard_cases};
+            let cases = select_cases(get_standard_cases(), |c| &c.id, case)?;
+            run_comparisons(&cases, |c| {
+                compare_backends(c, backend_a.clone(), backend_b.clone())
+            })
+            .await
+        }
+        "mail" => {
+            use bodhya_eval_mail_agent::{compare_backends, get_standard_cases};
+            let cases = select_cases(get_standard_cases(), |c| &c.id, case)?;
+            run_comparisons(&cases, |c| {
+                compare_backends(c, backend_a.clone(), backend_b.clone())
+            })
+            .await
+        }
+        other => {
+            return Err(Error::InvalidInput(format!(
+                "No evaluation cases for domain '{}'",
+                other
+            )))
+        }
+    };
+
+    println!("\n{}", tally(model_a, model_b, &winners));
+    Ok(())
+}
+
+/// Compare the backends on each case in turn, printing each comparison
+///
+/// Returns each case's winner (None for a tie).
+async fn run_comparisons<'a, T, R, Fut>(
+    cases: &'a [T],
+    compare: impl Fn(&'a T) -> Fut,
+) -> Vec<Option<String>>
+where
+    R: ScoredResult,
+    Fut: Future<Output = BackendComparison<R>>,
+{
+    let mut winners = Vec::new();
+    for test_case in cases {
+        let comparison = compare(test_case).await;
+        comparison.print_summary();
+        winners.push(comparison.winner_id().map(str::to_string));
+    }
+    winners
+}
+
+/// Look up `model_id`, requiring its files to be installed
+fn installed_model(registry: &ModelRegistry, model_id: &str) -> Result<ModelInfo> {
+    let info = registry.get_model_by_id(model_id)?;
+    if !info.installed {
+        return Err(Error::Config(format!(
+            "Model '{}' is not installed. Run 'bodhya models install {}' first.",
+            model_id, model_id
+        )));
+    }
+    Ok(info)
+}
+
+fn local_backend(info: &ModelInfo) -> Arc<dyn ModelBackend> {
+    Arc::new(LocalMistralBackend::from_model_path(
+        info.model_path.clone(),
+        info.id.clone(),
+    ))
+}
+
+/// All of `cases`, or only the one with id `case` when given
+fn select_cases<T>(
+    cases: Vec<T>,
+    id: impl Fn(&T) -> &String,
+    case: Option<&str>,
+) -> Result<Vec<T>> {
+    let Some(case) = case else {
+        return Ok(cases);
+    };
+    let known: Vec<String> = cases.iter().map(|c| id(c).clone()).collect();
+    let selected: Vec<T> = cases.into_iter().filter(|c| id(c) == case).collect();
+    if selected.is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "Unknown evaluation case '{}'. Available: {}",
+            case,
+            known.join(", ")
+        )));
+    }
+    Ok(selected)
+}
+
+/// One-line verdict over all cases, given each case's winner (None for a tie)
+fn tally(model_a: &str, model_b: &str, winners: &[Option<String>]) -> String {
+    let wins = |model: &str| {
+        winners
+            .iter()
+            .filter(|w| w.as_deref() == Some(model))
+            .count()
+    };
+    let (wins_a, wins_b) = (wins(model_a), wins(model_b));
+    let ties = winners.len() - wins_a - wins_b;
+
+    let verdict = match wins_a.cmp(&wins_b) {
+        std::cmp::Ordering::Greater => format!("{} wins", model_a),
+        std::cmp::Ordering::Less => format!("{} wins", model_b),
+        std::cmp::Ordering::Equal => "No clear winner".to_string(),
+    };
+    format!(
+        "{} ({}: {}, {}: {}, ties: {})",
+        verdict, model_a, wins_a, model_b, wins_b, ties
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tally_counts_wins_per_model() {
+        let winners = vec![
+            Some("qwen".to_string()),
+            None,
+            Some("qwen".to_string()),
+            Some("mistral".to_string()),
+        ];
+
+        assert_eq!(
+            tally("mistral", "qwen", &winners),
+            "qwen wins (mistral: 1, qwen: 2, ties: 1)"
+        );
+        assert_eq!(
+            tally("mistral", "qwen", &[None]),
+            "No clear winner (mistral: 0, qwen: 0, ties: 1)"
+        );
+    }
+
+    #[test]
+    fn test_select_cases_by_id() {
+        let cases = vec!["hello".to_string(), "fib".to_string()];
+
+        assert_eq!(
+            select_cases(cases.clone(), |c| c, Some("fib")).unwrap(),
+            vec!["fib".to_string()]
+        );
+        assert_eq!(select_cases(cases.clone(), |c| c, None).unwrap().len(), 2);
+
+        let err = select_cases(cases, |c| c, Some("nope")).unwrap_err();
+        assert!(err.to_string().contains("Available: hello, fib"));
+    }
+}
@@ -0,0 +1,90 @@
+/// Process exit codes for CLI errors
+///
+/// Each kind of `bodhya_core::Error` exits with its own code so scripts can
+/// tell failures apart:
+///
+/// | Code | Meaning                                           |
+/// |------|---------------------------------------------------|
+/// | 1    | Other errors (serialization, internal)            |
+/// | 2    | Invalid command line (reported by clap)           |
+/// | 3    | Model error, model not installed, bad checksum    |
+/// | 4    | Tool error                                        |
+/// | 5    | Agent error, agent not found, task failed         |
+/// | 6    | IO error                                          |
+/// | 7    | Network error                                     |
+/// | 8    | Invalid input                                     |
+/// | 9    | Engagement mode violation                         |
+/// | 10   | Configuration error                               |
+use bodhya_core::Error;
+
+pub const GENERAL: i32 = 1;
+/// Left to clap, which exits with 2 on command-line usage errors
+pub const USAGE: i32 = 2;
+pub const MODEL: i32 = 3;
+pub const TOOL: i32 = 4;
+pub const AGENT: i32 = 5;
+pub const IO: i32 = 6;
+pub const NETWORK: i32 = 7;
+pub const INVALID_INPUT: i32 = 8;
+pub const ENGAGEMENT: i32 = 9;
+pub const CONFIG: i32 = 10;
+
+/// Exit code for `error`
+pub fn error_exit_code(error: &Error) -> i32 {
+    match error {
+        Error::Config(_) => CONFIG,
+        Error::Model(_)
+        | Error::ModelUnavailable(_)
+        | Error::ModelNotFound(_)
+        | Error::ChecksumMismatch(_) => MODEL,
+        Error::Tool(_) => TOOL,
+        Error::Agent(_)
+        | Error::AgentNotFound(_)
+        | Error::NoAgentMatched { .. }
+        | Error::TaskFailed(_) => AGENT,
+        Error::Io(_) => IO,
+        Error::Network(_) => NETWORK,
+        Error::InvalidInput(_) => INVALID_INPUT,
+        Error::EngagementViolation(_) => ENGAGEMENT,
+        Error::Serialization(_) | Error::Internal(_) => GENERAL,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_variant_has_documented_code() {
+        let s = || "x".to_string();
+        let cases = [
+            (Error::Config(s()), 10),
+            (Error::Model(s()), 3),
+            (Error::ModelUnavailable(s()), 3),
+            (Error::ModelNotFound(s()), 3),
+            (Error::ChecksumMismatch(s()), 3),
+            (Error::Tool(s()), 4),
+            (Error::Agent(s()), 5),
+            (Error::AgentNotFound(s()), 5),
+            (
+                Error::NoAgentMatched {
+                    message: s(),
+                    suggestions: Vec::new(),
+                },
+                5,
+            ),
+            (Error::TaskFailed(s()), 5),
+            (Error::Io(s()), 6),
+            (Error::Network(s()), 7),
+            (Error::InvalidInput(s()), 8),
+            (Error::EngagementViolation(s()), 9),
+            (Error::Serialization(s()), 1),
+            (Error::Internal(s()), 1),
+        ];
+
+        for (error, code) in cases {
+            assert_eq!(error_exit_code(&error), code, "{:?}", error);
+            assert_ne!(code, USAGE, "{:?}", error);
+        }
+    }
+}
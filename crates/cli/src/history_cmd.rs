@@ -3,12 +3,17 @@
 /// This module provides the `bodhya history` command for viewing
 /// past task execution history and metrics.
 use bodhya_core::Result;
-use bodhya_storage::{Session, SqliteStorage, TaskRecord, TaskStatus};
-use std::path::PathBuf;
+use bodhya_storage::{
+    PruneStats, Session, SqliteStorage, TaskFilter, TaskRecord, TaskStatus, TimeBucket,
+};
+use std::io::Write;
+use std::path::Path;
+
+use crate::utils;
 
 /// Show execution history
 pub fn show_history(limit: usize) -> Result<()> {
-    let storage = open_storage()?;
+    let storage = utils::open_storage()?;
 
     let sessions = storage.list_sessions(limit)?;
 
@@ -29,8 +34,17 @@ pub fn show_history(limit: usize) -> Result<()> {
 }
 
 /// Show stats for a specific domain
-pub fn show_stats(domain: &str) -> Result<()> {
-    let storage = open_storage()?;
+///
+/// With `trend` set, also prints the average quality score per `bucket`
+/// ("day" or "week").
+pub fn show_stats(domain: &str, trend: bool, bucket: &str) -> Result<()> {
+    let bucket = TimeBucket::parse(bucket).ok_or_else(|| {
+        bodhya_core::Error::InvalidInput(format!(
+            "Invalid bucket '{}'. Valid buckets: day, week",
+            bucket
+        ))
+    })?;
+    let storage = utils::open_storage()?;
 
     let stats = storage.get_domain_stats(domain)?;
 
@@ -41,9 +55,134 @@ pub fn show_stats(domain: &str) -> Result<()> {
     println!("Failed tasks:     {}", stats.failed_tasks);
     println!("Success rate:     {:.1}%", stats.success_rate() * 100.0);
 
+    if trend {
+        println!();
+        print!("{}", format_trend(&storage, domain, bucket)?);
+    }
+
+    Ok(())
+}
+
+/// Format the quality-score trend for a domain
+fn format_trend(storage: &SqliteStorage, domain: &str, bucket: TimeBucket) -> Result<String> {
+    let trend = storage.quality_trend(domain, bucket)?;
+
+    let mut output = format!("Quality Trend (per {}):\n", bucket.as_str());
+    if trend.is_empty() {
+        output.push_str("  No quality scores recorded yet\n");
+        return Ok(output);
+    }
+
+    for (start, score) in trend {
+        output.push_str(&format!(
+            "  {}  {:>5.1}/100\n",
+            start.format("%Y-%m-%d"),
+            score
+        ));
+    }
+
+    Ok(output)
+}
+
+/// Prune sessions older than the given number of days
+pub fn prune_history(older_than_days: u32) -> Result<()> {
+    let storage = utils::open_storage()?;
+
+    let stats = prune_storage(&storage, older_than_days)?;
+
+    println!(
+        "✓ Pruned history older than {} day(s): {} session(s), {} task(s), {} metric record(s) removed",
+        older_than_days, stats.sessions_removed, stats.tasks_removed, stats.metrics_removed
+    );
+
+    Ok(())
+}
+
+/// Prune sessions from storage that started more than `older_than_days` ago
+fn prune_storage(storage: &SqliteStorage, older_than_days: u32) -> Result<PruneStats> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(older_than_days));
+    storage.prune_sessions_before(cutoff)
+}
+
+/// Output format for `bodhya history export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Some(ExportFormat::Json),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Export task history to a file (or stdout when `out` is None)
+pub fn export_history(
+    format: &str,
+    out: Option<&Path>,
+    domain: Option<String>,
+    since: Option<&str>,
+) -> Result<()> {
+    let format = ExportFormat::parse(format).ok_or_else(|| {
+        bodhya_core::Error::InvalidInput(format!(
+            "Invalid export format '{}'. Valid formats: json, csv",
+            format
+        ))
+    })?;
+    let filter = build_filter(domain, since)?;
+    let storage = utils::open_storage()?;
+
+    match out {
+        Some(path) => {
+            let file = std::fs::File::create(path)?;
+            let count = export_tasks(&storage, format, &filter, std::io::BufWriter::new(file))?;
+            println!("✓ Exported {} task(s) to {}", count, path.display());
+        }
+        None => {
+            export_tasks(&storage, format, &filter, std::io::stdout().lock())?;
+        }
+    }
+
     Ok(())
 }
 
+/// Build an export filter from CLI arguments (`since` is YYYY-MM-DD)
+fn build_filter(domain: Option<String>, since: Option<&str>) -> Result<TaskFilter> {
+    let mut filter = TaskFilter::new();
+    if let Some(domain) = domain {
+        filter = filter.with_domain(domain);
+    }
+    if let Some(since) = since {
+        let date = chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d").map_err(|_| {
+            bodhya_core::Error::InvalidInput(format!(
+                "Invalid date '{}'. Expected format: YYYY-MM-DD",
+                since
+            ))
+        })?;
+        filter = filter.with_since(date.and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+    Ok(filter)
+}
+
+/// Write matching tasks in the requested format
+fn export_tasks(
+    storage: &SqliteStorage,
+    format: ExportFormat,
+    filter: &TaskFilter,
+    writer: impl Write,
+) -> Result<usize> {
+    match format {
+        ExportFormat::Json => storage.export_tasks_json(writer, filter),
+        ExportFormat::Csv => storage.export_tasks_csv(writer, filter),
+    }
+}
+
 /// Print a session and its tasks
 fn print_session(storage: &SqliteStorage, session: &Session) -> Result<()> {
     println!(
@@ -121,30 +260,10 @@ fn print_task(task: &TaskRecord) {
     }
 }
 
-/// Open the storage database
-fn open_storage() -> Result<SqliteStorage> {
-    let db_path = get_db_path()?;
-
-    // Create parent directory if it doesn't exist
-    if let Some(parent) = db_path.parent() {
-        if !parent.exists() {
-            std::fs::create_dir_all(parent)?;
-        }
-    }
-
-    SqliteStorage::new(db_path)
-}
-
-/// Get the database path (~/.bodhya/storage/history.db)
-fn get_db_path() -> Result<PathBuf> {
-    let bodhya_home = crate::utils::bodhya_home()?;
-    let storage_dir = bodhya_home.join("storage");
-    Ok(storage_dir.join("history.db"))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bodhya_storage::QualityMetrics;
 
     #[test]
     fn test_show_history_empty() {
@@ -157,18 +276,11 @@ mod tests {
     #[test]
     fn test_show_stats_empty() {
         // With empty storage, should not error
-        let result = show_stats("code");
+        let result = show_stats("code", false, "day");
         // May fail if storage can't be opened, but shouldn't panic
         let _ = result;
     }
 
-    #[test]
-    fn test_get_db_path() {
-        let db_path = get_db_path().unwrap();
-        assert!(db_path.to_string_lossy().contains("history.db"));
-        assert!(db_path.to_string_lossy().contains(".bodhya"));
-    }
-
     #[test]
     fn test_print_task() {
         let task = TaskRecord::new("session-1", "code", "Test task", "code-agent");
@@ -200,4 +312,101 @@ mod tests {
         // Should not panic and should truncate
         print_task(&task);
     }
+
+    #[test]
+    fn test_prune_storage() {
+        let storage = SqliteStorage::in_memory().unwrap();
+
+        let mut old_session = Session::new();
+        old_session.started_at = chrono::Utc::now() - chrono::Duration::days(45);
+        storage.save_session(&old_session).unwrap();
+        storage
+            .save_task(&TaskRecord::new(
+                &old_session.id,
+                "code",
+                "Old",
+                "code-agent",
+            ))
+            .unwrap();
+
+        let recent_session = Session::new();
+        storage.save_session(&recent_session).unwrap();
+
+        let stats = prune_storage(&storage, 30).unwrap();
+        assert_eq!(stats.sessions_removed, 1);
+        assert_eq!(stats.tasks_removed, 1);
+
+        let remaining = storage.list_sessions(10).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, recent_session.id);
+    }
+
+    #[test]
+    fn test_export_format_parse() {
+        assert_eq!(ExportFormat::parse("json"), Some(ExportFormat::Json));
+        assert_eq!(ExportFormat::parse("CSV"), Some(ExportFormat::Csv));
+        assert_eq!(ExportFormat::parse("xml"), None);
+    }
+
+    #[test]
+    fn test_build_filter() {
+        let filter = build_filter(Some("code".to_string()), Some("2024-03-01")).unwrap();
+        assert_eq!(filter.domain.as_deref(), Some("code"));
+        assert_eq!(
+            filter.since.unwrap().to_rfc3339(),
+            "2024-03-01T00:00:00+00:00"
+        );
+
+        assert!(build_filter(None, Some("03/01/2024")).is_err());
+    }
+
+    #[test]
+    fn test_export_tasks_csv() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let session = Session::new();
+        storage.save_session(&session).unwrap();
+        storage
+            .save_task(&TaskRecord::new(
+                &session.id,
+                "code",
+                "Build, test",
+                "code-agent",
+            ))
+            .unwrap();
+
+        let mut buffer = Vec::new();
+        let count =
+            export_tasks(&storage, ExportFormat::Csv, &TaskFilter::new(), &mut buffer).unwrap();
+        assert_eq!(count, 1);
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.starts_with("id,session_id,domain"));
+        assert!(output.contains("\"Build, test\""));
+    }
+
+    #[test]
+    fn test_show_stats_invalid_bucket() {
+        assert!(show_stats("code", true, "month").is_err());
+    }
+
+    #[test]
+    fn test_format_trend() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let session = Session::new();
+        storage.save_session(&session).unwrap();
+        let task = TaskRecord::new(&session.id, "code", "Task", "code-agent");
+        storage.save_task(&task).unwrap();
+        let mut metrics = QualityMetrics::new(&task.id).with_quality_score(82.5);
+        metrics.recorded_at = chrono::DateTime::parse_from_rfc3339("2024-03-05T10:00:00+00:00")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        storage.save_metrics(&metrics).unwrap();
+
+        let output = format_trend(&storage, "code", TimeBucket::Day).unwrap();
+        assert!(output.contains("per day"));
+        assert!(output.contains("2024-03-05   82.5/100"));
+
+        let empty = format_trend(&storage, "mail", TimeBucket::Week).unwrap();
+        assert!(empty.contains("No quality scores recorded yet"));
+    }
 }
@@ -4,6 +4,10 @@
 /// including initialization, model management, and task execution.
 pub mod agents_cmd;
 pub mod config_templates;
+pub mod disk_space;
+pub mod doctor_cmd;
+pub mod eval_cmd;
+pub mod exit_code;
 pub mod history_cmd;
 pub mod init_cmd;
 pub mod models_cmd;
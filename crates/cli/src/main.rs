@@ -2,14 +2,21 @@
 ///
 /// This is the command-line interface for Bodhya, providing commands for:
 /// - Initialization: `bodhya init`
-/// - Model management: `bodhya models list/install/remove`
+/// - Model management: `bodhya models list/info/install/remove`
 /// - Task execution: `bodhya run`
 /// - History: `bodhya history`
+/// - Diagnostics: `bodhya doctor`
+/// - Evaluation: `bodhya eval compare`
+use bodhya_core::{LogFormat, Logging};
 use clap::{Parser, Subcommand};
 use std::process;
 
 use bodhya_cli::config_templates::{ConfigTemplate, Profile};
-use bodhya_cli::{agents_cmd, history_cmd, init_cmd, models_cmd, run_cmd, serve_cmd, tools_cmd};
+use bodhya_cli::exit_code;
+use bodhya_cli::{
+    agents_cmd, doctor_cmd, eval_cmd, history_cmd, init_cmd, models_cmd, run_cmd, serve_cmd,
+    tools_cmd,
+};
 
 #[derive(Parser)]
 #[command(name = "bodhya")]
@@ -22,6 +29,10 @@ struct Cli {
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     verbose: bool,
+
+    /// Log format: json or pretty (default: BODHYA_LOG_FORMAT, else pretty on a terminal)
+    #[arg(long, global = true, value_name = "FORMAT")]
+    log_format: Option<LogFormat>,
 }
 
 #[derive(Subcommand)]
@@ -55,14 +66,32 @@ enum Commands {
         #[arg(short, long)]
         domain: Option<String>,
 
-        /// Working directory for file operations
+        /// Working directory for file operations and commands
         #[arg(short, long)]
         working_dir: Option<String>,
 
-        /// Execution mode: generate-only, execute, execute-with-retry
+        /// Write generated files here instead of the working directory
+        /// (commands, such as tests, still run in the working directory)
+        #[arg(long)]
+        output_dir: Option<String>,
+
+        /// Execution mode: plan-only, generate-only, execute, execute-with-retry
         #[arg(long, default_value = "execute")]
         execution_mode: String,
 
+        /// Only produce the plan and BDD features (no code generation or file writes)
+        #[arg(long)]
+        plan_only: bool,
+
+        /// Don't ask for approval before each file write or command
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Continue the interrupted retry run of this task ID (as printed
+        /// by an earlier run) instead of starting over
+        #[arg(long, value_name = "TASK_ID")]
+        resume: Option<String>,
+
         /// Task description
         #[arg(required = true)]
         task: String,
@@ -82,12 +111,27 @@ enum Commands {
         #[arg(long, default_value = "127.0.0.1")]
         host: String,
     },
+
+    /// Check the config, models, toolchain and MCP servers for problems
+    Doctor,
+
+    /// Model evaluation commands
+    #[command(subcommand)]
+    Eval(EvalCommands),
 }
 
 #[derive(Subcommand)]
 enum AgentsCommands {
     /// List all available agents
-    List,
+    List {
+        /// Only show agents supporting this intent or keyword (e.g., "refine")
+        #[arg(long)]
+        intent: Option<String>,
+
+        /// Only show agents for this domain (e.g., "code")
+        #[arg(long)]
+        domain: Option<String>,
+    },
 
     /// Show detailed information about a specific agent
     Show {
@@ -99,7 +143,17 @@ enum AgentsCommands {
 #[derive(Subcommand)]
 enum ModelsCommands {
     /// List all available models
-    List,
+    List {
+        /// Only show models with this role (planner, coder, reviewer, ...)
+        #[arg(long)]
+        role: Option<String>,
+    },
+
+    /// Show full details for a model
+    Info {
+        /// Model ID to show
+        model_id: String,
+    },
 
     /// Install a model by ID
     Install {
@@ -167,6 +221,24 @@ enum ToolsCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum EvalCommands {
+    /// Run the same evaluation cases through two models and compare their scores
+    Compare {
+        /// First model ID (A)
+        #[arg(long)]
+        model_a: String,
+
+        /// Second model ID (B)
+        #[arg(long)]
+        model_b: String,
+
+        /// Only run the evaluation case with this ID
+        #[arg(long)]
+        case: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum HistoryCommands {
     /// Show recent task execution history
@@ -180,6 +252,40 @@ enum HistoryCommands {
     Stats {
         /// Domain to show stats for (e.g., "code", "mail")
         domain: String,
+
+        /// Also show the average quality score over time
+        #[arg(long)]
+        trend: bool,
+
+        /// Trend bucket size: day or week
+        #[arg(long, default_value = "day")]
+        bucket: String,
+    },
+
+    /// Export task history as JSON or CSV
+    Export {
+        /// Output format: json or csv
+        #[arg(short, long, default_value = "json")]
+        format: String,
+
+        /// File to write to (defaults to stdout)
+        #[arg(short, long)]
+        out: Option<std::path::PathBuf>,
+
+        /// Only export tasks for this domain
+        #[arg(short, long)]
+        domain: Option<String>,
+
+        /// Only export tasks started on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Delete sessions older than a number of days and compact the database
+    Prune {
+        /// Remove sessions started more than this many days ago
+        #[arg(long)]
+        older_than: u32,
     },
 }
 
@@ -188,7 +294,7 @@ async fn main() {
     let cli = Cli::parse();
 
     // Initialize logging
-    init_logging(cli.verbose);
+    init_logging(cli.verbose, cli.log_format);
 
     // Execute command
     let result = match cli.command {
@@ -204,18 +310,21 @@ async fn main() {
                     for p in ConfigTemplate::all_profiles() {
                         eprintln!("  {} - {}", p.as_str(), p.description());
                     }
-                    process::exit(1);
+                    process::exit(exit_code::INVALID_INPUT);
                 }
             };
 
             init_cmd::init(profile, force)
         }
         Commands::Agents(agents_cmd) => match agents_cmd {
-            AgentsCommands::List => agents_cmd::list_agents(),
+            AgentsCommands::List { intent, domain } => {
+                agents_cmd::list_agents(intent.as_deref(), domain.as_deref())
+            }
             AgentsCommands::Show { agent_id } => agents_cmd::show_agent(&agent_id),
         },
         Commands::Models(models_cmd) => match models_cmd {
-            ModelsCommands::List => models_cmd::list_models(),
+            ModelsCommands::List { role } => models_cmd::list_models(role.as_deref()),
+            ModelsCommands::Info { model_id } => models_cmd::show_model_info(&model_id),
             ModelsCommands::Install { model_id } => models_cmd::install_model(&model_id),
             ModelsCommands::Remove { model_id } => models_cmd::remove_model(&model_id),
         },
@@ -236,41 +345,64 @@ async fn main() {
         Commands::Run {
             domain,
             working_dir,
+            output_dir,
             execution_mode,
+            plan_only,
+            yes,
+            resume,
             task,
-        } => run_cmd::run_task(domain, working_dir, execution_mode, task).await,
+        } => {
+            run_cmd::run_task(
+                domain,
+                working_dir,
+                output_dir,
+                execution_mode,
+                plan_only,
+                yes,
+                resume,
+                task,
+                cli.verbose,
+            )
+            .await
+        }
         Commands::History(history_cmd) => match history_cmd {
             HistoryCommands::Show { limit } => history_cmd::show_history(limit),
-            HistoryCommands::Stats { domain } => history_cmd::show_stats(&domain),
+            HistoryCommands::Stats {
+                domain,
+                trend,
+                bucket,
+            } => history_cmd::show_stats(&domain, trend, &bucket),
+            HistoryCommands::Export {
+                format,
+                out,
+                domain,
+                since,
+            } => history_cmd::export_history(&format, out.as_deref(), domain, since.as_deref()),
+            HistoryCommands::Prune { older_than } => history_cmd::prune_history(older_than),
         },
         Commands::Serve { port, host } => serve_cmd::start_server(&host, port).await,
+        Commands::Doctor => doctor_cmd::run_doctor().await,
+        Commands::Eval(EvalCommands::Compare {
+            model_a,
+            model_b,
+            case,
+        }) => eval_cmd::compare_models(&model_a, &model_b, case.as_deref()).await,
     };
 
     // Handle errors
     if let Err(e) = result {
         eprintln!("Error: {}", e);
-        process::exit(1);
+        process::exit(exit_code::error_exit_code(&e));
     }
 }
 
-/// Initialize logging based on verbosity level
-fn init_logging(verbose: bool) {
-    use tracing_subscriber::fmt::format::FmtSpan;
-
-    let builder = tracing_subscriber::fmt()
-        .with_target(false)
-        .with_thread_ids(false)
-        .with_file(false)
-        .with_line_number(false);
-
-    if verbose {
-        builder
-            .with_span_events(FmtSpan::CLOSE)
-            .with_max_level(tracing::Level::DEBUG)
-            .init();
-    } else {
-        builder.with_max_level(tracing::Level::INFO).init();
-    }
+/// Initialize logging based on verbosity level and format
+fn init_logging(verbose: bool, format: Option<LogFormat>) {
+    let level = if verbose { "debug" } else { "info" };
+    Logging::new(LogFormat::resolve(format), level)
+        .with_targets(false)
+        .with_span_timing(verbose)
+        .init();
 }
 
 #[cfg(test)]
@@ -284,6 +416,14 @@ mod tests {
         Cli::command().debug_assert();
     }
 
+    #[test]
+    fn test_usage_errors_have_their_own_exit_code() {
+        let err = Cli::try_parse_from(["bodhya", "--no-such-flag"])
+            .err()
+            .unwrap();
+        assert_eq!(err.exit_code(), exit_code::USAGE);
+    }
+
     #[test]
     fn test_cli_help() {
         let mut cli = Cli::command();
@@ -299,7 +439,24 @@ mod tests {
     fn test_agents_list_command() {
         let cli = Cli::parse_from(["bodhya", "agents", "list"]);
         match cli.command {
-            Commands::Agents(AgentsCommands::List) => {}
+            Commands::Agents(AgentsCommands::List { intent, domain }) => {
+                assert!(intent.is_none());
+                assert!(domain.is_none());
+            }
+            _ => panic!("Expected Agents List command"),
+        }
+    }
+
+    #[test]
+    fn test_agents_list_with_filters() {
+        let cli = Cli::parse_from([
+            "bodhya", "agents", "list", "--intent", "refine", "--domain", "mail",
+        ]);
+        match cli.command {
+            Commands::Agents(AgentsCommands::List { intent, domain }) => {
+                assert_eq!(intent.as_deref(), Some("refine"));
+                assert_eq!(domain.as_deref(), Some("mail"));
+            }
             _ => panic!("Expected Agents List command"),
         }
     }
@@ -354,11 +511,33 @@ mod tests {
     fn test_models_list_command() {
         let cli = Cli::parse_from(["bodhya", "models", "list"]);
         match cli.command {
-            Commands::Models(ModelsCommands::List) => {}
+            Commands::Models(ModelsCommands::List { role }) => assert!(role.is_none()),
             _ => panic!("Expected Models List command"),
         }
     }
 
+    #[test]
+    fn test_models_list_with_role() {
+        let cli = Cli::parse_from(["bodhya", "models", "list", "--role", "planner"]);
+        match cli.command {
+            Commands::Models(ModelsCommands::List { role }) => {
+                assert_eq!(role.as_deref(), Some("planner"))
+            }
+            _ => panic!("Expected Models List command"),
+        }
+    }
+
+    #[test]
+    fn test_models_info_command() {
+        let cli = Cli::parse_from(["bodhya", "models", "info", "test_model"]);
+        match cli.command {
+            Commands::Models(ModelsCommands::Info { model_id }) => {
+                assert_eq!(model_id, "test_model");
+            }
+            _ => panic!("Expected Models Info command"),
+        }
+    }
+
     #[test]
     fn test_models_install_command() {
         let cli = Cli::parse_from(["bodhya", "models", "install", "test_model"]);
@@ -388,11 +567,19 @@ mod tests {
             Commands::Run {
                 domain,
                 working_dir,
+                output_dir,
                 execution_mode,
+                plan_only,
+                yes,
+                resume,
                 task,
             } => {
+                assert!(!yes);
+                assert!(!plan_only);
+                assert_eq!(resume, None);
                 assert_eq!(domain, None);
                 assert_eq!(working_dir, None);
+                assert_eq!(output_dir, None);
                 assert_eq!(execution_mode, "execute");
                 assert_eq!(task, "Generate hello world");
             }
@@ -400,6 +587,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_run_command_with_yes() {
+        let cli = Cli::parse_from(["bodhya", "run", "--yes", "Generate code"]);
+        match cli.command {
+            Commands::Run { yes, .. } => assert!(yes),
+            _ => panic!("Expected Run command"),
+        }
+    }
+
+    #[test]
+    fn test_run_command_with_resume() {
+        let cli = Cli::parse_from(["bodhya", "run", "--resume", "task-42", "Generate code"]);
+        match cli.command {
+            Commands::Run { resume, .. } => assert_eq!(resume, Some("task-42".to_string())),
+            _ => panic!("Expected Run command"),
+        }
+    }
+
     #[test]
     fn test_run_command_with_domain() {
         let cli = Cli::parse_from(["bodhya", "run", "--domain", "code", "Generate code"]);
@@ -408,8 +613,11 @@ mod tests {
                 domain,
                 working_dir,
                 execution_mode,
+                plan_only,
                 task,
+                ..
             } => {
+                assert!(!plan_only);
                 assert_eq!(domain, Some("code".to_string()));
                 assert_eq!(working_dir, None);
                 assert_eq!(execution_mode, "execute");
@@ -427,8 +635,11 @@ mod tests {
                 domain,
                 working_dir,
                 execution_mode,
+                plan_only,
                 task,
+                ..
             } => {
+                assert!(!plan_only);
                 assert_eq!(domain, None);
                 assert_eq!(working_dir, Some("/tmp".to_string()));
                 assert_eq!(execution_mode, "execute");
@@ -438,6 +649,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_run_command_with_output_dir() {
+        let cli = Cli::parse_from([
+            "bodhya",
+            "run",
+            "--working-dir",
+            "/project",
+            "--output-dir",
+            "/tmp/scratch",
+            "Generate code",
+        ]);
+        match cli.command {
+            Commands::Run {
+                working_dir,
+                output_dir,
+                ..
+            } => {
+                assert_eq!(working_dir, Some("/project".to_string()));
+                assert_eq!(output_dir, Some("/tmp/scratch".to_string()));
+            }
+            _ => panic!("Expected Run command"),
+        }
+    }
+
     #[test]
     fn test_run_command_with_all_flags() {
         let cli = Cli::parse_from([
@@ -454,8 +689,11 @@ mod tests {
                 domain,
                 working_dir,
                 execution_mode,
+                plan_only,
                 task,
+                ..
             } => {
+                assert!(!plan_only);
                 assert_eq!(domain, Some("code".to_string()));
                 assert_eq!(working_dir, Some("/home/user/project".to_string()));
                 assert_eq!(execution_mode, "execute");
@@ -479,8 +717,11 @@ mod tests {
                 domain,
                 working_dir,
                 execution_mode,
+                plan_only,
                 task,
+                ..
             } => {
+                assert!(!plan_only);
                 assert_eq!(domain, None);
                 assert_eq!(working_dir, None);
                 assert_eq!(execution_mode, "generate-only");
@@ -490,6 +731,110 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_run_command_with_plan_only() {
+        let cli = Cli::parse_from(["bodhya", "run", "--plan-only", "Generate code"]);
+        match cli.command {
+            Commands::Run {
+                execution_mode,
+                plan_only,
+                task,
+                ..
+            } => {
+                assert!(plan_only);
+                assert_eq!(execution_mode, "execute");
+                assert_eq!(task, "Generate code");
+            }
+            _ => panic!("Expected Run command"),
+        }
+    }
+
+    #[test]
+    fn test_history_stats_trend_command() {
+        let cli = Cli::parse_from(["bodhya", "history", "stats", "code", "--trend"]);
+        match cli.command {
+            Commands::History(HistoryCommands::Stats {
+                domain,
+                trend,
+                bucket,
+            }) => {
+                assert_eq!(domain, "code");
+                assert!(trend);
+                assert_eq!(bucket, "day");
+            }
+            _ => panic!("Expected History Stats command"),
+        }
+    }
+
+    #[test]
+    fn test_history_prune_command() {
+        let cli = Cli::parse_from(["bodhya", "history", "prune", "--older-than", "30"]);
+        match cli.command {
+            Commands::History(HistoryCommands::Prune { older_than }) => {
+                assert_eq!(older_than, 30);
+            }
+            _ => panic!("Expected History Prune command"),
+        }
+    }
+
+    #[test]
+    fn test_history_export_command() {
+        let cli = Cli::parse_from([
+            "bodhya",
+            "history",
+            "export",
+            "--format",
+            "csv",
+            "--out",
+            "tasks.csv",
+        ]);
+        match cli.command {
+            Commands::History(HistoryCommands::Export {
+                format,
+                out,
+                domain,
+                since,
+            }) => {
+                assert_eq!(format, "csv");
+                assert_eq!(out, Some(std::path::PathBuf::from("tasks.csv")));
+                assert!(domain.is_none());
+                assert!(since.is_none());
+            }
+            _ => panic!("Expected History Export command"),
+        }
+    }
+
+    #[test]
+    fn test_doctor_command() {
+        let cli = Cli::parse_from(["bodhya", "doctor"]);
+        assert!(matches!(cli.command, Commands::Doctor));
+    }
+
+    #[test]
+    fn test_eval_compare_command() {
+        let cli = Cli::parse_from([
+            "bodhya",
+            "eval",
+            "compare",
+            "--model-a",
+            "mistral",
+            "--model-b",
+            "qwen",
+        ]);
+        match cli.command {
+            Commands::Eval(EvalCommands::Compare {
+                model_a,
+                model_b,
+                case,
+            }) => {
+                assert_eq!(model_a, "mistral");
+                assert_eq!(model_b, "qwen");
+                assert!(case.is_none());
+            }
+            _ => panic!("Expected Eval Compare command"),
+        }
+    }
+
     #[test]
     fn test_verbose_flag() {
         let cli = Cli::parse_from(["bodhya", "--verbose", "models", "list"]);
@@ -503,4 +848,15 @@ mod tests {
         assert_eq!(Profile::parse("full"), Some(Profile::Full));
         assert_eq!(Profile::parse("invalid"), None);
     }
+
+    #[test]
+    fn test_log_format_flag() {
+        let cli = Cli::parse_from(["bodhya", "models", "list", "--log-format", "json"]);
+        assert_eq!(cli.log_format, Some(LogFormat::Json));
+
+        let cli = Cli::parse_from(["bodhya", "models", "list"]);
+        assert_eq!(cli.log_format, None);
+
+        assert!(Cli::try_parse_from(["bodhya", "--log-format", "xml", "models", "list"]).is_err());
+    }
 }
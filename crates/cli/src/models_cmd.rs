@@ -1,16 +1,19 @@
 /// Model management commands
 ///
-/// This module implements commands for listing, installing, and removing models:
-/// - `bodhya models list`
+/// This module implements commands for listing, inspecting, installing, and removing models:
+/// - `bodhya models list [--role <role>]`
+/// - `bodhya models info <id>`
 /// - `bodhya models install <id>`
 /// - `bodhya models remove <id>`
-use bodhya_core::Result;
+use bodhya_core::{ModelRole, Result};
 use bodhya_model_registry::{ModelListEntry, ModelRegistry};
 
+use crate::disk_space::{self, FreeSpace, SystemFreeSpace};
 use crate::utils;
 
-/// List all available models
-pub fn list_models() -> Result<()> {
+/// List available models, optionally only those filling `role`
+pub fn list_models(role: Option<&str>) -> Result<()> {
+    let role = role.map(ModelRole::parse).transpose()?;
     let manifest_path = utils::models_manifest_path()?;
     let models_dir = utils::models_dir()?;
 
@@ -21,10 +24,13 @@ pub fn list_models() -> Result<()> {
     }
 
     let registry = ModelRegistry::from_manifest_file(&manifest_path, &models_dir)?;
-    let models = registry.list_models();
+    let models = filter_models(&registry, role.as_ref());
 
     if models.is_empty() {
-        println!("No models defined in manifest.");
+        match role {
+            Some(role) => println!("No models with role '{}' in manifest.", role),
+            None => println!("No models defined in manifest."),
+        }
         return Ok(());
     }
 
@@ -39,12 +45,98 @@ pub fn list_models() -> Result<()> {
         print_model_entry(&model);
     }
 
-    println!("\nTo install a model: bodhya models install <id>");
+    println!("\nTo inspect a model: bodhya models info <id>");
+    println!("To install a model: bodhya models install <id>");
     println!("To remove a model:  bodhya models remove <id>");
 
     Ok(())
 }
 
+/// Models in the registry, restricted to `role` when given
+fn filter_models(registry: &ModelRegistry, role: Option<&ModelRole>) -> Vec<ModelListEntry> {
+    match role {
+        Some(role) => registry.list_models_by_role(role),
+        None => registry.list_models(),
+    }
+}
+
+/// Show full details for a single model
+pub fn show_model_info(model_id: &str) -> Result<()> {
+    let manifest_path = utils::models_manifest_path()?;
+    let models_dir = utils::models_dir()?;
+
+    if !manifest_path.exists() {
+        return Err(bodhya_core::Error::Config(
+            "Models manifest not found. Run 'bodhya init' first.".to_string(),
+        ));
+    }
+
+    let registry = ModelRegistry::from_manifest_file(&manifest_path, &models_dir)?;
+    println!("{}", format_model_info(&registry, model_id)?);
+
+    Ok(())
+}
+
+/// Format the details of a model from the registry
+fn format_model_info(registry: &ModelRegistry, model_id: &str) -> Result<String> {
+    let info = registry.get_model_by_id(model_id)?;
+    let def = &info.definition;
+
+    let backend = match registry.manifest().backends.get(&def.backend) {
+        Some(config) => format!("{} ({})", def.backend, config.backend_type),
+        None => def.backend.clone(),
+    };
+    let quantization = if def.quantization.is_empty() {
+        "-"
+    } else {
+        &def.quantization
+    };
+    let status = if info.installed {
+        let sizes: std::io::Result<Vec<u64>> = registry
+            .model_files(model_id)
+            .iter()
+            .map(|path| std::fs::metadata(path).map(|m| m.len()))
+            .collect();
+        match sizes {
+            Ok(sizes) => format!("✓ installed ({} bytes on disk)", sizes.iter().sum::<u64>()),
+            Err(_) => "✓ installed".to_string(),
+        }
+    } else {
+        "not installed".to_string()
+    };
+
+    let mut output = String::new();
+    output.push_str(&format!("Model: {}\n\n", info.id));
+    output.push_str(&format!("  Display name:  {}\n", def.display_name));
+    if !def.description.is_empty() {
+        output.push_str(&format!("  Description:   {}\n", def.description));
+    }
+    output.push_str(&format!("  Role:          {}\n", def.role));
+    output.push_str(&format!("  Domain:        {}\n", def.domain));
+    output.push_str(&format!("  Size:          {:.1} GB\n", def.size_gb));
+    output.push_str(&format!("  Quantization:  {}\n", quantization));
+    if def.is_sharded() {
+        output.push_str(&format!("  Backend:       {}\n", backend));
+        output.push_str(&format!("  Shards:        {}\n", def.shards.len()));
+        for (n, shard) in def.shards.iter().enumerate() {
+            output.push_str(&format!(
+                "    {}. {} ({})\n",
+                n + 1,
+                shard.url,
+                shard.checksum
+            ));
+        }
+    } else {
+        output.push_str(&format!("  Checksum:      {}\n", def.checksum));
+        output.push_str(&format!("  Backend:       {}\n", backend));
+        output.push_str(&format!("  Source URL:    {}\n", def.source_url));
+    }
+    output.push_str(&format!("  Status:        {}\n", status));
+    output.push_str(&format!("  Path:          {}", info.model_path.display()));
+
+    Ok(output)
+}
+
 /// Install a model by ID
 pub fn install_model(model_id: &str) -> Result<()> {
     let manifest_path = utils::models_manifest_path()?;
@@ -57,7 +149,15 @@ pub fn install_model(model_id: &str) -> Result<()> {
     }
 
     let registry = ModelRegistry::from_manifest_file(&manifest_path, &models_dir)?;
+    install_with(&registry, model_id, &SystemFreeSpace)
+}
 
+/// Install `model_id` from `registry`, checking free space with `free_space`
+fn install_with(
+    registry: &ModelRegistry,
+    model_id: &str,
+    free_space: &dyn FreeSpace,
+) -> Result<()> {
     // Check if model exists - get_model_path returns PathBuf (not Result)
     let model_path = registry.get_model_path(model_id);
 
@@ -71,6 +171,12 @@ pub fn install_model(model_id: &str) -> Result<()> {
         return Ok(());
     }
 
+    // Refuse up front rather than failing partway through a download
+    let info = registry.get_model_by_id(model_id)?;
+    if let Some(dir) = model_path.parent() {
+        disk_space::ensure_free_space(free_space, model_id, dir, info.definition.size_bytes())?;
+    }
+
     // In v1, we don't actually implement download
     // This is a placeholder that shows what would happen
     println!("Model '{}' is not installed.", model_id);
@@ -97,30 +203,60 @@ pub fn remove_model(model_id: &str) -> Result<()> {
     }
 
     let registry = ModelRegistry::from_manifest_file(&manifest_path, &models_dir)?;
+    let bytes_reclaimed = remove_installed_model(&registry, model_id)?;
 
-    // Check if model exists in manifest - get_model_path returns PathBuf (not Result)
-    let model_path = registry.get_model_path(model_id);
-
-    // Check if installed - is_model_installed returns bool (not Result)
-    if !registry.is_model_installed(model_id) {
-        println!("Model '{}' is not installed.", model_id);
-        return Ok(());
+    // Reload the registry to confirm the model is no longer reported as installed
+    let registry = ModelRegistry::from_manifest_file(&manifest_path, &models_dir)?;
+    if registry.get_model_by_id(model_id)?.installed {
+        return Err(bodhya_core::Error::Internal(format!(
+            "Model '{}' is still reported as installed after removal",
+            model_id
+        )));
     }
 
-    // Remove the model file
-    std::fs::remove_file(&model_path).map_err(|e| {
-        bodhya_core::Error::Internal(format!(
-            "Failed to remove model file {}: {}",
-            model_path.display(),
-            e
-        ))
-    })?;
-
-    println!("✓ Removed model '{}'", model_id);
+    println!(
+        "✓ Removed model '{}' ({} reclaimed)",
+        model_id,
+        utils::format_bytes(bytes_reclaimed)
+    );
 
     Ok(())
 }
 
+/// Delete an installed model's files and return the number of bytes reclaimed
+fn remove_installed_model(registry: &ModelRegistry, model_id: &str) -> Result<u64> {
+    ModelRegistry::validate_model_id(model_id)?;
+
+    let info = registry.get_model_by_id(model_id)?;
+    if !info.installed {
+        return Err(bodhya_core::Error::InvalidInput(format!(
+            "Model '{}' is not installed, nothing to remove",
+            model_id
+        )));
+    }
+
+    let mut size = 0;
+    for path in registry.model_files(model_id) {
+        size += std::fs::metadata(&path).map(|m| m.len()).map_err(|e| {
+            bodhya_core::Error::Internal(format!(
+                "Failed to read model file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        std::fs::remove_file(&path).map_err(|e| {
+            bodhya_core::Error::Internal(format!(
+                "Failed to remove model file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+    }
+
+    Ok(size)
+}
+
 /// Print a single model entry
 fn print_model_entry(model: &ModelListEntry) {
     let status = if model.installed { "✓" } else { " " };
@@ -130,7 +266,7 @@ fn print_model_entry(model: &ModelListEntry) {
         "{} {:<18} {:<12} {:<10} {:<10} {}",
         status,
         model.id,
-        format!("{:?}", model.role),
+        model.role.to_string(),
         model.domain,
         size_str,
         model.display_name
@@ -192,7 +328,7 @@ backends:
     #[test]
     fn test_list_models_without_init() {
         with_temp_home(|_temp_home| {
-            let result = list_models();
+            let result = list_models(None);
             assert!(result.is_err());
             assert!(result.unwrap_err().to_string().contains("not found"));
         });
@@ -204,7 +340,7 @@ backends:
         with_temp_home(|temp_home| {
             create_test_manifest(temp_home);
 
-            let result = list_models();
+            let result = list_models(None);
             assert!(result.is_ok());
         });
     }
@@ -247,8 +383,8 @@ backends:
             create_test_manifest(temp_home);
 
             let result = remove_model("test_model");
-            // Should succeed even if not installed
-            assert!(result.is_ok());
+            // Should refuse to remove a model that isn't installed
+            assert!(result.is_err());
         });
     }
 
@@ -285,6 +421,180 @@ backends:
         });
     }
 
+    fn create_in_memory_registry(models_dir: &std::path::Path) -> ModelRegistry {
+        let manifest: bodhya_model_registry::ModelManifest = serde_yaml::from_str(
+            r#"
+models:
+  test_model:
+    role: planner
+    domain: code
+    display_name: "Test Model"
+    description: "A test model"
+    source_url: "https://example.com/model.gguf"
+    size_gb: 4.0
+    quantization: "Q4_K_M"
+    checksum: "sha256:abc123"
+    backend: local
+
+backends:
+  local:
+    type: mistral_rs
+"#,
+        )
+        .unwrap();
+        ModelRegistry::from_manifest(manifest, models_dir)
+    }
+
+    #[test]
+    fn test_filter_models_by_role() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = create_in_memory_registry(temp_dir.path());
+
+        assert_eq!(filter_models(&registry, None).len(), 1);
+
+        let planners = filter_models(&registry, Some(&ModelRole::Planner));
+        assert_eq!(planners.len(), 1);
+        assert_eq!(planners[0].id, "test_model");
+
+        assert!(filter_models(&registry, Some(&ModelRole::Coder)).is_empty());
+    }
+
+    #[test]
+    fn test_list_models_rejects_unknown_role() {
+        let err = list_models(Some("architect")).unwrap_err();
+        assert!(err.to_string().contains("Invalid model role"));
+    }
+
+    #[test]
+    fn test_format_model_info() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = create_in_memory_registry(temp_dir.path());
+
+        let output = format_model_info(&registry, "test_model").unwrap();
+        assert!(output.contains("Model: test_model"));
+        assert!(output.contains("Test Model"));
+        assert!(output.contains("planner"));
+        assert!(output.contains("4.0 GB"));
+        assert!(output.contains("Q4_K_M"));
+        assert!(output.contains("sha256:abc123"));
+        assert!(output.contains("local (mistral_rs)"));
+        assert!(output.contains("https://example.com/model.gguf"));
+        assert!(output.contains("not installed"));
+        assert!(output.contains("test_model.gguf"));
+
+        // Installed models report their on-disk size
+        std::fs::write(temp_dir.path().join("test_model.gguf"), b"fake model data").unwrap();
+        let output = format_model_info(&registry, "test_model").unwrap();
+        assert!(output.contains("installed (15 bytes on disk)"));
+    }
+
+    #[test]
+    fn test_format_model_info_unknown_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = create_in_memory_registry(temp_dir.path());
+
+        let result = format_model_info(&registry, "nonexistent");
+        assert!(matches!(
+            result.unwrap_err(),
+            bodhya_core::Error::ModelNotFound(_)
+        ));
+    }
+
+    /// Reports a fixed amount of free space and records each query
+    struct FakeFreeSpace {
+        available: u64,
+        queried: std::cell::RefCell<Vec<std::path::PathBuf>>,
+    }
+
+    impl FreeSpace for FakeFreeSpace {
+        fn available_bytes(&self, path: &std::path::Path) -> std::io::Result<u64> {
+            self.queried.borrow_mut().push(path.to_path_buf());
+            Ok(self.available)
+        }
+    }
+
+    #[test]
+    fn test_install_aborts_when_disk_is_full() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = create_in_memory_registry(temp_dir.path());
+        let free_space = FakeFreeSpace {
+            available: 1_000_000_000,
+            queried: Default::default(),
+        };
+
+        let err = install_with(&registry, "test_model", &free_space).unwrap_err();
+
+        assert!(matches!(err, bodhya_core::Error::Io(_)));
+        assert!(err.to_string().contains("Not enough disk space"));
+        assert_eq!(*free_space.queried.borrow(), vec![temp_dir.path()]);
+        // Nothing was written to the models directory
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+        let roomy = FakeFreeSpace {
+            available: u64::MAX,
+            queried: Default::default(),
+        };
+        assert!(install_with(&registry, "test_model", &roomy).is_ok());
+    }
+
+    #[test]
+    fn test_remove_installed_model_deletes_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = create_in_memory_registry(temp_dir.path());
+
+        let model_path = temp_dir.path().join("test_model.gguf");
+        std::fs::write(&model_path, b"fake model data").unwrap();
+        assert!(registry.get_model_by_id("test_model").unwrap().installed);
+
+        let reclaimed = remove_installed_model(&registry, "test_model").unwrap();
+
+        assert_eq!(reclaimed, 15);
+        assert!(!model_path.exists());
+        assert!(!registry.get_model_by_id("test_model").unwrap().installed);
+    }
+
+    #[test]
+    fn test_remove_installed_model_not_installed() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = create_in_memory_registry(temp_dir.path());
+
+        let result = remove_installed_model(&registry, "test_model");
+        let err = result.unwrap_err();
+        assert!(matches!(err, bodhya_core::Error::InvalidInput(_)));
+        assert!(err.to_string().contains("not installed"));
+    }
+
+    #[test]
+    fn test_remove_installed_model_rejects_path_traversal() {
+        let temp_dir = TempDir::new().unwrap();
+        let models_dir = temp_dir.path().join("models");
+        std::fs::create_dir_all(&models_dir).unwrap();
+        let registry = create_in_memory_registry(&models_dir);
+
+        // A file outside the models directory that a traversal id would hit
+        let outside = temp_dir.path().join("secret.gguf");
+        std::fs::write(&outside, b"do not delete").unwrap();
+
+        let result = remove_installed_model(&registry, "../secret");
+        assert!(matches!(
+            result.unwrap_err(),
+            bodhya_core::Error::InvalidInput(_)
+        ));
+        assert!(outside.exists());
+    }
+
+    #[test]
+    fn test_remove_installed_model_unknown_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = create_in_memory_registry(temp_dir.path());
+
+        let result = remove_installed_model(&registry, "nonexistent");
+        assert!(matches!(
+            result.unwrap_err(),
+            bodhya_core::Error::ModelNotFound(_)
+        ));
+    }
+
     #[test]
     fn test_print_model_entry() {
         let model = ModelListEntry {
@@ -2,19 +2,31 @@
 ///
 /// This module implements the `bodhya run` command for executing tasks.
 /// Phase 5 adds full integration with the controller and code agent.
-use bodhya_agent_code::CodeAgent;
-use bodhya_controller::TaskOrchestrator;
-use bodhya_core::{AppConfig, ExecutionMode, Result, Task};
+use async_trait::async_trait;
+use bodhya_agent_code::{Clarification, CodeAgent};
+use bodhya_controller::{RoutingDecision, TaskOrchestrator};
+use bodhya_core::{
+    AgentResult, AppConfig, ApprovalHook, ApprovalRequest, ExecutionMode, Result, Task,
+};
+use bodhya_storage::{Session, SqliteStorage, TaskRecord};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::utils;
 
 /// Run a task through the controller
+#[allow(clippy::too_many_arguments)]
 pub async fn run_task(
     domain: Option<String>,
     working_dir: Option<String>,
+    output_dir: Option<String>,
     execution_mode_str: String,
+    plan_only: bool,
+    assume_yes: bool,
+    resume: Option<String>,
     task_description: String,
+    verbose: bool,
 ) -> Result<()> {
     // Check if initialized
     if !utils::is_initialized() {
@@ -44,6 +56,16 @@ pub async fn run_task(
         std::env::current_dir().ok()
     };
 
+    // Generated files go to the output directory, created if missing
+    let output_dir_path = match output_dir {
+        Some(dir) => {
+            let path = PathBuf::from(dir);
+            utils::ensure_dir(&path)?;
+            Some(path)
+        }
+        None => None,
+    };
+
     // Load config
     let config_path = utils::default_config_path()?;
     if !config_path.exists() {
@@ -63,40 +85,38 @@ pub async fn run_task(
     let config: AppConfig = serde_yaml::from_str(&config_content)
         .map_err(|e| bodhya_core::Error::Config(format!("Failed to parse config: {}", e)))?;
 
-    // Parse execution mode
-    let execution_mode = ExecutionMode::parse(&execution_mode_str).ok_or_else(|| {
-        bodhya_core::Error::Config(format!(
-            "Invalid execution mode '{}'. Valid modes: generate-only, execute, execute-with-retry",
-            execution_mode_str
-        ))
-    })?;
-
-    // Initialize orchestrator with code agent
-    // Note: TaskOrchestrator::new() already creates ToolRegistry with defaults
-    let mut orchestrator = TaskOrchestrator::new(config);
-
-    // Set execution mode
-    orchestrator.set_execution_mode(execution_mode.clone());
-
-    // Set working directory if specified
-    if let Some(wd) = working_dir_path {
-        orchestrator.set_working_dir(wd);
-    }
-
-    // Register CodeAgent (Phase 5: only code agent)
-    let code_agent = Arc::new(CodeAgent::new());
-    orchestrator.router_mut().register(code_agent);
+    // Parse execution mode (--plan-only overrides --execution-mode, and
+    // only retry runs are checkpointed, so --resume implies one)
+    let execution_mode = if plan_only {
+        ExecutionMode::PlanOnly
+    } else if resume.is_some() {
+        ExecutionMode::ExecuteWithRetry
+    } else {
+        ExecutionMode::parse(&execution_mode_str).ok_or_else(|| {
+            bodhya_core::Error::Config(format!(
+                "Invalid execution mode '{}'. Valid modes: plan-only, generate-only, execute, execute-with-retry",
+                execution_mode_str
+            ))
+        })?
+    };
 
-    // Create task
+    // Create task; interactive runs let the agent ask about vague tasks
     let mut task = Task::new(&task_description);
+    if let Some(id) = resume {
+        task.id = id;
+    }
     if let Some(d) = domain {
         task = task.with_domain(&d);
     }
+    if !assume_yes {
+        task.payload["clarify"] = serde_json::Value::Bool(true);
+    }
 
     // Execute task
     println!("Executing task: {}", task_description);
-    if task.domain_hint.is_some() {
-        println!("Domain: {}", task.domain_hint.as_ref().unwrap());
+    println!("Task ID: {}", task.id);
+    if let Some(ref d) = task.domain_hint {
+        println!("Domain: {}", d);
     }
     println!(
         "Execution Mode: {} - {}",
@@ -105,15 +125,81 @@ pub async fn run_task(
     );
     println!();
 
-    let result = orchestrator.execute(task).await?;
+    // Ask before each file write and command unless --yes was given
+    let approval: Option<Arc<dyn ApprovalHook>> = if assume_yes {
+        None
+    } else {
+        Some(Arc::new(PromptApproval))
+    };
+
+    // The run is recorded in the history database; retry runs checkpoint
+    // there so `--resume` can pick them up, and record their test counts
+    let storage = match utils::open_storage() {
+        Ok(storage) => Some(Arc::new(storage)),
+        Err(e) => {
+            tracing::warn!(error = %e, "History disabled: could not open storage");
+            None
+        }
+    };
+    if let Some(storage) = &storage {
+        if let Err(e) = record_task_start(storage, &task) {
+            tracing::warn!(error = %e, "Failed to record task in history");
+        }
+    }
+
+    let mut result = execute_task(
+        config.clone(),
+        task.clone(),
+        execution_mode.clone(),
+        working_dir_path.clone(),
+        output_dir_path.clone(),
+        verbose,
+        approval.clone(),
+        storage.clone(),
+    )
+    .await?;
+
+    // The agent asked instead of guessing: re-plan with the user's answers
+    if let Some(questions) = clarification_questions(&result) {
+        println!("The task is ambiguous. Please answer a few questions:\n");
+        let clarifications = ask_clarifications(questions).await;
+        task.payload["clarifications"] = serde_json::json!(clarifications);
+        println!();
+        result = execute_task(
+            config,
+            task.clone(),
+            execution_mode,
+            working_dir_path,
+            output_dir_path,
+            verbose,
+            approval,
+            storage.clone(),
+        )
+        .await?;
+    }
+    if let Some(storage) = &storage {
+        if let Err(e) = record_task_end(storage, &task.id, &result) {
+            tracing::warn!(error = %e, "Failed to record task result in history");
+        }
+    }
+    let color = utils::color_enabled();
+
+    if verbose {
+        if let Some(decision) = routing_decision(&result) {
+            println!("{}\n", decision);
+        }
+    }
 
     // Display result
     if result.success {
-        println!("✓ Task completed successfully\n");
-        println!("{}", result.content);
+        println!(
+            "{}\n",
+            utils::render_status(true, "Task completed successfully", color)
+        );
+        println!("{}", utils::render_agent_output(&result.content, color));
     } else {
-        println!("✗ Task failed\n");
-        println!("{}", result.content);
+        println!("{}\n", utils::render_status(false, "Task failed", color));
+        println!("{}", utils::render_agent_output(&result.content, color));
         if let Some(error) = &result.error {
             println!("\nError: {}", error);
         }
@@ -122,6 +208,158 @@ pub async fn run_task(
     Ok(())
 }
 
+/// Record `task` as running, unless the run being resumed already did
+///
+/// The agent's quality metrics refer to this record.
+fn record_task_start(storage: &SqliteStorage, task: &Task) -> Result<()> {
+    if storage.get_task(&task.id)?.is_some() {
+        return Ok(());
+    }
+    let session = Session::new();
+    storage.save_session(&session)?;
+    let domain = task.domain_hint.as_deref().unwrap_or("code");
+    let mut record = TaskRecord::new(&session.id, domain, &task.description, "code");
+    record.id = task.id.clone();
+    storage.save_task(&record)
+}
+
+/// Record how the run of task `task_id` ended
+fn record_task_end(storage: &SqliteStorage, task_id: &str, result: &AgentResult) -> Result<()> {
+    let Some(mut record) = storage.get_task(task_id)? else {
+        return Ok(());
+    };
+    if result.success {
+        record.mark_success(&result.content);
+    } else {
+        record.mark_failed(result.error.as_deref().unwrap_or("Task failed"));
+    }
+    storage.save_task(&record)
+}
+
+/// Execute a task through an orchestrator with the code agent registered
+#[allow(clippy::too_many_arguments)]
+async fn execute_task(
+    config: AppConfig,
+    task: Task,
+    execution_mode: ExecutionMode,
+    working_dir: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+    explain_routing: bool,
+    approval: Option<Arc<dyn ApprovalHook>>,
+    storage: Option<Arc<SqliteStorage>>,
+) -> Result<AgentResult> {
+    // Initialize orchestrator with code agent
+    // Note: the ToolRegistry holds the default tools plus those of the
+    // configured MCP servers
+    let mut orchestrator = TaskOrchestrator::new_with_mcp(config).await?;
+
+    // Set execution mode
+    orchestrator.set_execution_mode(execution_mode);
+    orchestrator.set_attach_routing(explain_routing);
+
+    // Set working directory if specified
+    if let Some(wd) = working_dir {
+        orchestrator.set_working_dir(wd);
+    }
+    if let Some(output_dir) = output_dir {
+        orchestrator.set_output_dir(output_dir);
+    }
+
+    if let Some(approval) = approval {
+        orchestrator.set_approval_hook(approval);
+    }
+
+    // Register CodeAgent (Phase 5: only code agent)
+    let mut code_agent = CodeAgent::new();
+    if let Some(storage) = storage {
+        code_agent = code_agent
+            .with_checkpoints(storage.clone())
+            .with_plan_cache(storage.clone())
+            .with_metrics(storage);
+    }
+    orchestrator.router_mut().register(Arc::new(code_agent));
+
+    orchestrator.execute(task).await
+}
+
+/// Interactive y/N prompt on the terminal before each file write and command
+struct PromptApproval;
+
+#[async_trait]
+impl ApprovalHook for PromptApproval {
+    async fn approve(&self, request: &ApprovalRequest) -> bool {
+        let prompt = approval_prompt(request);
+        tokio::task::spawn_blocking(move || {
+            print!("{}", prompt);
+            let _ = std::io::stdout().flush();
+
+            let mut answer = String::new();
+            match std::io::stdin().lock().read_line(&mut answer) {
+                Ok(0) | Err(_) => false,
+                Ok(_) => is_yes(&answer),
+            }
+        })
+        .await
+        .unwrap_or(false)
+    }
+}
+
+/// Question asked before `request`, preceded by its diff or planned files
+fn approval_prompt(request: &ApprovalRequest) -> String {
+    match request {
+        ApprovalRequest::WriteFile {
+            diff: Some(diff), ..
+        } => format!("{}Allow agent to {}? [y/N] ", diff, request),
+        ApprovalRequest::PlannedWrites { paths } => {
+            let mut prompt = String::from("The agent plans to create or modify:\n");
+            for path in paths {
+                prompt.push_str(&format!("  {}\n", path.display()));
+            }
+            prompt.push_str("Proceed? [y/N] ");
+            prompt
+        }
+        _ => format!("Allow agent to {}? [y/N] ", request),
+    }
+}
+
+/// Questions the agent asked instead of planning, if any
+fn clarification_questions(result: &AgentResult) -> Option<Vec<String>> {
+    let questions: Vec<String> =
+        serde_json::from_value(result.metadata.get("clarification_questions")?.clone()).ok()?;
+    (!questions.is_empty()).then_some(questions)
+}
+
+/// Ask each question on the terminal, pairing it with the answer typed
+async fn ask_clarifications(questions: Vec<String>) -> Vec<Clarification> {
+    tokio::task::spawn_blocking(move || {
+        questions
+            .into_iter()
+            .map(|question| {
+                print!("{} ", question);
+                let _ = std::io::stdout().flush();
+                let mut answer = String::new();
+                let _ = std::io::stdin().lock().read_line(&mut answer);
+                Clarification {
+                    question,
+                    answer: answer.trim().to_string(),
+                }
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Whether a prompt answer means yes (anything else, including empty, is no)
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Routing explanation attached to a result, if any
+fn routing_decision(result: &AgentResult) -> Option<RoutingDecision> {
+    serde_json::from_value(result.metadata.get("routing")?.clone()).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,6 +405,116 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn test_execute_task_plan_only_writes_no_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = ConfigTemplate::for_profile(Profile::Code);
+        let task = Task::new("Generate a fibonacci function").with_domain("code");
+
+        let result = execute_task(
+            config,
+            task,
+            ExecutionMode::PlanOnly,
+            Some(temp_dir.path().to_path_buf()),
+            None,
+            false,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.success);
+        assert!(result.content.contains("## Plan"));
+        assert!(result.content.contains("Feature:"));
+
+        let entries: Vec<_> = std::fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert!(entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_task_explains_routing() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = ConfigTemplate::for_profile(Profile::Code);
+        let task = Task::new("Generate a fibonacci function");
+
+        let result = execute_task(
+            config,
+            task,
+            ExecutionMode::PlanOnly,
+            Some(temp_dir.path().to_path_buf()),
+            None,
+            true,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let decision = routing_decision(&result).unwrap();
+        assert_eq!(
+            decision.chosen_agent,
+            bodhya_core::Agent::id(&CodeAgent::new())
+        );
+    }
+
+    #[test]
+    fn test_task_recorded_before_metrics_and_completed_after() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let task = Task::new("Generate a fibonacci function");
+
+        record_task_start(&storage, &task).unwrap();
+        storage
+            .save_metrics(&bodhya_storage::QualityMetrics::new(&task.id).with_iterations(2))
+            .unwrap();
+        // Resuming the task keeps the existing record
+        record_task_start(&storage, &task).unwrap();
+        record_task_end(&storage, &task.id, &AgentResult::success(&task.id, "done")).unwrap();
+
+        let record = storage.get_task(&task.id).unwrap().unwrap();
+        assert_eq!(record.status, bodhya_storage::TaskStatus::Success);
+        assert_eq!(
+            storage.get_metrics(&task.id).unwrap().unwrap().iterations,
+            2
+        );
+    }
+
+    #[test]
+    fn test_is_yes() {
+        assert!(is_yes("y\n"));
+        assert!(is_yes(" YES "));
+        assert!(!is_yes("\n"));
+        assert!(!is_yes("n"));
+        assert!(!is_yes("sure"));
+    }
+
+    #[test]
+    fn test_clarification_questions_from_metadata() {
+        let asked = AgentResult::failure("t", "Task is ambiguous").with_metadata(
+            serde_json::json!({ "clarification_questions": ["What should the code do?"] }),
+        );
+        assert_eq!(
+            clarification_questions(&asked),
+            Some(vec!["What should the code do?".to_string()])
+        );
+        assert!(clarification_questions(&AgentResult::success("t", "done")).is_none());
+    }
+
+    #[test]
+    fn test_planned_writes_prompt_lists_paths() {
+        let request = ApprovalRequest::PlannedWrites {
+            paths: vec![
+                PathBuf::from("tests/fibonacci_test.rs"),
+                PathBuf::from("src/fibonacci.rs"),
+            ],
+        };
+
+        assert_eq!(
+            approval_prompt(&request),
+            "The agent plans to create or modify:\n  tests/fibonacci_test.rs\n  src/fibonacci.rs\nProceed? [y/N] "
+        );
+    }
+
     #[tokio::test]
     #[ignore]
     async fn test_run_task_no_config_file() {
@@ -178,10 +526,15 @@ mod tests {
 
             let rt = tokio::runtime::Handle::current();
             let result = rt.block_on(run_task(
+                None,
                 None,
                 None,
                 "execute".to_string(),
+                false,
+                true,
+                None,
                 "test task".to_string(),
+                false,
             ));
             assert!(result.is_err());
             assert!(result.unwrap_err().to_string().contains("not found"));
@@ -197,10 +550,15 @@ mod tests {
 
             let rt = tokio::runtime::Handle::current();
             let result = rt.block_on(run_task(
+                None,
                 None,
                 None,
                 "execute".to_string(),
+                false,
+                true,
+                None,
                 "Generate a hello world function".to_string(),
+                false,
             ));
             assert!(result.is_ok());
         });
@@ -217,8 +575,13 @@ mod tests {
             let result = rt.block_on(run_task(
                 Some("code".to_string()),
                 None,
+                None,
                 "execute".to_string(),
+                false,
+                true,
+                None,
                 "Generate code".to_string(),
+                false,
             ));
             assert!(result.is_ok());
         });
@@ -235,10 +598,15 @@ mod tests {
             let rt = tokio::runtime::Handle::current();
             for task in tasks {
                 let result = rt.block_on(run_task(
+                    None,
                     None,
                     None,
                     "execute".to_string(),
+                    false,
+                    true,
+                    None,
                     task.to_string(),
+                    false,
                 ));
                 assert!(result.is_ok());
             }
@@ -1,8 +1,11 @@
 /// CLI utility functions
 ///
 /// This module provides common utilities for path management,
-/// directory creation, and file operations.
+/// directory creation, file operations, and terminal output rendering.
 use bodhya_core::{Error, Result};
+use bodhya_storage::SqliteStorage;
+use colored::Colorize;
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
 /// Get the Bodhya home directory (~/.bodhya)
@@ -32,6 +35,20 @@ pub fn models_manifest_path() -> Result<PathBuf> {
     Ok(bodhya_home()?.join("models.yaml"))
 }
 
+/// Get the history database path (~/.bodhya/storage/history.db)
+pub fn db_path() -> Result<PathBuf> {
+    Ok(bodhya_home()?.join("storage").join("history.db"))
+}
+
+/// Open the history database, creating its directory if necessary
+pub fn open_storage() -> Result<SqliteStorage> {
+    let db_path = db_path()?;
+    if let Some(parent) = db_path.parent() {
+        ensure_dir(&parent.to_path_buf())?;
+    }
+    SqliteStorage::new(db_path)
+}
+
 /// Ensure a directory exists, creating it if necessary
 pub fn ensure_dir(path: &PathBuf) -> Result<()> {
     if !path.exists() {
@@ -51,6 +68,154 @@ pub fn is_initialized() -> bool {
     config_dir().ok().map(|p| p.exists()).unwrap_or(false)
 }
 
+/// Format a byte count as a human-readable size (e.g., "4.2 GB")
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1000 {
+        return format!("{} bytes", bytes);
+    }
+
+    let mut size = bytes as f64;
+    let mut unit = "bytes";
+    for next in UNITS {
+        if size < 1000.0 {
+            break;
+        }
+        size /= 1000.0;
+        unit = next;
+    }
+    format!("{:.1} {}", size, unit)
+}
+
+/// Check whether colored terminal output should be used for this process
+///
+/// See [`should_color`]; this reads `NO_COLOR` and checks stdout.
+pub fn color_enabled() -> bool {
+    should_color(
+        std::env::var_os("NO_COLOR").is_some(),
+        std::io::stdout().is_terminal(),
+    )
+}
+
+/// Whether to color output, given whether `NO_COLOR` is set (to any value)
+/// and whether stdout is a terminal (not piped to a file or program)
+pub fn should_color(no_color_set: bool, stdout_is_terminal: bool) -> bool {
+    !no_color_set && stdout_is_terminal
+}
+
+/// Code pipeline phase, as shown in agent output headings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelinePhase {
+    /// Planning
+    Plan,
+    /// BDD/Gherkin feature generation
+    Bdd,
+    /// Test generation and execution
+    Tests,
+    /// Implementation generation and file writes
+    Impl,
+    /// Code review
+    Review,
+}
+
+impl PipelinePhase {
+    /// Detect the phase from a markdown heading (without the leading `#`s)
+    pub fn from_heading(heading: &str) -> Option<Self> {
+        let heading = heading.to_lowercase();
+        if heading.contains("review") {
+            Some(Self::Review)
+        } else if heading.contains("bdd") {
+            Some(Self::Bdd)
+        } else if heading.contains("test") {
+            Some(Self::Tests)
+        } else if heading.contains("implementation") || heading.contains("writing files") {
+            Some(Self::Impl)
+        } else if heading.contains("plan") {
+            Some(Self::Plan)
+        } else {
+            None
+        }
+    }
+
+    /// Short label used in rendered headers
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Plan => "PLAN",
+            Self::Bdd => "BDD",
+            Self::Tests => "TESTS",
+            Self::Impl => "IMPL",
+            Self::Review => "REVIEW",
+        }
+    }
+}
+
+/// Render a pipeline phase header
+pub fn render_phase_header(phase: PipelinePhase, title: &str, color: bool) -> String {
+    let label = format!("[{}]", phase.label());
+    if !color {
+        return format!("{} {}", label, title);
+    }
+
+    let label = match phase {
+        PipelinePhase::Plan => label.bright_blue(),
+        PipelinePhase::Bdd => label.bright_magenta(),
+        PipelinePhase::Tests => label.bright_yellow(),
+        PipelinePhase::Impl => label.bright_green(),
+        PipelinePhase::Review => label.bright_cyan(),
+    };
+    format!("{} {}", label.bold(), title.bold())
+}
+
+/// Render a task status line with a glyph (✓ or ✗)
+pub fn render_status(success: bool, message: &str, color: bool) -> String {
+    let line = if success {
+        format!("✓ {}", message)
+    } else {
+        format!("✗ {}", message)
+    };
+
+    match (color, success) {
+        (false, _) => line,
+        (true, true) => line.green().bold().to_string(),
+        (true, false) => line.red().bold().to_string(),
+    }
+}
+
+/// Render agent markdown output for the terminal
+///
+/// Pipeline phase headings (plan, bdd, tests, impl, review) get colored
+/// labels and status glyph lines are colored by outcome. When color is
+/// disabled the output is returned as plain text, unchanged.
+pub fn render_agent_output(content: &str, color: bool) -> String {
+    if !color {
+        return content.to_string();
+    }
+
+    let mut output = String::new();
+    for line in content.lines() {
+        let rendered = if let Some(title) = line.strip_prefix("## ") {
+            match PipelinePhase::from_heading(title) {
+                Some(phase) => render_phase_header(phase, title, true),
+                None => title.bold().to_string(),
+            }
+        } else if let Some(title) = line.strip_prefix("# ") {
+            title.bright_cyan().bold().to_string()
+        } else if line.starts_with('✓') {
+            line.green().to_string()
+        } else if line.starts_with('✗') {
+            line.red().to_string()
+        } else if line.starts_with('⚠') {
+            line.yellow().to_string()
+        } else {
+            line.to_string()
+        };
+        output.push_str(&rendered);
+        output.push('\n');
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,6 +255,13 @@ mod tests {
         assert!(path.to_str().unwrap().ends_with("models.yaml"));
     }
 
+    #[test]
+    fn test_db_path() {
+        let path = db_path().unwrap();
+        assert!(path.to_str().unwrap().contains(".bodhya"));
+        assert!(path.to_str().unwrap().ends_with("history.db"));
+    }
+
     #[test]
     fn test_ensure_dir() {
         let temp = TempDir::new().unwrap();
@@ -108,6 +280,64 @@ mod tests {
         ensure_dir(&test_dir).unwrap();
     }
 
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(15), "15 bytes");
+        assert_eq!(format_bytes(1_500), "1.5 KB");
+        assert_eq!(format_bytes(4_400_000_000), "4.4 GB");
+    }
+
+    #[test]
+    fn test_color_disabled_with_no_color() {
+        assert!(should_color(false, true));
+        assert!(!should_color(false, false));
+        let color = should_color(true, true);
+        assert!(!color);
+
+        let content = "# Executing: task\n\n## Step 1: Planning\n\n✓ Tests PASSED\n✗ Failed\n";
+        let rendered = render_agent_output(content, color);
+        assert!(!rendered.contains('\u{1b}'));
+        assert_eq!(rendered, content);
+
+        let status = render_status(true, "Task completed successfully", color);
+        assert!(!status.contains('\u{1b}'));
+        assert_eq!(status, "✓ Task completed successfully");
+
+        let header = render_phase_header(PipelinePhase::Review, "Code Review", color);
+        assert!(!header.contains('\u{1b}'));
+        assert_eq!(header, "[REVIEW] Code Review");
+    }
+
+    #[test]
+    fn test_pipeline_phase_from_heading() {
+        assert_eq!(
+            PipelinePhase::from_heading("Plan"),
+            Some(PipelinePhase::Plan)
+        );
+        assert_eq!(
+            PipelinePhase::from_heading("Step 2: Generating BDD Features"),
+            Some(PipelinePhase::Bdd)
+        );
+        assert_eq!(
+            PipelinePhase::from_heading("Tests (RED Phase)"),
+            Some(PipelinePhase::Tests)
+        );
+        assert_eq!(
+            PipelinePhase::from_heading("Implementation (GREEN Phase)"),
+            Some(PipelinePhase::Impl)
+        );
+        assert_eq!(
+            PipelinePhase::from_heading("Code Review (REFACTOR Phase)"),
+            Some(PipelinePhase::Review)
+        );
+        assert_eq!(PipelinePhase::from_heading("Execution Statistics"), None);
+    }
+
+    #[test]
+    fn test_render_status_plain() {
+        assert_eq!(render_status(false, "Task failed", false), "✗ Task failed");
+    }
+
     #[test]
     fn test_is_initialized_returns_bool() {
         // This test assumes ~/.bodhya/config may or may not exist
@@ -1,6 +1,6 @@
 /// Simple controller wrapper for easy API server integration
 use async_trait::async_trait;
-use bodhya_core::{Agent, AgentResult, AppConfig, Task};
+use bodhya_core::{Agent, AgentResult, AppConfig, ProgressCallback, Task};
 use std::sync::Arc;
 
 use crate::orchestrator::TaskOrchestrator;
@@ -67,11 +67,39 @@ impl Controller {
         Ok(Self { orchestrator })
     }
 
+    /// Finish setup and share the controller across threads
+    ///
+    /// Agents are fixed once the controller is built, so the shared controller
+    /// needs no lock; handlers call [`execute`](Self::execute) concurrently.
+    pub fn into_shared(self) -> Arc<Self> {
+        Arc::new(self)
+    }
+
     /// Execute a task
     pub async fn execute(&self, task: Task) -> bodhya_core::Result<AgentResult> {
         self.orchestrator.execute(task).await
     }
 
+    /// Execute a task, reporting pipeline phases to `progress`
+    pub async fn execute_with_progress(
+        &self,
+        task: Task,
+        progress: ProgressCallback,
+    ) -> bodhya_core::Result<AgentResult> {
+        self.orchestrator
+            .execute_with_progress(task, Some(progress))
+            .await
+    }
+
+    /// Id of the agent that would handle `task`, without executing it
+    ///
+    /// Uses the same routing as [`execute`](Self::execute), so it fails the
+    /// same way when no agent can take the task.
+    pub fn route_only(&self, task: &Task) -> bodhya_core::Result<String> {
+        let (agent, _) = self.orchestrator.router().route(task)?;
+        Ok(agent.id().to_string())
+    }
+
     /// List all registered agents
     pub fn list_agents(&self) -> Vec<Box<dyn Agent>> {
         self.orchestrator
@@ -89,6 +117,12 @@ impl Controller {
     }
 }
 
+// The API server shares one controller across handler threads
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Controller>();
+};
+
 /// Helper struct to clone agents for listing
 struct AgentClone {
     agent: Arc<dyn Agent>,
@@ -115,6 +149,14 @@ impl Agent for AgentClone {
     fn is_enabled(&self) -> bool {
         self.agent.is_enabled()
     }
+
+    fn default_execution_limits(&self) -> bodhya_core::ExecutionLimits {
+        self.agent.default_execution_limits()
+    }
+
+    fn required_tools(&self) -> Vec<String> {
+        self.agent.required_tools()
+    }
 }
 
 #[cfg(test)]
@@ -174,6 +216,60 @@ mod tests {
         assert_eq!(result.unwrap().content, "test result");
     }
 
+    #[test]
+    fn test_route_only() {
+        let agents: Vec<Arc<dyn Agent>> = vec![
+            Arc::new(TestAgent {
+                id: "code",
+                domain: "code".to_string(),
+            }),
+            Arc::new(TestAgent {
+                id: "mail",
+                domain: "mail".to_string(),
+            }),
+        ];
+        let controller = Controller::new(agents);
+
+        let code_task = Task::new("Write a parser").with_domain("code");
+        assert_eq!(controller.route_only(&code_task).unwrap(), "code");
+
+        let unmatched = Task::new("Book a flight").with_domain("travel");
+        assert!(controller.route_only(&unmatched).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_shared_controller_across_tasks() {
+        let agents: Vec<Arc<dyn Agent>> = vec![
+            Arc::new(TestAgent {
+                id: "code",
+                domain: "code".to_string(),
+            }),
+            Arc::new(TestAgent {
+                id: "mail",
+                domain: "mail".to_string(),
+            }),
+        ];
+        let controller = Controller::new(agents).into_shared();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let controller = Arc::clone(&controller);
+                let domain = if i % 2 == 0 { "code" } else { "mail" };
+                tokio::spawn(async move {
+                    controller
+                        .execute(Task::new(format!("task {}", i)).with_domain(domain))
+                        .await
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let result = handle.await.unwrap().unwrap();
+            assert_eq!(result.content, "test result");
+        }
+        assert_eq!(controller.list_agents().len(), 2);
+    }
+
     #[tokio::test]
     async fn test_controller_with_config() {
         let agent = Arc::new(TestAgent {
@@ -5,11 +5,13 @@
 pub use controller::Controller;
 pub use engagement::{EngagementManager, EngagementOperation, EngagementStrategy};
 pub use orchestrator::TaskOrchestrator;
-pub use routing::AgentRouter;
+pub use results::{InMemoryResultStore, ResultStore};
+pub use routing::{extract_intent, AgentRouter, AgentScore, RoutingDecision, RoutingReason};
 
 pub mod controller;
 pub mod engagement;
 pub mod orchestrator;
+pub mod results;
 pub mod routing;
 
 #[cfg(test)]
@@ -83,6 +85,73 @@ mod integration_tests {
         }
     }
 
+    /// Agent that tries to write `target` through the context's tool registry
+    struct WritingAgent {
+        target: std::path::PathBuf,
+    }
+
+    #[async_trait]
+    impl Agent for WritingAgent {
+        fn id(&self) -> &'static str {
+            "writer"
+        }
+
+        fn capability(&self) -> AgentCapability {
+            AgentCapability::new("files", vec!["write".to_string()], "Writes a file")
+        }
+
+        fn required_tools(&self) -> Vec<String> {
+            vec!["filesystem".to_string()]
+        }
+
+        async fn handle(&self, task: Task, ctx: AgentContext) -> bodhya_core::Result<AgentResult> {
+            let tools = Arc::clone(ctx.tools.as_ref().unwrap())
+                .downcast::<bodhya_tools_mcp::ToolRegistry>()
+                .unwrap();
+
+            let request = bodhya_core::ToolRequest::new(
+                "filesystem",
+                "write",
+                serde_json::json!({
+                    "path": self.target.to_str().unwrap(),
+                    "content": "escaped"
+                }),
+            );
+
+            Ok(match tools.execute(request).await {
+                Ok(response) if response.success => AgentResult::success(task.id, "written"),
+                Ok(response) => AgentResult::failure(task.id, response.error.unwrap_or_default()),
+                Err(e) => AgentResult::failure(task.id, e.to_string()),
+            })
+        }
+    }
+
+    /// Integration test: tools are sandboxed to the task's working directory
+    #[tokio::test]
+    async fn test_write_outside_working_dir_is_rejected() {
+        let sandbox = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+
+        for (target, allowed) in [
+            (outside.path().join("escape.txt"), false),
+            (sandbox.path().join("../escape-relative.txt"), false),
+            (sandbox.path().join("inside.txt"), true),
+        ] {
+            let mut orchestrator = TaskOrchestrator::new(create_test_config());
+            orchestrator.set_working_dir(sandbox.path());
+            orchestrator.router_mut().register(Arc::new(WritingAgent {
+                target: target.clone(),
+            }));
+
+            let result = orchestrator
+                .execute(Task::new("write a file"))
+                .await
+                .unwrap();
+            assert_eq!(result.success, allowed, "{}", target.display());
+            assert_eq!(target.exists(), allowed, "{}", target.display());
+        }
+    }
+
     fn create_test_config() -> AppConfig {
         AppConfig {
             engagement_mode: EngagementMode::Minimum,
@@ -234,6 +303,10 @@ mod integration_tests {
                     .with_keywords(vec!["tool".to_string(), "test".to_string()])
             }
 
+            fn required_tools(&self) -> Vec<String> {
+                vec!["filesystem".to_string()]
+            }
+
             async fn handle(
                 &self,
                 task: Task,
@@ -268,6 +341,81 @@ mod integration_tests {
         assert!(result.content.contains("Successfully accessed tools"));
     }
 
+    /// Agent that records the previous result it was asked to refine
+    #[derive(Default)]
+    struct RefiningAgent {
+        refined: std::sync::Mutex<Vec<bodhya_core::PreviousResult>>,
+    }
+
+    #[async_trait]
+    impl Agent for RefiningAgent {
+        fn id(&self) -> &'static str {
+            "refiner"
+        }
+
+        fn capability(&self) -> AgentCapability {
+            AgentCapability::new("mail", vec!["draft".to_string()], "Refining test agent")
+        }
+
+        async fn handle(&self, task: Task, ctx: AgentContext) -> bodhya_core::Result<AgentResult> {
+            let content = match ctx.previous_result {
+                Some(previous) => {
+                    let content = format!("{} (revised: {})", previous.content, task.description);
+                    self.refined.lock().unwrap().push(previous);
+                    content
+                }
+                None => format!("Draft for {}", task.description),
+            };
+            Ok(AgentResult::success(task.id, content))
+        }
+    }
+
+    /// Integration test: A refine task hands the earlier result to the agent
+    #[tokio::test]
+    async fn test_refine_task_receives_previous_result() {
+        let agent = Arc::new(RefiningAgent::default());
+        let mut orchestrator = TaskOrchestrator::new(create_test_config());
+        orchestrator.router_mut().register(Arc::new(MockCodeAgent));
+        orchestrator
+            .router_mut()
+            .register(Arc::clone(&agent) as Arc<dyn Agent>);
+
+        let first = orchestrator
+            .execute(Task::new("Announce the offsite").with_domain("mail"))
+            .await
+            .unwrap();
+
+        // No domain hint: the refinement follows the original to its domain
+        let refine = Task::new("Make it shorter").with_refine_of(first.task_id.clone());
+        let result = orchestrator.execute(refine).await.unwrap();
+
+        let refined = agent.refined.lock().unwrap();
+        assert_eq!(refined.len(), 1);
+        assert_eq!(refined[0].task_id, first.task_id);
+        assert_eq!(refined[0].content, first.content);
+        assert_eq!(refined[0].domain, "mail");
+        assert_eq!(
+            result.content,
+            "Draft for Announce the offsite (revised: Make it shorter)"
+        );
+    }
+
+    /// Integration test: Refining an unknown task is rejected
+    #[tokio::test]
+    async fn test_refine_unknown_task_is_rejected() {
+        let mut orchestrator = TaskOrchestrator::new(create_test_config());
+        orchestrator.router_mut().register(Arc::new(MockMailAgent));
+
+        let result = orchestrator
+            .execute(Task::new("Make it shorter").with_refine_of("missing"))
+            .await;
+
+        assert!(matches!(
+            result.unwrap_err(),
+            bodhya_core::Error::InvalidInput(_)
+        ));
+    }
+
     /// Integration test: Verify ToolRegistry is properly initialized
     #[test]
     fn test_orchestrator_has_tools() {
@@ -2,13 +2,20 @@
 ///
 /// This module handles the end-to-end task execution pipeline:
 /// task intake -> routing -> agent execution -> result collection -> logging
-use bodhya_core::{AgentContext, AgentResult, AppConfig, ExecutionMode, Task};
-use bodhya_tools_mcp::ToolRegistry;
+use bodhya_core::{
+    Agent, AgentContext, AgentResult, AppConfig, ApprovalHook, EventRecorder, EventSink,
+    ExecutionLimitOverrides, ExecutionLimits, ExecutionMode, JsonlEventSink, PreviousResult,
+    ProgressCallback, RunEvent, Task,
+};
+use bodhya_tools_mcp::{HttpTool, ToolRegistry};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::engagement::EngagementManager;
-use crate::routing::AgentRouter;
+use crate::results::{InMemoryResultStore, ResultStore};
+use crate::routing::{AgentRouter, RoutingDecision};
 
 /// Central orchestrator for task execution
 pub struct TaskOrchestrator {
@@ -22,24 +29,54 @@ pub struct TaskOrchestrator {
     tools: Arc<ToolRegistry>,
     /// Working directory for file operations
     working_dir: Option<PathBuf>,
+    /// Directory generated files are written to, if not the working directory
+    output_dir: Option<PathBuf>,
     /// Execution mode for task execution
     execution_mode: ExecutionMode,
+    /// Attach the routing decision to result metadata
+    attach_routing: bool,
+    /// Limits passed to agents, including the per-task timeout; unset
+    /// fields come from the agent's defaults
+    execution_limits: ExecutionLimitOverrides,
+    /// Approval hook for file writes and commands (None approves all)
+    approval: Option<Arc<dyn ApprovalHook>>,
+    /// Destination for run events (None disables the event log)
+    events: Option<Arc<dyn EventSink>>,
+    /// Completed results, looked up by refinement tasks
+    results: Arc<dyn ResultStore>,
 }
 
 impl TaskOrchestrator {
     /// Create a new orchestrator
     pub fn new(config: AppConfig) -> Self {
         let engagement = EngagementManager::new(config.engagement_mode.clone());
-        let tools = Arc::new(ToolRegistry::with_defaults());
+        let mut registry = ToolRegistry::with_defaults()
+            .with_max_response_bytes(config.tools.max_response_bytes)
+            .with_tool_timeout(Duration::from_secs(config.tools.tool_timeout_secs));
+        // HTTP access is opt-in: only registered when configured
+        if let Some(http) = &config.tools.http {
+            registry.register(Box::new(HttpTool::from_config(http)));
+        }
+        let tools = Arc::new(registry);
 
         Self {
-            router: AgentRouter::new(),
+            router: AgentRouter::new()
+                .with_fallbacks(config.routing.fallbacks.clone())
+                .with_keyword_matching(config.routing.keyword_matching.clone())
+                .with_overrides(&config.routing.overrides),
             engagement,
             config,
             tools,
             working_dir: None,
+            output_dir: None,
             execution_mode: ExecutionMode::default(),
+            attach_routing: false,
+            execution_limits: ExecutionLimitOverrides::default(),
+            approval: None,
+            events: None,
+            results: Arc::new(InMemoryResultStore::default()),
         }
+        .with_event_log_from_config()
     }
 
     /// Create a new orchestrator and load MCP servers from configuration
@@ -55,7 +92,9 @@ impl TaskOrchestrator {
     /// Load MCP servers from configuration
     ///
     /// This should be called after creating the orchestrator to connect to
-    /// configured MCP servers and register their tools.
+    /// configured MCP servers and register their tools. `${VAR}` references
+    /// in a server's command and env are expanded when it is started. A
+    /// server that fails to start is skipped with a warning.
     pub async fn load_mcp_servers(&mut self) -> bodhya_core::Result<()> {
         // Get mutable access to tools
         let tools = Arc::get_mut(&mut self.tools).ok_or_else(|| {
@@ -65,14 +104,20 @@ impl TaskOrchestrator {
         })?;
 
         // Load MCP servers from config
+        let builtin = tools.list_tools().len();
         tools
             .load_mcp_servers(&self.config.tools.mcp_servers)
             .await?;
 
+        let registered = &tools.list_tools()[builtin..];
         tracing::info!(
-            "Loaded {} MCP servers from configuration",
-            self.config.tools.mcp_servers.len()
+            "Registered {} tools from {} enabled MCP servers",
+            registered.len(),
+            self.config.tools.enabled_mcp_servers().len()
         );
+        for tool in registered {
+            tracing::info!("Registered MCP tool '{}'", tool);
+        }
 
         Ok(())
     }
@@ -82,13 +127,48 @@ impl TaskOrchestrator {
         let engagement = EngagementManager::new(config.engagement_mode.clone());
 
         Self {
-            router: AgentRouter::new(),
+            router: AgentRouter::new()
+                .with_fallbacks(config.routing.fallbacks.clone())
+                .with_keyword_matching(config.routing.keyword_matching.clone())
+                .with_overrides(&config.routing.overrides),
             engagement,
             config,
             tools,
             working_dir: None,
+            output_dir: None,
             execution_mode: ExecutionMode::default(),
+            attach_routing: false,
+            execution_limits: ExecutionLimitOverrides::default(),
+            approval: None,
+            events: None,
+            results: Arc::new(InMemoryResultStore::default()),
+        }
+        .with_event_log_from_config()
+    }
+
+    /// Open the event log configured under `logging.event_log`, if any
+    ///
+    /// A log that cannot be opened is reported and skipped; it never blocks tasks.
+    fn with_event_log_from_config(mut self) -> Self {
+        if let Some(path) = &self.config.logging.event_log {
+            match JsonlEventSink::new(path) {
+                Ok(sink) => self.events = Some(Arc::new(sink)),
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "Failed to open event log")
+                }
+            }
         }
+        self
+    }
+
+    /// Record run events for every task into `sink`
+    pub fn set_event_sink(&mut self, sink: Arc<dyn EventSink>) {
+        self.events = Some(sink);
+    }
+
+    /// Keep completed results in `results` instead of the default in-memory store
+    pub fn set_result_store(&mut self, results: Arc<dyn ResultStore>) {
+        self.results = results;
     }
 
     /// Set the working directory for file operations
@@ -96,11 +176,39 @@ impl TaskOrchestrator {
         self.working_dir = Some(working_dir.into());
     }
 
+    /// Write generated files under `output_dir`; commands still run in the
+    /// working directory
+    pub fn set_output_dir(&mut self, output_dir: impl Into<PathBuf>) {
+        self.output_dir = Some(output_dir.into());
+    }
+
     /// Set the execution mode for task execution
     pub fn set_execution_mode(&mut self, mode: ExecutionMode) {
         self.execution_mode = mode;
     }
 
+    /// Set the execution limits passed to agents
+    ///
+    /// `timeout_secs` bounds the whole task; `u64::MAX` disables the timeout.
+    pub fn set_execution_limits(&mut self, limits: ExecutionLimits) {
+        self.execution_limits = limits.into();
+    }
+
+    /// Set some execution limits, leaving the rest to each agent's defaults
+    pub fn set_execution_limit_overrides(&mut self, overrides: ExecutionLimitOverrides) {
+        self.execution_limits = overrides;
+    }
+
+    /// Require approval from `approval` before agents write files or run commands
+    pub fn set_approval_hook(&mut self, approval: Arc<dyn ApprovalHook>) {
+        self.approval = Some(approval);
+    }
+
+    /// Attach the routing decision to each result's metadata under `routing`
+    pub fn set_attach_routing(&mut self, attach_routing: bool) {
+        self.attach_routing = attach_routing;
+    }
+
     /// Get a reference to the tool registry
     pub fn tools(&self) -> &Arc<ToolRegistry> {
         &self.tools
@@ -124,12 +232,52 @@ impl TaskOrchestrator {
     /// Execute a task
     ///
     /// This is the main entry point for task execution:
-    /// 1. Select appropriate agent via router
-    /// 2. Create agent context
-    /// 3. Execute task through agent
-    /// 4. Log execution metrics
-    /// 5. Return result
+    /// 1. Fill `{name}` variables in the description from the payload
+    /// 2. For refinement tasks (`payload.refine_of`), look up the earlier result
+    /// 3. Select appropriate agent via router, plus its domain's fallbacks
+    /// 4. Create agent context, with file tools sandboxed to the working directory
+    /// 5. Execute task through agent, failing it if the timeout expires
+    /// 6. On failure, retry with the next fallback agent, within what is left
+    ///    of the task's timeout
+    /// 7. Log execution metrics
+    /// 8. Store and return the first successful result, or the aggregated failures
     pub async fn execute(&self, task: Task) -> bodhya_core::Result<AgentResult> {
+        self.execute_with_progress(task, None).await
+    }
+
+    /// Execute a task, reporting each pipeline phase to `progress`
+    ///
+    /// The orchestrator reports `routing` and `executing`; agents may report
+    /// finer-grained phases through [`AgentContext::report_phase`].
+    pub async fn execute_with_progress(
+        &self,
+        mut task: Task,
+        progress: Option<ProgressCallback>,
+    ) -> bodhya_core::Result<AgentResult> {
+        if let Some(progress) = &progress {
+            progress("routing");
+        }
+
+        // Agents only ever see the description with its variables filled in
+        task.description = task.render()?;
+
+        // A refinement goes back to the domain that produced the original
+        let previous = match task.refine_of() {
+            Some(task_id) => {
+                let previous = self.results.get(task_id).ok_or_else(|| {
+                    bodhya_core::Error::InvalidInput(format!(
+                        "Cannot refine unknown task '{}'",
+                        task_id
+                    ))
+                })?;
+                if task.domain_hint.is_none() {
+                    task.domain_hint = Some(previous.domain.clone());
+                }
+                Some(previous)
+            }
+            None => None,
+        };
+
         tracing::info!(
             task_id = %task.id,
             description = %task.description,
@@ -137,31 +285,232 @@ impl TaskOrchestrator {
             "Starting task execution"
         );
 
-        // Select agent
-        let agent = self.router.select_agent(&task)?;
+        // Select the agent, followed by its domain's fallbacks
+        let (chain, decision) = self.router.route_chain(&task)?;
 
         tracing::debug!(
             task_id = %task.id,
-            agent_id = agent.id(),
-            agent_domain = agent.capability().domain,
+            agent_id = %decision.chosen_agent,
+            fallbacks = chain.len() - 1,
+            reason = ?decision.reason,
             "Selected agent for task"
         );
 
-        // Create agent context with tools, working directory, and execution mode
+        // The whole chain shares the primary agent's timeout, so fallbacks
+        // only get what is left of it
+        let deadline = self
+            .execution_limits
+            .apply_to(chain[0].default_execution_limits())
+            .timeout()
+            .map(|limit| std::time::Instant::now() + limit);
+
+        // Try each agent in turn until one succeeds
+        let mut failures = Vec::new();
+        let mut result = None;
+        for (attempt, agent) in chain.iter().enumerate() {
+            let remaining = deadline
+                .map(|deadline| deadline.saturating_duration_since(std::time::Instant::now()));
+            if attempt > 0 {
+                if remaining.is_some_and(|remaining| remaining.is_zero()) {
+                    tracing::warn!(
+                        task_id = %task.id,
+                        agent_id = agent.id(),
+                        "Task timed out before the next fallback agent could run"
+                    );
+                    failures.push(format!("{}: not run, task timed out", agent.id()));
+                    break;
+                }
+                tracing::warn!(
+                    task_id = %task.id,
+                    agent_id = agent.id(),
+                    "Falling back to next agent"
+                );
+            }
+
+            let agent_result = match self.build_context(&task, agent, progress.clone()) {
+                Ok(mut context) => {
+                    if let Some(previous) = &previous {
+                        context = context.with_previous_result(previous.clone());
+                    }
+                    self.run_agent(agent, &task, context, remaining).await
+                }
+                Err(e) => Err(e),
+            };
+            match &agent_result {
+                Ok(r) if r.success => {
+                    self.results.save(PreviousResult {
+                        task_id: task.id.clone(),
+                        description: task.description.clone(),
+                        domain: agent.capability().domain,
+                        content: r.content.clone(),
+                        metadata: r.metadata.clone(),
+                    });
+                    result = Some(agent_result);
+                    break;
+                }
+                Ok(r) => failures.push(format!(
+                    "{}: {}",
+                    agent.id(),
+                    r.error.as_deref().unwrap_or("failed")
+                )),
+                Err(e) => failures.push(format!("{}: {}", agent.id(), e)),
+            }
+            result = Some(agent_result);
+        }
+
+        // A lone agent's failure is returned as-is; a failed chain is summarized
+        let mut result = match result {
+            Some(result) if chain.len() == 1 || matches!(&result, Ok(r) if r.success) => result,
+            _ => Err(bodhya_core::Error::Agent(format!(
+                "All agents failed for task: {}",
+                failures.join("; ")
+            ))),
+        };
+
+        if self.attach_routing {
+            if let Ok(agent_result) = &mut result {
+                Self::attach_routing_metadata(agent_result, &decision);
+            }
+        }
+
+        result
+    }
+
+    /// Registry of the tools `agent` requires, or None if it requires none
+    ///
+    /// Fails if a required tool is not registered.
+    fn tools_for(&self, agent: &dyn Agent) -> bodhya_core::Result<Option<ToolRegistry>> {
+        let required = agent.required_tools();
+        if required.is_empty() {
+            return Ok(None);
+        }
+        self.tools.subset(&required).map(Some).map_err(|e| match e {
+            bodhya_core::Error::Tool(msg) => {
+                bodhya_core::Error::Tool(format!("Agent '{}' cannot run: {}", agent.id(), msg))
+            }
+            other => other,
+        })
+    }
+
+    /// Create the context for one run of `agent`
+    ///
+    /// Only the tools the agent requires are attached. File tools are
+    /// sandboxed to the output directory if one is set, else to the working
+    /// directory.
+    fn build_context(
+        &self,
+        task: &Task,
+        agent: &Arc<dyn Agent>,
+        progress: Option<ProgressCallback>,
+    ) -> bodhya_core::Result<AgentContext> {
+        let limits = self
+            .execution_limits
+            .apply_to(agent.default_execution_limits());
+
+        // Create agent context with working directory and execution mode
         let mut context = AgentContext::new(self.config.clone())
-            .with_tools(Arc::clone(&self.tools) as Arc<dyn std::any::Any + Send + Sync>)
-            .with_execution_mode(self.execution_mode.clone());
+            .with_execution_mode(self.execution_mode.clone())
+            .with_execution_limits(limits);
 
         // Set working directory if specified
         if let Some(ref wd) = self.working_dir {
             context = context.with_working_dir(wd.clone());
         }
 
-        // Execute task through agent
+        if let Some(ref output_dir) = self.output_dir {
+            context = context.with_output_dir(output_dir.clone());
+        }
+
+        if let Some(tools) = self.tools_for(agent.as_ref())? {
+            // Confine file tools to where the task writes its files
+            let file_root = match &self.output_dir {
+                Some(output_dir) => Ok(output_dir.clone()),
+                None => context.get_working_dir(),
+            };
+            let tools = match file_root {
+                Ok(file_root) => tools.sandboxed(file_root),
+                Err(e) => {
+                    tracing::warn!("No working directory for sandboxing tools: {}", e);
+                    tools
+                }
+            };
+            context = context.with_tools(Arc::new(tools) as Arc<dyn std::any::Any + Send + Sync>);
+        }
+
+        if let Some(progress) = progress {
+            context = context.with_progress(progress);
+        }
+        if let Some(ref approval) = self.approval {
+            context = context.with_approval(Arc::clone(approval));
+        }
+        if let Some(ref sink) = self.events {
+            context = context.with_events(EventRecorder::new(task.id.clone(), Arc::clone(sink)));
+        }
+        Ok(context)
+    }
+
+    /// Run `task` through one agent, failing it if the timeout expires
+    ///
+    /// `remaining` is what is left of the task's timeout; the run stops at
+    /// whichever of it and the agent's own timeout comes first.
+    async fn run_agent(
+        &self,
+        agent: &Arc<dyn Agent>,
+        task: &Task,
+        context: AgentContext,
+        remaining: Option<Duration>,
+    ) -> bodhya_core::Result<AgentResult> {
+        context.report_phase("executing");
+        let events = context.events.clone();
+        if let Some(events) = &events {
+            events.record(RunEvent::TaskStarted {
+                agent_id: agent.id().to_string(),
+            });
+        }
+
+        // Execute task through agent, bounded by the global timeout
+        let timeout_secs = context.execution_limits.timeout_secs;
+        let timeout = match (context.execution_limits.timeout(), remaining) {
+            (Some(own), Some(remaining)) => Some(own.min(remaining)),
+            (own, remaining) => own.or(remaining),
+        };
         let start_time = std::time::Instant::now();
-        let result = agent.handle(task.clone(), context).await;
+        let result = match timeout {
+            Some(limit) => {
+                match tokio::time::timeout(limit, agent.handle(task.clone(), context)).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        tracing::warn!(
+                            task_id = %task.id,
+                            agent_id = agent.id(),
+                            timeout_secs,
+                            budget_ms = limit.as_millis() as u64,
+                            "Task timed out"
+                        );
+                        Ok(AgentResult::failure(
+                            task.id.clone(),
+                            format!("Task timed out after {}s", timeout_secs),
+                        ))
+                    }
+                }
+            }
+            None => agent.handle(task.clone(), context).await,
+        };
         let duration = start_time.elapsed();
 
+        if let Some(events) = &events {
+            let (success, error) = match &result {
+                Ok(agent_result) => (agent_result.success, agent_result.error.clone()),
+                Err(err) => (false, Some(err.to_string())),
+            };
+            events.record(RunEvent::TaskFinished {
+                agent_id: agent.id().to_string(),
+                success,
+                duration_ms: duration.as_millis() as u64,
+                error,
+            });
+        }
+
         match &result {
             Ok(agent_result) => {
                 tracing::info!(
@@ -186,6 +535,30 @@ impl TaskOrchestrator {
         result
     }
 
+    /// Insert the routing decision into result metadata under `routing`
+    ///
+    /// Metadata that is neither null nor an object is left untouched.
+    fn attach_routing_metadata(result: &mut AgentResult, decision: &RoutingDecision) {
+        let routing = match serde_json::to_value(decision) {
+            Ok(routing) => routing,
+            Err(e) => {
+                tracing::warn!("Failed to serialize routing decision: {}", e);
+                return;
+            }
+        };
+
+        if result.metadata.is_null() {
+            result.metadata = serde_json::json!({});
+        }
+
+        match result.metadata.as_object_mut() {
+            Some(metadata) => {
+                metadata.insert("routing".to_string(), routing);
+            }
+            None => tracing::debug!("Result metadata is not an object; skipping routing"),
+        }
+    }
+
     /// Execute multiple tasks concurrently
     pub async fn execute_batch(&self, tasks: Vec<Task>) -> Vec<bodhya_core::Result<AgentResult>> {
         tracing::info!(count = tasks.len(), "Executing batch of tasks");
@@ -212,6 +585,80 @@ impl TaskOrchestrator {
         results
     }
 
+    /// Execute tasks that may depend on each other's results
+    ///
+    /// A task runs once every task in its `depends_on` list has
+    /// succeeded, with their contents under `payload.dependencies` keyed by
+    /// task id. Tasks whose dependencies are met run concurrently, and a task
+    /// whose dependency failed fails without running. Results are returned in
+    /// the order given. Unknown or duplicate ids and dependency cycles are
+    /// rejected before anything runs.
+    pub async fn execute_pipeline(
+        &self,
+        tasks: Vec<Task>,
+    ) -> bodhya_core::Result<Vec<bodhya_core::Result<AgentResult>>> {
+        let stages = dependency_stages(&tasks)?;
+        tracing::info!(
+            count = tasks.len(),
+            stages = stages.len(),
+            "Executing task pipeline"
+        );
+
+        let index: HashMap<String, usize> = tasks
+            .iter()
+            .enumerate()
+            .map(|(i, task)| (task.id.clone(), i))
+            .collect();
+        let mut pending: Vec<Option<Task>> = tasks.into_iter().map(Some).collect();
+        let mut results: Vec<Option<bodhya_core::Result<AgentResult>>> =
+            pending.iter().map(|_| None).collect();
+
+        for stage in stages {
+            let mut runnable = Vec::new();
+            for i in stage {
+                let Some(mut task) = pending[i].take() else {
+                    continue;
+                };
+
+                let mut outputs = serde_json::Map::new();
+                let mut failed = None;
+                for dep in &task.depends_on {
+                    match &results[index[dep.as_str()]] {
+                        Some(Ok(result)) if result.success => {
+                            outputs.insert(dep.clone(), result.content.clone().into());
+                        }
+                        _ => {
+                            failed = Some(dep.clone());
+                            break;
+                        }
+                    }
+                }
+
+                if let Some(dep) = failed {
+                    results[i] = Some(Err(bodhya_core::Error::Agent(format!(
+                        "Task '{}' skipped: dependency '{}' failed",
+                        task.id, dep
+                    ))));
+                    continue;
+                }
+                if !outputs.is_empty() {
+                    task.payload["dependencies"] = serde_json::Value::Object(outputs);
+                }
+                runnable.push((i, task));
+            }
+
+            let (indices, batch): (Vec<usize>, Vec<Task>) = runnable.into_iter().unzip();
+            for (i, result) in indices.into_iter().zip(self.execute_batch(batch).await) {
+                results[i] = Some(result);
+            }
+        }
+
+        Ok(results
+            .into_iter()
+            .map(|result| result.expect("every task belongs to a stage"))
+            .collect())
+    }
+
     /// Clone this orchestrator for concurrent execution
     /// (Only clones immutable parts, agents are Arc-wrapped)
     fn clone_for_concurrent(&self) -> Arc<Self> {
@@ -221,11 +668,87 @@ impl TaskOrchestrator {
             config: self.config.clone(),
             tools: Arc::clone(&self.tools),
             working_dir: self.working_dir.clone(),
+            output_dir: self.output_dir.clone(),
             execution_mode: self.execution_mode.clone(),
+            attach_routing: self.attach_routing,
+            execution_limits: self.execution_limits.clone(),
+            approval: self.approval.clone(),
+            events: self.events.clone(),
+            results: Arc::clone(&self.results),
         })
     }
 }
 
+/// Group task indices into stages that only depend on earlier stages
+///
+/// Fails with `InvalidInput` on duplicate ids, unknown dependencies, a cycle,
+/// or a dependent task whose payload can't hold `dependencies` (not an object).
+fn dependency_stages(tasks: &[Task]) -> bodhya_core::Result<Vec<Vec<usize>>> {
+    let mut index = HashMap::new();
+    for (i, task) in tasks.iter().enumerate() {
+        if index.insert(task.id.as_str(), i).is_some() {
+            return Err(bodhya_core::Error::InvalidInput(format!(
+                "Duplicate task id '{}' in pipeline",
+                task.id
+            )));
+        }
+    }
+
+    let mut remaining = vec![0; tasks.len()];
+    let mut dependents = vec![Vec::new(); tasks.len()];
+    for (i, task) in tasks.iter().enumerate() {
+        if !(task.depends_on.is_empty() || task.payload.is_object() || task.payload.is_null()) {
+            return Err(bodhya_core::Error::InvalidInput(format!(
+                "Task '{}' has dependencies but its payload is not an object",
+                task.id
+            )));
+        }
+        for dep in &task.depends_on {
+            let &j = index.get(dep.as_str()).ok_or_else(|| {
+                bodhya_core::Error::InvalidInput(format!(
+                    "Task '{}' depends on unknown task '{}'",
+                    task.id, dep
+                ))
+            })?;
+            remaining[i] += 1;
+            dependents[j].push(i);
+        }
+    }
+
+    let mut stages = Vec::new();
+    let mut ready: Vec<usize> = (0..tasks.len()).filter(|&i| remaining[i] == 0).collect();
+    let mut staged = 0;
+    while !ready.is_empty() {
+        let mut next = Vec::new();
+        for &i in &ready {
+            for &dependent in &dependents[i] {
+                remaining[dependent] -= 1;
+                if remaining[dependent] == 0 {
+                    next.push(dependent);
+                }
+            }
+        }
+        next.sort_unstable();
+        staged += ready.len();
+        stages.push(std::mem::replace(&mut ready, next));
+    }
+
+    if staged < tasks.len() {
+        let cycle: Vec<&str> = tasks
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| remaining[*i] > 0)
+            .map(|(_, task)| task.id.as_str())
+            .collect();
+        return Err(bodhya_core::Error::InvalidInput(format!(
+            "Dependency cycle among tasks: {}",
+            cycle.join(", ")
+        )));
+    }
+
+    Ok(stages)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,6 +792,131 @@ mod tests {
         }
     }
 
+    /// Agent that reports which dependency outputs it received
+    struct PipelineAgent {
+        started: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Agent for PipelineAgent {
+        fn id(&self) -> &'static str {
+            "pipeline"
+        }
+
+        fn capability(&self) -> AgentCapability {
+            AgentCapability::new("test", vec![], "Pipeline test agent")
+        }
+
+        async fn handle(&self, task: Task, _ctx: AgentContext) -> bodhya_core::Result<AgentResult> {
+            self.started.lock().unwrap().push(task.id.clone());
+            let mut inputs: Vec<String> = task.payload["dependencies"]
+                .as_object()
+                .map(|deps| {
+                    deps.values()
+                        .filter_map(|v| v.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            inputs.sort();
+            let content = if inputs.is_empty() {
+                task.id.clone()
+            } else {
+                format!("{}({})", task.id, inputs.join("+"))
+            };
+            Ok(AgentResult::success(task.id, content))
+        }
+    }
+
+    fn pipeline_orchestrator() -> (TaskOrchestrator, Arc<std::sync::Mutex<Vec<String>>>) {
+        let started = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut orchestrator = TaskOrchestrator::new(create_test_config());
+        orchestrator.router_mut().register(Arc::new(PipelineAgent {
+            started: Arc::clone(&started),
+        }));
+        (orchestrator, started)
+    }
+
+    fn pipeline_task(id: &str, depends_on: &[&str]) -> Task {
+        let mut task = Task::new(format!("Step {}", id))
+            .with_domain("test")
+            .with_depends_on(depends_on.iter().map(|d| d.to_string()).collect());
+        task.id = id.to_string();
+        task
+    }
+
+    fn contents(results: Vec<bodhya_core::Result<AgentResult>>) -> Vec<String> {
+        results.into_iter().map(|r| r.unwrap().content).collect()
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_runs_linear_chain_in_order() {
+        let (orchestrator, started) = pipeline_orchestrator();
+
+        // Given out of order: c needs b, b needs a
+        let tasks = vec![
+            pipeline_task("c", &["b"]),
+            pipeline_task("a", &[]),
+            pipeline_task("b", &["a"]),
+        ];
+        let results = orchestrator.execute_pipeline(tasks).await.unwrap();
+
+        assert_eq!(*started.lock().unwrap(), vec!["a", "b", "c"]);
+        assert_eq!(contents(results), vec!["c(b(a))", "a", "b(a)"]);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_runs_diamond_dependencies() {
+        let (orchestrator, started) = pipeline_orchestrator();
+
+        let tasks = vec![
+            pipeline_task("a", &[]),
+            pipeline_task("b", &["a"]),
+            pipeline_task("c", &["a"]),
+            pipeline_task("d", &["b", "c"]),
+        ];
+        assert_eq!(
+            dependency_stages(&tasks).unwrap(),
+            vec![vec![0], vec![1, 2], vec![3]]
+        );
+
+        let results = orchestrator.execute_pipeline(tasks).await.unwrap();
+
+        let started = started.lock().unwrap();
+        assert_eq!(started.first().map(String::as_str), Some("a"));
+        assert_eq!(started.last().map(String::as_str), Some("d"));
+        assert_eq!(contents(results), vec!["a", "b(a)", "c(a)", "d(b(a)+c(a))"]);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_rejects_cycle() {
+        let (orchestrator, started) = pipeline_orchestrator();
+
+        let tasks = vec![
+            pipeline_task("a", &[]),
+            pipeline_task("b", &["c"]),
+            pipeline_task("c", &["b"]),
+        ];
+        let err = orchestrator.execute_pipeline(tasks).await.unwrap_err();
+
+        assert!(matches!(err, bodhya_core::Error::InvalidInput(_)));
+        assert!(err
+            .to_string()
+            .contains("Dependency cycle among tasks: b, c"));
+        assert!(started.lock().unwrap().is_empty());
+
+        let err = dependency_stages(&[pipeline_task("a", &["ghost"])]).unwrap_err();
+        assert!(err.to_string().contains("unknown task 'ghost'"));
+    }
+
+    #[test]
+    fn test_pipeline_rejects_dependent_with_non_object_payload() {
+        let plain = pipeline_task("b", &["a"]).with_payload(serde_json::json!("notes"));
+        let err = dependency_stages(&[pipeline_task("a", &[]), plain]).unwrap_err();
+
+        assert!(matches!(err, bodhya_core::Error::InvalidInput(_)));
+        assert!(err.to_string().contains("payload is not an object"));
+    }
+
     #[test]
     fn test_orchestrator_creation() {
         let config = create_test_config();
@@ -310,6 +958,247 @@ mod tests {
         assert!(result.content.contains("Processed by test"));
     }
 
+    #[tokio::test]
+    async fn test_execute_attaches_routing_decision() {
+        let mut orchestrator = TaskOrchestrator::new(create_test_config());
+        orchestrator.router_mut().register(Arc::new(MockAgent {
+            id: "test",
+            should_fail: false,
+        }));
+        orchestrator.set_attach_routing(true);
+
+        let result = orchestrator
+            .execute(Task::new("Run the test suite"))
+            .await
+            .unwrap();
+
+        let routing = &result.metadata["routing"];
+        assert_eq!(routing["chosen_agent"], "test");
+        assert_eq!(routing["reason"]["kind"], "keywords");
+        assert_eq!(routing["reason"]["matched_keywords"][0], "test");
+
+        let decision: RoutingDecision = serde_json::from_value(routing.clone()).unwrap();
+        assert!(decision.to_string().contains("test"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_omits_routing_by_default() {
+        let mut orchestrator = TaskOrchestrator::new(create_test_config());
+        orchestrator.router_mut().register(Arc::new(MockAgent {
+            id: "test",
+            should_fail: false,
+        }));
+
+        let result = orchestrator
+            .execute(Task::new("Run the test suite"))
+            .await
+            .unwrap();
+        assert!(result.metadata.get("routing").is_none());
+    }
+
+    /// Agent that sleeps before responding
+    struct SlowAgent {
+        id: &'static str,
+        delay: std::time::Duration,
+    }
+
+    #[async_trait]
+    impl Agent for SlowAgent {
+        fn id(&self) -> &'static str {
+            self.id
+        }
+
+        fn capability(&self) -> AgentCapability {
+            AgentCapability::new("test", vec!["test".to_string()], "Slow test agent")
+        }
+
+        async fn handle(&self, task: Task, _ctx: AgentContext) -> bodhya_core::Result<AgentResult> {
+            tokio::time::sleep(self.delay).await;
+            Ok(AgentResult::success(task.id, "finished"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_task_timeout() {
+        let mut orchestrator = TaskOrchestrator::new(create_test_config());
+        orchestrator.router_mut().register(Arc::new(SlowAgent {
+            id: "slow",
+            delay: std::time::Duration::from_secs(30),
+        }));
+        orchestrator.set_execution_limits(ExecutionLimits::new(3, 20, 10, 1));
+
+        let start = std::time::Instant::now();
+        let result = orchestrator.execute(Task::new("Slow task")).await.unwrap();
+
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("timed out"));
+    }
+
+    /// Reports the limits it ran with
+    struct RefactorAgent;
+
+    #[async_trait]
+    impl Agent for RefactorAgent {
+        fn id(&self) -> &'static str {
+            "code"
+        }
+
+        fn capability(&self) -> AgentCapability {
+            AgentCapability::new("code", vec![], "Refactoring agent")
+        }
+
+        fn default_execution_limits(&self) -> ExecutionLimits {
+            ExecutionLimits::new(8, 100, 40, 900)
+        }
+
+        async fn handle(&self, task: Task, ctx: AgentContext) -> bodhya_core::Result<AgentResult> {
+            let limits = ctx.execution_limits;
+            Ok(AgentResult::success(
+                task.id,
+                format!(
+                    "{} {} {} {}",
+                    limits.max_iterations,
+                    limits.max_file_writes,
+                    limits.max_command_executions,
+                    limits.timeout_secs
+                ),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_agent_default_limits_fill_unset_fields() {
+        let mut orchestrator = TaskOrchestrator::new(create_test_config());
+        orchestrator.router_mut().register(Arc::new(RefactorAgent));
+
+        let result = orchestrator.execute(Task::new("Refactor")).await.unwrap();
+        assert_eq!(result.content, "8 100 40 900");
+
+        orchestrator.set_execution_limit_overrides(ExecutionLimitOverrides {
+            max_file_writes: Some(5),
+            ..Default::default()
+        });
+        let result = orchestrator.execute(Task::new("Refactor")).await.unwrap();
+        assert_eq!(result.content, "8 5 40 900");
+
+        // Fully set limits leave nothing to the agent
+        orchestrator.set_execution_limits(ExecutionLimits::default());
+        let result = orchestrator.execute(Task::new("Refactor")).await.unwrap();
+        assert_eq!(result.content, "3 20 10 300");
+    }
+
+    #[tokio::test]
+    async fn test_configured_mcp_servers_register_tools() {
+        // Answers initialize, then lists two tools
+        let script = r#"read line; echo '{"jsonrpc":"2.0","id":1,"result":{}}'; read line; echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"echo"},{"name":"reverse"}]}}'; cat > /dev/null"#;
+        let mut config = create_test_config();
+        config.tools.mcp_servers = vec![
+            bodhya_core::McpServerConfig::new_stdio(
+                "mock",
+                vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            ),
+            bodhya_core::McpServerConfig::new_stdio(
+                "broken",
+                vec!["bodhya-no-such-mcp-server".to_string()],
+            ),
+        ];
+
+        let mut orchestrator = TaskOrchestrator::new_with_mcp(config).await.unwrap();
+
+        let tools = orchestrator.tools().list_tools();
+        assert!(tools.contains(&"echo".to_string()));
+        assert!(tools.contains(&"reverse".to_string()));
+        assert!(tools.contains(&"filesystem".to_string()));
+
+        // Agents that require MCP tools get the configured servers' tools
+        let code_tools = orchestrator
+            .tools_for(&bodhya_agent_code::CodeAgent::new())
+            .unwrap()
+            .unwrap()
+            .list_tools();
+        assert!(code_tools.contains(&"echo".to_string()));
+        assert!(code_tools.contains(&"reverse".to_string()));
+
+        Arc::get_mut(&mut orchestrator.tools)
+            .unwrap()
+            .disconnect_all()
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_agents_get_only_required_tools() {
+        use bodhya_agent_code::CodeAgent;
+        use bodhya_agent_mail::MailAgent;
+
+        let orchestrator = TaskOrchestrator::new(create_test_config());
+
+        assert!(orchestrator.tools_for(&MailAgent::new()).unwrap().is_none());
+
+        let code_tools = orchestrator
+            .tools_for(&CodeAgent::new())
+            .unwrap()
+            .unwrap()
+            .list_tools();
+        assert!(code_tools.contains(&"filesystem".to_string()));
+        assert!(code_tools.contains(&"shell".to_string()));
+        assert!(!code_tools.contains(&"git".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_missing_required_tool_fails_fast() {
+        let mut orchestrator =
+            TaskOrchestrator::with_tools(create_test_config(), Arc::new(ToolRegistry::new()));
+        orchestrator
+            .router_mut()
+            .register(Arc::new(bodhya_agent_code::CodeAgent::new()));
+
+        let err = orchestrator
+            .execute(Task::new("Write a function"))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Tool error: Agent 'code' cannot run: Tool 'filesystem' not found"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_task_unlimited_timeout() {
+        let mut orchestrator = TaskOrchestrator::new(create_test_config());
+        orchestrator.router_mut().register(Arc::new(SlowAgent {
+            id: "slow",
+            delay: std::time::Duration::from_millis(10),
+        }));
+        orchestrator.set_execution_limits(ExecutionLimits::unlimited());
+
+        let result = orchestrator.execute(Task::new("Slow task")).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.content, "finished");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_progress_reports_phases() {
+        let mut orchestrator = TaskOrchestrator::new(create_test_config());
+        orchestrator.router_mut().register(Arc::new(MockAgent {
+            id: "test",
+            should_fail: false,
+        }));
+
+        let phases = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&phases);
+        let progress: ProgressCallback =
+            Arc::new(move |phase| recorded.lock().unwrap().push(phase.to_string()));
+
+        orchestrator
+            .execute_with_progress(Task::new("Run the test suite"), Some(progress))
+            .await
+            .unwrap();
+
+        assert_eq!(*phases.lock().unwrap(), vec!["routing", "executing"]);
+    }
+
     #[tokio::test]
     async fn test_execute_task_failure() {
         let config = create_test_config();
@@ -327,6 +1216,122 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Orchestrator routing "test" tasks to `primary`, falling back to `secondary`
+    fn create_fallback_orchestrator(
+        primary_fails: bool,
+        secondary_fails: bool,
+    ) -> TaskOrchestrator {
+        let mut config = create_test_config();
+        config
+            .routing
+            .fallbacks
+            .insert("test".to_string(), vec!["secondary".to_string()]);
+
+        let mut orchestrator = TaskOrchestrator::new(config);
+        orchestrator.router_mut().register(Arc::new(MockAgent {
+            id: "primary",
+            should_fail: primary_fails,
+        }));
+        orchestrator.router_mut().register(Arc::new(MockAgent {
+            id: "secondary",
+            should_fail: secondary_fails,
+        }));
+        orchestrator
+    }
+
+    #[tokio::test]
+    async fn test_fallback_agent_after_primary_fails() {
+        let orchestrator = create_fallback_orchestrator(true, false);
+
+        let result = orchestrator
+            .execute(Task::new("Test task").with_domain("test"))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.content, "Processed by secondary");
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_aggregates_errors() {
+        let orchestrator = create_fallback_orchestrator(true, true);
+
+        let err = orchestrator
+            .execute(Task::new("Test task").with_domain("test"))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("All agents failed"));
+        assert!(err.contains("primary: "));
+        assert!(err.contains("secondary: "));
+    }
+
+    #[tokio::test]
+    async fn test_fallbacks_share_the_task_timeout() {
+        let mut config = create_test_config();
+        config
+            .routing
+            .fallbacks
+            .insert("test".to_string(), vec!["slow-fallback".to_string()]);
+        let mut orchestrator = TaskOrchestrator::new(config);
+        for id in ["slow-primary", "slow-fallback"] {
+            orchestrator.router_mut().register(Arc::new(SlowAgent {
+                id,
+                delay: std::time::Duration::from_secs(30),
+            }));
+        }
+        orchestrator.set_execution_limits(ExecutionLimits::new(3, 20, 10, 1));
+
+        let start = std::time::Instant::now();
+        let err = orchestrator
+            .execute(Task::new("Slow task").with_domain("test"))
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(start.elapsed() < std::time::Duration::from_millis(1900));
+        assert!(err.contains("slow-primary: Task timed out after 1s"));
+        assert!(err.contains("slow-fallback: not run, task timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_event_log_records_each_agent_run() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("events.jsonl");
+
+        let mut config = create_test_config();
+        config.logging.event_log = Some(path.clone());
+        config
+            .routing
+            .fallbacks
+            .insert("test".to_string(), vec!["secondary".to_string()]);
+        let mut orchestrator = TaskOrchestrator::new(config);
+        orchestrator.router_mut().register(Arc::new(MockAgent {
+            id: "primary",
+            should_fail: true,
+        }));
+        orchestrator.router_mut().register(Arc::new(MockAgent {
+            id: "secondary",
+            should_fail: false,
+        }));
+
+        let task = Task::new("Test task").with_domain("test");
+        let task_id = task.id.clone();
+        orchestrator.execute(task).await.unwrap();
+
+        let records = bodhya_core::events::read_events(&path).unwrap();
+        assert!(records.iter().all(|r| r.task_id == task_id));
+        let events: Vec<_> = records.into_iter().map(|r| r.event).collect();
+        assert!(matches!(
+            &events[..],
+            [
+                RunEvent::TaskStarted { agent_id: first },
+                RunEvent::TaskFinished { success: false, error: Some(_), .. },
+                RunEvent::TaskStarted { agent_id: second },
+                RunEvent::TaskFinished { success: true, .. },
+            ] if first == "primary" && second == "secondary"
+        ));
+    }
+
     #[tokio::test]
     async fn test_execute_task_no_agent() {
         let config = create_test_config();
@@ -0,0 +1,100 @@
+/// Storage for completed task results
+///
+/// The orchestrator records each successful result so a later task can
+/// refine it by id through `payload.refine_of`.
+use bodhya_core::PreviousResult;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Number of results kept by [`InMemoryResultStore::default`]
+pub const DEFAULT_RESULT_CAPACITY: usize = 100;
+
+/// Keeps completed results for later refinement
+pub trait ResultStore: Send + Sync {
+    /// Record a completed result, replacing any earlier one with the same task id
+    fn save(&self, result: PreviousResult);
+
+    /// Look up the result of task `task_id`
+    fn get(&self, task_id: &str) -> Option<PreviousResult>;
+}
+
+/// Keeps the most recent results in memory, evicting the oldest first
+pub struct InMemoryResultStore {
+    capacity: usize,
+    results: Mutex<VecDeque<PreviousResult>>,
+}
+
+impl InMemoryResultStore {
+    /// Create a store holding at most `capacity` results
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            results: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl Default for InMemoryResultStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_RESULT_CAPACITY)
+    }
+}
+
+impl ResultStore for InMemoryResultStore {
+    fn save(&self, result: PreviousResult) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut results = self.results.lock().unwrap_or_else(|e| e.into_inner());
+        results.retain(|r| r.task_id != result.task_id);
+        while results.len() >= self.capacity {
+            results.pop_front();
+        }
+        results.push_back(result);
+    }
+
+    fn get(&self, task_id: &str) -> Option<PreviousResult> {
+        let results = self.results.lock().unwrap_or_else(|e| e.into_inner());
+        results.iter().find(|r| r.task_id == task_id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(task_id: &str) -> PreviousResult {
+        PreviousResult {
+            task_id: task_id.to_string(),
+            description: "task".to_string(),
+            domain: "code".to_string(),
+            content: format!("content of {}", task_id),
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_evicts_oldest_beyond_capacity() {
+        let store = InMemoryResultStore::new(2);
+        store.save(result("a"));
+        store.save(result("b"));
+        store.save(result("c"));
+
+        assert!(store.get("a").is_none());
+        assert_eq!(store.get("b").unwrap().content, "content of b");
+        assert_eq!(store.get("c").unwrap().content, "content of c");
+    }
+
+    #[test]
+    fn test_save_replaces_same_task() {
+        let store = InMemoryResultStore::new(2);
+        store.save(result("a"));
+        let mut updated = result("a");
+        updated.content = "updated".to_string();
+        store.save(updated);
+        store.save(result("b"));
+
+        assert_eq!(store.get("a").unwrap().content, "updated");
+        assert!(store.get("b").is_some());
+    }
+}
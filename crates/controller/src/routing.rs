@@ -2,34 +2,263 @@
 ///
 /// This module implements intelligent routing that matches tasks to agents
 /// based on their capability metadata (domain, intents, keywords).
-use bodhya_core::{Agent, AgentCapability, Error, Result, Task};
+use bodhya_core::{
+    Agent, AgentCapability, AgentSuggestion, Error, KeywordMatching, Result, RoutingOverride, Task,
+};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Why the router chose an agent
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RoutingReason {
+    /// The task's domain hint restricted the candidates to this domain
+    DomainHint {
+        domain: String,
+        matched_keywords: Vec<String>,
+    },
+    /// The agent's keywords scored highest against the description
+    Keywords { matched_keywords: Vec<String> },
+    /// The agent supports the task's intent verb, which broke a tie in the
+    /// keyword scores
+    Intent {
+        intent: String,
+        matched_keywords: Vec<String>,
+    },
+    /// Nothing matched, so the first enabled agent was used
+    Fallback,
+    /// No enabled agent matched the domain, so its fallback chain was used
+    DomainFallback { domain: String },
+    /// A configured override pattern matched the description
+    Override { pattern: String },
+}
+
+/// Routing score for a single candidate agent
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentScore {
+    pub agent_id: String,
+    pub score: usize,
+    pub matched_keywords: Vec<String>,
+    /// The task's intent verb, if the agent supports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_intent: Option<String>,
+}
+
+/// Explanation of a routing choice
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingDecision {
+    /// ID of the selected agent
+    pub chosen_agent: String,
+    /// What drove the selection
+    pub reason: RoutingReason,
+    /// Scores for every candidate considered
+    pub scores: Vec<AgentScore>,
+}
+
+impl std::fmt::Display for RoutingDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Routed to '{}': ", self.chosen_agent)?;
+        match &self.reason {
+            RoutingReason::DomainHint {
+                domain,
+                matched_keywords,
+            } if matched_keywords.is_empty() => write!(f, "domain hint '{}'", domain)?,
+            RoutingReason::DomainHint {
+                domain,
+                matched_keywords,
+            } => write!(
+                f,
+                "domain hint '{}' (keywords: {})",
+                domain,
+                matched_keywords.join(", ")
+            )?,
+            RoutingReason::Keywords { matched_keywords } => {
+                write!(f, "matched keywords: {}", matched_keywords.join(", "))?
+            }
+            RoutingReason::Intent {
+                intent,
+                matched_keywords,
+            } if matched_keywords.is_empty() => write!(f, "intent '{}'", intent)?,
+            RoutingReason::Intent {
+                intent,
+                matched_keywords,
+            } => write!(
+                f,
+                "intent '{}' (keywords: {})",
+                intent,
+                matched_keywords.join(", ")
+            )?,
+            RoutingReason::Fallback => write!(f, "no keywords matched, using first agent")?,
+            RoutingReason::DomainFallback { domain } => {
+                write!(f, "no enabled agent for '{}', using its fallbacks", domain)?
+            }
+            RoutingReason::Override { pattern } => write!(f, "override '{}'", pattern)?,
+        }
+
+        for score in &self.scores {
+            write!(f, "\n  {:<12} score {}", score.agent_id, score.score)?;
+            if let Some(intent) = &score.matched_intent {
+                write!(f, " intent '{}'", intent)?;
+            }
+            if !score.matched_keywords.is_empty() {
+                write!(f, " [{}]", score.matched_keywords.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Closest agents listed when no agent can take a task
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Words skipped when looking for the intent verb ("please write ...",
+/// "can you refine ...", "I want to draft ...")
+const LEADING_FILLER: &[&str] = &[
+    "please", "kindly", "can", "could", "would", "will", "you", "i", "we", "want", "need", "like",
+    "to", "let", "lets", "let's", "us", "me", "help", "just", "now", "and", "also",
+];
+
+/// Words that show the task does not open with a verb
+const NOT_VERBS: &[&str] = &[
+    "a", "an", "the", "this", "that", "these", "those", "my", "our", "your", "it", "there", "what",
+    "why", "how", "when", "where", "who", "which", "is", "are",
+];
+
+/// The likely intent verb of a task: its first word after polite filler
+///
+/// Task descriptions are usually imperative ("Refine this email"), so the
+/// leading word is the verb. Returns None when the description opens with
+/// something that is clearly not a verb, such as an article or a question.
+pub fn extract_intent(description: &str) -> Option<String> {
+    let first = description
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .find(|word| !LEADING_FILLER.contains(&word.as_str()))?;
+
+    (!NOT_VERBS.contains(&first.as_str()) && first.chars().all(char::is_alphabetic))
+        .then_some(first)
+}
+
 /// Router for selecting agents based on task requirements
+///
+/// Agents are registered at startup; the registered set is an immutable
+/// shared slice, so cloning the router (e.g., for concurrent execution) is
+/// cheap and a built router can be shared across threads without locking.
+#[derive(Clone)]
 pub struct AgentRouter {
     /// Registered agents
-    agents: Vec<Arc<dyn Agent>>,
+    agents: Arc<[Arc<dyn Agent>]>,
+    /// Agent IDs to try after the routed agent fails (lowercase domain -> IDs)
+    fallbacks: HashMap<String, Vec<String>>,
+    /// How loosely keywords match task descriptions
+    keyword_matching: KeywordMatching,
+    /// Override patterns, checked in order before scoring
+    overrides: Vec<CompiledOverride>,
+}
+
+/// A routing override with its pattern compiled
+#[derive(Clone)]
+struct CompiledOverride {
+    pattern: String,
+    regex: Regex,
+    agent_id: String,
+}
+
+impl CompiledOverride {
+    fn compile(config: &RoutingOverride) -> std::result::Result<Self, regex::Error> {
+        let source = if config.regex {
+            config.pattern.clone()
+        } else {
+            format!(r"\b{}\b", regex::escape(config.pattern.trim()))
+        };
+        Ok(Self {
+            pattern: config.pattern.clone(),
+            regex: RegexBuilder::new(&source).case_insensitive(true).build()?,
+            agent_id: config.agent.clone(),
+        })
+    }
 }
 
 impl AgentRouter {
     /// Create a new router
     pub fn new() -> Self {
-        Self { agents: Vec::new() }
+        Self {
+            agents: Arc::new([]),
+            fallbacks: HashMap::new(),
+            keyword_matching: KeywordMatching::default(),
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Set the override patterns, checked in order before keyword scoring
+    ///
+    /// Overrides whose regex does not compile are logged and skipped.
+    pub fn with_overrides(mut self, overrides: &[RoutingOverride]) -> Self {
+        self.overrides = overrides
+            .iter()
+            .filter_map(|config| match CompiledOverride::compile(config) {
+                Ok(compiled) => Some(compiled),
+                Err(e) => {
+                    tracing::warn!(pattern = %config.pattern, "Ignoring routing override: {}", e);
+                    None
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Set how loosely keywords match task descriptions
+    pub fn with_keyword_matching(mut self, keyword_matching: KeywordMatching) -> Self {
+        self.keyword_matching = keyword_matching;
+        self
+    }
+
+    /// Set the fallback chains for several domains (domain -> agent IDs)
+    pub fn with_fallbacks(mut self, fallbacks: HashMap<String, Vec<String>>) -> Self {
+        for (domain, agent_ids) in fallbacks {
+            self.set_fallbacks(&domain, agent_ids);
+        }
+        self
+    }
+
+    /// Set the agents to try, in order, when the agent routed for `domain` fails
+    pub fn set_fallbacks(&mut self, domain: &str, agent_ids: Vec<String>) {
+        self.fallbacks.insert(domain.to_lowercase(), agent_ids);
     }
 
     /// Register an agent
+    ///
+    /// Copies the agent list, so this is meant for setup rather than for
+    /// routers that are already shared.
     pub fn register(&mut self, agent: Arc<dyn Agent>) {
-        self.agents.push(agent);
+        let mut agents = self.agents.to_vec();
+        agents.push(agent);
+        self.agents = agents.into();
     }
 
     /// Select the best agent for a task
     ///
     /// Selection logic:
     /// 1. If task has domain_hint, filter by exact domain match
-    /// 2. Score remaining agents by keyword matches in task description
-    /// 3. Return highest scoring enabled agent
-    /// 4. Error if no suitable agent found
+    /// 2. If a configured override matches, use its agent when it is a candidate
+    /// 3. Score remaining agents by keyword matches in task description,
+    ///    boosting agents that list the task's intent verb
+    /// 4. Return highest scoring enabled agent
+    /// 5. Error if no suitable agent found
     pub fn select_agent(&self, task: &Task) -> Result<Arc<dyn Agent>> {
+        self.route(task).map(|(agent, _)| agent)
+    }
+
+    /// Select an agent and explain the choice
+    ///
+    /// Uses the same logic as [`select_agent`](Self::select_agent), but also
+    /// returns a [`RoutingDecision`] recording the per-agent scores and
+    /// whether the domain hint or keywords drove the selection.
+    pub fn route(&self, task: &Task) -> Result<(Arc<dyn Agent>, RoutingDecision)> {
         if self.agents.is_empty() {
             return Err(Error::Agent("No agents registered".to_string()));
         }
@@ -44,34 +273,101 @@ impl AgentRouter {
         // If domain hint provided, filter by domain
         let candidates: Vec<_> = if let Some(ref domain_hint) = task.domain_hint {
             enabled_agents
-                .into_iter()
+                .iter()
+                .copied()
                 .filter(|a| a.capability().domain.eq_ignore_ascii_case(domain_hint))
                 .collect()
         } else {
-            enabled_agents
+            enabled_agents.clone()
         };
 
         if candidates.is_empty() {
-            if let Some(ref domain) = task.domain_hint {
-                Err(Error::AgentNotFound(format!(
-                    "No enabled agent found for domain '{}'",
-                    domain
-                )))
-            } else {
-                // No domain hint, fall through to keyword matching
-                self.select_by_keywords(
-                    &self
-                        .agents
-                        .iter()
-                        .filter(|a| a.is_enabled())
-                        .collect::<Vec<_>>(),
-                    task,
-                )
-            }
-        } else {
-            // Score by keyword matching
-            self.select_by_keywords(&candidates, task)
+            return Err(Error::NoAgentMatched {
+                message: match task.domain_hint {
+                    Some(ref domain) => format!("No enabled agent found for domain '{}'", domain),
+                    None => "No suitable agent found for task".to_string(),
+                },
+                suggestions: self.suggest(&enabled_agents, task),
+            });
+        }
+
+        // A matching override wins outright
+        if let Some(routed) = self.select_by_override(&candidates, task) {
+            return Ok(routed);
         }
+
+        // Score by keyword matching
+        self.select_by_keywords(&candidates, task)
+    }
+
+    /// The first override matching the description whose agent is a candidate
+    fn select_by_override(
+        &self,
+        candidates: &[&Arc<dyn Agent>],
+        task: &Task,
+    ) -> Option<(Arc<dyn Agent>, RoutingDecision)> {
+        self.overrides
+            .iter()
+            .filter(|o| o.regex.is_match(&task.description))
+            .find_map(|o| {
+                let agent = candidates.iter().find(|a| a.id() == o.agent_id)?;
+                let decision = RoutingDecision {
+                    chosen_agent: o.agent_id.clone(),
+                    reason: RoutingReason::Override {
+                        pattern: o.pattern.clone(),
+                    },
+                    scores: Vec::new(),
+                };
+                Some((Arc::clone(agent), decision))
+            })
+    }
+
+    /// Route a task to an ordered chain of agents to try
+    ///
+    /// The routed agent comes first, followed by the enabled fallbacks
+    /// configured for its domain. If no enabled agent matches the task's
+    /// domain hint, the chain is that domain's enabled fallbacks.
+    pub fn route_chain(&self, task: &Task) -> Result<(Vec<Arc<dyn Agent>>, RoutingDecision)> {
+        let err = match self.route(task) {
+            Ok((primary, decision)) => {
+                let mut chain = vec![primary];
+                for agent in self.fallback_agents(&chain[0].capability().domain) {
+                    if chain.iter().all(|a| a.id() != agent.id()) {
+                        chain.push(agent);
+                    }
+                }
+                return Ok((chain, decision));
+            }
+            Err(err) => err,
+        };
+
+        let Some(domain) = task.domain_hint.as_deref() else {
+            return Err(err);
+        };
+        let chain = self.fallback_agents(domain);
+        let Some(first) = chain.first() else {
+            return Err(err);
+        };
+
+        let decision = RoutingDecision {
+            chosen_agent: first.id().to_string(),
+            reason: RoutingReason::DomainFallback {
+                domain: domain.to_string(),
+            },
+            scores: Vec::new(),
+        };
+        Ok((chain, decision))
+    }
+
+    /// Enabled, registered agents in `domain`'s fallback chain
+    fn fallback_agents(&self, domain: &str) -> Vec<Arc<dyn Agent>> {
+        self.fallbacks
+            .get(&domain.to_lowercase())
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.get_agent(id))
+            .filter(|agent| agent.is_enabled())
+            .collect()
     }
 
     /// Select agent by keyword matching
@@ -79,46 +375,114 @@ impl AgentRouter {
         &self,
         candidates: &[&Arc<dyn Agent>],
         task: &Task,
-    ) -> Result<Arc<dyn Agent>> {
-        let mut best_agent: Option<&Arc<dyn Agent>> = None;
-        let mut best_score = 0;
+    ) -> Result<(Arc<dyn Agent>, RoutingDecision)> {
+        let scores = self.score_agents(candidates, task);
 
-        for agent in candidates {
-            let capability = agent.capability();
-            let score = self.score_capability(&capability, task);
+        // Keywords decide; the intent verb only breaks ties, so a verb shared
+        // by several domains ("create", "write") cannot outweigh the nouns
+        let rank = |score: &AgentScore| (score.score, score.matched_intent.is_some());
+        let mut best: Option<usize> = None;
+        let mut best_rank = (0, false);
 
-            if score > best_score {
-                best_score = score;
-                best_agent = Some(agent);
+        for (index, score) in scores.iter().enumerate() {
+            if rank(score) > best_rank {
+                best_rank = rank(score);
+                best = Some(index);
             }
         }
 
-        match best_agent {
-            Some(agent) => Ok(Arc::clone(agent)),
-            None => {
-                // Fallback: return first candidate if no keywords matched
-                if let Some(first) = candidates.first() {
-                    Ok(Arc::clone(first))
-                } else {
-                    Err(Error::AgentNotFound(
-                        "No suitable agent found for task".to_string(),
-                    ))
+        // Fallback: use the first candidate if nothing scored
+        let index = best.unwrap_or(0);
+        let intent_decided = scores[index].matched_intent.is_some()
+            && scores
+                .iter()
+                .any(|other| other.score == scores[index].score && other.matched_intent.is_none());
+        let agent = candidates
+            .get(index)
+            .ok_or_else(|| Error::AgentNotFound("No suitable agent found for task".to_string()))?;
+
+        let matched_keywords = scores[index].matched_keywords.clone();
+        let reason = match (&task.domain_hint, &scores[index].matched_intent) {
+            (Some(domain), _) => RoutingReason::DomainHint {
+                domain: domain.clone(),
+                matched_keywords,
+            },
+            (None, _) if best.is_none() => RoutingReason::Fallback,
+            (None, Some(intent)) if intent_decided || scores[index].score == 0 => {
+                RoutingReason::Intent {
+                    intent: intent.clone(),
+                    matched_keywords,
                 }
             }
+            (None, _) => RoutingReason::Keywords { matched_keywords },
+        };
+
+        let decision = RoutingDecision {
+            chosen_agent: agent.id().to_string(),
+            reason,
+            scores,
+        };
+
+        Ok((Arc::clone(agent), decision))
+    }
+
+    /// Score each candidate against the task, in candidate order
+    fn score_agents(&self, candidates: &[&Arc<dyn Agent>], task: &Task) -> Vec<AgentScore> {
+        let intent = extract_intent(&task.description);
+        candidates
+            .iter()
+            .map(|agent| {
+                let capability = agent.capability();
+                let matched_intent = intent
+                    .as_ref()
+                    .filter(|verb| capability.supports_intent(verb))
+                    .cloned();
+                AgentScore {
+                    agent_id: agent.id().to_string(),
+                    score: self.score_capability(&capability, task),
+                    matched_keywords: self.matched_keywords(&capability, task),
+                    matched_intent,
+                }
+            })
+            .collect()
+    }
+
+    /// The closest of `agents` to a task no agent could take, best first
+    ///
+    /// An unmatched domain hint is scored as one more word of the
+    /// description, so `--domain email` points at an agent with that keyword.
+    fn suggest(&self, agents: &[&Arc<dyn Agent>], task: &Task) -> Vec<AgentSuggestion> {
+        let mut probe = task.clone();
+        if let Some(domain) = probe.domain_hint.take() {
+            probe.description = format!("{} {}", domain, probe.description);
         }
+
+        let mut ranked: Vec<(AgentSuggestion, bool)> = self
+            .score_agents(agents, &probe)
+            .into_iter()
+            .zip(agents)
+            .map(|(score, agent)| {
+                let suggestion = AgentSuggestion {
+                    agent_id: score.agent_id,
+                    domain: agent.capability().domain,
+                    score: score.score,
+                };
+                (suggestion, score.matched_intent.is_some())
+            })
+            .collect();
+        // Stable, so ties keep registration order; the intent breaks ties
+        ranked.sort_by_key(|(s, intent)| std::cmp::Reverse((s.score, *intent)));
+        ranked.truncate(MAX_SUGGESTIONS);
+        ranked
+            .into_iter()
+            .map(|(suggestion, _)| suggestion)
+            .collect()
     }
 
     /// Score how well a capability matches a task
     fn score_capability(&self, capability: &AgentCapability, task: &Task) -> usize {
-        let mut score = 0;
-        let desc_lower = task.description.to_lowercase();
-
-        // Check keyword matches
-        for keyword in &capability.keywords {
-            if desc_lower.contains(&keyword.to_lowercase()) {
-                score += 10; // Each keyword match adds 10 points
-            }
-        }
+        // Each keyword match adds 10 points
+        let mut score = self.matched_keywords(capability, task).len() * 10;
 
         // Bonus if domain matches (even without domain_hint)
         if task
@@ -132,6 +496,11 @@ impl AgentRouter {
         score
     }
 
+    /// Capability keywords found in the task description
+    fn matched_keywords(&self, capability: &AgentCapability, task: &Task) -> Vec<String> {
+        capability.matched_keywords(&task.description, &self.keyword_matching)
+    }
+
     /// Get all registered agents
     pub fn agents(&self) -> &[Arc<dyn Agent>] {
         &self.agents
@@ -142,9 +511,19 @@ impl AgentRouter {
         self.agents.iter().find(|a| a.id() == id).cloned()
     }
 
+    /// Shared handle to the registered agents
+    pub fn shared_agents(&self) -> Arc<[Arc<dyn Agent>]> {
+        Arc::clone(&self.agents)
+    }
+
     /// Create a router with a specific set of agents
     pub fn with_agents(agents: Vec<Arc<dyn Agent>>) -> Self {
-        Self { agents }
+        Self {
+            agents: agents.into(),
+            fallbacks: HashMap::new(),
+            keyword_matching: KeywordMatching::default(),
+            overrides: Vec::new(),
+        }
     }
 }
 
@@ -154,14 +533,6 @@ impl Default for AgentRouter {
     }
 }
 
-impl Clone for AgentRouter {
-    fn clone(&self) -> Self {
-        Self {
-            agents: self.agents.clone(),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,6 +619,57 @@ mod tests {
         assert_eq!(router.agents().len(), 2);
     }
 
+    #[test]
+    fn test_cloned_router_shares_agents() {
+        let mut router = AgentRouter::new();
+        router.register(create_code_agent());
+
+        let clone = router.clone();
+        assert!(Arc::ptr_eq(&router.shared_agents(), &clone.shared_agents()));
+
+        // Registering on one copy leaves the other's agent set unchanged
+        router.register(create_mail_agent());
+        assert_eq!(router.agents().len(), 2);
+        assert_eq!(clone.agents().len(), 1);
+    }
+
+    #[test]
+    fn test_route_chain_with_fallbacks() {
+        let simple_code = Arc::new(MockAgent {
+            id: "code-simple",
+            capability: AgentCapability::new("general", vec![], "Simple code agent"),
+            enabled: true,
+        });
+        let disabled_code = Arc::new(MockAgent {
+            id: "code",
+            capability: AgentCapability::new("code", vec![], "Code agent"),
+            enabled: false,
+        });
+        let task = Task::new("Write a parser").with_domain("code");
+        let fallbacks: HashMap<String, Vec<String>> =
+            [("Code".to_string(), vec!["code-simple".to_string()])].into();
+
+        // Routed agent first, then the domain's fallbacks
+        let router = AgentRouter::with_agents(vec![create_code_agent(), simple_code.clone()])
+            .with_fallbacks(fallbacks.clone());
+        let (chain, _) = router.route_chain(&task).unwrap();
+        let ids: Vec<_> = chain.iter().map(|a| a.id()).collect();
+        assert_eq!(ids, vec!["code", "code-simple"]);
+
+        // A disabled primary leaves only the fallbacks
+        let router =
+            AgentRouter::with_agents(vec![disabled_code, simple_code]).with_fallbacks(fallbacks);
+        let (chain, decision) = router.route_chain(&task).unwrap();
+        assert_eq!(chain.len(), 1);
+        assert_eq!(decision.chosen_agent, "code-simple");
+        assert_eq!(
+            decision.reason,
+            RoutingReason::DomainFallback {
+                domain: "code".to_string()
+            }
+        );
+    }
+
     #[test]
     fn test_select_agent_with_domain_hint() {
         let mut router = AgentRouter::new();
@@ -300,11 +722,48 @@ mod tests {
 
         assert!(result.is_err());
         match result {
-            Err(Error::AgentNotFound(_)) => {}
-            _ => panic!("Expected Error::AgentNotFound variant"),
+            Err(Error::NoAgentMatched { .. }) => {}
+            _ => panic!("Expected Error::NoAgentMatched variant"),
         }
     }
 
+    #[test]
+    fn test_no_match_suggests_closest_agents() {
+        let mut router = AgentRouter::new();
+        router.register(Arc::new(MockAgent {
+            id: "summarize",
+            capability: AgentCapability::new("summary", vec![], "Summarizer")
+                .with_keywords(vec!["summary".to_string()]),
+            enabled: true,
+        }));
+        router.register(create_code_agent());
+        router.register(create_mail_agent());
+
+        // "Write" and the "email" hint lean to mail, "function" to code
+        let task = Task::new("Write up the function changes").with_domain("email");
+        let Err(Error::NoAgentMatched {
+            message,
+            suggestions,
+        }) = router.route(&task)
+        else {
+            panic!("Expected Error::NoAgentMatched");
+        };
+
+        assert_eq!(message, "No enabled agent found for domain 'email'");
+        let ranked: Vec<(&str, usize)> = suggestions
+            .iter()
+            .map(|s| (s.agent_id.as_str(), s.score))
+            .collect();
+        assert_eq!(ranked, vec![("mail", 20), ("code", 10), ("summarize", 0)]);
+        assert_eq!(suggestions[0].domain, "mail");
+        assert!(router
+            .select_agent(&task)
+            .err()
+            .unwrap()
+            .to_string()
+            .ends_with("did you mean the 'mail' agent?"));
+    }
+
     #[test]
     fn test_select_agent_disabled() {
         let mut router = AgentRouter::new();
@@ -372,6 +831,152 @@ mod tests {
         assert_eq!(agent.id(), "code"); // Has "generate", "rust", "code", "function"
     }
 
+    #[test]
+    fn test_inflected_and_misspelled_keywords_route() {
+        let mut router = AgentRouter::new();
+        router.register(create_code_agent());
+        router.register(create_mail_agent());
+
+        let task = Task::new("Emailing the team about the release");
+        assert_eq!(router.select_agent(&task).unwrap().id(), "mail");
+
+        let task = Task::new("Add a functon that parses dates");
+        assert_eq!(router.select_agent(&task).unwrap().id(), "code");
+
+        // With fuzzy matching off the typo no longer counts
+        let (_, decision) = router
+            .clone()
+            .with_keyword_matching(KeywordMatching::exact())
+            .route(&task)
+            .unwrap();
+        assert_eq!(decision.reason, RoutingReason::Fallback);
+    }
+
+    #[test]
+    fn test_extract_intent_skips_filler() {
+        assert_eq!(
+            extract_intent("Refine this email"),
+            Some("refine".to_string())
+        );
+        assert_eq!(
+            extract_intent("Please, can you draft a reply?"),
+            Some("draft".to_string())
+        );
+        assert_eq!(
+            extract_intent("I want to generate a parser"),
+            Some("generate".to_string())
+        );
+        assert_eq!(extract_intent("The build is broken"), None);
+        assert_eq!(extract_intent("How do lifetimes work?"), None);
+        assert_eq!(extract_intent("please"), None);
+    }
+
+    #[test]
+    fn test_intent_breaks_keyword_tie() {
+        let editor = Arc::new(MockAgent {
+            id: "editor",
+            capability: AgentCapability::new("mail", vec!["refine".to_string()], "Editor")
+                .with_keywords(vec!["email".to_string()]),
+            enabled: true,
+        });
+        let writer = Arc::new(MockAgent {
+            id: "writer",
+            capability: AgentCapability::new("mail", vec!["draft".to_string()], "Writer")
+                .with_keywords(vec!["email".to_string()]),
+            enabled: true,
+        });
+        let router = AgentRouter::with_agents(vec![writer, editor]);
+
+        let (agent, decision) = router.route(&Task::new("Refine this email")).unwrap();
+
+        assert_eq!(agent.id(), "editor");
+        assert_eq!(
+            decision.reason,
+            RoutingReason::Intent {
+                intent: "refine".to_string(),
+                matched_keywords: vec!["email".to_string()],
+            }
+        );
+        let writer_score = &decision.scores[0];
+        assert!(writer_score.matched_intent.is_none());
+        assert_eq!(decision.scores[1].score, writer_score.score);
+    }
+
+    #[test]
+    fn test_intent_does_not_outweigh_keywords() {
+        let mut router = AgentRouter::new();
+        router.register(Arc::new(bodhya_agent_code::CodeAgent::new()));
+        router.register(Arc::new(bodhya_agent_mail::MailAgent::new()));
+
+        // "create" is a code intent, but "email" and "message" are mail keywords
+        let task = Task::new("Create a polite email reply to the customer message");
+        let (agent, decision) = router.route(&task).unwrap();
+
+        assert_eq!(agent.id(), "mail");
+        assert!(matches!(decision.reason, RoutingReason::Keywords { .. }));
+    }
+
+    #[test]
+    fn test_override_wins_over_keyword_scoring() {
+        let mut router = AgentRouter::new().with_overrides(&[
+            RoutingOverride {
+                pattern: "unit test".to_string(),
+                agent: "code".to_string(),
+                regex: false,
+            },
+            RoutingOverride {
+                pattern: r"^re:\s".to_string(),
+                agent: "mail".to_string(),
+                regex: true,
+            },
+        ]);
+        router.register(create_code_agent());
+        router.register(create_mail_agent());
+
+        // Keywords alone favour mail ("email", "draft", "write")
+        let task = Task::new("Draft an email and write a Unit Test for it");
+        let (agent, decision) = router.route(&task).unwrap();
+        assert_eq!(agent.id(), "code");
+        assert_eq!(
+            decision.reason,
+            RoutingReason::Override {
+                pattern: "unit test".to_string()
+            }
+        );
+
+        let task = Task::new("Re: generate the rust code review");
+        assert_eq!(router.select_agent(&task).unwrap().id(), "mail");
+    }
+
+    #[test]
+    fn test_unmatched_overrides_fall_through() {
+        let mut router = AgentRouter::new().with_overrides(&[
+            RoutingOverride {
+                pattern: "unit test".to_string(),
+                agent: "code".to_string(),
+                regex: false,
+            },
+            // Invalid regex is skipped rather than breaking routing
+            RoutingOverride {
+                pattern: "(".to_string(),
+                agent: "code".to_string(),
+                regex: true,
+            },
+        ]);
+        router.register(create_code_agent());
+        router.register(create_mail_agent());
+
+        // Not a whole-word match
+        let task = Task::new("Write an email about the unit testing budget");
+        let (agent, decision) = router.route(&task).unwrap();
+        assert_eq!(agent.id(), "mail");
+        assert!(matches!(decision.reason, RoutingReason::Keywords { .. }));
+
+        // An override for a domain excluded by the hint does not apply
+        let task = Task::new("Write a unit test").with_domain("mail");
+        assert_eq!(router.select_agent(&task).unwrap().id(), "mail");
+    }
+
     #[test]
     fn test_case_insensitive_matching() {
         let mut router = AgentRouter::new();
@@ -391,4 +996,58 @@ mod tests {
         let agent = router.select_agent(&task).unwrap();
         assert_eq!(agent.id(), "code");
     }
+
+    #[test]
+    fn test_route_explains_keyword_match() {
+        let mut router = AgentRouter::new();
+        router.register(create_code_agent());
+        router.register(create_mail_agent());
+
+        let task = Task::new("Write an email to the team");
+        let (agent, decision) = router.route(&task).unwrap();
+
+        assert_eq!(agent.id(), "mail");
+        assert_eq!(decision.chosen_agent, "mail");
+        match &decision.reason {
+            RoutingReason::Keywords { matched_keywords } => {
+                assert!(matched_keywords.contains(&"email".to_string()))
+            }
+            other => panic!("Expected keyword routing, got {:?}", other),
+        }
+        assert_eq!(decision.scores.len(), 2);
+        assert!(decision.to_string().contains("email"));
+    }
+
+    #[test]
+    fn test_route_explains_domain_hint() {
+        let mut router = AgentRouter::new();
+        router.register(create_code_agent());
+        router.register(create_mail_agent());
+
+        let task = Task::new("Some task description").with_domain("code");
+        let (_, decision) = router.route(&task).unwrap();
+
+        assert_eq!(decision.chosen_agent, "code");
+        assert_eq!(
+            decision.reason,
+            RoutingReason::DomainHint {
+                domain: "code".to_string(),
+                matched_keywords: Vec::new(),
+            }
+        );
+        // Only agents in the hinted domain are scored
+        assert_eq!(decision.scores.len(), 1);
+    }
+
+    #[test]
+    fn test_route_fallback() {
+        let mut router = AgentRouter::new();
+        router.register(create_code_agent());
+        router.register(create_mail_agent());
+
+        let (agent, decision) = router.route(&Task::new("Hello there")).unwrap();
+        assert_eq!(agent.id(), "code");
+        assert_eq!(decision.reason, RoutingReason::Fallback);
+        assert!(decision.scores.iter().all(|s| s.score == 0));
+    }
 }
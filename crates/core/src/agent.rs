@@ -7,7 +7,9 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::config::AppConfig;
-use crate::errors::Result;
+use crate::errors::{Error, Result};
+use crate::events::{EventRecorder, RunEvent};
+use crate::tool::ApprovalHook;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -27,6 +29,16 @@ pub struct Task {
     /// Task creation timestamp
     #[serde(default = "chrono::Utc::now")]
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Scheduling priority; higher-priority tasks run first
+    #[serde(default)]
+    pub priority: TaskPriority,
+    /// Fail [`Task::render`] on placeholders missing from the payload
+    /// instead of leaving them in place
+    #[serde(default)]
+    pub strict_variables: bool,
+    /// Ids of the tasks that must succeed before this one runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
 }
 
 impl Task {
@@ -38,6 +50,9 @@ impl Task {
             description: description.into(),
             payload: serde_json::Value::Null,
             created_at: chrono::Utc::now(),
+            priority: TaskPriority::default(),
+            strict_variables: false,
+            depends_on: Vec::new(),
         }
     }
 
@@ -52,6 +67,99 @@ impl Task {
         self.payload = payload;
         self
     }
+
+    /// Set the scheduling priority
+    pub fn with_priority(mut self, priority: TaskPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Mark this task as feedback on the result of task `task_id`
+    ///
+    /// Sets `refine_of` in the payload, turning it into an object if needed.
+    pub fn with_refine_of(mut self, task_id: impl Into<String>) -> Self {
+        if !self.payload.is_object() {
+            self.payload = serde_json::json!({});
+        }
+        self.payload["refine_of"] = serde_json::Value::String(task_id.into());
+        self
+    }
+
+    /// Id of the task whose result this task refines, from `payload.refine_of`
+    pub fn refine_of(&self) -> Option<&str> {
+        self.payload.get("refine_of")?.as_str()
+    }
+
+    /// Run this task only after the tasks with ids `task_ids` have succeeded
+    pub fn with_depends_on(mut self, task_ids: Vec<String>) -> Self {
+        self.depends_on = task_ids;
+        self
+    }
+
+    /// Make [`Task::render`] fail on placeholders missing from the payload
+    pub fn with_strict_variables(mut self, strict: bool) -> Self {
+        self.strict_variables = strict;
+        self
+    }
+
+    /// The description with `{name}` placeholders filled from `payload` keys
+    ///
+    /// Only braces around a plain identifier are placeholders, so code and
+    /// JSON in descriptions pass through untouched. String values are
+    /// inserted as-is and other values as JSON; `null` counts as missing.
+    pub fn render(&self) -> Result<String> {
+        let mut output = String::with_capacity(self.description.len());
+        let mut missing = Vec::new();
+        let mut rest = self.description.as_str();
+
+        while let Some(open) = rest.find('{') {
+            output.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let name = after.find('}').map(|close| &after[..close]).filter(|name| {
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            });
+            let Some(name) = name else {
+                output.push('{');
+                rest = after;
+                continue;
+            };
+
+            match self.payload.get(name) {
+                Some(serde_json::Value::String(value)) => output.push_str(value),
+                Some(serde_json::Value::Null) | None => {
+                    missing.push(name);
+                    output.push_str(&rest[open..open + name.len() + 2]);
+                }
+                Some(value) => output.push_str(&value.to_string()),
+            }
+            rest = &after[name.len() + 1..];
+        }
+        output.push_str(rest);
+
+        if self.strict_variables && !missing.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "Task description uses variables missing from the payload: {}",
+                missing.join(", ")
+            )));
+        }
+        Ok(output)
+    }
+}
+
+/// Scheduling priority of a task
+///
+/// Ordered from lowest to highest, so `High > Normal > Low`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    /// Batch work that can wait
+    Low,
+    #[default]
+    Normal,
+    /// Interactive work that should run as soon as possible
+    High,
 }
 
 /// Result returned by an agent after handling a task
@@ -101,6 +209,22 @@ impl AgentResult {
     }
 }
 
+/// An earlier result that a refinement task asks an agent to improve
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PreviousResult {
+    /// Id of the task that produced the result
+    pub task_id: String,
+    /// That task's description
+    pub description: String,
+    /// Domain of the agent that handled it
+    pub domain: String,
+    /// The result being refined
+    pub content: String,
+    /// Structured metadata from the result
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
 /// Describes an agent's capabilities for intelligent routing
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AgentCapability {
@@ -137,14 +261,195 @@ impl AgentCapability {
     }
 
     /// Check if this capability matches a task description
+    ///
+    /// Uses the default [`KeywordMatching`]; see [`matched_keywords`](Self::matched_keywords).
     pub fn matches(&self, description: &str) -> bool {
-        let desc_lower = description.to_lowercase();
+        !self
+            .matched_keywords(description, &KeywordMatching::default())
+            .is_empty()
+    }
+
+    /// Whether `verb` is one of this capability's intents, ignoring case and
+    /// tense ("refining" supports the "refine" intent)
+    pub fn supports_intent(&self, verb: &str) -> bool {
+        let verb = stem(&verb.to_lowercase());
+        self.intents
+            .iter()
+            .any(|intent| stem(&intent.to_lowercase()) == verb)
+    }
+
+    /// Keywords found among the words of `description`
+    ///
+    /// Keywords are compared word by word, ignoring case and punctuation.
+    /// Both sides are reduced to stems first, so plurals and tenses match
+    /// their base form ("generates", "generated" and "generating" all match
+    /// "generate"). Longer keywords also match within `matching.max_edits`
+    /// typos ("functon" for "function"). A keyword of several words matches
+    /// the same words in sequence.
+    pub fn matched_keywords(&self, description: &str, matching: &KeywordMatching) -> Vec<String> {
+        let description_words = words(description);
 
-        // Check if any keyword appears in the description
         self.keywords
             .iter()
-            .any(|kw| desc_lower.contains(&kw.to_lowercase()))
+            .filter(|keyword| {
+                let keyword_words = words(keyword);
+                !keyword_words.is_empty()
+                    && description_words
+                        .windows(keyword_words.len())
+                        .any(|window| {
+                            window
+                                .iter()
+                                .zip(&keyword_words)
+                                .all(|(word, keyword)| matching.word_matches(word, keyword))
+                        })
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// How loosely capability keywords match the words of a task description
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeywordMatching {
+    /// Most single-character edits (insertions, deletions, substitutions)
+    /// tolerated between a word and a keyword; 0 disables fuzzy matching
+    #[serde(default = "default_max_edits")]
+    pub max_edits: usize,
+    /// Shortest keyword stem matched fuzzily; one edit turns short keywords
+    /// into other common words ("world" into "would")
+    #[serde(default = "default_min_fuzzy_len")]
+    pub min_fuzzy_len: usize,
+}
+
+fn default_max_edits() -> usize {
+    1
+}
+
+fn default_min_fuzzy_len() -> usize {
+    6
+}
+
+impl Default for KeywordMatching {
+    fn default() -> Self {
+        Self {
+            max_edits: default_max_edits(),
+            min_fuzzy_len: default_min_fuzzy_len(),
+        }
+    }
+}
+
+impl KeywordMatching {
+    /// Only words with the same stem match
+    pub fn exact() -> Self {
+        Self {
+            max_edits: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the stems `word` and `keyword` match
+    fn word_matches(&self, word: &str, keyword: &str) -> bool {
+        if word == keyword {
+            return true;
+        }
+
+        // Typos rarely hit the first letter, and insisting on it keeps
+        // pairs like "letter"/"better" apart
+        self.max_edits > 0
+            && keyword.chars().count() >= self.min_fuzzy_len
+            && word.chars().next() == keyword.chars().next()
+            && edit_distance(word, keyword, self.max_edits) <= self.max_edits
+    }
+}
+
+/// Stems of the lowercase alphanumeric words of `text`
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(stem)
+        .collect()
+}
+
+/// Reduce a lowercase English word to a stem shared by its plural and
+/// tense variants: "generates", "generated", "generating" -> "generat"
+///
+/// A few suffix rules in the spirit of Porter's first step, not a full
+/// stemmer: different words may share a stem and irregular forms are left
+/// alone, which is fine for comparing keywords.
+fn stem(word: &str) -> String {
+    let mut stem = word.to_string();
+
+    // Plurals and third person: "replies", "classes", "wishes", "tests"
+    if let Some(base) = stem.strip_suffix("ies").filter(|base| base.len() >= 2) {
+        stem = format!("{}y", base);
+    } else if stem.ends_with("sses") {
+        stem.truncate(stem.len() - 2);
+    } else if let Some(base) = ["ches", "shes", "xes", "zes"]
+        .iter()
+        .find_map(|suffix| stem.strip_suffix(suffix).map(|_| stem.len() - 2))
+    {
+        stem.truncate(base);
+    } else if stem.ends_with('s')
+        && !["ss", "us", "is"]
+            .iter()
+            .any(|suffix| stem.ends_with(suffix))
+        && stem.len() > 3
+    {
+        stem.pop();
+    }
+
+    // Tenses: "generated", "emailing"; the rest must still have a vowel
+    for suffix in ["ing", "ed"] {
+        if let Some(base) = stem.strip_suffix(suffix) {
+            if base.len() >= 3 && base.chars().any(is_vowel) {
+                stem.truncate(base.len());
+                // "running" -> "run", but "calling" keeps its "ll"
+                let bytes = stem.as_bytes();
+                let n = bytes.len();
+                if bytes[n - 1] == bytes[n - 2] && !b"aeioulsz".contains(&bytes[n - 1]) {
+                    stem.pop();
+                }
+                break;
+            }
+        }
+    }
+
+    // Silent final "e": "generate" and "generat(ing)" meet at "generat"
+    if stem.len() > 3 && stem.ends_with('e') {
+        stem.pop();
     }
+
+    stem
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+/// Levenshtein distance between `a` and `b`, or `limit + 1` once it is
+/// certain to exceed `limit`
+fn edit_distance(a: &str, b: &str, limit: usize) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > limit {
+        return limit + 1;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().min().is_some_and(|&best| best > limit) {
+            return limit + 1;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
 }
 
 /// Execution limits to prevent resource exhaustion and infinite loops
@@ -207,6 +512,11 @@ impl ExecutionLimits {
         }
     }
 
+    /// The global timeout, or `None` when unlimited (`u64::MAX`)
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        (self.timeout_secs != u64::MAX).then(|| std::time::Duration::from_secs(self.timeout_secs))
+    }
+
     /// Create limits with no restrictions (use cautiously!)
     pub fn unlimited() -> Self {
         Self {
@@ -218,10 +528,54 @@ impl ExecutionLimits {
     }
 }
 
+/// Execution limits set by the caller, field by field
+///
+/// Unset fields are filled from the running agent's
+/// [`default_execution_limits`](Agent::default_execution_limits).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionLimitOverrides {
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+    #[serde(default)]
+    pub max_file_writes: Option<usize>,
+    #[serde(default)]
+    pub max_command_executions: Option<usize>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl ExecutionLimitOverrides {
+    /// `defaults` with every set field replaced
+    pub fn apply_to(&self, defaults: ExecutionLimits) -> ExecutionLimits {
+        ExecutionLimits {
+            max_iterations: self.max_iterations.unwrap_or(defaults.max_iterations),
+            max_file_writes: self.max_file_writes.unwrap_or(defaults.max_file_writes),
+            max_command_executions: self
+                .max_command_executions
+                .unwrap_or(defaults.max_command_executions),
+            timeout_secs: self.timeout_secs.unwrap_or(defaults.timeout_secs),
+        }
+    }
+}
+
+/// Every field set
+impl From<ExecutionLimits> for ExecutionLimitOverrides {
+    fn from(limits: ExecutionLimits) -> Self {
+        Self {
+            max_iterations: Some(limits.max_iterations),
+            max_file_writes: Some(limits.max_file_writes),
+            max_command_executions: Some(limits.max_command_executions),
+            timeout_secs: Some(limits.timeout_secs),
+        }
+    }
+}
+
 /// Execution mode for code generation
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum ExecutionMode {
+    /// Plan and generate BDD features only (no code generation or file operations)
+    PlanOnly,
     /// Generate code only (no file writes or command execution)
     GenerateOnly,
     /// Execute code (write files and run tests)
@@ -235,6 +589,7 @@ impl ExecutionMode {
     /// Parse execution mode from string
     pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
+            "plan-only" | "plan_only" | "plan" => Some(Self::PlanOnly),
             "generate-only" | "generate_only" | "generate" => Some(Self::GenerateOnly),
             "execute" | "exec" => Some(Self::Execute),
             "execute-with-retry" | "execute_with_retry" | "retry" => Some(Self::ExecuteWithRetry),
@@ -245,6 +600,7 @@ impl ExecutionMode {
     /// Get string representation
     pub fn as_str(&self) -> &'static str {
         match self {
+            Self::PlanOnly => "plan-only",
             Self::GenerateOnly => "generate-only",
             Self::Execute => "execute",
             Self::ExecuteWithRetry => "execute-with-retry",
@@ -254,6 +610,7 @@ impl ExecutionMode {
     /// Get human-readable description
     pub fn description(&self) -> &'static str {
         match self {
+            Self::PlanOnly => "Plan and BDD features only (no code generation or file operations)",
             Self::GenerateOnly => "Generate code only (no file operations)",
             Self::Execute => "Generate code, write files, and run tests",
             Self::ExecuteWithRetry => "Execute with retry on failures (agentic loop)",
@@ -267,6 +624,9 @@ impl std::fmt::Display for ExecutionMode {
     }
 }
 
+/// Callback invoked with the name of each pipeline phase an agent enters
+pub type ProgressCallback = Arc<dyn Fn(&str) + Send + Sync>;
+
 /// Context provided to agents when handling tasks
 #[derive(Clone)]
 pub struct AgentContext {
@@ -276,6 +636,8 @@ pub struct AgentContext {
     pub metadata: serde_json::Value,
     /// Working directory for file operations
     pub working_dir: Option<PathBuf>,
+    /// Directory generated files are written to, if not the working directory
+    pub output_dir: Option<PathBuf>,
     /// Execution limits to prevent resource exhaustion
     pub execution_limits: ExecutionLimits,
     /// Execution mode (generate-only, execute, execute-with-retry)
@@ -283,6 +645,14 @@ pub struct AgentContext {
     /// Tool registry (type-erased to avoid circular dependency)
     /// Agents can downcast this to ToolRegistry using std::any::Any
     pub tools: Option<Arc<dyn std::any::Any + Send + Sync>>,
+    /// Progress callback for long-running work (e.g., API task heartbeats)
+    pub progress: Option<ProgressCallback>,
+    /// Approval hook consulted before file writes and commands (None approves all)
+    pub approval: Option<Arc<dyn ApprovalHook>>,
+    /// Event recorder for this task's run (None disables event logging)
+    pub events: Option<EventRecorder>,
+    /// Result to refine, with the task description as the feedback
+    pub previous_result: Option<PreviousResult>,
 }
 
 impl AgentContext {
@@ -292,9 +662,14 @@ impl AgentContext {
             config,
             metadata: serde_json::Value::Null,
             working_dir: None,
+            output_dir: None,
             execution_limits: ExecutionLimits::default(),
             execution_mode: ExecutionMode::default(),
             tools: None,
+            progress: None,
+            approval: None,
+            events: None,
+            previous_result: None,
         }
     }
 
@@ -310,6 +685,12 @@ impl AgentContext {
         self
     }
 
+    /// Write generated files under `output_dir` instead of the working directory
+    pub fn with_output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(output_dir.into());
+        self
+    }
+
     /// Set execution limits
     pub fn with_execution_limits(mut self, limits: ExecutionLimits) -> Self {
         self.execution_limits = limits;
@@ -329,6 +710,44 @@ impl AgentContext {
         self
     }
 
+    /// Set the progress callback
+    pub fn with_progress(mut self, progress: ProgressCallback) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Set the approval hook
+    pub fn with_approval(mut self, approval: Arc<dyn ApprovalHook>) -> Self {
+        self.approval = Some(approval);
+        self
+    }
+
+    /// Set the event recorder for this task
+    pub fn with_events(mut self, events: EventRecorder) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Ask the agent to refine `previous` rather than start from scratch
+    pub fn with_previous_result(mut self, previous: PreviousResult) -> Self {
+        self.previous_result = Some(previous);
+        self
+    }
+
+    /// Record `event` if an event recorder is set
+    pub fn record_event(&self, event: RunEvent) {
+        if let Some(events) = &self.events {
+            events.record(event);
+        }
+    }
+
+    /// Report that the agent has entered `phase`
+    pub fn report_phase(&self, phase: &str) {
+        if let Some(progress) = &self.progress {
+            progress(phase);
+        }
+    }
+
     /// Get the working directory, or current directory if not set
     pub fn get_working_dir(&self) -> Result<PathBuf> {
         self.working_dir
@@ -359,12 +778,78 @@ pub trait Agent: Send + Sync {
     fn is_enabled(&self) -> bool {
         true // Default: always enabled
     }
+
+    /// Optional: Limits for fields the caller leaves unset
+    fn default_execution_limits(&self) -> ExecutionLimits {
+        ExecutionLimits::default()
+    }
+
+    /// Optional: IDs of the tools the agent uses (e.g., "filesystem", "shell")
+    ///
+    /// Only these tools are attached to the agent's context; an agent that
+    /// requires none gets no tool registry. `"mcp"` stands for every tool
+    /// from the configured MCP servers.
+    fn required_tools(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_depends_on_keeps_payload() {
+        let task = Task::new("Write docs")
+            .with_payload(serde_json::json!("plain"))
+            .with_depends_on(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(task.depends_on, vec!["a", "b"]);
+        assert_eq!(task.payload, serde_json::json!("plain"));
+        assert!(Task::new("Standalone").depends_on.is_empty());
+
+        let json = serde_json::to_value(&task).unwrap();
+        assert_eq!(json["depends_on"], serde_json::json!(["a", "b"]));
+        let parsed: Task = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.depends_on, task.depends_on);
+    }
+
+    #[test]
+    fn test_render_substitutes_payload_variables() {
+        let task = Task::new("Draft a reminder email to {name} about {event} ({count} guests)")
+            .with_payload(serde_json::json!({
+                "name": "Priya",
+                "event": "the launch",
+                "count": 12
+            }));
+
+        assert_eq!(
+            task.render().unwrap(),
+            "Draft a reminder email to Priya about the launch (12 guests)"
+        );
+    }
+
+    #[test]
+    fn test_render_strict_rejects_missing_variables() {
+        let task = Task::new("Remind {name} about {event} on {date}")
+            .with_payload(serde_json::json!({ "name": "Priya" }))
+            .with_strict_variables(true);
+
+        let err = task.render().unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(_)));
+        assert!(err.to_string().contains("event, date"));
+    }
+
+    #[test]
+    fn test_render_lenient_leaves_missing_variables() {
+        let task = Task::new("Remind {name} about {event}: fn main() { {} }")
+            .with_payload(serde_json::json!({ "name": "Priya", "event": null }));
+
+        assert_eq!(
+            task.render().unwrap(),
+            "Remind Priya about {event}: fn main() { {} }"
+        );
+    }
+
     #[test]
     fn test_task_creation() {
         let task = Task::new("Test task");
@@ -380,6 +865,20 @@ mod tests {
         assert_eq!(task.domain_hint, Some("code".to_string()));
     }
 
+    #[test]
+    fn test_task_priority() {
+        let task = Task::new("Review PR");
+        assert_eq!(task.priority, TaskPriority::Normal);
+        assert!(TaskPriority::High > TaskPriority::Normal);
+        assert!(TaskPriority::Normal > TaskPriority::Low);
+
+        let task: Task =
+            serde_json::from_value(serde_json::json!({"id": "1", "description": "x"})).unwrap();
+        assert_eq!(task.priority, TaskPriority::Normal);
+        let task = task.with_priority(TaskPriority::High);
+        assert_eq!(serde_json::to_value(&task).unwrap()["priority"], "high");
+    }
+
     #[test]
     fn test_task_with_payload() {
         let payload = serde_json::json!({"key": "value"});
@@ -438,6 +937,137 @@ mod tests {
         assert!(!cap.matches("Write an email"));
     }
 
+    fn mail_capability() -> AgentCapability {
+        AgentCapability::new("mail", vec!["draft".to_string()], "Mail agent").with_keywords(vec![
+            "email".to_string(),
+            "mail".to_string(),
+            "draft".to_string(),
+            "write".to_string(),
+            "letter".to_string(),
+            "message".to_string(),
+        ])
+    }
+
+    #[test]
+    fn test_capability_matches_inflections_and_typos() {
+        let mail = mail_capability();
+        assert_eq!(
+            mail.matched_keywords("Emailing the team", &KeywordMatching::default()),
+            vec!["email".to_string()]
+        );
+        assert!(mail.matches("I'm writing to the landlord"));
+        assert!(mail.matches("Send a mesage to support"));
+
+        let code = AgentCapability::new("code", vec![], "Code agent")
+            .with_keywords(vec!["function".to_string(), "unit test".to_string()]);
+        assert!(code.matches("Write a functon that sorts"));
+        assert!(code.matches("Add unit tests for the parser"));
+    }
+
+    #[test]
+    fn test_capability_ignores_unrelated_phrases() {
+        let mail = mail_capability();
+        assert!(!mail.matches("Plan a birthday party for my grandmother"));
+        // Substrings of other words and near misses on short keywords
+        assert!(!mail.matches("Explain the bank's overdraft fees"));
+        assert!(!mail.matches("Fix the drift in the better sensor"));
+    }
+
+    #[test]
+    fn test_stem_strips_plurals_and_tenses() {
+        for word in ["generate", "generates", "generated", "generating"] {
+            assert_eq!(stem(word), "generat", "{}", word);
+        }
+        assert_eq!(stem("classes"), stem("class"));
+        assert_eq!(stem("replies"), stem("reply"));
+        assert_eq!(stem("running"), "run");
+        assert_eq!(stem("calling"), "call");
+        // Too short to carry a suffix
+        assert_eq!(stem("this"), "this");
+        assert_eq!(stem("string"), "string");
+    }
+
+    #[test]
+    fn test_capability_matches_variants_of_keywords() {
+        let code = AgentCapability::new("code", vec![], "Code agent").with_keywords(vec![
+            "generate".to_string(),
+            "test".to_string(),
+            "class".to_string(),
+        ]);
+        let exact = KeywordMatching::exact();
+
+        // Verb tenses
+        for task in [
+            "It generates a parser",
+            "Generated code for the parser",
+            "Generating a parser",
+        ] {
+            assert_eq!(
+                code.matched_keywords(task, &exact),
+                vec!["generate".to_string()],
+                "{}",
+                task
+            );
+        }
+        // Plural nouns
+        assert_eq!(
+            code.matched_keywords("Two classes with tests", &exact),
+            vec!["test".to_string(), "class".to_string()]
+        );
+
+        // Sharing a prefix is not enough
+        assert!(code
+            .matched_keywords("A general classification of testaments", &exact)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_supports_intent_ignores_case_and_tense() {
+        let cap = AgentCapability::new("mail", vec!["refine".to_string()], "Mail agent");
+        assert!(cap.supports_intent("Refine"));
+        assert!(cap.supports_intent("refining"));
+        assert!(!cap.supports_intent("draft"));
+    }
+
+    #[test]
+    fn test_keyword_fuzziness_is_tunable() {
+        let code = AgentCapability::new("code", vec![], "Code agent")
+            .with_keywords(vec!["function".to_string()]);
+
+        assert!(code
+            .matched_keywords("a functon", &KeywordMatching::exact())
+            .is_empty());
+        assert!(code.matches("a functon"));
+
+        let loose = KeywordMatching {
+            max_edits: 2,
+            min_fuzzy_len: 6,
+        };
+        assert_eq!(code.matched_keywords("a fnctin", &loose).len(), 1);
+        assert!(code
+            .matched_keywords("a fnctin", &KeywordMatching::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_limit_overrides_fill_from_defaults() {
+        let overrides = ExecutionLimitOverrides {
+            timeout_secs: Some(60),
+            ..Default::default()
+        };
+        let limits = overrides.apply_to(ExecutionLimits::new(5, 50, 25, 600));
+        assert_eq!(limits.max_iterations, 5);
+        assert_eq!(limits.max_file_writes, 50);
+        assert_eq!(limits.max_command_executions, 25);
+        assert_eq!(limits.timeout_secs, 60);
+
+        let all = ExecutionLimitOverrides::from(ExecutionLimits::unlimited());
+        assert_eq!(
+            all.apply_to(ExecutionLimits::default()).max_iterations,
+            usize::MAX
+        );
+    }
+
     #[test]
     fn test_agent_context_creation() {
         let config = AppConfig::default();
@@ -478,6 +1108,13 @@ mod tests {
         assert_eq!(limits.max_file_writes, usize::MAX);
         assert_eq!(limits.max_command_executions, usize::MAX);
         assert_eq!(limits.timeout_secs, u64::MAX);
+        assert_eq!(limits.timeout(), None);
+    }
+
+    #[test]
+    fn test_execution_limits_timeout() {
+        let limits = ExecutionLimits::new(5, 50, 20, 600);
+        assert_eq!(limits.timeout(), Some(std::time::Duration::from_secs(600)));
     }
 
     #[test]
@@ -593,6 +1230,11 @@ mod tests {
 
     #[test]
     fn test_execution_mode_parse() {
+        assert_eq!(
+            ExecutionMode::parse("plan-only"),
+            Some(ExecutionMode::PlanOnly)
+        );
+        assert_eq!(ExecutionMode::parse("plan"), Some(ExecutionMode::PlanOnly));
         assert_eq!(
             ExecutionMode::parse("generate-only"),
             Some(ExecutionMode::GenerateOnly)
@@ -632,6 +1274,7 @@ mod tests {
 
     #[test]
     fn test_execution_mode_as_str() {
+        assert_eq!(ExecutionMode::PlanOnly.as_str(), "plan-only");
         assert_eq!(ExecutionMode::GenerateOnly.as_str(), "generate-only");
         assert_eq!(ExecutionMode::Execute.as_str(), "execute");
         assert_eq!(
@@ -662,4 +1305,20 @@ mod tests {
         let ctx = AgentContext::new(config).with_execution_mode(ExecutionMode::GenerateOnly);
         assert_eq!(ctx.execution_mode, ExecutionMode::GenerateOnly);
     }
+
+    #[test]
+    fn test_agent_context_report_phase() {
+        let phases = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&phases);
+        let ctx = AgentContext::new(AppConfig::default()).with_progress(Arc::new(move |phase| {
+            recorded.lock().unwrap().push(phase.to_string())
+        }));
+
+        ctx.report_phase("planning");
+        ctx.clone().report_phase("review");
+        assert_eq!(*phases.lock().unwrap(), vec!["planning", "review"]);
+
+        // Reporting without a callback is a no-op
+        AgentContext::new(AppConfig::default()).report_phase("planning");
+    }
 }
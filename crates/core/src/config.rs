@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::agent::KeywordMatching;
 use crate::model::{EngagementMode, ModelRole};
 use crate::tool::McpServerConfig;
 
@@ -39,6 +40,10 @@ pub struct AppConfig {
     /// Logging configuration
     #[serde(default)]
     pub logging: LoggingConfig,
+
+    /// Agent routing configuration
+    #[serde(default)]
+    pub routing: RoutingConfig,
 }
 
 fn default_profile() -> String {
@@ -55,6 +60,7 @@ impl Default for AppConfig {
             tools: ToolsConfig::default(),
             paths: PathsConfig::default(),
             logging: LoggingConfig::default(),
+            routing: RoutingConfig::default(),
         }
     }
 }
@@ -154,6 +160,9 @@ pub struct ModelConfigs {
     pub defaults: ModelDefaults,
 }
 
+/// Default prompt budget of the code agent, in estimated tokens
+pub const DEFAULT_CONTEXT_TOKENS: usize = 8192;
+
 /// Default model settings
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ModelDefaults {
@@ -164,6 +173,11 @@ pub struct ModelDefaults {
     /// Default max tokens
     #[serde(default = "default_max_tokens")]
     pub max_tokens: usize,
+
+    /// Prompts the code agent builds for refinement are trimmed to this
+    /// many estimated tokens to fit the model's context window
+    #[serde(default = "default_context_tokens")]
+    pub context_tokens: usize,
 }
 
 fn default_temperature() -> f32 {
@@ -174,11 +188,16 @@ fn default_max_tokens() -> usize {
     2048
 }
 
+fn default_context_tokens() -> usize {
+    DEFAULT_CONTEXT_TOKENS
+}
+
 impl Default for ModelDefaults {
     fn default() -> Self {
         Self {
             temperature: default_temperature(),
             max_tokens: default_max_tokens(),
+            context_tokens: default_context_tokens(),
         }
     }
 }
@@ -193,6 +212,68 @@ pub struct ToolsConfig {
     /// MCP server configurations
     #[serde(default)]
     pub mcp_servers: Vec<McpServerConfig>,
+
+    /// Maximum serialized size of a tool response's data, in bytes
+    #[serde(default = "default_max_response_bytes")]
+    pub max_response_bytes: usize,
+
+    /// Seconds a single tool call may run before it fails
+    #[serde(default = "default_tool_timeout_secs")]
+    pub tool_timeout_secs: u64,
+
+    /// HTTP fetch tool settings (the tool is only registered when set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpToolConfig>,
+}
+
+fn default_max_response_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_tool_timeout_secs() -> u64 {
+    600
+}
+
+/// HTTP fetch tool configuration
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HttpToolConfig {
+    /// Hosts that may be fetched (with their subdomains); empty allows any public host
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+
+    /// Hosts that may never be fetched (with their subdomains)
+    #[serde(default)]
+    pub denied_hosts: Vec<String>,
+
+    /// Response bodies are cut off after this many bytes
+    #[serde(default = "default_max_response_bytes")]
+    pub max_response_bytes: usize,
+
+    /// Request timeout in seconds
+    #[serde(default = "default_http_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Allow loopback, link-local and private network addresses, which are
+    /// refused by default (after DNS resolution) so agents can't reach
+    /// internal services
+    #[serde(default)]
+    pub allow_private_networks: bool,
+}
+
+fn default_http_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for HttpToolConfig {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: Vec::new(),
+            denied_hosts: Vec::new(),
+            max_response_bytes: default_max_response_bytes(),
+            timeout_secs: default_http_timeout_secs(),
+            allow_private_networks: false,
+        }
+    }
 }
 
 fn default_builtin_tools() -> Vec<String> {
@@ -209,6 +290,9 @@ impl Default for ToolsConfig {
         Self {
             builtin: default_builtin_tools(),
             mcp_servers: Vec::new(),
+            max_response_bytes: default_max_response_bytes(),
+            tool_timeout_secs: default_tool_timeout_secs(),
+            http: None,
         }
     }
 }
@@ -316,6 +400,10 @@ pub struct LoggingConfig {
     /// Log format (json, pretty, compact)
     #[serde(default = "default_log_format")]
     pub format: String,
+
+    /// JSON-lines file that receives agent run events (None disables the event log)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_log: Option<PathBuf>,
 }
 
 fn default_log_level() -> String {
@@ -331,10 +419,40 @@ impl Default for LoggingConfig {
         Self {
             level: default_log_level(),
             format: default_log_format(),
+            event_log: None,
         }
     }
 }
 
+/// Agent routing configuration
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    /// Agent IDs to try, in order, when the routed agent for a domain fails
+    /// (domain -> agent IDs)
+    #[serde(default)]
+    pub fallbacks: HashMap<String, Vec<String>>,
+    /// How loosely agent keywords match task descriptions
+    #[serde(default)]
+    pub keyword_matching: KeywordMatching,
+    /// Patterns that send a task straight to an agent, checked in order
+    /// before keyword scoring
+    #[serde(default)]
+    pub overrides: Vec<RoutingOverride>,
+}
+
+/// Routes tasks whose description matches `pattern` to agent `agent`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingOverride {
+    /// Case-insensitive phrase matched as whole words, or a regular
+    /// expression when `regex` is set
+    pub pattern: String,
+    /// ID of the agent to route matching tasks to
+    pub agent: String,
+    /// Treat `pattern` as a regular expression
+    #[serde(default)]
+    pub regex: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -393,6 +511,11 @@ mod tests {
         let defaults = ModelDefaults::default();
         assert_eq!(defaults.temperature, 0.7);
         assert_eq!(defaults.max_tokens, 2048);
+        assert_eq!(defaults.context_tokens, DEFAULT_CONTEXT_TOKENS);
+
+        let parsed: ModelDefaults = serde_yaml::from_str("context_tokens: 32768").unwrap();
+        assert_eq!(parsed.context_tokens, 32768);
+        assert_eq!(parsed.max_tokens, 2048);
     }
 
     #[test]
@@ -402,6 +525,50 @@ mod tests {
         assert_eq!(logging.format, "compact");
     }
 
+    #[test]
+    fn test_routing_fallbacks_from_yaml() {
+        let yaml = "routing:\n  fallbacks:\n    code: [code, code-simple]\n";
+        let config: AppConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.routing.fallbacks["code"],
+            vec!["code".to_string(), "code-simple".to_string()]
+        );
+        assert!(AppConfig::default().routing.fallbacks.is_empty());
+    }
+
+    #[test]
+    fn test_routing_overrides_from_yaml() {
+        let yaml = "routing:\n  overrides:\n    - pattern: unit test\n      agent: code\n    - pattern: '^re:'\n      agent: mail\n      regex: true\n";
+        let config: AppConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.routing.overrides,
+            vec![
+                RoutingOverride {
+                    pattern: "unit test".to_string(),
+                    agent: "code".to_string(),
+                    regex: false,
+                },
+                RoutingOverride {
+                    pattern: "^re:".to_string(),
+                    agent: "mail".to_string(),
+                    regex: true,
+                },
+            ]
+        );
+        assert!(AppConfig::default().routing.overrides.is_empty());
+    }
+
+    #[test]
+    fn test_routing_keyword_matching_from_yaml() {
+        let yaml = "routing:\n  keyword_matching:\n    max_edits: 0\n";
+        let config: AppConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.routing.keyword_matching.max_edits, 0);
+        assert_eq!(
+            config.routing.keyword_matching.min_fuzzy_len,
+            KeywordMatching::default().min_fuzzy_len
+        );
+    }
+
     #[test]
     fn test_config_serialization() {
         let mut config = AppConfig {
@@ -3,6 +3,7 @@
 /// This module defines the error hierarchy used throughout the Bodhya codebase.
 /// We use thiserror for ergonomic error definition and anyhow for application-level
 /// error handling with context.
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Result type alias using Bodhya's Error type
@@ -19,6 +20,11 @@ pub enum Error {
     #[error("Model error: {0}")]
     Model(String),
 
+    /// Model backend failure that may go away if the call is simply repeated
+    /// (dropped connection, overloaded server, timeout)
+    #[error("Model unavailable: {0}")]
+    ModelUnavailable(String),
+
     /// Agent-related errors
     #[error("Agent error: {0}")]
     Agent(String),
@@ -27,6 +33,14 @@ pub enum Error {
     #[error("Agent '{0}' not found or disabled")]
     AgentNotFound(String),
 
+    /// No enabled agent could take the task; `suggestions` lists the
+    /// closest agents, best first
+    #[error("{message}{}", did_you_mean(.suggestions))]
+    NoAgentMatched {
+        message: String,
+        suggestions: Vec<AgentSuggestion>,
+    },
+
     /// Model not found or not installed
     #[error("Model '{0}' not found or not installed")]
     ModelNotFound(String),
@@ -68,6 +82,22 @@ pub enum Error {
     Internal(String),
 }
 
+/// An agent that came close to matching a task
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentSuggestion {
+    pub agent_id: String,
+    pub domain: String,
+    /// Routing score against the task
+    pub score: usize,
+}
+
+fn did_you_mean(suggestions: &[AgentSuggestion]) -> String {
+    match suggestions.first() {
+        Some(best) => format!("; did you mean the '{}' agent?", best.agent_id),
+        None => String::new(),
+    }
+}
+
 // Implement conversions for common error types
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
@@ -106,6 +136,28 @@ mod tests {
         assert_eq!(err.to_string(), "Configuration error: missing field");
     }
 
+    #[test]
+    fn test_no_agent_matched_display() {
+        let err = Error::NoAgentMatched {
+            message: "No enabled agent found for domain 'emails'".to_string(),
+            suggestions: vec![AgentSuggestion {
+                agent_id: "mail".to_string(),
+                domain: "mail".to_string(),
+                score: 20,
+            }],
+        };
+        assert_eq!(
+            err.to_string(),
+            "No enabled agent found for domain 'emails'; did you mean the 'mail' agent?"
+        );
+
+        let err = Error::NoAgentMatched {
+            message: "No agent".to_string(),
+            suggestions: Vec::new(),
+        };
+        assert_eq!(err.to_string(), "No agent");
+    }
+
     #[test]
     fn test_error_from_io() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
@@ -0,0 +1,206 @@
+//! Durable event log for agent runs
+//!
+//! Tracing output is meant for humans watching a run. The event log records
+//! the same run as JSON lines (stage transitions, tool calls, model calls,
+//! and the final result) keyed by task id, so runs can be inspected and
+//! replayed after the fact.
+
+use crate::errors::{Error, Result};
+use crate::model::ModelRole;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Something that happened during an agent run
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RunEvent {
+    /// An agent started handling the task
+    TaskStarted { agent_id: String },
+    /// A pipeline stage started
+    StageStarted { stage: String },
+    /// A pipeline stage finished
+    StageFinished {
+        stage: String,
+        duration_ms: u64,
+        success: bool,
+    },
+    /// A tool was invoked
+    ToolCall {
+        tool: String,
+        operation: String,
+        /// Summary of the parameters (e.g., path and byte count, not file contents)
+        params: serde_json::Value,
+        success: bool,
+    },
+    /// A model generated a response
+    ModelCall {
+        role: ModelRole,
+        model_id: String,
+        duration_ms: u64,
+        prompt_tokens: u64,
+        completion_tokens: u64,
+        success: bool,
+    },
+    /// The agent finished the task
+    TaskFinished {
+        agent_id: String,
+        success: bool,
+        duration_ms: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+/// An event with the task it belongs to and when it happened
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventRecord {
+    /// Task the event belongs to
+    pub task_id: String,
+    /// When the event was recorded
+    pub timestamp: DateTime<Utc>,
+    /// The event itself
+    #[serde(flatten)]
+    pub event: RunEvent,
+}
+
+/// Destination for run events
+pub trait EventSink: Send + Sync {
+    /// Record one event; failures are the sink's to handle
+    fn record(&self, record: &EventRecord);
+}
+
+/// Event sink that appends one JSON object per line to a file
+pub struct JsonlEventSink {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JsonlEventSink {
+    /// Open `path` for appending, creating it and its parent directories if needed
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl EventSink for JsonlEventSink {
+    fn record(&self, record: &EventRecord) {
+        let Ok(mut line) = serde_json::to_string(record) else {
+            return;
+        };
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        // The event log is diagnostic; a failed write must not fail the run
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+/// Read back every event in a JSON-lines log
+pub fn read_events(path: impl AsRef<Path>) -> Result<Vec<EventRecord>> {
+    let reader = BufReader::new(File::open(path)?);
+    reader
+        .lines()
+        .filter(|line| !matches!(line, Ok(l) if l.trim().is_empty()))
+        .map(|line| {
+            let line = line?;
+            serde_json::from_str(&line)
+                .map_err(|e| Error::Serialization(format!("Invalid event record: {}", e)))
+        })
+        .collect()
+}
+
+/// Records events for one task into a shared sink
+#[derive(Clone)]
+pub struct EventRecorder {
+    task_id: String,
+    sink: Arc<dyn EventSink>,
+}
+
+impl EventRecorder {
+    /// Create a recorder for `task_id`
+    pub fn new(task_id: impl Into<String>, sink: Arc<dyn EventSink>) -> Self {
+        Self {
+            task_id: task_id.into(),
+            sink,
+        }
+    }
+
+    /// Task whose events this recorder writes
+    pub fn task_id(&self) -> &str {
+        &self.task_id
+    }
+
+    /// Record `event` now
+    pub fn record(&self, event: RunEvent) {
+        self.sink.record(&EventRecord {
+            task_id: self.task_id.clone(),
+            timestamp: Utc::now(),
+            event,
+        });
+    }
+}
+
+impl std::fmt::Debug for EventRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventRecorder")
+            .field("task_id", &self.task_id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("logs/events.jsonl");
+        let sink = Arc::new(JsonlEventSink::new(&path).unwrap());
+        let recorder = EventRecorder::new("task-1", sink);
+
+        recorder.record(RunEvent::StageStarted {
+            stage: "plan".to_string(),
+        });
+        recorder.record(RunEvent::TaskFinished {
+            agent_id: "code".to_string(),
+            success: true,
+            duration_ms: 12,
+            error: None,
+        });
+
+        let events = read_events(&path).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.task_id == "task-1"));
+        assert_eq!(
+            events[0].event,
+            RunEvent::StageStarted {
+                stage: "plan".to_string()
+            }
+        );
+
+        let first_line = std::fs::read_to_string(&path).unwrap();
+        assert!(first_line
+            .lines()
+            .next()
+            .unwrap()
+            .contains("\"type\":\"stage_started\""));
+    }
+}
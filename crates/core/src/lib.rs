@@ -1,7 +1,11 @@
 pub use agent::{
-    Agent, AgentCapability, AgentContext, AgentResult, ExecutionLimits, ExecutionMode, Task,
+    Agent, AgentCapability, AgentContext, AgentResult, ExecutionLimitOverrides, ExecutionLimits,
+    ExecutionMode, KeywordMatching, PreviousResult, ProgressCallback, Task, TaskPriority,
+};
+pub use config::{
+    AgentConfig, AppConfig, LoggingConfig, ModelConfigs, PathsConfig, RoutingConfig,
+    RoutingOverride,
 };
-pub use config::{AgentConfig, AppConfig, LoggingConfig, ModelConfigs, PathsConfig};
 /// Bodhya Core Library
 ///
 /// This crate provides the foundational types, traits, and abstractions
@@ -10,21 +14,30 @@ pub use config::{AgentConfig, AppConfig, LoggingConfig, ModelConfigs, PathsConfi
 /// # Modules
 ///
 /// - `errors`: Error types and Result aliases
+/// - `events`: JSON-lines event log for agent runs
+/// - `logging`: Tracing subscriber setup (pretty or JSON)
 /// - `config`: Configuration structures for app, agents, and models
 /// - `model`: Model backend traits and types
 /// - `agent`: Agent trait and task handling types
 /// - `tool`: Tool and MCP interface abstractions
 // Re-export commonly used types at the crate root
-pub use errors::{Error, Result};
+pub use errors::{AgentSuggestion, Error, Result};
+pub use events::{EventRecord, EventRecorder, EventSink, JsonlEventSink, RunEvent};
+pub use logging::{LogFormat, Logging};
 pub use model::{
-    BackendType, EngagementMode, ModelBackend, ModelRequest, ModelResponse, ModelRole,
+    BackendType, EngagementMode, FinishReason, ModelBackend, ModelRequest, ModelResponse, ModelRole,
+};
+pub use tool::{
+    ApprovalHook, ApprovalRequest, AutoApprove, McpClient, McpServerConfig, Tool, ToolRequest,
+    ToolResponse,
 };
-pub use tool::{McpClient, McpServerConfig, Tool, ToolRequest, ToolResponse};
 
 // Public modules
 pub mod agent;
 pub mod config;
 pub mod errors;
+pub mod events;
+pub mod logging;
 pub mod model;
 pub mod tool;
 
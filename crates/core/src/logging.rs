@@ -0,0 +1,272 @@
+//! Tracing subscriber setup shared by the CLI and API server
+//!
+//! Logs are written to stderr either as human-readable lines or as one JSON
+//! object per line for log ingestion. The format comes from a command-line
+//! flag, then `BODHYA_LOG_FORMAT`, then whether stderr is a terminal.
+
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::io::IsTerminal;
+use std::str::FromStr;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::{FmtSpan, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, MakeWriter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Environment variable that selects the log format
+pub const LOG_FORMAT_ENV: &str = "BODHYA_LOG_FORMAT";
+
+/// How log lines are rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines
+    Pretty,
+    /// One JSON object per line
+    Json,
+}
+
+impl LogFormat {
+    /// `flag` if given, else `BODHYA_LOG_FORMAT`, else pretty for a terminal
+    /// and JSON otherwise
+    pub fn resolve(flag: Option<LogFormat>) -> Self {
+        flag.or_else(|| {
+            std::env::var(LOG_FORMAT_ENV)
+                .ok()
+                .and_then(|value| value.parse().ok())
+        })
+        .unwrap_or_else(|| {
+            if std::io::stderr().is_terminal() {
+                LogFormat::Pretty
+            } else {
+                LogFormat::Json
+            }
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Pretty => "pretty",
+            LogFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "pretty" | "text" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!(
+                "Unknown log format '{}' (expected json or pretty)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Logging setup for a binary
+#[derive(Debug, Clone)]
+pub struct Logging {
+    format: LogFormat,
+    /// Filter used when `RUST_LOG` is not set
+    default_filter: String,
+    targets: bool,
+    span_timing: bool,
+}
+
+impl Logging {
+    /// Log in `format`, filtering with `RUST_LOG` or else `default_filter`
+    pub fn new(format: LogFormat, default_filter: impl Into<String>) -> Self {
+        Self {
+            format,
+            default_filter: default_filter.into(),
+            targets: true,
+            span_timing: false,
+        }
+    }
+
+    /// Show the module each pretty log line comes from
+    pub fn with_targets(mut self, targets: bool) -> Self {
+        self.targets = targets;
+        self
+    }
+
+    /// Log when spans close, with their duration
+    pub fn with_span_timing(mut self, span_timing: bool) -> Self {
+        self.span_timing = span_timing;
+        self
+    }
+
+    /// Install the global subscriber, writing to stderr
+    pub fn init(self) {
+        let filter = EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| EnvFilter::new(&self.default_filter));
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(self.layer(std::io::stderr))
+            .init();
+    }
+
+    /// Formatting layer that writes to `writer`
+    pub fn layer<S, W>(&self, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+        W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+    {
+        let span_events = if self.span_timing {
+            FmtSpan::CLOSE
+        } else {
+            FmtSpan::NONE
+        };
+        let layer = tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_span_events(span_events);
+
+        match self.format {
+            LogFormat::Pretty => layer.with_target(self.targets).boxed(),
+            LogFormat::Json => layer.with_ansi(false).event_format(JsonFormat).boxed(),
+        }
+    }
+}
+
+/// Renders each event as a JSON object on its own line
+struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut fields = JsonFields::default();
+        event.record(&mut fields);
+
+        let mut line = json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "level": metadata.level().as_str(),
+            "target": metadata.target(),
+            "fields": fields.0,
+        });
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<&str> = scope.from_root().map(|span| span.name()).collect();
+            line["spans"] = json!(spans);
+        }
+
+        writeln!(writer, "{}", line)
+    }
+}
+
+/// Event fields as JSON values
+#[derive(Default)]
+struct JsonFields(Map<String, Value>);
+
+impl Visit for JsonFields {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), json!(format!("{:?}", value)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects everything written by the subscriber
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'w> MakeWriter<'w> for Captured {
+        type Writer = Captured;
+
+        fn make_writer(&'w self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_json_format_emits_json_lines() {
+        let captured = Captured::default();
+        let logging = Logging::new(LogFormat::Json, "info");
+        let subscriber = tracing_subscriber::registry().with(logging.layer(captured.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request");
+            let _entered = span.enter();
+            tracing::info!(task_id = "t-1", attempts = 2u64, "task \"started\"");
+            tracing::warn!(ok = false, "second line");
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["fields"]["message"], "task \"started\"");
+        assert_eq!(lines[0]["fields"]["task_id"], "t-1");
+        assert_eq!(lines[0]["fields"]["attempts"], 2);
+        assert_eq!(lines[0]["spans"], json!(["request"]));
+        assert_eq!(lines[1]["level"], "WARN");
+        assert_eq!(lines[1]["fields"]["ok"], false);
+    }
+
+    #[test]
+    fn test_log_format_parse() {
+        assert_eq!("json".parse(), Ok(LogFormat::Json));
+        assert_eq!("Pretty".parse(), Ok(LogFormat::Pretty));
+        assert!("xml".parse::<LogFormat>().is_err());
+        assert_eq!(LogFormat::resolve(Some(LogFormat::Json)), LogFormat::Json);
+    }
+}
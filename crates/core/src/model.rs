@@ -54,32 +54,56 @@ pub enum ModelRole {
     General,
 }
 
-impl std::fmt::Display for ModelRole {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl ModelRole {
+    /// All roles, in canonical order
+    pub const ALL: [ModelRole; 6] = [
+        ModelRole::Planner,
+        ModelRole::Coder,
+        ModelRole::Reviewer,
+        ModelRole::Writer,
+        ModelRole::Summarizer,
+        ModelRole::General,
+    ];
+
+    /// Parse a role from its canonical name (case-insensitive)
+    pub fn parse(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|role| role.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let valid: Vec<_> = Self::ALL.iter().map(|r| r.as_str()).collect();
+                Error::InvalidInput(format!(
+                    "Invalid model role: {} (expected one of: {})",
+                    s,
+                    valid.join(", ")
+                ))
+            })
+    }
+
+    /// Get the canonical name, as used in the models manifest
+    pub fn as_str(&self) -> &'static str {
         match self {
-            ModelRole::Planner => write!(f, "planner"),
-            ModelRole::Coder => write!(f, "coder"),
-            ModelRole::Reviewer => write!(f, "reviewer"),
-            ModelRole::Writer => write!(f, "writer"),
-            ModelRole::Summarizer => write!(f, "summarizer"),
-            ModelRole::General => write!(f, "general"),
+            ModelRole::Planner => "planner",
+            ModelRole::Coder => "coder",
+            ModelRole::Reviewer => "reviewer",
+            ModelRole::Writer => "writer",
+            ModelRole::Summarizer => "summarizer",
+            ModelRole::General => "general",
         }
     }
 }
 
+impl std::fmt::Display for ModelRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 impl std::str::FromStr for ModelRole {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        match s.to_lowercase().as_str() {
-            "planner" => Ok(ModelRole::Planner),
-            "coder" => Ok(ModelRole::Coder),
-            "reviewer" => Ok(ModelRole::Reviewer),
-            "writer" => Ok(ModelRole::Writer),
-            "summarizer" => Ok(ModelRole::Summarizer),
-            "general" => Ok(ModelRole::General),
-            _ => Err(Error::InvalidInput(format!("Invalid model role: {}", s))),
-        }
+        Self::parse(s)
     }
 }
 
@@ -92,12 +116,19 @@ pub struct ModelRequest {
     pub domain: String,
     /// The prompt/input text
     pub prompt: String,
+    /// Role instructions sent apart from the prompt (the system message for
+    /// chat models)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
     /// Optional temperature for sampling
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
     /// Optional max tokens to generate
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<usize>,
+    /// Generation halts before any of these sequences
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
 }
 
 impl ModelRequest {
@@ -107,8 +138,10 @@ impl ModelRequest {
             role,
             domain: domain.into(),
             prompt: prompt.into(),
+            system_prompt: None,
             temperature: None,
             max_tokens: None,
+            stop: Vec::new(),
         }
     }
 
@@ -123,6 +156,49 @@ impl ModelRequest {
         self.max_tokens = Some(max_tokens);
         self
     }
+
+    /// Set the sequences that halt generation for this request
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    /// Set the system prompt for this request
+    pub fn with_system_prompt(mut self, system_prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self
+    }
+
+    /// Move the prompt's opening "You are ..." paragraph into the system prompt
+    ///
+    /// Prompt templates open with the model's role; prompts that don't are
+    /// left unchanged.
+    pub fn with_role_as_system_prompt(mut self) -> Self {
+        let Some((role, rest)) = self.prompt.split_once("\n\n") else {
+            return self;
+        };
+        if !role.trim_start().starts_with("You are ") {
+            return self;
+        }
+        self.system_prompt = Some(role.trim().to_string());
+        self.prompt = rest.trim_start().to_string();
+        self
+    }
+}
+
+/// Why a model stopped generating
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    /// The model finished its output naturally
+    #[default]
+    Stop,
+    /// Generation hit the max token limit (output may be cut off)
+    Length,
+    /// Output was withheld by a content filter
+    ContentFilter,
+    /// The backend failed mid-generation
+    Error,
 }
 
 /// Response from a model backend
@@ -133,6 +209,9 @@ pub struct ModelResponse {
     /// Optional metadata (tokens used, timing, etc.)
     #[serde(default)]
     pub metadata: serde_json::Value,
+    /// Why generation stopped
+    #[serde(default)]
+    pub finish_reason: FinishReason,
 }
 
 impl ModelResponse {
@@ -141,6 +220,7 @@ impl ModelResponse {
         Self {
             text: text.into(),
             metadata: serde_json::Value::Null,
+            finish_reason: FinishReason::default(),
         }
     }
 
@@ -149,6 +229,17 @@ impl ModelResponse {
         self.metadata = metadata;
         self
     }
+
+    /// Set why generation stopped
+    pub fn with_finish_reason(mut self, finish_reason: FinishReason) -> Self {
+        self.finish_reason = finish_reason;
+        self
+    }
+
+    /// Whether the output was cut off by the token limit
+    pub fn is_truncated(&self) -> bool {
+        self.finish_reason == FinishReason::Length
+    }
 }
 
 /// Backend type identifier
@@ -180,6 +271,14 @@ pub trait ModelBackend: Send + Sync {
     async fn health_check(&self) -> Result<bool> {
         Ok(true) // Default implementation
     }
+
+    /// Load the model ahead of the first `generate` call
+    ///
+    /// Backends that load lazily should do that work here; the default does
+    /// nothing.
+    async fn load(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -232,17 +331,81 @@ mod tests {
         assert!("invalid".parse::<ModelRole>().is_err());
     }
 
+    #[test]
+    fn test_model_role_parse_round_trip() {
+        for role in ModelRole::ALL {
+            assert_eq!(ModelRole::parse(&role.to_string()).unwrap(), role);
+            // Display matches the serde name used in the manifest
+            assert_eq!(
+                serde_json::to_string(&role).unwrap(),
+                format!("\"{}\"", role)
+            );
+        }
+        assert_eq!(ModelRole::parse(" Planner ").unwrap(), ModelRole::Planner);
+    }
+
+    #[test]
+    fn test_model_role_parse_unknown() {
+        let err = ModelRole::parse("architect").unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(_)));
+        let message = err.to_string();
+        assert!(message.contains("architect"));
+        assert!(message.contains("planner"));
+    }
+
     #[test]
     fn test_model_request_builder() {
         let req = ModelRequest::new(ModelRole::Planner, "code", "test prompt")
             .with_temperature(0.7)
-            .with_max_tokens(1000);
+            .with_max_tokens(1000)
+            .with_stop(vec!["<<END>>".to_string()]);
 
         assert_eq!(req.role, ModelRole::Planner);
         assert_eq!(req.domain, "code");
         assert_eq!(req.prompt, "test prompt");
         assert_eq!(req.temperature, Some(0.7));
         assert_eq!(req.max_tokens, Some(1000));
+        assert_eq!(req.stop, vec!["<<END>>"]);
+        assert!(ModelRequest::new(ModelRole::Planner, "code", "p")
+            .stop
+            .is_empty());
+    }
+
+    #[test]
+    fn test_role_moves_to_system_prompt() {
+        let req = ModelRequest::new(
+            ModelRole::Planner,
+            "code",
+            "You are a planning assistant.\n\nYour task is to plan:\n\nparse CSV",
+        )
+        .with_role_as_system_prompt();
+        assert_eq!(
+            req.system_prompt.as_deref(),
+            Some("You are a planning assistant.")
+        );
+        assert_eq!(req.prompt, "Your task is to plan:\n\nparse CSV");
+
+        let plain = ModelRequest::new(ModelRole::Planner, "code", "Plan this.\n\nparse CSV")
+            .with_role_as_system_prompt();
+        assert!(plain.system_prompt.is_none());
+        assert_eq!(plain.prompt, "Plan this.\n\nparse CSV");
+    }
+
+    #[test]
+    fn test_model_response_finish_reason() {
+        let resp = ModelResponse::new("fn main() {");
+        assert_eq!(resp.finish_reason, FinishReason::Stop);
+        assert!(!resp.is_truncated());
+
+        let resp = resp.with_finish_reason(FinishReason::Length);
+        assert!(resp.is_truncated());
+
+        let json = serde_json::to_value(&resp).unwrap();
+        assert_eq!(json["finish_reason"], "length");
+
+        // Responses serialized before finish_reason existed default to Stop
+        let old: ModelResponse = serde_json::from_str(r#"{"text": "hi"}"#).unwrap();
+        assert_eq!(old.finish_reason, FinishReason::Stop);
     }
 
     #[test]
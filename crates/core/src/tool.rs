@@ -5,6 +5,7 @@
 /// git, shell, etc.) in a uniform way.
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 use crate::errors::Result;
 
@@ -18,6 +19,9 @@ pub struct ToolRequest {
     /// Parameters for the operation
     #[serde(default)]
     pub params: serde_json::Value,
+    /// Preview the operation without side effects (tools must support it)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
 }
 
 impl ToolRequest {
@@ -31,8 +35,15 @@ impl ToolRequest {
             tool: tool.into(),
             operation: operation.into(),
             params,
+            dry_run: false,
         }
     }
+
+    /// Request a preview of the operation instead of running it
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
 }
 
 /// Response from a tool execution
@@ -49,6 +60,9 @@ pub struct ToolResponse {
     /// Optional stdout/stderr output
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<String>,
+    /// Whether `data` or `output` was cut to fit the registry's size cap
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
 }
 
 impl ToolResponse {
@@ -59,6 +73,7 @@ impl ToolResponse {
             data,
             error: None,
             output: None,
+            truncated: false,
         }
     }
 
@@ -69,6 +84,7 @@ impl ToolResponse {
             data,
             error: None,
             output: Some(output.into()),
+            truncated: false,
         }
     }
 
@@ -79,6 +95,7 @@ impl ToolResponse {
             data: serde_json::Value::Null,
             error: Some(error.into()),
             output: None,
+            truncated: false,
         }
     }
 }
@@ -105,6 +122,19 @@ pub trait Tool: Send + Sync {
     fn supports_operation(&self, operation: &str) -> bool {
         self.supported_operations().iter().any(|op| op == operation)
     }
+
+    /// Whether `operation` can preview its effect when the request sets `dry_run`
+    fn supports_dry_run(&self, _operation: &str) -> bool {
+        false
+    }
+
+    /// JSON Schema for the params of `operation`
+    ///
+    /// Requests are validated against it before the tool runs. The default
+    /// accepts any object.
+    fn parameter_schema(&self, _operation: &str) -> serde_json::Value {
+        serde_json::json!({ "type": "object" })
+    }
 }
 
 /// MCP (Model Context Protocol) server configuration
@@ -239,6 +269,63 @@ pub trait McpClient: Send + Sync {
     async fn call_tool(&self, request: ToolRequest) -> Result<ToolResponse>;
 }
 
+/// An operation awaiting approval before it runs
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ApprovalRequest {
+    /// Write `bytes` bytes to `path`, with a unified diff when previewed
+    WriteFile {
+        path: PathBuf,
+        bytes: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        diff: Option<String>,
+    },
+    /// Run `command` with `args`
+    RunCommand { command: String, args: Vec<String> },
+    /// Create or modify each of `paths`, asked once before any is written
+    PlannedWrites { paths: Vec<PathBuf> },
+}
+
+impl std::fmt::Display for ApprovalRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApprovalRequest::WriteFile { path, bytes, .. } => {
+                write!(f, "write {} ({} bytes)", path.display(), bytes)
+            }
+            ApprovalRequest::RunCommand { command, args } if args.is_empty() => {
+                write!(f, "run `{}`", command)
+            }
+            ApprovalRequest::RunCommand { command, args } => {
+                write!(f, "run `{} {}`", command, args.join(" "))
+            }
+            ApprovalRequest::PlannedWrites { paths } => {
+                let paths: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "write {} file(s): {}", paths.len(), paths.join(", "))
+            }
+        }
+    }
+}
+
+/// Hook consulted before an agent writes files or runs commands
+///
+/// Returning `false` denies the operation.
+#[async_trait]
+pub trait ApprovalHook: Send + Sync {
+    /// Decide whether `request` may proceed
+    async fn approve(&self, request: &ApprovalRequest) -> bool;
+}
+
+/// Approval hook that allows every operation
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AutoApprove;
+
+#[async_trait]
+impl ApprovalHook for AutoApprove {
+    async fn approve(&self, _request: &ApprovalRequest) -> bool {
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -414,4 +501,37 @@ mod tests {
         assert!(deserialized.success);
         assert_eq!(deserialized.data["data"], "test");
     }
+
+    #[test]
+    fn test_approval_request_display() {
+        let write = ApprovalRequest::WriteFile {
+            path: PathBuf::from("src/lib.rs"),
+            bytes: 42,
+            diff: None,
+        };
+        assert_eq!(write.to_string(), "write src/lib.rs (42 bytes)");
+
+        let run = ApprovalRequest::RunCommand {
+            command: "cargo".to_string(),
+            args: vec!["test".to_string()],
+        };
+        assert_eq!(run.to_string(), "run `cargo test`");
+
+        let planned = ApprovalRequest::PlannedWrites {
+            paths: vec![PathBuf::from("tests/a_test.rs"), PathBuf::from("src/a.rs")],
+        };
+        assert_eq!(
+            planned.to_string(),
+            "write 2 file(s): tests/a_test.rs, src/a.rs"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auto_approve() {
+        let request = ApprovalRequest::RunCommand {
+            command: "ls".to_string(),
+            args: vec![],
+        };
+        assert!(AutoApprove.approve(&request).await);
+    }
 }
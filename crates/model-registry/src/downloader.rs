@@ -163,7 +163,11 @@ impl ModelDownloader {
     ///
     /// # Returns
     /// The actual checksum string
-    async fn verify_checksum(&self, file_path: &Path, expected_checksum: &str) -> Result<String> {
+    pub async fn verify_checksum(
+        &self,
+        file_path: &Path,
+        expected_checksum: &str,
+    ) -> Result<String> {
         use sha2::{Digest, Sha256};
 
         // Parse expected checksum format: "sha256:hash"
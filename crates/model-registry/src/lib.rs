@@ -5,14 +5,22 @@
 pub use downloader::{DownloadResult, ModelDownloader};
 pub use local_mistral::{LocalBackendConfig, LocalMistralBackend};
 pub use manager::ModelManager;
-pub use manifest::{BackendConfig, ModelDefinition, ModelManifest};
-pub use registry::{ModelInfo, ModelListEntry, ModelRegistry};
+pub use manifest::{BackendConfig, ManifestError, ModelDefinition, ModelManifest, ModelShard};
+#[cfg(any(test, feature = "mock"))]
+pub use mock::MockBackend;
+pub use recording::RecordingBackend;
+pub use registry::{
+    ModelInfo, ModelListEntry, ModelRegistry, SharedRegistry, SUPPORTED_ENGAGEMENT,
+};
 pub use remote_stub::{RemoteBackend, RemoteBackendConfig};
 
 pub mod downloader;
 pub mod local_mistral;
 pub mod manager;
 pub mod manifest;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod recording;
 pub mod registry;
 pub mod remote_stub;
 
@@ -138,6 +146,7 @@ backends:
                     quantization: "".to_string(),
                     checksum: "sha256:abc".to_string(),
                     backend: "local".to_string(),
+                    shards: Vec::new(),
                 },
             )]
             .into_iter()
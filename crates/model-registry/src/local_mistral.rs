@@ -4,7 +4,9 @@
 /// with mistral.rs for running GGUF models locally. For now, it's a stub that
 /// returns mock responses for testing and development.
 use async_trait::async_trait;
-use bodhya_core::{BackendType, ModelBackend, ModelRequest, ModelResponse, Result};
+use bodhya_core::{
+    BackendType, Error, FinishReason, ModelBackend, ModelRequest, ModelResponse, Result,
+};
 use std::path::PathBuf;
 
 /// Configuration for local mistral.rs backend
@@ -49,6 +51,9 @@ impl LocalMistralBackend {
     }
 
     /// Create a backend for a specific model file
+    ///
+    /// For a sharded model, `model_path` may be the first shard or the
+    /// directory holding the shards.
     pub fn from_model_path(model_path: PathBuf, model_id: impl Into<String>) -> Self {
         Self {
             config: LocalBackendConfig {
@@ -58,6 +63,22 @@ impl LocalMistralBackend {
             },
         }
     }
+
+    /// The model file to load: `model_path` itself, or the first GGUF file
+    /// in it when it is a directory
+    fn model_file(&self) -> Option<PathBuf> {
+        let path = &self.config.model_path;
+        if !path.is_dir() {
+            return path.exists().then(|| path.clone());
+        }
+        let mut ggufs: Vec<PathBuf> = std::fs::read_dir(path)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| file.extension().is_some_and(|ext| ext == "gguf"))
+            .collect();
+        ggufs.sort();
+        ggufs.into_iter().next()
+    }
 }
 
 #[async_trait]
@@ -74,11 +95,17 @@ impl ModelBackend for LocalMistralBackend {
         // Stub implementation - returns a mock response
         // TODO: Integrate with mistral.rs for actual inference
 
+        // Chat templates take the system prompt as its own message
+        let system = request
+            .system_prompt
+            .as_deref()
+            .map(|system| format!("System: {}\n", system))
+            .unwrap_or_default();
         let response_text = format!(
-            "[STUB] Local model response for role={:?} domain={}\nPrompt: {}\n\n\
+            "[STUB] Local model response for role={:?} domain={}\n{}Prompt: {}\n\n\
              This is a placeholder response from the local backend stub. \
              In production, this would use mistral.rs to generate actual responses.",
-            request.role, request.domain, request.prompt
+            request.role, request.domain, system, request.prompt
         );
 
         let metadata = serde_json::json!({
@@ -86,10 +113,14 @@ impl ModelBackend for LocalMistralBackend {
             "model_id": self.config.model_id,
             "model_path": self.config.model_path.display().to_string(),
             "device": self.config.device,
+            "stop": request.stop,
             "stub": true,
         });
 
-        Ok(ModelResponse::new(response_text).with_metadata(metadata))
+        // The stub never truncates its output
+        Ok(ModelResponse::new(response_text)
+            .with_metadata(metadata)
+            .with_finish_reason(FinishReason::Stop))
     }
 
     async fn health_check(&self) -> Result<bool> {
@@ -97,6 +128,17 @@ impl ModelBackend for LocalMistralBackend {
         // In a real implementation, this would check if the model file exists and can be loaded
         Ok(true)
     }
+
+    async fn load(&self) -> Result<()> {
+        // Stub implementation - only checks the model file is there
+        if self.model_file().is_some() {
+            Ok(())
+        } else {
+            Err(Error::ModelNotFound(
+                self.config.model_path.display().to_string(),
+            ))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -161,6 +203,20 @@ mod tests {
         assert_eq!(response.metadata["stub"], true);
     }
 
+    #[tokio::test]
+    async fn test_system_prompt_kept_apart_from_prompt() {
+        let backend =
+            LocalMistralBackend::from_model_path(PathBuf::from("/models/test.gguf"), "test_model");
+
+        let request = ModelRequest::new(ModelRole::Coder, "code", "Write add")
+            .with_system_prompt("You are a Rust assistant.");
+        let response = backend.generate(request).await.unwrap();
+
+        assert!(response
+            .text
+            .contains("System: You are a Rust assistant.\nPrompt: Write add\n"));
+    }
+
     #[tokio::test]
     async fn test_health_check() {
         let backend =
@@ -171,6 +227,31 @@ mod tests {
         assert!(health);
     }
 
+    #[tokio::test]
+    async fn test_load_requires_model_file() {
+        let missing =
+            LocalMistralBackend::from_model_path(PathBuf::from("/nonexistent/model.gguf"), "test");
+        assert!(matches!(missing.load().await, Err(Error::ModelNotFound(_))));
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let present = LocalMistralBackend::from_model_path(file.path().to_path_buf(), "test");
+        assert!(present.load().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_load_from_shard_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = LocalMistralBackend::from_model_path(dir.path().to_path_buf(), "big");
+        assert!(backend.load().await.is_err());
+
+        let second = dir.path().join("big-00002-of-00002.gguf");
+        let first = dir.path().join("big-00001-of-00002.gguf");
+        std::fs::write(&second, b"shard two").unwrap();
+        std::fs::write(&first, b"shard one").unwrap();
+        assert_eq!(backend.model_file(), Some(first));
+        assert!(backend.load().await.is_ok());
+    }
+
     #[test]
     fn test_default_config() {
         let config = LocalBackendConfig::default();
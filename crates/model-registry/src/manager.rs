@@ -28,39 +28,40 @@ impl ModelManager {
     /// Install a model from its definition
     ///
     /// Downloads the model from the source URL and verifies its checksum.
+    /// Sharded models have each shard downloaded and verified in turn.
     ///
     /// # Arguments
     /// * `model_id` - Unique identifier for the model
     /// * `definition` - Model definition containing source URL and checksum
     ///
     /// # Returns
-    /// DownloadResult with installation details
+    /// DownloadResult for each file installed, first shard first
     pub async fn install_model(
         &self,
         model_id: &str,
         definition: &ModelDefinition,
-    ) -> Result<DownloadResult> {
+    ) -> Result<Vec<DownloadResult>> {
         tracing::info!("Installing model: {}", model_id);
         tracing::info!("  Display name: {}", definition.display_name);
         tracing::info!("  Size: {:.2} GB", definition.size_gb);
-        tracing::info!("  Source: {}", definition.source_url);
 
-        // Determine destination path
-        let dest_path = self.models_dir.join(format!("{}.gguf", model_id));
-
-        // Download and verify
-        let result = self
-            .downloader
-            .download(
-                &definition.source_url,
-                &dest_path,
-                Some(&definition.checksum),
-            )
-            .await?;
+        let files = definition.file_names(model_id);
+        let mut results = Vec::with_capacity(files.len());
+        for (file_name, (url, checksum)) in files.iter().zip(definition.sources()) {
+            tracing::info!("  Source: {}", url);
+
+            // Download and verify
+            let dest_path = self.models_dir.join(file_name);
+            results.push(
+                self.downloader
+                    .download(url, &dest_path, Some(checksum))
+                    .await?,
+            );
+        }
 
         tracing::info!("Model installed successfully: {}", model_id);
 
-        Ok(result)
+        Ok(results)
     }
 
     /// Check if a model is installed
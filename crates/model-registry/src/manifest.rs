@@ -5,6 +5,7 @@
 use bodhya_core::{Error, ModelRole, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::path::Path;
 
 /// Model manifest loaded from models.yaml
@@ -37,21 +38,46 @@ impl ModelManifest {
             ))
         })?;
 
-        manifest.validate()?;
+        manifest.validate().map_err(|errors| {
+            let problems: Vec<String> = errors.iter().map(|e| format!("  - {}", e)).collect();
+            Error::Config(format!(
+                "Invalid manifest file {}:\n{}",
+                path.display(),
+                problems.join("\n")
+            ))
+        })?;
         Ok(manifest)
     }
 
-    /// Validate the manifest
-    pub fn validate(&self) -> Result<()> {
+    /// Validate the manifest, returning every problem found
+    ///
+    /// When the manifest has a `backends` section, each model's `backend`
+    /// must name one of its entries.
+    pub fn validate(&self) -> std::result::Result<(), Vec<ManifestError>> {
         if self.models.is_empty() {
-            return Err(Error::Config("Manifest contains no models".to_string()));
+            return Err(vec![ManifestError::NoModels]);
         }
 
-        for (id, model) in &self.models {
-            model.validate(id)?;
+        let mut ids: Vec<&String> = self.models.keys().collect();
+        ids.sort();
+
+        let mut errors = Vec::new();
+        for id in ids {
+            let model = &self.models[id];
+            errors.extend(model.problems(id));
+            if !self.backends.is_empty() && !self.backends.contains_key(&model.backend) {
+                errors.push(ManifestError::UnknownBackend {
+                    model: id.clone(),
+                    backend: model.backend.clone(),
+                });
+            }
         }
 
-        Ok(())
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 
     /// Get a model definition by ID
@@ -86,6 +112,7 @@ pub struct ModelDefinition {
     #[serde(default)]
     pub description: String,
     /// URL to download the model
+    #[serde(default)]
     pub source_url: String,
     /// Model size in GB
     pub size_gb: f64,
@@ -93,43 +120,112 @@ pub struct ModelDefinition {
     #[serde(default)]
     pub quantization: String,
     /// Checksum for verification (format: "sha256:...")
+    #[serde(default)]
     pub checksum: String,
     /// Backend type (local, remote)
     pub backend: String,
+    /// Files of a model split across several GGUF shards, in order
+    ///
+    /// When set, these replace `source_url` and `checksum`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shards: Vec<ModelShard>,
+}
+
+/// One file of a sharded model
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModelShard {
+    /// URL to download the shard
+    pub url: String,
+    /// Checksum for verification (format: "sha256:...")
+    pub checksum: String,
 }
 
 impl ModelDefinition {
     /// Validate this model definition
     pub fn validate(&self, id: &str) -> Result<()> {
+        match self.problems(id).into_iter().next() {
+            Some(problem) => Err(Error::Config(problem.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Every problem with this definition on its own
+    fn problems(&self, id: &str) -> Vec<ManifestError> {
+        let model = || id.to_string();
+        let mut problems = Vec::new();
+
         if self.display_name.is_empty() {
-            return Err(Error::Config(format!(
-                "Model '{}' has empty display_name",
-                id
-            )));
+            problems.push(ManifestError::EmptyDisplayName { model: model() });
+        }
+
+        if self.shards.is_empty() && self.source_url.trim().is_empty() {
+            problems.push(ManifestError::EmptySourceUrl { model: model() });
         }
 
-        if self.source_url.is_empty() {
-            return Err(Error::Config(format!(
-                "Model '{}' has empty source_url",
-                id
-            )));
+        if self.size_gb.is_nan() || self.size_gb <= 0.0 {
+            problems.push(ManifestError::InvalidSize {
+                model: model(),
+                size_gb: self.size_gb,
+            });
         }
 
-        if self.size_gb <= 0.0 {
-            return Err(Error::Config(format!(
-                "Model '{}' has invalid size_gb: {}",
-                id, self.size_gb
-            )));
+        if self.shards.is_empty() && !is_valid_checksum(&self.checksum) {
+            problems.push(ManifestError::InvalidChecksum {
+                model: model(),
+                checksum: self.checksum.clone(),
+            });
         }
 
-        if !self.checksum.starts_with("sha256:") {
-            return Err(Error::Config(format!(
-                "Model '{}' has invalid checksum format (must start with 'sha256:')",
-                id
-            )));
+        for (index, shard) in self.shards.iter().enumerate() {
+            if shard.url.trim().is_empty() {
+                problems.push(ManifestError::InvalidShard {
+                    model: model(),
+                    shard: index + 1,
+                    reason: "has empty url".to_string(),
+                });
+            }
+            if !is_valid_checksum(&shard.checksum) {
+                problems.push(ManifestError::InvalidShard {
+                    model: model(),
+                    shard: index + 1,
+                    reason: format!(
+                        "has invalid checksum '{}' (must be 'sha256:<digest>')",
+                        shard.checksum
+                    ),
+                });
+            }
+        }
+
+        problems
+    }
+
+    /// Whether the model is split across several files
+    pub fn is_sharded(&self) -> bool {
+        !self.shards.is_empty()
+    }
+
+    /// Names of the files the model is stored as, first shard first
+    ///
+    /// Shards follow the llama.cpp convention `<id>-00001-of-00002.gguf`.
+    pub fn file_names(&self, id: &str) -> Vec<String> {
+        let count = self.shards.len();
+        if count == 0 {
+            return vec![format!("{}.gguf", id)];
         }
+        (1..=count)
+            .map(|n| format!("{}-{:05}-of-{:05}.gguf", id, n, count))
+            .collect()
+    }
 
-        Ok(())
+    /// Download URL and checksum for each file in [`Self::file_names`]
+    pub fn sources(&self) -> Vec<(&str, &str)> {
+        if self.shards.is_empty() {
+            return vec![(&self.source_url, &self.checksum)];
+        }
+        self.shards
+            .iter()
+            .map(|shard| (shard.url.as_str(), shard.checksum.as_str()))
+            .collect()
     }
 
     /// Get the expected file size in bytes
@@ -145,6 +241,78 @@ impl ModelDefinition {
     }
 }
 
+/// Whether `checksum` is `sha256:` followed by a digest
+fn is_valid_checksum(checksum: &str) -> bool {
+    checksum
+        .strip_prefix("sha256:")
+        .is_some_and(|digest| !digest.is_empty() && !digest.contains(char::is_whitespace))
+}
+
+/// A problem found by [`ModelManifest::validate`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ManifestError {
+    /// The manifest defines no models
+    NoModels,
+    EmptyDisplayName {
+        model: String,
+    },
+    EmptySourceUrl {
+        model: String,
+    },
+    /// `size_gb` is zero, negative, or not a number
+    InvalidSize {
+        model: String,
+        size_gb: f64,
+    },
+    /// The checksum is not `sha256:<digest>`
+    InvalidChecksum {
+        model: String,
+        checksum: String,
+    },
+    /// `backend` names no entry in the `backends` section
+    UnknownBackend {
+        model: String,
+        backend: String,
+    },
+    /// A shard is missing its URL or has a malformed checksum
+    InvalidShard {
+        model: String,
+        /// 1-based shard number
+        shard: usize,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::NoModels => write!(f, "Manifest contains no models"),
+            ManifestError::EmptyDisplayName { model } => {
+                write!(f, "Model '{}' has empty display_name", model)
+            }
+            ManifestError::EmptySourceUrl { model } => {
+                write!(f, "Model '{}' has empty source_url", model)
+            }
+            ManifestError::InvalidSize { model, size_gb } => {
+                write!(f, "Model '{}' has invalid size_gb: {}", model, size_gb)
+            }
+            ManifestError::InvalidChecksum { model, checksum } => write!(
+                f,
+                "Model '{}' has invalid checksum '{}' (must be 'sha256:<digest>')",
+                model, checksum
+            ),
+            ManifestError::UnknownBackend { model, backend } => {
+                write!(f, "Model '{}' uses undefined backend '{}'", model, backend)
+            }
+            ManifestError::InvalidShard {
+                model,
+                shard,
+                reason,
+            } => write!(f, "Model '{}' shard {} {}", model, shard, reason),
+        }
+    }
+}
+
 /// Backend configuration
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BackendConfig {
@@ -235,6 +403,66 @@ backends:
         assert!(manifest.validate().is_err());
     }
 
+    #[test]
+    fn test_validation_reports_every_defect() {
+        let yaml = r#"
+models:
+  broken_planner:
+    role: planner
+    domain: code
+    display_name: "Broken Planner"
+    source_url: ""
+    size_gb: 0
+    checksum: "md5:abc"
+    backend: local
+  broken_coder:
+    role: coder
+    domain: code
+    display_name: "Broken Coder"
+    source_url: "https://example.com/coder.gguf"
+    size_gb: 3.5
+    checksum: "sha256:def456"
+    backend: cloud
+backends:
+  local:
+    type: mistral_rs
+"#;
+        let manifest: ModelManifest = serde_yaml::from_str(yaml).unwrap();
+
+        let errors = manifest.validate().unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![
+                ManifestError::UnknownBackend {
+                    model: "broken_coder".to_string(),
+                    backend: "cloud".to_string(),
+                },
+                ManifestError::EmptySourceUrl {
+                    model: "broken_planner".to_string(),
+                },
+                ManifestError::InvalidSize {
+                    model: "broken_planner".to_string(),
+                    size_gb: 0.0,
+                },
+                ManifestError::InvalidChecksum {
+                    model: "broken_planner".to_string(),
+                    checksum: "md5:abc".to_string(),
+                },
+            ]
+        );
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "{}", yaml).unwrap();
+        let message = ModelManifest::from_file(temp_file.path())
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("undefined backend 'cloud'"));
+        assert!(message.contains("empty source_url"));
+        assert!(message.contains("invalid size_gb: 0"));
+        assert!(message.contains("invalid checksum 'md5:abc'"));
+    }
+
     #[test]
     fn test_get_model() {
         let yaml = create_test_manifest();
@@ -286,6 +514,7 @@ backends:
             quantization: "Q4_K_M".to_string(),
             checksum: "sha256:abc123".to_string(),
             backend: "local".to_string(),
+            shards: Vec::new(),
         };
 
         assert!(model.validate("test_id").is_ok());
@@ -303,6 +532,7 @@ backends:
             quantization: "".to_string(),
             checksum: "sha256:abc".to_string(),
             backend: "local".to_string(),
+            shards: Vec::new(),
         };
 
         assert!(model.validate("test").is_err());
@@ -320,6 +550,7 @@ backends:
             quantization: "".to_string(),
             checksum: "invalid_checksum".to_string(),
             backend: "local".to_string(),
+            shards: Vec::new(),
         };
 
         assert!(model.validate("test").is_err());
@@ -337,6 +568,7 @@ backends:
             quantization: "".to_string(),
             checksum: "sha256:abc".to_string(),
             backend: "local".to_string(),
+            shards: Vec::new(),
         };
 
         assert_eq!(model.size_bytes(), 4_400_000_000);
@@ -354,6 +586,7 @@ backends:
             quantization: "".to_string(),
             checksum: "sha256:abc123def456".to_string(),
             backend: "local".to_string(),
+            shards: Vec::new(),
         };
 
         assert_eq!(model.checksum_hash(), "abc123def456");
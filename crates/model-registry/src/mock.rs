@@ -0,0 +1,207 @@
+/// Scripted model backend for tests
+///
+/// `MockBackend` returns canned responses instead of running a model, so
+/// agent pipelines can be driven end-to-end deterministically. Responses are
+/// taken from a per-role queue first, then from a shared queue.
+use async_trait::async_trait;
+use bodhya_core::{
+    BackendType, Error, FinishReason, ModelBackend, ModelRequest, ModelResponse, ModelRole, Result,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::registry::ModelRegistry;
+
+/// A scripted reply: a model response, or the error to fail with
+type Scripted = std::result::Result<ModelResponse, Error>;
+
+/// Model backend that replays scripted responses
+#[derive(Debug, Default)]
+pub struct MockBackend {
+    id: String,
+    /// Responses for any role, in order
+    queue: Mutex<VecDeque<Scripted>>,
+    /// Responses for specific roles, in order
+    by_role: Mutex<HashMap<ModelRole, VecDeque<Scripted>>>,
+    /// Requests received so far
+    requests: Mutex<Vec<ModelRequest>>,
+}
+
+impl MockBackend {
+    /// Create a mock backend with no scripted responses
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Queue a response for the next request of any role
+    pub fn with_response(self, text: impl Into<String>) -> Self {
+        self.push_response(text);
+        self
+    }
+
+    /// Queue a response cut off by the token limit for the next request of any role
+    pub fn with_truncated_response(self, text: impl Into<String>) -> Self {
+        lock(&self.queue).push_back(Ok(
+            ModelResponse::new(text).with_finish_reason(FinishReason::Length)
+        ));
+        self
+    }
+
+    /// Queue a response for the next request with `role`
+    pub fn with_role_response(self, role: ModelRole, text: impl Into<String>) -> Self {
+        self.push_role_response(role, text);
+        self
+    }
+
+    /// Fail the next request with `role` with a model error
+    pub fn with_role_failure(self, role: ModelRole, message: impl Into<String>) -> Self {
+        self.push_role_scripted(role, Err(Error::Model(message.into())));
+        self
+    }
+
+    /// Fail the next request with `role` as if the backend were briefly unreachable
+    pub fn with_role_unavailable(self, role: ModelRole, message: impl Into<String>) -> Self {
+        self.push_role_scripted(role, Err(Error::ModelUnavailable(message.into())));
+        self
+    }
+
+    /// Queue a response for the next request of any role
+    pub fn push_response(&self, text: impl Into<String>) {
+        lock(&self.queue).push_back(Ok(ModelResponse::new(text)));
+    }
+
+    /// Queue a response for the next request with `role`
+    pub fn push_role_response(&self, role: ModelRole, text: impl Into<String>) {
+        self.push_role_scripted(role, Ok(ModelResponse::new(text)));
+    }
+
+    fn push_role_scripted(&self, role: ModelRole, scripted: Scripted) {
+        lock(&self.by_role)
+            .entry(role)
+            .or_default()
+            .push_back(scripted);
+    }
+
+    /// Requests received so far, in order
+    pub fn requests(&self) -> Vec<ModelRequest> {
+        lock(&self.requests).clone()
+    }
+
+    /// Build a registry whose models for every role in `domain` use this backend
+    pub fn into_registry(self, domain: &str) -> ModelRegistry {
+        Arc::new(self).registry(domain)
+    }
+
+    /// Build a registry whose models for every role in `domain` share this backend
+    ///
+    /// Keep a clone of the `Arc` to inspect [`requests`](Self::requests) afterwards.
+    pub fn registry(self: Arc<Self>, domain: &str) -> ModelRegistry {
+        ModelRegistry::single_backend(domain, self)
+    }
+}
+
+#[async_trait]
+impl ModelBackend for MockBackend {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn backend_type(&self) -> BackendType {
+        BackendType::Local
+    }
+
+    async fn generate(&self, request: ModelRequest) -> Result<ModelResponse> {
+        let role = request.role.clone();
+        lock(&self.requests).push(request);
+
+        let scripted = lock(&self.by_role)
+            .get_mut(&role)
+            .and_then(VecDeque::pop_front)
+            .or_else(|| lock(&self.queue).pop_front());
+
+        match scripted {
+            Some(Ok(response)) => Ok(response),
+            Some(Err(error)) => Err(error),
+            None => Err(Error::Model(format!(
+                "Mock backend '{}' has no response queued for role {}",
+                self.id, role
+            ))),
+        }
+    }
+}
+
+/// Lock `mutex`, recovering the data if a panicking test poisoned it
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bodhya_core::EngagementMode;
+
+    #[tokio::test]
+    async fn test_role_queue_before_shared_queue() {
+        let backend = MockBackend::new("mock")
+            .with_response("shared")
+            .with_role_response(ModelRole::Coder, "coder");
+
+        let coder = backend
+            .generate(ModelRequest::new(ModelRole::Coder, "code", "p"))
+            .await
+            .unwrap();
+        let planner = backend
+            .generate(ModelRequest::new(ModelRole::Planner, "code", "p"))
+            .await
+            .unwrap();
+
+        assert_eq!(coder.text, "coder");
+        assert_eq!(planner.text, "shared");
+        assert!(backend
+            .generate(ModelRequest::new(ModelRole::Coder, "code", "p"))
+            .await
+            .is_err());
+        assert_eq!(backend.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_scripted_failure() {
+        let backend = MockBackend::new("mock")
+            .with_role_failure(ModelRole::Planner, "connection reset")
+            .with_role_response(ModelRole::Planner, "plan");
+
+        let err = backend
+            .generate(ModelRequest::new(ModelRole::Planner, "code", "p"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("connection reset"));
+
+        let planner = backend
+            .generate(ModelRequest::new(ModelRole::Planner, "code", "p"))
+            .await
+            .unwrap();
+        assert_eq!(planner.text, "plan");
+    }
+
+    #[tokio::test]
+    async fn test_registry_routes_roles_to_backend() {
+        let backend = Arc::new(MockBackend::new("mock").with_response("hello"));
+        let registry = Arc::clone(&backend).registry("mail");
+
+        let info = registry
+            .get_model(&ModelRole::Writer, "mail", &EngagementMode::Minimum)
+            .unwrap();
+        let response = registry
+            .get_backend(&info.id)
+            .unwrap()
+            .generate(ModelRequest::new(ModelRole::Writer, "mail", "Draft"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.text, "hello");
+        assert_eq!(backend.requests()[0].prompt, "Draft");
+    }
+}
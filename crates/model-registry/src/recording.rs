@@ -0,0 +1,91 @@
+/// Model backend wrapper that records calls to the event log
+///
+/// Wrapping a backend records each generation's role, duration, and token
+/// counts as a [`RunEvent::ModelCall`]. Token counts come from the response
+/// metadata when the backend reports them and are estimated otherwise.
+use async_trait::async_trait;
+use bodhya_core::{
+    BackendType, EventRecorder, ModelBackend, ModelRequest, ModelResponse, Result, RunEvent,
+};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Backend that records each call through an [`EventRecorder`]
+pub struct RecordingBackend {
+    inner: Arc<dyn ModelBackend>,
+    model_id: String,
+    events: EventRecorder,
+}
+
+impl RecordingBackend {
+    /// Record calls to `inner`, registered as `model_id`, into `events`
+    pub fn new(
+        inner: Arc<dyn ModelBackend>,
+        model_id: impl Into<String>,
+        events: EventRecorder,
+    ) -> Self {
+        Self {
+            inner,
+            model_id: model_id.into(),
+            events,
+        }
+    }
+}
+
+#[async_trait]
+impl ModelBackend for RecordingBackend {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn backend_type(&self) -> BackendType {
+        self.inner.backend_type()
+    }
+
+    async fn generate(&self, request: ModelRequest) -> Result<ModelResponse> {
+        let role = request.role.clone();
+        let prompt_estimate = estimate_tokens(&request.prompt)
+            + request.system_prompt.as_deref().map_or(0, estimate_tokens);
+
+        let start = Instant::now();
+        let result = self.inner.generate(request).await;
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        let (prompt_tokens, completion_tokens) = match &result {
+            Ok(response) => (
+                reported_tokens(response, "prompt_tokens").unwrap_or(prompt_estimate),
+                reported_tokens(response, "completion_tokens")
+                    .unwrap_or_else(|| estimate_tokens(&response.text)),
+            ),
+            Err(_) => (prompt_estimate, 0),
+        };
+        self.events.record(RunEvent::ModelCall {
+            role,
+            model_id: self.model_id.clone(),
+            duration_ms,
+            prompt_tokens,
+            completion_tokens,
+            success: result.is_ok(),
+        });
+
+        result
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        self.inner.health_check().await
+    }
+
+    async fn load(&self) -> Result<()> {
+        self.inner.load().await
+    }
+}
+
+/// Token count the backend reported under `key` in the response metadata
+fn reported_tokens(response: &ModelResponse, key: &str) -> Option<u64> {
+    response.metadata.get(key).and_then(|v| v.as_u64())
+}
+
+/// Rough token count of `text` (one token per four characters)
+fn estimate_tokens(text: &str) -> u64 {
+    text.chars().count().div_ceil(4) as u64
+}
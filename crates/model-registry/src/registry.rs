@@ -2,17 +2,25 @@
 ///
 /// This module provides a registry that maps (role, domain, engagement) tuples
 /// to appropriate model backends, handling model selection logic.
-use bodhya_core::{EngagementMode, Error, ModelBackend, ModelRole, Result};
+use bodhya_core::{EngagementMode, Error, EventRecorder, ModelBackend, ModelRole, Result};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::local_mistral::LocalMistralBackend;
 use crate::manifest::{ModelDefinition, ModelManifest};
+use crate::recording::RecordingBackend;
+
+/// The only engagement mode models can be selected in (v1 is local-only)
+pub const SUPPORTED_ENGAGEMENT: EngagementMode = EngagementMode::Minimum;
 
 /// Model registry for looking up and managing models
+#[derive(Clone)]
 pub struct ModelRegistry {
     /// Loaded manifest
     manifest: ModelManifest,
+    /// File the manifest was read from, if any
+    manifest_path: Option<PathBuf>,
     /// Cached backend instances
     backends: HashMap<String, Arc<dyn ModelBackend>>,
     /// Models directory path
@@ -30,6 +38,7 @@ impl ModelRegistry {
 
         Ok(Self {
             manifest,
+            manifest_path: Some(manifest_path),
             backends: HashMap::new(),
             models_dir: models_dir.into(),
         })
@@ -39,11 +48,96 @@ impl ModelRegistry {
     pub fn from_manifest(manifest: ModelManifest, models_dir: impl Into<PathBuf>) -> Self {
         Self {
             manifest,
+            manifest_path: None,
             backends: HashMap::new(),
             models_dir: models_dir.into(),
         }
     }
 
+    /// Create a registry whose models for every role in `domain` use `backend`
+    ///
+    /// Useful for running an agent against one particular backend, e.g. to
+    /// evaluate it or to drive a pipeline with a scripted backend in tests.
+    pub fn single_backend(domain: &str, backend: Arc<dyn ModelBackend>) -> Self {
+        let models = ModelRole::ALL
+            .iter()
+            .map(|role| {
+                let definition = ModelDefinition {
+                    role: role.clone(),
+                    domain: domain.to_string(),
+                    display_name: format!("{} {}", backend.id(), role),
+                    description: String::new(),
+                    source_url: String::new(),
+                    size_gb: 0.0,
+                    quantization: String::new(),
+                    checksum: String::new(),
+                    // Not a manifest backend type: the instance is registered below
+                    backend: "registered".to_string(),
+                    shards: Vec::new(),
+                };
+                (format!("{}-{}-{}", backend.id(), domain, role), definition)
+            })
+            .collect();
+
+        let manifest = ModelManifest {
+            models,
+            backends: HashMap::new(),
+        };
+        let mut registry = Self::from_manifest(manifest, PathBuf::from("/nonexistent"));
+        for role in ModelRole::ALL {
+            if let Ok(info) = registry.get_model(&role, domain, &EngagementMode::Minimum) {
+                registry.register_backend(info.id, Arc::clone(&backend));
+            }
+        }
+        registry
+    }
+
+    /// Re-read the manifest file, keeping backends for models still listed
+    ///
+    /// Installed state is read from the models directory on every lookup, so
+    /// after a reload it reflects files added or removed out-of-band as well
+    /// as manifest edits. If local backends were registered, newly listed
+    /// local models get one too. On error the registry is left unchanged.
+    pub fn reload(&mut self) -> Result<()> {
+        let path = self.manifest_path.as_ref().ok_or_else(|| {
+            Error::Config("Registry was not loaded from a manifest file".to_string())
+        })?;
+        let manifest = ModelManifest::from_file(path)?;
+
+        let had_local = self.backends.keys().any(|id| self.is_local(id));
+        self.backends
+            .retain(|model_id, _| manifest.models.contains_key(model_id));
+        self.manifest = manifest;
+        if had_local {
+            self.register_local_backends();
+        }
+        Ok(())
+    }
+
+    /// Register a local backend for every `local` model in the manifest
+    ///
+    /// Models that already have a backend keep it. A backend is registered
+    /// whether or not the model file is on disk yet; it fails to load (and
+    /// is skipped by [`Self::preload_all_installed`]) until it is installed.
+    pub fn register_local_backends(&mut self) {
+        let mut model_ids: Vec<String> = self.manifest.models.keys().cloned().collect();
+        model_ids.sort();
+        for model_id in model_ids {
+            if self.is_local(&model_id) && !self.backends.contains_key(&model_id) {
+                let backend =
+                    LocalMistralBackend::from_model_path(self.get_model_path(&model_id), &model_id);
+                self.backends.insert(model_id, Arc::new(backend));
+            }
+        }
+    }
+
+    /// Whether the manifest runs `model_id` on the local backend
+    fn is_local(&self, model_id: &str) -> bool {
+        self.manifest
+            .get_model(model_id)
+            .is_some_and(|definition| definition.backend == "local")
+    }
+
     /// Get a model backend for the given role, domain, and engagement mode
     ///
     /// This is the primary API for agents to obtain models.
@@ -54,7 +148,7 @@ impl ModelRegistry {
         engagement: &EngagementMode,
     ) -> Result<ModelInfo> {
         // In v1, we only support local models with Minimum engagement
-        if *engagement != EngagementMode::Minimum {
+        if *engagement != SUPPORTED_ENGAGEMENT {
             return Err(Error::EngagementViolation(format!(
                 "Only Minimum engagement mode is supported in v1, requested: {:?}",
                 engagement
@@ -83,15 +177,60 @@ impl ModelRegistry {
         })
     }
 
+    /// Look up a model by its manifest ID
+    pub fn get_model_by_id(&self, model_id: &str) -> Result<ModelInfo> {
+        let definition = self.manifest.get_model(model_id).ok_or_else(|| {
+            Error::ModelNotFound(format!("No model with id '{}' in manifest", model_id))
+        })?;
+
+        Ok(ModelInfo {
+            id: model_id.to_string(),
+            definition: definition.clone(),
+            installed: self.is_model_installed(model_id),
+            model_path: self.get_model_path(model_id),
+        })
+    }
+
+    /// Validate that a model ID is safe to use as a file name
+    ///
+    /// Rejects empty IDs and IDs containing path separators or `..`, which
+    /// could otherwise resolve to a path outside the models directory.
+    pub fn validate_model_id(model_id: &str) -> Result<()> {
+        if model_id.is_empty()
+            || model_id.contains('/')
+            || model_id.contains('\\')
+            || model_id.contains("..")
+        {
+            return Err(Error::InvalidInput(format!(
+                "Invalid model id '{}': must not be empty or contain path separators or '..'",
+                model_id
+            )));
+        }
+        Ok(())
+    }
+
     /// Check if a model is installed
+    ///
+    /// A sharded model is installed only when every shard is on disk.
     pub fn is_model_installed(&self, model_id: &str) -> bool {
-        let path = self.get_model_path(model_id);
-        path.exists()
+        self.model_files(model_id).iter().all(|path| path.exists())
     }
 
-    /// Get the filesystem path for a model
+    /// Get the filesystem path for a model, or its first shard
     pub fn get_model_path(&self, model_id: &str) -> PathBuf {
-        self.models_dir.join(format!("{}.gguf", model_id))
+        self.model_files(model_id).swap_remove(0)
+    }
+
+    /// Paths of every file a model is stored as, first shard first
+    pub fn model_files(&self, model_id: &str) -> Vec<PathBuf> {
+        let names = match self.manifest.get_model(model_id) {
+            Some(definition) => definition.file_names(model_id),
+            None => vec![format!("{}.gguf", model_id)],
+        };
+        names
+            .into_iter()
+            .map(|name| self.models_dir.join(name))
+            .collect()
     }
 
     /// List all models in the manifest
@@ -110,6 +249,14 @@ impl ModelRegistry {
             .collect()
     }
 
+    /// List models that fill the given role
+    pub fn list_models_by_role(&self, role: &ModelRole) -> Vec<ModelListEntry> {
+        self.list_models()
+            .into_iter()
+            .filter(|model| model.role == *role)
+            .collect()
+    }
+
     /// Get the manifest
     pub fn manifest(&self) -> &ModelManifest {
         &self.manifest
@@ -124,6 +271,102 @@ impl ModelRegistry {
     pub fn get_backend(&self, model_id: &str) -> Option<Arc<dyn ModelBackend>> {
         self.backends.get(model_id).cloned()
     }
+
+    /// Load the model for `role` in `domain` so its first request is fast
+    pub async fn preload(&self, role: &ModelRole, domain: &str) -> Result<()> {
+        let info = self.get_model(role, domain, &EngagementMode::Minimum)?;
+        let backend = self
+            .get_backend(&info.id)
+            .ok_or_else(|| Error::ModelNotFound(format!("{} (no backend registered)", info.id)))?;
+        backend.load().await
+    }
+
+    /// Load every model that has a registered backend and is installed
+    ///
+    /// Local models without a model file are skipped. Returns the outcome for
+    /// each model loaded, so callers can log failures and carry on.
+    pub async fn preload_all_installed(&self) -> Vec<(String, Result<()>)> {
+        let mut model_ids: Vec<&String> = self.backends.keys().collect();
+        model_ids.sort();
+
+        let mut outcomes = Vec::new();
+        for model_id in model_ids {
+            if self.is_local(model_id) && !self.is_model_installed(model_id) {
+                continue;
+            }
+            outcomes.push((model_id.clone(), self.backends[model_id].load().await));
+        }
+        outcomes
+    }
+
+    /// Copy of this registry whose backends record each call into `events`
+    pub fn with_events(&self, events: &EventRecorder) -> Self {
+        let backends = self
+            .backends
+            .iter()
+            .map(|(model_id, backend)| {
+                let recording =
+                    RecordingBackend::new(Arc::clone(backend), model_id.clone(), events.clone());
+                (
+                    model_id.clone(),
+                    Arc::new(recording) as Arc<dyn ModelBackend>,
+                )
+            })
+            .collect();
+
+        Self {
+            manifest: self.manifest.clone(),
+            manifest_path: self.manifest_path.clone(),
+            backends,
+            models_dir: self.models_dir.clone(),
+        }
+    }
+}
+
+/// Handle to a model registry that can be swapped while it is shared
+///
+/// Agents built with a handle read the current registry at the start of each
+/// task, so [`SharedRegistry::reload`] reaches every holder of a clone.
+/// Tasks already running keep the registry they started with.
+#[derive(Clone)]
+pub struct SharedRegistry {
+    current: Arc<std::sync::RwLock<Arc<ModelRegistry>>>,
+}
+
+impl SharedRegistry {
+    /// Share `registry`
+    pub fn new(registry: Arc<ModelRegistry>) -> Self {
+        Self {
+            current: Arc::new(std::sync::RwLock::new(registry)),
+        }
+    }
+
+    /// The registry in use now
+    pub fn current(&self) -> Arc<ModelRegistry> {
+        Arc::clone(&self.current.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Use `registry` from now on
+    pub fn replace(&self, registry: Arc<ModelRegistry>) {
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = registry;
+    }
+
+    /// Re-read the current registry's manifest and swap in the result
+    ///
+    /// On error the current registry stays in use.
+    pub fn reload(&self) -> Result<Arc<ModelRegistry>> {
+        let mut reloaded = (*self.current()).clone();
+        reloaded.reload()?;
+        let reloaded = Arc::new(reloaded);
+        self.replace(Arc::clone(&reloaded));
+        Ok(reloaded)
+    }
+}
+
+impl From<Arc<ModelRegistry>> for SharedRegistry {
+    fn from(registry: Arc<ModelRegistry>) -> Self {
+        Self::new(registry)
+    }
 }
 
 /// Information about a model resolved from the registry
@@ -269,6 +512,153 @@ backends:
         assert!(!registry.is_model_installed("test_coder"));
     }
 
+    #[test]
+    fn test_sharded_model_installed_only_with_every_shard() {
+        let yaml = r#"
+models:
+  big_coder:
+    role: coder
+    domain: code
+    display_name: "Big Coder"
+    size_gb: 40.0
+    backend: local
+    shards:
+      - url: "https://example.com/big-00001-of-00002.gguf"
+        checksum: "sha256:aaa111"
+      - url: "https://example.com/big-00002-of-00002.gguf"
+        checksum: "sha256:bbb222"
+"#;
+        let manifest: ModelManifest = serde_yaml::from_str(yaml).unwrap();
+        assert!(manifest.validate().is_ok());
+        let temp_dir = TempDir::new().unwrap();
+        let registry = ModelRegistry::from_manifest(manifest, temp_dir.path());
+
+        let files = registry.model_files("big_coder");
+        assert_eq!(
+            files,
+            vec![
+                temp_dir.path().join("big_coder-00001-of-00002.gguf"),
+                temp_dir.path().join("big_coder-00002-of-00002.gguf"),
+            ]
+        );
+        assert_eq!(registry.get_model_path("big_coder"), files[0]);
+
+        std::fs::write(&files[0], b"shard one").unwrap();
+        assert!(!registry.is_model_installed("big_coder"));
+        assert!(!registry.get_model_by_id("big_coder").unwrap().installed);
+
+        std::fs::write(&files[1], b"shard two").unwrap();
+        assert!(registry.is_model_installed("big_coder"));
+    }
+
+    #[test]
+    fn test_reload_picks_up_manifest_and_installed_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest_path = temp_dir.path().join("models.yaml");
+        let models_dir = temp_dir.path().join("models");
+        std::fs::create_dir_all(&models_dir).unwrap();
+
+        let planner = r#"
+models:
+  test_planner:
+    role: planner
+    domain: code
+    display_name: "Test Planner"
+    source_url: "https://example.com/model.gguf"
+    size_gb: 4.0
+    checksum: "sha256:abc123"
+    backend: local
+"#;
+        std::fs::write(&manifest_path, planner).unwrap();
+        let mut registry = ModelRegistry::from_manifest_file(&manifest_path, &models_dir).unwrap();
+        registry.register_local_backends();
+        let before = registry.clone();
+        assert!(!registry.get_model_by_id("test_planner").unwrap().installed);
+
+        // Install the planner and add a coder out-of-band
+        std::fs::write(models_dir.join("test_planner.gguf"), b"fake model data").unwrap();
+        let with_coder = format!(
+            "{}{}",
+            planner,
+            r#"
+  test_coder:
+    role: coder
+    domain: code
+    display_name: "Test Coder"
+    source_url: "https://example.com/coder.gguf"
+    size_gb: 3.5
+    checksum: "sha256:def456"
+    backend: local
+"#
+        );
+        std::fs::write(&manifest_path, with_coder).unwrap();
+        registry.reload().unwrap();
+
+        assert!(registry.get_model_by_id("test_planner").unwrap().installed);
+        assert!(registry.get_model_by_id("test_coder").is_ok());
+        assert!(registry.get_backend("test_coder").is_some());
+        // Copies taken before the reload keep the old manifest
+        assert!(before.get_model_by_id("test_coder").is_err());
+
+        // A broken manifest leaves the registry as it was
+        std::fs::write(&manifest_path, "models: {}").unwrap();
+        assert!(registry.reload().is_err());
+        assert_eq!(registry.list_models().len(), 2);
+    }
+
+    #[test]
+    fn test_register_local_backends_keeps_existing() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut registry = ModelRegistry::from_manifest(create_test_manifest(), temp_dir.path());
+        let mock: Arc<dyn ModelBackend> = Arc::new(crate::MockBackend::new("mock"));
+        registry.register_backend("test_planner".to_string(), Arc::clone(&mock));
+
+        registry.register_local_backends();
+
+        assert!(Arc::ptr_eq(
+            &registry.get_backend("test_planner").unwrap(),
+            &mock
+        ));
+        let coder = registry.get_backend("test_coder").unwrap();
+        assert_eq!(coder.backend_type(), bodhya_core::BackendType::Local);
+        assert!(registry.get_backend("test_writer").is_some());
+    }
+
+    #[test]
+    fn test_shared_registry_reload_reaches_clones() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest_path = temp_dir.path().join("models.yaml");
+        let mut manifest = create_test_manifest();
+        let coder = manifest.models.remove("test_coder").unwrap();
+        std::fs::write(&manifest_path, serde_yaml::to_string(&manifest).unwrap()).unwrap();
+        let registry = ModelRegistry::from_manifest_file(&manifest_path, temp_dir.path()).unwrap();
+        let shared = SharedRegistry::new(Arc::new(registry));
+        let held_by_agent = shared.clone();
+        let before = held_by_agent.current();
+
+        manifest.models.insert("test_coder".to_string(), coder);
+        std::fs::write(&manifest_path, serde_yaml::to_string(&manifest).unwrap()).unwrap();
+        shared.reload().unwrap();
+
+        assert!(before.get_model_by_id("test_coder").is_err());
+        assert!(held_by_agent
+            .current()
+            .get_model_by_id("test_coder")
+            .is_ok());
+
+        // A failed reload keeps the current registry
+        std::fs::write(&manifest_path, "models: {}").unwrap();
+        let current = held_by_agent.current();
+        assert!(shared.reload().is_err());
+        assert!(Arc::ptr_eq(&current, &held_by_agent.current()));
+    }
+
+    #[test]
+    fn test_reload_requires_manifest_file() {
+        let mut registry = ModelRegistry::from_manifest(create_test_manifest(), "/tmp");
+        assert!(matches!(registry.reload(), Err(Error::Config(_))));
+    }
+
     #[test]
     fn test_get_model_path() {
         let manifest = create_test_manifest();
@@ -294,6 +684,21 @@ backends:
         assert!(!planner.installed);
     }
 
+    #[test]
+    fn test_list_models_by_role() {
+        let manifest = create_test_manifest();
+        let temp_dir = TempDir::new().unwrap();
+        let registry = ModelRegistry::from_manifest(manifest, temp_dir.path());
+
+        let planners = registry.list_models_by_role(&ModelRole::Planner);
+        assert_eq!(planners.len(), 1);
+        assert_eq!(planners[0].id, "test_planner");
+
+        assert!(registry
+            .list_models_by_role(&ModelRole::Summarizer)
+            .is_empty());
+    }
+
     #[test]
     fn test_list_models_with_installed() {
         let manifest = create_test_manifest();
@@ -312,6 +717,32 @@ backends:
         assert!(!planner.installed);
     }
 
+    #[test]
+    fn test_get_model_by_id() {
+        let manifest = create_test_manifest();
+        let temp_dir = TempDir::new().unwrap();
+        let registry = ModelRegistry::from_manifest(manifest, temp_dir.path());
+
+        let model = registry.get_model_by_id("test_coder").unwrap();
+        assert_eq!(model.id, "test_coder");
+        assert_eq!(model.definition.role, ModelRole::Coder);
+        assert!(!model.installed);
+
+        let result = registry.get_model_by_id("nonexistent");
+        assert!(matches!(result.unwrap_err(), Error::ModelNotFound(_)));
+    }
+
+    #[test]
+    fn test_validate_model_id() {
+        assert!(ModelRegistry::validate_model_id("test_planner").is_ok());
+        assert!(ModelRegistry::validate_model_id("code-coder.v2").is_ok());
+
+        for bad in ["", "../etc/passwd", "a/b", "a\\b", ".."] {
+            let result = ModelRegistry::validate_model_id(bad);
+            assert!(matches!(result.unwrap_err(), Error::InvalidInput(_)));
+        }
+    }
+
     #[test]
     fn test_get_model_info_includes_path() {
         let manifest = create_test_manifest();
@@ -328,4 +759,91 @@ backends:
             .unwrap()
             .contains("test_writer.gguf"));
     }
+
+    /// Backend that counts `load` calls, failing them when `fail` is set
+    #[derive(Default)]
+    struct LoadCounter {
+        loads: std::sync::atomic::AtomicUsize,
+        fail: bool,
+    }
+
+    impl LoadCounter {
+        fn loads(&self) -> usize {
+            self.loads.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ModelBackend for LoadCounter {
+        fn id(&self) -> &str {
+            "counter"
+        }
+
+        fn backend_type(&self) -> bodhya_core::BackendType {
+            bodhya_core::BackendType::Local
+        }
+
+        async fn generate(
+            &self,
+            _request: bodhya_core::ModelRequest,
+        ) -> Result<bodhya_core::ModelResponse> {
+            Ok(bodhya_core::ModelResponse::new("ok"))
+        }
+
+        async fn load(&self) -> Result<()> {
+            self.loads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if self.fail {
+                Err(Error::Model("out of memory".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preload_all_installed_loads_each_model_once() {
+        let temp_dir = TempDir::new().unwrap();
+        for model_id in ["test_planner", "test_writer"] {
+            std::fs::write(temp_dir.path().join(format!("{}.gguf", model_id)), b"model").unwrap();
+        }
+        let mut registry = ModelRegistry::from_manifest(create_test_manifest(), temp_dir.path());
+
+        let planner = Arc::new(LoadCounter::default());
+        let coder = Arc::new(LoadCounter::default());
+        let writer = Arc::new(LoadCounter {
+            fail: true,
+            ..Default::default()
+        });
+        registry.register_backend("test_planner".to_string(), planner.clone());
+        registry.register_backend("test_coder".to_string(), coder.clone());
+        registry.register_backend("test_writer".to_string(), writer.clone());
+
+        let outcomes = registry.preload_all_installed().await;
+
+        // test_coder is not installed, so it is skipped
+        let ids: Vec<&str> = outcomes.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["test_planner", "test_writer"]);
+        assert!(outcomes[0].1.is_ok());
+        assert!(outcomes[1].1.is_err());
+        assert_eq!(planner.loads(), 1);
+        assert_eq!(coder.loads(), 0);
+        assert_eq!(writer.loads(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_preload_by_role_and_domain() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut registry = ModelRegistry::from_manifest(create_test_manifest(), temp_dir.path());
+        let planner = Arc::new(LoadCounter::default());
+        registry.register_backend("test_planner".to_string(), planner.clone());
+
+        registry.preload(&ModelRole::Planner, "code").await.unwrap();
+        assert_eq!(planner.loads(), 1);
+
+        // Known model without a backend
+        assert!(matches!(
+            registry.preload(&ModelRole::Coder, "code").await,
+            Err(Error::ModelNotFound(_))
+        ));
+    }
 }
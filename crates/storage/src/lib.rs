@@ -2,8 +2,8 @@
 ///
 /// This crate provides persistence for task execution history and quality metrics
 /// using SQLite as the storage backend.
-pub use models::{QualityMetrics, Session, TaskRecord, TaskStatus};
-pub use sqlite::{DomainStats, SqliteStorage};
+pub use models::{QualityMetrics, Session, TaskFilter, TaskRecord, TaskStatus, TimeBucket};
+pub use sqlite::{DomainStats, PruneStats, SqliteStorage};
 
 pub mod models;
 pub mod sqlite;
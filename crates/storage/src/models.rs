@@ -2,7 +2,7 @@
 ///
 /// This module defines the data structures used for persisting
 /// task execution history and quality metrics.
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
 
 /// A task execution session
@@ -226,6 +226,82 @@ impl QualityMetrics {
     }
 }
 
+/// Time bucket size for trend queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeBucket {
+    /// One bucket per calendar day (UTC)
+    Day,
+    /// One bucket per ISO week, starting Monday (UTC)
+    Week,
+}
+
+impl TimeBucket {
+    /// Convert to string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeBucket::Day => "day",
+            TimeBucket::Week => "week",
+        }
+    }
+
+    /// Parse from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "day" | "daily" => Some(TimeBucket::Day),
+            "week" | "weekly" => Some(TimeBucket::Week),
+            _ => None,
+        }
+    }
+
+    /// Start of the bucket containing `at`
+    pub fn start_of(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let date = at.date_naive();
+        let date = match self {
+            TimeBucket::Day => date,
+            TimeBucket::Week => {
+                date - chrono::Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+        };
+        date.and_time(chrono::NaiveTime::MIN).and_utc()
+    }
+}
+
+/// Filter for selecting task records (e.g., for export)
+#[derive(Debug, Clone, Default)]
+pub struct TaskFilter {
+    /// Only include tasks in this domain
+    pub domain: Option<String>,
+    /// Only include tasks started at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only include tasks started before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl TaskFilter {
+    /// Create a filter matching all tasks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restrict to a domain
+    pub fn with_domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Restrict to tasks started at or after `since`
+    pub fn with_since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Restrict to tasks started before `until`
+    pub fn with_until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -370,4 +446,39 @@ mod tests {
         assert_eq!(metrics.task_id, deserialized.task_id);
         assert_eq!(metrics.quality_score, deserialized.quality_score);
     }
+
+    #[test]
+    fn test_task_filter_builder() {
+        let since = Utc::now();
+        let filter = TaskFilter::new().with_domain("code").with_since(since);
+
+        assert_eq!(filter.domain.as_deref(), Some("code"));
+        assert_eq!(filter.since, Some(since));
+        assert!(filter.until.is_none());
+    }
+
+    #[test]
+    fn test_time_bucket_parse() {
+        assert_eq!(TimeBucket::parse("day"), Some(TimeBucket::Day));
+        assert_eq!(TimeBucket::parse("Weekly"), Some(TimeBucket::Week));
+        assert_eq!(TimeBucket::parse("month"), None);
+        assert_eq!(TimeBucket::Week.as_str(), "week");
+    }
+
+    #[test]
+    fn test_time_bucket_start_of() {
+        // Thursday afternoon
+        let at = DateTime::parse_from_rfc3339("2024-03-07T15:30:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            TimeBucket::Day.start_of(at).to_rfc3339(),
+            "2024-03-07T00:00:00+00:00"
+        );
+        assert_eq!(
+            TimeBucket::Week.start_of(at).to_rfc3339(),
+            "2024-03-04T00:00:00+00:00"
+        );
+    }
 }
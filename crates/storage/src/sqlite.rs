@@ -2,14 +2,21 @@
 ///
 /// This module provides SQLite-based persistence for task execution
 /// history and quality metrics.
-use crate::models::{QualityMetrics, Session, TaskRecord, TaskStatus};
+use crate::models::{QualityMetrics, Session, TaskFilter, TaskRecord, TaskStatus, TimeBucket};
 use bodhya_core::Result;
 use rusqlite::{params, Connection, OptionalExtension};
+use std::io::Write;
 use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
 
 /// SQLite storage manager
+///
+/// `SqliteStorage` is `Send + Sync` and can be shared across tasks behind an
+/// `Arc`. The single connection is guarded by a mutex, so each method call
+/// holds exclusive access for its duration and writes are serialized; file
+/// databases use WAL journaling so readers in other processes aren't blocked.
 pub struct SqliteStorage {
-    conn: Connection,
+    conn: Mutex<Connection>,
 }
 
 impl SqliteStorage {
@@ -18,7 +25,15 @@ impl SqliteStorage {
         let conn = Connection::open(db_path.as_ref())
             .map_err(|e| bodhya_core::Error::Io(format!("Failed to open database: {}", e)))?;
 
-        let storage = Self { conn };
+        // WAL mode allows concurrent readers while a write is in progress
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to enable WAL mode: {}", e)))?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to set busy timeout: {}", e)))?;
+
+        let storage = Self {
+            conn: Mutex::new(conn),
+        };
         storage.initialize_schema()?;
         Ok(storage)
     }
@@ -29,32 +44,39 @@ impl SqliteStorage {
             bodhya_core::Error::Io(format!("Failed to create in-memory database: {}", e))
         })?;
 
-        let storage = Self { conn };
+        let storage = Self {
+            conn: Mutex::new(conn),
+        };
         storage.initialize_schema()?;
         Ok(storage)
     }
 
+    /// Lock the connection for exclusive use
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        // A panic while holding the lock cannot leave a statement half-applied,
+        // so a poisoned lock is still safe to reuse
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     /// Initialize database schema
     fn initialize_schema(&self) -> Result<()> {
+        let conn = self.conn();
+
         // Sessions table
-        self.conn
-            .execute(
-                "CREATE TABLE IF NOT EXISTS sessions (
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS sessions (
                     id TEXT PRIMARY KEY,
                     started_at TEXT NOT NULL,
                     ended_at TEXT,
                     metadata TEXT
                 )",
-                [],
-            )
-            .map_err(|e| {
-                bodhya_core::Error::Io(format!("Failed to create sessions table: {}", e))
-            })?;
+            [],
+        )
+        .map_err(|e| bodhya_core::Error::Io(format!("Failed to create sessions table: {}", e)))?;
 
         // Tasks table
-        self.conn
-            .execute(
-                "CREATE TABLE IF NOT EXISTS tasks (
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tasks (
                     id TEXT PRIMARY KEY,
                     session_id TEXT NOT NULL,
                     domain TEXT NOT NULL,
@@ -67,14 +89,13 @@ impl SqliteStorage {
                     error TEXT,
                     FOREIGN KEY (session_id) REFERENCES sessions(id)
                 )",
-                [],
-            )
-            .map_err(|e| bodhya_core::Error::Io(format!("Failed to create tasks table: {}", e)))?;
+            [],
+        )
+        .map_err(|e| bodhya_core::Error::Io(format!("Failed to create tasks table: {}", e)))?;
 
         // Quality metrics table
-        self.conn
-            .execute(
-                "CREATE TABLE IF NOT EXISTS quality_metrics (
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS quality_metrics (
                     task_id TEXT PRIMARY KEY,
                     quality_score REAL,
                     iterations INTEGER NOT NULL,
@@ -84,33 +105,55 @@ impl SqliteStorage {
                     recorded_at TEXT NOT NULL,
                     FOREIGN KEY (task_id) REFERENCES tasks(id)
                 )",
-                [],
-            )
-            .map_err(|e| {
-                bodhya_core::Error::Io(format!("Failed to create quality_metrics table: {}", e))
-            })?;
+            [],
+        )
+        .map_err(|e| {
+            bodhya_core::Error::Io(format!("Failed to create quality_metrics table: {}", e))
+        })?;
 
-        // Create indexes for common queries
-        self.conn
-            .execute(
-                "CREATE INDEX IF NOT EXISTS idx_tasks_session ON tasks(session_id)",
-                [],
-            )
-            .map_err(|e| bodhya_core::Error::Io(format!("Failed to create index: {}", e)))?;
+        // Cached planner output, keyed by a hash of the task description
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS plan_cache (
+                    key TEXT PRIMARY KEY,
+                    plan TEXT NOT NULL,
+                    created_at TEXT NOT NULL
+                )",
+            [],
+        )
+        .map_err(|e| bodhya_core::Error::Io(format!("Failed to create plan_cache table: {}", e)))?;
 
-        self.conn
-            .execute(
-                "CREATE INDEX IF NOT EXISTS idx_tasks_domain ON tasks(domain)",
-                [],
-            )
-            .map_err(|e| bodhya_core::Error::Io(format!("Failed to create index: {}", e)))?;
+        // Last checkpoint of each interrupted agentic run, keyed by task id
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS run_checkpoints (
+                    task_id TEXT PRIMARY KEY,
+                    checkpoint TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                )",
+            [],
+        )
+        .map_err(|e| {
+            bodhya_core::Error::Io(format!("Failed to create run_checkpoints table: {}", e))
+        })?;
+
+        // Create indexes for common queries
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tasks_session ON tasks(session_id)",
+            [],
+        )
+        .map_err(|e| bodhya_core::Error::Io(format!("Failed to create index: {}", e)))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tasks_domain ON tasks(domain)",
+            [],
+        )
+        .map_err(|e| bodhya_core::Error::Io(format!("Failed to create index: {}", e)))?;
 
         Ok(())
     }
 
     /// Save a session
     pub fn save_session(&self, session: &Session) -> Result<()> {
-        self.conn
+        self.conn()
             .execute(
                 "INSERT OR REPLACE INTO sessions (id, started_at, ended_at, metadata)
                  VALUES (?1, ?2, ?3, ?4)",
@@ -128,8 +171,8 @@ impl SqliteStorage {
 
     /// Get a session by ID
     pub fn get_session(&self, session_id: &str) -> Result<Option<Session>> {
-        let mut stmt = self
-            .conn
+        let conn = self.conn();
+        let mut stmt = conn
             .prepare("SELECT id, started_at, ended_at, metadata FROM sessions WHERE id = ?1")
             .map_err(|e| bodhya_core::Error::Io(format!("Failed to prepare query: {}", e)))?;
 
@@ -159,8 +202,8 @@ impl SqliteStorage {
 
     /// List all sessions (most recent first)
     pub fn list_sessions(&self, limit: usize) -> Result<Vec<Session>> {
-        let mut stmt = self
-            .conn
+        let conn = self.conn();
+        let mut stmt = conn
             .prepare(
                 "SELECT id, started_at, ended_at, metadata FROM sessions
                  ORDER BY started_at DESC LIMIT ?1",
@@ -194,7 +237,7 @@ impl SqliteStorage {
 
     /// Save a task record
     pub fn save_task(&self, task: &TaskRecord) -> Result<()> {
-        self.conn
+        self.conn()
             .execute(
                 "INSERT OR REPLACE INTO tasks
                  (id, session_id, domain, description, agent_id, status,
@@ -220,8 +263,8 @@ impl SqliteStorage {
 
     /// Get a task by ID
     pub fn get_task(&self, task_id: &str) -> Result<Option<TaskRecord>> {
-        let mut stmt = self
-            .conn
+        let conn = self.conn();
+        let mut stmt = conn
             .prepare(
                 "SELECT id, session_id, domain, description, agent_id, status,
                         started_at, completed_at, result, error
@@ -230,30 +273,7 @@ impl SqliteStorage {
             .map_err(|e| bodhya_core::Error::Io(format!("Failed to prepare query: {}", e)))?;
 
         let task = stmt
-            .query_row(params![task_id], |row| {
-                let started_str: String = row.get(6)?;
-                let completed_str: Option<String> = row.get(7)?;
-                let status_str: String = row.get(5)?;
-
-                Ok(TaskRecord {
-                    id: row.get(0)?,
-                    session_id: row.get(1)?,
-                    domain: row.get(2)?,
-                    description: row.get(3)?,
-                    agent_id: row.get(4)?,
-                    status: TaskStatus::parse(&status_str).unwrap_or(TaskStatus::Failed),
-                    started_at: chrono::DateTime::parse_from_rfc3339(&started_str)
-                        .unwrap()
-                        .with_timezone(&chrono::Utc),
-                    completed_at: completed_str.and_then(|s| {
-                        chrono::DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&chrono::Utc))
-                    }),
-                    result: row.get(8)?,
-                    error: row.get(9)?,
-                })
-            })
+            .query_row(params![task_id], task_from_row)
             .optional()
             .map_err(|e| bodhya_core::Error::Io(format!("Failed to query task: {}", e)))?;
 
@@ -262,8 +282,8 @@ impl SqliteStorage {
 
     /// List tasks for a session
     pub fn list_tasks_for_session(&self, session_id: &str) -> Result<Vec<TaskRecord>> {
-        let mut stmt = self
-            .conn
+        let conn = self.conn();
+        let mut stmt = conn
             .prepare(
                 "SELECT id, session_id, domain, description, agent_id, status,
                         started_at, completed_at, result, error
@@ -273,30 +293,7 @@ impl SqliteStorage {
             .map_err(|e| bodhya_core::Error::Io(format!("Failed to prepare query: {}", e)))?;
 
         let tasks = stmt
-            .query_map(params![session_id], |row| {
-                let started_str: String = row.get(6)?;
-                let completed_str: Option<String> = row.get(7)?;
-                let status_str: String = row.get(5)?;
-
-                Ok(TaskRecord {
-                    id: row.get(0)?,
-                    session_id: row.get(1)?,
-                    domain: row.get(2)?,
-                    description: row.get(3)?,
-                    agent_id: row.get(4)?,
-                    status: TaskStatus::parse(&status_str).unwrap_or(TaskStatus::Failed),
-                    started_at: chrono::DateTime::parse_from_rfc3339(&started_str)
-                        .unwrap()
-                        .with_timezone(&chrono::Utc),
-                    completed_at: completed_str.and_then(|s| {
-                        chrono::DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&chrono::Utc))
-                    }),
-                    result: row.get(8)?,
-                    error: row.get(9)?,
-                })
-            })
+            .query_map(params![session_id], task_from_row)
             .map_err(|e| bodhya_core::Error::Io(format!("Failed to query tasks: {}", e)))?
             .collect::<std::result::Result<Vec<_>, _>>()
             .map_err(|e| bodhya_core::Error::Io(format!("Failed to collect tasks: {}", e)))?;
@@ -306,7 +303,7 @@ impl SqliteStorage {
 
     /// Save quality metrics
     pub fn save_metrics(&self, metrics: &QualityMetrics) -> Result<()> {
-        self.conn
+        self.conn()
             .execute(
                 "INSERT OR REPLACE INTO quality_metrics
                  (task_id, quality_score, iterations, tokens_used,
@@ -327,10 +324,67 @@ impl SqliteStorage {
         Ok(())
     }
 
+    /// Get a cached plan (serialized JSON) by key
+    pub fn get_cached_plan(&self, key: &str) -> Result<Option<String>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT plan FROM plan_cache WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| bodhya_core::Error::Io(format!("Failed to get cached plan: {}", e)))
+    }
+
+    /// Store a plan (serialized JSON) under `key`, replacing any existing entry
+    pub fn put_cached_plan(&self, key: &str, plan: &str) -> Result<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR REPLACE INTO plan_cache (key, plan, created_at) VALUES (?1, ?2, ?3)",
+            params![key, plan, chrono::Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| bodhya_core::Error::Io(format!("Failed to cache plan: {}", e)))?;
+        Ok(())
+    }
+
+    /// Get the checkpoint (serialized JSON) of a task's run
+    pub fn get_checkpoint(&self, task_id: &str) -> Result<Option<String>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT checkpoint FROM run_checkpoints WHERE task_id = ?1",
+            params![task_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| bodhya_core::Error::Io(format!("Failed to get checkpoint: {}", e)))
+    }
+
+    /// Store a task's checkpoint (serialized JSON), replacing any earlier one
+    pub fn put_checkpoint(&self, task_id: &str, checkpoint: &str) -> Result<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR REPLACE INTO run_checkpoints (task_id, checkpoint, updated_at) VALUES (?1, ?2, ?3)",
+            params![task_id, checkpoint, chrono::Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| bodhya_core::Error::Io(format!("Failed to save checkpoint: {}", e)))?;
+        Ok(())
+    }
+
+    /// Remove a task's checkpoint, if it has one
+    pub fn delete_checkpoint(&self, task_id: &str) -> Result<()> {
+        let conn = self.conn();
+        conn.execute(
+            "DELETE FROM run_checkpoints WHERE task_id = ?1",
+            params![task_id],
+        )
+        .map_err(|e| bodhya_core::Error::Io(format!("Failed to delete checkpoint: {}", e)))?;
+        Ok(())
+    }
+
     /// Get quality metrics for a task
     pub fn get_metrics(&self, task_id: &str) -> Result<Option<QualityMetrics>> {
-        let mut stmt = self
-            .conn
+        let conn = self.conn();
+        let mut stmt = conn
             .prepare(
                 "SELECT task_id, quality_score, iterations, tokens_used,
                         execution_time_ms, custom_metrics, recorded_at
@@ -362,8 +416,8 @@ impl SqliteStorage {
 
     /// Get aggregate statistics by domain
     pub fn get_domain_stats(&self, domain: &str) -> Result<DomainStats> {
-        let mut stmt = self
-            .conn
+        let conn = self.conn();
+        let mut stmt = conn
             .prepare(
                 "SELECT COUNT(*),
                         COALESCE(SUM(CASE WHEN status = 'success' THEN 1 ELSE 0 END), 0),
@@ -385,6 +439,264 @@ impl SqliteStorage {
 
         Ok(stats)
     }
+
+    /// Average quality score per time bucket for a domain, oldest bucket first
+    ///
+    /// Buckets are keyed on when metrics were recorded. Metrics without a
+    /// quality score are ignored, and buckets with no scored metrics are omitted
+    /// rather than reported as zero.
+    pub fn quality_trend(
+        &self,
+        domain: &str,
+        bucket: TimeBucket,
+    ) -> Result<Vec<(chrono::DateTime<chrono::Utc>, f64)>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare(
+                "SELECT m.recorded_at, m.quality_score
+                 FROM quality_metrics m
+                 JOIN tasks t ON m.task_id = t.id
+                 WHERE t.domain = ?1 AND m.quality_score IS NOT NULL",
+            )
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to prepare query: {}", e)))?;
+
+        let rows = stmt
+            .query_map(params![domain], |row| {
+                let recorded_str: String = row.get(0)?;
+                let score: f64 = row.get(1)?;
+                Ok((recorded_str, score))
+            })
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to query metrics: {}", e)))?;
+
+        // bucket start -> (sum, count)
+        let mut buckets: std::collections::BTreeMap<_, (f64, usize)> =
+            std::collections::BTreeMap::new();
+        for row in rows {
+            let (recorded_str, score) =
+                row.map_err(|e| bodhya_core::Error::Io(format!("Failed to read metrics: {}", e)))?;
+            let recorded_at = chrono::DateTime::parse_from_rfc3339(&recorded_str)
+                .map_err(|e| {
+                    bodhya_core::Error::Io(format!("Invalid timestamp '{}': {}", recorded_str, e))
+                })?
+                .with_timezone(&chrono::Utc);
+
+            let entry = buckets.entry(bucket.start_of(recorded_at)).or_default();
+            entry.0 += score;
+            entry.1 += 1;
+        }
+
+        Ok(buckets
+            .into_iter()
+            .map(|(start, (sum, count))| (start, sum / count as f64))
+            .collect())
+    }
+
+    /// Delete sessions started before `cutoff`, along with their tasks and metrics
+    ///
+    /// Deletes run in a single transaction so the tables stay consistent, and the
+    /// database file is vacuumed afterwards to reclaim disk space.
+    pub fn prune_sessions_before(
+        &self,
+        cutoff: chrono::DateTime<chrono::Utc>,
+    ) -> Result<PruneStats> {
+        let cutoff = cutoff.to_rfc3339();
+        let mut conn = self.conn();
+
+        let tx = conn
+            .transaction()
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to start transaction: {}", e)))?;
+
+        let metrics_removed = tx
+            .execute(
+                "DELETE FROM quality_metrics WHERE task_id IN (
+                    SELECT t.id FROM tasks t
+                    JOIN sessions s ON t.session_id = s.id
+                    WHERE s.started_at < ?1
+                )",
+                params![cutoff],
+            )
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to prune metrics: {}", e)))?;
+
+        let tasks_removed = tx
+            .execute(
+                "DELETE FROM tasks WHERE session_id IN (
+                    SELECT id FROM sessions WHERE started_at < ?1
+                )",
+                params![cutoff],
+            )
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to prune tasks: {}", e)))?;
+
+        let sessions_removed = tx
+            .execute(
+                "DELETE FROM sessions WHERE started_at < ?1",
+                params![cutoff],
+            )
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to prune sessions: {}", e)))?;
+
+        tx.commit()
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to commit prune: {}", e)))?;
+
+        // VACUUM cannot run inside a transaction
+        conn.execute("VACUUM", [])
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to vacuum database: {}", e)))?;
+
+        Ok(PruneStats {
+            sessions_removed,
+            tasks_removed,
+            metrics_removed,
+        })
+    }
+
+    /// Stream task records matching `filter` to `writer` as a JSON array
+    ///
+    /// Returns the number of records written.
+    pub fn export_tasks_json(&self, mut writer: impl Write, filter: &TaskFilter) -> Result<usize> {
+        let mut count = 0;
+        writer.write_all(b"[")?;
+        self.for_each_task(filter, |task| {
+            if count > 0 {
+                writer.write_all(b",")?;
+            }
+            writer.write_all(b"\n  ")?;
+            serde_json::to_writer(&mut writer, task)?;
+            count += 1;
+            Ok(())
+        })?;
+        writer.write_all(if count > 0 { b"\n]\n" } else { b"]\n" })?;
+        writer.flush()?;
+        Ok(count)
+    }
+
+    /// Stream task records matching `filter` to `writer` as CSV with a header row
+    ///
+    /// Returns the number of records written (excluding the header).
+    pub fn export_tasks_csv(&self, mut writer: impl Write, filter: &TaskFilter) -> Result<usize> {
+        let mut count = 0;
+        writeln!(writer, "{}", TASK_CSV_HEADER.join(","))?;
+        self.for_each_task(filter, |task| {
+            let completed_at = task.completed_at.map(|dt| dt.to_rfc3339());
+            let started_at = task.started_at.to_rfc3339();
+            let fields = [
+                task.id.as_str(),
+                task.session_id.as_str(),
+                task.domain.as_str(),
+                task.description.as_str(),
+                task.agent_id.as_str(),
+                task.status.as_str(),
+                started_at.as_str(),
+                completed_at.as_deref().unwrap_or(""),
+                task.result.as_deref().unwrap_or(""),
+                task.error.as_deref().unwrap_or(""),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            writeln!(writer, "{}", row.join(","))?;
+            count += 1;
+            Ok(())
+        })?;
+        writer.flush()?;
+        Ok(count)
+    }
+
+    /// Run `f` on each task matching `filter`, oldest first, without buffering all rows
+    fn for_each_task(
+        &self,
+        filter: &TaskFilter,
+        mut f: impl FnMut(&TaskRecord) -> Result<()>,
+    ) -> Result<()> {
+        let mut sql = String::from(
+            "SELECT id, session_id, domain, description, agent_id, status,
+                    started_at, completed_at, result, error
+             FROM tasks WHERE 1 = 1",
+        );
+        let mut values: Vec<String> = Vec::new();
+        if let Some(ref domain) = filter.domain {
+            values.push(domain.clone());
+            sql.push_str(&format!(" AND domain = ?{}", values.len()));
+        }
+        if let Some(since) = filter.since {
+            values.push(since.to_rfc3339());
+            sql.push_str(&format!(" AND started_at >= ?{}", values.len()));
+        }
+        if let Some(until) = filter.until {
+            values.push(until.to_rfc3339());
+            sql.push_str(&format!(" AND started_at < ?{}", values.len()));
+        }
+        sql.push_str(" ORDER BY started_at ASC");
+
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to prepare query: {}", e)))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(values.iter()), task_from_row)
+            .map_err(|e| bodhya_core::Error::Io(format!("Failed to query tasks: {}", e)))?;
+
+        for row in rows {
+            let task =
+                row.map_err(|e| bodhya_core::Error::Io(format!("Failed to read task: {}", e)))?;
+            f(&task)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Column order for CSV task exports
+pub const TASK_CSV_HEADER: [&str; 10] = [
+    "id",
+    "session_id",
+    "domain",
+    "description",
+    "agent_id",
+    "status",
+    "started_at",
+    "completed_at",
+    "result",
+    "error",
+];
+
+/// Quote a CSV field if it contains a delimiter, quote, or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Map a `tasks` row (in standard column order) to a `TaskRecord`
+fn task_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<TaskRecord> {
+    let started_str: String = row.get(6)?;
+    let completed_str: Option<String> = row.get(7)?;
+    let status_str: String = row.get(5)?;
+
+    Ok(TaskRecord {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        domain: row.get(2)?,
+        description: row.get(3)?,
+        agent_id: row.get(4)?,
+        status: TaskStatus::parse(&status_str).unwrap_or(TaskStatus::Failed),
+        started_at: chrono::DateTime::parse_from_rfc3339(&started_str)
+            .unwrap()
+            .with_timezone(&chrono::Utc),
+        completed_at: completed_str.and_then(|s| {
+            chrono::DateTime::parse_from_rfc3339(&s)
+                .ok()
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+        }),
+        result: row.get(8)?,
+        error: row.get(9)?,
+    })
+}
+
+/// Counts of records removed by a prune
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PruneStats {
+    pub sessions_removed: usize,
+    pub tasks_removed: usize,
+    pub metrics_removed: usize,
 }
 
 /// Statistics for a specific domain
@@ -415,7 +727,7 @@ mod tests {
     fn test_storage_creation() {
         let storage = SqliteStorage::in_memory().unwrap();
         // Storage created successfully
-        assert!(storage.conn.is_autocommit());
+        assert!(storage.conn().is_autocommit());
     }
 
     #[test]
@@ -577,4 +889,324 @@ mod tests {
         assert_eq!(retrieved.status, TaskStatus::Success);
         assert!(retrieved.result.is_some());
     }
+
+    #[test]
+    fn test_storage_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SqliteStorage>();
+    }
+
+    #[test]
+    fn test_file_database_uses_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = SqliteStorage::new(dir.path().join("history.db")).unwrap();
+
+        let mode: String = storage
+            .conn()
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode.to_lowercase(), "wal");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_writers() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage =
+            std::sync::Arc::new(SqliteStorage::new(dir.path().join("history.db")).unwrap());
+        let session = Session::new();
+        storage.save_session(&session).unwrap();
+
+        let mut handles = Vec::new();
+        for writer in 0..8 {
+            let storage = storage.clone();
+            let session_id = session.id.clone();
+            handles.push(tokio::task::spawn_blocking(move || {
+                for i in 0..10 {
+                    let mut task = TaskRecord::new(
+                        &session_id,
+                        "code",
+                        format!("writer {} task {}", writer, i),
+                        "code-agent",
+                    );
+                    task.mark_success("done");
+                    storage.save_task(&task).unwrap();
+                    storage
+                        .save_metrics(&QualityMetrics::new(&task.id).with_quality_score(0.5))
+                        .unwrap();
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let tasks = storage.list_tasks_for_session(&session.id).unwrap();
+        assert_eq!(tasks.len(), 80);
+
+        let stats = storage.get_domain_stats("code").unwrap();
+        assert_eq!(stats.total_tasks, 80);
+        assert_eq!(stats.successful_tasks, 80);
+    }
+
+    #[test]
+    fn test_prune_sessions_before() {
+        let storage = SqliteStorage::in_memory().unwrap();
+
+        let mut old_session = Session::new();
+        old_session.started_at = chrono::Utc::now() - chrono::Duration::days(30);
+        storage.save_session(&old_session).unwrap();
+        let old_task = TaskRecord::new(&old_session.id, "code", "Old task", "code-agent");
+        storage.save_task(&old_task).unwrap();
+        storage
+            .save_metrics(&QualityMetrics::new(&old_task.id))
+            .unwrap();
+
+        let recent_session = Session::new();
+        storage.save_session(&recent_session).unwrap();
+        let recent_task = TaskRecord::new(&recent_session.id, "code", "Recent task", "code-agent");
+        storage.save_task(&recent_task).unwrap();
+        storage
+            .save_metrics(&QualityMetrics::new(&recent_task.id))
+            .unwrap();
+
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(7);
+        let stats = storage.prune_sessions_before(cutoff).unwrap();
+        assert_eq!(
+            stats,
+            PruneStats {
+                sessions_removed: 1,
+                tasks_removed: 1,
+                metrics_removed: 1,
+            }
+        );
+
+        assert!(storage.get_session(&old_session.id).unwrap().is_none());
+        assert!(storage.get_task(&old_task.id).unwrap().is_none());
+        assert!(storage.get_metrics(&old_task.id).unwrap().is_none());
+
+        assert!(storage.get_session(&recent_session.id).unwrap().is_some());
+        assert!(storage.get_task(&recent_task.id).unwrap().is_some());
+        assert!(storage.get_metrics(&recent_task.id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_prune_sessions_before_nothing_to_prune() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        storage.save_session(&Session::new()).unwrap();
+
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(7);
+        let stats = storage.prune_sessions_before(cutoff).unwrap();
+        assert_eq!(stats, PruneStats::default());
+        assert_eq!(storage.list_sessions(10).unwrap().len(), 1);
+    }
+
+    /// Minimal RFC 4180 parser used to check exported CSV round-trips
+    fn parse_csv(input: &str) -> Vec<Vec<String>> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = input.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match (c, in_quotes) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                ('"', _) => in_quotes = !in_quotes,
+                (',', false) => row.push(std::mem::take(&mut field)),
+                ('\n', false) => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                _ => field.push(c),
+            }
+        }
+        rows
+    }
+
+    fn export_fixture() -> (SqliteStorage, TaskRecord, TaskRecord) {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let session = Session::new();
+        storage.save_session(&session).unwrap();
+
+        let mut code_task = TaskRecord::new(
+            &session.id,
+            "code",
+            "Write a parser, with \"quoted\" input",
+            "code-agent",
+        );
+        code_task.mark_success("line one\nline two");
+        storage.save_task(&code_task).unwrap();
+
+        let mut mail_task = TaskRecord::new(&session.id, "mail", "Draft a reply", "mail-agent");
+        mail_task.started_at = code_task.started_at + chrono::Duration::seconds(1);
+        mail_task.mark_failed("model unavailable");
+        storage.save_task(&mail_task).unwrap();
+
+        (storage, code_task, mail_task)
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_export_tasks_csv_round_trip() {
+        let (storage, code_task, mail_task) = export_fixture();
+
+        let mut buffer = Vec::new();
+        let count = storage
+            .export_tasks_csv(&mut buffer, &TaskFilter::new())
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let rows = parse_csv(&String::from_utf8(buffer).unwrap());
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], TASK_CSV_HEADER);
+        assert_eq!(rows[1][0], code_task.id);
+        assert_eq!(rows[1][3], code_task.description);
+        assert_eq!(rows[1][5], "success");
+        assert_eq!(rows[1][8], "line one\nline two");
+        assert_eq!(rows[2][0], mail_task.id);
+        assert_eq!(rows[2][5], "failed");
+        assert_eq!(rows[2][9], "model unavailable");
+    }
+
+    #[test]
+    fn test_export_tasks_json_round_trip() {
+        let (storage, code_task, mail_task) = export_fixture();
+
+        let mut buffer = Vec::new();
+        let count = storage
+            .export_tasks_json(&mut buffer, &TaskFilter::new())
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let tasks: Vec<TaskRecord> = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].id, code_task.id);
+        assert_eq!(tasks[0].result, code_task.result);
+        assert_eq!(tasks[1].id, mail_task.id);
+        assert_eq!(tasks[1].status, TaskStatus::Failed);
+    }
+
+    #[test]
+    fn test_export_tasks_filtered() {
+        let (storage, _, mail_task) = export_fixture();
+
+        let mut buffer = Vec::new();
+        let filter = TaskFilter::new().with_domain("mail");
+        assert_eq!(storage.export_tasks_json(&mut buffer, &filter).unwrap(), 1);
+        let tasks: Vec<TaskRecord> = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(tasks[0].id, mail_task.id);
+
+        let mut buffer = Vec::new();
+        let filter = TaskFilter::new().with_since(mail_task.started_at + chrono::Duration::days(1));
+        assert_eq!(storage.export_tasks_json(&mut buffer, &filter).unwrap(), 0);
+        let tasks: Vec<TaskRecord> = serde_json::from_slice(&buffer).unwrap();
+        assert!(tasks.is_empty());
+    }
+
+    #[test]
+    fn test_quality_trend_daily() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let session = Session::new();
+        storage.save_session(&session).unwrap();
+
+        let day1 = chrono::DateTime::parse_from_rfc3339("2024-03-04T09:00:00+00:00")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let day2 = day1 + chrono::Duration::days(1);
+
+        let scored = [
+            (day1, Some(60.0)),
+            (day1 + chrono::Duration::hours(5), Some(80.0)),
+            (day2, Some(90.0)),
+            // Unscored metrics are ignored
+            (day2 + chrono::Duration::hours(1), None),
+        ];
+        for (recorded_at, score) in scored {
+            let task = TaskRecord::new(&session.id, "code", "Task", "code-agent");
+            storage.save_task(&task).unwrap();
+            let mut metrics = QualityMetrics::new(&task.id);
+            metrics.quality_score = score;
+            metrics.recorded_at = recorded_at;
+            storage.save_metrics(&metrics).unwrap();
+        }
+
+        // Metrics from other domains don't leak into the trend
+        let mail_task = TaskRecord::new(&session.id, "mail", "Task", "mail-agent");
+        storage.save_task(&mail_task).unwrap();
+        let mut mail_metrics = QualityMetrics::new(&mail_task.id).with_quality_score(10.0);
+        mail_metrics.recorded_at = day1;
+        storage.save_metrics(&mail_metrics).unwrap();
+
+        let trend = storage.quality_trend("code", TimeBucket::Day).unwrap();
+        assert_eq!(trend.len(), 2);
+        assert_eq!(trend[0].0, TimeBucket::Day.start_of(day1));
+        assert!((trend[0].1 - 70.0).abs() < f64::EPSILON);
+        assert_eq!(trend[1].0, TimeBucket::Day.start_of(day2));
+        assert!((trend[1].1 - 90.0).abs() < f64::EPSILON);
+
+        // Both days fall in the same week
+        let weekly = storage.quality_trend("code", TimeBucket::Week).unwrap();
+        assert_eq!(weekly.len(), 1);
+        assert!((weekly[0].1 - 230.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_quality_trend_no_data() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        let trend = storage.quality_trend("code", TimeBucket::Day).unwrap();
+        assert!(trend.is_empty());
+    }
+
+    #[test]
+    fn test_plan_cache_round_trip() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        assert_eq!(storage.get_cached_plan("abc").unwrap(), None);
+
+        storage
+            .put_cached_plan("abc", r#"{"purpose":"v1"}"#)
+            .unwrap();
+        assert_eq!(
+            storage.get_cached_plan("abc").unwrap().as_deref(),
+            Some(r#"{"purpose":"v1"}"#)
+        );
+
+        storage
+            .put_cached_plan("abc", r#"{"purpose":"v2"}"#)
+            .unwrap();
+        assert_eq!(
+            storage.get_cached_plan("abc").unwrap().as_deref(),
+            Some(r#"{"purpose":"v2"}"#)
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let storage = SqliteStorage::in_memory().unwrap();
+        assert_eq!(storage.get_checkpoint("task-1").unwrap(), None);
+
+        storage
+            .put_checkpoint("task-1", r#"{"iteration":1}"#)
+            .unwrap();
+        storage
+            .put_checkpoint("task-1", r#"{"iteration":2}"#)
+            .unwrap();
+        assert_eq!(
+            storage.get_checkpoint("task-1").unwrap().as_deref(),
+            Some(r#"{"iteration":2}"#)
+        );
+
+        storage.delete_checkpoint("task-1").unwrap();
+        assert_eq!(storage.get_checkpoint("task-1").unwrap(), None);
+    }
 }
@@ -0,0 +1,211 @@
+//! Atomic file writes
+//!
+//! Writing straight to the target leaves a truncated file if the process dies
+//! or the write fails part way. Content is written to a temporary file in
+//! the target's directory instead and renamed over the target only once it
+//! is complete, so readers see either the old file or the new one.
+//!
+//! A symlinked target is resolved first, so the file it points to is
+//! replaced rather than the link, and the replacement keeps the original
+//! file's permissions.
+
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncRead, AsyncWriteExt};
+
+/// Atomically replace `path` with `content`
+pub(crate) async fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    write_atomic_from(path, content).await.map(|_| ())
+}
+
+/// Atomically replace `path` with everything read from `reader`
+///
+/// Returns the number of bytes written. If reading or writing fails, the
+/// temp file is removed and `path` is left as it was.
+pub(crate) async fn write_atomic_from(
+    path: &Path,
+    mut reader: impl AsyncRead + Unpin,
+) -> std::io::Result<u64> {
+    let target = resolve_target(path).await;
+    let path = target.as_path();
+    let temp = temp_path(path);
+    let permissions = tokio::fs::metadata(path)
+        .await
+        .ok()
+        .map(|metadata| metadata.permissions());
+
+    let written = async {
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp)
+            .await?;
+        if let Some(permissions) = permissions {
+            file.set_permissions(permissions).await?;
+        }
+        let written = tokio::io::copy(&mut reader, &mut file).await?;
+        file.flush().await?;
+        file.sync_all().await?;
+        Ok::<_, std::io::Error>(written)
+    }
+    .await;
+
+    let written = match written {
+        Ok(written) => written,
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&temp).await;
+            return Err(e);
+        }
+    };
+
+    if let Err(e) = tokio::fs::rename(&temp, path).await {
+        // e.g., the target is on a filesystem that does not support rename
+        // over an existing file; fall back to copying the complete content
+        tracing::warn!(
+            path = %path.display(),
+            error = %e,
+            "Atomic rename failed, writing file directly"
+        );
+        let result = tokio::fs::copy(&temp, path).await;
+        let _ = tokio::fs::remove_file(&temp).await;
+        result?;
+    }
+
+    Ok(written)
+}
+
+/// The file `path` refers to once symlinks are followed
+///
+/// A path that does not exist yet (or a dangling link) is used as given.
+async fn resolve_target(path: &Path) -> PathBuf {
+    tokio::fs::canonicalize(path)
+        .await
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Hidden temp file next to `path` (same directory, so the same filesystem)
+///
+/// The random suffix keeps concurrent writers, in this process or another,
+/// from sharing a temp file.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let unique = format!(
+        ".{}.{}.{}.tmp",
+        name,
+        std::process::id(),
+        uuid::Uuid::new_v4().simple()
+    );
+    path.with_file_name(unique)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tempfile::TempDir;
+    use tokio::io::ReadBuf;
+
+    /// Reader that yields `remaining` bytes, then fails
+    struct FailingReader {
+        remaining: usize,
+    }
+
+    impl AsyncRead for FailingReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            if self.remaining == 0 {
+                return Poll::Ready(Err(std::io::Error::other("disk unplugged")));
+            }
+            let n = self.remaining.min(buf.remaining());
+            buf.put_slice(&vec![b'x'; n]);
+            self.remaining -= n;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn dir_entries(dir: &Path) -> Vec<String> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_large_write_is_complete() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("model.gguf");
+        let content: Vec<u8> = (0..8 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+
+        write_atomic(&path, &content).await.unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), content);
+        assert_eq!(dir_entries(temp_dir.path()), vec!["model.gguf"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_leaves_no_partial_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let new_path = temp_dir.path().join("new.rs");
+        let existing_path = temp_dir.path().join("existing.rs");
+        std::fs::write(&existing_path, "original").unwrap();
+
+        let reader = FailingReader {
+            remaining: 64 * 1024,
+        };
+        assert!(write_atomic_from(&new_path, reader).await.is_err());
+        let reader = FailingReader {
+            remaining: 64 * 1024,
+        };
+        assert!(write_atomic_from(&existing_path, reader).await.is_err());
+
+        assert!(!new_path.exists());
+        assert_eq!(std::fs::read_to_string(&existing_path).unwrap(), "original");
+        assert_eq!(dir_entries(temp_dir.path()), vec!["existing.rs"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_write_through_symlink_replaces_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("real.rs");
+        let link = temp_dir.path().join("link.rs");
+        std::fs::write(&target, "old").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_atomic(&link, b"new").await.unwrap();
+
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "new");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_write_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("build.sh");
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        write_atomic(&path, b"#!/bin/sh\necho hi\n").await.unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+
+    #[test]
+    fn test_temp_paths_are_unique() {
+        let path = Path::new("/tmp/file.rs");
+        assert_ne!(temp_path(path), temp_path(path));
+    }
+}
@@ -0,0 +1,70 @@
+/// Backups taken before destructive writes and edits
+///
+/// The original file is copied to `<path>.bak` so a bad write (e.g., a
+/// refinement in the agentic retry loop) can be rolled back.
+use bodhya_core::{Error, Result};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Path of the backup kept for `path`
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = OsString::from(path.as_os_str());
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
+/// Copy `path` to `backup`, returning false if there is nothing to back up
+pub(crate) async fn create_backup(path: &Path, backup: &Path) -> Result<bool> {
+    if !tokio::fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(false);
+    }
+
+    tokio::fs::copy(path, backup)
+        .await
+        .map_err(|e| Error::Tool(format!("Failed to back up '{}': {}", path.display(), e)))?;
+    Ok(true)
+}
+
+/// Move `backup` back over `path`
+pub(crate) async fn restore_backup(path: &Path, backup: &Path) -> Result<()> {
+    tokio::fs::rename(backup, path).await.map_err(|e| {
+        Error::Tool(format!(
+            "Failed to restore '{}' from '{}': {}",
+            path.display(),
+            backup.display(),
+            e
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_backup_path() {
+        assert_eq!(
+            backup_path(Path::new("src/lib.rs")),
+            PathBuf::from("src/lib.rs.bak")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("main.rs");
+        let backup = backup_path(&path);
+
+        assert!(!create_backup(&path, &backup).await.unwrap());
+        assert!(!backup.exists());
+
+        std::fs::write(&path, "original").unwrap();
+        assert!(create_backup(&path, &backup).await.unwrap());
+        std::fs::write(&path, "clobbered").unwrap();
+
+        restore_backup(&path, &backup).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+        assert!(!backup.exists());
+    }
+}
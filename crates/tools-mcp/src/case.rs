@@ -0,0 +1,208 @@
+//! Identifier case styles
+//!
+//! Splits identifiers into words and renders them in each common case
+//! style, so a rename of `my_func` can also rewrite `MyFunc`, `myFunc`,
+//! `MY_FUNC`, and `my-func`.
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+/// How the words of an identifier are joined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStyle {
+    /// `my_func`
+    Snake,
+    /// `MY_FUNC`
+    ScreamingSnake,
+    /// `my-func`
+    Kebab,
+    /// `myFunc`
+    Camel,
+    /// `MyFunc`
+    Pascal,
+}
+
+impl CaseStyle {
+    /// Every case style
+    pub const ALL: [CaseStyle; 5] = [
+        CaseStyle::Snake,
+        CaseStyle::ScreamingSnake,
+        CaseStyle::Kebab,
+        CaseStyle::Camel,
+        CaseStyle::Pascal,
+    ];
+
+    /// Rewrite `ident` (in any style) in this style
+    pub fn convert(self, ident: &str) -> String {
+        self.render(&split_words(ident))
+    }
+
+    /// Join lowercase `words` in this style
+    fn render(self, words: &[String]) -> String {
+        match self {
+            CaseStyle::Snake => words.join("_"),
+            CaseStyle::ScreamingSnake => words.join("_").to_uppercase(),
+            CaseStyle::Kebab => words.join("-"),
+            CaseStyle::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    if i == 0 {
+                        word.clone()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+            CaseStyle::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+        }
+    }
+}
+
+/// Lowercase words of an identifier in any case style
+///
+/// Words are separated by `_`, `-`, a lowercase-to-uppercase change, or the
+/// end of an acronym (`HTTPServer` is `http`, `server`).
+pub fn split_words(ident: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in ident.split(['_', '-']).filter(|part| !part.is_empty()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut word = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            let prev = i.checked_sub(1).map(|p| chars[p]);
+            let next = chars.get(i + 1);
+            let boundary = c.is_uppercase()
+                && prev.is_some_and(|p| {
+                    p.is_lowercase()
+                        || p.is_ascii_digit()
+                        || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+                });
+            if boundary && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            word.extend(c.to_lowercase());
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words
+}
+
+/// Replace every whole-token occurrence of `old`, in any case style, with
+/// `new` in the same style
+///
+/// Returns the new text and the number of tokens replaced.
+pub(crate) fn rename_identifier(content: &str, old: &str, new: &str) -> (String, usize) {
+    let old_words = split_words(old);
+    let new_words = split_words(new);
+    if old_words.is_empty() || new_words.is_empty() {
+        return (content.to_string(), 0);
+    }
+
+    // Styles can coincide (single words are the same in snake, kebab, and
+    // camel case); the first style wins
+    let mut renames: Vec<(String, String)> = Vec::new();
+    for style in CaseStyle::ALL {
+        let from = style.render(&old_words);
+        if !renames.iter().any(|(seen, _)| *seen == from) {
+            renames.push((from, style.render(&new_words)));
+        }
+    }
+    // Longest first so `my-func` is not matched as `my`, then `func`
+    renames.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+
+    let alternatives: Vec<String> = renames
+        .iter()
+        .map(|(from, _)| regex::escape(from))
+        .collect();
+    let Ok(regex) = Regex::new(&format!(r"\b(?:{})\b", alternatives.join("|"))) else {
+        return (content.to_string(), 0);
+    };
+
+    let mut changes = 0;
+    let renamed = regex.replace_all(content, |caps: &Captures| {
+        changes += 1;
+        let from = &caps[0];
+        renames
+            .iter()
+            .find(|(candidate, _)| candidate == from)
+            .map(|(_, to)| to.clone())
+            .unwrap_or_else(|| from.to_string())
+    });
+    (renamed.into_owned(), changes)
+}
+
+/// Rewrite every whole-token occurrence of `ident` (exactly as written) in
+/// the `to` style
+///
+/// Returns the new text and the number of tokens converted.
+pub(crate) fn convert_identifier(content: &str, ident: &str, to: CaseStyle) -> (String, usize) {
+    let converted = to.convert(ident);
+    let Ok(regex) = Regex::new(&format!(r"\b{}\b", regex::escape(ident))) else {
+        return (content.to_string(), 0);
+    };
+    if ident.is_empty() || converted == ident {
+        return (content.to_string(), 0);
+    }
+
+    let changes = regex.find_iter(content).count();
+    let converted = regex.replace_all(content, regex::NoExpand(&converted));
+    (converted.into_owned(), changes)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("my_func"), vec!["my", "func"]);
+        assert_eq!(split_words("MyFunc"), vec!["my", "func"]);
+        assert_eq!(split_words("myFunc2Value"), vec!["my", "func2", "value"]);
+        assert_eq!(split_words("HTTPServer"), vec!["http", "server"]);
+        assert_eq!(split_words("MAX-SIZE"), vec!["max", "size"]);
+    }
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(
+            CaseStyle::Camel.convert("parse_http_header"),
+            "parseHttpHeader"
+        );
+        assert_eq!(
+            CaseStyle::Pascal.convert("parse-http-header"),
+            "ParseHttpHeader"
+        );
+        assert_eq!(
+            CaseStyle::ScreamingSnake.convert("parseHttpHeader"),
+            "PARSE_HTTP_HEADER"
+        );
+        assert_eq!(
+            CaseStyle::Kebab.convert("ParseHTTPHeader"),
+            "parse-http-header"
+        );
+    }
+
+    #[test]
+    fn test_rename_identifier_preserves_styles() {
+        let content = "struct MyFunc;\nfn my_func() -> MyFunc { MyFunc }\nconst MY_FUNC: u8 = 1;\nlet x = my_func_extra;\n";
+
+        let (renamed, changes) = rename_identifier(content, "my_func", "load_config");
+
+        assert_eq!(changes, 5);
+        assert_eq!(
+            renamed,
+            "struct LoadConfig;\nfn load_config() -> LoadConfig { LoadConfig }\nconst LOAD_CONFIG: u8 = 1;\nlet x = my_func_extra;\n"
+        );
+    }
+}
@@ -3,15 +3,26 @@
 /// Provides precise file editing capabilities including:
 /// - String replacement
 /// - Line-based insertion/deletion
+/// - Case-style aware identifier renames and conversions
 /// - Patch application
 /// - Dry-run validation
+/// - Optional `<path>.bak` backup before modifying
+/// - Optional report of the lines changed, for review before approving
+use crate::case::{self, CaseStyle};
+use crate::line_changes::{changed_lines, ChangedLine};
+use crate::schema::object_schema;
+use crate::{atomic, backup, sandbox};
 use async_trait::async_trait;
 use bodhya_core::{Result, Tool, ToolRequest, ToolResponse};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use serde_json::json;
+use std::path::{Path, PathBuf};
 
 /// EditTool provides advanced file editing capabilities
-pub struct EditTool;
+pub struct EditTool {
+    /// Base directory for sandboxing (optional)
+    base_dir: Option<PathBuf>,
+}
 
 /// Edit operation types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +41,11 @@ pub enum EditOperation {
     DeleteLines { start: usize, end: usize },
     /// Apply a unified diff patch
     Patch { patch: String },
+    /// Rename an identifier in every case style it appears in
+    /// (`my_func` -> `new_name` also rewrites `MyFunc` -> `NewName`)
+    RenameIdentifier { old: String, new: String },
+    /// Rewrite an identifier (exactly as written) in another case style
+    ConvertCase { identifier: String, to: CaseStyle },
 }
 
 /// Edit result containing the modified content and metadata
@@ -40,21 +56,80 @@ pub struct EditResult {
     pub changes_made: usize,
     pub dry_run: bool,
     pub error: Option<String>,
+    /// Where the original content was backed up, if a backup was taken
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_path: Option<PathBuf>,
+    /// Lines the edit changes, if a report was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changed_lines: Option<Vec<ChangedLine>>,
+}
+
+/// Options for [`EditTool::edit`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditOptions {
+    /// Compute the result without writing the file
+    pub dry_run: bool,
+    /// Copy the original file to `<path>.bak` before writing the changes
+    pub backup: bool,
+    /// List each changed line with its text before and after
+    pub report_changes: bool,
+}
+
+impl EditOptions {
+    /// Compute the result without writing the file
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Back up the original file before writing the changes
+    pub fn with_backup(mut self) -> Self {
+        self.backup = true;
+        self
+    }
+
+    /// Report the changed lines in the result
+    pub fn with_changes_report(mut self) -> Self {
+        self.report_changes = true;
+        self
+    }
 }
 
 impl EditTool {
     pub fn new() -> Self {
-        Self
+        Self { base_dir: None }
     }
 
-    /// Perform edit operation with optional dry-run
+    /// Create an edit tool that only touches files under `base_dir`
+    pub fn with_base_dir(base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_dir: Some(base_dir.into()),
+        }
+    }
+
+    /// Resolve `path` against the base directory (if set)
+    fn resolve_path(&self, path: &Path) -> Result<PathBuf> {
+        match &self.base_dir {
+            Some(base) => sandbox::resolve_within(base, path),
+            None => Ok(path.to_path_buf()),
+        }
+    }
+
+    /// Perform edit operation, writing the file unless `options` asks for a dry run
     pub async fn edit(
         &self,
         path: impl AsRef<Path>,
         operation: EditOperation,
-        dry_run: bool,
+        options: EditOptions,
     ) -> Result<EditResult> {
-        let path = path.as_ref();
+        let EditOptions {
+            dry_run,
+            backup,
+            report_changes,
+        } = options;
+        let raw_path = path.as_ref();
+        let path = self.resolve_path(raw_path)?;
+        let path = path.as_path();
 
         // Read current content
         let original_content = tokio::fs::read_to_string(path)
@@ -74,21 +149,39 @@ impl EditTool {
                 self.apply_delete(&original_content, start, end)?
             }
             EditOperation::Patch { patch } => self.apply_patch(&original_content, &patch)?,
+            EditOperation::RenameIdentifier { old, new } => {
+                case::rename_identifier(&original_content, &old, &new)
+            }
+            EditOperation::ConvertCase { identifier, to } => {
+                case::convert_identifier(&original_content, &identifier, to)
+            }
         };
 
         // If not dry-run, write the changes
+        let mut backup_path = None;
         if !dry_run && changes_made > 0 {
-            tokio::fs::write(path, &modified_content)
+            if backup {
+                let target = self.resolve_path(&backup::backup_path(raw_path))?;
+                backup::create_backup(path, &target).await?;
+                backup_path = Some(target);
+            }
+
+            atomic::write_atomic(path, modified_content.as_bytes())
                 .await
                 .map_err(|e| bodhya_core::Error::Tool(format!("Failed to write file: {}", e)))?;
         }
 
+        let changed_lines =
+            report_changes.then(|| changed_lines(&original_content, &modified_content));
+
         Ok(EditResult {
             success: true,
             modified_content,
             changes_made,
             dry_run,
             error: None,
+            backup_path,
+            changed_lines,
         })
     }
 
@@ -237,13 +330,33 @@ impl Tool for EditTool {
     }
 
     fn description(&self) -> &'static str {
-        "Advanced file editing with replace, insert, delete, and patch operations"
+        "Advanced file editing with replace, insert, delete, patch, and identifier rename operations"
     }
 
     fn supported_operations(&self) -> Vec<String> {
         vec!["edit".to_string()]
     }
 
+    fn supports_dry_run(&self, operation: &str) -> bool {
+        operation == "edit"
+    }
+
+    fn parameter_schema(&self, operation: &str) -> serde_json::Value {
+        match operation {
+            "edit" => object_schema(
+                &[
+                    ("path", json!("string")),
+                    ("operation", json!("object")),
+                    ("dry_run", json!("boolean")),
+                    ("backup", json!("boolean")),
+                    ("report_changes", json!("boolean")),
+                ],
+                &["path", "operation"],
+            ),
+            _ => json!({ "type": "object" }),
+        }
+    }
+
     async fn execute(&self, request: ToolRequest) -> Result<ToolResponse> {
         let path = request
             .params
@@ -260,13 +373,36 @@ impl Tool for EditTool {
                     .map_err(|e| bodhya_core::Error::Tool(format!("Invalid operation: {}", e)))
             })?;
 
-        let dry_run = request
+        let dry_run = request.dry_run
+            || request
+                .params
+                .get("dry_run")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+
+        let backup = request
+            .params
+            .get("backup")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let report_changes = request
             .params
-            .get("dry_run")
+            .get("report_changes")
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
-        let result = self.edit(path, operation, dry_run).await?;
+        let result = self
+            .edit(
+                path,
+                operation,
+                EditOptions {
+                    dry_run,
+                    backup,
+                    report_changes,
+                },
+            )
+            .await?;
 
         let data = serde_json::to_value(result)
             .map_err(|e| bodhya_core::Error::Tool(format!("Failed to serialize result: {}", e)))?;
@@ -305,7 +441,10 @@ mod tests {
             count: None,
         };
 
-        let result = tool.edit(&path, operation, false).await.unwrap();
+        let result = tool
+            .edit(&path, operation, EditOptions::default())
+            .await
+            .unwrap();
 
         assert!(result.success);
         assert_eq!(result.changes_made, 2);
@@ -317,6 +456,59 @@ mod tests {
         assert_eq!(content, result.modified_content);
     }
 
+    #[tokio::test]
+    async fn test_replace_reports_changed_lines() {
+        let tool = EditTool::new();
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_file(
+            &temp_dir,
+            "test.txt",
+            "hello world\nunchanged\nhello rust\n",
+        )
+        .await;
+
+        let request = ToolRequest::new(
+            "edit",
+            "edit",
+            json!({
+                "path": path.to_str().unwrap(),
+                "operation": {"operation": "replace", "old": "hello", "new": "goodbye"},
+                "dry_run": true,
+                "report_changes": true
+            }),
+        );
+        let response = tool.execute(request).await.unwrap();
+        let result: EditResult = serde_json::from_value(response.data).unwrap();
+
+        assert_eq!(
+            result.changed_lines.unwrap(),
+            vec![
+                ChangedLine {
+                    line_number: 1,
+                    before: Some("hello world".to_string()),
+                    after: Some("goodbye world".to_string()),
+                },
+                ChangedLine {
+                    line_number: 3,
+                    before: Some("hello rust".to_string()),
+                    after: Some("goodbye rust".to_string()),
+                },
+            ]
+        );
+
+        // Without the flag no report is computed
+        let operation = EditOperation::Replace {
+            old: "hello".to_string(),
+            new: "goodbye".to_string(),
+            count: None,
+        };
+        let result = tool
+            .edit(&path, operation, EditOptions::default().dry_run())
+            .await
+            .unwrap();
+        assert!(result.changed_lines.is_none());
+    }
+
     #[tokio::test]
     async fn test_replace_with_count() {
         let tool = EditTool::new();
@@ -341,7 +533,10 @@ mod tests {
             content: "inserted line".to_string(),
         };
 
-        let result = tool.edit(&path, operation, false).await.unwrap();
+        let result = tool
+            .edit(&path, operation, EditOptions::default())
+            .await
+            .unwrap();
 
         assert!(result.success);
         assert_eq!(result.changes_made, 1);
@@ -359,7 +554,10 @@ mod tests {
 
         let operation = EditOperation::DeleteLines { start: 2, end: 3 };
 
-        let result = tool.edit(&path, operation, false).await.unwrap();
+        let result = tool
+            .edit(&path, operation, EditOptions::default())
+            .await
+            .unwrap();
 
         assert!(result.success);
         assert_eq!(result.changes_made, 2);
@@ -382,7 +580,10 @@ mod tests {
             count: None,
         };
 
-        let result = tool.edit(&path, operation, true).await.unwrap();
+        let result = tool
+            .edit(&path, operation, EditOptions::default().dry_run())
+            .await
+            .unwrap();
 
         assert!(result.success);
         assert!(result.dry_run);
@@ -393,6 +594,84 @@ mod tests {
         assert_eq!(content, original_content);
     }
 
+    #[tokio::test]
+    async fn test_request_dry_run_flag() {
+        let tool = EditTool::new();
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_file(&temp_dir, "test.txt", "hello world\n").await;
+
+        let request = ToolRequest::new(
+            "edit",
+            "edit",
+            serde_json::json!({
+                "path": path.to_str().unwrap(),
+                "operation": { "operation": "replace", "old": "hello", "new": "goodbye" }
+            }),
+        )
+        .with_dry_run(true);
+        let response = tool.execute(request).await.unwrap();
+
+        assert!(tool.supports_dry_run("edit"));
+        assert_eq!(response.data["dry_run"], true);
+        assert_eq!(response.data["modified_content"], "goodbye world\n");
+        let content = tokio::fs::read_to_string(&path).await.unwrap();
+        assert_eq!(content, "hello world\n");
+    }
+
+    #[tokio::test]
+    async fn test_edit_with_backup_keeps_original() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = EditTool::with_base_dir(temp_dir.path());
+        create_test_file(&temp_dir, "lib.rs", "fn old() {}\n").await;
+
+        let operation = EditOperation::Replace {
+            old: "old".to_string(),
+            new: "new".to_string(),
+            count: None,
+        };
+        let result = tool
+            .edit("lib.rs", operation, EditOptions::default().with_backup())
+            .await
+            .unwrap();
+
+        let backup = result.backup_path.unwrap();
+        assert!(backup.ends_with("lib.rs.bak"));
+        assert_eq!(
+            tokio::fs::read_to_string(&backup).await.unwrap(),
+            "fn old() {}\n"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(temp_dir.path().join("lib.rs"))
+                .await
+                .unwrap(),
+            "fn new() {}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_takes_no_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = EditTool::with_base_dir(temp_dir.path());
+        create_test_file(&temp_dir, "lib.rs", "fn old() {}\n").await;
+
+        let operation = EditOperation::Replace {
+            old: "old".to_string(),
+            new: "new".to_string(),
+            count: None,
+        };
+        let result = tool
+            .edit(
+                "lib.rs",
+                operation,
+                EditOptions::default().dry_run().with_backup(),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.backup_path.is_none());
+        assert!(!temp_dir.path().join("lib.rs.bak").exists());
+    }
+
     #[tokio::test]
     async fn test_insert_at_invalid_line() {
         let tool = EditTool::new();
@@ -444,4 +723,56 @@ mod tests {
         assert_eq!(changes, 0);
         assert_eq!(result, content);
     }
+
+    #[tokio::test]
+    async fn test_convert_case_snake_to_camel() {
+        let tool = EditTool::new();
+        let temp_dir = TempDir::new().unwrap();
+        let content = "function fetch_user(user_id) {\n  return api.get(user_id);\n}\nconst other_user_id = fetch_user(user_id);\n";
+        let path = create_test_file(&temp_dir, "user.js", content).await;
+
+        let request = ToolRequest::new(
+            "edit",
+            "edit",
+            json!({
+                "path": path.to_str().unwrap(),
+                "operation": { "operation": "convert_case", "identifier": "user_id", "to": "camel" }
+            }),
+        );
+        let response = tool.execute(request).await.unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.data["changes_made"], 3);
+        assert_eq!(
+            tokio::fs::read_to_string(&path).await.unwrap(),
+            "function fetch_user(userId) {\n  return api.get(userId);\n}\nconst other_user_id = fetch_user(userId);\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_identifier_across_styles() {
+        let tool = EditTool::new();
+        let temp_dir = TempDir::new().unwrap();
+        let path = create_test_file(
+            &temp_dir,
+            "lib.rs",
+            "pub struct MyFunc;\npub fn my_func() -> MyFunc {\n    MyFunc\n}\n",
+        )
+        .await;
+
+        let operation = EditOperation::RenameIdentifier {
+            old: "my_func".to_string(),
+            new: "build_widget".to_string(),
+        };
+        let result = tool
+            .edit(&path, operation, EditOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(result.changes_made, 4);
+        assert_eq!(
+            result.modified_content,
+            "pub struct BuildWidget;\npub fn build_widget() -> BuildWidget {\n    BuildWidget\n}\n"
+        );
+    }
 }
@@ -1,9 +1,14 @@
 /// Filesystem tool for file operations
 ///
 /// This module provides filesystem operations (read, write, list) as a Tool implementation.
+/// Writes can keep a `<path>.bak` backup of the previous content, which `restore` rolls back to.
+/// Writes are atomic: content goes to a temp file that is renamed over the target.
+use crate::schema::object_schema;
+use crate::{atomic, backup, sandbox};
 use async_trait::async_trait;
 use bodhya_core::{Result, Tool, ToolRequest, ToolResponse};
-use std::path::PathBuf;
+use serde_json::json;
+use std::path::{Path, PathBuf};
 
 /// Filesystem tool for file operations
 pub struct FilesystemTool {
@@ -28,59 +33,9 @@ impl FilesystemTool {
     fn resolve_path(&self, path: &str) -> Result<PathBuf> {
         let path = PathBuf::from(path);
 
-        if let Some(base) = &self.base_dir {
-            // Resolve relative to base directory
-            let resolved = if path.is_absolute() {
-                path
-            } else {
-                base.join(&path)
-            };
-
-            // Ensure the resolved path is within base_dir (security check)
-            let canonical_base = base.canonicalize().map_err(|e| {
-                bodhya_core::Error::Tool(format!("Failed to canonicalize base dir: {}", e))
-            })?;
-
-            // For non-existent paths, we can't canonicalize, so check the parent
-            let canonical_resolved = if resolved.exists() {
-                resolved.canonicalize().map_err(|e| {
-                    bodhya_core::Error::Tool(format!("Failed to canonicalize path: {}", e))
-                })?
-            } else {
-                // Check parent directory if file doesn't exist
-                if let Some(parent) = resolved.parent() {
-                    if parent.exists() {
-                        let canonical_parent = parent.canonicalize().map_err(|e| {
-                            bodhya_core::Error::Tool(format!(
-                                "Failed to canonicalize parent: {}",
-                                e
-                            ))
-                        })?;
-                        // Join canonical parent with filename
-                        if let Some(filename) = resolved.file_name() {
-                            canonical_parent.join(filename)
-                        } else {
-                            resolved
-                        }
-                    } else {
-                        resolved
-                    }
-                } else {
-                    resolved
-                }
-            };
-
-            // Security check: ensure path is within base_dir
-            if !canonical_resolved.starts_with(&canonical_base) {
-                return Err(bodhya_core::Error::Tool(format!(
-                    "Path '{}' is outside base directory",
-                    canonical_resolved.display()
-                )));
-            }
-
-            Ok(canonical_resolved)
-        } else {
-            Ok(path)
+        match &self.base_dir {
+            Some(base) => sandbox::resolve_within(base, &path),
+            None => Ok(path),
         }
     }
 
@@ -101,10 +56,25 @@ impl FilesystemTool {
         }
     }
 
-    /// Write to a file
-    async fn write_file(&self, path: &str, content: &str) -> Result<ToolResponse> {
+    /// Resolve the backup path for `path`, sandboxed like `path` itself
+    fn resolve_backup_path(&self, path: &str) -> Result<PathBuf> {
+        let backup = backup::backup_path(Path::new(path));
+        self.resolve_path(&backup.to_string_lossy())
+    }
+
+    /// Write to a file, optionally backing up its previous content first
+    async fn write_file(&self, path: &str, content: &str, backup: bool) -> Result<ToolResponse> {
         let resolved = self.resolve_path(path)?;
 
+        let backup_path = if backup {
+            let backup_path = self.resolve_backup_path(path)?;
+            backup::create_backup(&resolved, &backup_path)
+                .await?
+                .then_some(backup_path)
+        } else {
+            None
+        };
+
         // Create parent directories if they don't exist
         if let Some(parent) = resolved.parent() {
             if let Err(e) = tokio::fs::create_dir_all(parent).await {
@@ -115,11 +85,12 @@ impl FilesystemTool {
             }
         }
 
-        match tokio::fs::write(&resolved, content).await {
+        match atomic::write_atomic(&resolved, content.as_bytes()).await {
             Ok(_) => Ok(ToolResponse::success(serde_json::json!({
                 "path": path,
                 "size": content.len(),
-                "written": true
+                "written": true,
+                "backup_path": backup_path.map(|p| p.display().to_string())
             }))),
             Err(e) => Ok(ToolResponse::failure(format!(
                 "Failed to write file '{}': {}",
@@ -128,6 +99,40 @@ impl FilesystemTool {
         }
     }
 
+    /// Describe what a write would do without touching disk
+    async fn preview_write(&self, path: &str, content: &str) -> Result<ToolResponse> {
+        let resolved = self.resolve_path(path)?;
+        let existing_size = tokio::fs::metadata(&resolved).await.ok().map(|m| m.len());
+
+        Ok(ToolResponse::success(serde_json::json!({
+            "path": path,
+            "size": content.len(),
+            "written": false,
+            "dry_run": true,
+            "exists": existing_size.is_some(),
+            "existing_size": existing_size,
+        })))
+    }
+
+    /// Restore a file from the backup taken by a previous write or edit
+    async fn restore_file(&self, path: &str) -> Result<ToolResponse> {
+        let resolved = self.resolve_path(path)?;
+        let backup_path = self.resolve_backup_path(path)?;
+
+        if !backup_path.exists() {
+            return Ok(ToolResponse::failure(format!(
+                "No backup found for '{}'",
+                path
+            )));
+        }
+
+        backup::restore_backup(&resolved, &backup_path).await?;
+        Ok(ToolResponse::success(serde_json::json!({
+            "path": path,
+            "restored": true
+        })))
+    }
+
     /// List files in a directory
     async fn list_dir(&self, path: &str) -> Result<ToolResponse> {
         let resolved = self.resolve_path(path)?;
@@ -190,18 +195,39 @@ impl Tool for FilesystemTool {
     }
 
     fn description(&self) -> &str {
-        "Filesystem operations: read, write, list, and check existence of files and directories"
+        "Filesystem operations: read, write, restore, list, and check existence of files and directories"
     }
 
     fn supported_operations(&self) -> Vec<String> {
         vec![
             "read".to_string(),
             "write".to_string(),
+            "restore".to_string(),
             "list".to_string(),
             "exists".to_string(),
         ]
     }
 
+    fn supports_dry_run(&self, operation: &str) -> bool {
+        operation == "write"
+    }
+
+    fn parameter_schema(&self, operation: &str) -> serde_json::Value {
+        let path = ("path", json!("string"));
+        match operation {
+            "write" => object_schema(
+                &[
+                    path,
+                    ("content", json!("string")),
+                    ("backup", json!("boolean")),
+                ],
+                &["path", "content"],
+            ),
+            "read" | "restore" | "list" | "exists" => object_schema(&[path], &["path"]),
+            _ => json!({ "type": "object" }),
+        }
+    }
+
     async fn execute(&self, request: ToolRequest) -> Result<ToolResponse> {
         match request.operation.as_str() {
             "read" => {
@@ -217,7 +243,17 @@ impl Tool for FilesystemTool {
                 let content = request.params["content"].as_str().ok_or_else(|| {
                     bodhya_core::Error::Tool("Missing 'content' parameter".to_string())
                 })?;
-                self.write_file(path, content).await
+                if request.dry_run {
+                    return self.preview_write(path, content).await;
+                }
+                let backup = request.params["backup"].as_bool().unwrap_or(false);
+                self.write_file(path, content, backup).await
+            }
+            "restore" => {
+                let path = request.params["path"].as_str().ok_or_else(|| {
+                    bodhya_core::Error::Tool("Missing 'path' parameter".to_string())
+                })?;
+                self.restore_file(path).await
             }
             "list" => {
                 let path = request.params["path"].as_str().ok_or_else(|| {
@@ -408,6 +444,61 @@ mod tests {
         assert_eq!(read_resp.data["content"], "nested content");
     }
 
+    #[tokio::test]
+    async fn test_write_with_backup_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = FilesystemTool::with_base_dir(temp_dir.path());
+        std::fs::write(temp_dir.path().join("lib.rs"), "original").unwrap();
+
+        let write_req = ToolRequest::new(
+            "filesystem",
+            "write",
+            serde_json::json!({
+                "path": "lib.rs",
+                "content": "clobbered",
+                "backup": true
+            }),
+        );
+        let write_resp = tool.execute(write_req).await.unwrap();
+        assert!(write_resp.success);
+        assert!(write_resp.data["backup_path"]
+            .as_str()
+            .unwrap()
+            .ends_with("lib.rs.bak"));
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("lib.rs.bak")).unwrap(),
+            "original"
+        );
+
+        let restore_req = ToolRequest::new(
+            "filesystem",
+            "restore",
+            serde_json::json!({ "path": "lib.rs" }),
+        );
+        let restore_resp = tool.execute(restore_req).await.unwrap();
+        assert!(restore_resp.success);
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("lib.rs")).unwrap(),
+            "original"
+        );
+        assert!(!temp_dir.path().join("lib.rs.bak").exists());
+    }
+
+    #[tokio::test]
+    async fn test_restore_without_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = FilesystemTool::with_base_dir(temp_dir.path());
+
+        let req = ToolRequest::new(
+            "filesystem",
+            "restore",
+            serde_json::json!({ "path": "lib.rs" }),
+        );
+        let resp = tool.execute(req).await.unwrap();
+        assert!(!resp.success);
+        assert!(resp.error.unwrap().contains("No backup found"));
+    }
+
     #[tokio::test]
     async fn test_unsupported_operation() {
         let tool = FilesystemTool::new();
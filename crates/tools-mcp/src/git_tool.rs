@@ -0,0 +1,518 @@
+/// Git tool for inspecting and committing changes
+///
+/// This module runs `git` in a repository directory and returns structured
+/// results (changed files, diff text, commit hashes) so agents can commit
+/// the code they generate. With a base directory, the repository's top level
+/// and every pathspec must lie inside it, and magic pathspecs (`:/`,
+/// `:(top)`, ...) are refused since they can reach past it.
+use crate::sandbox;
+use crate::schema::object_schema;
+use async_trait::async_trait;
+use bodhya_core::{Result, Tool, ToolRequest, ToolResponse};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// Git tool for status, diff, add, commit, and branch operations
+pub struct GitTool {
+    /// Base directory for sandboxing (optional)
+    base_dir: Option<PathBuf>,
+}
+
+/// Output of one git invocation
+struct GitOutput {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+impl GitTool {
+    /// Create a git tool that runs in the current directory (or `path`)
+    pub fn new() -> Self {
+        Self { base_dir: None }
+    }
+
+    /// Create a git tool confined to `base_dir`
+    pub fn with_base_dir(base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_dir: Some(base_dir.into()),
+        }
+    }
+
+    /// Repository directory for a request: `path` if given, else the base directory
+    fn repo_dir(&self, request: &ToolRequest) -> Result<PathBuf> {
+        let path = request.params.get("path").and_then(|v| v.as_str());
+        match (&self.base_dir, path) {
+            (Some(base), Some(path)) => sandbox::resolve_within(base, &PathBuf::from(path)),
+            (Some(base), None) => Ok(base.clone()),
+            (None, Some(path)) => Ok(PathBuf::from(path)),
+            (None, None) => Ok(PathBuf::from(".")),
+        }
+    }
+
+    /// Pathspecs from the `files` param, each checked against the sandbox
+    fn files(&self, request: &ToolRequest, repo: &Path) -> Result<Vec<String>> {
+        let files: Vec<String> = request.params["files"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect();
+
+        if let Some(base) = &self.base_dir {
+            for file in &files {
+                if file.starts_with(':') {
+                    return Err(bodhya_core::Error::Tool(format!(
+                        "Magic pathspec '{}' is not allowed",
+                        file
+                    )));
+                }
+                sandbox::resolve_within(base, &repo.join(file))?;
+            }
+        }
+        Ok(files)
+    }
+
+    /// Run `git` with `args` in `repo`
+    async fn git(&self, repo: &Path, args: &[&str]) -> Result<GitOutput> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(repo)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| bodhya_core::Error::Tool(format!("Failed to run git: {}", e)))?;
+
+        Ok(GitOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
+    }
+
+    /// Failure response unless `repo` is inside a git work tree
+    ///
+    /// With a base directory, a work tree whose top level is outside it (a
+    /// plain subdirectory of an enclosing repository) is an error.
+    async fn check_repo(&self, repo: &Path) -> Result<Option<ToolResponse>> {
+        if !repo.is_dir() {
+            return Ok(Some(ToolResponse::failure(format!(
+                "'{}' is not a directory",
+                repo.display()
+            ))));
+        }
+        let output = self
+            .git(repo, &["rev-parse", "--is-inside-work-tree"])
+            .await?;
+        if !output.success || output.stdout.trim() != "true" {
+            return Ok(Some(ToolResponse::failure(format!(
+                "'{}' is not a git repository",
+                repo.display()
+            ))));
+        }
+
+        if let Some(base) = &self.base_dir {
+            let toplevel = self.git(repo, &["rev-parse", "--show-toplevel"]).await?;
+            let toplevel = Path::new(toplevel.stdout.trim());
+            sandbox::resolve_within(base, toplevel).map_err(|_| {
+                bodhya_core::Error::Tool(format!(
+                    "Repository at '{}' is outside base directory",
+                    toplevel.display()
+                ))
+            })?;
+        }
+        Ok(None)
+    }
+
+    /// Changed files and the current branch
+    async fn status(&self, repo: &Path) -> Result<ToolResponse> {
+        let output = self
+            .git(repo, &["status", "--porcelain=v1", "--branch"])
+            .await?;
+        if !output.success {
+            return Ok(ToolResponse::failure(output.stderr));
+        }
+
+        let mut branch = None;
+        let mut files = Vec::new();
+        for line in output.stdout.lines() {
+            if let Some(header) = line.strip_prefix("## ") {
+                // e.g., "main...origin/main [ahead 1]" or "No commits yet on main"
+                let name = header.split("...").next().unwrap_or(header);
+                let name = name.strip_prefix("No commits yet on ").unwrap_or(name);
+                branch = Some(name.split_whitespace().next().unwrap_or(name).to_string());
+            } else if line.len() > 3 {
+                files.push(json!({
+                    "path": &line[3..],
+                    "index": &line[0..1],
+                    "worktree": &line[1..2],
+                }));
+            }
+        }
+
+        Ok(ToolResponse::success(json!({
+            "branch": branch,
+            "clean": files.is_empty(),
+            "files": files,
+        })))
+    }
+
+    /// Unified diff of the work tree (or the index, if `staged`)
+    async fn diff(&self, repo: &Path, staged: bool, files: &[String]) -> Result<ToolResponse> {
+        let mut args = vec!["diff"];
+        if staged {
+            args.push("--cached");
+        }
+        let mut names_args = args.clone();
+        names_args.push("--name-only");
+
+        let pathspecs: Vec<&str> = files.iter().map(String::as_str).collect();
+        for list in [&mut args, &mut names_args] {
+            list.push("--");
+            list.extend(&pathspecs);
+        }
+
+        let diff = self.git(repo, &args).await?;
+        if !diff.success {
+            return Ok(ToolResponse::failure(diff.stderr));
+        }
+        let names = self.git(repo, &names_args).await?;
+        let changed: Vec<&str> = names.stdout.lines().collect();
+
+        Ok(ToolResponse::success(json!({
+            "staged": staged,
+            "files": changed,
+            "diff": diff.stdout,
+        })))
+    }
+
+    /// Stage `files`
+    async fn add(&self, repo: &Path, files: &[String]) -> Result<ToolResponse> {
+        if files.is_empty() {
+            return Ok(ToolResponse::failure("No files to add"));
+        }
+        let mut args = vec!["add", "--"];
+        args.extend(files.iter().map(String::as_str));
+
+        let output = self.git(repo, &args).await?;
+        if !output.success {
+            return Ok(ToolResponse::failure(output.stderr));
+        }
+        Ok(ToolResponse::success(json!({ "added": files })))
+    }
+
+    /// Commit the index, returning the new commit hash
+    async fn commit(
+        &self,
+        repo: &Path,
+        message: &str,
+        author: Option<(&str, &str)>,
+    ) -> Result<ToolResponse> {
+        let identity;
+        let mut args = Vec::new();
+        if let Some((name, email)) = author {
+            identity = [
+                format!("user.name={}", name),
+                format!("user.email={}", email),
+            ];
+            args.extend(["-c", identity[0].as_str(), "-c", identity[1].as_str()]);
+        }
+        args.extend(["commit", "-m", message]);
+
+        let output = self.git(repo, &args).await?;
+        if !output.success {
+            let reason = if output.stderr.is_empty() {
+                output.stdout.trim().to_string()
+            } else {
+                output.stderr
+            };
+            return Ok(ToolResponse::failure(format!("Commit failed: {}", reason)));
+        }
+
+        let head = self.git(repo, &["rev-parse", "HEAD"]).await?;
+        Ok(ToolResponse::success(json!({
+            "commit": head.stdout.trim(),
+            "message": message,
+        })))
+    }
+
+    /// List branches, or create and switch to `name`
+    async fn branch(&self, repo: &Path, name: Option<&str>) -> Result<ToolResponse> {
+        if let Some(name) = name {
+            // A leading dash would be read as an option
+            if name.is_empty() || name.starts_with('-') {
+                return Ok(ToolResponse::failure(format!(
+                    "Invalid branch name '{}'",
+                    name
+                )));
+            }
+            let output = self.git(repo, &["checkout", "-b", name]).await?;
+            if !output.success {
+                return Ok(ToolResponse::failure(output.stderr));
+            }
+        }
+
+        let current = self
+            .git(repo, &["rev-parse", "--abbrev-ref", "HEAD"])
+            .await?;
+        let list = self
+            .git(repo, &["branch", "--format=%(refname:short)"])
+            .await?;
+        let branches: Vec<&str> = list.stdout.lines().collect();
+
+        Ok(ToolResponse::success(json!({
+            "current": current.stdout.trim(),
+            "branches": branches,
+            "created": name,
+        })))
+    }
+}
+
+impl Default for GitTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for GitTool {
+    fn id(&self) -> &str {
+        "git"
+    }
+
+    fn description(&self) -> &str {
+        "Git operations: status, diff, add, commit, and branch"
+    }
+
+    fn supported_operations(&self) -> Vec<String> {
+        vec![
+            "status".to_string(),
+            "diff".to_string(),
+            "add".to_string(),
+            "commit".to_string(),
+            "branch".to_string(),
+        ]
+    }
+
+    fn parameter_schema(&self, operation: &str) -> serde_json::Value {
+        let path = ("path", json!("string"));
+        match operation {
+            "status" => object_schema(&[path], &[]),
+            "diff" => object_schema(
+                &[
+                    path,
+                    ("staged", json!("boolean")),
+                    ("files", json!("array")),
+                ],
+                &[],
+            ),
+            "add" => object_schema(&[path, ("files", json!("array"))], &["files"]),
+            "commit" => object_schema(
+                &[
+                    path,
+                    ("message", json!("string")),
+                    ("author_name", json!("string")),
+                    ("author_email", json!("string")),
+                ],
+                &["message"],
+            ),
+            "branch" => object_schema(&[path, ("name", json!("string"))], &[]),
+            _ => json!({ "type": "object" }),
+        }
+    }
+
+    async fn execute(&self, request: ToolRequest) -> Result<ToolResponse> {
+        if !self.supports_operation(&request.operation) {
+            return Ok(ToolResponse::failure(format!(
+                "Unsupported operation: {}",
+                request.operation
+            )));
+        }
+
+        let repo = self.repo_dir(&request)?;
+        if let Some(failure) = self.check_repo(&repo).await? {
+            return Ok(failure);
+        }
+
+        let params = &request.params;
+        match request.operation.as_str() {
+            "status" => self.status(&repo).await,
+            "diff" => {
+                let staged = params["staged"].as_bool().unwrap_or(false);
+                let files = self.files(&request, &repo)?;
+                self.diff(&repo, staged, &files).await
+            }
+            "add" => {
+                let files = self.files(&request, &repo)?;
+                self.add(&repo, &files).await
+            }
+            "commit" => {
+                let message = params["message"].as_str().ok_or_else(|| {
+                    bodhya_core::Error::Tool("Missing 'message' parameter".to_string())
+                })?;
+                let author = params["author_name"]
+                    .as_str()
+                    .zip(params["author_email"].as_str());
+                self.commit(&repo, message, author).await
+            }
+            _ => self.branch(&repo, params["name"].as_str()).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn init_repo() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let status = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(temp_dir.path())
+            .status()
+            .await
+            .unwrap();
+        assert!(status.success());
+        temp_dir
+    }
+
+    async fn run(tool: &GitTool, operation: &str, params: serde_json::Value) -> ToolResponse {
+        tool.execute(ToolRequest::new("git", operation, params))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_and_commit() {
+        let repo = init_repo().await;
+        std::fs::write(repo.path().join("lib.rs"), "pub fn add() {}\n").unwrap();
+        let tool = GitTool::with_base_dir(repo.path());
+
+        let status = run(&tool, "status", json!({})).await;
+        assert!(status.success);
+        assert_eq!(status.data["clean"], false);
+        assert_eq!(status.data["files"][0]["path"], "lib.rs");
+        assert_eq!(status.data["files"][0]["worktree"], "?");
+
+        let added = run(&tool, "add", json!({ "files": ["lib.rs"] })).await;
+        assert!(added.success);
+
+        let staged = run(&tool, "diff", json!({ "staged": true })).await;
+        assert_eq!(staged.data["files"], json!(["lib.rs"]));
+        assert!(staged.data["diff"]
+            .as_str()
+            .unwrap()
+            .contains("+pub fn add() {}"));
+
+        let commit = run(
+            &tool,
+            "commit",
+            json!({
+                "message": "Add lib",
+                "author_name": "Bodhya",
+                "author_email": "bodhya@example.com",
+            }),
+        )
+        .await;
+        assert!(commit.success, "{:?}", commit.error);
+        let hash = commit.data["commit"].as_str().unwrap();
+        assert_eq!(hash.len(), 40);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+
+        let status = run(&tool, "status", json!({})).await;
+        assert_eq!(status.data["clean"], true);
+    }
+
+    #[tokio::test]
+    async fn test_branch_create_and_list() {
+        let repo = init_repo().await;
+        std::fs::write(repo.path().join("a.txt"), "a").unwrap();
+        let tool = GitTool::with_base_dir(repo.path());
+        run(&tool, "add", json!({ "files": ["a.txt"] })).await;
+        run(
+            &tool,
+            "commit",
+            json!({ "message": "init", "author_name": "B", "author_email": "b@example.com" }),
+        )
+        .await;
+
+        let branch = run(&tool, "branch", json!({ "name": "feature" })).await;
+        assert!(branch.success, "{:?}", branch.error);
+        assert_eq!(branch.data["current"], "feature");
+        assert!(branch.data["branches"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("feature")));
+    }
+
+    #[tokio::test]
+    async fn test_not_a_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = GitTool::with_base_dir(temp_dir.path());
+
+        let status = run(&tool, "status", json!({})).await;
+        assert!(!status.success);
+        assert!(status.error.unwrap().contains("not a git repository"));
+    }
+
+    #[tokio::test]
+    async fn test_pathspec_outside_sandbox_rejected() {
+        let repo = init_repo().await;
+        let tool = GitTool::with_base_dir(repo.path());
+
+        let result = tool
+            .execute(ToolRequest::new(
+                "git",
+                "add",
+                json!({ "files": ["../../etc/passwd"] }),
+            ))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_magic_pathspec_rejected() {
+        let repo = init_repo().await;
+        let tool = GitTool::with_base_dir(repo.path());
+
+        for pathspec in [":/", ":(top)src", ":!lib.rs"] {
+            let result = tool
+                .execute(ToolRequest::new(
+                    "git",
+                    "diff",
+                    json!({ "files": [pathspec] }),
+                ))
+                .await;
+            assert!(result.is_err(), "{} was accepted", pathspec);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enclosing_repo_outside_sandbox_rejected() {
+        let repo = init_repo().await;
+        let sub_dir = repo.path().join("sub");
+        std::fs::create_dir(&sub_dir).unwrap();
+        let tool = GitTool::with_base_dir(&sub_dir);
+
+        let err = tool
+            .execute(ToolRequest::new("git", "status", json!({})))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("outside base directory"));
+    }
+
+    #[tokio::test]
+    async fn test_branch_name_with_leading_dash_rejected() {
+        let repo = init_repo().await;
+        let tool = GitTool::with_base_dir(repo.path());
+
+        let branch = run(&tool, "branch", json!({ "name": "--orphan=x" })).await;
+        assert!(!branch.success);
+        assert!(branch.error.unwrap().contains("Invalid branch name"));
+    }
+}
@@ -0,0 +1,502 @@
+/// HTTP fetch tool
+///
+/// This module lets agents fetch documentation or API schemas over HTTP.
+/// Only hosts permitted by the allow/deny lists can be reached (redirects
+/// included), and response bodies are cut off at a size cap. Loopback,
+/// link-local and private addresses are refused unless explicitly enabled,
+/// both as URL literals and as the result of DNS resolution.
+use crate::schema::object_schema;
+use async_trait::async_trait;
+use base64::Engine;
+use bodhya_core::config::HttpToolConfig;
+use bodhya_core::{Result, Tool, ToolRequest, ToolResponse};
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::Url;
+use serde_json::{json, Map, Value};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Which hosts may be fetched
+#[derive(Clone, Debug, Default)]
+struct HostPolicy {
+    allowed: Vec<String>,
+    denied: Vec<String>,
+    allow_private: bool,
+}
+
+impl HostPolicy {
+    /// Whether `host` (or a parent domain) is denied, or not allowed when an allowlist is set
+    fn permits(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+        let matches = |pattern: &String| {
+            let pattern = pattern.to_ascii_lowercase();
+            host == pattern || host.ends_with(&format!(".{}", pattern))
+        };
+        !self.denied.iter().any(matches)
+            && (self.allowed.is_empty() || self.allowed.iter().any(matches))
+    }
+
+    /// Whether `url` is an http(s) URL to a permitted host
+    fn check(&self, url: &Url) -> std::result::Result<(), String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("Unsupported URL scheme '{}'", url.scheme()));
+        }
+        let ip = url.host_str().and_then(|host| {
+            host.trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .ok()
+        });
+        match url.host_str() {
+            Some(host) if !self.permits(host) => Err(format!("Host '{}' is not allowed", host)),
+            Some(host) if ip.is_some_and(|ip| !self.allow_private && !is_public_address(ip)) => {
+                Err(format!(
+                    "Host '{}' is a private network address and is not allowed",
+                    host
+                ))
+            }
+            Some(_) => Ok(()),
+            None => Err(format!("URL '{}' has no host", url)),
+        }
+    }
+}
+
+/// Whether `ip` is reachable on the public internet (not loopback,
+/// link-local, private, shared or unspecified)
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_public_address(IpAddr::V4(v4)),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// DNS resolver that drops non-public addresses, so a public host name can't
+/// be pointed at an internal service
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+                .await?
+                .filter(|addr| is_public_address(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(
+                    format!("Host '{}' resolves only to private network addresses", host).into(),
+                );
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// HTTP tool with `get` and `post` operations
+pub struct HttpTool {
+    client: reqwest::Client,
+    hosts: Arc<HostPolicy>,
+    max_response_bytes: usize,
+    timeout: Duration,
+}
+
+impl HttpTool {
+    /// Create an HTTP tool that may fetch any public host
+    pub fn new() -> Self {
+        Self::from_config(&HttpToolConfig::default())
+    }
+
+    /// Create an HTTP tool from configuration
+    pub fn from_config(config: &HttpToolConfig) -> Self {
+        let hosts = Arc::new(HostPolicy {
+            allowed: config.allowed_hosts.clone(),
+            denied: config.denied_hosts.clone(),
+            allow_private: config.allow_private_networks,
+        });
+        Self {
+            client: Self::client(&hosts),
+            hosts,
+            max_response_bytes: config.max_response_bytes,
+            timeout: Duration::from_secs(config.timeout_secs),
+        }
+    }
+
+    /// Only allow hosts in `hosts` (and their subdomains)
+    pub fn with_allowed_hosts(
+        mut self,
+        hosts: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let mut policy = (*self.hosts).clone();
+        policy.allowed = hosts.into_iter().map(Into::into).collect();
+        self.set_hosts(policy);
+        self
+    }
+
+    /// Never fetch hosts in `hosts` (or their subdomains)
+    pub fn with_denied_hosts(mut self, hosts: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let mut policy = (*self.hosts).clone();
+        policy.denied = hosts.into_iter().map(Into::into).collect();
+        self.set_hosts(policy);
+        self
+    }
+
+    /// Allow (or refuse) loopback, link-local and private network addresses
+    pub fn with_private_networks(mut self, allow: bool) -> Self {
+        let mut policy = (*self.hosts).clone();
+        policy.allow_private = allow;
+        self.set_hosts(policy);
+        self
+    }
+
+    /// Cut response bodies off after `max_response_bytes`
+    pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    /// Set the request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn set_hosts(&mut self, policy: HostPolicy) {
+        self.hosts = Arc::new(policy);
+        self.client = Self::client(&self.hosts);
+    }
+
+    /// HTTP client that refuses redirects to hosts outside `hosts`, and
+    /// private addresses unless `hosts` allows them
+    fn client(hosts: &Arc<HostPolicy>) -> reqwest::Client {
+        let builder = if hosts.allow_private {
+            reqwest::Client::builder()
+        } else {
+            reqwest::Client::builder().dns_resolver(Arc::new(PublicResolver))
+        };
+        let hosts = Arc::clone(hosts);
+        let redirects = reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= 10 {
+                attempt.error("too many redirects")
+            } else if let Err(reason) = hosts.check(attempt.url()) {
+                attempt.error(reason)
+            } else {
+                attempt.follow()
+            }
+        });
+        builder.redirect(redirects).build().unwrap_or_default()
+    }
+
+    /// Send a request and read at most `max_response_bytes` of the body
+    async fn fetch(&self, request: &ToolRequest) -> Result<ToolResponse> {
+        let params = &request.params;
+        let raw_url = params["url"]
+            .as_str()
+            .ok_or_else(|| bodhya_core::Error::Tool("Missing 'url' parameter".to_string()))?;
+        let url = Url::parse(raw_url).map_err(|e| {
+            bodhya_core::Error::InvalidInput(format!("Invalid URL '{}': {}", raw_url, e))
+        })?;
+        if let Err(reason) = self.hosts.check(&url) {
+            return Ok(ToolResponse::failure(reason));
+        }
+
+        let mut builder = match request.operation.as_str() {
+            "post" => self.client.post(url.clone()),
+            _ => self.client.get(url.clone()),
+        }
+        .timeout(self.timeout);
+        for (name, value) in params["headers"].as_object().into_iter().flatten() {
+            if let Some(value) = value.as_str() {
+                builder = builder.header(name.as_str(), value);
+            }
+        }
+        if let Some(body) = params.get("json") {
+            builder = builder
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.to_string());
+        } else if let Some(body) = params["body"].as_str() {
+            builder = builder.body(body.to_string());
+        }
+
+        let mut response = match builder.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                return Ok(ToolResponse::failure(format!(
+                    "Request to '{}' failed: {}",
+                    url, e
+                )))
+            }
+        };
+
+        let status = response.status();
+        let headers: Map<String, Value> = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), json!(value.to_str().ok()?))))
+            .collect();
+        let is_text = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(is_text_content_type);
+
+        let mut body = Vec::new();
+        let mut truncated = false;
+        loop {
+            let chunk = match response.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    return Ok(ToolResponse::failure(format!(
+                        "Failed to read response from '{}': {}",
+                        url, e
+                    )))
+                }
+            };
+            let room = self.max_response_bytes - body.len();
+            if chunk.len() > room {
+                body.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        let mut data = json!({
+            "url": url.as_str(),
+            "status": status.as_u16(),
+            "headers": headers,
+            "size": body.len(),
+            "truncated": truncated,
+        });
+        // Text unless the server says otherwise or the bytes aren't UTF-8
+        match String::from_utf8(body) {
+            Ok(text) if is_text != Some(false) => data["body"] = json!(text),
+            Ok(text) => {
+                data["body_base64"] = json!(base64::engine::general_purpose::STANDARD.encode(text))
+            }
+            Err(e) => {
+                data["body_base64"] =
+                    json!(base64::engine::general_purpose::STANDARD.encode(e.into_bytes()))
+            }
+        }
+
+        let mut result = ToolResponse::success(data);
+        if !status.is_success() {
+            result.success = false;
+            result.error = Some(format!("HTTP {}", status));
+        }
+        Ok(result)
+    }
+}
+
+/// Whether a Content-Type names a textual format
+fn is_text_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    mime.starts_with("text/")
+        || ["json", "xml", "javascript", "yaml", "toml"]
+            .iter()
+            .any(|kind| mime.contains(kind))
+}
+
+impl Default for HttpTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for HttpTool {
+    fn id(&self) -> &str {
+        "http"
+    }
+
+    fn description(&self) -> &str {
+        "HTTP requests: get and post to allowed hosts"
+    }
+
+    fn supported_operations(&self) -> Vec<String> {
+        vec!["get".to_string(), "post".to_string()]
+    }
+
+    fn parameter_schema(&self, operation: &str) -> serde_json::Value {
+        let url = ("url", json!("string"));
+        let headers = ("headers", json!("object"));
+        match operation {
+            "get" => object_schema(&[url, headers], &["url"]),
+            "post" => object_schema(
+                &[
+                    url,
+                    headers,
+                    ("body", json!("string")),
+                    ("json", json!(["object", "array"])),
+                ],
+                &["url"],
+            ),
+            _ => json!({ "type": "object" }),
+        }
+    }
+
+    async fn execute(&self, request: ToolRequest) -> Result<ToolResponse> {
+        match request.operation.as_str() {
+            "get" | "post" => self.fetch(&request).await,
+            _ => Ok(ToolResponse::failure(format!(
+                "Unsupported operation: {}",
+                request.operation
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `body` as text/plain to every connection; returns the base URL
+    async fn mock_server(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = vec![0; 4096];
+                let _ = socket.read(&mut request).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\
+                     Connection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn get(url: &str) -> ToolRequest {
+        ToolRequest::new("http", "get", json!({ "url": url }))
+    }
+
+    #[tokio::test]
+    async fn test_get_returns_body() {
+        let base = mock_server("hello from the docs").await;
+        let tool = HttpTool::new().with_private_networks(true);
+
+        let response = tool.execute(get(&format!("{}/docs", base))).await.unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.data["status"], 200);
+        assert_eq!(response.data["body"], "hello from the docs");
+        assert_eq!(response.data["headers"]["content-type"], "text/plain");
+        assert_eq!(response.data["truncated"], false);
+    }
+
+    #[tokio::test]
+    async fn test_get_respects_size_cap() {
+        let base = mock_server("0123456789abcdefghij").await;
+        let tool = HttpTool::new()
+            .with_private_networks(true)
+            .with_max_response_bytes(8);
+
+        let response = tool.execute(get(&base)).await.unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.data["body"], "01234567");
+        assert_eq!(response.data["size"], 8);
+        assert_eq!(response.data["truncated"], true);
+    }
+
+    #[tokio::test]
+    async fn test_host_lists_enforced() {
+        let base = mock_server("secret").await;
+
+        let allowlisted = HttpTool::new().with_allowed_hosts(["docs.rs"]);
+        let response = allowlisted.execute(get(&base)).await.unwrap();
+        assert!(!response.success);
+        assert!(response.error.unwrap().contains("not allowed"));
+
+        let denylisted = HttpTool::new()
+            .with_private_networks(true)
+            .with_denied_hosts(["127.0.0.1"]);
+        assert!(!denylisted.execute(get(&base)).await.unwrap().success);
+
+        let scheme = HttpTool::new()
+            .execute(get("file:///etc/passwd"))
+            .await
+            .unwrap();
+        assert!(scheme.error.unwrap().contains("Unsupported URL scheme"));
+    }
+
+    #[test]
+    fn test_host_policy_matches_subdomains() {
+        let policy = HostPolicy {
+            allowed: vec!["docs.rs".to_string()],
+            denied: vec!["internal.docs.rs".to_string()],
+            allow_private: false,
+        };
+        assert!(policy.permits("docs.rs"));
+        assert!(policy.permits("API.docs.rs"));
+        assert!(!policy.permits("evildocs.rs"));
+        assert!(!policy.permits("a.internal.docs.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_private_networks_refused_by_default() {
+        let base = mock_server("secret").await;
+        let tool = HttpTool::new();
+
+        let literal = tool.execute(get(&base)).await.unwrap();
+        assert!(!literal.success);
+        assert!(literal.error.unwrap().contains("private network"));
+
+        for url in ["http://[::1]/", "http://169.254.169.254/latest/meta-data"] {
+            assert!(!tool.execute(get(url)).await.unwrap().success, "{}", url);
+        }
+
+        // A host name is checked after resolution
+        let port = base.rsplit(':').next().unwrap();
+        let resolved = tool
+            .execute(get(&format!("http://localhost:{}/", port)))
+            .await
+            .unwrap();
+        assert!(!resolved.success);
+        assert!(resolved.error.unwrap().contains("private network"));
+    }
+
+    #[test]
+    fn test_is_public() {
+        for ip in [
+            "10.1.2.3",
+            "192.168.0.1",
+            "127.0.0.1",
+            "100.64.0.1",
+            "::1",
+            "fd00::1",
+        ] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in ["93.184.216.34", "2606:4700::1111"] {
+            assert!(is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+        assert!(!is_public_address("::ffff:127.0.0.1".parse().unwrap()));
+    }
+}
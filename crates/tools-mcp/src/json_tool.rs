@@ -0,0 +1,327 @@
+//! JSON query tool
+//!
+//! Lets agents pull fields out of JSON (e.g., an HTTP response body) and
+//! check JSON against a schema without string manipulation. Paths are
+//! either JSON Pointers (`/items/0/name`) or a JSONPath subset
+//! (`$.items[0].name`, `$.items[*].name`, `$['odd key']`).
+
+use crate::schema::{check_value, object_schema, unsupported_keywords};
+use async_trait::async_trait;
+use bodhya_core::{Error, Result, Tool, ToolRequest, ToolResponse};
+use serde_json::{json, Value};
+
+/// One step of a JSONPath
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    /// Every element of an array or value of an object
+    Wildcard,
+}
+
+/// JSON tool with `query` and `validate` operations
+pub struct JsonTool;
+
+impl JsonTool {
+    /// Create the tool; it holds no state, so one instance serves every request
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Values at `path` in `document`
+    ///
+    /// Returns `None` if nothing matches. Paths with a wildcard always yield
+    /// an array of every match.
+    pub fn query(document: &Value, path: &str) -> Result<Option<Value>> {
+        if path.starts_with('/') {
+            return Ok(document.pointer(path).cloned());
+        }
+
+        let segments = parse_path(path)?;
+        let mut matches = vec![document];
+        for segment in &segments {
+            matches = matches
+                .into_iter()
+                .flat_map(|value| step(value, segment))
+                .collect();
+        }
+
+        if segments.contains(&Segment::Wildcard) {
+            Ok(Some(Value::Array(matches.into_iter().cloned().collect())))
+        } else {
+            Ok(matches.first().map(|value| (*value).clone()))
+        }
+    }
+
+    fn run_query(&self, params: &Value) -> Result<ToolResponse> {
+        let document = document(params)?;
+        let path = params["path"].as_str().unwrap_or_default();
+
+        Ok(match Self::query(&document, path)? {
+            Some(value) => ToolResponse::success(json!({ "path": path, "value": value })),
+            None => ToolResponse::failure(format!("No value at path '{}'", path)),
+        })
+    }
+
+    fn run_validate(&self, params: &Value) -> Result<ToolResponse> {
+        let document = document(params)?;
+        // A keyword the checker skips would let invalid JSON pass as valid
+        let unsupported = unsupported_keywords(&params["schema"]);
+        if !unsupported.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "Schema uses keywords that are not supported (only type, properties, \
+                 required and items are checked): {}",
+                unsupported.join(", ")
+            )));
+        }
+        let errors: Vec<String> = check_value(&params["schema"], &document)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        let mut response = ToolResponse::success(json!({
+            "valid": errors.is_empty(),
+            "errors": errors,
+        }));
+        if !errors.is_empty() {
+            response.success = false;
+            response.error = Some(format!("JSON does not match schema: {}", errors.join("; ")));
+        }
+        Ok(response)
+    }
+}
+
+/// The `json` param, parsing it if it was passed as a string
+fn document(params: &Value) -> Result<Value> {
+    match &params["json"] {
+        Value::String(text) => serde_json::from_str(text)
+            .map_err(|e| Error::InvalidInput(format!("'json' is not valid JSON: {}", e))),
+        value => Ok(value.clone()),
+    }
+}
+
+/// Children of `value` selected by `segment`
+fn step<'a>(value: &'a Value, segment: &Segment) -> Vec<&'a Value> {
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(fields)) => fields.get(key).into_iter().collect(),
+        (Segment::Index(index), Value::Array(elements)) => {
+            elements.get(*index).into_iter().collect()
+        }
+        (Segment::Wildcard, Value::Array(elements)) => elements.iter().collect(),
+        (Segment::Wildcard, Value::Object(fields)) => fields.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Parse `$.a.b[0]['c d'][*]` (the leading `$` is optional)
+fn parse_path(path: &str) -> Result<Vec<Segment>> {
+    let invalid =
+        |reason: &str| Error::InvalidInput(format!("Invalid path '{}': {}", path, reason));
+    let mut rest = path.strip_prefix('$').unwrap_or(path);
+    let mut segments = Vec::new();
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(|| invalid("unclosed '['"))?;
+            let inner = after[..end].trim();
+            segments.push(if inner == "*" {
+                Segment::Wildcard
+            } else if let Some(key) = quoted(inner) {
+                Segment::Key(key.to_string())
+            } else {
+                Segment::Index(inner.parse().map_err(|_| invalid("bad index"))?)
+            });
+            rest = &after[end + 1..];
+        } else {
+            // A field name, with or without a leading '.' (e.g., `a.b` or `.a`)
+            let after = rest.strip_prefix('.').unwrap_or(rest);
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            let name = &after[..end];
+            if name.is_empty() {
+                return Err(invalid("empty field name"));
+            }
+            segments.push(if name == "*" {
+                Segment::Wildcard
+            } else {
+                Segment::Key(name.to_string())
+            });
+            rest = &after[end..];
+        }
+    }
+
+    Ok(segments)
+}
+
+/// `text` without surrounding single or double quotes, if it has them
+fn quoted(text: &str) -> Option<&str> {
+    ['\'', '"'].iter().find_map(|quote| {
+        text.strip_prefix(*quote)
+            .and_then(|inner| inner.strip_suffix(*quote))
+    })
+}
+
+impl Default for JsonTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for JsonTool {
+    fn id(&self) -> &str {
+        "json"
+    }
+
+    fn description(&self) -> &str {
+        "JSON operations: query by pointer or path, validate against a schema"
+    }
+
+    fn supported_operations(&self) -> Vec<String> {
+        vec!["query".to_string(), "validate".to_string()]
+    }
+
+    fn parameter_schema(&self, operation: &str) -> Value {
+        let document = ("json", json!(["object", "array", "string"]));
+        match operation {
+            "query" => object_schema(&[document, ("path", json!("string"))], &["json", "path"]),
+            "validate" => object_schema(
+                &[document, ("schema", json!("object"))],
+                &["json", "schema"],
+            ),
+            _ => json!({ "type": "object" }),
+        }
+    }
+
+    async fn execute(&self, request: ToolRequest) -> Result<ToolResponse> {
+        match request.operation.as_str() {
+            "query" => self.run_query(&request.params),
+            "validate" => self.run_validate(&request.params),
+            _ => Ok(ToolResponse::failure(format!(
+                "Unsupported operation: {}",
+                request.operation
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crate_info() -> Value {
+        json!({
+            "crate": { "name": "serde", "versions": [{ "num": "1.0.200" }, { "num": "1.0.199" }] },
+            "odd key": true,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_query_pointer_and_path() {
+        let tool = JsonTool::new();
+        let query = |path: &str| {
+            ToolRequest::new(
+                "json",
+                "query",
+                json!({ "json": crate_info(), "path": path }),
+            )
+        };
+
+        let response = tool.execute(query("/crate/versions/0/num")).await.unwrap();
+        assert!(response.success);
+        assert_eq!(response.data["value"], "1.0.200");
+
+        let response = tool
+            .execute(query("$.crate.versions[1].num"))
+            .await
+            .unwrap();
+        assert_eq!(response.data["value"], "1.0.199");
+
+        let response = tool
+            .execute(query("$.crate.versions[*].num"))
+            .await
+            .unwrap();
+        assert_eq!(response.data["value"], json!(["1.0.200", "1.0.199"]));
+
+        let response = tool.execute(query("$['odd key']")).await.unwrap();
+        assert_eq!(response.data["value"], true);
+
+        let response = tool.execute(query("/crate/missing")).await.unwrap();
+        assert!(!response.success);
+    }
+
+    #[tokio::test]
+    async fn test_query_json_string() {
+        let request = ToolRequest::new(
+            "json",
+            "query",
+            json!({ "json": r#"{"status": "ok"}"#, "path": "status" }),
+        );
+        let response = JsonTool::new().execute(request).await.unwrap();
+        assert_eq!(response.data["value"], "ok");
+
+        let request = ToolRequest::new("json", "query", json!({ "json": "{", "path": "a" }));
+        let err = JsonTool::new().execute(request).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(_)));
+    }
+
+    #[tokio::test]
+    async fn test_validate_reports_schema_failure() {
+        let schema = object_schema(
+            &[("name", json!("string")), ("version", json!("string"))],
+            &["name", "version"],
+        );
+        let request = ToolRequest::new(
+            "json",
+            "validate",
+            json!({ "json": { "name": 7 }, "schema": schema }),
+        );
+
+        let response = JsonTool::new().execute(request).await.unwrap();
+
+        assert!(!response.success);
+        assert_eq!(response.data["valid"], false);
+        assert_eq!(
+            response.data["errors"],
+            json!(["missing 'version'", "'name' should be string, got number"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_unsupported_keywords() {
+        let schema = json!({
+            "type": "object",
+            "description": "a package",
+            "properties": { "age": { "type": "integer", "minimum": 0 } },
+            "additionalProperties": false,
+        });
+        let request = ToolRequest::new(
+            "json",
+            "validate",
+            json!({ "json": { "age": -1 }, "schema": schema }),
+        );
+
+        let err = JsonTool::new().execute(request).await.unwrap_err();
+
+        let Error::InvalidInput(message) = err else {
+            panic!("expected InvalidInput, got {:?}", err);
+        };
+        assert!(message.contains("'additionalProperties' at $"));
+        assert!(message.contains("'minimum' at $.properties.age"));
+        assert!(!message.contains("description"));
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("$.a[2]['b.c'][*]").unwrap(),
+            vec![
+                Segment::Key("a".to_string()),
+                Segment::Index(2),
+                Segment::Key("b.c".to_string()),
+                Segment::Wildcard,
+            ]
+        );
+        assert!(parse_path("$.a[").is_err());
+        assert!(parse_path("$.a..b").is_err());
+    }
+}
@@ -6,33 +6,74 @@
 // Re-export core tool types for convenience
 pub use bodhya_core::{McpClient, McpServerConfig, Tool, ToolRequest, ToolResponse};
 
+mod atomic;
+mod backup;
+mod case;
 mod edit_tool;
 mod fs_tool;
+mod git_tool;
+mod http_tool;
 mod json_rpc;
+mod json_tool;
+mod line_changes;
 mod mcp_client;
 mod mcp_tool_wrapper;
+mod sandbox;
+mod schema;
 mod search_tool;
 mod shell_tool;
 mod stdio_mcp_client;
+mod symbols;
+mod truncate;
 
 // Re-export tool implementations
-pub use edit_tool::{EditOperation, EditResult, EditTool};
+pub use backup::backup_path;
+pub use case::{split_words, CaseStyle};
+pub use edit_tool::{EditOperation, EditOptions, EditResult, EditTool};
 pub use fs_tool::FilesystemTool;
+pub use git_tool::GitTool;
+pub use http_tool::{is_public_address, HttpTool, PublicResolver};
 pub use json_rpc::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, RequestId};
+pub use json_tool::JsonTool;
+pub use line_changes::{line_edits, ChangedLine, LineEdit, LineOp};
 pub use mcp_client::BasicMcpClient;
 pub use mcp_tool_wrapper::McpToolWrapper;
-pub use search_tool::{SearchMatch, SearchResult, SearchTool};
+pub use schema::{check_params, validate_params, ParamViolation};
+pub use search_tool::{
+    FileMatches, GrepOptions, GrepStream, SearchMatch, SearchResult, SearchTool,
+    DEFAULT_MAX_FILE_SIZE,
+};
 pub use shell_tool::ShellTool;
 pub use stdio_mcp_client::StdioMcpClient;
+pub use symbols::SymbolMatch;
+pub use truncate::DEFAULT_MAX_RESPONSE_BYTES;
 
+use futures::StreamExt;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
+/// Default limit on a single tool call, generous enough for long shell commands
+pub const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Most requests of one batch that run at the same time
+pub const MAX_CONCURRENT_TOOL_CALLS: usize = 8;
+
+/// Tool requirement that stands for every tool registered from MCP servers
+pub const MCP_TOOLS: &str = "mcp";
+
 /// Tool registry for managing available tools
 pub struct ToolRegistry {
-    tools: Vec<Box<dyn Tool>>,
+    tools: Vec<Arc<dyn Tool>>,
     /// MCP clients (shared across tools from same server)
     mcp_clients: Vec<Arc<Mutex<Box<dyn McpClient>>>>,
+    /// IDs of the tools registered from MCP servers
+    mcp_tools: Vec<String>,
+    /// Responses whose serialized data exceeds this are truncated
+    max_response_bytes: usize,
+    /// Tool calls running longer than this fail
+    tool_timeout: Duration,
 }
 
 impl ToolRegistry {
@@ -41,19 +82,128 @@ impl ToolRegistry {
         Self {
             tools: Vec::new(),
             mcp_clients: Vec::new(),
+            mcp_tools: Vec::new(),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            tool_timeout: DEFAULT_TOOL_TIMEOUT,
         }
     }
 
-    /// Create a tool registry with default tools (filesystem, shell, edit, search)
+    /// Set the maximum serialized size of a response's data, in bytes
+    ///
+    /// Larger responses are cut down and flagged `truncated`.
+    pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    /// Get the maximum serialized size of a response's data, in bytes
+    pub fn max_response_bytes(&self) -> usize {
+        self.max_response_bytes
+    }
+
+    /// Set how long a single tool call may run before it fails
+    pub fn with_tool_timeout(mut self, tool_timeout: Duration) -> Self {
+        self.tool_timeout = tool_timeout;
+        self
+    }
+
+    /// Get how long a single tool call may run before it fails
+    pub fn tool_timeout(&self) -> Duration {
+        self.tool_timeout
+    }
+
+    /// Create a tool registry with default tools (filesystem, shell, edit, search, git)
     pub fn with_defaults() -> Self {
         let mut registry = Self::new();
         registry.register(Box::new(FilesystemTool::new()));
         registry.register(Box::new(ShellTool::new()));
         registry.register(Box::new(EditTool::new()));
         registry.register(Box::new(SearchTool::new()));
+        registry.register(Box::new(GitTool::new()));
+        registry.register(Box::new(JsonTool::new()));
         registry
     }
 
+    /// Create a tool registry with default tools confined to `base_dir`
+    ///
+    /// The filesystem, edit, search, and git tools reject paths outside `base_dir`.
+    pub fn with_defaults_sandboxed(base_dir: impl Into<PathBuf>) -> Self {
+        let base_dir = base_dir.into();
+        let mut registry = Self::new();
+        registry.register(Box::new(FilesystemTool::with_base_dir(&base_dir)));
+        registry.register(Box::new(ShellTool::new()));
+        registry.register(Box::new(EditTool::with_base_dir(&base_dir)));
+        registry.register(Box::new(SearchTool::with_base_dir(&base_dir)));
+        registry.register(Box::new(GitTool::with_base_dir(&base_dir)));
+        registry.register(Box::new(JsonTool::new()));
+        registry
+    }
+
+    /// Copy this registry with its filesystem, edit, search, and git tools confined to `base_dir`
+    ///
+    /// Other tools (e.g., from MCP servers) are shared with this registry.
+    /// The copy does not own MCP connections, so `disconnect_all` on it is a no-op.
+    pub fn sandboxed(&self, base_dir: impl Into<PathBuf>) -> Self {
+        let base_dir = base_dir.into();
+        let tools = self
+            .tools
+            .iter()
+            .map(|tool| -> Arc<dyn Tool> {
+                match tool.id() {
+                    "filesystem" => Arc::new(FilesystemTool::with_base_dir(&base_dir)),
+                    "edit" => Arc::new(EditTool::with_base_dir(&base_dir)),
+                    "search" => Arc::new(SearchTool::with_base_dir(&base_dir)),
+                    "git" => Arc::new(GitTool::with_base_dir(&base_dir)),
+                    _ => Arc::clone(tool),
+                }
+            })
+            .collect();
+
+        Self {
+            tools,
+            mcp_clients: Vec::new(),
+            mcp_tools: self.mcp_tools.clone(),
+            max_response_bytes: self.max_response_bytes,
+            tool_timeout: self.tool_timeout,
+        }
+    }
+
+    /// Copy this registry with only the tools in `tool_ids`
+    ///
+    /// [`MCP_TOOLS`] selects every tool registered from MCP servers, which
+    /// may be none. Fails if any other tool is not registered. Like
+    /// [`Self::sandboxed`], the copy does not own MCP connections.
+    pub fn subset(&self, tool_ids: &[String]) -> bodhya_core::Result<Self> {
+        let mut tools = Vec::new();
+        let mut mcp_tools = Vec::new();
+        for id in tool_ids {
+            if id == MCP_TOOLS {
+                tools.extend(
+                    self.tools
+                        .iter()
+                        .filter(|t| self.mcp_tools.iter().any(|mcp| mcp == t.id()))
+                        .map(Arc::clone),
+                );
+                mcp_tools.extend(self.mcp_tools.iter().cloned());
+                continue;
+            }
+            let tool = self
+                .tools
+                .iter()
+                .find(|t| t.id() == id)
+                .ok_or_else(|| bodhya_core::Error::Tool(format!("Tool '{}' not found", id)))?;
+            tools.push(Arc::clone(tool));
+        }
+
+        Ok(Self {
+            tools,
+            mcp_clients: Vec::new(),
+            mcp_tools,
+            max_response_bytes: self.max_response_bytes,
+            tool_timeout: self.tool_timeout,
+        })
+    }
+
     /// Load MCP servers from configuration and register their tools
     pub async fn load_mcp_servers(
         &mut self,
@@ -77,6 +227,7 @@ impl ToolRegistry {
 
                             // Register each tool
                             for tool_name in tools {
+                                self.mcp_tools.push(tool_name.clone());
                                 let wrapper = McpToolWrapper::new(
                                     tool_name,
                                     client_arc.clone(),
@@ -110,7 +261,7 @@ impl ToolRegistry {
 
     /// Register a tool
     pub fn register(&mut self, tool: Box<dyn Tool>) {
-        self.tools.push(tool);
+        self.tools.push(Arc::from(tool));
     }
 
     /// Get a tool by ID
@@ -127,12 +278,59 @@ impl ToolRegistry {
     }
 
     /// Execute a tool request
+    ///
+    /// Params are checked against the tool's schema for the operation before
+    /// it runs. Dry-run requests are only passed to operations that support
+    /// previews. Responses larger than the size cap are truncated and flagged.
+    /// A call that outlives the tool timeout is abandoned with a tool error.
     pub async fn execute(&self, request: ToolRequest) -> bodhya_core::Result<ToolResponse> {
         let tool = self.get_tool(&request.tool).ok_or_else(|| {
             bodhya_core::Error::Tool(format!("Tool '{}' not found", request.tool))
         })?;
 
-        tool.execute(request).await
+        validate_params(&tool.parameter_schema(&request.operation), &request.params).map_err(
+            |e| match e {
+                bodhya_core::Error::InvalidInput(msg) => bodhya_core::Error::InvalidInput(format!(
+                    "{}.{}: {}",
+                    request.tool, request.operation, msg
+                )),
+                other => other,
+            },
+        )?;
+
+        if request.dry_run && !tool.supports_dry_run(&request.operation) {
+            return Err(bodhya_core::Error::InvalidInput(format!(
+                "{}.{} does not support dry run",
+                request.tool, request.operation
+            )));
+        }
+
+        let call = format!("{}.{}", request.tool, request.operation);
+        let mut response = tokio::time::timeout(self.tool_timeout, tool.execute(request))
+            .await
+            .map_err(|_| {
+                bodhya_core::Error::Tool(format!(
+                    "{} timed out after {:?}",
+                    call, self.tool_timeout
+                ))
+            })??;
+        truncate::truncate_response(&mut response, self.max_response_bytes);
+        Ok(response)
+    }
+
+    /// Execute independent requests concurrently, returning results in request order
+    ///
+    /// At most [`MAX_CONCURRENT_TOOL_CALLS`] requests run at a time. One failed
+    /// request does not stop the others.
+    pub async fn execute_batch(
+        &self,
+        requests: Vec<ToolRequest>,
+    ) -> Vec<bodhya_core::Result<ToolResponse>> {
+        futures::stream::iter(requests)
+            .map(|request| self.execute(request))
+            .buffered(MAX_CONCURRENT_TOOL_CALLS)
+            .collect()
+            .await
     }
 
     /// Disconnect all MCP clients
@@ -171,6 +369,8 @@ mod tests {
         assert!(tools.contains(&"shell".to_string()));
         assert!(tools.contains(&"edit".to_string()));
         assert!(tools.contains(&"search".to_string()));
+        assert!(tools.contains(&"git".to_string()));
+        assert!(tools.contains(&"json".to_string()));
     }
 
     #[test]
@@ -185,6 +385,26 @@ mod tests {
         assert!(nonexistent.is_none());
     }
 
+    #[test]
+    fn test_subset_keeps_only_requested_tools() {
+        let registry = ToolRegistry::with_defaults().with_max_response_bytes(1024);
+
+        let subset = registry
+            .subset(&["shell".to_string(), "filesystem".to_string()])
+            .unwrap();
+        assert_eq!(subset.list_tools(), vec!["shell", "filesystem"]);
+        assert_eq!(subset.max_response_bytes(), 1024);
+
+        let err = registry.subset(&["teleport".to_string()]).err().unwrap();
+        assert!(err.to_string().contains("Tool 'teleport' not found"));
+
+        // Without MCP servers the MCP requirement adds nothing
+        let subset = registry
+            .subset(&["shell".to_string(), MCP_TOOLS.to_string()])
+            .unwrap();
+        assert_eq!(subset.list_tools(), vec!["shell"]);
+    }
+
     #[tokio::test]
     async fn test_tool_registry_execute() {
         let registry = ToolRegistry::with_defaults();
@@ -204,6 +424,193 @@ mod tests {
         assert!(response.success);
     }
 
+    /// Tool whose only operation never finishes in time
+    struct StuckTool;
+
+    #[async_trait::async_trait]
+    impl Tool for StuckTool {
+        fn id(&self) -> &str {
+            "stuck"
+        }
+
+        fn description(&self) -> &str {
+            "Sleeps for a minute"
+        }
+
+        fn supported_operations(&self) -> Vec<String> {
+            vec!["wait".to_string()]
+        }
+
+        async fn execute(&self, _request: ToolRequest) -> bodhya_core::Result<ToolResponse> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(ToolResponse::success(serde_json::json!({})))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_tool_call_times_out() {
+        let mut registry = ToolRegistry::new().with_tool_timeout(Duration::from_millis(50));
+        registry.register(Box::new(StuckTool));
+
+        let request = ToolRequest::new("stuck", "wait", serde_json::json!({}));
+        let err = registry.execute(request).await.unwrap_err();
+
+        assert!(matches!(err, bodhya_core::Error::Tool(_)));
+        assert!(err.to_string().contains("stuck.wait timed out after 50ms"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_batch_preserves_order() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let registry = ToolRegistry::with_defaults();
+        let mut requests = Vec::new();
+        for i in 0..20 {
+            let path = temp_dir.path().join(format!("{}.txt", i));
+            std::fs::write(&path, i.to_string()).unwrap();
+            requests.push(ToolRequest::new(
+                "filesystem",
+                "read",
+                serde_json::json!({ "path": path.to_str().unwrap() }),
+            ));
+        }
+        requests.insert(5, ToolRequest::new("teleport", "go", serde_json::json!({})));
+
+        let results = registry.execute_batch(requests).await;
+        assert_eq!(results.len(), 21);
+        assert!(results[5].is_err());
+
+        let contents: Vec<String> = results
+            .into_iter()
+            .filter_map(|r| r.ok())
+            .map(|r| r.data["content"].as_str().unwrap().to_string())
+            .collect();
+        let expected: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+        assert_eq!(contents, expected);
+    }
+
+    #[tokio::test]
+    async fn test_huge_file_read_is_truncated() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("huge.txt");
+        std::fs::write(&path, "x".repeat(100_000)).unwrap();
+
+        let registry = ToolRegistry::with_defaults().with_max_response_bytes(10_000);
+        let request = ToolRequest::new(
+            "filesystem",
+            "read",
+            serde_json::json!({ "path": path.to_str().unwrap() }),
+        );
+        let response = registry.execute(request).await.unwrap();
+
+        assert!(response.success);
+        assert!(response.truncated);
+        assert_eq!(response.data["truncated"], true);
+        assert_eq!(response.data["size"], 100_000);
+        assert!(serde_json::to_string(&response.data).unwrap().len() <= 10_000);
+
+        let content = response.data["content"].as_str().unwrap();
+        assert!(content.starts_with("xxx"));
+        assert!(content.ends_with("[truncated]"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_validates_params_against_schema() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.txt");
+        let registry = ToolRegistry::with_defaults();
+
+        let valid = ToolRequest::new(
+            "filesystem",
+            "write",
+            serde_json::json!({ "path": path.to_str().unwrap(), "content": "hi" }),
+        );
+        assert!(registry.execute(valid).await.unwrap().success);
+
+        // Wrong type for `args`: rejected before the command runs
+        let invalid = ToolRequest::new(
+            "shell",
+            "exec",
+            serde_json::json!({ "command": "echo", "args": 42 }),
+        );
+        let err = registry.execute(invalid).await.unwrap_err();
+        assert!(matches!(&err, bodhya_core::Error::InvalidInput(msg)
+            if msg.contains("shell.exec") && msg.contains("'args' should be array or string")));
+
+        let schema = registry
+            .get_tool("filesystem")
+            .unwrap()
+            .parameter_schema("write");
+        assert_eq!(schema["required"], serde_json::json!(["path", "content"]));
+    }
+
+    #[tokio::test]
+    async fn test_write_missing_content_is_invalid_input() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("out.txt");
+        let registry = ToolRegistry::with_defaults();
+
+        let request = ToolRequest::new(
+            "filesystem",
+            "write",
+            serde_json::json!({ "path": path.to_str().unwrap() }),
+        );
+        let err = registry.execute(request).await.unwrap_err();
+
+        assert!(matches!(&err, bodhya_core::Error::InvalidInput(msg)
+            if msg.contains("filesystem.write") && msg.contains("missing 'content'")));
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_grep_missing_pattern_is_invalid_input() {
+        let registry = ToolRegistry::with_defaults();
+
+        let request = ToolRequest::new(
+            "search",
+            "grep",
+            serde_json::json!({ "path": ".", "context_lines": "two" }),
+        );
+        let err = registry.execute(request).await.unwrap_err().to_string();
+
+        assert!(err.contains("search.grep"));
+        assert!(err.contains("missing 'pattern'"));
+        assert!(err.contains("'context_lines' should be integer, got string"));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_write_leaves_disk_untouched() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("new/out.txt");
+        let registry = ToolRegistry::with_defaults();
+
+        let request = ToolRequest::new(
+            "filesystem",
+            "write",
+            serde_json::json!({ "path": path.to_str().unwrap(), "content": "hello" }),
+        )
+        .with_dry_run(true);
+        let response = registry.execute(request).await.unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.data["dry_run"], true);
+        assert_eq!(response.data["written"], false);
+        assert_eq!(response.data["size"], 5);
+        assert!(!path.exists());
+        assert!(!path.parent().unwrap().exists());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_rejected_without_preview() {
+        let registry = ToolRegistry::with_defaults();
+
+        let request = ToolRequest::new("shell", "exec", serde_json::json!({ "command": "true" }))
+            .with_dry_run(true);
+        let err = registry.execute(request).await.unwrap_err();
+
+        assert!(matches!(&err, bodhya_core::Error::InvalidInput(msg)
+            if msg.contains("does not support dry run")));
+    }
+
     #[tokio::test]
     async fn test_tool_registry_execute_nonexistent_tool() {
         let registry = ToolRegistry::with_defaults();
@@ -223,4 +630,52 @@ mod tests {
         assert_eq!(registry.list_tools().len(), 1);
         assert!(registry.get_tool("filesystem").is_some());
     }
+
+    #[tokio::test]
+    async fn test_sandboxed_registry_rejects_outside_writes() {
+        let sandbox = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        let registry = ToolRegistry::with_defaults_sandboxed(sandbox.path());
+
+        let inside = registry
+            .execute(ToolRequest::new(
+                "filesystem",
+                "write",
+                serde_json::json!({ "path": "ok.txt", "content": "ok" }),
+            ))
+            .await
+            .unwrap();
+        assert!(inside.success);
+        assert!(sandbox.path().join("ok.txt").exists());
+
+        let target = outside.path().join("escape.txt");
+        let escaped = registry
+            .execute(ToolRequest::new(
+                "filesystem",
+                "write",
+                serde_json::json!({ "path": target.to_str().unwrap(), "content": "x" }),
+            ))
+            .await;
+        assert!(escaped.is_err());
+        assert!(!target.exists());
+
+        let search = registry
+            .execute(ToolRequest::new(
+                "search",
+                "grep",
+                serde_json::json!({ "path": outside.path().to_str().unwrap(), "pattern": "x" }),
+            ))
+            .await;
+        assert!(search.is_err());
+    }
+
+    #[test]
+    fn test_sandboxed_copy_keeps_tool_set() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(FilesystemTool::new()));
+        registry.register(Box::new(ShellTool::new()));
+
+        let sandboxed = registry.sandboxed(std::env::temp_dir());
+        assert_eq!(sandboxed.list_tools(), registry.list_tools());
+    }
 }
@@ -0,0 +1,259 @@
+//! Changed-line reports for edits
+//!
+//! An approval step needs to show which lines an edit touches, not just the
+//! new file. The original and modified contents are diffed line by line
+//! (longest common subsequence) and each changed line is reported with its
+//! text before and after. The same edit script backs the unified diffs of
+//! the code agent's write previews.
+
+use serde::{Deserialize, Serialize};
+
+/// Largest diff table computed exactly; bigger changes are reported as one
+/// block of replaced lines
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// A line an edit changed, inserted, or deleted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedLine {
+    /// 1-indexed line in the original file, or in the modified file for
+    /// inserted lines
+    pub line_number: usize,
+    /// The line before the edit (None if it was inserted)
+    pub before: Option<String>,
+    /// The line after the edit (None if it was deleted)
+    pub after: Option<String>,
+}
+
+/// How a line of an edit script relates the two files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineOp {
+    /// The line is in both files
+    Equal,
+    /// The line is only in the original
+    Delete,
+    /// The line is only in the modified file
+    Insert,
+}
+
+/// One line of an edit script, with its 0-indexed position in both files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineEdit<'a> {
+    /// Whether the line is kept, deleted, or inserted
+    pub op: LineOp,
+    /// The line's text
+    pub line: &'a str,
+    /// Lines of the original before this one
+    pub old_pos: usize,
+    /// Lines of the modified file before this one
+    pub new_pos: usize,
+}
+
+/// Line-level edit script turning `old` into `new`
+///
+/// Deletions come before insertions within a run of changes, as in
+/// `diff -u`. Inputs too large to diff exactly have their whole middle
+/// (between the common head and tail) replaced.
+pub fn line_edits<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<LineEdit<'a>> {
+    // Unchanged head and tail need no diffing
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut edits = Vec::with_capacity(old.len() + new.len());
+    let equal = |old_pos: usize, new_pos: usize| LineEdit {
+        op: LineOp::Equal,
+        line: old[old_pos],
+        old_pos,
+        new_pos,
+    };
+    edits.extend((0..prefix).map(|k| equal(k, k)));
+
+    let table = lcs_table(old_mid, new_mid);
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        let op = match &table {
+            Some(_) if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] => {
+                LineOp::Equal
+            }
+            Some(table)
+                if i < old_mid.len()
+                    && (j == new_mid.len() || table[i + 1][j] >= table[i][j + 1]) =>
+            {
+                LineOp::Delete
+            }
+            Some(_) => LineOp::Insert,
+            // Too large to diff exactly: everything in the middle changed
+            None if i < old_mid.len() => LineOp::Delete,
+            None => LineOp::Insert,
+        };
+        edits.push(LineEdit {
+            op,
+            line: if op == LineOp::Insert {
+                new_mid[j]
+            } else {
+                old_mid[i]
+            },
+            old_pos: prefix + i,
+            new_pos: prefix + j,
+        });
+        match op {
+            LineOp::Equal => {
+                i += 1;
+                j += 1;
+            }
+            LineOp::Delete => i += 1,
+            LineOp::Insert => j += 1,
+        }
+    }
+
+    let (old_tail, new_tail) = (old.len() - suffix, new.len() - suffix);
+    edits.extend((0..suffix).map(|k| equal(old_tail + k, new_tail + k)));
+    edits
+}
+
+/// Lines that differ between `original` and `modified`, in file order
+///
+/// Within each run of differing lines, deleted and inserted lines are paired
+/// up as changed lines; any left over are reported as pure deletions or
+/// insertions.
+pub fn changed_lines(original: &str, modified: &str) -> Vec<ChangedLine> {
+    let old: Vec<&str> = original.lines().collect();
+    let new: Vec<&str> = modified.lines().collect();
+
+    let mut changes = Vec::new();
+    let mut hunk = Hunk::default();
+    for edit in line_edits(&old, &new) {
+        match edit.op {
+            LineOp::Equal => hunk.flush(&mut changes),
+            LineOp::Delete => hunk.deleted.push((edit.old_pos + 1, edit.line)),
+            LineOp::Insert => hunk.inserted.push((edit.new_pos + 1, edit.line)),
+        }
+    }
+    hunk.flush(&mut changes);
+
+    changes
+}
+
+/// `table[i][j]` is the length of the longest common subsequence of
+/// `old[i..]` and `new[j..]`, or None if the table would be too large
+fn lcs_table(old: &[&str], new: &[&str]) -> Option<Vec<Vec<u32>>> {
+    if (old.len() + 1).saturating_mul(new.len() + 1) > MAX_DIFF_CELLS {
+        return None;
+    }
+
+    let mut table = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            table[i][j] = if old[i] == new[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    Some(table)
+}
+
+/// A run of deleted and inserted lines, with their line numbers
+#[derive(Default)]
+struct Hunk<'a> {
+    deleted: Vec<(usize, &'a str)>,
+    inserted: Vec<(usize, &'a str)>,
+}
+
+impl Hunk<'_> {
+    /// Report the run as changed lines and start a new one
+    fn flush(&mut self, changes: &mut Vec<ChangedLine>) {
+        let paired = self.deleted.len().min(self.inserted.len());
+        for (k, &(line_number, before)) in self.deleted.iter().enumerate() {
+            changes.push(ChangedLine {
+                line_number,
+                before: Some(before.to_string()),
+                after: self.inserted.get(k).map(|(_, after)| after.to_string()),
+            });
+        }
+        for &(line_number, after) in &self.inserted[paired..] {
+            changes.push(ChangedLine {
+                line_number,
+                before: None,
+                after: Some(after.to_string()),
+            });
+        }
+        self.deleted.clear();
+        self.inserted.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modified_lines_are_paired() {
+        let changes = changed_lines("a\nb\nc\nd\n", "a\nB\nc\nD\n");
+
+        assert_eq!(
+            changes,
+            vec![
+                ChangedLine {
+                    line_number: 2,
+                    before: Some("b".to_string()),
+                    after: Some("B".to_string()),
+                },
+                ChangedLine {
+                    line_number: 4,
+                    before: Some("d".to_string()),
+                    after: Some("D".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insertions_and_deletions() {
+        let inserted = changed_lines("a\nc\n", "a\nb\nc\n");
+        assert_eq!(
+            inserted,
+            vec![ChangedLine {
+                line_number: 2,
+                before: None,
+                after: Some("b".to_string()),
+            }]
+        );
+
+        let deleted = changed_lines("a\nb\nc\n", "a\nc\n");
+        assert_eq!(
+            deleted,
+            vec![ChangedLine {
+                line_number: 2,
+                before: Some("b".to_string()),
+                after: None,
+            }]
+        );
+
+        assert!(changed_lines("same\n", "same\n").is_empty());
+    }
+
+    #[test]
+    fn test_oversized_diff_replaces_middle() {
+        let old: Vec<String> = (0..2100).map(|i| format!("old {}", i)).collect();
+        let new: Vec<String> = (0..2100).map(|i| format!("new {}", i)).collect();
+        let mut old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let mut new: Vec<&str> = new.iter().map(String::as_str).collect();
+        old.insert(0, "head");
+        new.insert(0, "head");
+
+        let edits = line_edits(&old, &new);
+
+        assert_eq!(edits[0].op, LineOp::Equal);
+        assert!(edits[1..2101].iter().all(|e| e.op == LineOp::Delete));
+        assert!(edits[2101..].iter().all(|e| e.op == LineOp::Insert));
+        assert_eq!(edits.len(), 1 + 2 * 2100);
+    }
+}
@@ -0,0 +1,85 @@
+/// Path sandboxing shared by the filesystem, edit, and search tools
+use bodhya_core::{Error, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// Resolve `path` against `base_dir`, rejecting anything outside it
+///
+/// Relative paths are joined onto `base_dir`. Existing path components are
+/// canonicalized one at a time (so symlinks cannot escape) and `..` is applied
+/// to the resolved prefix, so paths to files that don't exist yet are checked
+/// as strictly as existing ones.
+pub(crate) fn resolve_within(base_dir: &Path, path: &Path) -> Result<PathBuf> {
+    let canonical_base = base_dir
+        .canonicalize()
+        .map_err(|e| Error::Tool(format!("Failed to canonicalize base dir: {}", e)))?;
+
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        canonical_base.join(path)
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => resolved.push(component.as_os_str()),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(name) => {
+                resolved.push(name);
+                if resolved.exists() {
+                    resolved = resolved
+                        .canonicalize()
+                        .map_err(|e| Error::Tool(format!("Failed to canonicalize path: {}", e)))?;
+                }
+            }
+        }
+    }
+
+    if !resolved.starts_with(&canonical_base) {
+        return Err(Error::Tool(format!(
+            "Path '{}' is outside base directory",
+            resolved.display()
+        )));
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_relative_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let resolved = resolve_within(temp_dir.path(), Path::new("src/new.rs")).unwrap();
+        assert_eq!(
+            resolved,
+            temp_dir.path().canonicalize().unwrap().join("src/new.rs")
+        );
+    }
+
+    #[test]
+    fn test_rejects_parent_escape_through_missing_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join("sandbox");
+        std::fs::create_dir(&base).unwrap();
+
+        assert!(resolve_within(&base, Path::new("../outside/new.txt")).is_err());
+        assert!(resolve_within(&base, Path::new("missing/../../escape.txt")).is_err());
+        assert!(resolve_within(&base, Path::new("missing/../inside.txt")).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_absolute_path_outside() {
+        let base = TempDir::new().unwrap();
+        let other = TempDir::new().unwrap();
+
+        assert!(resolve_within(base.path(), &other.path().join("file.txt")).is_err());
+        assert!(resolve_within(base.path(), &base.path().join("file.txt")).is_ok());
+    }
+}
@@ -0,0 +1,326 @@
+//! Parameter schemas for tool operations
+//!
+//! Tools describe each operation's params as a JSON Schema so requests can be
+//! checked before a tool runs and models can be told how to call it. Only
+//! the subset the builtin tools use is checked: `type` (a name or a list of
+//! names), `properties`, `required`, and `items`, applied recursively.
+
+use bodhya_core::{Error, Result};
+use serde_json::{json, Map, Value};
+
+/// Schema of an object whose `properties` map names to JSON types
+///
+/// A type may be a single name (e.g., "string") or a list of names.
+pub(crate) fn object_schema(properties: &[(&str, Value)], required: &[&str]) -> Value {
+    let properties: Map<String, Value> = properties
+        .iter()
+        .map(|(name, ty)| (name.to_string(), json!({ "type": ty })))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/// A field of a request's params that does not match the schema
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParamViolation {
+    /// A required field is absent
+    Missing {
+        /// Dotted path of the field (e.g., "content")
+        field: String,
+    },
+    /// A field has the wrong JSON type
+    WrongType {
+        /// Dotted path of the field
+        field: String,
+        /// Type(s) the schema allows (e.g., "string or array")
+        expected: String,
+        /// Type the field has
+        found: String,
+    },
+}
+
+impl std::fmt::Display for ParamViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing { field } => write!(f, "missing '{}'", field),
+            Self::WrongType {
+                field,
+                expected,
+                found,
+            } => write!(f, "'{}' should be {}, got {}", field, expected, found),
+        }
+    }
+}
+
+/// Every way `params` fails to match `schema`
+pub fn check_params(schema: &Value, params: &Value) -> Vec<ParamViolation> {
+    // Requests without params deserialize to null; treat them as `{}`
+    let empty = Value::Object(Map::new());
+    let params = if params.is_null() { &empty } else { params };
+
+    let mut violations = Vec::new();
+    collect_violations(schema, params, "", "params", &mut violations);
+    violations
+}
+
+/// Every way an arbitrary JSON `value` fails to match `schema`
+///
+/// Unlike [`check_params`], null is not treated as an empty object, and a
+/// mistyped root is reported as `$`.
+pub(crate) fn check_value(schema: &Value, value: &Value) -> Vec<ParamViolation> {
+    let mut violations = Vec::new();
+    collect_violations(schema, value, "", "$", &mut violations);
+    violations
+}
+
+/// Keywords that only annotate a schema and never reject a value
+const ANNOTATIONS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$comment",
+    "title",
+    "description",
+    "default",
+    "examples",
+];
+
+/// Keywords [`check_value`] enforces
+const CHECKED: &[&str] = &["type", "properties", "required", "items"];
+
+/// Constraining keywords in `schema` that [`check_value`] would ignore
+///
+/// Each entry names the keyword and where it appears (e.g.,
+/// "'minimum' at $.properties.age"), so a caller can refuse a schema it
+/// cannot fully check instead of reporting values as valid.
+pub(crate) fn unsupported_keywords(schema: &Value) -> Vec<String> {
+    let mut found = Vec::new();
+    collect_unsupported(schema, "$", &mut found);
+    found
+}
+
+fn collect_unsupported(schema: &Value, path: &str, found: &mut Vec<String>) {
+    let Value::Object(keywords) = schema else {
+        return;
+    };
+
+    for (keyword, value) in keywords {
+        if ANNOTATIONS.contains(&keyword.as_str()) {
+            continue;
+        }
+        if !CHECKED.contains(&keyword.as_str()) {
+            found.push(format!("'{}' at {}", keyword, path));
+            continue;
+        }
+        match keyword.as_str() {
+            "items" => collect_unsupported(value, &format!("{}.items", path), found),
+            "properties" => {
+                for (name, property) in value.as_object().into_iter().flatten() {
+                    let property_path = format!("{}.properties.{}", path, name);
+                    collect_unsupported(property, &property_path, found);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Check `params` against `schema`
+///
+/// Fails with `InvalidInput` listing every missing and mistyped field.
+pub fn validate_params(schema: &Value, params: &Value) -> Result<()> {
+    let violations = check_params(schema, params);
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    let listed: Vec<String> = violations.iter().map(ToString::to_string).collect();
+    Err(Error::InvalidInput(format!(
+        "invalid params: {}",
+        listed.join("; ")
+    )))
+}
+
+fn collect_violations(
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    root: &str,
+    violations: &mut Vec<ParamViolation>,
+) {
+    if let Some(expected) = schema.get("type") {
+        if !matches_type(expected, value) {
+            violations.push(ParamViolation::WrongType {
+                field: if path.is_empty() { root } else { path }.to_string(),
+                expected: type_names(expected),
+                found: type_of(value).to_string(),
+            });
+            return;
+        }
+    }
+
+    if let (Some(items), Value::Array(elements)) = (schema.get("items"), value) {
+        for (index, element) in elements.iter().enumerate() {
+            let element_path = format!("{}[{}]", path, index);
+            collect_violations(items, element, &element_path, root, violations);
+        }
+    }
+
+    let Value::Object(fields) = value else {
+        return;
+    };
+    let field_path = |name: &str| {
+        if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", path, name)
+        }
+    };
+
+    for name in schema["required"].as_array().into_iter().flatten() {
+        let name = name.as_str().unwrap_or_default();
+        if !fields.contains_key(name) {
+            violations.push(ParamViolation::Missing {
+                field: field_path(name),
+            });
+        }
+    }
+
+    for (name, property) in schema["properties"].as_object().into_iter().flatten() {
+        if let Some(field) = fields.get(name) {
+            collect_violations(property, field, &field_path(name), root, violations);
+        }
+    }
+}
+
+/// Whether `value` has the JSON type named by `expected` (or one of them)
+fn matches_type(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(name) => match name.as_str() {
+            "integer" => value.is_u64() || value.is_i64(),
+            name => name == type_of(value),
+        },
+        Value::Array(names) => names.iter().any(|name| matches_type(name, value)),
+        _ => true,
+    }
+}
+
+fn type_names(expected: &Value) -> String {
+    match expected {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        other => other.as_str().unwrap_or("any").to_string(),
+    }
+}
+
+/// JSON Schema type name of `value`
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_schema() -> Value {
+        object_schema(
+            &[
+                ("path", json!("string")),
+                ("content", json!("string")),
+                ("backup", json!("boolean")),
+            ],
+            &["path", "content"],
+        )
+    }
+
+    #[test]
+    fn test_valid_params() {
+        let params = json!({ "path": "src/lib.rs", "content": "fn main() {}", "backup": true });
+        assert!(validate_params(&write_schema(), &params).is_ok());
+        assert!(validate_params(&json!({ "type": "object" }), &Value::Null).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let err = validate_params(&write_schema(), &json!({ "path": "a.rs" })).unwrap_err();
+        assert!(matches!(&err, Error::InvalidInput(msg) if msg.contains("missing 'content'")));
+
+        let err = validate_params(&write_schema(), &json!({ "path": 1, "content": "" }))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("'path' should be string, got number"));
+    }
+
+    #[test]
+    fn test_all_violations_listed() {
+        let violations = check_params(&write_schema(), &json!({ "backup": "yes" }));
+        assert_eq!(
+            violations,
+            vec![
+                ParamViolation::Missing {
+                    field: "path".to_string()
+                },
+                ParamViolation::Missing {
+                    field: "content".to_string()
+                },
+                ParamViolation::WrongType {
+                    field: "backup".to_string(),
+                    expected: "boolean".to_string(),
+                    found: "string".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_integer_and_union_types() {
+        let schema = object_schema(
+            &[
+                ("lines", json!("integer")),
+                ("args", json!(["array", "string"])),
+            ],
+            &[],
+        );
+        assert!(validate_params(&schema, &json!({ "lines": 3, "args": "-v" })).is_ok());
+        assert!(validate_params(&schema, &json!({ "lines": 1.5 })).is_err());
+        assert!(validate_params(&schema, &json!({ "args": true })).is_err());
+    }
+
+    #[test]
+    fn test_array_items_checked() {
+        let schema = json!({
+            "type": "array",
+            "items": object_schema(&[("name", json!("string"))], &["name"]),
+        });
+        let violations = check_value(&schema, &json!([{ "name": "a" }, { "name": 2 }, {}]));
+        assert_eq!(
+            violations,
+            vec![
+                ParamViolation::WrongType {
+                    field: "[1].name".to_string(),
+                    expected: "string".to_string(),
+                    found: "number".to_string(),
+                },
+                ParamViolation::Missing {
+                    field: "[2].name".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            check_value(&schema, &Value::Null)[0].to_string(),
+            "'$' should be array, got null"
+        );
+    }
+}
@@ -1,19 +1,32 @@
 /// SearchTool - Code search with grep and pattern matching
 ///
 /// Provides search capabilities including:
-/// - Recursive grep with regex support
+/// - Recursive grep with regex support, for one or several patterns at once
 /// - File pattern filtering
 /// - Line number tracking
 /// - Context lines (before/after)
+/// - Depth limit, with symlink loops searched only once
+/// - Large and binary files skipped unless asked for
+/// - Rust symbol definitions (falls back to grep for other files)
+use crate::sandbox;
+use crate::schema::object_schema;
+use crate::symbols::{find_definitions, SymbolMatch};
 use async_trait::async_trait;
 use bodhya_core::{Result, Tool, ToolRequest, ToolResponse};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 use tokio::fs;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 /// SearchTool provides code search capabilities
-pub struct SearchTool;
+pub struct SearchTool {
+    /// Base directory for sandboxing (optional)
+    base_dir: Option<PathBuf>,
+}
 
 /// A single search match
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +35,9 @@ pub struct SearchMatch {
     pub line_number: usize,
     pub line_content: String,
     pub column: usize,
+    /// The pattern this line matched, as given to the search
+    #[serde(default)]
+    pub matched_pattern: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub context_before: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -38,9 +54,170 @@ pub struct SearchResult {
     pub error: Option<String>,
 }
 
+impl SearchResult {
+    /// The matches grouped by file, sorted by path
+    ///
+    /// Matches keep their order within each file.
+    pub fn group_by_file(&self) -> Vec<FileMatches> {
+        let mut by_file: BTreeMap<&Path, Vec<SearchMatch>> = BTreeMap::new();
+        for mat in &self.matches {
+            by_file
+                .entry(mat.file_path.as_path())
+                .or_default()
+                .push(mat.clone());
+        }
+
+        by_file
+            .into_iter()
+            .map(|(file_path, matches)| FileMatches {
+                file_path: file_path.to_path_buf(),
+                count: matches.len(),
+                matches,
+            })
+            .collect()
+    }
+}
+
+/// The matches found in one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMatches {
+    pub file_path: PathBuf,
+    /// Number of matches in the file
+    pub count: usize,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Files larger than this are skipped by default (10 MiB)
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Bytes sniffed for a NUL byte to decide whether a file is binary
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Options for [`SearchTool::grep_with_options`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrepOptions {
+    /// Search subdirectories as well
+    pub recursive: bool,
+    /// Match the pattern case-sensitively
+    pub case_sensitive: bool,
+    /// Only search files whose names match this glob
+    pub file_pattern: Option<String>,
+    /// Lines of context to include before and after each match
+    pub context_lines: usize,
+    /// Deepest subdirectory level searched below the starting directory
+    /// (0 = unlimited)
+    pub max_depth: usize,
+    /// Skip files larger than this many bytes (0 = unlimited)
+    pub max_file_size: u64,
+    /// Search files that look binary instead of skipping them
+    pub include_binary: bool,
+}
+
+impl GrepOptions {
+    /// Options with the given settings, no depth limit and the default file filters
+    pub fn new(
+        recursive: bool,
+        case_sensitive: bool,
+        file_pattern: Option<&str>,
+        context_lines: usize,
+    ) -> Self {
+        Self {
+            recursive,
+            case_sensitive,
+            file_pattern: file_pattern.map(str::to_string),
+            context_lines,
+            max_depth: 0,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            include_binary: false,
+        }
+    }
+
+    /// Stop recursing `max_depth` levels below the starting directory (0 = unlimited)
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Skip files larger than `max_file_size` bytes (0 = unlimited)
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Search binary files too, rather than skipping them
+    pub fn with_include_binary(mut self, include_binary: bool) -> Self {
+        self.include_binary = include_binary;
+        self
+    }
+}
+
+impl Default for GrepOptions {
+    fn default() -> Self {
+        Self::new(true, false, None, 0)
+    }
+}
+
+/// Matches buffered between the directory walk and a [`GrepStream`] reader
+///
+/// The walk pauses when the buffer is full, so a slow reader bounds memory.
+const STREAM_BUFFER: usize = 64;
+
+/// Grep matches delivered while the search is still walking the tree
+pub struct GrepStream {
+    matches: mpsc::Receiver<SearchMatch>,
+    walk: JoinHandle<Result<usize>>,
+    /// Matches already taken with `next`
+    received: usize,
+}
+
+impl GrepStream {
+    /// Next match, or `None` once the walk is complete and every match was taken
+    pub async fn next(&mut self) -> Option<SearchMatch> {
+        let mat = self.matches.recv().await?;
+        self.received += 1;
+        Some(mat)
+    }
+
+    /// Whether the walk has finished (matches may still be buffered)
+    pub fn is_finished(&self) -> bool {
+        self.walk.is_finished()
+    }
+
+    /// Wait for the walk to complete
+    ///
+    /// The result holds the matches not yet taken with [`GrepStream::next`];
+    /// `total_matches` and `files_searched` cover the whole search.
+    pub async fn finish(mut self) -> Result<SearchResult> {
+        let mut matches = Vec::new();
+        while let Some(mat) = self.matches.recv().await {
+            matches.push(mat);
+        }
+
+        let files_searched = self
+            .walk
+            .await
+            .map_err(|e| bodhya_core::Error::Tool(format!("Search task failed: {}", e)))??;
+
+        Ok(SearchResult {
+            success: true,
+            total_matches: self.received + matches.len(),
+            matches,
+            files_searched,
+            error: None,
+        })
+    }
+}
+
 impl SearchTool {
     pub fn new() -> Self {
-        Self
+        Self { base_dir: None }
+    }
+
+    /// Create a search tool that only searches under `base_dir`
+    pub fn with_base_dir(base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_dir: Some(base_dir.into()),
+        }
     }
 
     /// Perform grep search in a directory
@@ -53,130 +230,354 @@ impl SearchTool {
         file_pattern: Option<&str>,
         context_lines: usize,
     ) -> Result<SearchResult> {
-        let path = path.as_ref();
+        self.grep_with_options(
+            path,
+            pattern,
+            &GrepOptions::new(recursive, case_sensitive, file_pattern, context_lines),
+        )
+        .await
+    }
 
-        // Compile regex pattern
-        let regex_pattern = if case_sensitive {
-            pattern
-        } else {
-            &format!("(?i){}", pattern)
-        };
+    /// Perform grep search in a directory with the given options
+    pub async fn grep_with_options(
+        &self,
+        path: impl AsRef<Path>,
+        pattern: &str,
+        options: &GrepOptions,
+    ) -> Result<SearchResult> {
+        self.grep_stream_with_options(path, pattern, options)
+            .await?
+            .finish()
+            .await
+    }
 
-        let regex = Regex::new(regex_pattern)
-            .map_err(|e| bodhya_core::Error::Tool(format!("Invalid regex pattern: {}", e)))?;
+    /// Start a grep search whose matches are delivered as they are found
+    ///
+    /// Invalid patterns and missing paths fail here; errors during the walk
+    /// are returned by [`GrepStream::finish`].
+    pub async fn grep_stream(
+        &self,
+        path: impl AsRef<Path>,
+        pattern: &str,
+        recursive: bool,
+        case_sensitive: bool,
+        file_pattern: Option<&str>,
+        context_lines: usize,
+    ) -> Result<GrepStream> {
+        self.grep_stream_with_options(
+            path,
+            pattern,
+            &GrepOptions::new(recursive, case_sensitive, file_pattern, context_lines),
+        )
+        .await
+    }
 
-        let file_filter = file_pattern.map(|p| {
-            glob::Pattern::new(p)
-                .map_err(|e| bodhya_core::Error::Tool(format!("Invalid file pattern: {}", e)))
-        });
+    /// Start a streaming grep search with the given options
+    pub async fn grep_stream_with_options(
+        &self,
+        path: impl AsRef<Path>,
+        pattern: &str,
+        options: &GrepOptions,
+    ) -> Result<GrepStream> {
+        self.grep_stream_any(path, &[pattern], options).await
+    }
 
-        if let Some(Err(e)) = file_filter {
-            return Err(e);
-        }
+    /// Search for any of `patterns` in a single walk of the tree
+    ///
+    /// Each match records the pattern it hit; a line matching several
+    /// patterns is reported once per pattern.
+    pub async fn grep_any(
+        &self,
+        path: impl AsRef<Path>,
+        patterns: &[&str],
+        options: &GrepOptions,
+    ) -> Result<SearchResult> {
+        self.grep_stream_any(path, patterns, options)
+            .await?
+            .finish()
+            .await
+    }
 
-        let file_filter = file_filter.map(|r| r.unwrap());
+    /// Start a streaming search for any of `patterns`
+    pub async fn grep_stream_any(
+        &self,
+        path: impl AsRef<Path>,
+        patterns: &[&str],
+        options: &GrepOptions,
+    ) -> Result<GrepStream> {
+        let path = match &self.base_dir {
+            Some(base) => sandbox::resolve_within(base, path.as_ref())?,
+            None => path.as_ref().to_path_buf(),
+        };
 
-        let mut matches = Vec::new();
-        let mut files_searched = 0;
+        if patterns.is_empty() {
+            return Err(bodhya_core::Error::Tool(
+                "At least one search pattern is required".to_string(),
+            ));
+        }
 
-        if path.is_file() {
-            if let Ok(file_matches) = self.search_file(path, &regex, context_lines).await {
-                matches.extend(file_matches);
-                files_searched += 1;
-            }
-        } else if path.is_dir() {
-            if recursive {
-                self.search_directory_recursive(
-                    path,
-                    &regex,
-                    &file_filter,
-                    context_lines,
-                    &mut matches,
-                    &mut files_searched,
-                )
-                .await?;
-            } else {
-                self.search_directory_shallow(
-                    path,
-                    &regex,
-                    &file_filter,
-                    context_lines,
-                    &mut matches,
-                    &mut files_searched,
-                )
-                .await?;
-            }
-        } else {
+        // Compile regex patterns
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                let regex_pattern = if options.case_sensitive {
+                    pattern.to_string()
+                } else {
+                    format!("(?i){}", pattern)
+                };
+                Regex::new(&regex_pattern)
+                    .map(|regex| (pattern.to_string(), regex))
+                    .map_err(|e| bodhya_core::Error::Tool(format!("Invalid regex pattern: {}", e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let file_filter = options
+            .file_pattern
+            .as_deref()
+            .map(|p| {
+                glob::Pattern::new(p)
+                    .map_err(|e| bodhya_core::Error::Tool(format!("Invalid file pattern: {}", e)))
+            })
+            .transpose()?;
+
+        if !path.is_file() && !path.is_dir() {
             return Err(bodhya_core::Error::Tool(format!(
                 "Path does not exist: {}",
                 path.display()
             )));
         }
 
-        Ok(SearchResult {
-            success: true,
-            total_matches: matches.len(),
-            matches,
-            files_searched,
-            error: None,
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let mut walker = Walker {
+            patterns,
+            file_filter,
+            context_lines: options.context_lines,
+            recursive: options.recursive,
+            max_depth: options.max_depth,
+            max_file_size: options.max_file_size,
+            include_binary: options.include_binary,
+            matches: sender,
+            files_searched: 0,
+            visited: HashSet::new(),
+        };
+        let walk = tokio::spawn(async move {
+            if path.is_file() {
+                walker.search_file(&path).await;
+            } else {
+                walker.search_directory(&path, 0).await?;
+            }
+            Ok(walker.files_searched)
+        });
+
+        Ok(GrepStream {
+            matches: receiver,
+            walk,
+            received: 0,
         })
     }
 
-    /// Search a single file
-    async fn search_file(
+    /// Find where the symbol `name` is defined under `path`
+    ///
+    /// Rust files are parsed, so only definitions are reported. Other files,
+    /// and Rust files that fail to parse, fall back to whole-word grep matches
+    /// of kind `text`. With `kind`, only definitions of that kind are returned.
+    pub async fn find_symbol(
         &self,
-        path: &Path,
-        regex: &Regex,
-        context_lines: usize,
-    ) -> Result<Vec<SearchMatch>> {
-        let content = fs::read_to_string(path)
-            .await
-            .map_err(|e| bodhya_core::Error::Tool(format!("Failed to read file: {}", e)))?;
+        path: impl AsRef<Path>,
+        name: &str,
+        kind: Option<&str>,
+    ) -> Result<Vec<SymbolMatch>> {
+        // Only files that mention the name can define it
+        let pattern = format!(r"\b{}\b", regex::escape(name));
+        let candidates = self.grep(path, &pattern, true, true, None, 0).await?;
+
+        let mut by_file: Vec<(PathBuf, Vec<SearchMatch>)> = Vec::new();
+        for mat in candidates.matches {
+            match by_file.last_mut() {
+                Some((file, lines)) if *file == mat.file_path => lines.push(mat),
+                _ => by_file.push((mat.file_path.clone(), vec![mat])),
+            }
+        }
 
-        let lines: Vec<&str> = content.lines().collect();
-        let mut matches = Vec::new();
+        let mut symbols = Vec::new();
+        for (file, lines) in by_file {
+            let definitions = if file.extension().is_some_and(|ext| ext == "rs") {
+                fs::read_to_string(&file)
+                    .await
+                    .ok()
+                    .and_then(|source| find_definitions(&source, name))
+            } else {
+                None
+            };
+
+            let found: Vec<(usize, &str)> = match definitions {
+                Some(definitions) => definitions,
+                None => lines.iter().map(|m| (m.line_number, "text")).collect(),
+            };
+            symbols.extend(found.into_iter().map(|(line_number, kind)| SymbolMatch {
+                file_path: file.clone(),
+                line_number,
+                kind: kind.to_string(),
+                name: name.to_string(),
+            }));
+        }
 
-        for (idx, line) in lines.iter().enumerate() {
-            if let Some(mat) = regex.find(line) {
-                let context_before = if context_lines > 0 && idx > 0 {
-                    let start = idx.saturating_sub(context_lines);
-                    Some(lines[start..idx].iter().map(|s| s.to_string()).collect())
-                } else {
-                    None
-                };
+        if let Some(kind) = kind {
+            symbols.retain(|symbol| symbol.kind == kind);
+        }
+        Ok(symbols)
+    }
+}
 
-                let context_after = if context_lines > 0 && idx + 1 < lines.len() {
-                    let end = (idx + 1 + context_lines).min(lines.len());
-                    Some(lines[idx + 1..end].iter().map(|s| s.to_string()).collect())
-                } else {
-                    None
-                };
+/// Whether `path` looks binary: a NUL byte in its first few kilobytes
+///
+/// Files that cannot be read count as binary, so they are skipped.
+async fn is_binary(path: &Path) -> bool {
+    use tokio::io::AsyncReadExt;
+
+    let Ok(file) = fs::File::open(path).await else {
+        return true;
+    };
+    let mut head = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    match file
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut head)
+        .await
+    {
+        Ok(_) => head.contains(&0),
+        Err(_) => true,
+    }
+}
+
+/// Search a single file for each of `patterns` (source text and compiled regex)
+///
+/// Invalid UTF-8 is replaced rather than failing, so binary files can be
+/// searched when asked for.
+async fn search_file(
+    path: &Path,
+    patterns: &[(String, Regex)],
+    context_lines: usize,
+) -> Result<Vec<SearchMatch>> {
+    let bytes = fs::read(path)
+        .await
+        .map_err(|e| bodhya_core::Error::Tool(format!("Failed to read file: {}", e)))?;
+    let content = String::from_utf8_lossy(&bytes);
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut matches = Vec::new();
+
+    for (idx, line) in lines.iter().enumerate() {
+        for (pattern, regex) in patterns {
+            let Some(mat) = regex.find(line) else {
+                continue;
+            };
+
+            let context_before = if context_lines > 0 && idx > 0 {
+                let start = idx.saturating_sub(context_lines);
+                Some(lines[start..idx].iter().map(|s| s.to_string()).collect())
+            } else {
+                None
+            };
+
+            let context_after = if context_lines > 0 && idx + 1 < lines.len() {
+                let end = (idx + 1 + context_lines).min(lines.len());
+                Some(lines[idx + 1..end].iter().map(|s| s.to_string()).collect())
+            } else {
+                None
+            };
+
+            matches.push(SearchMatch {
+                file_path: path.to_path_buf(),
+                line_number: idx + 1, // 1-indexed
+                line_content: line.to_string(),
+                column: mat.start() + 1, // 1-indexed
+                matched_pattern: pattern.clone(),
+                context_before,
+                context_after,
+            });
+        }
+    }
+
+    Ok(matches)
+}
 
-                matches.push(SearchMatch {
-                    file_path: path.to_path_buf(),
-                    line_number: idx + 1, // 1-indexed
-                    line_content: line.to_string(),
-                    column: mat.start() + 1, // 1-indexed
-                    context_before,
-                    context_after,
-                });
+/// State of one grep walk over a file tree
+struct Walker {
+    /// Patterns as given, with their compiled regexes
+    patterns: Vec<(String, Regex)>,
+    file_filter: Option<glob::Pattern>,
+    context_lines: usize,
+    recursive: bool,
+    max_depth: usize,
+    max_file_size: u64,
+    include_binary: bool,
+    matches: mpsc::Sender<SearchMatch>,
+    files_searched: usize,
+    /// Canonical paths of directories already searched, to break symlink loops
+    visited: HashSet<PathBuf>,
+}
+
+impl Walker {
+    /// Search one file, sending its matches
+    async fn search_file(&mut self, path: &Path) {
+        if let Ok(file_matches) = search_file(path, &self.patterns, self.context_lines).await {
+            self.files_searched += 1;
+            for mat in file_matches {
+                if self.matches.send(mat).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Search `path` if it passes the file filter and is neither too large
+    /// nor binary
+    async fn search_filtered_file(&mut self, path: &Path) {
+        if let Some(filter) = &self.file_filter {
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                if !filter.matches(name) {
+                    return;
+                }
+            }
+        }
+
+        if self.max_file_size > 0 {
+            match fs::metadata(path).await {
+                Ok(metadata) if metadata.len() > self.max_file_size => {
+                    tracing::debug!("Skipping large file {}", path.display());
+                    return;
+                }
+                Ok(_) => {}
+                Err(_) => return,
             }
         }
 
-        Ok(matches)
+        if !self.include_binary && is_binary(path).await {
+            tracing::debug!("Skipping binary file {}", path.display());
+            return;
+        }
+
+        self.search_file(path).await;
     }
 
-    /// Search directory recursively
-    fn search_directory_recursive<'a>(
-        &'a self,
+    /// Search the files in `path`, which is `depth` levels below the root
+    ///
+    /// Subdirectories are searched when recursive, up to `max_depth` levels
+    /// below the root. Each directory is searched at most once, however many
+    /// symlinks lead to it.
+    fn search_directory<'a>(
+        &'a mut self,
         path: &'a Path,
-        regex: &'a Regex,
-        file_filter: &'a Option<glob::Pattern>,
-        context_lines: usize,
-        matches: &'a mut Vec<SearchMatch>,
-        files_searched: &'a mut usize,
+        depth: usize,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
         Box::pin(async move {
+            if let Ok(canonical) = fs::canonicalize(path).await {
+                if !self.visited.insert(canonical) {
+                    tracing::debug!("Skipping already searched directory {}", path.display());
+                    return Ok(());
+                }
+            }
+
             let mut entries = fs::read_dir(path).await.map_err(|e| {
                 bodhya_core::Error::Tool(format!("Failed to read directory: {}", e))
             })?;
@@ -186,9 +587,18 @@ impl SearchTool {
                 .await
                 .map_err(|e| bodhya_core::Error::Tool(format!("Failed to read entry: {}", e)))?
             {
+                // Stop walking once nobody is listening
+                if self.matches.is_closed() {
+                    return Ok(());
+                }
+
                 let path = entry.path();
 
                 if path.is_dir() {
+                    if !self.recursive || (self.max_depth > 0 && depth >= self.max_depth) {
+                        continue;
+                    }
+
                     // Skip hidden directories
                     if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
                         if name.starts_with('.') {
@@ -197,78 +607,15 @@ impl SearchTool {
                     }
 
                     // Recurse into subdirectory
-                    self.search_directory_recursive(
-                        &path,
-                        regex,
-                        file_filter,
-                        context_lines,
-                        matches,
-                        files_searched,
-                    )
-                    .await?;
+                    self.search_directory(&path, depth + 1).await?;
                 } else if path.is_file() {
-                    // Check file filter
-                    if let Some(filter) = file_filter {
-                        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                            if !filter.matches(name) {
-                                continue;
-                            }
-                        }
-                    }
-
-                    // Search file
-                    if let Ok(file_matches) = self.search_file(&path, regex, context_lines).await {
-                        matches.extend(file_matches);
-                        *files_searched += 1;
-                    }
+                    self.search_filtered_file(&path).await;
                 }
             }
 
             Ok(())
         })
     }
-
-    /// Search directory (non-recursive)
-    async fn search_directory_shallow(
-        &self,
-        path: &Path,
-        regex: &Regex,
-        file_filter: &Option<glob::Pattern>,
-        context_lines: usize,
-        matches: &mut Vec<SearchMatch>,
-        files_searched: &mut usize,
-    ) -> Result<()> {
-        let mut entries = fs::read_dir(path)
-            .await
-            .map_err(|e| bodhya_core::Error::Tool(format!("Failed to read directory: {}", e)))?;
-
-        while let Some(entry) = entries
-            .next_entry()
-            .await
-            .map_err(|e| bodhya_core::Error::Tool(format!("Failed to read entry: {}", e)))?
-        {
-            let path = entry.path();
-
-            if path.is_file() {
-                // Check file filter
-                if let Some(filter) = file_filter {
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        if !filter.matches(name) {
-                            continue;
-                        }
-                    }
-                }
-
-                // Search file
-                if let Ok(file_matches) = self.search_file(&path, regex, context_lines).await {
-                    matches.extend(file_matches);
-                    *files_searched += 1;
-                }
-            }
-        }
-
-        Ok(())
-    }
 }
 
 impl Default for SearchTool {
@@ -288,21 +635,74 @@ impl Tool for SearchTool {
     }
 
     fn supported_operations(&self) -> Vec<String> {
-        vec!["grep".to_string()]
+        vec!["grep".to_string(), "find_symbol".to_string()]
+    }
+
+    fn parameter_schema(&self, operation: &str) -> serde_json::Value {
+        match operation {
+            "grep" => object_schema(
+                &[
+                    ("path", json!("string")),
+                    ("pattern", json!(["string", "array"])),
+                    ("recursive", json!("boolean")),
+                    ("case_sensitive", json!("boolean")),
+                    ("file_pattern", json!("string")),
+                    ("context_lines", json!("integer")),
+                    ("max_depth", json!("integer")),
+                    ("max_file_size", json!("integer")),
+                    ("include_binary", json!("boolean")),
+                ],
+                &["path", "pattern"],
+            ),
+            "find_symbol" => object_schema(
+                &[
+                    ("path", json!("string")),
+                    ("name", json!("string")),
+                    ("kind", json!("string")),
+                ],
+                &["path", "name"],
+            ),
+            _ => json!({ "type": "object" }),
+        }
     }
 
     async fn execute(&self, request: ToolRequest) -> Result<ToolResponse> {
+        match request.operation.as_str() {
+            "grep" => self.execute_grep(request).await,
+            "find_symbol" => self.execute_find_symbol(request).await,
+            _ => Ok(ToolResponse::failure(format!(
+                "Unsupported operation: {}",
+                request.operation
+            ))),
+        }
+    }
+}
+
+impl SearchTool {
+    async fn execute_grep(&self, request: ToolRequest) -> Result<ToolResponse> {
         let path = request
             .params
             .get("path")
             .and_then(|v| v.as_str())
             .ok_or_else(|| bodhya_core::Error::Tool("Missing 'path' parameter".to_string()))?;
 
-        let pattern = request
-            .params
-            .get("pattern")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| bodhya_core::Error::Tool("Missing 'pattern' parameter".to_string()))?;
+        // One pattern, or a list of patterns searched in a single walk
+        let patterns: Vec<&str> = match request.params.get("pattern") {
+            Some(serde_json::Value::String(pattern)) => vec![pattern.as_str()],
+            Some(serde_json::Value::Array(patterns)) => patterns
+                .iter()
+                .map(|p| {
+                    p.as_str().ok_or_else(|| {
+                        bodhya_core::Error::Tool("Patterns must be strings".to_string())
+                    })
+                })
+                .collect::<Result<_>>()?,
+            _ => {
+                return Err(bodhya_core::Error::Tool(
+                    "Missing 'pattern' parameter".to_string(),
+                ))
+            }
+        };
 
         let recursive = request
             .params
@@ -324,22 +724,52 @@ impl Tool for SearchTool {
             .and_then(|v| v.as_u64())
             .unwrap_or(0) as usize;
 
-        let result = self
-            .grep(
-                path,
-                pattern,
-                recursive,
-                case_sensitive,
-                file_pattern,
-                context_lines,
-            )
-            .await?;
+        let max_depth = request
+            .params
+            .get("max_depth")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize;
+
+        let max_file_size = request
+            .params
+            .get("max_file_size")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_MAX_FILE_SIZE);
+
+        let include_binary = request
+            .params
+            .get("include_binary")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let options = GrepOptions::new(recursive, case_sensitive, file_pattern, context_lines)
+            .with_max_depth(max_depth)
+            .with_max_file_size(max_file_size)
+            .with_include_binary(include_binary);
+        let result = self.grep_any(path, &patterns, &options).await?;
 
         let data = serde_json::to_value(result)
             .map_err(|e| bodhya_core::Error::Tool(format!("Failed to serialize result: {}", e)))?;
 
         Ok(ToolResponse::success(data))
     }
+
+    async fn execute_find_symbol(&self, request: ToolRequest) -> Result<ToolResponse> {
+        let path = request.params["path"].as_str().unwrap_or(".");
+        let name = request
+            .params
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| bodhya_core::Error::Tool("Missing 'name' parameter".to_string()))?;
+        let kind = request.params.get("kind").and_then(|v| v.as_str());
+
+        let symbols = self.find_symbol(path, name, kind).await?;
+
+        Ok(ToolResponse::success(json!({
+            "total_matches": symbols.len(),
+            "matches": symbols,
+        })))
+    }
 }
 
 #[cfg(test)]
@@ -380,6 +810,7 @@ mod tests {
         assert_eq!(result.files_searched, 1);
         assert_eq!(result.matches[0].line_number, 1);
         assert_eq!(result.matches[1].line_number, 3);
+        assert_eq!(result.matches[0].matched_pattern, "hello");
     }
 
     #[tokio::test]
@@ -450,6 +881,169 @@ mod tests {
         assert_eq!(result.files_searched, 3);
     }
 
+    #[tokio::test]
+    async fn test_grep_max_depth_excludes_grandchild() {
+        let tool = SearchTool::new();
+        let temp_dir = TempDir::new().unwrap();
+
+        create_test_file(&temp_dir, "file1.txt", "hello world\n").await;
+        create_test_file(&temp_dir, "sub/file2.txt", "hello rust\n").await;
+        create_test_file(&temp_dir, "sub/nested/file3.txt", "hello bodhya\n").await;
+
+        let request = ToolRequest::new(
+            "search",
+            "grep",
+            json!({
+                "path": temp_dir.path().to_str().unwrap(),
+                "pattern": "hello",
+                "max_depth": 1
+            }),
+        );
+        let response = tool.execute(request).await.unwrap();
+
+        assert_eq!(response.data["total_matches"], 2);
+        assert_eq!(response.data["files_searched"], 2);
+        let files: Vec<&str> = response.data["matches"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["file_path"].as_str().unwrap())
+            .collect();
+        assert!(files.iter().all(|f| !f.contains("nested")));
+    }
+
+    #[tokio::test]
+    async fn test_grep_skips_large_and_binary_files() {
+        let tool = SearchTool::new();
+        let temp_dir = TempDir::new().unwrap();
+
+        create_test_file(&temp_dir, "src/lib.rs", "// needle\n").await;
+        create_test_file(&temp_dir, "big.log", &"needle\n".repeat(1000)).await;
+        let binary = temp_dir.path().join("blob.bin");
+        tokio::fs::write(&binary, b"needle\0\x01\x02")
+            .await
+            .unwrap();
+
+        let options = GrepOptions::default().with_max_file_size(1024);
+        let result = tool
+            .grep_with_options(temp_dir.path(), "needle", &options)
+            .await
+            .unwrap();
+
+        assert_eq!(result.files_searched, 1);
+        assert_eq!(result.total_matches, 1);
+        assert!(result.matches[0].file_path.ends_with("src/lib.rs"));
+
+        // Both can still be searched when asked for
+        let request = ToolRequest::new(
+            "search",
+            "grep",
+            json!({
+                "path": temp_dir.path().to_str().unwrap(),
+                "pattern": "needle",
+                "max_file_size": 0,
+                "include_binary": true
+            }),
+        );
+        let response = tool.execute(request).await.unwrap();
+        assert_eq!(response.data["files_searched"], 3);
+        assert_eq!(response.data["total_matches"], 1002);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_grep_symlink_loop_terminates() {
+        let tool = SearchTool::new();
+        let temp_dir = TempDir::new().unwrap();
+
+        create_test_file(&temp_dir, "sub/file.txt", "hello loop\n").await;
+        std::os::unix::fs::symlink(temp_dir.path(), temp_dir.path().join("sub/back")).unwrap();
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            tool.grep(temp_dir.path(), "hello", true, true, None, 0),
+        )
+        .await
+        .expect("search should not loop forever")
+        .unwrap();
+
+        assert_eq!(result.total_matches, 1);
+        assert_eq!(result.files_searched, 1);
+    }
+
+    #[tokio::test]
+    async fn test_grep_any_attributes_each_match() {
+        let tool = SearchTool::new();
+        let temp_dir = TempDir::new().unwrap();
+
+        create_test_file(&temp_dir, "a.rs", "// TODO: parse\nfn a() {}\n").await;
+        create_test_file(&temp_dir, "sub/b.rs", "fn b() {}\n// FIXME: overflow\n").await;
+
+        let result = tool
+            .grep_any(temp_dir.path(), &["TODO", "FIXME"], &GrepOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(result.total_matches, 2);
+        assert_eq!(result.files_searched, 2);
+        for mat in &result.matches {
+            let expected = if mat.file_path.ends_with("a.rs") {
+                "TODO"
+            } else {
+                "FIXME"
+            };
+            assert_eq!(mat.matched_pattern, expected);
+            assert!(mat.line_content.contains(expected));
+        }
+
+        // The tool accepts a list of patterns too
+        let request = ToolRequest::new(
+            "search",
+            "grep",
+            json!({
+                "path": temp_dir.path().to_str().unwrap(),
+                "pattern": ["TODO", "FIXME"]
+            }),
+        );
+        let response = tool.execute(request).await.unwrap();
+        assert_eq!(response.data["total_matches"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_group_by_file() {
+        let tool = SearchTool::new();
+        let temp_dir = TempDir::new().unwrap();
+
+        create_test_file(&temp_dir, "c.txt", "hit\n").await;
+        create_test_file(&temp_dir, "a.txt", "hit\nmiss\nhit\n").await;
+        create_test_file(&temp_dir, "sub/b.txt", "hit\nhit\nhit\n").await;
+
+        let result = tool
+            .grep(temp_dir.path(), "hit", true, true, None, 0)
+            .await
+            .unwrap();
+        let groups = result.group_by_file();
+
+        let summary: Vec<(PathBuf, usize)> = groups
+            .iter()
+            .map(|g| (g.file_path.clone(), g.count))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (temp_dir.path().join("a.txt"), 2),
+                (temp_dir.path().join("c.txt"), 1),
+                (temp_dir.path().join("sub/b.txt"), 3),
+            ]
+        );
+        for group in &groups {
+            assert_eq!(group.matches.len(), group.count);
+            assert!(group.matches.iter().all(|m| m.file_path == group.file_path));
+        }
+        assert_eq!(groups[0].matches[0].line_number, 1);
+        assert_eq!(groups[0].matches[1].line_number, 3);
+    }
+
     #[tokio::test]
     async fn test_grep_with_file_pattern() {
         let tool = SearchTool::new();
@@ -509,4 +1103,83 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_find_symbol_in_rust_file() {
+        let tool = SearchTool::new();
+        let temp_dir = TempDir::new().unwrap();
+        let source = "use std::fmt;\n\n// Config is loaded once\npub struct Config {\n    pub name: String,\n}\n\npub fn load_config() -> Config {\n    Config { name: String::new() }\n}\n";
+        create_test_file(&temp_dir, "src/config.rs", source).await;
+        create_test_file(&temp_dir, "src/main.rs", "fn main() { load_config(); }\n").await;
+
+        let structs = tool
+            .find_symbol(temp_dir.path(), "Config", None)
+            .await
+            .unwrap();
+        assert_eq!(structs.len(), 1);
+        assert_eq!(structs[0].kind, "struct");
+        assert_eq!(structs[0].line_number, 4);
+        assert!(structs[0].file_path.ends_with("src/config.rs"));
+
+        let functions = tool
+            .find_symbol(temp_dir.path(), "load_config", Some("fn"))
+            .await
+            .unwrap();
+        assert_eq!(functions.len(), 1);
+        assert_eq!(functions[0].line_number, 8);
+    }
+
+    #[tokio::test]
+    async fn test_find_symbol_falls_back_to_grep() {
+        let tool = SearchTool::new();
+        let temp_dir = TempDir::new().unwrap();
+        create_test_file(
+            &temp_dir,
+            "app.py",
+            "import os\n\ndef load_config():\n    pass\n",
+        )
+        .await;
+
+        let request = ToolRequest::new(
+            "search",
+            "find_symbol",
+            json!({ "path": temp_dir.path().to_str().unwrap(), "name": "load_config" }),
+        );
+        let response = tool.execute(request).await.unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.data["total_matches"], 1);
+        assert_eq!(response.data["matches"][0]["kind"], "text");
+        assert_eq!(response.data["matches"][0]["line_number"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_grep_stream_yields_before_walk_completes() {
+        let tool = SearchTool::new();
+        let temp_dir = TempDir::new().unwrap();
+        let files = STREAM_BUFFER * 4;
+        for i in 0..files {
+            create_test_file(
+                &temp_dir,
+                &format!("dir{}/file{}.txt", i % 8, i),
+                "needle\n",
+            )
+            .await;
+        }
+
+        let mut stream = tool
+            .grep_stream(temp_dir.path(), "needle", true, true, None, 0)
+            .await
+            .unwrap();
+
+        // The walk can buffer at most STREAM_BUFFER matches until they are read
+        let first = stream.next().await.unwrap();
+        assert!(!stream.is_finished());
+        assert_eq!(first.line_number, 1);
+
+        let rest = stream.finish().await.unwrap();
+        assert_eq!(rest.matches.len(), files - 1);
+        assert_eq!(rest.total_matches, files);
+        assert_eq!(rest.files_searched, files);
+    }
 }
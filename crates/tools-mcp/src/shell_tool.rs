@@ -1,11 +1,18 @@
 /// Shell command execution tool
 ///
 /// This module provides shell command execution as a Tool implementation.
+/// Commands either run to completion (`exec`) or are started in the
+/// background (`spawn`) and later stopped by id (`kill`).
+use crate::schema::object_schema;
 use async_trait::async_trait;
 use bodhya_core::{Result, Tool, ToolRequest, ToolResponse};
-use std::path::PathBuf;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use tokio::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::process::{Child, Command};
 
 /// Shell execution tool for running commands
 pub struct ShellTool {
@@ -13,6 +20,10 @@ pub struct ShellTool {
     working_dir: Option<PathBuf>,
     /// Maximum execution time in seconds
     timeout_secs: u64,
+    /// Background processes by id; killed when the tool is dropped
+    processes: Mutex<HashMap<u64, Child>>,
+    /// Id of the next background process
+    next_id: AtomicU64,
 }
 
 impl ShellTool {
@@ -21,6 +32,8 @@ impl ShellTool {
         Self {
             working_dir: None,
             timeout_secs: 300, // 5 minutes default
+            processes: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
         }
     }
 
@@ -28,7 +41,7 @@ impl ShellTool {
     pub fn with_working_dir(working_dir: impl Into<PathBuf>) -> Self {
         Self {
             working_dir: Some(working_dir.into()),
-            timeout_secs: 300,
+            ..Self::new()
         }
     }
 
@@ -38,8 +51,13 @@ impl ShellTool {
         self
     }
 
-    /// Execute a shell command
-    async fn execute_command(&self, command: &str, args: Vec<String>) -> Result<ToolResponse> {
+    /// Execute a shell command, in `cwd` if given, else the tool's working directory
+    async fn execute_command(
+        &self,
+        command: &str,
+        args: Vec<String>,
+        cwd: Option<&Path>,
+    ) -> Result<ToolResponse> {
         let mut cmd = Command::new(command);
 
         // Set arguments
@@ -48,7 +66,7 @@ impl ShellTool {
         }
 
         // Set working directory if specified
-        if let Some(dir) = &self.working_dir {
+        if let Some(dir) = cwd.or(self.working_dir.as_deref()) {
             cmd.current_dir(dir);
         }
 
@@ -65,8 +83,8 @@ impl ShellTool {
 
         match result {
             Ok(Ok(output)) => {
-                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+                let (stdout, stdout_lossy) = decode_output(&output.stdout);
+                let (stderr, stderr_lossy) = decode_output(&output.stderr);
                 let success = output.status.success();
                 let exit_code = output.status.code();
 
@@ -82,6 +100,10 @@ impl ShellTool {
                             "exit_code": exit_code,
                             "stdout": stdout,
                             "stderr": stderr,
+                            "stdout_bytes": output.stdout.len(),
+                            "stderr_bytes": output.stderr.len(),
+                            "stdout_lossy": stdout_lossy,
+                            "stderr_lossy": stderr_lossy,
                         }),
                         combined_output,
                     ))
@@ -105,6 +127,110 @@ impl ShellTool {
     }
 }
 
+impl ShellTool {
+    fn processes(&self) -> std::sync::MutexGuard<'_, HashMap<u64, Child>> {
+        self.processes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Start `command` in the background, returning its id
+    ///
+    /// Output is discarded. The process runs until [`ShellTool::kill`] or
+    /// until the tool is dropped.
+    pub fn spawn(&self, command: &str, args: &[String]) -> Result<u64> {
+        let mut cmd = Command::new(command);
+        cmd.args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+
+        if let Some(dir) = &self.working_dir {
+            cmd.current_dir(dir);
+        }
+
+        let child = cmd.spawn().map_err(|e| {
+            bodhya_core::Error::Tool(format!("Failed to spawn '{}': {}", command, e))
+        })?;
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        self.processes().insert(id, child);
+
+        tracing::debug!(id, command, "Spawned background process");
+        Ok(id)
+    }
+
+    /// Whether background process `id` is still running
+    ///
+    /// Returns `None` for unknown ids, and otherwise `Some(None)` while the
+    /// process runs or `Some(Some(code))` once it has exited.
+    pub fn status(&self, id: u64) -> Option<Option<i32>> {
+        let mut processes = self.processes();
+        let child = processes.get_mut(&id)?;
+        match child.try_wait() {
+            Ok(Some(status)) => Some(Some(status.code().unwrap_or(-1))),
+            Ok(None) => Some(None),
+            Err(_) => Some(Some(-1)),
+        }
+    }
+
+    /// Terminate background process `id` and wait for it to exit
+    pub async fn kill(&self, id: u64) -> Result<()> {
+        let mut child = self.processes().remove(&id).ok_or_else(|| {
+            bodhya_core::Error::Tool(format!("No background process with id {}", id))
+        })?;
+
+        // Already-exited processes just need reaping
+        if let Ok(None) = child.try_wait() {
+            child.kill().await.map_err(|e| {
+                bodhya_core::Error::Tool(format!("Failed to kill process {}: {}", id, e))
+            })?;
+        } else {
+            let _ = child.wait().await;
+        }
+
+        tracing::debug!(id, "Killed background process");
+        Ok(())
+    }
+}
+
+/// Decode command output as UTF-8, replacing invalid sequences with U+FFFD
+///
+/// Returns the text and whether anything had to be replaced.
+fn decode_output(bytes: &[u8]) -> (String, bool) {
+    match String::from_utf8_lossy(bytes) {
+        std::borrow::Cow::Borrowed(text) => (text.to_string(), false),
+        std::borrow::Cow::Owned(text) => (text, true),
+    }
+}
+
+/// Arguments of an `exec` or `spawn` request: an array, or a shell-style string
+fn parse_args(request: &ToolRequest) -> Result<Vec<String>> {
+    let Some(args_value) = request.params.get("args") else {
+        return Ok(Vec::new());
+    };
+
+    if let Some(args_array) = args_value.as_array() {
+        Ok(args_array
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect())
+    } else if let Some(args_str) = args_value.as_str() {
+        // Parse space-separated string
+        shell_words::split(args_str)
+            .map_err(|e| bodhya_core::Error::Tool(format!("Failed to parse args: {}", e)))
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// The `id` param of a `kill` or `status` request
+fn process_id(request: &ToolRequest) -> Result<u64> {
+    request
+        .params
+        .get("id")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| bodhya_core::Error::Tool("Missing 'id' parameter".to_string()))
+}
+
 impl Default for ShellTool {
     fn default() -> Self {
         Self::new()
@@ -122,7 +248,35 @@ impl Tool for ShellTool {
     }
 
     fn supported_operations(&self) -> Vec<String> {
-        vec!["exec".to_string(), "run".to_string()]
+        vec![
+            "exec".to_string(),
+            "run".to_string(),
+            "spawn".to_string(),
+            "kill".to_string(),
+            "status".to_string(),
+        ]
+    }
+
+    fn parameter_schema(&self, operation: &str) -> serde_json::Value {
+        match operation {
+            "exec" | "run" => object_schema(
+                &[
+                    ("command", json!("string")),
+                    ("args", json!(["array", "string"])),
+                    ("cwd", json!("string")),
+                ],
+                &["command"],
+            ),
+            "spawn" => object_schema(
+                &[
+                    ("command", json!("string")),
+                    ("args", json!(["array", "string"])),
+                ],
+                &["command"],
+            ),
+            "kill" | "status" => object_schema(&[("id", json!("integer"))], &["id"]),
+            _ => json!({ "type": "object" }),
+        }
     }
 
     async fn execute(&self, request: ToolRequest) -> Result<ToolResponse> {
@@ -132,26 +286,41 @@ impl Tool for ShellTool {
                     bodhya_core::Error::Tool("Missing 'command' parameter".to_string())
                 })?;
 
-                // Parse arguments
-                let args = if let Some(args_value) = request.params.get("args") {
-                    if let Some(args_array) = args_value.as_array() {
-                        args_array
-                            .iter()
-                            .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                            .collect()
-                    } else if let Some(args_str) = args_value.as_str() {
-                        // Parse space-separated string
-                        shell_words::split(args_str).map_err(|e| {
-                            bodhya_core::Error::Tool(format!("Failed to parse args: {}", e))
-                        })?
-                    } else {
-                        Vec::new()
-                    }
-                } else {
-                    Vec::new()
-                };
+                let args = parse_args(&request)?;
+                let cwd = request.params["cwd"].as_str().map(Path::new);
+                self.execute_command(command, args, cwd).await
+            }
+            "spawn" => {
+                let command = request.params["command"].as_str().ok_or_else(|| {
+                    bodhya_core::Error::Tool("Missing 'command' parameter".to_string())
+                })?;
+                let args = parse_args(&request)?;
 
-                self.execute_command(command, args).await
+                match self.spawn(command, &args) {
+                    Ok(id) => Ok(ToolResponse::success(json!({ "id": id }))),
+                    Err(e) => Ok(ToolResponse::failure(e.to_string())),
+                }
+            }
+            "kill" => {
+                let id = process_id(&request)?;
+                match self.kill(id).await {
+                    Ok(()) => Ok(ToolResponse::success(json!({ "id": id, "killed": true }))),
+                    Err(e) => Ok(ToolResponse::failure(e.to_string())),
+                }
+            }
+            "status" => {
+                let id = process_id(&request)?;
+                match self.status(id) {
+                    Some(exit_code) => Ok(ToolResponse::success(json!({
+                        "id": id,
+                        "running": exit_code.is_none(),
+                        "exit_code": exit_code,
+                    }))),
+                    None => Ok(ToolResponse::failure(format!(
+                        "No background process with id {}",
+                        id
+                    ))),
+                }
             }
             _ => Ok(ToolResponse::failure(format!(
                 "Unsupported operation: {}",
@@ -225,6 +394,27 @@ mod tests {
         assert!(resp.data["stdout"].as_str().unwrap().contains("test.txt"));
     }
 
+    #[tokio::test]
+    async fn test_execute_cwd_param_overrides_working_dir() {
+        let working_dir = TempDir::new().unwrap();
+        let cwd = TempDir::new().unwrap();
+        std::fs::write(cwd.path().join("here.txt"), "content").unwrap();
+        let tool = ShellTool::with_working_dir(working_dir.path());
+
+        let req = ToolRequest::new(
+            "shell",
+            "exec",
+            serde_json::json!({
+                "command": "ls",
+                "cwd": cwd.path().to_str().unwrap()
+            }),
+        );
+
+        let resp = tool.execute(req).await.unwrap();
+        assert!(resp.success);
+        assert!(resp.data["stdout"].as_str().unwrap().contains("here.txt"));
+    }
+
     #[tokio::test]
     async fn test_execute_failing_command() {
         let tool = ShellTool::new();
@@ -324,6 +514,114 @@ mod tests {
         assert!(!stderr.is_empty() || resp.error.is_some());
     }
 
+    #[tokio::test]
+    async fn test_spawn_and_kill_background_process() {
+        let tool = ShellTool::new();
+
+        let spawned = tool
+            .execute(ToolRequest::new(
+                "shell",
+                "spawn",
+                serde_json::json!({ "command": "sleep", "args": ["30"] }),
+            ))
+            .await
+            .unwrap();
+        assert!(spawned.success);
+        let id = spawned.data["id"].as_u64().unwrap();
+
+        let status = tool
+            .execute(ToolRequest::new(
+                "shell",
+                "status",
+                serde_json::json!({ "id": id }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(status.data["running"], true);
+
+        let killed = tool
+            .execute(ToolRequest::new(
+                "shell",
+                "kill",
+                serde_json::json!({ "id": id }),
+            ))
+            .await
+            .unwrap();
+        assert!(killed.success);
+        assert_eq!(tool.status(id), None);
+
+        // A process can only be killed once
+        let again = tool
+            .execute(ToolRequest::new(
+                "shell",
+                "kill",
+                serde_json::json!({ "id": id }),
+            ))
+            .await
+            .unwrap();
+        assert!(!again.success);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_background_processes_killed_on_drop() {
+        let temp_dir = TempDir::new().unwrap();
+        let tool = ShellTool::with_working_dir(temp_dir.path());
+        let script = "echo $$ > pid; exec sleep 30";
+        let id = tool
+            .spawn("sh", &["-c".to_string(), script.to_string()])
+            .unwrap();
+
+        let pid_file = temp_dir.path().join("pid");
+        let mut pid = String::new();
+        for _ in 0..100 {
+            pid = std::fs::read_to_string(&pid_file).unwrap_or_default();
+            if pid.ends_with('\n') {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(tool.status(id), Some(None));
+        drop(tool);
+
+        // The process exits once the tool is gone
+        let proc_dir = std::path::Path::new("/proc").join(pid.trim());
+        let mut exited = false;
+        for _ in 0..100 {
+            let zombie = std::fs::read_to_string(proc_dir.join("stat"))
+                .map(|stat| stat.contains(") Z "))
+                .unwrap_or(true);
+            if zombie {
+                exited = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(exited, "background process {} still running", pid.trim());
+    }
+
+    #[tokio::test]
+    async fn test_execute_non_utf8_output() {
+        let tool = ShellTool::new();
+
+        // "ok", an invalid byte, then "!"
+        let req = ToolRequest::new(
+            "shell",
+            "exec",
+            serde_json::json!({
+                "command": "printf",
+                "args": ["ok\\377!"]
+            }),
+        );
+
+        let resp = tool.execute(req).await.unwrap();
+        assert!(resp.success);
+        assert_eq!(resp.data["stdout"], "ok\u{FFFD}!");
+        assert_eq!(resp.data["stdout_bytes"], 4);
+        assert_eq!(resp.data["stdout_lossy"], true);
+        assert_eq!(resp.data["stderr_lossy"], false);
+    }
+
     #[tokio::test]
     async fn test_run_operation_alias() {
         let tool = ShellTool::new();
@@ -52,6 +52,10 @@ impl StdioMcpClient {
     }
 
     /// Send a JSON-RPC request and wait for response
+    ///
+    /// Requests are sent one at a time. Replies with another id are skipped:
+    /// they belong to earlier requests whose caller stopped waiting (e.g. a
+    /// timed-out tool call), and would otherwise be taken as this reply.
     async fn send_request(&self, method: &str, params: Option<Value>) -> Result<JsonRpcResponse> {
         let id = self.next_id();
         let request = JsonRpcRequest::new(id.clone(), method, params);
@@ -61,6 +65,12 @@ impl StdioMcpClient {
             .map_err(|e| Error::Tool(format!("Failed to serialize request: {}", e)))?;
         request_json.push('\n');
 
+        // Hold the reader for the whole round trip
+        let Some(ref stdout) = self.stdout else {
+            return Err(Error::Tool("MCP client not connected".to_string()));
+        };
+        let mut stdout_guard = stdout.lock().await;
+
         // Write to stdin
         if let Some(ref stdin) = self.stdin {
             let mut stdin_guard = stdin.lock().await;
@@ -76,31 +86,48 @@ impl StdioMcpClient {
             return Err(Error::Tool("MCP client not connected".to_string()));
         }
 
-        // Read response from stdout
-        if let Some(ref stdout) = self.stdout {
-            let mut stdout_guard = stdout.lock().await;
+        // Read responses from stdout until ours arrives
+        let response = loop {
             let mut line = String::new();
-            stdout_guard
+            let read = stdout_guard
                 .read_line(&mut line)
                 .await
                 .map_err(|e| Error::Tool(format!("Failed to read from MCP server: {}", e)))?;
+            if read == 0 {
+                return Err(Error::Tool("MCP server closed its output".to_string()));
+            }
 
-            // Parse response
-            let response: JsonRpcResponse = serde_json::from_str(&line)
-                .map_err(|e| Error::Tool(format!("Failed to parse response: {}", e)))?;
-
-            // Check for errors
-            if let Some(error) = &response.error {
+            // Notifications have no id and can't be parsed as responses
+            let Ok(response) = serde_json::from_str::<JsonRpcResponse>(&line) else {
+                let is_message = serde_json::from_str::<Value>(&line)
+                    .is_ok_and(|message| message.get("method").is_some());
+                if is_message {
+                    continue;
+                }
                 return Err(Error::Tool(format!(
-                    "MCP server error {}: {}",
-                    error.code, error.message
+                    "Failed to parse response: {}",
+                    line.trim_end()
                 )));
+            };
+            if response.id == id {
+                break response;
             }
-
-            Ok(response)
-        } else {
-            Err(Error::Tool("MCP client not connected".to_string()))
+            tracing::debug!(
+                expected = ?id,
+                received = ?response.id,
+                "Skipping stale MCP response"
+            );
+        };
+
+        // Check for errors
+        if let Some(error) = &response.error {
+            return Err(Error::Tool(format!(
+                "MCP server error {}: {}",
+                error.code, error.message
+            )));
         }
+
+        Ok(response)
     }
 
     /// Initialize connection with MCP server
@@ -313,6 +340,48 @@ mod tests {
         assert_ne!(id1, id2);
     }
 
+    /// MCP server whose `slow` tool answers after a second and `fast` at once
+    const SCRIPTED_SERVER: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed 's/.*"id":\([0-9]*\).*/\1/')
+  case "$line" in
+    *'"method":"initialize"'*) result='{"capabilities":{}}' ;;
+    *'"method":"tools/list"'*) result='{"tools":[{"name":"slow"},{"name":"fast"}]}' ;;
+    *'"name":"slow"'*) sleep 1; result='{"content":[{"type":"text","text":"slow"}]}' ;;
+    *) result='{"content":[{"type":"text","text":"fast"}]}' ;;
+  esac
+  printf '{"jsonrpc":"2.0","id":%s,"result":%s}\n' "$id" "$result"
+done
+"#;
+
+    #[tokio::test]
+    async fn test_call_after_timeout_gets_its_own_reply() {
+        let config = McpServerConfig::new_stdio(
+            "scripted",
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                SCRIPTED_SERVER.to_string(),
+            ],
+        );
+        let mut client = StdioMcpClient::new();
+        client.connect(&config).await.unwrap();
+        assert_eq!(client.list_tools().await.unwrap(), vec!["slow", "fast"]);
+
+        let call = |tool: &str| ToolRequest::new(tool, "call", serde_json::json!({}));
+        let timed_out = tokio::time::timeout(
+            std::time::Duration::from_millis(100),
+            client.call_tool(call("slow")),
+        )
+        .await;
+        assert!(timed_out.is_err());
+
+        let response = client.call_tool(call("fast")).await.unwrap();
+        assert_eq!(response.data["content"][0]["text"], "fast");
+
+        client.disconnect().await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_disconnect_when_not_connected() {
         let mut client = StdioMcpClient::new();
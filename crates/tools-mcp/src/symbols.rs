@@ -0,0 +1,160 @@
+//! Rust symbol definitions
+//!
+//! Parses Rust source with `syn` to find where items are defined, which is
+//! far more precise than grepping for a name: uses, comments, and strings
+//! that mention the name are not reported.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+/// Where a symbol is defined
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolMatch {
+    pub file_path: PathBuf,
+    /// 1-indexed line of the symbol's name (or `impl` keyword)
+    pub line_number: usize,
+    /// Item kind: fn, method, struct, enum, trait, impl, mod, const, static,
+    /// type, union, macro, or `text` for grep matches in non-Rust files
+    pub kind: String,
+    pub name: String,
+}
+
+/// Definitions of `name` in Rust `source`, as (line, kind) pairs
+///
+/// Returns `None` if `source` does not parse.
+pub(crate) fn find_definitions(source: &str, name: &str) -> Option<Vec<(usize, &'static str)>> {
+    let file = syn::parse_file(source).ok()?;
+    let mut finder = DefinitionFinder {
+        name,
+        found: Vec::new(),
+    };
+    finder.visit_file(&file);
+    finder.found.sort();
+    Some(finder.found)
+}
+
+struct DefinitionFinder<'a> {
+    name: &'a str,
+    found: Vec<(usize, &'static str)>,
+}
+
+impl DefinitionFinder<'_> {
+    fn check(&mut self, ident: &syn::Ident, kind: &'static str) {
+        if ident == self.name {
+            self.found.push((ident.span().start().line, kind));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for DefinitionFinder<'_> {
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        self.check(&item.sig.ident, "fn");
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        self.check(&item.sig.ident, "method");
+        visit::visit_impl_item_fn(self, item);
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast syn::TraitItemFn) {
+        self.check(&item.sig.ident, "method");
+        visit::visit_trait_item_fn(self, item);
+    }
+
+    fn visit_item_struct(&mut self, item: &'ast syn::ItemStruct) {
+        self.check(&item.ident, "struct");
+        visit::visit_item_struct(self, item);
+    }
+
+    fn visit_item_enum(&mut self, item: &'ast syn::ItemEnum) {
+        self.check(&item.ident, "enum");
+        visit::visit_item_enum(self, item);
+    }
+
+    fn visit_item_union(&mut self, item: &'ast syn::ItemUnion) {
+        self.check(&item.ident, "union");
+        visit::visit_item_union(self, item);
+    }
+
+    fn visit_item_trait(&mut self, item: &'ast syn::ItemTrait) {
+        self.check(&item.ident, "trait");
+        visit::visit_item_trait(self, item);
+    }
+
+    fn visit_item_type(&mut self, item: &'ast syn::ItemType) {
+        self.check(&item.ident, "type");
+        visit::visit_item_type(self, item);
+    }
+
+    fn visit_item_const(&mut self, item: &'ast syn::ItemConst) {
+        self.check(&item.ident, "const");
+        visit::visit_item_const(self, item);
+    }
+
+    fn visit_item_static(&mut self, item: &'ast syn::ItemStatic) {
+        self.check(&item.ident, "static");
+        visit::visit_item_static(self, item);
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        self.check(&item.ident, "mod");
+        visit::visit_item_mod(self, item);
+    }
+
+    fn visit_item_macro(&mut self, item: &'ast syn::ItemMacro) {
+        if let Some(ident) = &item.ident {
+            self.check(ident, "macro");
+        }
+        visit::visit_item_macro(self, item);
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        // `impl Foo` and `impl Trait for Foo` both count as impls of `Foo`
+        if let syn::Type::Path(self_ty) = &*item.self_ty {
+            if let Some(segment) = self_ty.path.segments.last() {
+                if segment.ident == self.name {
+                    self.found
+                        .push((item.impl_token.span().start().line, "impl"));
+                }
+            }
+        }
+        visit::visit_item_impl(self, item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+/// Parser state
+pub struct Parser {
+    input: String,
+}
+
+impl Parser {
+    pub fn parse(&self) {}
+}
+
+mod nested {
+    fn parse() {}
+}
+"#;
+
+    #[test]
+    fn test_find_definitions() {
+        assert_eq!(
+            find_definitions(SOURCE, "Parser"),
+            Some(vec![(3, "struct"), (7, "impl")])
+        );
+        assert_eq!(
+            find_definitions(SOURCE, "parse"),
+            Some(vec![(8, "method"), (12, "fn")])
+        );
+        assert_eq!(find_definitions(SOURCE, "input"), Some(vec![]));
+        assert_eq!(find_definitions("fn broken(", "broken"), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Size cap for tool responses
+//!
+//! Reads, searches, and commands can return payloads large enough to exhaust
+//! memory or a model's context. Oversized responses are cut down field by
+//! field (long lists lose trailing entries, long strings are cut with a
+//! note) and marked as truncated.
+
+use bodhya_core::ToolResponse;
+use serde_json::Value;
+
+/// Default maximum serialized size of a response's data, in bytes
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// Appended to strings that were cut short
+const TRUNCATION_NOTE: &str = "\n... [truncated]";
+
+/// Cut `response` down so its serialized `data` and its `output` fit in `max_bytes`
+pub(crate) fn truncate_response(response: &mut ToolResponse, max_bytes: usize) {
+    if let Some(output) = &mut response.output {
+        if output.len() > max_bytes {
+            let end = floor_char_boundary(output, max_bytes.saturating_sub(TRUNCATION_NOTE.len()));
+            output.truncate(end);
+            output.push_str(TRUNCATION_NOTE);
+            response.truncated = true;
+        }
+    }
+
+    if json_len(&response.data) > max_bytes {
+        truncate_data(&mut response.data, max_bytes);
+        response.truncated = true;
+    }
+}
+
+/// Shrink the largest fields of `data` until it fits, marking it `truncated`
+fn truncate_data(data: &mut Value, max_bytes: usize) {
+    let original_size = json_len(data);
+
+    if let Value::Object(fields) = data {
+        fields.insert("truncated".to_string(), Value::Bool(true));
+
+        let mut keys: Vec<(String, usize)> = fields
+            .iter()
+            .filter(|(_, value)| value.is_string() || value.is_array())
+            .map(|(key, value)| (key.clone(), json_len(value)))
+            .collect();
+        keys.sort_by_key(|(_, len)| std::cmp::Reverse(*len));
+
+        for (key, _) in keys {
+            let excess = json_len(data).saturating_sub(max_bytes);
+            if excess == 0 {
+                return;
+            }
+            if let Some(value) = data.get_mut(&key) {
+                shrink(value, excess);
+            }
+        }
+    }
+
+    // Nothing left to cut (e.g., a huge number of small fields)
+    if json_len(data) > max_bytes {
+        *data = serde_json::json!({
+            "truncated": true,
+            "original_size": original_size,
+        });
+    }
+}
+
+/// Reduce the serialized size of `value` by at least `excess` bytes if possible
+fn shrink(value: &mut Value, excess: usize) {
+    match value {
+        Value::Array(items) => {
+            let mut removed = 0;
+            while removed < excess {
+                match items.pop() {
+                    // Each element also costs a separating comma
+                    Some(item) => removed += json_len(&item) + 1,
+                    None => break,
+                }
+            }
+        }
+        Value::String(text) => {
+            let budget = (json_len(&Value::String(text.clone())) - 2)
+                .saturating_sub(excess + escaped_len(TRUNCATION_NOTE));
+            let mut used = 0;
+            let end = text
+                .char_indices()
+                .find(|(_, c)| {
+                    used += escaped_char_len(*c);
+                    used > budget
+                })
+                .map(|(i, _)| i)
+                .unwrap_or(text.len());
+            text.truncate(end);
+            text.push_str(TRUNCATION_NOTE);
+        }
+        _ => {}
+    }
+}
+
+/// Length of `value` serialized as compact JSON
+fn json_len(value: &Value) -> usize {
+    serde_json::to_vec(value)
+        .map(|bytes| bytes.len())
+        .unwrap_or(0)
+}
+
+fn escaped_len(text: &str) -> usize {
+    text.chars().map(escaped_char_len).sum()
+}
+
+/// Bytes `c` takes inside a JSON string
+fn escaped_char_len(c: char) -> usize {
+    match c {
+        '"' | '\\' | '\n' | '\r' | '\t' | '\u{08}' | '\u{0c}' => 2,
+        c if c < ' ' => 6,
+        c => c.len_utf8(),
+    }
+}
+
+/// Largest char boundary in `text` at or below `index`
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    (0..=index.min(text.len()))
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_response_untouched() {
+        let mut response = ToolResponse::success(serde_json::json!({ "content": "hello" }));
+        truncate_response(&mut response, 1024);
+
+        assert!(!response.truncated);
+        assert_eq!(response.data, serde_json::json!({ "content": "hello" }));
+    }
+
+    #[test]
+    fn test_search_matches_truncated() {
+        let matches: Vec<Value> = (0..1000)
+            .map(|i| {
+                serde_json::json!({
+                    "file": "src/lib.rs",
+                    "line_number": i,
+                    "line": "fn foo() {}",
+                })
+            })
+            .collect();
+        let mut response = ToolResponse::success(serde_json::json!({
+            "success": true,
+            "matches": matches,
+            "total_matches": 1000,
+        }));
+
+        truncate_response(&mut response, 4096);
+
+        assert!(response.truncated);
+        assert!(json_len(&response.data) <= 4096);
+        assert_eq!(response.data["truncated"], true);
+        assert_eq!(response.data["total_matches"], 1000);
+        let kept = response.data["matches"].as_array().unwrap().len();
+        assert!(kept > 0 && kept < 1000);
+    }
+
+    #[test]
+    fn test_escaped_and_multibyte_content_fits() {
+        let content = "\"quoted\"\té\n".repeat(1000);
+        let mut response = ToolResponse::success(serde_json::json!({ "content": content }));
+
+        truncate_response(&mut response, 500);
+
+        assert!(json_len(&response.data) <= 500);
+        assert!(response.data["content"]
+            .as_str()
+            .unwrap()
+            .ends_with(TRUNCATION_NOTE));
+    }
+
+    #[test]
+    fn test_output_truncated() {
+        let mut response = ToolResponse::success_with_output(Value::Null, "é".repeat(100));
+        truncate_response(&mut response, 51);
+
+        let output = response.output.unwrap();
+        assert!(response.truncated);
+        assert!(output.len() <= 51);
+        assert!(output.ends_with(TRUNCATION_NOTE));
+    }
+}
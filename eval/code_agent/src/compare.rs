@@ -0,0 +1,137 @@
+/// A/B comparison of two model backends on the same test case
+///
+/// Each backend serves every role of the code domain in turn, the outputs are
+/// scored with [`CodeScorer`], and the comparison reports the winner along
+/// with how each criterion moved from backend A to backend B (see
+/// [`bodhya_eval_common::BackendComparison`]).
+use crate::runner::{EvaluationResult, EvaluationRunner};
+use crate::test_case::CodeTestCase;
+use bodhya_agent_code::CodeAgent;
+use bodhya_core::ModelBackend;
+use bodhya_eval_common::ScoredResult;
+use bodhya_model_registry::ModelRegistry;
+use std::sync::Arc;
+
+/// Comparison of two backends' results on one test case
+pub type BackendComparison = bodhya_eval_common::BackendComparison<EvaluationResult>;
+
+impl ScoredResult for EvaluationResult {
+    fn case_id(&self) -> &str {
+        &self.test_case_id
+    }
+
+    fn case_name(&self) -> &str {
+        &self.test_case_name
+    }
+
+    fn criteria(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("correctness", self.score.correctness),
+            ("style", self.score.style),
+            ("coverage", self.score.coverage),
+            ("total", self.score.total),
+        ]
+    }
+}
+
+/// Run `test_case` with each backend serving every code role and compare the scores
+pub async fn compare_backends(
+    test_case: &CodeTestCase,
+    backend_a: Arc<dyn ModelBackend>,
+    backend_b: Arc<dyn ModelBackend>,
+) -> BackendComparison {
+    let id_a = backend_a.id().to_string();
+    let id_b = backend_b.id().to_string();
+    let result_a = run_with_backend(test_case, backend_a).await;
+    let result_b = run_with_backend(test_case, backend_b).await;
+
+    BackendComparison::new(id_a, result_a, id_b, result_b)
+}
+
+async fn run_with_backend(
+    test_case: &CodeTestCase,
+    backend: Arc<dyn ModelBackend>,
+) -> EvaluationResult {
+    let registry = ModelRegistry::single_backend("code", backend);
+    let runner = EvaluationRunner::new(CodeAgent::with_registry(Arc::new(registry)));
+    runner.run_test_case(test_case).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standard_cases::fibonacci_case;
+    use bodhya_core::ModelRole;
+    use bodhya_model_registry::MockBackend;
+
+    const GOOD_IMPL: &str = "```rust
+/// Errors from computing Fibonacci numbers
+#[derive(Debug, PartialEq)]
+pub enum FibError {
+    /// The result does not fit in a u64
+    Overflow,
+}
+
+/// The nth Fibonacci number
+pub fn fib(n: u32) -> Result<u64, FibError> {
+    let (mut a, mut b) = (0u64, 1u64);
+    for _ in 0..n {
+        let next = a.checked_add(b).ok_or(FibError::Overflow)?;
+        a = b;
+        b = next;
+    }
+    Ok(a)
+}
+```";
+
+    const POOR_IMPL: &str = "```rust
+pub fn fib(n: u32) -> u64 { if n > 93 { panic!(\"too big\") } else { n.into() } }
+```";
+
+    /// A backend scripted through the TDD pipeline, with `implementation`
+    /// as the coder's answer
+    fn backend(id: &str, implementation: &str) -> Arc<dyn ModelBackend> {
+        Arc::new(
+            MockBackend::new(id)
+                .with_role_response(
+                    ModelRole::Planner,
+                    "## Purpose: Compute Fibonacci numbers\n## Components\n- fib function\n",
+                )
+                .with_role_response(
+                    ModelRole::Planner,
+                    "Feature: Fibonacci\n  Scenario: Small input\n    \
+                     Given n is 10\n    When I compute fib\n    Then I get 55\n",
+                )
+                .with_role_response(
+                    ModelRole::Coder,
+                    "```rust\n#[test]\nfn test_fib() {\n    assert_eq!(fib(10), Ok(55));\n}\n```",
+                )
+                .with_role_response(ModelRole::Coder, implementation)
+                .with_role_response(ModelRole::Reviewer, "APPROVED\n"),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_higher_quality_backend_wins() {
+        let comparison = compare_backends(
+            &fibonacci_case(),
+            backend("sloppy", POOR_IMPL),
+            backend("careful", GOOD_IMPL),
+        )
+        .await;
+
+        assert!(comparison.result_a.error.is_none());
+        assert!(comparison.result_b.error.is_none());
+        assert_eq!(comparison.winner_id(), Some("careful"));
+
+        let total = comparison.deltas.last().unwrap();
+        assert_eq!(total.criterion, "total");
+        assert!(total.delta > 0.0);
+        let correctness = comparison
+            .deltas
+            .iter()
+            .find(|d| d.criterion == "correctness")
+            .unwrap();
+        assert!(correctness.score_b > correctness.score_a);
+    }
+}
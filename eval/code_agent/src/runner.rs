@@ -159,6 +159,9 @@ impl EvaluationRunner {
             description: test_case.description.clone(),
             payload: serde_json::json!({}),
             created_at: chrono::Utc::now(),
+            priority: Default::default(),
+            strict_variables: false,
+            depends_on: Vec::new(),
         };
 
         // Create minimal agent context
@@ -166,9 +169,14 @@ impl EvaluationRunner {
             config: bodhya_core::AppConfig::default(),
             metadata: serde_json::Value::Null,
             working_dir: None,
+            output_dir: None,
             execution_limits: bodhya_core::ExecutionLimits::default(),
             execution_mode: bodhya_core::ExecutionMode::default(),
             tools: None,
+            progress: None,
+            approval: None,
+            events: None,
+            previous_result: None,
         };
 
         // Run the agent
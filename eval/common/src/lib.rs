@@ -0,0 +1,167 @@
+/// Shared pieces of the Bodhya evaluation harnesses
+///
+/// The per-agent harnesses score outputs differently, but compare two model
+/// backends the same way: criterion by criterion, with the total deciding
+/// the winner.
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+/// A scored evaluation result that can be compared criterion by criterion
+pub trait ScoredResult {
+    /// ID of the test case the result belongs to
+    fn case_id(&self) -> &str;
+
+    /// Display name of the test case
+    fn case_name(&self) -> &str;
+
+    /// Criterion names and scores, ending with the total that decides a comparison
+    fn criteria(&self) -> Vec<(&'static str, f64)>;
+}
+
+/// Which backend scored higher
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Winner {
+    A,
+    B,
+    Tie,
+}
+
+/// Score of one criterion for both backends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CriterionDelta {
+    pub criterion: String,
+    pub score_a: f64,
+    pub score_b: f64,
+    /// `score_b - score_a`: positive when backend B did better
+    pub delta: f64,
+}
+
+/// Outcome of running one test case through two backends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendComparison<R> {
+    pub backend_a: String,
+    pub backend_b: String,
+    pub result_a: R,
+    pub result_b: R,
+    pub winner: Winner,
+    /// One entry per criterion of the results, the total last
+    pub deltas: Vec<CriterionDelta>,
+}
+
+impl<R: ScoredResult> BackendComparison<R> {
+    /// Compare two scored results of the same test case
+    pub fn new(
+        backend_a: impl Into<String>,
+        result_a: R,
+        backend_b: impl Into<String>,
+        result_b: R,
+    ) -> Self {
+        let deltas: Vec<CriterionDelta> = result_a
+            .criteria()
+            .into_iter()
+            .zip(result_b.criteria())
+            .map(|((criterion, score_a), (_, score_b))| CriterionDelta {
+                criterion: criterion.to_string(),
+                score_a,
+                score_b,
+                delta: score_b - score_a,
+            })
+            .collect();
+        let winner = match deltas.last().map(|total| total.delta) {
+            Some(delta) if delta < 0.0 => Winner::A,
+            Some(delta) if delta > 0.0 => Winner::B,
+            _ => Winner::Tie,
+        };
+
+        Self {
+            backend_a: backend_a.into(),
+            backend_b: backend_b.into(),
+            result_a,
+            result_b,
+            winner,
+            deltas,
+        }
+    }
+
+    /// Id of the winning backend, or None on a tie
+    pub fn winner_id(&self) -> Option<&str> {
+        match self.winner {
+            Winner::A => Some(&self.backend_a),
+            Winner::B => Some(&self.backend_b),
+            Winner::Tie => None,
+        }
+    }
+
+    pub fn print_summary(&self) {
+        println!(
+            "\n{} ({})",
+            self.result_a.case_name().bold(),
+            self.result_a.case_id()
+        );
+        println!(
+            "  {:<12} {:>10} {:>10} {:>8}",
+            "Criterion", "A", "B", "Δ (B-A)"
+        );
+        for delta in &self.deltas {
+            println!(
+                "  {:<12} {:>10.2} {:>10.2} {:>+8.2}",
+                delta.criterion, delta.score_a, delta.score_b, delta.delta
+            );
+        }
+        match self.winner_id() {
+            Some(id) => println!("  Winner: {}", id.bright_green().bold()),
+            None => println!("  Winner: {}", "tie".yellow()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Scores(f64, f64);
+
+    impl ScoredResult for Scores {
+        fn case_id(&self) -> &str {
+            "fib"
+        }
+
+        fn case_name(&self) -> &str {
+            "Fibonacci"
+        }
+
+        fn criteria(&self) -> Vec<(&'static str, f64)> {
+            vec![
+                ("correctness", self.0),
+                ("style", self.1),
+                ("total", self.0 + self.1),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_comparison_reports_winner_and_deltas() {
+        let comparison =
+            BackendComparison::new("small", Scores(30.0, 20.0), "large", Scores(35.0, 20.0));
+
+        assert_eq!(comparison.winner, Winner::B);
+        assert_eq!(comparison.winner_id(), Some("large"));
+        let deltas: Vec<(&str, f64)> = comparison
+            .deltas
+            .iter()
+            .map(|d| (d.criterion.as_str(), d.delta))
+            .collect();
+        assert_eq!(
+            deltas,
+            vec![("correctness", 5.0), ("style", 0.0), ("total", 5.0)]
+        );
+    }
+
+    #[test]
+    fn test_equal_totals_are_a_tie() {
+        let comparison = BackendComparison::new("a", Scores(30.0, 10.0), "b", Scores(20.0, 20.0));
+
+        assert_eq!(comparison.winner, Winner::Tie);
+        assert!(comparison.winner_id().is_none());
+    }
+}
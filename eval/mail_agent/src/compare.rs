@@ -0,0 +1,109 @@
+/// A/B comparison of two model backends on the same test case
+///
+/// Each backend serves every role of the mail domain in turn, the outputs are
+/// scored with [`EmailScorer`](crate::scorer::EmailScorer), and the comparison reports the winner along
+/// with how each criterion moved from backend A to backend B (see
+/// [`bodhya_eval_common::BackendComparison`]).
+use crate::runner::{EvaluationResult, EvaluationRunner};
+use crate::test_case::MailTestCase;
+use bodhya_agent_mail::MailAgent;
+use bodhya_core::ModelBackend;
+use bodhya_eval_common::ScoredResult;
+use bodhya_model_registry::ModelRegistry;
+use std::sync::Arc;
+
+/// Comparison of two backends' results on one test case
+pub type BackendComparison = bodhya_eval_common::BackendComparison<EvaluationResult>;
+
+impl ScoredResult for EvaluationResult {
+    fn case_id(&self) -> &str {
+        &self.test_case_id
+    }
+
+    fn case_name(&self) -> &str {
+        &self.test_case_name
+    }
+
+    fn criteria(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("tone", self.rating.tone),
+            ("clarity", self.rating.clarity),
+            ("length", self.rating.length),
+            ("completeness", self.rating.completeness),
+            ("total", self.rating.total),
+        ]
+    }
+}
+
+/// Run `test_case` with each backend serving every mail role and compare the scores
+pub async fn compare_backends(
+    test_case: &MailTestCase,
+    backend_a: Arc<dyn ModelBackend>,
+    backend_b: Arc<dyn ModelBackend>,
+) -> BackendComparison {
+    let id_a = backend_a.id().to_string();
+    let id_b = backend_b.id().to_string();
+    let result_a = run_with_backend(test_case, backend_a).await;
+    let result_b = run_with_backend(test_case, backend_b).await;
+
+    BackendComparison::new(id_a, result_a, id_b, result_b)
+}
+
+async fn run_with_backend(
+    test_case: &MailTestCase,
+    backend: Arc<dyn ModelBackend>,
+) -> EvaluationResult {
+    let registry = ModelRegistry::single_backend("mail", backend);
+    let runner = EvaluationRunner::new(MailAgent::with_registry(Arc::new(registry)));
+    runner.run_test_case(test_case).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::standard_cases::team_introduction;
+    use bodhya_eval_common::Winner;
+    use bodhya_model_registry::MockBackend;
+
+    const GOOD_EMAIL: &str = "Subject: Joining the platform team\n\n\
+        Dear Team,\n\n\
+        My name is Alex and I am joining the platform team as a software engineer next week. \
+        I have spent the last four years building backend services and developer tooling.\n\n\
+        I am looking forward to learning how the team works and to helping where I can. \
+        Additionally, I would appreciate a short call with each of you during my first week.\n\n\
+        Best regards,\nAlex";
+
+    /// A backend answering both the draft and the refinement request with `email`
+    fn backend(id: &str, email: &str) -> Arc<dyn ModelBackend> {
+        Arc::new(
+            MockBackend::new(id)
+                .with_response(email)
+                .with_response(email),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_higher_quality_backend_wins() {
+        let comparison = compare_backends(
+            &team_introduction(),
+            backend("terse", "Subject: Intro\n\nnew here. ok."),
+            backend("careful", GOOD_EMAIL),
+        )
+        .await;
+
+        assert!(comparison.result_a.error.is_none());
+        assert!(comparison.result_b.error.is_none());
+        assert_eq!(comparison.winner, Winner::B);
+        assert_eq!(comparison.winner_id(), Some("careful"));
+
+        let total = comparison.deltas.last().unwrap();
+        assert_eq!(total.criterion, "total");
+        assert!(total.delta > 0.0);
+        let completeness = comparison
+            .deltas
+            .iter()
+            .find(|d| d.criterion == "completeness")
+            .unwrap();
+        assert!(completeness.score_b > completeness.score_a);
+    }
+}
@@ -2,11 +2,14 @@
 ///
 /// Provides repeatable quality evaluation for MailAgent with standard test cases,
 /// rating system, and comparison framework.
+pub mod compare;
 pub mod runner;
 pub mod scorer;
 pub mod standard_cases;
 pub mod test_case;
 
+pub use bodhya_eval_common::{CriterionDelta, Winner};
+pub use compare::{compare_backends, BackendComparison};
 pub use runner::{EvaluationResult, EvaluationRunner, EvaluationSummary};
 pub use scorer::{EmailRating, EmailScorer, MAX_RATING};
 pub use standard_cases::get_standard_cases;
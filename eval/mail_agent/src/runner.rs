@@ -163,6 +163,9 @@ impl EvaluationRunner {
                 "purpose": test_case.purpose,
             }),
             created_at: chrono::Utc::now(),
+            priority: Default::default(),
+            strict_variables: false,
+            depends_on: Vec::new(),
         };
 
         // Create minimal agent context
@@ -170,9 +173,14 @@ impl EvaluationRunner {
             config: bodhya_core::AppConfig::default(),
             metadata: serde_json::Value::Null,
             working_dir: None,
+            output_dir: None,
             execution_limits: bodhya_core::ExecutionLimits::default(),
             execution_mode: bodhya_core::ExecutionMode::default(),
             tools: None,
+            progress: None,
+            approval: None,
+            events: None,
+            previous_result: None,
         };
 
         // Run the agent